@@ -1,191 +1,159 @@
+//! Token validation lives in `common::auth_middleware`; this module tells it
+//! how the attendance service decides whether a user is an admin, and adds
+//! the equity officer check that guards conduct reports, the welfare check
+//! that guards members' requirements, the treasurer check that guards fees,
+//! payments and expenses, and the check that lets event staff check members in.
+
 use axum::{
-    extract::{Request, State},
+    extract::{Path, Request, State},
     http::StatusCode,
     middleware::Next,
     response::Response,
-    Json,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use serde_json::json;
+use common::{
+    api_keys::ApiClient,
+    auth_middleware::{authenticate_active, check_admin_cached, check_suspended_cached},
+    error::{api_error, db_error},
+    ApiError, AuthState, Role,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::{models::Claims, AppState};
+use crate::AppState;
 
-/// Middleware to authenticate requests using JWT access tokens
-pub async fn auth_middleware(
-    State(state): State<Arc<AppState>>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
+pub use common::auth_middleware::{
+    admin_middleware, auth_middleware, event_admin_middleware, optional_auth_middleware,
+    read_access_middleware,
+};
+
+impl AuthState for AppState {
+    fn jwt_secret(&self) -> &str {
+        &self.config.jwt_secret
     }
 
-    let token = &auth_header[7..]; // Remove "Bearer " prefix
-
-    // Validate the access token
-    let mut validation = Validation::default();
-    validation.validate_exp = true;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
+    /// Admin status is owned by the auth service
+    async fn is_admin(&self, _user_id: Uuid, auth_header: &str) -> Result<bool, ApiError> {
+        check_admin_cached(
+            &self.kv,
+            self.config.admin_check_cache,
+            &self.config.auth_service_url,
+            auth_header,
         )
-    })?;
+        .await
+    }
 
-    let claims = token_data.claims;
+    async fn find_api_key(&self, key_hash: &str) -> Result<Option<ApiClient>, ApiError> {
+        self.db.find_api_key(key_hash).await.map_err(db_error)
+    }
 
-    // Verify it's an access token
-    if claims.token_type != "access" {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid token type"})),
-        ));
+    async fn is_suspended(&self, user_id: Uuid) -> Result<bool, ApiError> {
+        check_suspended_cached(&self.kv, user_id, async {
+            self.db.is_user_suspended(user_id).await.map_err(db_error)
+        })
+        .await
     }
+}
 
-    // Parse user_id from claims
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
+/// Equity middleware - requires the equity role. Admin rights are not
+/// enough: conduct reports are confidential to equity officers.
+pub async fn equity_middleware(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, _) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    let is_officer = state
+        .db
+        .has_role(user_id, Role::Equity)
+        .await
+        .map_err(|_| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to verify role"))?;
+    if !is_officer {
+        return Err(api_error(StatusCode::FORBIDDEN, "Equity role required"));
+    }
 
-    // Add user_id and username to request extensions
     request.extensions_mut().insert(user_id);
     request.extensions_mut().insert(claims.username);
 
     Ok(next.run(request).await)
 }
 
-/// Middleware for admin-only routes - checks with auth service
-pub async fn admin_middleware(
+/// Welfare middleware - requires the welfare role. As with conduct reports,
+/// admin rights are not enough: requirements can reveal health conditions.
+pub async fn welfare_middleware(
     State(state): State<Arc<AppState>>,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
-    }
-
-    let token = &auth_header[7..];
-
-    // Validate the access token
-    let mut validation = Validation::default();
-    validation.validate_exp = true;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
-        )
-    })?;
-
-    let claims = token_data.claims;
+) -> Result<Response, ApiError> {
+    let (user_id, claims, _) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
 
-    if claims.token_type != "access" {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid token type"})),
-        ));
+    let is_welfare = state
+        .db
+        .has_role(user_id, Role::Welfare)
+        .await
+        .map_err(|_| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to verify role"))?;
+    if !is_welfare {
+        return Err(api_error(StatusCode::FORBIDDEN, "Welfare role required"));
     }
 
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
+    request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(claims.username);
+
+    Ok(next.run(request).await)
+}
 
-    // Check admin status with auth service
-    let client = reqwest::Client::new();
-    let admin_check_url = format!("{}/admin/check", state.config.auth_service_url);
+/// Treasurer middleware - requires the treasurer role or admin rights
+pub async fn treasurer_middleware(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, token) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
 
-    let response = client
-        .get(&admin_check_url)
-        .header("Authorization", auth_header)
-        .send()
+    let allowed = state
+        .db
+        .has_role(user_id, Role::Treasurer)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to verify admin status"})),
-            )
-        })?;
-
-    if !response.status().is_success() {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Admin access required"})),
-        ));
+        .map_err(|_| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to verify role"))?
+        || state.is_admin(user_id, &token.auth_header()).await?;
+    if !allowed {
+        return Err(api_error(StatusCode::FORBIDDEN, "Treasurer role required"));
     }
 
-    #[derive(serde::Deserialize)]
-    struct AdminCheckResponse {
-        is_admin: bool,
-    }
+    request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(claims.username);
 
-    let admin_response: AdminCheckResponse = response.json().await.map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to parse admin status"})),
-        )
-    })?;
+    Ok(next.run(request).await)
+}
 
-    if !admin_response.is_admin {
-        return Err((
+/// Check-in middleware - admins, the event's managers, and the conveners
+/// and volunteers on its staff
+pub async fn check_in_middleware(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, token) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    let allowed = claims.managed_events.contains(&event_id)
+        || state
+            .db
+            .is_event_staff(event_id, user_id)
+            .await
+            .map_err(|_| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to verify role"))?
+        || state.is_admin(user_id, &token.auth_header()).await?;
+    if !allowed {
+        return Err(api_error(
             StatusCode::FORBIDDEN,
-            Json(json!({"error": "Admin access required"})),
+            "Admin access to this event required",
         ));
     }
 
-    // Add user_id and username to request extensions
     request.extensions_mut().insert(user_id);
     request.extensions_mut().insert(claims.username);
 
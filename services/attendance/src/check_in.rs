@@ -0,0 +1,86 @@
+//! Members checking themselves in. Self check-in opens shortly before an
+//! event starts and closes some hours after. An event may also set a
+//! geofence: members then send their coordinates, and check-ins from
+//! outside the circle are rejected or flagged for review, depending on
+//! `CHECKIN_OUT_OF_RANGE`. Only the distance from the venue is stored.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use validator::Validate;
+
+/// How long before the event starts members can check themselves in
+const OPENS_BEFORE_MINUTES: i64 = 60;
+/// How long after the event starts members can still check themselves in
+const CLOSES_AFTER_MINUTES: i64 = 6 * 60;
+
+/// Mean radius of the Earth, for distances between coordinates
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Whether self check-in is open for an event starting at `event_date`
+pub fn is_open(event_date: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now >= event_date - Duration::minutes(OPENS_BEFORE_MINUTES)
+        && now <= event_date + Duration::minutes(CLOSES_AFTER_MINUTES)
+}
+
+/// The circle members must be inside to check themselves in
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Validate)]
+pub struct Geofence {
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: f64,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: f64,
+    #[validate(range(min = 10, max = 50000))]
+    pub radius_m: i32,
+}
+
+impl Geofence {
+    /// Great-circle distance in metres from the centre to a point
+    pub fn distance_m(&self, latitude: f64, longitude: f64) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+
+    pub fn contains(&self, distance_m: f64) -> bool {
+        distance_m <= f64::from(self.radius_m)
+    }
+}
+
+/// What happens to a self check-in from outside the geofence
+/// (`CHECKIN_OUT_OF_RANGE`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutOfRangePolicy {
+    /// Refuse the check-in
+    Reject,
+    /// Accept it, flagged for an admin to confirm or revoke
+    #[default]
+    Flag,
+}
+
+impl FromStr for OutOfRangePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "reject" => Ok(OutOfRangePolicy::Reject),
+            "flag" => Ok(OutOfRangePolicy::Flag),
+            other => Err(format!(
+                "Invalid CHECKIN_OUT_OF_RANGE '{}': expected reject or flag",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutOfRangePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutOfRangePolicy::Reject => write!(f, "reject"),
+            OutOfRangePolicy::Flag => write!(f, "flag"),
+        }
+    }
+}
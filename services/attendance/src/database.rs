@@ -1,11 +1,241 @@
-use crate::models::{AttendanceRecord, AttendanceRecordWithUser, Event};
-use chrono::{DateTime, Utc};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use crate::check_in::Geofence;
+use crate::models::{
+    AbsenceExcuse, AbsenceExcuseSummary, AccessibilityNeed, Announcement, AnnouncementForUser,
+    AnnouncementWithStats, AttendanceRecord, AttendanceRecordWithUser, AttendanceSummary,
+    CheckoutEquipmentRequest, ConductReport, ConductReportSummary, CreateExpenseRequest,
+    DietaryCount, EquipmentCheckout, EquipmentItem, EquipmentRequest, Event, EventCategory,
+    EventExpense, EventFee, EventFinances, EventManager, EventReportRow, EventStaffMember,
+    EventStats, EventSummary, EventTemplate, ExcuseStatus, ExpenseCategoryTotal, ExpenseStatus,
+    MatrixTotals, MemberPayment, MemberReminder, PaymentStatus, ReportAuditEntry, ReportStatus,
+    StaffAssignment, StaffRole, TagCount, TemplateSeries, TemplateSeriesRequest,
+    UserAttendanceSummary, WalkIn, WalkInRequest,
+};
+use crate::outbox;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use common::{
+    api_keys::{ApiClient, ApiKeyScope},
+    preferences::{
+        allows,
+        NotificationCategory::{Allocations, Reminders},
+        NotificationChannel,
+    },
+    stats::STATS_MONTHS,
+    PeriodCount, ReadReplica, Role,
+};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
+/// Columns of `Event`, selected from or returned by `events`
+const EVENT_COLUMNS: &str = r#"
+    id, title, description, event_type, event_date, location, created_by, is_locked,
+    max_participants, latitude, longitude, checkin_radius_m, category_id,
+    ARRAY(SELECT tag FROM event_tags WHERE event_tags.event_id = events.id ORDER BY tag) AS tags,
+    duration_minutes, reminder_lead_hours, reminder_schedule, created_at, updated_at
+"#;
+
+/// A member's payment for the event bound to `$1`, as `MemberPayment`;
+/// callers add the WHERE clause
+const MEMBER_PAYMENTS: &str = r#"
+    SELECT u.id AS user_id, u.username,
+        COALESCE(ar.is_available, false) AS is_available,
+        COALESCE(ar.is_checked_in, false) AS is_checked_in,
+        COALESCE(ep.status, 'unpaid') AS status,
+        ep.method, ep.amount_minor, ep.currency, ep.note, ep.recorded_by, ep.paid_at
+    FROM users u
+    LEFT JOIN attendance_records ar ON ar.user_id = u.id AND ar.event_id = $1
+    LEFT JOIN event_payments ep ON ep.user_id = u.id AND ep.event_id = $1
+"#;
+
+/// Equipment from the `items` CTE with whoever has each item, as
+/// `EquipmentItem`
+const EQUIPMENT_ITEMS: &str = r#"
+    SELECT i.*, c.id AS checkout_id, c.user_id AS holder_id,
+        u.username AS holder_username, c.due_at
+    FROM items i
+    LEFT JOIN equipment_checkouts c ON c.item_id = i.id AND c.returned_at IS NULL
+    LEFT JOIN users u ON u.id = c.user_id
+"#;
+
+/// Checkouts from the `checkouts` CTE with their item, borrower and event,
+/// as `EquipmentCheckout`
+const EQUIPMENT_CHECKOUTS: &str = r#"
+    SELECT c.id, c.item_id, i.name AS item_name, i.asset_tag, c.user_id, u.username,
+        c.event_id, e.title AS event_title, c.checked_out_by, c.checked_out_at, c.due_at,
+        c.note, c.returned_at, c.returned_to, c.return_note,
+        (c.returned_at IS NULL AND c.due_at < NOW()) AS is_overdue
+    FROM checkouts c
+    JOIN equipment_items i ON i.id = c.item_id
+    JOIN users u ON u.id = c.user_id
+    LEFT JOIN events e ON e.id = c.event_id
+"#;
+
+/// Ids of the category bound to `$3` and every category below it, as the
+/// `category_tree` CTE
+const CATEGORY_TREE: &str = r#"
+    WITH RECURSIVE category_tree AS (
+        SELECT id FROM event_categories WHERE id = $3
+        UNION ALL
+        SELECT c.id FROM event_categories c
+        INNER JOIN category_tree t ON c.parent_id = t.id
+    )
+"#;
+
+/// Events matching the list filters: event type `$1`, dated from `$2`,
+/// filed under `category_tree`, and carrying every tag in `$4`. Unbound
+/// filters match everything.
+const EVENT_LIST_FILTER: &str = r#"
+    ($1::text IS NULL OR event_type = $1)
+    AND ($2::timestamptz IS NULL OR event_date >= $2)
+    AND ($3::uuid IS NULL OR category_id IN (SELECT id FROM category_tree))
+    AND ($4::text[] IS NULL OR $4 <@ ARRAY(
+        SELECT tag::text FROM event_tags WHERE event_tags.event_id = events.id))
+"#;
+
+/// Announcements the user identified by `$1` may see: published, and
+/// matching the event or role they are targeted at, if any
+const ANNOUNCEMENT_VISIBLE_TO_USER: &str = r#"
+    a.publish_at <= NOW()
+    AND (a.event_id IS NULL OR EXISTS (
+        SELECT 1 FROM attendance_records ar
+        WHERE ar.event_id = a.event_id AND ar.user_id = $1 AND ar.is_available))
+    AND (a.audience_role IS NULL OR EXISTS (
+        SELECT 1 FROM user_roles ur
+        WHERE ur.user_id = $1 AND ur.role = a.audience_role))
+"#;
+
+/// Each member's attendance over the events dated from `$1` (inclusive) to
+/// `$2` (exclusive), either bound optional, as the `user_stats` CTE. An
+/// approved excuse for an event the member did not check in to takes that
+/// event out of their counts.
+const MATRIX_USER_STATS: &str = r#"
+    WITH matrix_events AS (
+        SELECT id FROM events
+        WHERE ($1::timestamptz IS NULL OR event_date >= $1)
+          AND ($2::timestamptz IS NULL OR event_date < $2)
+    ),
+    excused AS (
+        SELECT ax.event_id, ax.user_id
+        FROM absence_excuses ax
+        INNER JOIN matrix_events me ON ax.event_id = me.id
+        LEFT JOIN attendance_records ar
+            ON ar.event_id = ax.event_id AND ar.user_id = ax.user_id
+        WHERE ax.status = 'approved' AND ar.is_checked_in IS NOT TRUE
+    ),
+    record_counts AS (
+        SELECT ar.user_id,
+            COUNT(*) FILTER (WHERE ar.is_available) AS events_available,
+            COUNT(*) FILTER (WHERE ar.is_checked_in) AS events_checked_in
+        FROM attendance_records ar
+        INNER JOIN matrix_events me ON ar.event_id = me.id
+        WHERE NOT EXISTS (
+            SELECT 1 FROM excused x
+            WHERE x.event_id = ar.event_id AND x.user_id = ar.user_id)
+        GROUP BY ar.user_id
+    ),
+    excused_counts AS (
+        SELECT user_id, COUNT(*) AS events_excused FROM excused GROUP BY user_id
+    ),
+    user_stats AS (
+        SELECT u.id AS user_id, u.username,
+            COALESCE(r.events_available, 0) AS events_available,
+            COALESCE(r.events_checked_in, 0) AS events_checked_in,
+            COALESCE(x.events_excused, 0) AS events_excused,
+            (SELECT COUNT(*) FROM matrix_events) AS total_events
+        FROM users u
+        LEFT JOIN record_counts r ON r.user_id = u.id
+        LEFT JOIN excused_counts x ON x.user_id = u.id
+    )
+"#;
+
+/// Parameters for creating an event
+pub struct CreateEventParams<'a> {
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub event_type: &'a str,
+    pub event_date: DateTime<Utc>,
+    pub location: Option<&'a str>,
+    pub max_participants: Option<i32>,
+    pub geofence: Option<Geofence>,
+    pub category_id: Option<Uuid>,
+    /// Normalised tags
+    pub tags: &'a [String],
+    pub duration_minutes: Option<i32>,
+    pub reminder_lead_hours: Option<i32>,
+    /// None uses the default member reminder schedule
+    pub reminder_schedule: Option<&'a [i32]>,
+    pub created_by: Uuid,
+}
+
+/// Settings of an event template; every one is replaced on update
+pub struct EventTemplateParams<'a> {
+    pub name: &'a str,
+    pub title: &'a str,
+    pub description: Option<&'a str>,
+    pub event_type: &'a str,
+    pub start_time: NaiveTime,
+    pub duration_minutes: Option<i32>,
+    pub location: Option<&'a str>,
+    pub max_participants: Option<i32>,
+    pub category_id: Option<Uuid>,
+    /// Normalised tags
+    pub tags: &'a [String],
+    pub reminder_lead_hours: Option<i32>,
+    pub reminder_schedule: Option<&'a [i32]>,
+    pub series: &'a [TemplateSeriesRequest],
+}
+
+/// Which events to list; unset filters match every event
+pub struct EventListFilter<'a> {
+    pub event_type: Option<&'a str>,
+    pub upcoming_only: bool,
+    /// Events filed under this category or any category below it
+    pub category_id: Option<Uuid>,
+    /// Events carrying every one of these tags
+    pub tags: Option<&'a [String]>,
+}
+
+/// Which equipment checkouts to list; unset filters match every checkout
+#[derive(Default)]
+pub struct CheckoutFilter {
+    pub item_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    /// Only items still out
+    pub open_only: bool,
+    /// Only items still out after they were due back
+    pub overdue_only: bool,
+}
+
+/// Parameters for updating an event; unset fields are left alone
+pub struct UpdateEventParams<'a> {
+    pub event_id: Uuid,
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub event_type: Option<&'a str>,
+    pub event_date: Option<DateTime<Utc>>,
+    pub location: Option<&'a str>,
+    /// Replaces the seat limit when set, `Some(None)` removing it
+    pub max_participants: Option<Option<i32>>,
+    /// Replaces the geofence when set, `Some(None)` removing it
+    pub geofence: Option<Option<Geofence>>,
+    /// Refiles the event when set, `Some(None)` removing its category
+    pub category_id: Option<Option<Uuid>>,
+    /// Replaces the tags when set; normalised
+    pub tags: Option<&'a [String]>,
+    /// Replaces the length when set, `Some(None)` going back to the default
+    pub duration_minutes: Option<Option<i32>>,
+    /// Replaces the reminder lead time when set, `Some(None)` going back to
+    /// the default
+    pub reminder_lead_hours: Option<Option<i32>>,
+    /// Replaces the member reminder schedule when set, `Some(None)` going
+    /// back to the default
+    pub reminder_schedule: Option<Option<&'a [i32]>>,
+}
+
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
+    replica: Option<ReadReplica>,
 }
 
 impl Database {
@@ -15,14 +245,29 @@ impl Database {
             .connect(database_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            replica: None,
+        })
+    }
+
+    /// Serve the reads of `GET` requests from `replica` while it is reachable
+    pub fn with_replica(mut self, replica: ReadReplica) -> Self {
+        self.replica = Some(replica);
+        self
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
 
-    pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+    /// Pool for read-only queries: the replica during `GET` requests, the
+    /// primary otherwise
+    fn reader(&self) -> &PgPool {
+        common::replica::reader(&self.pool, self.replica.as_ref())
+    }
+
+    pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
         sqlx::migrate!("../migrations").run(&self.pool).await?;
         Ok(())
     }
@@ -31,46 +276,78 @@ impl Database {
     // Event Methods
     // ========================================================================
 
-    pub async fn create_event(
-        &self,
-        title: &str,
-        description: Option<&str>,
-        event_type: &str,
-        event_date: DateTime<Utc>,
-        location: Option<&str>,
-        created_by: Uuid,
-    ) -> Result<Event, sqlx::Error> {
-        let event = sqlx::query_as::<_, Event>(
+    pub async fn create_event(&self, params: CreateEventParams<'_>) -> Result<Event, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let event_id = Self::insert_event(&mut tx, params).await?;
+        let event = Self::event_in(&mut tx, event_id).await?;
+        tx.commit().await?;
+
+        Ok(event)
+    }
+
+    async fn insert_event(
+        tx: &mut Transaction<'_, Postgres>,
+        params: CreateEventParams<'_>,
+    ) -> Result<Uuid, sqlx::Error> {
+        let event_id = Uuid::new_v4();
+
+        sqlx::query(
             r#"
-            INSERT INTO events (id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, false, $8, $8)
-            RETURNING id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
+            INSERT INTO events (id, title, description, event_type, event_date, location, created_by, is_locked, max_participants, latitude, longitude, checkin_radius_m, category_id, duration_minutes, reminder_lead_hours, reminder_schedule, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, false, $9, $10, $11, $12, $13, $14, $15, $16, $8, $8)
             "#,
         )
-        .bind(Uuid::new_v4())
-        .bind(title)
-        .bind(description)
-        .bind(event_type)
-        .bind(event_date)
-        .bind(location)
-        .bind(created_by)
+        .bind(event_id)
+        .bind(params.title)
+        .bind(params.description)
+        .bind(params.event_type)
+        .bind(params.event_date)
+        .bind(params.location)
+        .bind(params.created_by)
         .bind(Utc::now())
-        .fetch_one(&self.pool)
+        .bind(params.max_participants)
+        .bind(params.geofence.map(|g| g.latitude))
+        .bind(params.geofence.map(|g| g.longitude))
+        .bind(params.geofence.map(|g| g.radius_m))
+        .bind(params.category_id)
+        .bind(params.duration_minutes)
+        .bind(params.reminder_lead_hours)
+        .bind(params.reminder_schedule)
+        .execute(&mut **tx)
         .await?;
 
-        Ok(event)
+        Self::replace_event_tags(tx, event_id, params.tags).await?;
+        Ok(event_id)
+    }
+
+    /// Changes whenever the event or its tags do, for conditional GETs
+    pub async fn event_version(&self, event_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT concat_ws('|', e.updated_at,
+                (SELECT string_agg(tag, ',' ORDER BY tag) FROM event_tags WHERE event_id = e.id),
+                (SELECT string_agg(user_id || ':' || role, ',' ORDER BY user_id)
+                 FROM event_staff WHERE event_id = e.id))
+            FROM events e
+            WHERE e.id = $1
+            "#,
+        )
+        .bind(event_id)
+        .fetch_optional(self.reader())
+        .await
     }
 
     pub async fn get_event_by_id(&self, event_id: Uuid) -> Result<Option<Event>, sqlx::Error> {
-        let event = sqlx::query_as::<_, Event>(
+        let event = sqlx::query_as::<_, Event>(&format!(
             r#"
-            SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
+            SELECT {}
             FROM events
             WHERE id = $1
             "#,
-        )
+            EVENT_COLUMNS
+        ))
         .bind(event_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(event)
@@ -80,147 +357,226 @@ impl Database {
         &self,
         page: i32,
         per_page: i32,
-        event_type: Option<&str>,
-        upcoming_only: bool,
+        filter: EventListFilter<'_>,
     ) -> Result<(Vec<Event>, i64), sqlx::Error> {
         let offset = (page - 1) * per_page;
 
         // Use start of today for "upcoming" filter so events on the current day stay visible
-        let today_start = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let today_start_utc = chrono::DateTime::<Utc>::from_naive_utc_and_offset(today_start, Utc);
-
-        // Build dynamic query based on filters
-        let (events, total): (Vec<Event>, i64) = if upcoming_only {
-            if let Some(et) = event_type {
-                let total: (i64,) = sqlx::query_as(
-                    "SELECT COUNT(*) FROM events WHERE event_type = $1 AND event_date >= $2",
-                )
-                .bind(et)
-                .bind(today_start_utc)
-                .fetch_one(&self.pool)
-                .await?;
-
-                let events = sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
-                    FROM events
-                    WHERE event_type = $1 AND event_date >= $2
-                    ORDER BY event_date ASC
-                    LIMIT $3 OFFSET $4
-                    "#,
-                )
-                .bind(et)
-                .bind(today_start_utc)
-                .bind(per_page as i64)
-                .bind(offset as i64)
-                .fetch_all(&self.pool)
-                .await?;
-
-                (events, total.0)
-            } else {
-                let total: (i64,) =
-                    sqlx::query_as("SELECT COUNT(*) FROM events WHERE event_date >= $1")
-                        .bind(today_start_utc)
-                        .fetch_one(&self.pool)
-                        .await?;
-
-                let events = sqlx::query_as::<_, Event>(
-                    r#"
-                    SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
-                    FROM events
-                    WHERE event_date >= $1
-                    ORDER BY event_date ASC
-                    LIMIT $2 OFFSET $3
-                    "#,
-                )
-                .bind(today_start_utc)
-                .bind(per_page as i64)
-                .bind(offset as i64)
-                .fetch_all(&self.pool)
-                .await?;
-
-                (events, total.0)
-            }
-        } else if let Some(et) = event_type {
-            let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events WHERE event_type = $1")
-                .bind(et)
-                .fetch_one(&self.pool)
-                .await?;
+        let from = filter.upcoming_only.then(|| {
+            let today_start = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+            chrono::DateTime::<Utc>::from_naive_utc_and_offset(today_start, Utc)
+        });
 
-            let events = sqlx::query_as::<_, Event>(
-                r#"
-                SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
-                FROM events
-                WHERE event_type = $1
-                ORDER BY event_date DESC
-                LIMIT $2 OFFSET $3
-                "#,
-            )
-            .bind(et)
-            .bind(per_page as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.pool)
-            .await?;
-
-            (events, total.0)
-        } else {
-            let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM events")
-                .fetch_one(&self.pool)
-                .await?;
-
-            let events = sqlx::query_as::<_, Event>(
-                r#"
-                SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
-                FROM events
-                ORDER BY event_date DESC
-                LIMIT $1 OFFSET $2
-                "#,
-            )
-            .bind(per_page as i64)
-            .bind(offset as i64)
-            .fetch_all(&self.pool)
-            .await?;
+        let total: (i64,) = sqlx::query_as(&format!(
+            "{} SELECT COUNT(*) FROM events WHERE {}",
+            CATEGORY_TREE, EVENT_LIST_FILTER
+        ))
+        .bind(filter.event_type)
+        .bind(from)
+        .bind(filter.category_id)
+        .bind(filter.tags)
+        .fetch_one(self.reader())
+        .await?;
 
-            (events, total.0)
-        };
+        // Upcoming events soonest first, otherwise the most recent first
+        let events = sqlx::query_as::<_, Event>(&format!(
+            r#"
+            {}
+            SELECT {}
+            FROM events
+            WHERE {}
+            ORDER BY CASE WHEN $2::timestamptz IS NULL THEN NULL ELSE event_date END ASC,
+                event_date DESC
+            LIMIT $5 OFFSET $6
+            "#,
+            CATEGORY_TREE, EVENT_COLUMNS, EVENT_LIST_FILTER
+        ))
+        .bind(filter.event_type)
+        .bind(from)
+        .bind(filter.category_id)
+        .bind(filter.tags)
+        .bind(per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(self.reader())
+        .await?;
 
-        Ok((events, total))
+        Ok((events, total.0))
     }
 
     pub async fn update_event(
         &self,
-        event_id: Uuid,
-        title: Option<&str>,
-        description: Option<&str>,
-        event_type: Option<&str>,
-        event_date: Option<DateTime<Utc>>,
-        location: Option<&str>,
+        params: UpdateEventParams<'_>,
     ) -> Result<Option<Event>, sqlx::Error> {
         // Get current event first
-        let current = match self.get_event_by_id(event_id).await? {
+        let current = match self.get_event_by_id(params.event_id).await? {
             Some(e) => e,
             None => return Ok(None),
         };
+        let geofence = params.geofence.unwrap_or(current.geofence());
+        let mut tx = self.pool.begin().await?;
 
-        let event = sqlx::query_as::<_, Event>(
+        let updated = sqlx::query(
             r#"
             UPDATE events
-            SET title = $1, description = $2, event_type = $3, event_date = $4, location = $5, updated_at = $6
+            SET title = $1, description = $2, event_type = $3, event_date = $4, location = $5, updated_at = $6,
+                reminder_sent_at = CASE WHEN event_date = $4 THEN reminder_sent_at END,
+                max_participants = $8, latitude = $9, longitude = $10, checkin_radius_m = $11,
+                category_id = $12, duration_minutes = $13, reminder_lead_hours = $14,
+                reminder_schedule = $15
             WHERE id = $7
-            RETURNING id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
             "#,
         )
-        .bind(title.unwrap_or(&current.title))
-        .bind(description.or(current.description.as_deref()))
-        .bind(event_type.unwrap_or(&current.event_type))
-        .bind(event_date.unwrap_or(current.event_date))
-        .bind(location.or(current.location.as_deref()))
+        .bind(params.title.unwrap_or(&current.title))
+        .bind(params.description.or(current.description.as_deref()))
+        .bind(params.event_type.unwrap_or(&current.event_type))
+        .bind(params.event_date.unwrap_or(current.event_date))
+        .bind(params.location.or(current.location.as_deref()))
         .bind(Utc::now())
+        .bind(params.event_id)
+        .bind(params.max_participants.unwrap_or(current.max_participants))
+        .bind(geofence.map(|g| g.latitude))
+        .bind(geofence.map(|g| g.longitude))
+        .bind(geofence.map(|g| g.radius_m))
+        .bind(params.category_id.unwrap_or(current.category_id))
+        .bind(params.duration_minutes.unwrap_or(current.duration_minutes))
+        .bind(
+            params
+                .reminder_lead_hours
+                .unwrap_or(current.reminder_lead_hours),
+        )
+        .bind(
+            params
+                .reminder_schedule
+                .unwrap_or(current.reminder_schedule.as_deref()),
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        // Reminders sent for the old start time go out again for the new one
+        if params
+            .event_date
+            .is_some_and(|date| date != current.event_date)
+        {
+            sqlx::query("DELETE FROM event_member_reminders WHERE event_id = $1")
+                .bind(params.event_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if let Some(tags) = params.tags {
+            Self::replace_event_tags(&mut tx, params.event_id, tags).await?;
+        }
+        let event = Self::event_in(&mut tx, params.event_id).await?;
+        tx.commit().await?;
+
+        Ok(Some(event))
+    }
+
+    /// Replace an event's tags, which must already be normalised
+    pub async fn set_event_tags(
+        &self,
+        event_id: Uuid,
+        tags: &[String],
+    ) -> Result<Option<Event>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query("UPDATE events SET updated_at = NOW() WHERE id = $1")
+            .bind(event_id)
+            .execute(&mut *tx)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        Self::replace_event_tags(&mut tx, event_id, tags).await?;
+        let event = Self::event_in(&mut tx, event_id).await?;
+        tx.commit().await?;
+
+        Ok(Some(event))
+    }
+
+    async fn replace_event_tags(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+        tags: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM event_tags WHERE event_id = $1")
+            .bind(event_id)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO event_tags (event_id, tag) SELECT $1, UNNEST($2::text[]) ON CONFLICT DO NOTHING",
+        )
         .bind(event_id)
-        .fetch_optional(&self.pool)
+        .bind(tags)
+        .execute(&mut **tx)
         .await?;
 
-        Ok(event)
+        Ok(())
+    }
+
+    /// An event as seen inside a transaction that has just written it
+    /// Create every event in one transaction, so a failure creates none
+    pub async fn create_events(
+        &self,
+        events: Vec<CreateEventParams<'_>>,
+    ) -> Result<Vec<Event>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut created = Vec::with_capacity(events.len());
+        for params in events {
+            let event_id = Self::insert_event(&mut tx, params).await?;
+            created.push(Self::event_in(&mut tx, event_id).await?);
+        }
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    /// Title and start of every event starting in `[from, to)`, for
+    /// spotting duplicates in an imported schedule
+    pub async fn event_titles_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(String, DateTime<Utc>)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT title, event_date FROM events
+            WHERE event_date >= $1 AND event_date < $2
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    async fn event_in(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+    ) -> Result<Event, sqlx::Error> {
+        sqlx::query_as::<_, Event>(&format!(
+            "SELECT {} FROM events WHERE id = $1",
+            EVENT_COLUMNS
+        ))
+        .bind(event_id)
+        .fetch_one(&mut **tx)
+        .await
+    }
+
+    /// Whether the event has been archived and is therefore read-only
+    pub async fn is_event_archived(&self, event_id: Uuid) -> Result<bool, sqlx::Error> {
+        let archived: Option<(bool,)> =
+            sqlx::query_as("SELECT archived_at IS NOT NULL FROM events WHERE id = $1")
+                .bind(event_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(archived.map(|(archived,)| archived).unwrap_or(false))
     }
 
     pub async fn delete_event(&self, event_id: Uuid) -> Result<bool, sqlx::Error> {
@@ -232,19 +588,37 @@ impl Database {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Ballots recorded for an event's matches, which deleting the event
+    /// would erase
+    pub async fn event_ballot_count(&self, event_id: Uuid) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            WHERE s.event_id = $1
+            "#,
+        )
+        .bind(event_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count.0)
+    }
+
     pub async fn lock_event(
         &self,
         event_id: Uuid,
         is_locked: bool,
     ) -> Result<Option<Event>, sqlx::Error> {
-        let event = sqlx::query_as::<_, Event>(
+        let event = sqlx::query_as::<_, Event>(&format!(
             r#"
             UPDATE events
             SET is_locked = $1, updated_at = $2
             WHERE id = $3
-            RETURNING id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
+            RETURNING {}
             "#,
-        )
+            EVENT_COLUMNS
+        ))
         .bind(is_locked)
         .bind(Utc::now())
         .bind(event_id)
@@ -254,263 +628,2648 @@ impl Database {
         Ok(event)
     }
 
-    // ========================================================================
-    // Attendance Methods
-    // ========================================================================
+    /// Every event from the start of today onwards, soonest first
+    pub async fn list_upcoming_events(&self) -> Result<Vec<Event>, sqlx::Error> {
+        let today_start = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+        let today_start_utc = chrono::DateTime::<Utc>::from_naive_utc_and_offset(today_start, Utc);
 
-    pub async fn get_attendance_record(
+        sqlx::query_as::<_, Event>(&format!(
+            r#"
+            SELECT {}
+            FROM events
+            WHERE event_date >= $1
+            ORDER BY event_date ASC
+            "#,
+            EVENT_COLUMNS
+        ))
+        .bind(today_start_utc)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Mark every event starting within its reminder lead time (or
+    /// `default_lead_hours`) whose reminder has not been sent, returning
+    /// them. Claiming and marking in one statement means concurrent
+    /// instances never post the same reminder twice.
+    pub async fn claim_events_due_for_reminder(
         &self,
-        event_id: Uuid,
-        user_id: Uuid,
-    ) -> Result<Option<AttendanceRecord>, sqlx::Error> {
-        let record = sqlx::query_as::<_, AttendanceRecord>(
+        default_lead_hours: i32,
+    ) -> Result<Vec<Event>, sqlx::Error> {
+        sqlx::query_as::<_, Event>(&format!(
             r#"
-            SELECT id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at
-            FROM attendance_records
-            WHERE event_id = $1 AND user_id = $2
+            UPDATE events
+            SET reminder_sent_at = NOW()
+            WHERE reminder_sent_at IS NULL AND archived_at IS NULL
+              AND event_date > NOW()
+              AND event_date <= NOW() + make_interval(hours => COALESCE(reminder_lead_hours, $1))
+            RETURNING {}
             "#,
-        )
-        .bind(event_id)
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+            EVENT_COLUMNS
+        ))
+        .bind(default_lead_hours)
+        .fetch_all(&self.pool)
+        .await
+    }
 
-        Ok(record)
+    /// Record and return every member reminder that has come due: for each
+    /// member available for an upcoming event, the shortest lead time on the
+    /// event's schedule (or `default_schedule`) that has passed, unless it
+    /// was already sent. Members who became available after a reminder's
+    /// time skip it, and a member is sent one reminder per poll however many
+    /// have passed. Members who turned reminders off both by email and in
+    /// the app are skipped. Inserting and returning in one statement means
+    /// concurrent instances never send a reminder twice.
+    pub async fn claim_due_member_reminders(
+        &self,
+        default_schedule: &[i32],
+    ) -> Result<Vec<MemberReminder>, sqlx::Error> {
+        sqlx::query_as::<_, MemberReminder>(&format!(
+            r#"
+            WITH due AS (
+                SELECT e.id AS event_id, ar.user_id, MIN(h.hours) AS hours_before
+                FROM events e
+                CROSS JOIN LATERAL UNNEST(COALESCE(e.reminder_schedule, $1::int[])) AS h(hours)
+                JOIN attendance_records ar ON ar.event_id = e.id AND ar.is_available
+                WHERE e.archived_at IS NULL AND e.event_date > NOW()
+                  AND e.event_date - make_interval(hours => h.hours) <= NOW()
+                  AND ar.availability_set_at <= e.event_date - make_interval(hours => h.hours)
+                  AND ({} OR {})
+                GROUP BY e.id, ar.user_id
+            ),
+            claimed AS (
+                INSERT INTO event_member_reminders (event_id, user_id, hours_before)
+                SELECT event_id, user_id, hours_before FROM due
+                ON CONFLICT DO NOTHING
+                RETURNING event_id, user_id, hours_before, sent_at
+            )
+            SELECT c.event_id, e.title, e.event_date, e.location, c.hours_before, c.sent_at,
+                c.user_id, u.username, u.email, {} AS wants_email
+            FROM claimed c
+            JOIN events e ON e.id = c.event_id
+            JOIN users u ON u.id = c.user_id
+            WHERE u.deleted_at IS NULL
+            "#,
+            allows("ar.user_id", Reminders, NotificationChannel::Email),
+            allows("ar.user_id", Reminders, NotificationChannel::InApp),
+            allows("c.user_id", Reminders, NotificationChannel::Email),
+        ))
+        .bind(default_schedule)
+        .fetch_all(&self.pool)
+        .await
     }
 
-    pub async fn set_availability(
+    /// Reminders a member has been sent for events still to come, soonest
+    /// event first. Empty for members who turned in-app reminders off.
+    pub async fn list_member_reminders(
         &self,
-        event_id: Uuid,
         user_id: Uuid,
-        is_available: bool,
-    ) -> Result<AttendanceRecord, sqlx::Error> {
-        // Upsert - insert or update
-        let record = sqlx::query_as::<_, AttendanceRecord>(
+    ) -> Result<Vec<MemberReminder>, sqlx::Error> {
+        sqlx::query_as::<_, MemberReminder>(&format!(
             r#"
-            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, availability_set_at, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, false, $5, $5, $5)
-            ON CONFLICT (event_id, user_id)
-            DO UPDATE SET is_available = $4, availability_set_at = $5, updated_at = $5
-            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at
+            SELECT r.event_id, e.title, e.event_date, e.location, r.hours_before, r.sent_at,
+                r.user_id, u.username, u.email, {} AS wants_email
+            FROM event_member_reminders r
+            JOIN events e ON e.id = r.event_id
+            JOIN users u ON u.id = r.user_id
+            WHERE r.user_id = $1 AND e.event_date > NOW() AND {}
+            ORDER BY e.event_date, r.hours_before DESC
             "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(event_id)
+            allows("r.user_id", Reminders, NotificationChannel::Email),
+            allows("r.user_id", Reminders, NotificationChannel::InApp),
+        ))
         .bind(user_id)
-        .bind(is_available)
-        .bind(Utc::now())
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(record)
+        .fetch_all(self.reader())
+        .await
     }
 
-    pub async fn check_in_user(
-        &self,
-        event_id: Uuid,
-        user_id: Uuid,
-        is_checked_in: bool,
-        checked_in_by: Uuid,
-    ) -> Result<AttendanceRecord, sqlx::Error> {
-        let now = Utc::now();
-        let checked_in_at = if is_checked_in { Some(now) } else { None };
+    // ========================================================================
+    // Category and Tag Methods
+    // ========================================================================
 
-        // Upsert with check-in information
-        let record = sqlx::query_as::<_, AttendanceRecord>(
+    /// Every category with its full path, in path order so children follow
+    /// their parent
+    pub async fn list_categories(&self) -> Result<Vec<EventCategory>, sqlx::Error> {
+        sqlx::query_as::<_, EventCategory>(
             r#"
-            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at)
-            VALUES ($1, $2, $3, true, $4, $5, $6, $7, $7, $7)
-            ON CONFLICT (event_id, user_id)
-            DO UPDATE SET is_checked_in = $4, checked_in_by = $5, checked_in_at = $6, updated_at = $7
-            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at
+            WITH RECURSIVE tree AS (
+                SELECT id, name::text AS path, 0 AS depth
+                FROM event_categories
+                WHERE parent_id IS NULL
+                UNION ALL
+                SELECT c.id, t.path || ' / ' || c.name, t.depth + 1
+                FROM event_categories c
+                INNER JOIN tree t ON c.parent_id = t.id
+            )
+            SELECT c.id, c.name, c.parent_id, t.path, t.depth,
+                (SELECT COUNT(*) FROM events e WHERE e.category_id = c.id) AS event_count,
+                c.created_at, c.updated_at
+            FROM event_categories c
+            INNER JOIN tree t ON t.id = c.id
+            ORDER BY LOWER(t.path)
             "#,
         )
-        .bind(Uuid::new_v4())
-        .bind(event_id)
-        .bind(user_id)
-        .bind(is_checked_in)
-        .bind(if is_checked_in { Some(checked_in_by) } else { None })
-        .bind(checked_in_at)
-        .bind(now)
-        .fetch_one(&self.pool)
-        .await?;
+        .fetch_all(self.reader())
+        .await
+    }
 
-        Ok(record)
+    pub async fn category_exists(&self, category_id: Uuid) -> Result<bool, sqlx::Error> {
+        let exists: (bool,) =
+            sqlx::query_as("SELECT EXISTS (SELECT 1 FROM event_categories WHERE id = $1)")
+                .bind(category_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(exists.0)
     }
 
-    pub async fn revoke_availability(
+    pub async fn create_category(
         &self,
-        event_id: Uuid,
-        user_id: Uuid,
-    ) -> Result<Option<AttendanceRecord>, sqlx::Error> {
-        let record = sqlx::query_as::<_, AttendanceRecord>(
+        name: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO event_categories (id, name, parent_id) VALUES ($1, $2, $3)")
+            .bind(id)
+            .bind(name)
+            .bind(parent_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Rename or move a category; `parent_id` of `Some(None)` moves it to the
+    /// top level. Returns false if it does not exist.
+    pub async fn update_category(
+        &self,
+        category_id: Uuid,
+        name: Option<&str>,
+        parent_id: Option<Option<Uuid>>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
             r#"
-            UPDATE attendance_records
-            SET is_available = false, is_checked_in = false, checked_in_by = NULL, checked_in_at = NULL, updated_at = $1
-            WHERE event_id = $2 AND user_id = $3
-            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at
+            UPDATE event_categories
+            SET name = COALESCE($2, name),
+                parent_id = CASE WHEN $3 THEN $4 ELSE parent_id END,
+                updated_at = NOW()
+            WHERE id = $1
             "#,
         )
-        .bind(Utc::now())
-        .bind(event_id)
-        .bind(user_id)
-        .fetch_optional(&self.pool)
+        .bind(category_id)
+        .bind(name)
+        .bind(parent_id.is_some())
+        .bind(parent_id.flatten())
+        .execute(&self.pool)
         .await?;
 
-        Ok(record)
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_event_attendance(
+    /// Whether `candidate` is `category_id` or one of the categories below
+    /// it, so making it the parent would create a cycle
+    pub async fn is_in_category_subtree(
         &self,
-        event_id: Uuid,
-    ) -> Result<Vec<AttendanceRecordWithUser>, sqlx::Error> {
-        let records = sqlx::query_as::<_, AttendanceRecordWithUser>(
+        category_id: Uuid,
+        candidate: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let found: (bool,) = sqlx::query_as(
             r#"
-            SELECT 
-                ar.id, ar.event_id, ar.user_id, 
-                u.username,
-                ar.is_available, ar.is_checked_in, ar.checked_in_by, ar.checked_in_at, 
-                ar.availability_set_at, ar.created_at, ar.updated_at
-            FROM attendance_records ar
-            JOIN users u ON ar.user_id = u.id
-            WHERE ar.event_id = $1
-            ORDER BY ar.is_checked_in DESC, ar.is_available DESC, ar.availability_set_at ASC
+            WITH RECURSIVE subtree AS (
+                SELECT id FROM event_categories WHERE id = $1
+                UNION ALL
+                SELECT c.id FROM event_categories c
+                INNER JOIN subtree s ON c.parent_id = s.id
+            )
+            SELECT EXISTS (SELECT 1 FROM subtree WHERE id = $2)
             "#,
         )
-        .bind(event_id)
-        .fetch_all(&self.pool)
+        .bind(category_id)
+        .bind(candidate)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(records)
+        Ok(found.0)
     }
 
-    pub async fn get_attendance_stats(&self, event_id: Uuid) -> Result<(i64, i64), sqlx::Error> {
+    pub async fn category_has_children(&self, category_id: Uuid) -> Result<bool, sqlx::Error> {
+        let found: (bool,) =
+            sqlx::query_as("SELECT EXISTS (SELECT 1 FROM event_categories WHERE parent_id = $1)")
+                .bind(category_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(found.0)
+    }
+
+    /// Delete a category without children; its events become uncategorised
+    pub async fn delete_category(&self, category_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_categories WHERE id = $1")
+            .bind(category_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every tag in use with how many events carry it, most used first
+    pub async fn list_tags(&self) -> Result<Vec<TagCount>, sqlx::Error> {
+        sqlx::query_as::<_, TagCount>(
+            r#"
+            SELECT tag, COUNT(*) AS event_count
+            FROM event_tags
+            GROUP BY tag
+            ORDER BY event_count DESC, tag ASC
+            "#,
+        )
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Rename a tag on every event, merging it into `to` where an event
+    /// already has both. Returns how many events carried the old tag.
+    pub async fn rename_tag(&self, from: &str, to: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO event_tags (event_id, tag)
+            SELECT event_id, $2 FROM event_tags WHERE tag = $1
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .execute(&mut *tx)
+        .await?;
+
+        let result = sqlx::query("DELETE FROM event_tags WHERE tag = $1")
+            .bind(from)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a tag from every event, returning how many carried it
+    pub async fn delete_tag(&self, tag: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_tags WHERE tag = $1")
+            .bind(tag)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // ========================================================================
+    // Event Template Methods
+    // ========================================================================
+
+    pub async fn list_event_templates(&self) -> Result<Vec<EventTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, EventTemplate>("SELECT * FROM event_templates ORDER BY name")
+            .fetch_all(self.reader())
+            .await
+    }
+
+    pub async fn get_event_template(
+        &self,
+        template_id: Uuid,
+    ) -> Result<Option<EventTemplate>, sqlx::Error> {
+        sqlx::query_as::<_, EventTemplate>("SELECT * FROM event_templates WHERE id = $1")
+            .bind(template_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    pub async fn list_template_series(
+        &self,
+        template_id: Uuid,
+    ) -> Result<Vec<TemplateSeries>, sqlx::Error> {
+        sqlx::query_as::<_, TemplateSeries>(
+            r#"
+            SELECT position, name, description, round_number, team_format::text AS team_format,
+                allow_reply_speeches, is_break_round, is_training
+            FROM event_template_series
+            WHERE template_id = $1
+            ORDER BY position
+            "#,
+        )
+        .bind(template_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn create_event_template(
+        &self,
+        params: EventTemplateParams<'_>,
+        created_by: Uuid,
+    ) -> Result<EventTemplate, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let template = sqlx::query_as::<_, EventTemplate>(
+            r#"
+            INSERT INTO event_templates (id, name, title, description, event_type, start_time,
+                duration_minutes, location, max_participants, category_id, tags,
+                reminder_lead_hours, reminder_schedule, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(params.name)
+        .bind(params.title)
+        .bind(params.description)
+        .bind(params.event_type)
+        .bind(params.start_time)
+        .bind(params.duration_minutes)
+        .bind(params.location)
+        .bind(params.max_participants)
+        .bind(params.category_id)
+        .bind(params.tags)
+        .bind(params.reminder_lead_hours)
+        .bind(params.reminder_schedule)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::replace_template_series(&mut tx, template.id, params.series).await?;
+        tx.commit().await?;
+
+        Ok(template)
+    }
+
+    /// Replace every setting and series of a template
+    pub async fn update_event_template(
+        &self,
+        template_id: Uuid,
+        params: EventTemplateParams<'_>,
+    ) -> Result<Option<EventTemplate>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let template = sqlx::query_as::<_, EventTemplate>(
+            r#"
+            UPDATE event_templates
+            SET name = $2, title = $3, description = $4, event_type = $5, start_time = $6,
+                duration_minutes = $7, location = $8, max_participants = $9, category_id = $10,
+                tags = $11, reminder_lead_hours = $12, reminder_schedule = $13, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(template_id)
+        .bind(params.name)
+        .bind(params.title)
+        .bind(params.description)
+        .bind(params.event_type)
+        .bind(params.start_time)
+        .bind(params.duration_minutes)
+        .bind(params.location)
+        .bind(params.max_participants)
+        .bind(params.category_id)
+        .bind(params.tags)
+        .bind(params.reminder_lead_hours)
+        .bind(params.reminder_schedule)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(template) = template else {
+            return Ok(None);
+        };
+
+        Self::replace_template_series(&mut tx, template_id, params.series).await?;
+        tx.commit().await?;
+
+        Ok(Some(template))
+    }
+
+    async fn replace_template_series(
+        tx: &mut Transaction<'_, Postgres>,
+        template_id: Uuid,
+        series: &[TemplateSeriesRequest],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM event_template_series WHERE template_id = $1")
+            .bind(template_id)
+            .execute(&mut **tx)
+            .await?;
+
+        for (position, series) in series.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO event_template_series (template_id, position, name, description,
+                    round_number, team_format, allow_reply_speeches, is_break_round, is_training)
+                VALUES ($1, $2, $3, $4, $5, $6::team_format, $7, $8, $9)
+                "#,
+            )
+            .bind(template_id)
+            .bind(position as i32)
+            .bind(&series.name)
+            .bind(&series.description)
+            .bind(series.round_number)
+            .bind(series.team_format.to_string())
+            .bind(series.allow_reply_speeches)
+            .bind(series.is_break_round)
+            .bind(series.is_training)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_event_template(&self, template_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_templates WHERE id = $1")
+            .bind(template_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Create an event from a template, starting at `event_date`, along
+    /// with the template's series. Returns the event and how many series
+    /// were created.
+    pub async fn create_event_from_template(
+        &self,
+        template: &EventTemplate,
+        event_date: DateTime<Utc>,
+        created_by: Uuid,
+    ) -> Result<(Event, u64), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let event_id = Self::insert_event(
+            &mut tx,
+            CreateEventParams {
+                title: &template.title,
+                description: template.description.as_deref(),
+                event_type: &template.event_type,
+                event_date,
+                location: template.location.as_deref(),
+                max_participants: template.max_participants,
+                geofence: None,
+                category_id: template.category_id,
+                tags: &template.tags,
+                duration_minutes: template.duration_minutes,
+                reminder_lead_hours: template.reminder_lead_hours,
+                reminder_schedule: template.reminder_schedule.as_deref(),
+                created_by,
+            },
+        )
+        .await?;
+
+        let series = sqlx::query(
+            r#"
+            INSERT INTO match_series (id, event_id, name, description, round_number, team_format,
+                allow_reply_speeches, is_break_round, is_training, created_by, created_at, updated_at)
+            SELECT gen_random_uuid(), $2, name, description, round_number, team_format,
+                allow_reply_speeches, is_break_round, is_training, $3, NOW(), NOW()
+            FROM event_template_series
+            WHERE template_id = $1
+            "#,
+        )
+        .bind(template.id)
+        .bind(event_id)
+        .bind(created_by)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let event = Self::event_in(&mut tx, event_id).await?;
+        tx.commit().await?;
+
+        Ok((event, series))
+    }
+
+    // ========================================================================
+    // Attendance Methods
+    // ========================================================================
+
+    pub async fn get_attendance_record(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AttendanceRecord>, sqlx::Error> {
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            SELECT id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            FROM attendance_records
+            WHERE event_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(self.reader())
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn set_availability(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        is_available: bool,
+    ) -> Result<AttendanceRecord, sqlx::Error> {
+        // Upsert - insert or update. Capacity is not checked, and either way
+        // the user leaves the waitlist.
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, availability_set_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, false, $5, $5, $5)
+            ON CONFLICT (event_id, user_id)
+            DO UPDATE SET is_available = $4, waitlisted_at = NULL, availability_set_at = $5, updated_at = $5
+            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(user_id)
+        .bind(is_available)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_events_by_ids(&self, event_ids: &[Uuid]) -> Result<Vec<Event>, sqlx::Error> {
+        let events = sqlx::query_as::<_, Event>(&format!(
+            r#"
+            SELECT {}
+            FROM events
+            WHERE id = ANY($1)
+            "#,
+            EVENT_COLUMNS
+        ))
+        .bind(event_ids)
+        .fetch_all(self.reader())
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Mark the user available if the event has a free seat, and otherwise
+    /// put them on its waitlist. A user already holding a seat keeps it, and
+    /// one already waiting keeps their place.
+    pub async fn claim_seat(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<AttendanceRecord, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+
+        // Locking the event row serialises seat allocation for the event
+        let (max_participants,): (Option<i32>,) =
+            sqlx::query_as("SELECT max_participants FROM events WHERE id = $1 FOR UPDATE")
+                .bind(event_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let (taken,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM attendance_records WHERE event_id = $1 AND is_available AND user_id <> $2",
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let full = max_participants.is_some_and(|max| taken >= i64::from(max));
+
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, waitlisted_at, availability_set_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, false, $5, $6, $6, $6)
+            ON CONFLICT (event_id, user_id)
+            DO UPDATE SET
+                is_available = attendance_records.is_available OR $4,
+                waitlisted_at = CASE WHEN attendance_records.is_available OR $4 THEN NULL
+                                     ELSE COALESCE(attendance_records.waitlisted_at, $5) END,
+                availability_set_at = $6,
+                updated_at = $6
+            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(user_id)
+        .bind(!full)
+        .bind(full.then_some(now))
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(record)
+    }
+
+    /// Give any free seats to the members who have waited longest, returning
+    /// the records of those promoted
+    pub async fn promote_from_waitlist(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<AttendanceRecordWithUser>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let (max_participants,): (Option<i32>,) =
+            sqlx::query_as("SELECT max_participants FROM events WHERE id = $1 FOR UPDATE")
+                .bind(event_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let (taken,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM attendance_records WHERE event_id = $1 AND is_available",
+        )
+        .bind(event_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // No limit promotes everyone waiting
+        let free = max_participants.map(|max| i64::from(max) - taken);
+        if free.is_some_and(|free| free <= 0) {
+            return Ok(Vec::new());
+        }
+
+        let promoted = sqlx::query_as::<_, AttendanceRecordWithUser>(
+            r#"
+            WITH promoted AS (
+                UPDATE attendance_records
+                SET is_available = true, waitlisted_at = NULL, availability_set_at = $2, updated_at = $2
+                WHERE id IN (
+                    SELECT id FROM attendance_records
+                    WHERE event_id = $1 AND waitlisted_at IS NOT NULL
+                    ORDER BY waitlisted_at
+                    LIMIT $3
+                )
+                RETURNING *
+            )
+            SELECT
+                p.id, p.event_id, p.user_id,
+                u.username,
+                p.is_available, p.is_checked_in, p.checked_in_by, p.checked_in_at,
+                p.waitlisted_at, p.checkin_distance_m, p.checkin_flagged, p.availability_set_at, p.created_at, p.updated_at
+            FROM promoted p
+            JOIN users u ON p.user_id = u.id
+            "#,
+        )
+        .bind(event_id)
+        .bind(Utc::now())
+        .bind(free)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(promoted)
+    }
+
+    /// The user's place in the event's waitlist, starting from 1, or None
+    /// when they are not waiting
+    pub async fn waitlist_position(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let position: Option<(i64,)> = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM attendance_records ahead
+            JOIN attendance_records me
+                ON me.event_id = ahead.event_id AND me.user_id = $2 AND me.waitlisted_at IS NOT NULL
+            WHERE ahead.event_id = $1
+                AND ahead.waitlisted_at IS NOT NULL
+                AND ahead.waitlisted_at <= me.waitlisted_at
+            GROUP BY me.id
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(position.map(|(position,)| position))
+    }
+
+    pub async fn check_in_user(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        is_checked_in: bool,
+        checked_in_by: Uuid,
+    ) -> Result<AttendanceRecord, sqlx::Error> {
+        let now = Utc::now();
+        let checked_in_at = if is_checked_in { Some(now) } else { None };
+        let mut tx = self.pool.begin().await?;
+
+        // Upsert with check-in information
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, availability_set_at, created_at, updated_at)
+            VALUES ($1, $2, $3, true, $4, $5, $6, $7, $7, $7)
+            ON CONFLICT (event_id, user_id)
+            DO UPDATE SET is_checked_in = $4, checked_in_by = $5, checked_in_at = $6, updated_at = $7,
+                checkin_distance_m = CASE WHEN $4 THEN attendance_records.checkin_distance_m END,
+                checkin_flagged = false
+            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(user_id)
+        .bind(is_checked_in)
+        .bind(if is_checked_in { Some(checked_in_by) } else { None })
+        .bind(checked_in_at)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if record.is_checked_in {
+            Self::enqueue_checked_in(&mut tx, record.id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// Check a member in on their own behalf. Without a seat limit this
+    /// also marks them available; with one, callers must check they hold a
+    /// seat first. Records a `user_checked_in` event.
+    pub async fn self_check_in(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        distance_m: Option<f64>,
+        flagged: bool,
+    ) -> Result<AttendanceRecord, sqlx::Error> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            INSERT INTO attendance_records (id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at,
+                checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at)
+            VALUES ($1, $2, $3, true, true, $3, $4, $5, $6, $4, $4, $4)
+            ON CONFLICT (event_id, user_id)
+            DO UPDATE SET is_available = true, waitlisted_at = NULL, is_checked_in = true, checked_in_by = $3,
+                checked_in_at = $4, checkin_distance_m = $5, checkin_flagged = $6, updated_at = $4
+            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(user_id)
+        .bind(now)
+        .bind(distance_m)
+        .bind(flagged)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::enqueue_checked_in(&mut tx, record.id).await?;
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// Record a `user_checked_in` event for the attendance record on the
+    /// transaction that checked the member in
+    async fn enqueue_checked_in(
+        conn: &mut PgConnection,
+        record_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        let payload: serde_json::Value = sqlx::query_scalar(
+            r#"
+            SELECT jsonb_build_object(
+                'record_id', ar.id,
+                'event_id', e.id,
+                'event_title', e.title,
+                'event_date', e.event_date,
+                'user_id', ar.user_id,
+                'username', u.username,
+                'checked_in_by', ar.checked_in_by,
+                'checked_in_by_username', cb.username,
+                'self_check_in', ar.checked_in_by = ar.user_id,
+                'distance_m', ar.checkin_distance_m,
+                'flagged', ar.checkin_flagged,
+                'checked_in_at', ar.checked_in_at
+            )
+            FROM attendance_records ar
+            JOIN events e ON ar.event_id = e.id
+            LEFT JOIN users u ON ar.user_id = u.id
+            LEFT JOIN users cb ON ar.checked_in_by = cb.id
+            WHERE ar.id = $1
+            "#,
+        )
+        .bind(record_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        common::outbox::enqueue(conn, outbox::SERVICE, outbox::USER_CHECKED_IN, &payload).await?;
+        Ok(())
+    }
+
+    /// Self check-ins from outside the geofence still awaiting review,
+    /// oldest first
+    pub async fn list_flagged_check_ins(
+        &self,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<AttendanceRecordWithUser>, sqlx::Error> {
+        sqlx::query_as::<_, AttendanceRecordWithUser>(
+            r#"
+            SELECT
+                ar.id, ar.event_id, ar.user_id,
+                u.username,
+                ar.is_available, ar.is_checked_in, ar.checked_in_by, ar.checked_in_at,
+                ar.waitlisted_at, ar.checkin_distance_m, ar.checkin_flagged, ar.availability_set_at,
+                ar.created_at, ar.updated_at
+            FROM attendance_records ar
+            INNER JOIN users u ON ar.user_id = u.id
+            WHERE ar.checkin_flagged AND ($1::uuid IS NULL OR ar.event_id = $1)
+            ORDER BY ar.checked_in_at ASC
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn revoke_availability(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<AttendanceRecord>, sqlx::Error> {
+        let record = sqlx::query_as::<_, AttendanceRecord>(
+            r#"
+            UPDATE attendance_records
+            SET is_available = false, is_checked_in = false, checked_in_by = NULL, checked_in_at = NULL,
+                waitlisted_at = NULL, checkin_distance_m = NULL, checkin_flagged = false, updated_at = $1
+            WHERE event_id = $2 AND user_id = $3
+            RETURNING id, event_id, user_id, is_available, is_checked_in, checked_in_by, checked_in_at, waitlisted_at, checkin_distance_m, checkin_flagged, availability_set_at, created_at, updated_at
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    pub async fn get_event_attendance(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<AttendanceRecordWithUser>, sqlx::Error> {
+        let records = sqlx::query_as::<_, AttendanceRecordWithUser>(
+            r#"
+            SELECT 
+                ar.id, ar.event_id, ar.user_id, 
+                u.username,
+                ar.is_available, ar.is_checked_in, ar.checked_in_by, ar.checked_in_at,
+                ar.waitlisted_at, ar.checkin_distance_m, ar.checkin_flagged, ar.availability_set_at,
+                CASE WHEN EXISTS (SELECT 1 FROM event_fees f WHERE f.event_id = ar.event_id)
+                    THEN COALESCE(ep.status, 'unpaid')
+                END AS payment_status,
+                ar.created_at, ar.updated_at
+            FROM attendance_records ar
+            JOIN users u ON ar.user_id = u.id
+            LEFT JOIN event_payments ep ON ep.event_id = ar.event_id AND ep.user_id = ar.user_id
+            WHERE ar.event_id = $1
+            ORDER BY ar.is_checked_in DESC, ar.is_available DESC, ar.waitlisted_at ASC NULLS LAST, ar.availability_set_at ASC
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await?;
+
+        Ok(records)
+    }
+
+    pub async fn get_attendance_stats(&self, event_id: Uuid) -> Result<(i64, i64), sqlx::Error> {
         let available: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM attendance_records WHERE event_id = $1 AND is_available = true",
         )
         .bind(event_id)
+        .fetch_one(self.reader())
+        .await?;
+
+        let checked_in: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM attendance_records WHERE event_id = $1 AND is_checked_in = true",
+        )
+        .bind(event_id)
+        .fetch_one(self.reader())
+        .await?;
+
+        Ok((available.0, checked_in.0))
+    }
+
+    /// Everyone who responded to the event or submitted an excuse for it,
+    /// by username
+    pub async fn event_report_rows(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<EventReportRow>, sqlx::Error> {
+        sqlx::query_as::<_, EventReportRow>(
+            r#"
+            SELECT u.id AS user_id, u.username,
+                COALESCE(ar.is_available, false) AS is_available,
+                ar.waitlisted_at IS NOT NULL AS is_waitlisted,
+                COALESCE(ar.is_checked_in, false) AS is_checked_in,
+                ar.checked_in_at,
+                COALESCE(ar.checkin_flagged, false) AS checkin_flagged,
+                ax.status AS excuse_status,
+                CASE WHEN EXISTS (SELECT 1 FROM event_fees WHERE event_id = $1)
+                    THEN COALESCE(ep.status, 'unpaid')
+                END AS payment_status
+            FROM users u
+            LEFT JOIN attendance_records ar ON ar.user_id = u.id AND ar.event_id = $1
+            LEFT JOIN absence_excuses ax ON ax.user_id = u.id AND ax.event_id = $1
+            LEFT JOIN event_payments ep ON ep.user_id = u.id AND ep.event_id = $1
+            WHERE ar.id IS NOT NULL OR ax.id IS NOT NULL OR ep.id IS NOT NULL
+            ORDER BY u.username
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    // ========================================================================
+    // Attendance Matrix/Dashboard Methods
+    // ========================================================================
+
+    /// Events dated in the range with their availability and check-in
+    /// counts, oldest first
+    pub async fn matrix_events(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<EventSummary>, sqlx::Error> {
+        sqlx::query_as::<_, EventSummary>(
+            r#"
+            SELECT e.id, e.title, e.event_type, e.event_date, e.is_locked,
+                COUNT(ar.id) FILTER (WHERE ar.is_available) AS total_available,
+                COUNT(ar.id) FILTER (WHERE ar.is_checked_in) AS total_checked_in
+            FROM events e
+            LEFT JOIN attendance_records ar ON ar.event_id = e.id
+            WHERE ($1::timestamptz IS NULL OR e.event_date >= $1)
+              AND ($2::timestamptz IS NULL OR e.event_date < $2)
+            GROUP BY e.id
+            ORDER BY e.event_date ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// One page of members' attendance over the events in the range, most
+    /// reliable first
+    pub async fn matrix_user_stats(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserAttendanceSummary>, sqlx::Error> {
+        sqlx::query_as::<_, UserAttendanceSummary>(&format!(
+            r#"
+            {}
+            SELECT user_id, username, events_available, events_checked_in, events_excused,
+                total_events,
+                COALESCE(events_available * 100.0 / NULLIF(total_events - events_excused, 0), 0)::FLOAT8
+                    AS availability_rate,
+                COALESCE(events_checked_in * 100.0 / NULLIF(total_events - events_excused, 0), 0)::FLOAT8
+                    AS attendance_rate
+            FROM user_stats
+            ORDER BY attendance_rate DESC, username ASC
+            LIMIT $3 OFFSET $4
+            "#,
+            MATRIX_USER_STATS
+        ))
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Attendance summed over every member and event in the range
+    pub async fn matrix_totals(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<MatrixTotals, sqlx::Error> {
+        sqlx::query_as::<_, MatrixTotals>(&format!(
+            r#"
+            {}
+            SELECT
+                (SELECT COUNT(*) FROM matrix_events) AS total_events,
+                COUNT(*) AS total_users,
+                COALESCE(SUM(events_available), 0)::BIGINT AS events_available,
+                COALESCE(SUM(events_checked_in), 0)::BIGINT AS events_checked_in,
+                COALESCE(SUM(events_excused), 0)::BIGINT AS events_excused
+            FROM user_stats
+            "#,
+            MATRIX_USER_STATS
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// (event_id, user_id, is_available, is_checked_in) of the members'
+    /// attendance records for events in the range
+    pub async fn matrix_records(
+        &self,
+        user_ids: &[Uuid],
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, Uuid, bool, bool)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT ar.event_id, ar.user_id, ar.is_available, ar.is_checked_in
+            FROM attendance_records ar
+            INNER JOIN events e ON ar.event_id = e.id
+            WHERE ar.user_id = ANY($1)
+              AND ($2::timestamptz IS NULL OR e.event_date >= $2)
+              AND ($3::timestamptz IS NULL OR e.event_date < $3)
+            "#,
+        )
+        .bind(user_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// (event_id, user_id) of the members' approved absence excuses for
+    /// events in the range
+    pub async fn matrix_excuses(
+        &self,
+        user_ids: &[Uuid],
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT ax.event_id, ax.user_id
+            FROM absence_excuses ax
+            INNER JOIN events e ON ax.event_id = e.id
+            WHERE ax.status = 'approved' AND ax.user_id = ANY($1)
+              AND ($2::timestamptz IS NULL OR e.event_date >= $2)
+              AND ($3::timestamptz IS NULL OR e.event_date < $3)
+            "#,
+        )
+        .bind(user_ids)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// How often one member has been available and checked in
+    pub async fn user_attendance_summary(
+        &self,
+        user_id: Uuid,
+    ) -> Result<AttendanceSummary, sqlx::Error> {
+        sqlx::query_as::<_, AttendanceSummary>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE is_available = true) as events_available,
+                COUNT(*) FILTER (WHERE is_checked_in = true) as events_checked_in,
+                (SELECT COUNT(*) FROM absence_excuses
+                 WHERE user_id = $1 AND status = 'approved') as events_excused,
+                MAX(checked_in_at) as last_checked_in_at
+            FROM attendance_records
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Get event type statistics for events in the range
+    pub async fn get_event_type_stats(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, i64, f64)>, sqlx::Error> {
+        let stats: Vec<(String, i64, f64)> = sqlx::query_as(
+            r#"
+            SELECT 
+                e.event_type,
+                COUNT(DISTINCT e.id)::BIGINT as event_count,
+                COALESCE(AVG(
+                    CASE WHEN ar.is_checked_in THEN 1.0 ELSE 0.0 END
+                )::FLOAT8 * 100, 0)::FLOAT8 as avg_attendance
+            FROM events e
+            LEFT JOIN attendance_records ar ON e.id = ar.event_id
+            WHERE ($1::timestamptz IS NULL OR e.event_date >= $1)
+              AND ($2::timestamptz IS NULL OR e.event_date < $2)
+            GROUP BY e.event_type
+            ORDER BY event_count DESC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.reader())
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Event totals and how many were held in each recent month
+    pub async fn event_stats(&self) -> Result<EventStats, sqlx::Error> {
+        let (total_events, upcoming_events): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE event_date > NOW()) FROM events
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let events_per_month = sqlx::query_as::<_, (DateTime<Utc>, i64)>(
+            r#"
+            SELECT m.period, COUNT(e.id)
+            FROM generate_series(
+                date_trunc('month', NOW()) - make_interval(months => $1 - 1),
+                date_trunc('month', NOW()),
+                INTERVAL '1 month'
+            ) AS m(period)
+            LEFT JOIN events e ON date_trunc('month', e.event_date) = m.period
+            GROUP BY m.period
+            ORDER BY m.period
+            "#,
+        )
+        .bind(STATS_MONTHS)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(PeriodCount::from)
+        .collect();
+
+        Ok(EventStats {
+            total_events,
+            upcoming_events,
+            events_per_month,
+        })
+    }
+
+    // ========================================================================
+    // Event Managers
+    // ========================================================================
+
+    pub async fn list_event_managers(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<EventManager>, sqlx::Error> {
+        sqlx::query_as::<_, EventManager>(
+            r#"
+            SELECT em.user_id, u.username, em.granted_by, em.created_at
+            FROM event_managers em
+            JOIN users u ON u.id = em.user_id
+            WHERE em.event_id = $1
+            ORDER BY em.created_at
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Make a verified member a manager of the event. Returns None if there
+    /// is no such member; making a manager again changes nothing.
+    pub async fn add_event_manager(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        granted_by: Uuid,
+    ) -> Result<Option<EventManager>, sqlx::Error> {
+        sqlx::query_as::<_, EventManager>(
+            r#"
+            WITH added AS (
+                INSERT INTO event_managers (event_id, user_id, granted_by)
+                SELECT $1, id, $3 FROM users WHERE id = $2 AND email_verified = true
+                ON CONFLICT (event_id, user_id) DO UPDATE SET user_id = EXCLUDED.user_id
+                RETURNING user_id, granted_by, created_at
+            )
+            SELECT added.user_id, u.username, added.granted_by, added.created_at
+            FROM added
+            JOIN users u ON u.id = added.user_id
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .bind(granted_by)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn remove_event_manager(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_managers WHERE event_id = $1 AND user_id = $2")
+            .bind(event_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Event Staff
+    // ========================================================================
+
+    pub async fn list_event_staff(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<EventStaffMember>, sqlx::Error> {
+        sqlx::query_as::<_, EventStaffMember>(
+            r#"
+            SELECT es.user_id, u.username, es.role, es.assigned_by, es.created_at
+            FROM event_staff es
+            JOIN users u ON u.id = es.user_id
+            WHERE es.event_id = $1
+            ORDER BY es.role, u.username
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Put a verified member on the event's staff, or change their role if
+    /// they are already on it. Returns None if there is no such member.
+    pub async fn assign_event_staff(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        role: StaffRole,
+        assigned_by: Uuid,
+    ) -> Result<Option<StaffAssignment>, sqlx::Error> {
+        sqlx::query_as::<_, StaffAssignment>(&format!(
+            r#"
+            WITH assigned AS (
+                INSERT INTO event_staff (event_id, user_id, role, assigned_by)
+                SELECT $1, id, $3, $4 FROM users
+                WHERE id = $2 AND email_verified = true AND deleted_at IS NULL
+                ON CONFLICT (event_id, user_id)
+                DO UPDATE SET role = EXCLUDED.role, assigned_by = EXCLUDED.assigned_by
+                RETURNING user_id, role, assigned_by, created_at
+            )
+            SELECT a.user_id, u.username, a.role, a.assigned_by, a.created_at,
+                u.email, {} AS wants_email
+            FROM assigned a
+            JOIN users u ON u.id = a.user_id
+            "#,
+            allows("a.user_id", Allocations, NotificationChannel::Email),
+        ))
+        .bind(event_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .bind(assigned_by)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn remove_event_staff(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_staff WHERE event_id = $1 AND user_id = $2")
+            .bind(event_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn is_event_staff(&self, event_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let staff: Option<(i32,)> =
+            sqlx::query_as("SELECT 1 FROM event_staff WHERE event_id = $1 AND user_id = $2")
+                .bind(event_id)
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(staff.is_some())
+    }
+
+    // ========================================================================
+    // Walk-ins
+    // ========================================================================
+
+    pub async fn list_walk_ins(&self, event_id: Uuid) -> Result<Vec<WalkIn>, sqlx::Error> {
+        sqlx::query_as::<_, WalkIn>(
+            r#"
+            SELECT w.*, i.name AS institution_name
+            FROM event_walk_ins w
+            LEFT JOIN institutions i ON i.id = w.institution_id
+            WHERE w.event_id = $1
+            ORDER BY w.created_at
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn institution_exists(&self, institution_id: Uuid) -> Result<bool, sqlx::Error> {
+        let found: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM institutions WHERE id = $1")
+            .bind(institution_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(found.is_some())
+    }
+
+    /// Register a guest for the event. Fails with a unique violation if a
+    /// guest of the same name is already registered for it.
+    pub async fn create_walk_in(
+        &self,
+        event_id: Uuid,
+        request: &WalkInRequest,
+        registered_by: Uuid,
+    ) -> Result<WalkIn, sqlx::Error> {
+        sqlx::query_as::<_, WalkIn>(
+            r#"
+            WITH registered AS (
+                INSERT INTO event_walk_ins (event_id, name, institution_id, email, registered_by)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            )
+            SELECT r.*, i.name AS institution_name
+            FROM registered r
+            LEFT JOIN institutions i ON i.id = r.institution_id
+            "#,
+        )
+        .bind(event_id)
+        .bind(request.name.trim())
+        .bind(request.institution_id)
+        .bind(&request.email)
+        .bind(registered_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn mark_walk_in_invited(&self, walk_in_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE event_walk_ins SET invited_at = NOW() WHERE id = $1")
+            .bind(walk_in_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Member Requirements
+    // ========================================================================
+
+    pub async fn count_attendees(&self, event_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM attendance_records
+            WHERE event_id = $1 AND (is_available OR is_checked_in)
+            "#,
+        )
+        .bind(event_id)
+        .fetch_one(self.reader())
+        .await
+    }
+
+    /// Attendees' dietary requirements, counted case-insensitively
+    pub async fn dietary_counts(&self, event_id: Uuid) -> Result<Vec<DietaryCount>, sqlx::Error> {
+        sqlx::query_as::<_, DietaryCount>(
+            r#"
+            SELECT LOWER(mr.dietary) AS requirement, COUNT(*) AS count
+            FROM attendance_records ar
+            JOIN member_requirements mr ON mr.user_id = ar.user_id
+            WHERE ar.event_id = $1 AND (ar.is_available OR ar.is_checked_in)
+              AND mr.dietary IS NOT NULL
+            GROUP BY LOWER(mr.dietary)
+            ORDER BY count DESC, requirement
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn accessibility_needs(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<AccessibilityNeed>, sqlx::Error> {
+        sqlx::query_as::<_, AccessibilityNeed>(
+            r#"
+            SELECT ar.user_id, u.username, mr.accessibility AS requirement
+            FROM attendance_records ar
+            JOIN member_requirements mr ON mr.user_id = ar.user_id
+            JOIN users u ON u.id = ar.user_id
+            WHERE ar.event_id = $1 AND (ar.is_available OR ar.is_checked_in)
+              AND mr.accessibility IS NOT NULL
+            ORDER BY u.username
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Fees
+    // ========================================================================
+
+    pub async fn get_event_fee(&self, event_id: Uuid) -> Result<Option<EventFee>, sqlx::Error> {
+        sqlx::query_as::<_, EventFee>(
+            r#"
+            SELECT event_id, amount_minor, currency, description, set_by, updated_at
+            FROM event_fees WHERE event_id = $1
+            "#,
+        )
+        .bind(event_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Set or change an event's fee. Payments already recorded keep the
+    /// amount they were made for.
+    pub async fn set_event_fee(
+        &self,
+        event_id: Uuid,
+        amount_minor: i32,
+        currency: &str,
+        description: Option<&str>,
+        set_by: Uuid,
+    ) -> Result<EventFee, sqlx::Error> {
+        sqlx::query_as::<_, EventFee>(
+            r#"
+            INSERT INTO event_fees (event_id, amount_minor, currency, description, set_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (event_id) DO UPDATE SET
+                amount_minor = EXCLUDED.amount_minor,
+                currency = EXCLUDED.currency,
+                description = EXCLUDED.description,
+                set_by = EXCLUDED.set_by,
+                updated_at = NOW()
+            RETURNING event_id, amount_minor, currency, description, set_by, updated_at
+            "#,
+        )
+        .bind(event_id)
+        .bind(amount_minor)
+        .bind(currency)
+        .bind(description)
+        .bind(set_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Make an event free again, keeping the payments already made
+    pub async fn remove_event_fee(&self, event_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_fees WHERE event_id = $1")
+            .bind(event_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Attendees and anyone who has paid, with their payments, by username
+    pub async fn list_event_payments(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<MemberPayment>, sqlx::Error> {
+        sqlx::query_as::<_, MemberPayment>(&format!(
+            "{} WHERE (ar.is_available OR ar.is_checked_in OR ep.id IS NOT NULL) ORDER BY u.username",
+            MEMBER_PAYMENTS
+        ))
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn get_member_payment(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<MemberPayment>, sqlx::Error> {
+        sqlx::query_as::<_, MemberPayment>(&format!("{} WHERE u.id = $2", MEMBER_PAYMENTS))
+            .bind(event_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Record a payment of the fee's current amount taken by hand, or a
+    /// waiver, replacing any unfinished online payment. Returns false if
+    /// there is no such member.
+    pub async fn record_payment(
+        &self,
+        fee: &EventFee,
+        user_id: Uuid,
+        status: PaymentStatus,
+        note: Option<&str>,
+        recorded_by: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO event_payments
+                (event_id, user_id, status, method, amount_minor, currency, note, recorded_by,
+                 paid_at)
+            SELECT $1, id, $3, 'manual', CASE WHEN $3 = 'paid' THEN $4 END, $5, $6, $7,
+                CASE WHEN $3 = 'paid' THEN NOW() END
+            FROM users WHERE id = $2
+            ON CONFLICT (event_id, user_id) DO UPDATE SET
+                status = EXCLUDED.status,
+                method = EXCLUDED.method,
+                amount_minor = EXCLUDED.amount_minor,
+                currency = EXCLUDED.currency,
+                stripe_session_id = NULL,
+                note = EXCLUDED.note,
+                recorded_by = EXCLUDED.recorded_by,
+                paid_at = EXCLUDED.paid_at,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(fee.event_id)
+        .bind(user_id)
+        .bind(status.as_str())
+        .bind(fee.amount_minor)
+        .bind(&fee.currency)
+        .bind(note)
+        .bind(recorded_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Undo a recorded payment or waiver, so the member owes the fee again
+    pub async fn clear_payment(&self, event_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM event_payments WHERE event_id = $1 AND user_id = $2")
+            .bind(event_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Note an online payment the member has been sent to Stripe for. An
+    /// earlier unfinished checkout is replaced; a settled payment is kept.
+    pub async fn start_checkout_payment(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        session_id: &str,
+        currency: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO event_payments
+                (event_id, user_id, status, method, stripe_session_id, currency)
+            VALUES ($1, $2, 'pending', 'stripe', $3, $4)
+            ON CONFLICT (event_id, user_id) DO UPDATE SET
+                stripe_session_id = EXCLUDED.stripe_session_id,
+                currency = EXCLUDED.currency,
+                updated_at = NOW()
+            WHERE event_payments.status = 'pending'
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .bind(session_id)
+        .bind(currency)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a checkout Stripe has confirmed as paid, returning who paid for
+    /// what. None if the checkout is unknown or was already settled.
+    pub async fn complete_checkout_payment(
+        &self,
+        session_id: &str,
+        amount_minor: Option<i32>,
+    ) -> Result<Option<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE event_payments
+            SET status = 'paid', amount_minor = $2, paid_at = NOW(), updated_at = NOW()
+            WHERE stripe_session_id = $1 AND status = 'pending'
+            RETURNING event_id, user_id
+            "#,
+        )
+        .bind(session_id)
+        .bind(amount_minor)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Forget a checkout the member abandoned
+    pub async fn expire_checkout_payment(&self, session_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM event_payments WHERE stripe_session_id = $1 AND status = 'pending'",
+        )
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Equipment
+    // ========================================================================
+
+    pub async fn list_equipment(
+        &self,
+        include_retired: bool,
+    ) -> Result<Vec<EquipmentItem>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentItem>(&format!(
+            r#"
+            WITH items AS (
+                SELECT * FROM equipment_items WHERE $1 OR retired_at IS NULL
+            )
+            {}
+            ORDER BY i.kind, i.name
+            "#,
+            EQUIPMENT_ITEMS
+        ))
+        .bind(include_retired)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn get_equipment(&self, item_id: Uuid) -> Result<Option<EquipmentItem>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentItem>(&format!(
+            "WITH items AS (SELECT * FROM equipment_items WHERE id = $1) {}",
+            EQUIPMENT_ITEMS
+        ))
+        .bind(item_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    pub async fn create_equipment(
+        &self,
+        request: &EquipmentRequest,
+        created_by: Uuid,
+    ) -> Result<EquipmentItem, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentItem>(&format!(
+            r#"
+            WITH items AS (
+                INSERT INTO equipment_items (name, kind, asset_tag, notes, created_by)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            )
+            {}
+            "#,
+            EQUIPMENT_ITEMS
+        ))
+        .bind(request.name.trim())
+        .bind(request.kind.trim().to_lowercase())
+        .bind(request.asset_tag.as_deref().map(str::trim))
+        .bind(request.notes.as_deref())
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn update_equipment(
+        &self,
+        item_id: Uuid,
+        request: &EquipmentRequest,
+    ) -> Result<Option<EquipmentItem>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentItem>(&format!(
+            r#"
+            WITH items AS (
+                UPDATE equipment_items
+                SET name = $2, kind = $3, asset_tag = $4, notes = $5, updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            {}
+            "#,
+            EQUIPMENT_ITEMS
+        ))
+        .bind(item_id)
+        .bind(request.name.trim())
+        .bind(request.kind.trim().to_lowercase())
+        .bind(request.asset_tag.as_deref().map(str::trim))
+        .bind(request.notes.as_deref())
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Stop lending an item, keeping its history. Items that are out must
+    /// come back first; returns false for those and for retired items.
+    pub async fn retire_equipment(&self, item_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE equipment_items SET retired_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND retired_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM equipment_checkouts
+                  WHERE item_id = $1 AND returned_at IS NULL
+              )
+            "#,
+        )
+        .bind(item_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Lend an item. Returns None if it is already out or has been retired.
+    pub async fn checkout_equipment(
+        &self,
+        item_id: Uuid,
+        request: &CheckoutEquipmentRequest,
+        due_at: DateTime<Utc>,
+        checked_out_by: Uuid,
+    ) -> Result<Option<EquipmentCheckout>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentCheckout>(&format!(
+            r#"
+            WITH checkouts AS (
+                INSERT INTO equipment_checkouts
+                    (item_id, user_id, event_id, due_at, note, checked_out_by)
+                SELECT id, $2, $3, $4, $5, $6
+                FROM equipment_items
+                WHERE id = $1 AND retired_at IS NULL
+                ON CONFLICT (item_id) WHERE returned_at IS NULL DO NOTHING
+                RETURNING *
+            )
+            {}
+            "#,
+            EQUIPMENT_CHECKOUTS
+        ))
+        .bind(item_id)
+        .bind(request.user_id)
+        .bind(request.event_id)
+        .bind(due_at)
+        .bind(request.note.as_deref())
+        .bind(checked_out_by)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Close an item's open checkout. Returns None if it was not out.
+    pub async fn return_equipment(
+        &self,
+        item_id: Uuid,
+        returned_to: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<EquipmentCheckout>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentCheckout>(&format!(
+            r#"
+            WITH checkouts AS (
+                UPDATE equipment_checkouts
+                SET returned_at = NOW(), returned_to = $2, return_note = $3
+                WHERE item_id = $1 AND returned_at IS NULL
+                RETURNING *
+            )
+            {}
+            "#,
+            EQUIPMENT_CHECKOUTS
+        ))
+        .bind(item_id)
+        .bind(returned_to)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Checkouts matching the filter, most recent first
+    pub async fn list_equipment_checkouts(
+        &self,
+        filter: CheckoutFilter,
+    ) -> Result<Vec<EquipmentCheckout>, sqlx::Error> {
+        sqlx::query_as::<_, EquipmentCheckout>(&format!(
+            r#"
+            WITH checkouts AS (
+                SELECT * FROM equipment_checkouts
+                WHERE ($1::UUID IS NULL OR item_id = $1)
+                  AND ($2::UUID IS NULL OR event_id = $2)
+                  AND ($3::UUID IS NULL OR user_id = $3)
+                  AND (NOT $4 OR returned_at IS NULL)
+                  AND (NOT $5 OR (returned_at IS NULL AND due_at < NOW()))
+            )
+            {}
+            ORDER BY c.checked_out_at DESC
+            "#,
+            EQUIPMENT_CHECKOUTS
+        ))
+        .bind(filter.item_id)
+        .bind(filter.event_id)
+        .bind(filter.user_id)
+        .bind(filter.open_only)
+        .bind(filter.overdue_only)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Expenses
+    // ========================================================================
+
+    pub async fn create_expense(
+        &self,
+        event_id: Uuid,
+        request: &CreateExpenseRequest,
+        currency: &str,
+        submitted_by: Uuid,
+    ) -> Result<EventExpense, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>(
+            r#"
+            INSERT INTO event_expenses
+                (event_id, category, description, amount_minor, currency, spent_on, submitted_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(event_id)
+        .bind(request.category.as_str())
+        .bind(request.description.trim())
+        .bind(request.amount_minor)
+        .bind(currency)
+        .bind(request.spent_on)
+        .bind(submitted_by)
         .fetch_one(&self.pool)
-        .await?;
+        .await
+    }
 
-        let checked_in: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM attendance_records WHERE event_id = $1 AND is_checked_in = true",
+    pub async fn get_expense(&self, expense_id: Uuid) -> Result<Option<EventExpense>, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>("SELECT * FROM event_expenses WHERE id = $1")
+            .bind(expense_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    /// An event's expenses, most recent spending first
+    pub async fn list_expenses(
+        &self,
+        event_id: Uuid,
+        status: Option<ExpenseStatus>,
+    ) -> Result<Vec<EventExpense>, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>(
+            r#"
+            SELECT * FROM event_expenses
+            WHERE event_id = $1 AND ($2::VARCHAR IS NULL OR status = $2)
+            ORDER BY spent_on DESC, created_at DESC
+            "#,
         )
         .bind(event_id)
-        .fetch_one(&self.pool)
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Delete an expense that has not been reviewed, returning it so its
+    /// receipt can be removed. None if it is missing or already reviewed.
+    pub async fn delete_pending_expense(
+        &self,
+        expense_id: Uuid,
+    ) -> Result<Option<EventExpense>, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>(
+            "DELETE FROM event_expenses WHERE id = $1 AND status = 'pending' RETURNING *",
+        )
+        .bind(expense_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Attach a receipt to an expense that has not been reviewed
+    pub async fn set_expense_receipt(
+        &self,
+        expense_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        key: &str,
+        size_bytes: i64,
+    ) -> Result<Option<EventExpense>, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>(
+            r#"
+            UPDATE event_expenses
+            SET receipt_filename = $2, receipt_content_type = $3, receipt_key = $4,
+                receipt_size_bytes = $5, updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(expense_id)
+        .bind(filename)
+        .bind(content_type)
+        .bind(key)
+        .bind(size_bytes)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Approve or reject an expense. A decision can be revised later.
+    pub async fn review_expense(
+        &self,
+        expense_id: Uuid,
+        status: ExpenseStatus,
+        reviewed_by: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<EventExpense>, sqlx::Error> {
+        sqlx::query_as::<_, EventExpense>(
+            r#"
+            UPDATE event_expenses
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_note = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(expense_id)
+        .bind(status.as_str())
+        .bind(reviewed_by)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Fees collected and money spent per event and currency, for events in
+    /// the date range, or for one event. Events with no money moving are
+    /// left out.
+    pub async fn event_finances(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<EventFinances>, sqlx::Error> {
+        sqlx::query_as::<_, EventFinances>(
+            r#"
+            WITH flows AS (
+                SELECT event_id, currency, amount_minor AS collected, 0 AS approved, 0 AS pending
+                FROM event_payments
+                WHERE status = 'paid' AND currency IS NOT NULL AND amount_minor IS NOT NULL
+                UNION ALL
+                SELECT event_id, currency, 0,
+                    CASE WHEN status = 'approved' THEN amount_minor ELSE 0 END,
+                    CASE WHEN status = 'pending' THEN amount_minor ELSE 0 END
+                FROM event_expenses
+            )
+            SELECT e.id AS event_id, e.title, e.event_date, f.currency,
+                SUM(f.collected) AS collected_minor,
+                SUM(f.approved) AS approved_minor,
+                SUM(f.pending) AS pending_minor,
+                SUM(f.collected) - SUM(f.approved) AS balance_minor
+            FROM flows f
+            JOIN events e ON e.id = f.event_id
+            WHERE ($1::DATE IS NULL OR e.event_date::DATE >= $1)
+              AND ($2::DATE IS NULL OR e.event_date::DATE <= $2)
+              AND ($3::UUID IS NULL OR e.id = $3)
+            GROUP BY e.id, e.title, e.event_date, f.currency
+            ORDER BY e.event_date DESC, f.currency
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// An event's spending by category, rejected expenses left out
+    pub async fn expense_category_totals(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<ExpenseCategoryTotal>, sqlx::Error> {
+        sqlx::query_as::<_, ExpenseCategoryTotal>(
+            r#"
+            SELECT category, currency, COUNT(*) AS expenses,
+                COALESCE(SUM(amount_minor) FILTER (WHERE status = 'approved'), 0) AS approved_minor,
+                COALESCE(SUM(amount_minor) FILTER (WHERE status = 'pending'), 0) AS pending_minor
+            FROM event_expenses
+            WHERE event_id = $1 AND status <> 'rejected'
+            GROUP BY category, currency
+            ORDER BY category, currency
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Role Methods
+    // ========================================================================
+
+    pub async fn has_role(&self, user_id: Uuid, role: Role) -> Result<bool, sqlx::Error> {
+        let held: Option<(i32,)> =
+            sqlx::query_as("SELECT 1 FROM user_roles WHERE user_id = $1 AND role = $2")
+                .bind(user_id)
+                .bind(role.as_str())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(held.is_some())
+    }
+
+    /// Whether the member is suspended by the auth service now
+    pub async fn is_user_suspended(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT user_is_suspended($1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Live key with this hash that may read from this service, noting
+    /// that it was used. Keys made by a suspended member stop working.
+    pub async fn find_api_key(&self, key_hash: &str) -> Result<Option<ApiClient>, sqlx::Error> {
+        let key: Option<(Uuid, String)> = sqlx::query_as(
+            r#"
+            UPDATE api_keys SET last_used_at = NOW()
+            WHERE key_hash = $1 AND revoked_at IS NULL AND $2 = ANY(scopes)
+                AND (created_by IS NULL OR NOT user_is_suspended(created_by))
+            RETURNING id, name
+            "#,
+        )
+        .bind(key_hash)
+        .bind(ApiKeyScope::Attendance.as_str())
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok((available.0, checked_in.0))
+        Ok(key.map(|(key_id, name)| ApiClient { key_id, name }))
     }
 
     // ========================================================================
-    // Attendance Matrix/Dashboard Methods
+    // Conduct Report Methods
     // ========================================================================
 
-    /// Get all events ordered by date for the matrix
-    pub async fn get_all_events_for_matrix(&self) -> Result<Vec<Event>, sqlx::Error> {
-        let events = sqlx::query_as::<_, Event>(
+    /// Event a match belongs to, if the match exists
+    pub async fn get_match_event_id(&self, match_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        let event: Option<(Uuid,)> = sqlx::query_as(
             r#"
-            SELECT id, title, description, event_type, event_date, location, created_by, is_locked, created_at, updated_at
-            FROM events
-            ORDER BY event_date ASC
+            SELECT s.event_id FROM matches m
+            JOIN match_series s ON m.series_id = s.id
+            WHERE m.id = $1
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(match_id)
+        .fetch_optional(self.reader())
         .await?;
 
-        Ok(events)
+        Ok(event.map(|(event_id,)| event_id))
     }
 
-    pub async fn get_all_users(&self) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
-        let users: Vec<(Uuid, String)> = sqlx::query_as(
+    /// File a report and record its creation in the audit log. Anonymous
+    /// reports store no reporter anywhere, including the log.
+    pub async fn create_conduct_report(
+        &self,
+        reporter_id: Option<Uuid>,
+        event_id: Option<Uuid>,
+        match_id: Option<Uuid>,
+        description: &str,
+    ) -> Result<ConductReport, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let report = sqlx::query_as::<_, ConductReport>(
             r#"
-            SELECT id, username
-            FROM users
-            ORDER BY username ASC
+            INSERT INTO conduct_reports (id, reporter_id, is_anonymous, event_id, match_id, description)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(Uuid::new_v4())
+        .bind(reporter_id)
+        .bind(reporter_id.is_none())
+        .bind(event_id)
+        .bind(match_id)
+        .bind(description)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::insert_report_audit(
+            &mut tx,
+            report.id,
+            reporter_id,
+            "created",
+            None,
+            Some(ReportStatus::Open),
+            None,
+        )
         .await?;
 
-        Ok(users)
+        tx.commit().await?;
+        Ok(report)
     }
 
-    /// Get all attendance records for the matrix (returns event_id, user_id, is_available, is_checked_in)
-    pub async fn get_all_attendance_records(
+    /// Reports the user filed under their own name
+    pub async fn list_reports_by_reporter(
         &self,
-    ) -> Result<Vec<(Uuid, Uuid, bool, bool)>, sqlx::Error> {
-        let records: Vec<(Uuid, Uuid, bool, bool)> = sqlx::query_as(
+        reporter_id: Uuid,
+    ) -> Result<Vec<ConductReport>, sqlx::Error> {
+        sqlx::query_as::<_, ConductReport>(
+            "SELECT * FROM conduct_reports WHERE reporter_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(reporter_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn list_conduct_reports(
+        &self,
+        status: Option<ReportStatus>,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<ConductReportSummary>, sqlx::Error> {
+        sqlx::query_as::<_, ConductReportSummary>(
             r#"
-            SELECT event_id, user_id, is_available, is_checked_in
-            FROM attendance_records
+            SELECT r.id, r.is_anonymous, r.event_id, e.title AS event_title, r.match_id,
+                r.status, r.created_at, r.updated_at
+            FROM conduct_reports r
+            LEFT JOIN events e ON r.event_id = e.id
+            WHERE ($1::text IS NULL OR r.status = $1)
+              AND ($2::uuid IS NULL OR r.event_id = $2)
+            ORDER BY r.created_at DESC
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(status.map(|s| s.to_string()))
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Read a report on behalf of an equity officer, logging the access
+    pub async fn view_conduct_report(
+        &self,
+        report_id: Uuid,
+        officer_id: Uuid,
+    ) -> Result<Option<ConductReport>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let report =
+            sqlx::query_as::<_, ConductReport>("SELECT * FROM conduct_reports WHERE id = $1")
+                .bind(report_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        if report.is_some() {
+            Self::insert_report_audit(
+                &mut tx,
+                report_id,
+                Some(officer_id),
+                "viewed",
+                None,
+                None,
+                None,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    pub async fn list_report_audit(
+        &self,
+        report_id: Uuid,
+    ) -> Result<Vec<ReportAuditEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ReportAuditEntry>(
+            r#"
+            SELECT a.id, a.actor_id, u.username AS actor_username, a.action, a.from_status,
+                a.to_status, a.note, a.created_at
+            FROM conduct_report_audit a
+            LEFT JOIN users u ON a.actor_id = u.id
+            WHERE a.report_id = $1
+            ORDER BY a.created_at ASC
+            "#,
+        )
+        .bind(report_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Current status of a report, without recording an access
+    pub async fn get_conduct_report_status(
+        &self,
+        report_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>("SELECT status FROM conduct_reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    /// Move a report from `from` to `to`. Returns None if the report is no
+    /// longer in `from`, e.g. because another officer changed it first.
+    pub async fn update_conduct_report_status(
+        &self,
+        report_id: Uuid,
+        officer_id: Uuid,
+        from: ReportStatus,
+        to: ReportStatus,
+        note: Option<&str>,
+    ) -> Result<Option<ConductReport>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let report = sqlx::query_as::<_, ConductReport>(
+            r#"
+            UPDATE conduct_reports SET status = $3, updated_at = NOW()
+            WHERE id = $1 AND status = $2
+            RETURNING *
+            "#,
+        )
+        .bind(report_id)
+        .bind(from.to_string())
+        .bind(to.to_string())
+        .fetch_optional(&mut *tx)
         .await?;
 
-        Ok(records)
+        if report.is_some() {
+            Self::insert_report_audit(
+                &mut tx,
+                report_id,
+                Some(officer_id),
+                "status_changed",
+                Some(from),
+                Some(to),
+                note,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    pub async fn add_conduct_report_note(
+        &self,
+        report_id: Uuid,
+        officer_id: Uuid,
+        note: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        Self::insert_report_audit(
+            &mut tx,
+            report_id,
+            Some(officer_id),
+            "note_added",
+            None,
+            None,
+            Some(note),
+        )
+        .await?;
+        sqlx::query("UPDATE conduct_reports SET updated_at = NOW() WHERE id = $1")
+            .bind(report_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
     }
 
-    /// Get event stats (available count, checked-in count) for each event
-    pub async fn get_all_event_stats(&self) -> Result<Vec<(Uuid, i64, i64)>, sqlx::Error> {
-        let stats: Vec<(Uuid, i64, i64)> = sqlx::query_as(
+    async fn insert_report_audit(
+        tx: &mut Transaction<'_, Postgres>,
+        report_id: Uuid,
+        actor_id: Option<Uuid>,
+        action: &str,
+        from_status: Option<ReportStatus>,
+        to_status: Option<ReportStatus>,
+        note: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
             r#"
-            SELECT 
-                event_id,
-                COUNT(*) FILTER (WHERE is_available = true) as available_count,
-                COUNT(*) FILTER (WHERE is_checked_in = true) as checked_in_count
-            FROM attendance_records
-            GROUP BY event_id
+            INSERT INTO conduct_report_audit (report_id, actor_id, action, from_status, to_status, note)
+            VALUES ($1, $2, $3, $4, $5, $6)
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(report_id)
+        .bind(actor_id)
+        .bind(action)
+        .bind(from_status.map(|s| s.to_string()))
+        .bind(to_status.map(|s| s.to_string()))
+        .bind(note)
+        .execute(&mut **tx)
         .await?;
 
-        Ok(stats)
+        Ok(())
     }
 
-    /// Get user attendance stats
-    pub async fn get_user_attendance_stats(
+    // ========================================================================
+    // Absence Excuse Methods
+    // ========================================================================
+
+    /// File an excuse, or replace the reason on one still awaiting review.
+    /// Returns None if the member's excuse for the event was already reviewed.
+    pub async fn submit_excuse(
         &self,
-    ) -> Result<Vec<(Uuid, String, i64, i64)>, sqlx::Error> {
-        let stats: Vec<(Uuid, String, i64, i64)> = sqlx::query_as(
+        event_id: Uuid,
+        user_id: Uuid,
+        reason: &str,
+    ) -> Result<Option<AbsenceExcuse>, sqlx::Error> {
+        sqlx::query_as::<_, AbsenceExcuse>(
             r#"
-            SELECT 
-                u.id,
-                u.username,
-                COUNT(*) FILTER (WHERE ar.is_available = true) as available_count,
-                COUNT(*) FILTER (WHERE ar.is_checked_in = true) as checked_in_count
-            FROM users u
-            LEFT JOIN attendance_records ar ON u.id = ar.user_id
-            GROUP BY u.id, u.username
-            ORDER BY u.username ASC
+            INSERT INTO absence_excuses (id, event_id, user_id, reason)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (event_id, user_id) DO UPDATE
+            SET reason = EXCLUDED.reason, updated_at = NOW()
+            WHERE absence_excuses.status = 'pending'
+            RETURNING *
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(Uuid::new_v4())
+        .bind(event_id)
+        .bind(user_id)
+        .bind(reason)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn get_excuse_by_id(
+        &self,
+        excuse_id: Uuid,
+    ) -> Result<Option<AbsenceExcuse>, sqlx::Error> {
+        sqlx::query_as::<_, AbsenceExcuse>("SELECT * FROM absence_excuses WHERE id = $1")
+            .bind(excuse_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    /// Excuses matching the filters, newest first; `user_id` limits the list
+    /// to one member's own excuses
+    pub async fn list_excuses(
+        &self,
+        user_id: Option<Uuid>,
+        status: Option<ExcuseStatus>,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<AbsenceExcuseSummary>, sqlx::Error> {
+        sqlx::query_as::<_, AbsenceExcuseSummary>(
+            r#"
+            SELECT x.id, x.event_id, e.title AS event_title, e.event_date, x.user_id,
+                u.username, x.reason, x.attachment_filename, x.status, x.reviewed_at,
+                x.review_note, x.created_at
+            FROM absence_excuses x
+            INNER JOIN events e ON x.event_id = e.id
+            INNER JOIN users u ON x.user_id = u.id
+            WHERE ($1::uuid IS NULL OR x.user_id = $1)
+              AND ($2::text IS NULL OR x.status = $2)
+              AND ($3::uuid IS NULL OR x.event_id = $3)
+            ORDER BY x.created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(status.map(|s| s.to_string()))
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Record the evidence stored for a pending excuse. Returns None once
+    /// the excuse has been reviewed.
+    pub async fn set_excuse_attachment(
+        &self,
+        excuse_id: Uuid,
+        filename: &str,
+        content_type: &str,
+        key: &str,
+        size_bytes: i64,
+    ) -> Result<Option<AbsenceExcuse>, sqlx::Error> {
+        sqlx::query_as::<_, AbsenceExcuse>(
+            r#"
+            UPDATE absence_excuses
+            SET attachment_filename = $2, attachment_content_type = $3, attachment_key = $4,
+                attachment_size_bytes = $5, updated_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(excuse_id)
+        .bind(filename)
+        .bind(content_type)
+        .bind(key)
+        .bind(size_bytes)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Approve or reject an excuse. A decision can be revised later.
+    pub async fn review_excuse(
+        &self,
+        excuse_id: Uuid,
+        status: ExcuseStatus,
+        reviewed_by: Uuid,
+        note: Option<&str>,
+    ) -> Result<Option<AbsenceExcuse>, sqlx::Error> {
+        sqlx::query_as::<_, AbsenceExcuse>(
+            r#"
+            UPDATE absence_excuses
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_note = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(excuse_id)
+        .bind(status.to_string())
+        .bind(reviewed_by)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    // ========================================================================
+    // Announcement Methods
+    // ========================================================================
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_announcement(
+        &self,
+        title: &str,
+        body: &str,
+        event_id: Option<Uuid>,
+        audience_role: Option<Role>,
+        is_important: bool,
+        publish_at: DateTime<Utc>,
+        created_by: Uuid,
+    ) -> Result<Announcement, sqlx::Error> {
+        sqlx::query_as::<_, Announcement>(
+            r#"
+            INSERT INTO announcements
+                (id, title, body, event_id, audience_role, is_important, publish_at, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(title)
+        .bind(body)
+        .bind(event_id)
+        .bind(audience_role.map(|r| r.as_str()))
+        .bind(is_important)
+        .bind(publish_at)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn update_announcement(
+        &self,
+        announcement_id: Uuid,
+        title: Option<&str>,
+        body: Option<&str>,
+        is_important: Option<bool>,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Announcement>, sqlx::Error> {
+        sqlx::query_as::<_, Announcement>(
+            r#"
+            UPDATE announcements
+            SET title = COALESCE($2, title), body = COALESCE($3, body),
+                is_important = COALESCE($4, is_important),
+                publish_at = COALESCE($5, publish_at), updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(announcement_id)
+        .bind(title)
+        .bind(body)
+        .bind(is_important)
+        .bind(publish_at)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn delete_announcement(&self, announcement_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM announcements WHERE id = $1")
+            .bind(announcement_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every announcement, newest publication first, with how many members
+    /// have read it
+    pub async fn list_all_announcements(
+        &self,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<AnnouncementWithStats>, i64), sqlx::Error> {
+        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM announcements")
+            .fetch_one(self.reader())
+            .await?;
+
+        let announcements = sqlx::query_as::<_, AnnouncementWithStats>(
+            r#"
+            SELECT a.id, a.title, a.body, a.event_id, e.title AS event_title, a.audience_role,
+                a.is_important, a.publish_at, a.publish_at <= NOW() AS is_published,
+                (SELECT COUNT(*) FROM announcement_reads r WHERE r.announcement_id = a.id)
+                    AS read_count,
+                a.created_by, a.created_at, a.updated_at
+            FROM announcements a
+            LEFT JOIN events e ON a.event_id = e.id
+            ORDER BY a.publish_at DESC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(per_page as i64)
+        .bind(((page - 1) * per_page) as i64)
+        .fetch_all(self.reader())
         .await?;
 
-        Ok(stats)
+        Ok((announcements, total.0))
     }
 
-    /// Get event type statistics
-    pub async fn get_event_type_stats(&self) -> Result<Vec<(String, i64, f64)>, sqlx::Error> {
-        let stats: Vec<(String, i64, f64)> = sqlx::query_as(
+    /// Announcements visible to the user, newest first. Returns the page,
+    /// the number of matching announcements and the number still unread.
+    pub async fn list_announcements_for_user(
+        &self,
+        user_id: Uuid,
+        page: i32,
+        per_page: i32,
+        unread_only: bool,
+    ) -> Result<(Vec<AnnouncementForUser>, i64, i64), sqlx::Error> {
+        let (total, unread): (i64, i64) = sqlx::query_as(&format!(
             r#"
-            SELECT 
-                e.event_type,
-                COUNT(DISTINCT e.id)::BIGINT as event_count,
-                COALESCE(AVG(
-                    CASE WHEN ar.is_checked_in THEN 1.0 ELSE 0.0 END
-                )::FLOAT8 * 100, 0)::FLOAT8 as avg_attendance
-            FROM events e
-            LEFT JOIN attendance_records ar ON e.id = ar.event_id
-            GROUP BY e.event_type
-            ORDER BY event_count DESC
+            SELECT COUNT(*) FILTER (WHERE NOT $2 OR r.read_at IS NULL),
+                COUNT(*) FILTER (WHERE r.read_at IS NULL)
+            FROM announcements a
+            LEFT JOIN announcement_reads r ON r.announcement_id = a.id AND r.user_id = $1
+            WHERE {}
+            "#,
+            ANNOUNCEMENT_VISIBLE_TO_USER
+        ))
+        .bind(user_id)
+        .bind(unread_only)
+        .fetch_one(self.reader())
+        .await?;
+
+        let announcements = sqlx::query_as::<_, AnnouncementForUser>(&format!(
+            r#"
+            SELECT a.id, a.title, a.body, a.event_id, e.title AS event_title, a.audience_role,
+                a.is_important, a.publish_at, r.read_at
+            FROM announcements a
+            LEFT JOIN events e ON a.event_id = e.id
+            LEFT JOIN announcement_reads r ON r.announcement_id = a.id AND r.user_id = $1
+            WHERE {} AND (NOT $2 OR r.read_at IS NULL)
+            ORDER BY a.publish_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            ANNOUNCEMENT_VISIBLE_TO_USER
+        ))
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(per_page as i64)
+        .bind(((page - 1) * per_page) as i64)
+        .fetch_all(self.reader())
+        .await?;
+
+        Ok((announcements, total, unread))
+    }
+
+    /// Mark an announcement read. Returns false if the user cannot see it.
+    pub async fn mark_announcement_read(
+        &self,
+        user_id: Uuid,
+        announcement_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let visible: Option<(Uuid,)> = sqlx::query_as(&format!(
+            "SELECT a.id FROM announcements a WHERE a.id = $2 AND {}",
+            ANNOUNCEMENT_VISIBLE_TO_USER
+        ))
+        .bind(user_id)
+        .bind(announcement_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if visible.is_none() {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO announcement_reads (announcement_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (announcement_id, user_id) DO NOTHING
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(announcement_id)
+        .bind(user_id)
+        .execute(&self.pool)
         .await?;
 
-        Ok(stats)
+        Ok(true)
+    }
+
+    /// Mark every announcement currently visible to the user as read
+    pub async fn mark_all_announcements_read(&self, user_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(&format!(
+            r#"
+            INSERT INTO announcement_reads (announcement_id, user_id)
+            SELECT a.id, $1 FROM announcements a WHERE {}
+            ON CONFLICT (announcement_id, user_id) DO NOTHING
+            "#,
+            ANNOUNCEMENT_VISIBLE_TO_USER
+        ))
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
     }
 }
@@ -0,0 +1,14 @@
+//! Equipment loans. Admins keep a register of the society's kit and lend
+//! items to members for an event; each item is out until it is handed back,
+//! so there is always a name against missing kit. Items still out after they
+//! were due back are reported as overdue.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::Event;
+
+/// When kit lent for an event is due back if no time was given: a set
+/// number of hours after the event ends
+pub fn default_due(event: &Event, return_hours: u32) -> DateTime<Utc> {
+    event.ends_at() + Duration::hours(i64::from(return_hours))
+}
@@ -0,0 +1,23 @@
+//! Conditional GETs for the event endpoint that clients poll. The event
+//! looks the same to every reader, so they share one ETag.
+
+use axum::{
+    extract::{Path, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use common::etag::conditional_get;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub async fn event_etag(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let version = state.db.event_version(event_id).await.ok().flatten();
+    conditional_get(request, next, version, None).await
+}
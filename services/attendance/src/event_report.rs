@@ -0,0 +1,323 @@
+//! Attendance report for one event: who said they would come, who checked
+//! in, who did not show and whose absence was excused. Admins download it
+//! as CSV or PDF, or email both to the committee (`REPORT_RECIPIENTS`).
+
+use chrono::{DateTime, Utc};
+use common::pdf::{PdfDocument, TextStyle};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::mailer::MailAttachment;
+use crate::models::{Event, EventReportRow, ExcuseStatus};
+
+/// Longest username shown in full in the PDF table
+const MAX_NAME_COLUMN: usize = 28;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Csv,
+    #[default]
+    Pdf,
+}
+
+/// What became of a member's response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Attended,
+    /// Said they would come, did not check in and has no approved excuse
+    NoShow,
+    Excused,
+    Waitlisted,
+    Unavailable,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Attended => write!(f, "attended"),
+            Outcome::NoShow => write!(f, "no_show"),
+            Outcome::Excused => write!(f, "excused"),
+            Outcome::Waitlisted => write!(f, "waitlisted"),
+            Outcome::Unavailable => write!(f, "unavailable"),
+        }
+    }
+}
+
+impl EventReportRow {
+    pub fn outcome(&self) -> Outcome {
+        if self.is_checked_in {
+            Outcome::Attended
+        } else if self.excuse_status.as_deref() == Some(&ExcuseStatus::Approved.to_string()) {
+            Outcome::Excused
+        } else if self.is_available {
+            Outcome::NoShow
+        } else if self.is_waitlisted {
+            Outcome::Waitlisted
+        } else {
+            Outcome::Unavailable
+        }
+    }
+
+    fn response(&self) -> &'static str {
+        if self.is_available {
+            "available"
+        } else if self.is_waitlisted {
+            "waitlisted"
+        } else {
+            "unavailable"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportTotals {
+    pub available: i64,
+    pub checked_in: i64,
+    pub no_shows: i64,
+    pub excused: i64,
+    pub waitlisted: i64,
+    pub unavailable: i64,
+    /// Self check-ins from outside the geofence still awaiting review
+    pub flagged_check_ins: i64,
+}
+
+pub struct EventReport {
+    pub event: Event,
+    pub rows: Vec<EventReportRow>,
+    pub totals: ReportTotals,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl EventReport {
+    pub fn new(event: Event, rows: Vec<EventReportRow>) -> Self {
+        let mut totals = ReportTotals::default();
+        for row in &rows {
+            totals.available += i64::from(row.is_available);
+            totals.flagged_check_ins += i64::from(row.is_checked_in && row.checkin_flagged);
+            match row.outcome() {
+                Outcome::Attended => totals.checked_in += 1,
+                Outcome::NoShow => totals.no_shows += 1,
+                Outcome::Excused => totals.excused += 1,
+                Outcome::Waitlisted => totals.waitlisted += 1,
+                Outcome::Unavailable => totals.unavailable += 1,
+            }
+        }
+
+        Self {
+            event,
+            rows,
+            totals,
+            generated_at: Utc::now(),
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        format!(
+            "Attendance report: {} ({})",
+            self.event.title,
+            self.event.event_date.format("%d %b %Y")
+        )
+    }
+
+    /// `attendance-<date>-<title>.<extension>`, safe to use as a filename
+    pub fn filename(&self, extension: &str) -> String {
+        let mut slug = String::new();
+        for c in self.event.title.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        format!(
+            "attendance-{}-{}.{}",
+            self.event.event_date.format("%Y-%m-%d"),
+            slug.trim_end_matches('-'),
+            extension
+        )
+    }
+
+    /// Event details and totals, shared by the email and the PDF
+    fn summary_lines(&self) -> Vec<String> {
+        let mut when = format!(
+            "{} ({})",
+            self.event.event_date.format("%a %d %b %Y, %H:%M UTC"),
+            self.event.event_type
+        );
+        if let Some(location) = &self.event.location {
+            when.push_str(&format!(" at {}", location));
+        }
+
+        let totals = &self.totals;
+        let mut checked_in = format!("Checked in: {}", totals.checked_in);
+        if totals.available > 0 {
+            checked_in.push_str(&format!(
+                " ({:.0}% of those available)",
+                totals.checked_in as f64 * 100.0 / totals.available as f64
+            ));
+        }
+
+        let mut lines = vec![
+            when,
+            String::new(),
+            format!("Available: {}", totals.available),
+            checked_in,
+            format!("No-shows: {}", totals.no_shows),
+            format!("Excused: {}", totals.excused),
+            format!("Waitlisted: {}", totals.waitlisted),
+            format!("Unavailable: {}", totals.unavailable),
+        ];
+        if totals.flagged_check_ins > 0 {
+            lines.push(format!(
+                "Flagged check-ins awaiting review: {}",
+                totals.flagged_check_ins
+            ));
+        }
+        lines
+    }
+
+    fn no_show_names(&self) -> Vec<&str> {
+        self.rows
+            .iter()
+            .filter(|row| row.outcome() == Outcome::NoShow)
+            .map(|row| row.username.as_str())
+            .collect()
+    }
+
+    pub fn email_body(&self) -> String {
+        let mut body = format!("Attendance report for {}\n", self.event.title);
+        for line in self.summary_lines() {
+            body.push_str(&line);
+            body.push('\n');
+        }
+
+        let no_shows = self.no_show_names();
+        if !no_shows.is_empty() {
+            body.push_str(&format!("\nNo-shows: {}\n", no_shows.join(", ")));
+        }
+        body.push_str("\nThe full list of responses is attached as CSV and PDF.\n");
+        body
+    }
+
+    pub fn csv(&self) -> Result<String, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record([
+            "username",
+            "response",
+            "checked_in",
+            "checked_in_at",
+            "outcome",
+            "excuse",
+            "flagged",
+            "payment",
+        ])?;
+        for row in &self.rows {
+            writer.write_record([
+                row.username.as_str(),
+                row.response(),
+                yes_no(row.is_checked_in),
+                &row.checked_in_at
+                    .map(|at| at.to_rfc3339())
+                    .unwrap_or_default(),
+                &row.outcome().to_string(),
+                row.excuse_status.as_deref().unwrap_or(""),
+                yes_no(row.checkin_flagged),
+                row.payment_status.as_deref().unwrap_or(""),
+            ])?;
+        }
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| csv::Error::from(e.into_error()))?;
+        Ok(String::from_utf8(bytes).expect("CSV built from UTF-8 strings"))
+    }
+
+    pub fn pdf(&self) -> PdfDocument {
+        let mut doc = PdfDocument::new(self.subject(), self.filename("pdf"));
+        doc.push(TextStyle::Heading, &self.event.title);
+        for line in self.summary_lines() {
+            doc.push(TextStyle::Body, &line);
+        }
+        doc.blank_line();
+
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.username.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp("Member".len(), MAX_NAME_COLUMN);
+        doc.push(
+            TextStyle::Mono,
+            &format!(
+                "{:<name_width$}  {:<11}  {:<10}  {:<11}  {}",
+                "Member", "Response", "Checked in", "Outcome", "Excuse"
+            ),
+        );
+        for row in &self.rows {
+            let mut name: String = row.username.chars().take(name_width).collect();
+            if row.username.chars().count() > name_width {
+                name.pop();
+                name.push('~');
+            }
+            let checked_in = match (row.checked_in_at, row.checkin_flagged) {
+                (Some(at), true) => format!("{} (!)", at.format("%H:%M")),
+                (Some(at), false) => at.format("%H:%M").to_string(),
+                (None, _) => "-".to_string(),
+            };
+            doc.push(
+                TextStyle::Mono,
+                &format!(
+                    "{:<name_width$}  {:<11}  {:<10}  {:<11}  {}",
+                    name,
+                    row.response(),
+                    checked_in,
+                    row.outcome().to_string().replace('_', "-"),
+                    row.excuse_status.as_deref().unwrap_or("-")
+                ),
+            );
+        }
+        if self.totals.flagged_check_ins > 0 {
+            doc.blank_line();
+            doc.push(
+                TextStyle::Body,
+                "(!) Checked in from outside the event's geofence; awaiting review.",
+            );
+        }
+
+        doc.blank_line();
+        doc.push(
+            TextStyle::Body,
+            &format!(
+                "Generated {}",
+                self.generated_at.format("%d %b %Y, %H:%M UTC")
+            ),
+        );
+        doc
+    }
+
+    /// The CSV and PDF, for emailing
+    pub fn attachments(&self) -> Result<Vec<MailAttachment>, csv::Error> {
+        Ok(vec![
+            MailAttachment {
+                filename: self.filename("csv"),
+                content_type: "text/csv; charset=utf-8",
+                body: self.csv()?.into_bytes(),
+            },
+            MailAttachment {
+                filename: self.filename("pdf"),
+                content_type: "application/pdf",
+                body: self.pdf().render(),
+            },
+        ])
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
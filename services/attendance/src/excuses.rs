@@ -0,0 +1,29 @@
+//! Absence excuses. A member who missed a locked event explains why,
+//! optionally attaching evidence such as a doctor's note, and an admin
+//! approves or rejects the excuse. Approved absences are left out of the
+//! member's attendance rates and count as activity for merit decay.
+//!
+//! Evidence is uploaded through this service, which caps its size, and is
+//! kept in the same file storage as event attachments.
+
+use chrono::Duration;
+use common::Storage;
+use uuid::Uuid;
+
+/// How long a presigned download URL stays valid
+const DOWNLOAD_URL_EXPIRY_MINUTES: i64 = 5;
+
+/// Storage key for an excuse's evidence. A new upload replaces the old file.
+pub fn storage_key(excuse_id: Uuid) -> String {
+    format!("excuses/{}", excuse_id)
+}
+
+/// Short-lived direct download URL, when the storage backend supports it
+pub fn download_url(storage: &Storage, key: &str) -> Option<String> {
+    storage.presigned_get(
+        key,
+        Duration::minutes(DOWNLOAD_URL_EXPIRY_MINUTES)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
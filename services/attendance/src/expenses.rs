@@ -0,0 +1,14 @@
+//! Event expenses. A treasurer enters what was spent on an event, attaching
+//! the receipt, and another treasurer or an admin approves or rejects it.
+//! Approved expenses are set against the fees collected for the event in
+//! the finance summaries.
+//!
+//! Receipts are uploaded through this service, which caps their size, and
+//! are kept in the same file storage as excuse evidence.
+
+use uuid::Uuid;
+
+/// Storage key for an expense's receipt. A new upload replaces the old file.
+pub fn storage_key(expense_id: Uuid) -> String {
+    format!("receipts/{}", expense_id)
+}
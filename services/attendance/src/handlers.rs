@@ -1,21 +1,50 @@
 use axum::{
-    extract::{Extension, Path, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
+use chrono::{Duration, NaiveTime, Utc};
+use common::{
+    error::{api_error, db_error},
+    pending_actions::{self, ProtectedAction},
+    storage::{check_content_type, content_disposition, StorageError},
+    Calendar, CalendarEntry, Pagination,
+};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
+    check_in::{self, OutOfRangePolicy},
+    database::{
+        CheckoutFilter, CreateEventParams, EventListFilter, EventTemplateParams, UpdateEventParams,
+    },
+    equipment,
+    event_report::{EventReport, ReportFormat},
+    excuses, expenses,
+    mailer::Mail,
     models::{
-        AdminSetAvailabilityRequest, AttendanceResponse, AttendanceStats, CheckInRequest,
-        CreateEventRequest, EventAttendanceResponse, EventListParams, EventListResponse,
-        EventResponse, LockEventRequest, RevokeAvailabilityRequest, SetAvailabilityRequest,
-        UpdateEventRequest,
+        AbsenceExcuse, AddEventManagerRequest, AddReportNoteRequest, AdminSetAvailabilityRequest,
+        AnnouncementListParams, AnnouncementListResponse, AssignStaffRequest, AttendanceRecord,
+        AttendanceResponse, AttendanceStats, BulkSetAvailabilityRequest, CheckInRequest,
+        CheckoutEquipmentRequest, CreateAnnouncementRequest, CreateCategoryRequest,
+        CreateEventRequest, CreateExpenseRequest, CreateReportRequest, EquipmentItem,
+        EquipmentListQuery, EquipmentRequest, Event, EventAttendanceResponse, EventExpense,
+        EventFee, EventFinanceSummary, EventListParams, EventListResponse, EventPaymentsResponse,
+        EventReportQuery, EventResponse, EventTemplate, EventTemplateRequest,
+        EventTemplateResponse, ExcuseAttachmentParams, ExcuseListQuery, ExcuseStatus,
+        ExpenseListQuery, ExpenseStatus, FinanceQuery, FlaggedCheckInQuery, ImportScheduleQuery,
+        InstantiateTemplateRequest, LockEventRequest, PaymentStatus, PaymentTotals, ReceiptParams,
+        RecordPaymentRequest, RenameTagRequest, ReportListQuery, ReportStatus, RequirementsReport,
+        ReturnEquipmentRequest, ReviewExcuseRequest, ReviewExpenseRequest,
+        RevokeAvailabilityRequest, SelfCheckInRequest, SetAvailabilityRequest, SetEventFeeRequest,
+        SetEventTagsRequest, SkippedEvent, SubmitExcuseRequest, UpdateAnnouncementRequest,
+        UpdateCategoryRequest, UpdateEventRequest, UpdateReportStatusRequest, WalkInRequest,
     },
-    AppState,
+    reminders, schedule_import, staff, tags, waitlist, walk_ins, AppState,
 };
 
 // ============================================================================
@@ -34,17 +63,27 @@ pub async fn create_event(
             Json(json!({"error": format!("Validation error: {}", e)})),
         )
     })?;
+    let tags = normalize_tags(&payload.tags)?;
+    let reminder_schedule = check_reminder_schedule(payload.reminder_schedule.as_deref())?;
+    ensure_category_exists(&state, payload.category_id).await?;
 
     let event = state
         .db
-        .create_event(
-            &payload.title,
-            payload.description.as_deref(),
-            &payload.event_type.to_string(),
-            payload.event_date,
-            payload.location.as_deref(),
-            user_id,
-        )
+        .create_event(CreateEventParams {
+            title: &payload.title,
+            description: payload.description.as_deref(),
+            event_type: &payload.event_type.to_string(),
+            event_date: payload.event_date,
+            location: payload.location.as_deref(),
+            max_participants: payload.max_participants,
+            geofence: payload.geofence,
+            category_id: payload.category_id,
+            tags: &tags,
+            duration_minutes: payload.duration_minutes,
+            reminder_lead_hours: payload.reminder_lead_hours,
+            reminder_schedule: reminder_schedule.as_deref(),
+            created_by: user_id,
+        })
         .await
         .map_err(|e| {
             tracing::error!("Failed to create event: {:?}", e);
@@ -86,7 +125,23 @@ pub async fn get_event(
             )
         })?;
 
-    let response: EventResponse = event.into();
+    let staff = state.db.list_event_staff(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    let fee = state.db.get_event_fee(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    let mut response: EventResponse = event.into();
+    response.staff = Some(staff);
+    response.fee = fee;
     Ok((StatusCode::OK, Json(json!(response))))
 }
 
@@ -95,13 +150,27 @@ pub async fn list_events(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(params): axum::extract::Query<EventListParams>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let page = params.page.unwrap_or(1).max(1);
-    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
-    let upcoming_only = params.upcoming_only.unwrap_or(false);
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+    let tags = match params.tags.as_deref().map(str::trim) {
+        Some(tags) if !tags.is_empty() => Some(
+            tags.split(',')
+                .map(tags::normalize)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?,
+        ),
+        _ => None,
+    };
 
+    let filter = EventListFilter {
+        event_type: params.event_type.as_deref(),
+        upcoming_only: params.upcoming_only.unwrap_or(false),
+        category_id: params.category_id,
+        tags: tags.as_deref(),
+    };
     let (events, total) = state
         .db
-        .list_events(page, per_page, params.event_type.as_deref(), upcoming_only)
+        .list_events(page, per_page, filter)
         .await
         .map_err(|_| {
             (
@@ -111,7 +180,7 @@ pub async fn list_events(
         })?;
 
     let event_responses: Vec<EventResponse> = events.into_iter().map(|e| e.into()).collect();
-    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+    let total_pages = pagination.total_pages(total);
 
     let response = EventListResponse {
         events: event_responses,
@@ -130,6 +199,8 @@ pub async fn update_event(
     Path(event_id): Path<Uuid>,
     Json(payload): Json<UpdateEventRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    ensure_not_archived(&state, event_id).await?;
+
     payload.validate().map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
@@ -137,16 +208,61 @@ pub async fn update_event(
         )
     })?;
 
+    if payload.remove_geofence && payload.geofence.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot set and remove the geofence at once"})),
+        ));
+    }
+    if payload.remove_category && payload.category_id.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot set and remove the category at once"})),
+        ));
+    }
+    if payload.reset_reminder_schedule && payload.reminder_schedule.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot set and reset the reminder schedule at once"})),
+        ));
+    }
+    let tags = payload.tags.as_deref().map(normalize_tags).transpose()?;
+    let reminder_schedule = check_reminder_schedule(payload.reminder_schedule.as_deref())?;
+    ensure_category_exists(&state, payload.category_id).await?;
+
     let event = state
         .db
-        .update_event(
+        .update_event(UpdateEventParams {
             event_id,
-            payload.title.as_deref(),
-            payload.description.as_deref(),
-            payload.event_type.map(|t| t.to_string()).as_deref(),
-            payload.event_date,
-            payload.location.as_deref(),
-        )
+            title: payload.title.as_deref(),
+            description: payload.description.as_deref(),
+            event_type: payload.event_type.map(|t| t.to_string()).as_deref(),
+            event_date: payload.event_date,
+            location: payload.location.as_deref(),
+            max_participants: payload.max_participants.map(|max| (max > 0).then_some(max)),
+            geofence: if payload.remove_geofence {
+                Some(None)
+            } else {
+                payload.geofence.map(Some)
+            },
+            category_id: if payload.remove_category {
+                Some(None)
+            } else {
+                payload.category_id.map(Some)
+            },
+            tags: tags.as_deref(),
+            duration_minutes: payload
+                .duration_minutes
+                .map(|minutes| (minutes > 0).then_some(minutes)),
+            reminder_lead_hours: payload
+                .reminder_lead_hours
+                .map(|hours| (hours > 0).then_some(hours)),
+            reminder_schedule: if payload.reset_reminder_schedule {
+                Some(None)
+            } else {
+                reminder_schedule.as_deref().map(Some)
+            },
+        })
         .await
         .map_err(|_| {
             (
@@ -161,6 +277,9 @@ pub async fn update_event(
             )
         })?;
 
+    // A raised or removed limit frees seats
+    promote_waitlist(&state, &event).await;
+
     let response: EventResponse = event.into();
     Ok((
         StatusCode::OK,
@@ -171,11 +290,46 @@ pub async fn update_event(
     ))
 }
 
-/// Delete an event (Admin only)
+/// Delete an event (Admin only). An event with ballots is only deleted
+/// once a second admin confirms.
 pub async fn delete_event(
     State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
     Path(event_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    ensure_not_archived(&state, event_id).await?;
+
+    let ballots = state
+        .db
+        .event_ballot_count(event_id)
+        .await
+        .map_err(db_error)?;
+    if ballots > 0 {
+        let event = find_event(&state, event_id).await?;
+        let pending = state
+            .pending_actions
+            .request(
+                ProtectedAction::DeleteEvent,
+                event_id,
+                &format!("Delete {} and its {} ballots", event.title, ballots),
+                admin_user_id,
+            )
+            .await
+            .map_err(db_error)?;
+        return Ok(pending_actions::awaiting_confirmation(&pending));
+    }
+
+    remove_event(&state, event_id).await
+}
+
+/// Delete an event, once any confirmation has been given. The event may
+/// have been archived while the confirmation was pending.
+async fn remove_event(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    ensure_not_archived(state, event_id).await?;
+
     let deleted = state.db.delete_event(event_id).await.map_err(|_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -196,55 +350,59 @@ pub async fn delete_event(
     ))
 }
 
-/// Lock or unlock an event (Admin only)
-pub async fn lock_event(
+/// List destructive actions waiting for a second admin (Admin only)
+pub async fn list_pending_actions(
     State(state): State<Arc<AppState>>,
-    Path(event_id): Path<Uuid>,
-    Json(payload): Json<LockEventRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let event = state
-        .db
-        .lock_event(event_id, payload.is_locked)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to update event lock status"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Event not found"})),
-            )
-        })?;
+    let actions = state.pending_actions.list().await.map_err(db_error)?;
+    Ok((StatusCode::OK, Json(json!({"pending_actions": actions}))))
+}
 
-    let response: EventResponse = event.into();
-    let message = if payload.is_locked {
-        "Event locked successfully"
-    } else {
-        "Event unlocked successfully"
+/// Confirm another admin's pending action, carrying it out (Admin only)
+pub async fn confirm_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_user_id, true)
+        .await?;
+
+    let result = match action.kind() {
+        Ok(ProtectedAction::DeleteEvent) => remove_event(&state, action.target_id).await,
+        _ => Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Unknown action: {}", action.action),
+        )),
     };
+    if result.is_err() {
+        if let Err(e) = state.pending_actions.reopen(action.id).await {
+            tracing::error!("Failed to reopen pending action {}: {}", action.id, e);
+        }
+    }
+    result
+}
 
+/// Reject a pending action, or withdraw one's own (Admin only)
+pub async fn reject_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_user_id, false)
+        .await?;
     Ok((
         StatusCode::OK,
-        Json(json!({
-            "message": message,
-            "event": response
-        })),
+        Json(json!({"message": "Action rejected", "pending_action": action})),
     ))
 }
 
-// ============================================================================
-// Attendance Handlers
-// ============================================================================
-
-/// Get event with all attendance records
-pub async fn get_event_attendance(
-    State(state): State<Arc<AppState>>,
-    Path(event_id): Path<Uuid>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let event = state
+/// Look up an event an admin is acting on
+async fn find_event(state: &AppState, event_id: Uuid) -> Result<Event, (StatusCode, Json<Value>)> {
+    state
         .db
         .get_event_by_id(event_id)
         .await
@@ -259,126 +417,157 @@ pub async fn get_event_attendance(
                 StatusCode::NOT_FOUND,
                 Json(json!({"error": "Event not found"})),
             )
-        })?;
+        })
+}
 
-    let records = state.db.get_event_attendance(event_id).await.map_err(|_| {
+/// List the members managing an event (Admin only)
+pub async fn list_event_managers(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    find_event(&state, event_id).await?;
+    let managers = state.db.list_event_managers(event_id).await.map_err(|_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch attendance"})),
+            Json(json!({"error": "Database error"})),
         )
     })?;
 
-    let (total_available, total_checked_in) =
-        state.db.get_attendance_stats(event_id).await.map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to fetch attendance stats"})),
-            )
-        })?;
-
-    let attendance_responses: Vec<AttendanceResponse> =
-        records.into_iter().map(|r| r.into()).collect();
-
-    let response = EventAttendanceResponse {
-        event: event.into(),
-        attendance: attendance_responses,
-        stats: AttendanceStats {
-            total_available,
-            total_checked_in,
-            total_unavailable: 0, // Can't know total users from here
-        },
-    };
-
-    Ok((StatusCode::OK, Json(json!(response))))
+    Ok(Json(json!({ "managers": managers })))
 }
 
-/// Set user's own availability for an event
-pub async fn set_availability(
+/// Let a member run an event's availability, check-in and allocations
+/// without admin rights elsewhere (Admin only). It takes effect from their
+/// next login or token refresh.
+pub async fn add_event_manager(
     State(state): State<Arc<AppState>>,
-    Extension(user_id): Extension<Uuid>,
+    Extension(admin_user_id): Extension<Uuid>,
     Path(event_id): Path<Uuid>,
-    Json(payload): Json<SetAvailabilityRequest>,
+    Json(payload): Json<AddEventManagerRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if event exists and is not locked
-    let event = state
+    find_event(&state, event_id).await?;
+    let manager = state
         .db
-        .get_event_by_id(event_id)
+        .add_event_manager(event_id, payload.user_id, admin_user_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to add event manager: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to add event manager"})),
             )
         })?
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "Event not found"})),
+                Json(json!({"error": "User not found"})),
             )
         })?;
 
-    if event.is_locked {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
-        ));
-    }
+    tracing::info!(
+        "Admin {} made user {} a manager of event {}",
+        admin_user_id,
+        payload.user_id,
+        event_id
+    );
 
-    let record = state
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Event manager added",
+            "manager": manager,
+        })),
+    ))
+}
+
+/// Stop a member managing an event (Admin only). Tokens already issued
+/// keep the access until they expire.
+pub async fn remove_event_manager(
+    State(state): State<Arc<AppState>>,
+    Path((event_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let removed = state
         .db
-        .set_availability(event_id, user_id, payload.is_available)
+        .remove_event_manager(event_id, user_id)
         .await
-        .map_err(|e| {
-            tracing::error!("Failed to set availability: {:?}", e);
+        .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to set availability"})),
+                Json(json!({"error": "Database error"})),
             )
         })?;
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User does not manage this event"})),
+        ));
+    }
 
-    let response: AttendanceResponse = record.into();
-    let message = if payload.is_available {
-        "Marked as available"
-    } else {
-        "Marked as unavailable"
-    };
+    Ok(Json(json!({"message": "Event manager removed"})))
+}
 
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "message": message,
-            "attendance": response
-        })),
-    ))
+/// List an event's conveners and volunteers (Admin only)
+pub async fn list_event_staff(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    find_event(&state, event_id).await?;
+    let staff = state.db.list_event_staff(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!({ "staff": staff })))
 }
 
-/// Get user's own attendance record for an event
-pub async fn get_my_attendance(
+/// Put a member on an event's staff, or change their role, and let them
+/// know (Admin only)
+pub async fn assign_event_staff(
     State(state): State<Arc<AppState>>,
-    Extension(user_id): Extension<Uuid>,
+    Extension(admin_user_id): Extension<Uuid>,
     Path(event_id): Path<Uuid>,
+    Json(payload): Json<AssignStaffRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if event exists
-    state
+    let event = find_event(&state, event_id).await?;
+    let assignment = state
         .db
-        .get_event_by_id(event_id)
+        .assign_event_staff(event_id, payload.user_id, payload.role, admin_user_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to assign event staff: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to assign event staff"})),
             )
         })?
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "Event not found"})),
+                Json(json!({"error": "User not found"})),
             )
         })?;
 
-    let record = state
+    staff::notify(&state, &event, &assignment).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Staff assigned",
+            "staff": assignment.staff,
+        })),
+    ))
+}
+
+/// Take a member off an event's staff (Admin only)
+pub async fn remove_event_staff(
+    State(state): State<Arc<AppState>>,
+    Path((event_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let removed = state
         .db
-        .get_attendance_record(event_id, user_id)
+        .remove_event_staff(event_id, user_id)
         .await
         .map_err(|_| {
             (
@@ -386,43 +575,32 @@ pub async fn get_my_attendance(
                 Json(json!({"error": "Database error"})),
             )
         })?;
-
-    match record {
-        Some(r) => {
-            let response: AttendanceResponse = r.into();
-            Ok((StatusCode::OK, Json(json!(response))))
-        }
-        None => {
-            // No record means unavailable by default
-            Ok((
-                StatusCode::OK,
-                Json(json!({
-                    "event_id": event_id,
-                    "user_id": user_id,
-                    "is_available": false,
-                    "is_checked_in": false
-                })),
-            ))
-        }
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User is not on this event's staff"})),
+        ));
     }
+
+    Ok(Json(json!({"message": "Staff removed"})))
 }
 
-/// Check in a user (Admin only)
-pub async fn check_in_user(
+/// Lock or unlock an event (Admin only)
+pub async fn lock_event(
     State(state): State<Arc<AppState>>,
-    Extension(admin_user_id): Extension<Uuid>,
     Path(event_id): Path<Uuid>,
-    Json(payload): Json<CheckInRequest>,
+    Json(payload): Json<LockEventRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if event exists and is not locked
+    ensure_not_archived(&state, event_id).await?;
+
     let event = state
         .db
-        .get_event_by_id(event_id)
+        .lock_event(event_id, payload.is_locked)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to update event lock status"})),
             )
         })?
         .ok_or_else(|| {
@@ -432,403 +610,3543 @@ pub async fn check_in_user(
             )
         })?;
 
-    if event.is_locked {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
-        ));
-    }
-
-    let record = state
-        .db
-        .check_in_user(
-            event_id,
-            payload.user_id,
-            payload.is_checked_in,
-            admin_user_id,
-        )
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to check in user: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to update check-in status"})),
-            )
-        })?;
-
-    let response: AttendanceResponse = record.into();
-    let message = if payload.is_checked_in {
-        "User checked in successfully"
-    } else {
-        "User check-in revoked"
-    };
+    let response: EventResponse = event.into();
+    let message = if payload.is_locked {
+        "Event locked successfully"
+    } else {
+        "Event unlocked successfully"
+    };
 
     Ok((
         StatusCode::OK,
         Json(json!({
             "message": message,
-            "attendance": response
+            "event": response
         })),
     ))
 }
 
-/// Revoke a user's availability (Admin only)
-pub async fn revoke_availability(
-    State(state): State<Arc<AppState>>,
-    Path(event_id): Path<Uuid>,
-    Json(payload): Json<RevokeAvailabilityRequest>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if event exists and is not locked
-    let event = state
-        .db
-        .get_event_by_id(event_id)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Event not found"})),
-            )
-        })?;
+/// Archived events are read-only; the database enforces this too, but the
+/// check here gives a clear error instead of a failed write
+async fn ensure_not_archived(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let archived = state.db.is_event_archived(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
 
-    if event.is_locked {
+    if archived {
         return Err((
             StatusCode::FORBIDDEN,
-            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+            Json(json!({"error": "Event is archived and read-only"})),
         ));
     }
 
-    let record = state
-        .db
-        .revoke_availability(event_id, payload.user_id)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to revoke availability: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to revoke availability"})),
-            )
-        })?;
+    Ok(())
+}
 
-    match record {
-        Some(r) => {
-            let response: AttendanceResponse = r.into();
-            Ok((
-                StatusCode::OK,
-                Json(json!({
-                    "message": "Availability revoked successfully",
-                    "attendance": response
-                })),
-            ))
-        }
-        None => Err((
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "No attendance record found for this user"})),
-        )),
-    }
+fn normalize_tags(tags: &[String]) -> Result<Vec<String>, (StatusCode, Json<Value>)> {
+    tags::normalize_all(tags).map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
 }
 
-/// Set any user's availability (Admin only)
-pub async fn admin_set_availability(
-    State(state): State<Arc<AppState>>,
-    Path(event_id): Path<Uuid>,
-    Json(payload): Json<AdminSetAvailabilityRequest>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if event exists and is not locked
-    let event = state
+async fn ensure_category_exists(
+    state: &AppState,
+    category_id: Option<Uuid>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let Some(category_id) = category_id else {
+        return Ok(());
+    };
+    if !state
         .db
-        .get_event_by_id(event_id)
+        .category_exists(category_id)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Event not found"})),
-            )
-        })?;
-
-    if event.is_locked {
+        .map_err(db_error)?
+    {
         return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Category not found"})),
         ));
     }
+    Ok(())
+}
 
-    let record = state
-        .db
-        .set_availability(event_id, payload.user_id, payload.is_available)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to set availability for user: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to set availability"})),
-            )
-        })?;
-
-    let response: AttendanceResponse = record.into();
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "message": format!("Availability {} successfully", if payload.is_available { "set" } else { "revoked" }),
-            "attendance": response
-        })),
-    ))
+/// Check a member reminder schedule, returning it longest lead first
+fn check_reminder_schedule(
+    hours: Option<&[i32]>,
+) -> Result<Option<Vec<i32>>, (StatusCode, Json<Value>)> {
+    hours
+        .map(reminders::normalize_schedule)
+        .transpose()
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))
 }
 
 // ============================================================================
-// Attendance Matrix/Dashboard Handlers (Admin only)
+// Category and Tag Handlers
 // ============================================================================
 
-use crate::models::{
-    AggregateStats, AttendanceCellStatus, AttendanceMatrixResponse, AttendanceMatrixRow,
-    EventSummary, EventTypeStats, UserAttendanceSummary,
-};
-use std::collections::HashMap;
+/// Every event category, each followed by those below it
+pub async fn list_categories(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let categories = state.db.list_categories().await.map_err(db_error)?;
 
-/// Get the full attendance matrix with all statistics (Admin only)
-pub async fn get_attendance_matrix(
+    Ok((StatusCode::OK, Json(json!({"categories": categories}))))
+}
+
+fn category_name_taken(e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    match e.as_database_error() {
+        Some(db_err) if db_err.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A category with that name already exists there"})),
+        ),
+        _ => db_error(e),
+    }
+}
+
+/// Create a category, optionally below another (Admin only)
+pub async fn create_category(
     State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateCategoryRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Fetch all data in parallel-ish manner
-    let events = state.db.get_all_events_for_matrix().await.map_err(|e| {
-        tracing::error!("Failed to fetch events: {:?}", e);
+    payload.validate().map_err(|e| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch events"})),
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
         )
     })?;
+    ensure_category_exists(&state, payload.parent_id).await?;
 
-    let users = state.db.get_all_users().await.map_err(|e| {
-        tracing::error!("Failed to fetch users: {:?}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch users"})),
-        )
-    })?;
+    let id = state
+        .db
+        .create_category(payload.name.trim(), payload.parent_id)
+        .await
+        .map_err(category_name_taken)?;
 
-    let attendance_records = state.db.get_all_attendance_records().await.map_err(|e| {
-        tracing::error!("Failed to fetch attendance records: {:?}", e);
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({"message": "Category created", "id": id})),
+    ))
+}
+
+/// Rename a category or move it below another (Admin only)
+pub async fn update_category(
+    State(state): State<Arc<AppState>>,
+    Path(category_id): Path<Uuid>,
+    Json(payload): Json<UpdateCategoryRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch attendance records"})),
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
         )
     })?;
+    if payload.move_to_top && payload.parent_id.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot set a parent and move to the top at once"})),
+        ));
+    }
 
-    let event_type_stats = state.db.get_event_type_stats().await.map_err(|e| {
-        tracing::error!("Failed to fetch event type stats: {:?}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch event type stats"})),
+    if let Some(parent_id) = payload.parent_id {
+        ensure_category_exists(&state, Some(parent_id)).await?;
+        let cycle = state
+            .db
+            .is_in_category_subtree(category_id, parent_id)
+            .await
+            .map_err(db_error)?;
+        if cycle {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "A category cannot be moved below itself"})),
+            ));
+        }
+    }
+
+    let parent_id = if payload.move_to_top {
+        Some(None)
+    } else {
+        payload.parent_id.map(Some)
+    };
+    let updated = state
+        .db
+        .update_category(
+            category_id,
+            payload.name.as_deref().map(str::trim),
+            parent_id,
         )
-    })?;
+        .await
+        .map_err(category_name_taken)?;
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Category not found"})),
+        ));
+    }
 
-    // Build lookup maps
-    // Key: (event_id, user_id) -> (is_available, is_checked_in)
-    let attendance_map: HashMap<(Uuid, Uuid), (bool, bool)> = attendance_records
-        .iter()
-        .map(|(event_id, user_id, is_available, is_checked_in)| {
-            ((*event_id, *user_id), (*is_available, *is_checked_in))
-        })
-        .collect();
+    Ok((StatusCode::OK, Json(json!({"message": "Category updated"}))))
+}
 
-    // Event stats: event_id -> (available, checked_in)
-    let mut event_stats: HashMap<Uuid, (i64, i64)> = HashMap::new();
-    for (event_id, _user_id, is_available, is_checked_in) in &attendance_records {
-        let entry = event_stats.entry(*event_id).or_insert((0, 0));
-        if *is_available {
-            entry.0 += 1;
-        }
-        if *is_checked_in {
-            entry.1 += 1;
-        }
+/// Delete a category with no subcategories; its events become
+/// uncategorised (Admin only)
+pub async fn delete_category(
+    State(state): State<Arc<AppState>>,
+    Path(category_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let has_children = state
+        .db
+        .category_has_children(category_id)
+        .await
+        .map_err(db_error)?;
+    if has_children {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Move or delete the category's subcategories first"})),
+        ));
     }
 
-    // User stats: user_id -> (available_count, checked_in_count)
-    let mut user_stats: HashMap<Uuid, (i64, i64)> = HashMap::new();
-    for (_event_id, user_id, is_available, is_checked_in) in &attendance_records {
-        let entry = user_stats.entry(*user_id).or_insert((0, 0));
-        if *is_available {
-            entry.0 += 1;
-        }
-        if *is_checked_in {
-            entry.1 += 1;
-        }
+    let deleted = state
+        .db
+        .delete_category(category_id)
+        .await
+        .map_err(db_error)?;
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Category not found"})),
+        ));
     }
 
-    let total_events = events.len() as i64;
-    let total_users = users.len() as i64;
+    Ok((StatusCode::OK, Json(json!({"message": "Category deleted"}))))
+}
 
-    // Build event summaries
-    let event_summaries: Vec<EventSummary> = events
-        .iter()
-        .map(|e| {
-            let (available, checked_in) = event_stats.get(&e.id).copied().unwrap_or((0, 0));
-            EventSummary {
-                id: e.id,
-                title: e.title.clone(),
-                event_type: e.event_type.clone(),
-                event_date: e.event_date,
-                is_locked: e.is_locked,
-                total_available: available,
-                total_checked_in: checked_in,
-            }
-        })
-        .collect();
+/// Every tag in use, with how many events carry it
+pub async fn list_tags(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let tags = state.db.list_tags().await.map_err(db_error)?;
 
-    // Build user summaries and matrix rows
-    let mut rows: Vec<AttendanceMatrixRow> = Vec::new();
-    let mut all_user_summaries: Vec<UserAttendanceSummary> = Vec::new();
+    Ok((StatusCode::OK, Json(json!({"tags": tags}))))
+}
 
-    for (user_id, username) in &users {
-        let (events_available, events_checked_in) =
-            user_stats.get(user_id).copied().unwrap_or((0, 0));
+/// Replace an event's tags (Admin only)
+pub async fn set_event_tags(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<SetEventTagsRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    ensure_not_archived(&state, event_id).await?;
+    let tags = normalize_tags(&payload.tags)?;
 
-        let availability_rate = if total_events > 0 {
-            (events_available as f64 / total_events as f64) * 100.0
-        } else {
-            0.0
-        };
+    let event = state
+        .db
+        .set_event_tags(event_id, &tags)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
 
-        let attendance_rate = if total_events > 0 {
-            (events_checked_in as f64 / total_events as f64) * 100.0
-        } else {
-            0.0
-        };
+    let response: EventResponse = event.into();
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Tags updated", "event": response})),
+    ))
+}
 
-        let user_summary = UserAttendanceSummary {
-            user_id: *user_id,
-            username: username.clone(),
-            events_available,
-            events_checked_in,
-            total_events,
-            availability_rate,
-            attendance_rate,
-        };
+/// Rename a tag on every event, merging it into an existing tag of the new
+/// name (Admin only)
+pub async fn rename_tag(
+    State(state): State<Arc<AppState>>,
+    Path(tag): Path<String>,
+    Json(payload): Json<RenameTagRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let invalid = |e: String| (StatusCode::BAD_REQUEST, Json(json!({"error": e})));
+    let from = tags::normalize(&tag).map_err(invalid)?;
+    let to = tags::normalize(&payload.to).map_err(invalid)?;
 
-        // Build cells for this user (one per event)
-        let cells: Vec<AttendanceCellStatus> = events
-            .iter()
-            .map(|e| match attendance_map.get(&(e.id, *user_id)) {
-                Some((is_available, is_checked_in)) => {
-                    if *is_checked_in {
-                        AttendanceCellStatus::CheckedIn
-                    } else if *is_available {
-                        AttendanceCellStatus::Available
-                    } else {
-                        AttendanceCellStatus::Unavailable
-                    }
-                }
-                None => AttendanceCellStatus::NoResponse,
-            })
-            .collect();
-
-        all_user_summaries.push(user_summary.clone());
-        rows.push(AttendanceMatrixRow {
-            user: user_summary,
-            cells,
-        });
+    let events = state.db.rename_tag(&from, &to).await.map_err(db_error)?;
+    if events == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Tag not found"})),
+        ));
     }
 
-    // Sort rows by attendance rate (descending)
-    rows.sort_by(|a, b| {
-        b.user
-            .attendance_rate
-            .partial_cmp(&a.user.attendance_rate)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Tag renamed", "tag": to, "events": events})),
+    ))
+}
 
-    // Calculate aggregate statistics
-    let total_availability_records: i64 = user_stats.values().map(|(a, _)| a).sum();
-    let total_checkin_records: i64 = user_stats.values().map(|(_, c)| c).sum();
-    let total_possible = total_events * total_users;
+/// Remove a tag from every event (Admin only)
+pub async fn delete_tag(
+    State(state): State<Arc<AppState>>,
+    Path(tag): Path<String>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let tag =
+        tags::normalize(&tag).map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
 
-    let overall_availability_rate = if total_possible > 0 {
-        (total_availability_records as f64 / total_possible as f64) * 100.0
-    } else {
-        0.0
-    };
+    let events = state.db.delete_tag(&tag).await.map_err(db_error)?;
+    if events == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Tag not found"})),
+        ));
+    }
 
-    let overall_attendance_rate = if total_possible > 0 {
-        (total_checkin_records as f64 / total_possible as f64) * 100.0
-    } else {
-        0.0
-    };
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Tag deleted", "events": events})),
+    ))
+}
 
-    let avg_available_per_event = if total_events > 0 {
-        total_availability_records as f64 / total_events as f64
-    } else {
-        0.0
-    };
+// ============================================================================
+// Event Template Handlers (Admin only)
+// ============================================================================
 
-    let avg_checked_in_per_event = if total_events > 0 {
-        total_checkin_records as f64 / total_events as f64
-    } else {
-        0.0
-    };
+fn template_name_taken(e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    match e.as_database_error() {
+        Some(db_err) if db_err.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A template with that name already exists"})),
+        ),
+        _ => db_error(e),
+    }
+}
 
-    // Find most and least attended events
-    let most_attended_event = event_summaries
-        .iter()
-        .max_by_key(|e| e.total_checked_in)
-        .cloned();
+/// A template with the series it creates
+async fn template_response(
+    state: &AppState,
+    template: EventTemplate,
+) -> Result<EventTemplateResponse, (StatusCode, Json<Value>)> {
+    let series = state
+        .db
+        .list_template_series(template.id)
+        .await
+        .map_err(db_error)?;
+    Ok(EventTemplateResponse { template, series })
+}
+
+/// Check a template request, returning its normalised tags and reminder
+/// schedule
+async fn check_template_request(
+    state: &AppState,
+    payload: &EventTemplateRequest,
+) -> Result<(Vec<String>, Option<Vec<i32>>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    let tags = normalize_tags(&payload.tags)?;
+    let reminder_schedule = check_reminder_schedule(payload.reminder_schedule.as_deref())?;
+    ensure_category_exists(state, payload.category_id).await?;
+    Ok((tags, reminder_schedule))
+}
+
+fn template_params<'a>(
+    payload: &'a EventTemplateRequest,
+    event_type: &'a str,
+    tags: &'a [String],
+    reminder_schedule: Option<&'a [i32]>,
+) -> EventTemplateParams<'a> {
+    EventTemplateParams {
+        name: payload.name.trim(),
+        title: &payload.title,
+        description: payload.description.as_deref(),
+        event_type,
+        start_time: payload.start_time,
+        duration_minutes: payload.duration_minutes,
+        location: payload.location.as_deref(),
+        max_participants: payload.max_participants,
+        category_id: payload.category_id,
+        tags,
+        reminder_lead_hours: payload.reminder_lead_hours,
+        reminder_schedule,
+        series: &payload.series,
+    }
+}
+
+/// Every event template, by name
+pub async fn list_event_templates(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let templates = state.db.list_event_templates().await.map_err(db_error)?;
+
+    let mut responses = Vec::with_capacity(templates.len());
+    for template in templates {
+        responses.push(template_response(&state, template).await?);
+    }
+
+    Ok((StatusCode::OK, Json(json!({"templates": responses}))))
+}
+
+pub async fn get_event_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let template = state
+        .db
+        .get_event_template(template_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Template not found"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!(template_response(&state, template).await?)),
+    ))
+}
+
+/// Save an event's settings and series structure for reuse
+pub async fn create_event_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<EventTemplateRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let (tags, reminder_schedule) = check_template_request(&state, &payload).await?;
+    let event_type = payload.event_type.to_string();
+
+    let template = state
+        .db
+        .create_event_template(
+            template_params(&payload, &event_type, &tags, reminder_schedule.as_deref()),
+            user_id,
+        )
+        .await
+        .map_err(template_name_taken)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Template created",
+            "template": template_response(&state, template).await?
+        })),
+    ))
+}
+
+/// Replace a template's settings and series; events already created from
+/// it are left alone
+pub async fn update_event_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+    Json(payload): Json<EventTemplateRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let (tags, reminder_schedule) = check_template_request(&state, &payload).await?;
+    let event_type = payload.event_type.to_string();
+
+    let template = state
+        .db
+        .update_event_template(
+            template_id,
+            template_params(&payload, &event_type, &tags, reminder_schedule.as_deref()),
+        )
+        .await
+        .map_err(template_name_taken)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Template not found"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Template updated",
+            "template": template_response(&state, template).await?
+        })),
+    ))
+}
+
+pub async fn delete_event_template(
+    State(state): State<Arc<AppState>>,
+    Path(template_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let deleted = state
+        .db
+        .delete_event_template(template_id)
+        .await
+        .map_err(db_error)?;
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Template not found"})),
+        ));
+    }
+
+    Ok((StatusCode::OK, Json(json!({"message": "Template deleted"}))))
+}
+
+/// Create an event and its series from a template, on the given date at
+/// the template's start time
+pub async fn create_event_from_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(template_id): Path<Uuid>,
+    Json(payload): Json<InstantiateTemplateRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let template = state
+        .db
+        .get_event_template(template_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Template not found"})),
+            )
+        })?;
+
+    let event_date = payload.date.and_time(template.start_time).and_utc();
+    let (event, series_created) = state
+        .db
+        .create_event_from_template(&template, event_date, user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create event from template: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to create event"})),
+            )
+        })?;
+
+    let response: EventResponse = event.into();
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Event created from template",
+            "event": response,
+            "series_created": series_created
+        })),
+    ))
+}
 
-    let least_attended_event = if !event_summaries.is_empty() {
-        event_summaries
-            .iter()
-            .min_by_key(|e| e.total_checked_in)
-            .cloned()
+/// Create a term's events from a CSV or JSON schedule (admin only). See
+/// `schedule_import` for the format. Rows repeating an existing event are
+/// skipped; with `?dry_run=true` nothing is created.
+pub async fn import_events(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<ImportScheduleQuery>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let rows = if is_json {
+        serde_json::from_str(&body)
+            .map(schedule_import::number_rows)
+            .map_err(|e| e.to_string())
     } else {
-        None
-    };
+        schedule_import::parse_csv(&body)
+    }
+    .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
 
-    // Get top 5 most reliable users (by attendance rate)
-    let mut most_reliable_users = all_user_summaries.clone();
-    most_reliable_users.sort_by(|a, b| {
-        b.attendance_rate
-            .partial_cmp(&a.attendance_rate)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    most_reliable_users.truncate(5);
+    if rows.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "The schedule has no events"})),
+        ));
+    }
+    let events = schedule_import::check_rows(rows).map_err(|problems| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "The schedule has problems", "problems": problems})),
+        )
+    })?;
 
-    // Build event type stats
-    let events_by_type: Vec<EventTypeStats> = event_type_stats
+    // Existing events are compared by day, so look at whole days
+    let from = events
         .iter()
-        .map(|(event_type, count, avg_attendance)| EventTypeStats {
-            event_type: event_type.clone(),
-            count: *count,
-            avg_attendance: *avg_attendance,
-        })
-        .collect();
+        .map(|e| e.event_date)
+        .min()
+        .unwrap_or_default();
+    let to = events
+        .iter()
+        .map(|e| e.event_date)
+        .max()
+        .unwrap_or_default();
+    let existing = state
+        .db
+        .event_titles_between(
+            from.date_naive().and_time(NaiveTime::MIN).and_utc(),
+            to.date_naive().and_time(NaiveTime::MIN).and_utc() + Duration::days(1),
+        )
+        .await
+        .map_err(db_error)?;
+    let (events, duplicates) = schedule_import::split_duplicates(events, &existing);
 
-    let aggregate_stats = AggregateStats {
-        total_events,
-        total_users,
-        overall_availability_rate,
-        overall_attendance_rate,
-        avg_available_per_event,
-        avg_checked_in_per_event,
-        most_attended_event,
-        least_attended_event,
-        most_reliable_users,
-        events_by_type,
-    };
+    if query.dry_run {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({
+                "dry_run": true,
+                "events": events,
+                "duplicates": duplicates
+            })),
+        ));
+    }
 
-    let response = AttendanceMatrixResponse {
-        events: event_summaries,
-        rows,
-        aggregate_stats,
+    let event_types: Vec<String> = events.iter().map(|e| e.event_type.to_string()).collect();
+    let created = state
+        .db
+        .create_events(
+            events
+                .iter()
+                .zip(&event_types)
+                .map(|(event, event_type)| CreateEventParams {
+                    title: &event.title,
+                    description: event.description.as_deref(),
+                    event_type,
+                    event_date: event.event_date,
+                    location: event.location.as_deref(),
+                    max_participants: None,
+                    geofence: None,
+                    category_id: None,
+                    tags: &[],
+                    duration_minutes: event.duration_minutes,
+                    reminder_lead_hours: None,
+                    reminder_schedule: None,
+                    created_by: user_id,
+                })
+                .collect(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to import events: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to create events"})),
+            )
+        })?;
+
+    tracing::info!(
+        "User {} imported {} events ({} duplicates skipped)",
+        user_id,
+        created.len(),
+        duplicates.len()
+    );
+
+    let created: Vec<EventResponse> = created.into_iter().map(Into::into).collect();
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": format!("{} events created", created.len()),
+            "events": created,
+            "duplicates": duplicates
+        })),
+    ))
+}
+
+// ============================================================================
+// Calendar Handlers
+// ============================================================================
+
+/// iCalendar feed of all upcoming events. Public so calendar apps can
+/// subscribe to it without a token.
+pub async fn events_calendar(
+    State(state): State<Arc<AppState>>,
+) -> Result<Calendar, (StatusCode, Json<Value>)> {
+    let events = state.db.list_upcoming_events().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    let mut calendar = Calendar::new("Tabrela events");
+    for event in events {
+        calendar.push(CalendarEntry {
+            uid: format!("event-{}@tabrela", event.id),
+            start: event.event_date,
+            end: event.ends_at(),
+            summary: event.title,
+            description: event.description,
+            location: event.location,
+            updated_at: event.updated_at,
+        });
+    }
+
+    Ok(calendar)
+}
+
+// ============================================================================
+// Attendance Handlers
+// ============================================================================
+
+/// Get event with all attendance records
+pub async fn get_event_attendance(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let records = state.db.get_event_attendance(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch attendance"})),
+        )
+    })?;
+
+    let (total_available, total_checked_in) =
+        state.db.get_attendance_stats(event_id).await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch attendance stats"})),
+            )
+        })?;
+
+    let attendance_responses: Vec<AttendanceResponse> =
+        records.into_iter().map(|r| r.into()).collect();
+
+    let mut event: EventResponse = event.into();
+    event.fee = state.db.get_event_fee(event_id).await.map_err(db_error)?;
+
+    let response = EventAttendanceResponse {
+        event,
+        attendance: attendance_responses,
+        stats: AttendanceStats {
+            total_available,
+            total_checked_in,
+            total_unavailable: 0, // Can't know total users from here
+        },
     };
 
     Ok((StatusCode::OK, Json(json!(response))))
 }
+
+/// Set user's own availability for an event
+pub async fn set_availability(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<SetAvailabilityRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if event exists and is not locked
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+
+    let record = apply_availability(&state, &event, user_id, payload.is_available)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to set availability: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to set availability"})),
+            )
+        })?;
+
+    let waitlisted = record.waitlisted_at.is_some();
+    let response: AttendanceResponse = record.into();
+    let message = if waitlisted {
+        "Event is full; added to the waitlist"
+    } else if payload.is_available {
+        "Marked as available"
+    } else {
+        "Marked as unavailable"
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": message,
+            "attendance": response
+        })),
+    ))
+}
+
+/// Apply a member's own availability change: claim a seat, or a place on
+/// the waitlist when the event is full, or give theirs up to the member
+/// who has waited longest
+async fn apply_availability(
+    state: &AppState,
+    event: &Event,
+    user_id: Uuid,
+    is_available: bool,
+) -> Result<AttendanceRecord, sqlx::Error> {
+    if is_available {
+        return state.db.claim_seat(event.id, user_id).await;
+    }
+
+    let record = state.db.set_availability(event.id, user_id, false).await?;
+    promote_waitlist(state, event).await;
+    Ok(record)
+}
+
+/// Hand any free seats to the waitlist. The change that freed them stands
+/// even if this fails; the next one retries.
+async fn promote_waitlist(state: &AppState, event: &Event) {
+    if let Err(e) = waitlist::promote(state, event).await {
+        tracing::error!("Failed to promote from waitlist: {:?}", e);
+    }
+}
+
+/// Set user's own availability for several events at once. Locked or
+/// unknown events are skipped and listed rather than failing the request,
+/// and full events put the user on their waitlist.
+pub async fn bulk_set_availability(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(mut payload): Json<BulkSetAvailabilityRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    payload.event_ids.sort();
+    payload.event_ids.dedup();
+
+    let events = state
+        .db
+        .get_events_by_ids(&payload.event_ids)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let mut attendance: Vec<AttendanceResponse> = Vec::new();
+    let mut skipped: Vec<SkippedEvent> = Vec::new();
+    for &event_id in &payload.event_ids {
+        let Some(event) = events.iter().find(|e| e.id == event_id) else {
+            skipped.push(SkippedEvent {
+                event_id,
+                reason: "not_found",
+            });
+            continue;
+        };
+        if event.is_locked {
+            skipped.push(SkippedEvent {
+                event_id,
+                reason: "locked",
+            });
+            continue;
+        }
+
+        let record = apply_availability(&state, event, user_id, payload.is_available)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to set availability: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Failed to set availability"})),
+                )
+            })?;
+        attendance.push(record.into());
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": format!("Availability updated for {} events", attendance.len()),
+            "attendance": attendance,
+            "skipped": skipped
+        })),
+    ))
+}
+
+/// Reminders the current user has been sent for upcoming events, for the
+/// app to show alongside the emails
+pub async fn list_my_reminders(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let reminders = state
+        .db
+        .list_member_reminders(user_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok((StatusCode::OK, Json(json!({"reminders": reminders}))))
+}
+
+/// Get user's own attendance record for an event
+pub async fn get_my_attendance(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if event exists
+    state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let record = state
+        .db
+        .get_attendance_record(event_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    // Members of paid events see where they stand with the fee
+    let fee = state.db.get_event_fee(event_id).await.map_err(db_error)?;
+    let payment_status = match fee {
+        Some(_) => Some(
+            state
+                .db
+                .get_member_payment(event_id, user_id)
+                .await
+                .map_err(db_error)?
+                .map_or_else(|| PaymentStatus::Unpaid.as_str().to_string(), |p| p.status),
+        ),
+        None => None,
+    };
+
+    match record {
+        Some(r) => {
+            let waitlisted = r.waitlisted_at.is_some();
+            let mut response = json!(AttendanceResponse {
+                payment_status,
+                ..r.into()
+            });
+            if waitlisted {
+                let position = state
+                    .db
+                    .waitlist_position(event_id, user_id)
+                    .await
+                    .map_err(|_| {
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(json!({"error": "Database error"})),
+                        )
+                    })?;
+                response["waitlist_position"] = json!(position);
+            }
+            Ok((StatusCode::OK, Json(response)))
+        }
+        None => {
+            // No record means unavailable by default
+            Ok((
+                StatusCode::OK,
+                Json(json!({
+                    "event_id": event_id,
+                    "user_id": user_id,
+                    "is_available": false,
+                    "is_checked_in": false,
+                    "payment_status": payment_status
+                })),
+            ))
+        }
+    }
+}
+
+/// Check the current user in to an event that is under way. Events with a
+/// geofence need the member's coordinates; check-ins from outside it are
+/// refused or flagged, as `CHECKIN_OUT_OF_RANGE` says.
+pub async fn self_check_in(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<SelfCheckInRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+    if !check_in::is_open(event.event_date, Utc::now()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Self check-in is not open for this event"})),
+        ));
+    }
+
+    let record = state
+        .db
+        .get_attendance_record(event_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    if record.as_ref().is_some_and(|r| r.is_checked_in) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "You are already checked in"})),
+        ));
+    }
+    // Checking in must not jump the waitlist
+    if event.max_participants.is_some() && !record.is_some_and(|r| r.is_available) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "You do not have a seat at this event"})),
+        ));
+    }
+
+    let (distance_m, flagged) = match event.geofence() {
+        None => (None, false),
+        Some(geofence) => {
+            let (Some(latitude), Some(longitude)) = (payload.latitude, payload.longitude) else {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": "Your location is needed to check in to this event"})),
+                ));
+            };
+            let distance_m = geofence.distance_m(latitude, longitude);
+            let inside = geofence.contains(distance_m);
+            if !inside && state.config.checkin_out_of_range == OutOfRangePolicy::Reject {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    Json(json!({
+                        "error": "You are too far from the event to check in",
+                        "distance_m": distance_m.round(),
+                        "radius_m": geofence.radius_m
+                    })),
+                ));
+            }
+            (Some(distance_m), !inside)
+        }
+    };
+
+    let record = state
+        .db
+        .self_check_in(event_id, user_id, distance_m, flagged)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to check in: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to check in"})),
+            )
+        })?;
+
+    let response: AttendanceResponse = record.into();
+    let message = if flagged {
+        "Checked in; an admin will review it as you seem to be away from the event"
+    } else {
+        "Checked in successfully"
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": message,
+            "attendance": response
+        })),
+    ))
+}
+
+/// Self check-ins flagged as out of range, for confirming through the
+/// check-in endpoint or revoking (Admin only)
+pub async fn list_flagged_check_ins(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FlaggedCheckInQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let records = state
+        .db
+        .list_flagged_check_ins(query.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let check_ins: Vec<AttendanceResponse> = records.into_iter().map(|r| r.into()).collect();
+    Ok((StatusCode::OK, Json(json!({"check_ins": check_ins}))))
+}
+
+/// Check in a user, or confirm or revoke a flagged self check-in (Admins,
+/// and the event's managers and staff)
+pub async fn check_in_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<CheckInRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if event exists and is not locked
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+
+    let record = state
+        .db
+        .check_in_user(
+            event_id,
+            payload.user_id,
+            payload.is_checked_in,
+            admin_user_id,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to check in user: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update check-in status"})),
+            )
+        })?;
+
+    let response: AttendanceResponse = record.into();
+    let message = if payload.is_checked_in {
+        "User checked in successfully"
+    } else {
+        "User check-in revoked"
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": message,
+            "attendance": response
+        })),
+    ))
+}
+
+/// Register a guest at the desk, putting them in the event's allocation
+/// pool and inviting them to sign up if they left an email (Admins, and the
+/// event's managers and staff)
+pub async fn register_walk_in(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<WalkInRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    if payload.name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Name is required"})),
+        ));
+    }
+
+    let event = find_event(&state, event_id).await?;
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+    if let Some(institution_id) = payload.institution_id {
+        let exists = state
+            .db
+            .institution_exists(institution_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?;
+        if !exists {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Institution not found"})),
+            ));
+        }
+    }
+
+    let mut walk_in = state
+        .db
+        .create_walk_in(event_id, &payload, user_id)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_err) if db_err.is_unique_violation() => (
+                StatusCode::CONFLICT,
+                Json(
+                    json!({"error": "A guest with this name is already registered for the event"}),
+                ),
+            ),
+            _ => {
+                tracing::error!("Failed to register walk-in: {:?}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Failed to register walk-in"})),
+                )
+            }
+        })?;
+
+    walk_ins::invite(&state, &event, &mut walk_in).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Walk-in registered",
+            "walk_in": walk_in,
+        })),
+    ))
+}
+
+/// List the guests registered at the desk (Admins, and the event's managers
+/// and staff)
+pub async fn list_walk_ins(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    find_event(&state, event_id).await?;
+    let walk_ins = state.db.list_walk_ins(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!({ "walk_ins": walk_ins })))
+}
+
+/// Revoke a user's availability (Admins and the event's managers)
+pub async fn revoke_availability(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<RevokeAvailabilityRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if event exists and is not locked
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+
+    let record = state
+        .db
+        .revoke_availability(event_id, payload.user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to revoke availability: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to revoke availability"})),
+            )
+        })?;
+    promote_waitlist(&state, &event).await;
+
+    match record {
+        Some(r) => {
+            let response: AttendanceResponse = r.into();
+            Ok((
+                StatusCode::OK,
+                Json(json!({
+                    "message": "Availability revoked successfully",
+                    "attendance": response
+                })),
+            ))
+        }
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "No attendance record found for this user"})),
+        )),
+    }
+}
+
+/// Set any user's availability (Admins and the event's managers)
+pub async fn admin_set_availability(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<AdminSetAvailabilityRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if event exists and is not locked
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event attendance is locked and cannot be modified"})),
+        ));
+    }
+
+    let record = state
+        .db
+        .set_availability(event_id, payload.user_id, payload.is_available)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to set availability for user: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to set availability"})),
+            )
+        })?;
+    // Admins may seat members past the limit, but a revoked seat goes to
+    // the waitlist
+    if !payload.is_available {
+        promote_waitlist(&state, &event).await;
+    }
+
+    let response: AttendanceResponse = record.into();
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": format!("Availability {} successfully", if payload.is_available { "set" } else { "revoked" }),
+            "attendance": response
+        })),
+    ))
+}
+
+// ============================================================================
+// Attendance Matrix/Dashboard Handlers (Admin only)
+// ============================================================================
+
+use crate::models::{
+    AggregateStats, AttendanceCellStatus, AttendanceMatrixParams, AttendanceMatrixResponse,
+    AttendanceMatrixRow, EventTypeStats,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Event counts for the committee's termly report (Admin only)
+pub async fn get_event_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let stats = state.db.event_stats().await.map_err(|e| {
+        tracing::error!("Failed to compute event stats: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch event stats"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!(stats))))
+}
+
+/// Get one page of the attendance matrix with statistics over every member,
+/// optionally limited to events in a date range (Admin only)
+pub async fn get_attendance_matrix(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AttendanceMatrixParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 50);
+    let (from, to) = (params.from, params.to);
+
+    let event_summaries = state.db.matrix_events(from, to).await.map_err(db_error)?;
+
+    let user_summaries = state
+        .db
+        .matrix_user_stats(from, to, pagination.per_page as i64, pagination.offset())
+        .await
+        .map_err(db_error)?;
+
+    let totals = state.db.matrix_totals(from, to).await.map_err(db_error)?;
+
+    let most_reliable_users = state
+        .db
+        .matrix_user_stats(from, to, 5, 0)
+        .await
+        .map_err(db_error)?;
+
+    let event_type_stats = state
+        .db
+        .get_event_type_stats(from, to)
+        .await
+        .map_err(db_error)?;
+
+    // Cells are only needed for the members on this page
+    let user_ids: Vec<Uuid> = user_summaries.iter().map(|u| u.user_id).collect();
+    let records = state
+        .db
+        .matrix_records(&user_ids, from, to)
+        .await
+        .map_err(db_error)?;
+    let excused: HashSet<(Uuid, Uuid)> = state
+        .db
+        .matrix_excuses(&user_ids, from, to)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .collect();
+
+    // Key: (event_id, user_id) -> (is_available, is_checked_in)
+    let attendance_map: HashMap<(Uuid, Uuid), (bool, bool)> = records
+        .into_iter()
+        .map(|(event_id, user_id, is_available, is_checked_in)| {
+            ((event_id, user_id), (is_available, is_checked_in))
+        })
+        .collect();
+
+    let rows: Vec<AttendanceMatrixRow> = user_summaries
+        .into_iter()
+        .map(|user| {
+            let cells = event_summaries
+                .iter()
+                .map(|e| match attendance_map.get(&(e.id, user.user_id)) {
+                    Some((_, true)) => AttendanceCellStatus::CheckedIn,
+                    _ if excused.contains(&(e.id, user.user_id)) => AttendanceCellStatus::Excused,
+                    Some((true, false)) => AttendanceCellStatus::Available,
+                    Some((false, false)) => AttendanceCellStatus::Unavailable,
+                    None => AttendanceCellStatus::NoResponse,
+                })
+                .collect();
+            AttendanceMatrixRow { user, cells }
+        })
+        .collect();
+
+    // Excused absences are left out of the possible attendances
+    let total_possible = totals.total_events * totals.total_users - totals.events_excused;
+    let rate = |count: i64, of: i64| {
+        if of > 0 {
+            (count as f64 / of as f64) * 100.0
+        } else {
+            0.0
+        }
+    };
+    let per_event = |count: i64| {
+        if totals.total_events > 0 {
+            count as f64 / totals.total_events as f64
+        } else {
+            0.0
+        }
+    };
+
+    let most_attended_event = event_summaries
+        .iter()
+        .max_by_key(|e| e.total_checked_in)
+        .cloned();
+    let least_attended_event = event_summaries
+        .iter()
+        .min_by_key(|e| e.total_checked_in)
+        .cloned();
+
+    let events_by_type: Vec<EventTypeStats> = event_type_stats
+        .into_iter()
+        .map(|(event_type, count, avg_attendance)| EventTypeStats {
+            event_type,
+            count,
+            avg_attendance,
+        })
+        .collect();
+
+    let aggregate_stats = AggregateStats {
+        total_events: totals.total_events,
+        total_users: totals.total_users,
+        overall_availability_rate: rate(totals.events_available, total_possible),
+        overall_attendance_rate: rate(totals.events_checked_in, total_possible),
+        avg_available_per_event: per_event(totals.events_available),
+        avg_checked_in_per_event: per_event(totals.events_checked_in),
+        most_attended_event,
+        least_attended_event,
+        most_reliable_users,
+        events_by_type,
+    };
+
+    let response = AttendanceMatrixResponse {
+        events: event_summaries,
+        rows,
+        aggregate_stats,
+        page: pagination.page,
+        per_page: pagination.per_page,
+        total_pages: pagination.total_pages(totals.total_users),
+    };
+
+    Ok((StatusCode::OK, Json(json!(response))))
+}
+
+// ============================================================================
+// Event Report Handlers (Admin only)
+// ============================================================================
+
+async fn event_report(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<EventReport, (StatusCode, Json<Value>)> {
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+    let rows = state
+        .db
+        .event_report_rows(event_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(EventReport::new(event, rows))
+}
+
+fn report_write_error(_: csv::Error) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "Failed to write report"})),
+    )
+}
+
+/// Download an event's attendance report as PDF (default) or CSV (Admin only)
+pub async fn download_event_report(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<EventReportQuery>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let report = event_report(&state, event_id).await?;
+
+    Ok(match query.format {
+        ReportFormat::Pdf => report.pdf().into_response(),
+        ReportFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", report.filename("csv")),
+                ),
+            ],
+            report.csv().map_err(report_write_error)?,
+        )
+            .into_response(),
+    })
+}
+
+/// Email an event's attendance report, with the CSV and PDF attached, to
+/// the committee addresses in `REPORT_RECIPIENTS` (Admin only)
+pub async fn send_event_report(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let recipients = &state.config.report_recipients;
+    if recipients.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "No report recipients are configured"})),
+        ));
+    }
+
+    let report = event_report(&state, event_id).await?;
+    let mail = Mail {
+        to: recipients.clone(),
+        subject: report.subject(),
+        body: report.email_body(),
+        attachments: report.attachments().map_err(report_write_error)?,
+    };
+
+    state.mailer.send(mail).await.map_err(|e| {
+        tracing::error!("Failed to email the report for event {}: {}", event_id, e);
+        (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({"error": "Failed to send the report"})),
+        )
+    })?;
+
+    let sent_to: Vec<String> = recipients
+        .iter()
+        .map(|mailbox| mailbox.email.to_string())
+        .collect();
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Report sent",
+            "recipients": sent_to,
+            "totals": report.totals
+        })),
+    ))
+}
+
+// ============================================================================
+// Conduct Report Handlers
+// ============================================================================
+
+/// File a code-of-conduct report about an event or match
+pub async fn file_report(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateReportRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    // A match implies its event; an event on its own is also fine
+    let event_id = match payload.match_id {
+        Some(match_id) => {
+            let match_event = state.db.get_match_event_id(match_id).await.map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?;
+            match (match_event, payload.event_id) {
+                (None, _) => {
+                    return Err((
+                        StatusCode::NOT_FOUND,
+                        Json(json!({"error": "Match not found"})),
+                    ));
+                }
+                (Some(found), Some(given)) if found != given => {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": "Match does not belong to the event"})),
+                    ));
+                }
+                (Some(found), _) => found,
+            }
+        }
+        None => {
+            let event_id = payload.event_id.ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": "Must provide event_id or match_id"})),
+                )
+            })?;
+            state
+                .db
+                .get_event_by_id(event_id)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({"error": "Database error"})),
+                    )
+                })?
+                .ok_or_else(|| {
+                    (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({"error": "Event not found"})),
+                    )
+                })?;
+            event_id
+        }
+    };
+
+    let reporter_id = (!payload.anonymous).then_some(user_id);
+    let report = state
+        .db
+        .create_conduct_report(
+            reporter_id,
+            Some(event_id),
+            payload.match_id,
+            &payload.description,
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to file report"})),
+            )
+        })?;
+
+    // Anonymous reporters get nothing back they could be identified by later
+    let body = if payload.anonymous {
+        json!({"message": "Report filed anonymously"})
+    } else {
+        json!({
+            "message": "Report filed successfully",
+            "report": {"id": report.id, "status": report.status}
+        })
+    };
+
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+/// Reports the current user filed under their own name
+pub async fn list_my_reports(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let reports = state
+        .db
+        .list_reports_by_reporter(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({"reports": reports}))))
+}
+
+/// List reports without their contents (Equity only)
+pub async fn equity_list_reports(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReportListQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let reports = state
+        .db
+        .list_conduct_reports(query.status, query.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({"reports": reports}))))
+}
+
+/// Open a report with its audit trail; the access itself is audited
+/// (Equity only)
+pub async fn equity_get_report(
+    State(state): State<Arc<AppState>>,
+    Extension(officer_id): Extension<Uuid>,
+    Path(report_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let report = state
+        .db
+        .view_conduct_report(report_id, officer_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Report not found"})),
+            )
+        })?;
+
+    let audit = state.db.list_report_audit(report_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "report": report,
+            "audit": audit
+        })),
+    ))
+}
+
+/// Move a report through open → investigating → resolved (Equity only)
+pub async fn equity_update_report_status(
+    State(state): State<Arc<AppState>>,
+    Extension(officer_id): Extension<Uuid>,
+    Path(report_id): Path<Uuid>,
+    Json(payload): Json<UpdateReportStatusRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let current = current_report_status(&state, report_id).await?;
+    if !current.can_transition_to(payload.status) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("Cannot move a report from {} to {}", current, payload.status)
+            })),
+        ));
+    }
+
+    let report = state
+        .db
+        .update_conduct_report_status(
+            report_id,
+            officer_id,
+            current,
+            payload.status,
+            payload.note.as_deref(),
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update report"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Report was changed by someone else, reload and retry"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Report status updated successfully",
+            "report": {"id": report.id, "status": report.status, "updated_at": report.updated_at}
+        })),
+    ))
+}
+
+/// Add an internal note to a report's audit trail (Equity only)
+pub async fn equity_add_report_note(
+    State(state): State<Arc<AppState>>,
+    Extension(officer_id): Extension<Uuid>,
+    Path(report_id): Path<Uuid>,
+    Json(payload): Json<AddReportNoteRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    current_report_status(&state, report_id).await?;
+    state
+        .db
+        .add_conduct_report_note(report_id, officer_id, &payload.note)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to add note"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({"message": "Note added successfully"})),
+    ))
+}
+
+async fn current_report_status(
+    state: &AppState,
+    report_id: Uuid,
+) -> Result<ReportStatus, (StatusCode, Json<Value>)> {
+    let status = state
+        .db
+        .get_conduct_report_status(report_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Report not found"})),
+            )
+        })?;
+
+    status
+        .parse()
+        .map_err(|e: String| (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": e}))))
+}
+
+// ============================================================================
+// Absence Excuse Handlers
+// ============================================================================
+
+/// Explain an absence from a locked event. Until an admin reviews the
+/// excuse, submitting again replaces the reason.
+pub async fn submit_excuse(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<SubmitExcuseRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if !event.is_locked {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Excuses can only be submitted once attendance is locked"})),
+        ));
+    }
+    ensure_not_archived(&state, event_id).await?;
+
+    let record = state
+        .db
+        .get_attendance_record(event_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    if record.is_some_and(|r| r.is_checked_in) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "You were checked in to this event"})),
+        ));
+    }
+
+    let excuse = state
+        .db
+        .submit_excuse(event_id, user_id, &payload.reason)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to submit excuse: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to submit excuse"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Your excuse for this event has already been reviewed"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Excuse submitted for review",
+            "excuse": excuse
+        })),
+    ))
+}
+
+/// The current user's excuses with their review outcome
+pub async fn list_my_excuses(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let excuses = state
+        .db
+        .list_excuses(Some(user_id), None, None)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({"excuses": excuses}))))
+}
+
+/// Attach evidence to one of the current user's pending excuses. The file
+/// is the request body, sent with its own Content-Type.
+pub async fn upload_excuse_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(excuse_id): Path<Uuid>,
+    Query(params): Query<ExcuseAttachmentParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    params.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(str::trim)
+        .unwrap_or_default();
+    check_content_type(content_type)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+    if body.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "File cannot be empty"})),
+        ));
+    }
+
+    let excuse = find_excuse(&state, excuse_id)
+        .await?
+        .filter(|x| x.user_id == user_id)
+        .ok_or_else(excuse_not_found)?;
+    if !excuse.is_pending() {
+        return Err(excuse_already_reviewed());
+    }
+
+    let key = excuses::storage_key(excuse_id);
+    state
+        .storage
+        .put(&key, body.to_vec(), content_type)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to store excuse evidence {}: {}", excuse_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to store file"})),
+            )
+        })?;
+
+    let excuse = state
+        .db
+        .set_excuse_attachment(
+            excuse_id,
+            &params.filename,
+            content_type,
+            &key,
+            body.len() as i64,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update excuse: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update excuse"})),
+            )
+        })?
+        .ok_or_else(excuse_already_reviewed)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Evidence attached",
+            "excuse": excuse
+        })),
+    ))
+}
+
+/// Download the evidence attached to one of the current user's excuses
+pub async fn get_my_excuse_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(excuse_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let excuse = find_excuse(&state, excuse_id)
+        .await?
+        .filter(|x| x.user_id == user_id)
+        .ok_or_else(excuse_not_found)?;
+
+    excuse_attachment(&state, &excuse).await
+}
+
+/// List excuses, optionally by status or event (Admin only)
+pub async fn list_excuses(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExcuseListQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let excuses = state
+        .db
+        .list_excuses(None, query.status, query.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!({"excuses": excuses}))))
+}
+
+/// Approve or reject an excuse, or revise an earlier decision (Admin only)
+pub async fn review_excuse(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(excuse_id): Path<Uuid>,
+    Json(payload): Json<ReviewExcuseRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    if payload.status == ExcuseStatus::Pending {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Status must be approved or rejected"})),
+        ));
+    }
+
+    let excuse = state
+        .db
+        .review_excuse(excuse_id, payload.status, admin_id, payload.note.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to review excuse: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to review excuse"})),
+            )
+        })?
+        .ok_or_else(excuse_not_found)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": format!("Excuse {}", payload.status),
+            "excuse": excuse
+        })),
+    ))
+}
+
+/// Download the evidence attached to any excuse (Admin only)
+pub async fn get_excuse_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(excuse_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let excuse = find_excuse(&state, excuse_id)
+        .await?
+        .ok_or_else(excuse_not_found)?;
+
+    excuse_attachment(&state, &excuse).await
+}
+
+/// Redirect to the evidence in object storage, or serve it from disk
+async fn excuse_attachment(
+    state: &AppState,
+    excuse: &AbsenceExcuse,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    stored_file(
+        state,
+        StoredFile {
+            key: excuse.attachment_key.as_deref(),
+            filename: excuse.attachment_filename.as_deref(),
+            content_type: excuse.attachment_content_type.as_deref(),
+        },
+        "Excuse has no attachment",
+    )
+    .await
+}
+
+/// A private file kept in storage, as recorded against its owner
+struct StoredFile<'a> {
+    key: Option<&'a str>,
+    filename: Option<&'a str>,
+    content_type: Option<&'a str>,
+}
+
+/// Serve a private file, redirecting to a short-lived URL when the storage
+/// backend supports one
+async fn stored_file(
+    state: &AppState,
+    file: StoredFile<'_>,
+    missing: &str,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let not_found = || (StatusCode::NOT_FOUND, Json(json!({ "error": missing })));
+    let key = file.key.ok_or_else(not_found)?;
+
+    if let Some(url) = excuses::download_url(&state.storage, key) {
+        return Ok(Redirect::temporary(&url).into_response());
+    }
+
+    let body = state.storage.get(key).await.map_err(|e| match e {
+        StorageError::NotFound(_) => not_found(),
+        e => {
+            tracing::error!("Failed to read stored file {}: {}", key, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to read attachment"})),
+            )
+        }
+    })?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                file.content_type.unwrap_or_default().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition(file.filename.unwrap_or_default()),
+            ),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+async fn find_excuse(
+    state: &AppState,
+    excuse_id: Uuid,
+) -> Result<Option<AbsenceExcuse>, (StatusCode, Json<Value>)> {
+    state.db.get_excuse_by_id(excuse_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })
+}
+
+fn excuse_not_found() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Excuse not found"})),
+    )
+}
+
+fn excuse_already_reviewed() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({"error": "Excuse has already been reviewed"})),
+    )
+}
+
+// ============================================================================
+// Announcement Handlers
+// ============================================================================
+
+/// Post an announcement, optionally targeted and scheduled (Admin only)
+pub async fn create_announcement(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateAnnouncementRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    if let Some(event_id) = payload.event_id {
+        state
+            .db
+            .get_event_by_id(event_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Event not found"})),
+                )
+            })?;
+    }
+
+    let announcement = state
+        .db
+        .create_announcement(
+            &payload.title,
+            &payload.body,
+            payload.event_id,
+            payload.audience_role,
+            payload.is_important,
+            payload.publish_at.unwrap_or_else(Utc::now),
+            user_id,
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to create announcement"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Announcement created successfully",
+            "announcement": announcement
+        })),
+    ))
+}
+
+/// Edit or reschedule an announcement (Admin only)
+pub async fn update_announcement(
+    State(state): State<Arc<AppState>>,
+    Path(announcement_id): Path<Uuid>,
+    Json(payload): Json<UpdateAnnouncementRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let announcement = state
+        .db
+        .update_announcement(
+            announcement_id,
+            payload.title.as_deref(),
+            payload.body.as_deref(),
+            payload.is_important,
+            payload.publish_at,
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update announcement"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Announcement not found"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Announcement updated successfully",
+            "announcement": announcement
+        })),
+    ))
+}
+
+/// Delete an announcement (Admin only)
+pub async fn delete_announcement(
+    State(state): State<Arc<AppState>>,
+    Path(announcement_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let deleted = state
+        .db
+        .delete_announcement(announcement_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to delete announcement"})),
+            )
+        })?;
+
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Announcement not found"})),
+        ));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Announcement deleted successfully"})),
+    ))
+}
+
+/// Every announcement including scheduled ones, with read counts (Admin only)
+pub async fn list_all_announcements(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<AnnouncementListParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let (announcements, total) = state
+        .db
+        .list_all_announcements(page, per_page)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch announcements"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "announcements": announcements,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "total_pages": pagination.total_pages(total)
+        })),
+    ))
+}
+
+/// Published announcements addressed to the current user, with read state
+pub async fn list_announcements(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(params): Query<AnnouncementListParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let (announcements, total, unread_count) = state
+        .db
+        .list_announcements_for_user(user_id, page, per_page, params.unread_only.unwrap_or(false))
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch announcements"})),
+            )
+        })?;
+
+    let response = AnnouncementListResponse {
+        announcements,
+        unread_count,
+        total,
+        page,
+        per_page,
+        total_pages: pagination.total_pages(total),
+    };
+
+    Ok((StatusCode::OK, Json(json!(response))))
+}
+
+/// Mark one announcement as read by the current user
+pub async fn mark_announcement_read(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(announcement_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let marked = state
+        .db
+        .mark_announcement_read(user_id, announcement_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    // Scheduled and untargeted announcements are indistinguishable from
+    // missing ones
+    if !marked {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Announcement not found"})),
+        ));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Announcement marked as read"})),
+    ))
+}
+
+/// Mark every announcement visible to the current user as read
+pub async fn mark_all_announcements_read(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let marked = state
+        .db
+        .mark_all_announcements_read(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Announcements marked as read",
+            "marked": marked
+        })),
+    ))
+}
+
+// ============================================================================
+// Member Requirements Handlers
+// ============================================================================
+
+/// Dietary and accessibility requirements of an event's attendees, for
+/// catering and venue planning (Welfare only)
+pub async fn get_requirements_report(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<RequirementsReport>, (StatusCode, Json<Value>)> {
+    let event = find_event(&state, event_id).await?;
+
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let attendees = state.db.count_attendees(event_id).await.map_err(db_error)?;
+    let dietary = state.db.dietary_counts(event_id).await.map_err(db_error)?;
+    let accessibility = state
+        .db
+        .accessibility_needs(event_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(RequirementsReport {
+        event_id,
+        title: event.title,
+        attendees,
+        dietary,
+        accessibility,
+    }))
+}
+
+// ============================================================================
+// Fee Handlers
+// ============================================================================
+
+/// Upper-case a currency code given with a fee or expense, defaulting to
+/// `FEE_CURRENCY`
+fn currency_code(
+    state: &AppState,
+    currency: Option<&str>,
+) -> Result<String, (StatusCode, Json<Value>)> {
+    match currency {
+        Some(currency) if !currency.chars().all(|c| c.is_ascii_alphabetic()) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Currency must be a three-letter code"})),
+        )),
+        Some(currency) => Ok(currency.to_ascii_uppercase()),
+        None => Ok(state.config.fee_currency.clone()),
+    }
+}
+
+/// Look up the fee of an event, which must have one
+async fn find_fee(state: &AppState, event_id: Uuid) -> Result<EventFee, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_event_fee(event_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "This event has no fee"})),
+            )
+        })
+}
+
+/// Set or change what members pay to attend an event (Treasurers and admins)
+pub async fn set_event_fee(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<SetEventFeeRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    let currency = currency_code(&state, payload.currency.as_deref())?;
+    let description = payload
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|description| !description.is_empty());
+
+    find_event(&state, event_id).await?;
+    let fee = state
+        .db
+        .set_event_fee(
+            event_id,
+            payload.amount_minor,
+            &currency,
+            description,
+            user_id,
+        )
+        .await
+        .map_err(db_error)?;
+
+    tracing::info!(
+        "User {} set the fee of event {} to {} {}",
+        user_id,
+        event_id,
+        fee.amount_minor,
+        fee.currency
+    );
+
+    Ok(Json(json!({
+        "message": "Event fee set",
+        "fee": fee,
+    })))
+}
+
+/// Make an event free again (Treasurers and admins). Payments already
+/// recorded are kept.
+pub async fn remove_event_fee(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let removed = state
+        .db
+        .remove_event_fee(event_id)
+        .await
+        .map_err(db_error)?;
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "This event has no fee"})),
+        ));
+    }
+
+    Ok(Json(json!({"message": "Event fee removed"})))
+}
+
+/// Who has paid an event's fee, with totals (Treasurers and admins). Lists
+/// the event's attendees and anyone else who has paid.
+pub async fn list_event_payments(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<EventPaymentsResponse>, (StatusCode, Json<Value>)> {
+    let event = find_event(&state, event_id).await?;
+    let fee = find_fee(&state, event_id).await?;
+    let payments = state
+        .db
+        .list_event_payments(event_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(EventPaymentsResponse {
+        event_id,
+        title: event.title,
+        fee,
+        totals: PaymentTotals::tally(&payments),
+        payments,
+    }))
+}
+
+/// Mark a member paid or excused from paying, or undo either by marking
+/// them unpaid (Treasurers and admins). Payments marked here are recorded
+/// as made by hand, for the fee's current amount.
+pub async fn record_payment(
+    State(state): State<Arc<AppState>>,
+    Extension(treasurer_id): Extension<Uuid>,
+    Path((event_id, user_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RecordPaymentRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    let fee = find_fee(&state, event_id).await?;
+
+    let found = match payload.status {
+        PaymentStatus::Unpaid => {
+            state
+                .db
+                .clear_payment(event_id, user_id)
+                .await
+                .map_err(db_error)?;
+            true
+        }
+        PaymentStatus::Pending => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Only online payments can be pending"})),
+            ));
+        }
+        status => {
+            let note = payload
+                .note
+                .as_deref()
+                .map(str::trim)
+                .filter(|note| !note.is_empty());
+            state
+                .db
+                .record_payment(&fee, user_id, status, note, treasurer_id)
+                .await
+                .map_err(db_error)?
+        }
+    };
+    let payment = match found {
+        true => state
+            .db
+            .get_member_payment(event_id, user_id)
+            .await
+            .map_err(db_error)?,
+        false => None,
+    };
+    let Some(payment) = payment else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User not found"})),
+        ));
+    };
+
+    tracing::info!(
+        "User {} marked user {} {} for event {}",
+        treasurer_id,
+        user_id,
+        payload.status.as_str(),
+        event_id
+    );
+
+    Ok(Json(json!({
+        "message": "Payment recorded",
+        "payment": payment,
+    })))
+}
+
+/// Send the current user to Stripe to pay an event's fee. Returns the URL
+/// of the payment page; the payment is recorded when Stripe confirms it.
+pub async fn start_checkout(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let Some(stripe) = &state.stripe else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "Online payment is not available; please pay a treasurer"})),
+        ));
+    };
+    let event = find_event(&state, event_id).await?;
+    let fee = find_fee(&state, event_id).await?;
+
+    let payment = state
+        .db
+        .get_member_payment(event_id, user_id)
+        .await
+        .map_err(db_error)?;
+    if let Some(payment) = payment.filter(|p| p.status == "paid" || p.status == "waived") {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": format!("Your fee is already {}", payment.status)})),
+        ));
+    }
+
+    let session = stripe
+        .create_checkout(&event, &fee, user_id, &state.config.frontend_url)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to start checkout for event {}: {}", event_id, e);
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({"error": "Failed to start the payment"})),
+            )
+        })?;
+    state
+        .db
+        .start_checkout_payment(event_id, user_id, &session.id, &fee.currency)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "checkout_url": session.url })))
+}
+
+/// Stripe's notice that a checkout was completed or abandoned. Unsigned
+/// calls are refused; events the service does not act on are acknowledged
+/// so Stripe stops retrying them.
+pub async fn stripe_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let Some(stripe) = &state.stripe else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Online payment is not enabled"})),
+        ));
+    };
+    let event = headers
+        .get("Stripe-Signature")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|signature| stripe.parse_webhook(signature, &body))
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Invalid signature"})),
+            )
+        })?;
+
+    let session = &event.data.object;
+    match event.kind.as_str() {
+        "checkout.session.completed" | "checkout.session.async_payment_succeeded"
+            if session.payment_status.as_deref() == Some("paid") =>
+        {
+            let amount = session
+                .amount_total
+                .and_then(|amount| i32::try_from(amount).ok());
+            let paid = state
+                .db
+                .complete_checkout_payment(&session.id, amount)
+                .await
+                .map_err(db_error)?;
+            if let Some((event_id, user_id)) = paid {
+                tracing::info!("User {} paid the fee of event {} online", user_id, event_id);
+            }
+        }
+        "checkout.session.expired" | "checkout.session.async_payment_failed" => {
+            state
+                .db
+                .expire_checkout_payment(&session.id)
+                .await
+                .map_err(db_error)?;
+        }
+        _ => {}
+    }
+
+    Ok(StatusCode::OK)
+}
+
+// ============================================================================
+// Expense Handlers
+// ============================================================================
+
+fn expense_not_found() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Expense not found"})),
+    )
+}
+
+fn expense_already_reviewed() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({"error": "Expense has already been reviewed"})),
+    )
+}
+
+async fn find_expense(
+    state: &AppState,
+    expense_id: Uuid,
+) -> Result<EventExpense, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_expense(expense_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(expense_not_found)
+}
+
+/// Enter money spent on an event (Treasurers and admins). It awaits
+/// approval by someone else; attach the receipt with a separate upload.
+pub async fn create_expense(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<CreateExpenseRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    if payload.description.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Description cannot be blank"})),
+        ));
+    }
+    let currency = currency_code(&state, payload.currency.as_deref())?;
+
+    find_event(&state, event_id).await?;
+    let expense = state
+        .db
+        .create_expense(event_id, &payload, &currency, user_id)
+        .await
+        .map_err(db_error)?;
+
+    tracing::info!(
+        "User {} entered expense {} for event {}",
+        user_id,
+        expense.id,
+        event_id
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Expense entered",
+            "expense": expense,
+        })),
+    ))
+}
+
+/// An event's expenses, optionally by status (Treasurers and admins)
+pub async fn list_expenses(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<ExpenseListQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    find_event(&state, event_id).await?;
+    let expenses = state
+        .db
+        .list_expenses(event_id, query.status)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "expenses": expenses })))
+}
+
+/// Withdraw an expense entered by mistake, with its receipt (Treasurers and
+/// admins). Reviewed expenses stay on the books; reject them instead.
+pub async fn delete_expense(
+    State(state): State<Arc<AppState>>,
+    Path(expense_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let expense = find_expense(&state, expense_id).await?;
+    if !expense.is_pending() {
+        return Err(expense_already_reviewed());
+    }
+    let expense = state
+        .db
+        .delete_pending_expense(expense_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(expense_already_reviewed)?;
+
+    if let Some(key) = &expense.receipt_key {
+        if let Err(e) = state.storage.delete(key).await {
+            tracing::warn!("Failed to delete receipt of expense {}: {}", expense_id, e);
+        }
+    }
+
+    Ok(Json(json!({"message": "Expense deleted"})))
+}
+
+/// Attach the receipt for an expense that has not been reviewed, replacing
+/// any earlier one (Treasurers and admins)
+pub async fn upload_expense_receipt(
+    State(state): State<Arc<AppState>>,
+    Path(expense_id): Path<Uuid>,
+    Query(params): Query<ReceiptParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    params.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(str::trim)
+        .unwrap_or_default();
+    check_content_type(content_type)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+    if body.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "File cannot be empty"})),
+        ));
+    }
+
+    let expense = find_expense(&state, expense_id).await?;
+    if !expense.is_pending() {
+        return Err(expense_already_reviewed());
+    }
+
+    let key = expenses::storage_key(expense_id);
+    state
+        .storage
+        .put(&key, body.to_vec(), content_type)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to store receipt {}: {}", expense_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to store file"})),
+            )
+        })?;
+
+    let expense = state
+        .db
+        .set_expense_receipt(
+            expense_id,
+            &params.filename,
+            content_type,
+            &key,
+            body.len() as i64,
+        )
+        .await
+        .map_err(db_error)?
+        .ok_or_else(expense_already_reviewed)?;
+
+    Ok(Json(json!({
+        "message": "Receipt attached",
+        "expense": expense,
+    })))
+}
+
+/// Download an expense's receipt (Treasurers and admins)
+pub async fn get_expense_receipt(
+    State(state): State<Arc<AppState>>,
+    Path(expense_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let expense = find_expense(&state, expense_id).await?;
+
+    stored_file(
+        &state,
+        StoredFile {
+            key: expense.receipt_key.as_deref(),
+            filename: expense.receipt_filename.as_deref(),
+            content_type: expense.receipt_content_type.as_deref(),
+        },
+        "Expense has no receipt",
+    )
+    .await
+}
+
+/// Approve or reject an expense (Treasurers and admins). Nobody may approve
+/// an expense they entered themselves.
+pub async fn review_expense(
+    State(state): State<Arc<AppState>>,
+    Extension(reviewer_id): Extension<Uuid>,
+    Path(expense_id): Path<Uuid>,
+    Json(payload): Json<ReviewExpenseRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    if payload.status == ExpenseStatus::Pending {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Status must be approved or rejected"})),
+        ));
+    }
+
+    let expense = find_expense(&state, expense_id).await?;
+    if payload.status == ExpenseStatus::Approved && expense.submitted_by == Some(reviewer_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                json!({"error": "Expenses must be approved by someone other than who entered them"}),
+            ),
+        ));
+    }
+
+    let expense = state
+        .db
+        .review_expense(
+            expense_id,
+            payload.status,
+            reviewer_id,
+            payload.note.as_deref(),
+        )
+        .await
+        .map_err(db_error)?
+        .ok_or_else(expense_not_found)?;
+
+    tracing::info!(
+        "User {} marked expense {} {}",
+        reviewer_id,
+        expense_id,
+        payload.status.as_str()
+    );
+
+    Ok(Json(json!({
+        "message": format!("Expense {}", payload.status.as_str()),
+        "expense": expense,
+    })))
+}
+
+/// Fees collected against money spent on an event, by currency, with its
+/// spending by category (Treasurers and admins)
+pub async fn get_event_finances(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<EventFinanceSummary>, (StatusCode, Json<Value>)> {
+    let event = find_event(&state, event_id).await?;
+    let totals = state
+        .db
+        .event_finances(None, None, Some(event_id))
+        .await
+        .map_err(db_error)?;
+    let by_category = state
+        .db
+        .expense_category_totals(event_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(EventFinanceSummary {
+        event_id,
+        title: event.title,
+        totals,
+        by_category,
+    }))
+}
+
+/// Fees collected and money spent per event, most recent first, for events
+/// between two dates (Treasurers and admins)
+pub async fn list_finances(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FinanceQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let events = state
+        .db
+        .event_finances(query.from, query.to, None)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "events": events })))
+}
+
+// ============================================================================
+// Equipment Handlers
+// ============================================================================
+
+fn asset_tag_taken(e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    match e.as_database_error() {
+        Some(db_err) if db_err.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Another item already has that asset tag"})),
+        ),
+        _ => db_error(e),
+    }
+}
+
+async fn find_equipment(
+    state: &AppState,
+    item_id: Uuid,
+) -> Result<EquipmentItem, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_equipment(item_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Item not found"})),
+            )
+        })
+}
+
+/// The equipment register, with who has each item (Admin only)
+pub async fn list_equipment(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EquipmentListQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let items = state
+        .db
+        .list_equipment(query.include_retired)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "items": items })))
+}
+
+/// Add an item to the register (Admin only)
+pub async fn create_equipment(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<EquipmentRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    let item = state
+        .db
+        .create_equipment(&payload, user_id)
+        .await
+        .map_err(asset_tag_taken)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Item added",
+            "item": item,
+        })),
+    ))
+}
+
+/// An item with every time it has been lent (Admin only)
+pub async fn get_equipment(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let item = find_equipment(&state, item_id).await?;
+    let checkouts = state
+        .db
+        .list_equipment_checkouts(CheckoutFilter {
+            item_id: Some(item_id),
+            ..Default::default()
+        })
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({
+        "item": item,
+        "checkouts": checkouts,
+    })))
+}
+
+/// Replace an item's details (Admin only)
+pub async fn update_equipment(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<EquipmentRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+    let item = state
+        .db
+        .update_equipment(item_id, &payload)
+        .await
+        .map_err(asset_tag_taken)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Item not found"})),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "message": "Item updated",
+        "item": item,
+    })))
+}
+
+/// Take an item out of use, keeping its history (Admin only). It must be
+/// returned first.
+pub async fn retire_equipment(
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let item = find_equipment(&state, item_id).await?;
+    if item.retired_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Item is already retired"})),
+        ));
+    }
+    let retired = state.db.retire_equipment(item_id).await.map_err(db_error)?;
+    if !retired {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Item must be returned before it is retired"})),
+        ));
+    }
+
+    Ok(Json(json!({"message": "Item retired"})))
+}
+
+/// Lend an item to a member for an event (Admin only). Unless a due time
+/// is given it is due back `EQUIPMENT_RETURN_HOURS` after the event ends.
+pub async fn checkout_equipment(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<CheckoutEquipmentRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let item = find_equipment(&state, item_id).await?;
+    if item.retired_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Item has been retired"})),
+        ));
+    }
+    if let Some(holder) = &item.holder_username {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": format!("Item is already out with {}", holder)})),
+        ));
+    }
+
+    let event = find_event(&state, payload.event_id).await?;
+    let due_at = payload
+        .due_at
+        .unwrap_or_else(|| equipment::default_due(&event, state.config.equipment_return_hours));
+    if due_at <= Utc::now() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Due time must be in the future"})),
+        ));
+    }
+
+    let checkout = state
+        .db
+        .checkout_equipment(item_id, &payload, due_at, admin_user_id)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_err) if db_err.is_foreign_key_violation() => (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            ),
+            _ => db_error(e),
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Item is already out"})),
+            )
+        })?;
+
+    tracing::info!(
+        "User {} lent item {} to {} for event {}",
+        admin_user_id,
+        item_id,
+        checkout.user_id,
+        payload.event_id
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Item checked out",
+            "checkout": checkout,
+        })),
+    ))
+}
+
+/// Record an item coming back (Admin only)
+pub async fn return_equipment(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<ReturnEquipmentRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    find_equipment(&state, item_id).await?;
+    let checkout = state
+        .db
+        .return_equipment(item_id, admin_user_id, payload.note.as_deref())
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Item is not checked out"})),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "message": "Item returned",
+        "checkout": checkout,
+    })))
+}
+
+/// Items still out after they were due back, longest out first (Admin only)
+pub async fn list_overdue_equipment(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut checkouts = state
+        .db
+        .list_equipment_checkouts(CheckoutFilter {
+            overdue_only: true,
+            ..Default::default()
+        })
+        .await
+        .map_err(db_error)?;
+    checkouts.sort_by_key(|c| c.due_at);
+
+    Ok(Json(json!({ "checkouts": checkouts })))
+}
+
+/// Everything lent for an event, returned or not (Admin only)
+pub async fn list_event_equipment(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    find_event(&state, event_id).await?;
+    let checkouts = state
+        .db
+        .list_equipment_checkouts(CheckoutFilter {
+            event_id: Some(event_id),
+            ..Default::default()
+        })
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "checkouts": checkouts })))
+}
+
+/// Equipment the current user has and has yet to return
+pub async fn list_my_equipment(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let checkouts = state
+        .db
+        .list_equipment_checkouts(CheckoutFilter {
+            user_id: Some(user_id),
+            open_only: true,
+            ..Default::default()
+        })
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({ "checkouts": checkouts })))
+}
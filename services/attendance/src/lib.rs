@@ -1,45 +1,132 @@
 pub mod auth_middleware;
+pub mod check_in;
 pub mod config;
 pub mod database;
+pub mod equipment;
+pub mod etags;
+pub mod event_report;
+pub mod excuses;
+pub mod expenses;
 pub mod handlers;
+pub mod mailer;
 pub mod models;
+pub mod outbox;
+pub mod payments;
+pub mod reminders;
+pub mod schedule_import;
+pub mod staff;
+pub mod tags;
+pub mod waitlist;
+pub mod walk_ins;
 
+pub use common::startup::StartupError;
 pub use config::Config;
 pub use database::Database;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
+use common::{
+    config::ConfigError, fields::sparse_fieldsets, versioning::CURRENT_VERSION, EventBus, Feature,
+    Features, KvStore, Maintenance, Notifier, PendingActions, Storage,
+};
+use mailer::Mailer;
+use payments::Stripe;
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
 
 pub struct AppState {
     pub db: Database,
     pub config: Config,
+    pub notifier: Notifier,
+    pub events: EventBus,
+    /// Cached admin and suspension checks, shared between replicas when
+    /// Redis is set up
+    pub kv: KvStore,
+    pub storage: Storage,
+    pub mailer: Mailer,
+    /// Online fee payment, when Stripe is configured
+    pub stripe: Option<Stripe>,
+    /// Feature flags, with admins' overrides from the database
+    pub features: Features,
+    /// Whether non-admins are being turned away
+    pub maintenance: Maintenance,
+    /// Destructive admin actions waiting for a second admin
+    pub pending_actions: PendingActions,
 }
 
-pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
-    let config = Config::from_env()?;
+/// Connect to the database, run migrations and build the shared state.
+/// Tests can skip this and construct `AppState` around their own database.
+pub async fn build_state(config: Config) -> Result<Arc<AppState>, StartupError> {
     let db = Database::new(&config.database_url).await?;
+    let db = match &config.database_replica_url {
+        Some(url) => db.with_replica(common::ReadReplica::connect(url, 5)?),
+        None => db,
+    };
     db.migrate().await?;
 
-    let state = Arc::new(AppState {
+    let notifier = Notifier::new(&config.notifications);
+    let storage = Storage::new(&config.storage);
+    let events = EventBus::connect(&config.event_bus).await;
+    let kv = KvStore::new(&config.kv);
+    let mailer = Mailer::from_config(&config).map_err(|problem| ConfigError {
+        problems: vec![problem],
+    })?;
+    let stripe = config.stripe.as_ref().map(Stripe::new);
+    let features = Features::new(db.pool().clone(), &config.features);
+    let maintenance = Maintenance::new(db.pool().clone(), outbox::SERVICE);
+    let pending_actions = PendingActions::new(db.pool().clone(), outbox::SERVICE);
+
+    Ok(Arc::new(AppState {
         db,
-        config: config.clone(),
-    });
+        config,
+        notifier,
+        events,
+        kv,
+        storage,
+        mailer,
+        stripe,
+        features,
+        maintenance,
+        pending_actions,
+    }))
+}
 
-    let cors = configure_cors(&config);
+/// Assemble the service's routes around already-built state
+pub fn create_app(state: Arc<AppState>) -> Router {
+    let cors = common::configure_cors(&state.config.cors, &[]);
+    // Fee routes answer 404 while payments are turned off
+    let require_payments = middleware::from_fn_with_state(
+        (state.features.clone(), Feature::Payments),
+        common::features::require_feature,
+    );
 
-    // Public routes (require authentication)
-    let public_routes = Router::new()
+    // Read-only routes (require authentication or an API key for integrations)
+    let readable_routes = Router::new()
         .route("/events", get(handlers::list_events))
-        .route("/events/:event_id", get(handlers::get_event))
+        .route(
+            "/events/:event_id",
+            get(handlers::get_event).layer(middleware::from_fn_with_state(
+                state.clone(),
+                etags::event_etag,
+            )),
+        )
         .route(
             "/events/:event_id/attendance",
             get(handlers::get_event_attendance),
         )
+        .route("/event-categories", get(handlers::list_categories))
+        .route("/tags", get(handlers::list_tags))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::read_access_middleware::<AppState>,
+        ))
+        .with_state(state.clone());
+
+    // Public routes (require authentication)
+    let public_routes = Router::new()
         .route(
             "/events/:event_id/my-attendance",
             get(handlers::get_my_attendance),
@@ -48,19 +135,188 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/events/:event_id/availability",
             post(handlers::set_availability),
         )
+        .route("/reminders/mine", get(handlers::list_my_reminders))
+        .route(
+            "/events/availability/bulk",
+            post(handlers::bulk_set_availability),
+        )
+        .route(
+            "/events/:event_id/self-check-in",
+            post(handlers::self_check_in),
+        )
+        // Absence excuses
+        .route("/events/:event_id/excuse", post(handlers::submit_excuse))
+        .route("/excuses/mine", get(handlers::list_my_excuses))
+        .route(
+            "/excuses/:excuse_id/attachment",
+            get(handlers::get_my_excuse_attachment).merge(
+                put(handlers::upload_excuse_attachment).layer(DefaultBodyLimit::max(
+                    state.config.excuse_attachment_max_bytes as usize,
+                )),
+            ),
+        )
+        // Event fees, paid online
+        .route(
+            "/events/:event_id/checkout",
+            post(handlers::start_checkout).layer(require_payments.clone()),
+        )
+        // Equipment on loan
+        .route("/equipment/mine", get(handlers::list_my_equipment))
+        // Code-of-conduct reports
+        .route("/reports", post(handlers::file_report))
+        .route("/reports/mine", get(handlers::list_my_reports))
+        // Announcements
+        .route("/announcements", get(handlers::list_announcements))
+        .route(
+            "/announcements/read-all",
+            post(handlers::mark_all_announcements_read),
+        )
+        .route(
+            "/announcements/:announcement_id/read",
+            post(handlers::mark_announcement_read),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::auth_middleware,
+            auth_middleware::auth_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
     // Admin routes
     let admin_routes = Router::new()
         .route("/events", post(handlers::create_event))
+        .route(
+            "/admin/events/import",
+            post(handlers::import_events)
+                .layer(DefaultBodyLimit::max(state.config.limits.bulk_body_bytes)),
+        )
         .route("/events/:event_id", patch(handlers::update_event))
         .route("/events/:event_id", delete(handlers::delete_event))
+        .route(
+            "/admin/pending-actions",
+            get(handlers::list_pending_actions),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/confirm",
+            post(handlers::confirm_pending_action),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/reject",
+            post(handlers::reject_pending_action),
+        )
         .route("/events/:event_id/lock", post(handlers::lock_event))
-        .route("/events/:event_id/check-in", post(handlers::check_in_user))
+        .route("/events/:event_id/tags", put(handlers::set_event_tags))
+        .route(
+            "/admin/check-ins/flagged",
+            get(handlers::list_flagged_check_ins),
+        )
+        .route(
+            "/attendance/matrix",
+            get(handlers::get_attendance_matrix).layer(middleware::from_fn(sparse_fieldsets)),
+        )
+        .route(
+            "/admin/stats",
+            get(handlers::get_event_stats)
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route(
+            "/admin/events/:event_id/report",
+            get(handlers::download_event_report),
+        )
+        .route(
+            "/admin/events/:event_id/send-report",
+            post(handlers::send_event_report),
+        )
+        .route(
+            "/admin/events/:event_id/managers",
+            get(handlers::list_event_managers).post(handlers::add_event_manager),
+        )
+        .route(
+            "/admin/events/:event_id/managers/:user_id",
+            delete(handlers::remove_event_manager),
+        )
+        .route(
+            "/admin/events/:event_id/staff",
+            get(handlers::list_event_staff).post(handlers::assign_event_staff),
+        )
+        .route(
+            "/admin/events/:event_id/staff/:user_id",
+            delete(handlers::remove_event_staff),
+        )
+        .route("/admin/event-categories", post(handlers::create_category))
+        .route(
+            "/admin/event-categories/:category_id",
+            patch(handlers::update_category).delete(handlers::delete_category),
+        )
+        .route(
+            "/admin/event-templates",
+            get(handlers::list_event_templates).post(handlers::create_event_template),
+        )
+        .route(
+            "/admin/event-templates/:template_id",
+            get(handlers::get_event_template)
+                .put(handlers::update_event_template)
+                .delete(handlers::delete_event_template),
+        )
+        .route(
+            "/admin/events/from-template/:template_id",
+            post(handlers::create_event_from_template),
+        )
+        .route("/admin/tags/:tag/rename", post(handlers::rename_tag))
+        .route("/admin/tags/:tag", delete(handlers::delete_tag))
+        .route("/admin/excuses", get(handlers::list_excuses))
+        .route(
+            "/admin/excuses/:excuse_id/review",
+            post(handlers::review_excuse),
+        )
+        .route(
+            "/admin/excuses/:excuse_id/attachment",
+            get(handlers::get_excuse_attachment),
+        )
+        .route(
+            "/admin/equipment",
+            get(handlers::list_equipment).post(handlers::create_equipment),
+        )
+        .route(
+            "/admin/equipment/overdue",
+            get(handlers::list_overdue_equipment),
+        )
+        .route(
+            "/admin/equipment/:item_id",
+            get(handlers::get_equipment)
+                .put(handlers::update_equipment)
+                .delete(handlers::retire_equipment),
+        )
+        .route(
+            "/admin/equipment/:item_id/checkout",
+            post(handlers::checkout_equipment),
+        )
+        .route(
+            "/admin/equipment/:item_id/return",
+            post(handlers::return_equipment),
+        )
+        .route(
+            "/admin/events/:event_id/equipment",
+            get(handlers::list_event_equipment),
+        )
+        .route("/announcements", post(handlers::create_announcement))
+        .route("/announcements/all", get(handlers::list_all_announcements))
+        .route(
+            "/announcements/:announcement_id",
+            patch(handlers::update_announcement),
+        )
+        .route(
+            "/announcements/:announcement_id",
+            delete(handlers::delete_announcement),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::admin_middleware::<AppState>,
+        ))
+        .with_state(state.clone());
+
+    // Event admin routes - admins, or members an admin has made managers
+    // of the event in the path
+    let event_admin_routes = Router::new()
         .route(
             "/events/:event_id/revoke",
             post(handlers::revoke_availability),
@@ -69,76 +325,137 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/events/:event_id/set-availability",
             post(handlers::admin_set_availability),
         )
-        .route("/attendance/matrix", get(handlers::get_attendance_matrix))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::admin_middleware,
+            auth_middleware::event_admin_middleware::<AppState>,
+        ))
+        .with_state(state.clone());
+
+    // Check-in desk routes - admins, and the event's managers and staff
+    let check_in_routes = Router::new()
+        .route("/events/:event_id/check-in", post(handlers::check_in_user))
+        .route(
+            "/events/:event_id/walk-in",
+            post(handlers::register_walk_in),
+        )
+        .route("/events/:event_id/walk-ins", get(handlers::list_walk_ins))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::check_in_middleware,
+        ))
+        .with_state(state.clone());
+
+    // Equity routes - conduct reports, equity officers only
+    let equity_routes = Router::new()
+        .route("/equity/reports", get(handlers::equity_list_reports))
+        .route(
+            "/equity/reports/:report_id",
+            get(handlers::equity_get_report),
+        )
+        .route(
+            "/equity/reports/:report_id/status",
+            post(handlers::equity_update_report_status),
+        )
+        .route(
+            "/equity/reports/:report_id/notes",
+            post(handlers::equity_add_report_note),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::equity_middleware,
         ))
         .with_state(state.clone());
 
-    let app = Router::new()
+    // Welfare routes - members' requirements, welfare role only
+    let welfare_routes = Router::new()
+        .route(
+            "/welfare/events/:event_id/requirements",
+            get(handlers::get_requirements_report),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::welfare_middleware,
+        ))
+        .with_state(state.clone());
+
+    // Treasurer routes - event fees and payments, treasurers and admins
+    let treasurer_routes = Router::new()
+        .route(
+            "/treasurer/events/:event_id/fee",
+            put(handlers::set_event_fee)
+                .delete(handlers::remove_event_fee)
+                .layer(require_payments.clone()),
+        )
+        .route(
+            "/treasurer/events/:event_id/payments",
+            get(handlers::list_event_payments).layer(require_payments.clone()),
+        )
+        .route(
+            "/treasurer/events/:event_id/payments/:user_id",
+            put(handlers::record_payment).layer(require_payments.clone()),
+        )
+        .route(
+            "/treasurer/events/:event_id/expenses",
+            get(handlers::list_expenses).post(handlers::create_expense),
+        )
+        .route(
+            "/treasurer/events/:event_id/finances",
+            get(handlers::get_event_finances),
+        )
+        .route("/treasurer/finances", get(handlers::list_finances))
+        .route(
+            "/treasurer/expenses/:expense_id",
+            delete(handlers::delete_expense),
+        )
+        .route(
+            "/treasurer/expenses/:expense_id/receipt",
+            get(handlers::get_expense_receipt).merge(put(handlers::upload_expense_receipt).layer(
+                DefaultBodyLimit::max(state.config.expense_receipt_max_bytes as usize),
+            )),
+        )
+        .route(
+            "/treasurer/expenses/:expense_id/review",
+            post(handlers::review_expense),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::treasurer_middleware,
+        ))
+        .with_state(state.clone());
+
+    let api = Router::new()
+        .merge(readable_routes)
         .merge(public_routes)
         .merge(admin_routes)
-        .route("/health", get(|| async { "OK" }))
-        .layer(cors);
-
-    Ok(app)
-}
+        .merge(event_admin_routes)
+        .merge(check_in_routes)
+        .merge(equity_routes)
+        .merge(welfare_routes)
+        .merge(treasurer_routes)
+        .route("/calendar/events.ics", get(handlers::events_calendar))
+        // Called by Stripe, which signs the body instead of authenticating
+        .route(
+            "/payments/stripe/webhook",
+            post(handlers::stripe_webhook).layer(require_payments),
+        )
+        .with_state(state.clone());
 
-fn configure_cors(config: &Config) -> CorsLayer {
-    if config.cors_strict_mode {
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
-    } else if config.allowed_origins.contains(&"*".to_string()) {
-        CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers(Any)
-            .allow_credentials(false)
-    } else {
-        // Specific origins mode (non-strict) - allow listed origins only
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
-    }
+    // New clients use /v1; the unversioned paths stay for app releases
+    // that predate it, announcing their deprecation
+    common::Versions::new()
+        .mount(CURRENT_VERSION, api.clone())
+        .unversioned(api, state.config.unversioned.clone())
+        .into_router()
+        .route("/health", get(|| async { "OK" }))
+        .layer(middleware::from_fn_with_state(
+            (state.clone(), state.maintenance.clone()),
+            common::maintenance::maintenance_mode::<AppState>,
+        ))
+        .layer(DefaultBodyLimit::max(state.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.config.compression.layer())
+        .layer(cors)
 }
@@ -0,0 +1,139 @@
+//! Email from the attendance service, for now only event reports to the
+//! committee. Mail goes through the SMTP relay, or to the log when
+//! `REPORT_EMAIL_BACKEND=log`.
+
+use lettre::{
+    message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::{error::Error, fmt, str::FromStr};
+
+use crate::config::Config;
+
+/// How email is sent (`REPORT_EMAIL_BACKEND`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MailBackend {
+    /// Send through the SMTP relay (`SMTP_*`)
+    Smtp,
+    /// Log emails instead of sending them (local development)
+    #[default]
+    Log,
+}
+
+impl FromStr for MailBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "smtp" => Ok(MailBackend::Smtp),
+            "log" => Ok(MailBackend::Log),
+            other => Err(format!(
+                "Invalid REPORT_EMAIL_BACKEND '{}': expected smtp or log",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MailBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailBackend::Smtp => write!(f, "smtp"),
+            MailBackend::Log => write!(f, "log"),
+        }
+    }
+}
+
+/// Parse a comma-separated list of addresses (`REPORT_RECIPIENTS`)
+pub fn parse_recipients(list: &str) -> Result<Vec<Mailbox>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address.parse().map_err(|_| {
+                format!(
+                    "Invalid REPORT_RECIPIENTS: '{}' is not an email address",
+                    address
+                )
+            })
+        })
+        .collect()
+}
+
+pub struct MailAttachment {
+    pub filename: String,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+pub struct Mail {
+    pub to: Vec<Mailbox>,
+    pub subject: String,
+    pub body: String,
+    pub attachments: Vec<MailAttachment>,
+}
+
+#[derive(Clone)]
+pub enum Mailer {
+    Smtp {
+        transport: AsyncSmtpTransport<Tokio1Executor>,
+        from: Mailbox,
+    },
+    Log,
+}
+
+impl Mailer {
+    /// Build the mailer selected by `REPORT_EMAIL_BACKEND`
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        Ok(match config.report_email_backend {
+            MailBackend::Smtp => Mailer::Smtp {
+                transport: config.smtp.transport()?,
+                from: config.smtp.sender()?,
+            },
+            MailBackend::Log => Mailer::Log,
+        })
+    }
+
+    pub async fn send(&self, mail: Mail) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match self {
+            Mailer::Smtp { transport, from } => {
+                let mut message = Message::builder().from(from.clone()).subject(mail.subject);
+                for to in mail.to {
+                    message = message.to(to);
+                }
+
+                let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(mail.body));
+                for attachment in mail.attachments {
+                    parts = parts.singlepart(Attachment::new(attachment.filename).body(
+                        attachment.body,
+                        ContentType::parse(attachment.content_type)?,
+                    ));
+                }
+
+                transport.send(message.multipart(parts)?).await?;
+            }
+            Mailer::Log => {
+                let to = mail
+                    .to
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let attachments = mail
+                    .attachments
+                    .iter()
+                    .map(|attachment| attachment.filename.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                tracing::info!(
+                    "Email not sent (REPORT_EMAIL_BACKEND=log)\nTo: {}\nSubject: {}\nAttachments: {}\n\n{}",
+                    to,
+                    mail.subject,
+                    attachments,
+                    mail.body
+                );
+            }
+        }
+        Ok(())
+    }
+}
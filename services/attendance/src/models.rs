@@ -1,4 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use common::{PeriodCount, Role};
+
+use crate::check_in::Geofence;
+use crate::event_report::ReportFormat;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
@@ -7,6 +11,9 @@ use validator::Validate;
 // Database Models
 // ============================================================================
 
+/// Length assumed for events that do not set one
+const EVENT_DURATION_MINUTES: i64 = 180;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Event {
     pub id: Uuid,
@@ -17,10 +24,42 @@ pub struct Event {
     pub location: Option<String>,
     pub created_by: Uuid,
     pub is_locked: bool,
+    /// Seats available; None means no limit
+    pub max_participants: Option<i32>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub checkin_radius_m: Option<i32>,
+    pub category_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    /// None assumes the default length
+    pub duration_minutes: Option<i32>,
+    /// Hours ahead the reminder is posted; None uses the default
+    pub reminder_lead_hours: Option<i32>,
+    /// Hours ahead available members are reminded; None uses the default
+    pub reminder_schedule: Option<Vec<i32>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Event {
+    /// Where members must be to check themselves in, if anywhere
+    pub fn geofence(&self) -> Option<Geofence> {
+        Some(Geofence {
+            latitude: self.latitude?,
+            longitude: self.longitude?,
+            radius_m: self.checkin_radius_m?,
+        })
+    }
+
+    pub fn ends_at(&self) -> DateTime<Utc> {
+        self.event_date
+            + Duration::minutes(
+                self.duration_minutes
+                    .map_or(EVENT_DURATION_MINUTES, i64::from),
+            )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct AttendanceRecord {
     pub id: Uuid,
@@ -30,6 +69,12 @@ pub struct AttendanceRecord {
     pub is_checked_in: bool,
     pub checked_in_by: Option<Uuid>,
     pub checked_in_at: Option<DateTime<Utc>>,
+    /// Set while the member waits for a seat; they are not yet available
+    pub waitlisted_at: Option<DateTime<Utc>>,
+    /// Distance from the venue reported by a self check-in
+    pub checkin_distance_m: Option<f64>,
+    /// Self check-in from outside the geofence, awaiting admin review
+    pub checkin_flagged: bool,
     pub availability_set_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -46,11 +91,41 @@ pub struct AttendanceRecordWithUser {
     pub is_checked_in: bool,
     pub checked_in_by: Option<Uuid>,
     pub checked_in_at: Option<DateTime<Utc>>,
+    /// Set while the member waits for a seat; they are not yet available
+    pub waitlisted_at: Option<DateTime<Utc>>,
+    /// Distance from the venue reported by a self check-in
+    pub checkin_distance_m: Option<f64>,
+    /// Self check-in from outside the geofence, awaiting admin review
+    pub checkin_flagged: bool,
     pub availability_set_at: DateTime<Utc>,
+    /// unpaid, pending, paid or waived; None when the event is free or the
+    /// query does not look it up
+    #[sqlx(default)]
+    pub payment_status: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A reminder sent to a member who marked themselves available
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MemberReminder {
+    pub event_id: Uuid,
+    pub title: String,
+    pub event_date: DateTime<Utc>,
+    pub location: Option<String>,
+    pub hours_before: i32,
+    pub sent_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    #[serde(skip)]
+    pub username: String,
+    #[serde(skip)]
+    pub email: String,
+    /// Whether the member wants reminders by email
+    #[serde(skip)]
+    pub wants_email: bool,
+}
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -99,6 +174,32 @@ pub struct CreateEventRequest {
     pub event_date: DateTime<Utc>,
     #[validate(length(max = 255))]
     pub location: Option<String>,
+    /// Limit on seats; members marking themselves available once it is
+    /// reached join a waitlist
+    #[validate(range(min = 1))]
+    pub max_participants: Option<i32>,
+    /// Where members must be to check themselves in
+    #[validate(nested)]
+    pub geofence: Option<Geofence>,
+    pub category_id: Option<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[validate(range(min = 1, max = 1440))]
+    pub duration_minutes: Option<i32>,
+    /// Hours before the start to post the chat reminder
+    #[validate(range(min = 1, max = 336))]
+    pub reminder_lead_hours: Option<i32>,
+    /// Hours before the start to remind available members, replacing the
+    /// default schedule; empty sends none
+    pub reminder_schedule: Option<Vec<i32>>,
+}
+
+/// Query string of a schedule import
+#[derive(Debug, Deserialize)]
+pub struct ImportScheduleQuery {
+    /// Check the schedule and report what would be created, creating nothing
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -110,6 +211,31 @@ pub struct UpdateEventRequest {
     pub event_date: Option<DateTime<Utc>>,
     #[validate(length(max = 255))]
     pub location: Option<String>,
+    /// New limit on seats; 0 removes the limit
+    #[validate(range(min = 0))]
+    pub max_participants: Option<i32>,
+    #[validate(nested)]
+    pub geofence: Option<Geofence>,
+    /// Let members check in from anywhere again
+    #[serde(default)]
+    pub remove_geofence: bool,
+    pub category_id: Option<Uuid>,
+    /// Leave the event uncategorised
+    #[serde(default)]
+    pub remove_category: bool,
+    /// Replaces every tag on the event
+    pub tags: Option<Vec<String>>,
+    /// New length; 0 goes back to the default
+    #[validate(range(min = 0, max = 1440))]
+    pub duration_minutes: Option<i32>,
+    /// New reminder lead time; 0 goes back to the default
+    #[validate(range(min = 0, max = 336))]
+    pub reminder_lead_hours: Option<i32>,
+    /// Replaces the member reminder schedule; empty sends none
+    pub reminder_schedule: Option<Vec<i32>>,
+    /// Go back to the default member reminder schedule
+    #[serde(default)]
+    pub reset_reminder_schedule: bool,
 }
 
 // Event Responses
@@ -123,6 +249,21 @@ pub struct EventResponse {
     pub location: Option<String>,
     pub created_by: Uuid,
     pub is_locked: bool,
+    /// Seats available; None means no limit
+    pub max_participants: Option<i32>,
+    pub geofence: Option<Geofence>,
+    pub category_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub duration_minutes: Option<i32>,
+    pub reminder_lead_hours: Option<i32>,
+    pub reminder_schedule: Option<Vec<i32>>,
+    /// Conveners and volunteers; only filled in when a single event is
+    /// fetched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staff: Option<Vec<EventStaffMember>>,
+    /// Filled in for a single event and its attendance, if it has a fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<EventFee>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -130,6 +271,7 @@ pub struct EventResponse {
 impl From<Event> for EventResponse {
     fn from(event: Event) -> Self {
         EventResponse {
+            geofence: event.geofence(),
             id: event.id,
             title: event.title,
             description: event.description,
@@ -138,6 +280,14 @@ impl From<Event> for EventResponse {
             location: event.location,
             created_by: event.created_by,
             is_locked: event.is_locked,
+            max_participants: event.max_participants,
+            category_id: event.category_id,
+            tags: event.tags,
+            duration_minutes: event.duration_minutes,
+            reminder_lead_hours: event.reminder_lead_hours,
+            reminder_schedule: event.reminder_schedule,
+            staff: None,
+            fee: None,
             created_at: event.created_at,
             updated_at: event.updated_at,
         }
@@ -159,12 +309,119 @@ pub struct SetAvailabilityRequest {
     pub is_available: bool,
 }
 
+/// Availability for several events at once, e.g. every Tuesday this month
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkSetAvailabilityRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub event_ids: Vec<Uuid>,
+    pub is_available: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CheckInRequest {
     pub user_id: Uuid,
     pub is_checked_in: bool,
 }
 
+/// Register a guest at the desk. Guests who leave an email are invited to
+/// sign up.
+#[derive(Debug, Deserialize, Validate)]
+pub struct WalkInRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    pub institution_id: Option<Uuid>,
+    #[validate(email)]
+    pub email: Option<String>,
+}
+
+/// A guest registered at the desk, in the event's allocation pool
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WalkIn {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub institution_id: Option<Uuid>,
+    pub institution_name: Option<String>,
+    pub email: Option<String>,
+    pub invited_at: Option<DateTime<Utc>>,
+    pub registered_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Make a member a manager of an event (admin only)
+#[derive(Debug, Deserialize)]
+pub struct AddEventManagerRequest {
+    pub user_id: Uuid,
+}
+
+/// A member who runs an event's availability, check-in and allocations
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventManager {
+    pub user_id: Uuid,
+    pub username: String,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Who a member is on an event's staff. Either may check members in at
+/// the event; the role says who to go to on the day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StaffRole {
+    Convener,
+    Volunteer,
+}
+
+impl StaffRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StaffRole::Convener => "convener",
+            StaffRole::Volunteer => "volunteer",
+        }
+    }
+}
+
+/// Put a member on an event's staff, or change their role (admin only)
+#[derive(Debug, Deserialize)]
+pub struct AssignStaffRequest {
+    pub user_id: Uuid,
+    pub role: StaffRole,
+}
+
+/// A convener or volunteer running an event
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventStaffMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub assigned_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A member just put on an event's staff, with what is needed to tell them
+#[derive(Debug, sqlx::FromRow)]
+pub struct StaffAssignment {
+    #[sqlx(flatten)]
+    pub staff: EventStaffMember,
+    pub email: String,
+    pub wants_email: bool,
+}
+
+/// A member checking themselves in; coordinates are needed when the event
+/// has a geofence
+#[derive(Debug, Deserialize, Validate)]
+pub struct SelfCheckInRequest {
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: Option<f64>,
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FlaggedCheckInQuery {
+    pub event_id: Option<Uuid>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RevokeAvailabilityRequest {
     pub user_id: Uuid,
@@ -187,7 +444,13 @@ pub struct AttendanceResponse {
     pub is_checked_in: bool,
     pub checked_in_by: Option<Uuid>,
     pub checked_in_at: Option<DateTime<Utc>>,
+    pub waitlisted_at: Option<DateTime<Utc>>,
+    pub checkin_distance_m: Option<f64>,
+    pub checkin_flagged: bool,
     pub availability_set_at: DateTime<Utc>,
+    /// Only filled in on an event's attendance list, for events with a fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_status: Option<String>,
 }
 
 impl From<AttendanceRecord> for AttendanceResponse {
@@ -201,11 +464,22 @@ impl From<AttendanceRecord> for AttendanceResponse {
             is_checked_in: record.is_checked_in,
             checked_in_by: record.checked_in_by,
             checked_in_at: record.checked_in_at,
+            waitlisted_at: record.waitlisted_at,
+            checkin_distance_m: record.checkin_distance_m,
+            checkin_flagged: record.checkin_flagged,
             availability_set_at: record.availability_set_at,
+            payment_status: None,
         }
     }
 }
 
+/// An event left unchanged by a bulk availability update, and why
+#[derive(Debug, Serialize)]
+pub struct SkippedEvent {
+    pub event_id: Uuid,
+    pub reason: &'static str,
+}
+
 impl From<AttendanceRecordWithUser> for AttendanceResponse {
     fn from(record: AttendanceRecordWithUser) -> Self {
         AttendanceResponse {
@@ -217,7 +491,11 @@ impl From<AttendanceRecordWithUser> for AttendanceResponse {
             is_checked_in: record.is_checked_in,
             checked_in_by: record.checked_in_by,
             checked_in_at: record.checked_in_at,
+            waitlisted_at: record.waitlisted_at,
+            checkin_distance_m: record.checkin_distance_m,
+            checkin_flagged: record.checkin_flagged,
             availability_set_at: record.availability_set_at,
+            payment_status: record.payment_status,
         }
     }
 }
@@ -243,17 +521,10 @@ pub struct EventListParams {
     pub per_page: Option<i32>,
     pub event_type: Option<String>,
     pub upcoming_only: Option<bool>,
-}
-
-// JWT Claims (for validating tokens from auth service)
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String, // user_id
-    pub username: String,
-    pub exp: i64,
-    pub iat: i64,
-    pub jti: String,
-    pub token_type: String,
+    /// Events in this category or any category below it
+    pub category_id: Option<Uuid>,
+    /// Comma-separated; events must carry every tag
+    pub tags: Option<String>,
 }
 
 // Lock/Unlock request
@@ -266,8 +537,18 @@ pub struct LockEventRequest {
 // Attendance Matrix/Dashboard Types
 // ============================================================================
 
+/// Matrix query: one page of members, over the events dated from `from`
+/// (inclusive) to `to` (exclusive)
+#[derive(Debug, Deserialize)]
+pub struct AttendanceMatrixParams {
+    pub page: Option<i32>,
+    pub per_page: Option<i32>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
 /// Summary info for an event in the matrix
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct EventSummary {
     pub id: Uuid,
     pub title: String,
@@ -279,12 +560,14 @@ pub struct EventSummary {
 }
 
 /// User info with their attendance stats
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct UserAttendanceSummary {
     pub user_id: Uuid,
     pub username: String,
     pub events_available: i64,
     pub events_checked_in: i64,
+    /// Locked events missed with an approved excuse; left out of both rates
+    pub events_excused: i64,
     pub total_events: i64,
     pub availability_rate: f64,
     pub attendance_rate: f64,
@@ -305,6 +588,7 @@ pub enum AttendanceCellStatus {
     Available,
     CheckedIn,
     Unavailable,
+    Excused,
 }
 
 /// Row in the attendance matrix (one per user)
@@ -336,10 +620,950 @@ pub struct EventTypeStats {
     pub avg_attendance: f64,
 }
 
-/// Full attendance matrix response
+/// Attendance counts across every member, for the aggregate statistics
+#[derive(Debug, sqlx::FromRow)]
+pub struct MatrixTotals {
+    pub total_events: i64,
+    pub total_users: i64,
+    pub events_available: i64,
+    pub events_checked_in: i64,
+    pub events_excused: i64,
+}
+
+/// Attendance matrix response; rows are paged, most reliable members first
 #[derive(Debug, Serialize)]
 pub struct AttendanceMatrixResponse {
     pub events: Vec<EventSummary>,
     pub rows: Vec<AttendanceMatrixRow>,
     pub aggregate_stats: AggregateStats,
+    pub page: i32,
+    pub per_page: i32,
+    pub total_pages: i64,
+}
+
+/// Event numbers for the admin stats report
+#[derive(Debug, Serialize)]
+pub struct EventStats {
+    pub total_events: i64,
+    pub upcoming_events: i64,
+    /// Events held in each recent month, by event date
+    pub events_per_month: Vec<PeriodCount>,
+}
+
+/// How many attendees share a dietary requirement
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DietaryCount {
+    pub requirement: String,
+    pub count: i64,
+}
+
+/// An attendee's accessibility requirement, named so the venue can be
+/// arranged for them
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AccessibilityNeed {
+    pub user_id: Uuid,
+    pub username: String,
+    pub requirement: String,
+}
+
+/// Catering and access needs of the members attending an event, for the
+/// welfare role
+#[derive(Debug, Serialize)]
+pub struct RequirementsReport {
+    pub event_id: Uuid,
+    pub title: String,
+    /// Members available for or checked in to the event
+    pub attendees: i64,
+    /// Dietary requirements, most common first; attendees not listed have
+    /// none
+    pub dietary: Vec<DietaryCount>,
+    pub accessibility: Vec<AccessibilityNeed>,
+}
+
+/// One member's attendance across all events
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AttendanceSummary {
+    pub events_available: i64,
+    pub events_checked_in: i64,
+    /// Missed events covered by an approved excuse
+    pub events_excused: i64,
+    pub last_checked_in_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// Conduct Report Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    Open,
+    Investigating,
+    Resolved,
+}
+
+impl ReportStatus {
+    /// Reports move forward through the workflow; a resolved report can be
+    /// reopened for further investigation
+    pub fn can_transition_to(self, next: ReportStatus) -> bool {
+        matches!(
+            (self, next),
+            (ReportStatus::Open, ReportStatus::Investigating)
+                | (ReportStatus::Open, ReportStatus::Resolved)
+                | (ReportStatus::Investigating, ReportStatus::Resolved)
+                | (ReportStatus::Resolved, ReportStatus::Investigating)
+        )
+    }
+}
+
+impl std::fmt::Display for ReportStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportStatus::Open => write!(f, "open"),
+            ReportStatus::Investigating => write!(f, "investigating"),
+            ReportStatus::Resolved => write!(f, "resolved"),
+        }
+    }
+}
+
+impl std::str::FromStr for ReportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(ReportStatus::Open),
+            "investigating" => Ok(ReportStatus::Investigating),
+            "resolved" => Ok(ReportStatus::Resolved),
+            _ => Err(format!("Invalid report status: {}", s)),
+        }
+    }
+}
+
+/// A code-of-conduct report. Only equity officers may see the full record.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ConductReport {
+    pub id: Uuid,
+    pub reporter_id: Option<Uuid>,
+    pub is_anonymous: bool,
+    pub event_id: Option<Uuid>,
+    pub match_id: Option<Uuid>,
+    pub description: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Report listing for equity officers; the description is only returned
+/// when a single report is opened, so that every read is audited
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ConductReportSummary {
+    pub id: Uuid,
+    pub is_anonymous: bool,
+    pub event_id: Option<Uuid>,
+    pub event_title: Option<String>,
+    pub match_id: Option<Uuid>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ReportAuditEntry {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub from_status: Option<String>,
+    pub to_status: Option<String>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReportRequest {
+    pub event_id: Option<Uuid>,
+    pub match_id: Option<Uuid>,
+    #[validate(length(
+        min = 1,
+        max = 10000,
+        message = "Description must be between 1 and 10000 characters"
+    ))]
+    pub description: String,
+    /// Leave no link between the report and the reporter
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportListQuery {
+    pub status: Option<ReportStatus>,
+    pub event_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateReportStatusRequest {
+    pub status: ReportStatus,
+    #[validate(length(max = 10000))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddReportNoteRequest {
+    #[validate(length(min = 1, max = 10000))]
+    pub note: String,
+}
+
+// ============================================================================
+// Category and Tag Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    /// Names from the top-level category down, e.g. "Training / Workshops"
+    pub path: String,
+    /// 0 for top-level categories
+    pub depth: i32,
+    /// Events filed directly under this category
+    pub event_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateCategoryRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCategoryRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    pub parent_id: Option<Uuid>,
+    /// Make it a top-level category
+    #[serde(default)]
+    pub move_to_top: bool,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TagCount {
+    pub tag: String,
+    pub event_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetEventTagsRequest {
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameTagRequest {
+    /// New name; an existing tag of that name absorbs this one
+    pub to: String,
+}
+
+// ============================================================================
+// Event Template Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub event_type: String,
+    /// Time of day (UTC) the event starts
+    pub start_time: NaiveTime,
+    pub duration_minutes: Option<i32>,
+    pub location: Option<String>,
+    pub max_participants: Option<i32>,
+    pub category_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub reminder_lead_hours: Option<i32>,
+    pub reminder_schedule: Option<Vec<i32>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A series created with each event from a template
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TemplateSeries {
+    pub position: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub round_number: Option<i32>,
+    pub team_format: String,
+    pub allow_reply_speeches: bool,
+    pub is_break_round: bool,
+    pub is_training: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventTemplateResponse {
+    #[serde(flatten)]
+    pub template: EventTemplate,
+    pub series: Vec<TemplateSeries>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamFormat {
+    TwoTeam,
+    FourTeam,
+}
+
+impl std::fmt::Display for TeamFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TeamFormat::TwoTeam => write!(f, "two_team"),
+            TeamFormat::FourTeam => write!(f, "four_team"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct TemplateSeriesRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    pub description: Option<String>,
+    pub round_number: Option<i32>,
+    pub team_format: TeamFormat,
+    #[serde(default)]
+    pub allow_reply_speeches: bool,
+    #[serde(default)]
+    pub is_break_round: bool,
+    #[serde(default)]
+    pub is_training: bool,
+}
+
+/// Creates or replaces a template
+#[derive(Debug, Deserialize, Validate)]
+pub struct EventTemplateRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: String,
+    #[validate(length(min = 1, max = 255))]
+    pub title: String,
+    pub description: Option<String>,
+    pub event_type: EventType,
+    /// Time of day (UTC) the event starts, e.g. "18:30"
+    pub start_time: NaiveTime,
+    #[validate(range(min = 1, max = 1440))]
+    pub duration_minutes: Option<i32>,
+    #[validate(length(max = 255))]
+    pub location: Option<String>,
+    #[validate(range(min = 1))]
+    pub max_participants: Option<i32>,
+    pub category_id: Option<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[validate(range(min = 1, max = 336))]
+    pub reminder_lead_hours: Option<i32>,
+    /// Member reminder schedule for each event; None uses the default
+    pub reminder_schedule: Option<Vec<i32>>,
+    /// Series created with each event, in order
+    #[serde(default)]
+    #[validate(length(max = 50), nested)]
+    pub series: Vec<TemplateSeriesRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    /// Day the event is held; it starts at the template's start time
+    pub date: NaiveDate,
+}
+
+// ============================================================================
+// Event Report Types
+// ============================================================================
+
+/// One member's response to an event and what became of it
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventReportRow {
+    pub user_id: Uuid,
+    pub username: String,
+    pub is_available: bool,
+    pub is_waitlisted: bool,
+    pub is_checked_in: bool,
+    pub checked_in_at: Option<DateTime<Utc>>,
+    pub checkin_flagged: bool,
+    /// Status of the member's absence excuse, if they submitted one
+    pub excuse_status: Option<String>,
+    /// unpaid, pending, paid or waived; None when the event is free
+    pub payment_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventReportQuery {
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+// ============================================================================
+// Absence Excuse Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExcuseStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl std::fmt::Display for ExcuseStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExcuseStatus::Pending => write!(f, "pending"),
+            ExcuseStatus::Approved => write!(f, "approved"),
+            ExcuseStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExcuseStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(ExcuseStatus::Pending),
+            "approved" => Ok(ExcuseStatus::Approved),
+            "rejected" => Ok(ExcuseStatus::Rejected),
+            _ => Err(format!("Invalid excuse status: {}", s)),
+        }
+    }
+}
+
+/// A member's explanation for missing a locked event
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AbsenceExcuse {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub reason: String,
+    pub attachment_filename: Option<String>,
+    pub attachment_content_type: Option<String>,
+    #[serde(skip_serializing)]
+    pub attachment_key: Option<String>,
+    pub attachment_size_bytes: Option<i64>,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AbsenceExcuse {
+    pub fn is_pending(&self) -> bool {
+        self.status == ExcuseStatus::Pending.to_string()
+    }
+}
+
+/// Excuse listing with the member and event it concerns
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AbsenceExcuseSummary {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub event_title: String,
+    pub event_date: DateTime<Utc>,
+    pub user_id: Uuid,
+    pub username: String,
+    pub reason: String,
+    pub attachment_filename: Option<String>,
+    pub status: String,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SubmitExcuseRequest {
+    #[validate(length(
+        min = 1,
+        max = 5000,
+        message = "Reason must be between 1 and 5000 characters"
+    ))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ExcuseAttachmentParams {
+    #[validate(length(min = 1, max = 255))]
+    pub filename: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExcuseListQuery {
+    pub status: Option<ExcuseStatus>,
+    pub event_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReviewExcuseRequest {
+    /// approved or rejected
+    pub status: ExcuseStatus,
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
+
+// ============================================================================
+// Announcement Types
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<Uuid>,
+    pub audience_role: Option<String>,
+    pub is_important: bool,
+    pub publish_at: DateTime<Utc>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An announcement as seen by a member
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AnnouncementForUser {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<Uuid>,
+    pub event_title: Option<String>,
+    pub audience_role: Option<String>,
+    pub is_important: bool,
+    pub publish_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// An announcement as seen by admins, including scheduled ones
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AnnouncementWithStats {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub event_id: Option<Uuid>,
+    pub event_title: Option<String>,
+    pub audience_role: Option<String>,
+    pub is_important: bool,
+    pub publish_at: DateTime<Utc>,
+    pub is_published: bool,
+    pub read_count: i64,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAnnouncementRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Title must be between 1 and 255 characters"
+    ))]
+    pub title: String,
+    #[validate(length(
+        min = 1,
+        max = 20000,
+        message = "Body must be between 1 and 20000 characters"
+    ))]
+    pub body: String,
+    /// Only show to members available for this event
+    pub event_id: Option<Uuid>,
+    /// Only show to holders of this role
+    pub audience_role: Option<Role>,
+    #[serde(default)]
+    pub is_important: bool,
+    /// Hide until this time; published immediately when omitted
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAnnouncementRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 20000))]
+    pub body: Option<String>,
+    pub is_important: Option<bool>,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementListParams {
+    pub page: Option<i32>,
+    pub per_page: Option<i32>,
+    pub unread_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnnouncementListResponse {
+    pub announcements: Vec<AnnouncementForUser>,
+    pub unread_count: i64,
+    pub total: i64,
+    pub page: i32,
+    pub per_page: i32,
+    pub total_pages: i64,
+}
+
+// ============================================================================
+// Fee Types
+// ============================================================================
+
+/// What members pay to attend an event; events without one are free
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventFee {
+    pub event_id: Uuid,
+    /// In the currency's smallest unit (paisa, cents)
+    pub amount_minor: i32,
+    pub currency: String,
+    /// Shown on the payment page; the event title is used without one
+    pub description: Option<String>,
+    pub set_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetEventFeeRequest {
+    #[validate(range(min = 1))]
+    pub amount_minor: i32,
+    /// Defaults to `FEE_CURRENCY`
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    #[validate(length(max = 255))]
+    pub description: Option<String>,
+}
+
+/// Where a member stands with an event's fee
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    Unpaid,
+    /// An online payment was started but Stripe has not confirmed it
+    Pending,
+    Paid,
+    /// Excused from paying by a treasurer
+    Waived,
+}
+
+impl PaymentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentStatus::Unpaid => "unpaid",
+            PaymentStatus::Pending => "pending",
+            PaymentStatus::Paid => "paid",
+            PaymentStatus::Waived => "waived",
+        }
+    }
+}
+
+/// A treasurer recording a payment taken by hand, a waiver, or undoing
+/// either
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecordPaymentRequest {
+    pub status: PaymentStatus,
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+/// An attendee, or anyone who has paid, and their payment
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MemberPayment {
+    pub user_id: Uuid,
+    pub username: String,
+    pub is_available: bool,
+    pub is_checked_in: bool,
+    pub status: String,
+    /// manual or stripe
+    pub method: Option<String>,
+    pub amount_minor: Option<i32>,
+    pub currency: Option<String>,
+    pub note: Option<String>,
+    pub recorded_by: Option<Uuid>,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PaymentTotals {
+    pub paid: i64,
+    pub waived: i64,
+    pub pending: i64,
+    pub unpaid: i64,
+    /// Sum of payments received, in the fee's smallest unit
+    pub collected_minor: i64,
+}
+
+impl PaymentTotals {
+    pub fn tally(payments: &[MemberPayment]) -> Self {
+        let mut totals = PaymentTotals::default();
+        for payment in payments {
+            match payment.status.as_str() {
+                "paid" => {
+                    totals.paid += 1;
+                    totals.collected_minor += i64::from(payment.amount_minor.unwrap_or(0));
+                }
+                "waived" => totals.waived += 1,
+                "pending" => totals.pending += 1,
+                _ => totals.unpaid += 1,
+            }
+        }
+        totals
+    }
+}
+
+/// Everyone's payment for an event, for the treasurer to reconcile
+#[derive(Debug, Serialize)]
+pub struct EventPaymentsResponse {
+    pub event_id: Uuid,
+    pub title: String,
+    pub fee: EventFee,
+    pub payments: Vec<MemberPayment>,
+    pub totals: PaymentTotals,
+}
+
+// ============================================================================
+// Expense Types
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseCategory {
+    Venue,
+    Travel,
+    Food,
+    Printing,
+    Prizes,
+    Equipment,
+    Other,
+}
+
+impl ExpenseCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpenseCategory::Venue => "venue",
+            ExpenseCategory::Travel => "travel",
+            ExpenseCategory::Food => "food",
+            ExpenseCategory::Printing => "printing",
+            ExpenseCategory::Prizes => "prizes",
+            ExpenseCategory::Equipment => "equipment",
+            ExpenseCategory::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ExpenseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpenseStatus::Pending => "pending",
+            ExpenseStatus::Approved => "approved",
+            ExpenseStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// Money spent on an event, awaiting or past a treasurer's approval
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventExpense {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub category: String,
+    pub description: String,
+    /// In the currency's smallest unit, like fees
+    pub amount_minor: i32,
+    pub currency: String,
+    pub spent_on: NaiveDate,
+    pub receipt_filename: Option<String>,
+    pub receipt_content_type: Option<String>,
+    #[serde(skip_serializing)]
+    pub receipt_key: Option<String>,
+    pub receipt_size_bytes: Option<i64>,
+    pub status: String,
+    pub submitted_by: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EventExpense {
+    pub fn is_pending(&self) -> bool {
+        self.status == ExpenseStatus::Pending.as_str()
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateExpenseRequest {
+    pub category: ExpenseCategory,
+    #[validate(length(min = 1, max = 500))]
+    pub description: String,
+    #[validate(range(min = 1))]
+    pub amount_minor: i32,
+    /// Defaults to `FEE_CURRENCY`
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    pub spent_on: NaiveDate,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExpenseListQuery {
+    pub status: Option<ExpenseStatus>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReceiptParams {
+    #[validate(length(min = 1, max = 255))]
+    pub filename: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReviewExpenseRequest {
+    /// approved or rejected
+    pub status: ExpenseStatus,
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
+
+/// Money in and out of an event in one currency. The balance sets approved
+/// expenses against fees collected; pending expenses are left out of it.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EventFinances {
+    pub event_id: Uuid,
+    pub title: String,
+    pub event_date: DateTime<Utc>,
+    pub currency: String,
+    pub collected_minor: i64,
+    pub approved_minor: i64,
+    pub pending_minor: i64,
+    pub balance_minor: i64,
+}
+
+/// An event's spending in one category and currency
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExpenseCategoryTotal {
+    pub category: String,
+    pub currency: String,
+    pub expenses: i64,
+    pub approved_minor: i64,
+    pub pending_minor: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventFinanceSummary {
+    pub event_id: Uuid,
+    pub title: String,
+    /// One entry per currency money moved in
+    pub totals: Vec<EventFinances>,
+    pub by_category: Vec<ExpenseCategoryTotal>,
+}
+
+/// Events between two dates, by event date; either end may be left open
+#[derive(Debug, Deserialize)]
+pub struct FinanceQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+// ============================================================================
+// Equipment Types
+// ============================================================================
+
+/// A piece of the society's kit, with whoever has it now
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EquipmentItem {
+    pub id: Uuid,
+    pub name: String,
+    pub kind: String,
+    pub asset_tag: Option<String>,
+    pub notes: Option<String>,
+    pub retired_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// The open checkout, if the item is out
+    pub checkout_id: Option<Uuid>,
+    pub holder_id: Option<Uuid>,
+    pub holder_username: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl EquipmentItem {
+    pub fn is_out(&self) -> bool {
+        self.checkout_id.is_some()
+    }
+}
+
+/// Create an item, or replace its details
+#[derive(Debug, Deserialize, Validate)]
+pub struct EquipmentRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// What sort of thing it is, such as "timer" or "banner"
+    #[validate(length(min = 1, max = 50))]
+    pub kind: String,
+    #[validate(length(min = 1, max = 50))]
+    pub asset_tag: Option<String>,
+    #[validate(length(max = 2000))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EquipmentListQuery {
+    #[serde(default)]
+    pub include_retired: bool,
+}
+
+/// Lend an item to a member for an event
+#[derive(Debug, Deserialize, Validate)]
+pub struct CheckoutEquipmentRequest {
+    pub user_id: Uuid,
+    pub event_id: Uuid,
+    /// Defaults to `EQUIPMENT_RETURN_HOURS` after the event ends
+    pub due_at: Option<DateTime<Utc>>,
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReturnEquipmentRequest {
+    /// Condition on return, missing parts and so on
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
+
+/// One loan of an item, open until it is returned
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EquipmentCheckout {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub asset_tag: Option<String>,
+    pub user_id: Uuid,
+    pub username: String,
+    pub event_id: Option<Uuid>,
+    pub event_title: Option<String>,
+    pub checked_out_by: Option<Uuid>,
+    pub checked_out_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+    pub note: Option<String>,
+    pub returned_at: Option<DateTime<Utc>>,
+    pub returned_to: Option<Uuid>,
+    pub return_note: Option<String>,
+    /// Still out after it was due back
+    pub is_overdue: bool,
 }
@@ -0,0 +1,111 @@
+//! Domain events the attendance service publishes through the outbox (see
+//! `common::outbox`)
+
+use std::sync::Arc;
+
+use common::outbox::{OutboxEvent, Relay};
+use common::{Notification, NotificationKind};
+use serde::Deserialize;
+
+use crate::AppState;
+
+pub const SERVICE: &str = "attendance";
+
+/// A member was checked in to an event, by an admin or by themselves
+pub const USER_CHECKED_IN: &str = "user_checked_in";
+
+/// `user_checked_in` payload, built in SQL by the check-in queries
+#[derive(Debug, Deserialize)]
+struct UserCheckedIn {
+    event_title: String,
+    username: Option<String>,
+    checked_in_by_username: Option<String>,
+    self_check_in: bool,
+    distance_m: Option<f64>,
+    flagged: bool,
+}
+
+/// Start delivering the service's events in the background
+pub fn spawn(state: Arc<AppState>) {
+    Relay::new(state.db.pool().clone(), SERVICE, &state.config.outbox)
+        .with_chat(state.notifier.clone(), notification)
+        .with_bus(state.events.clone())
+        .with_features(state.features.clone())
+        .spawn();
+}
+
+fn notification(event: &OutboxEvent) -> Option<Notification> {
+    if event.kind != USER_CHECKED_IN {
+        return None;
+    }
+    let check_in: UserCheckedIn = match serde_json::from_value(event.payload.clone()) {
+        Ok(check_in) => check_in,
+        Err(e) => {
+            tracing::warn!("Unreadable {} event {}: {}", event.kind, event.id, e);
+            return None;
+        }
+    };
+    Some(user_checked_in(&check_in))
+}
+
+fn user_checked_in(check_in: &UserCheckedIn) -> Notification {
+    let by = match (&check_in.checked_in_by_username, check_in.self_check_in) {
+        (_, true) => "Self check-in".to_string(),
+        (Some(admin), false) => admin.clone(),
+        (None, false) => "An admin".to_string(),
+    };
+
+    let mut notification = Notification::new(
+        NotificationKind::UserCheckedIn,
+        format!(
+            "Checked in: {}",
+            check_in.username.as_deref().unwrap_or("A member")
+        ),
+    )
+    .description(check_in.event_title.clone())
+    .inline_field("By", by);
+
+    if check_in.flagged {
+        let distance = check_in
+            .distance_m
+            .map(|d| format!("{:.0} m from the venue", d))
+            .unwrap_or_else(|| "Unknown distance".to_string());
+        notification = notification.inline_field("Flagged for review", distance);
+    }
+    notification
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_user_checked_in_notification() {
+        let event = OutboxEvent {
+            id: Uuid::nil(),
+            service: SERVICE.to_string(),
+            kind: USER_CHECKED_IN.to_string(),
+            payload: json!({
+                "event_title": "Weekly practice",
+                "username": "sana",
+                "checked_in_by": Uuid::nil(),
+                "checked_in_by_username": "sana",
+                "self_check_in": true,
+                "distance_m": 412.6,
+                "flagged": true
+            }),
+            created_at: Utc::now(),
+            attempts: 0,
+            delivered_to: Vec::new(),
+        };
+
+        let notification = notification(&event).unwrap();
+        assert_eq!(notification.title, "Checked in: sana");
+        assert_eq!(notification.description, "Weekly practice");
+        assert_eq!(notification.fields[0].value, "Self check-in");
+        assert_eq!(notification.fields[1].value, "413 m from the venue");
+    }
+}
@@ -0,0 +1,219 @@
+//! Online payment of event fees through Stripe Checkout. Members are sent to
+//! a Stripe-hosted page; Stripe calls the webhook when they have paid, and
+//! the payment is recorded then. Without `STRIPE_SECRET_KEY` fees can only
+//! be marked paid by a treasurer.
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::models::{Event, EventFee};
+
+const STRIPE_API: &str = "https://api.stripe.com/v1";
+
+/// How old a webhook signature may be before the call is refused as a replay
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// `STRIPE_*` settings; online payment is off unless both are set
+#[derive(Debug, Clone)]
+pub struct StripeSettings {
+    pub secret_key: String,
+    pub webhook_secret: String,
+}
+
+#[derive(Debug)]
+pub struct PaymentError(String);
+
+impl fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+impl From<reqwest::Error> for PaymentError {
+    fn from(e: reqwest::Error) -> Self {
+        PaymentError(format!("Stripe request failed: {}", e))
+    }
+}
+
+/// A Stripe-hosted payment page
+#[derive(Debug, Deserialize)]
+pub struct CheckoutSession {
+    pub id: String,
+    pub url: String,
+}
+
+/// The parts of a webhook call the service acts on
+#[derive(Debug, Deserialize)]
+pub struct WebhookEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub data: WebhookData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookData {
+    pub object: WebhookObject,
+}
+
+/// A checkout session, for the `checkout.session.*` events
+#[derive(Debug, Deserialize)]
+pub struct WebhookObject {
+    pub id: String,
+    pub payment_status: Option<String>,
+    pub amount_total: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct Stripe {
+    client: reqwest::Client,
+    secret_key: String,
+    webhook_secret: String,
+}
+
+impl Stripe {
+    pub fn new(settings: &StripeSettings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            secret_key: settings.secret_key.clone(),
+            webhook_secret: settings.webhook_secret.clone(),
+        }
+    }
+
+    /// Open a payment page for a member's fee. Members come back to the
+    /// event page whether or not they pay.
+    pub async fn create_checkout(
+        &self,
+        event: &Event,
+        fee: &EventFee,
+        user_id: Uuid,
+        frontend_url: &str,
+    ) -> Result<CheckoutSession, PaymentError> {
+        let event_url = format!("{}/events/{}", frontend_url, event.id);
+        let form = [
+            ("mode", "payment".to_string()),
+            ("success_url", format!("{}?payment=success", event_url)),
+            ("cancel_url", format!("{}?payment=cancelled", event_url)),
+            ("client_reference_id", user_id.to_string()),
+            ("line_items[0][quantity]", "1".to_string()),
+            (
+                "line_items[0][price_data][currency]",
+                fee.currency.to_ascii_lowercase(),
+            ),
+            (
+                "line_items[0][price_data][unit_amount]",
+                fee.amount_minor.to_string(),
+            ),
+            (
+                "line_items[0][price_data][product_data][name]",
+                fee.description
+                    .clone()
+                    .unwrap_or_else(|| event.title.clone()),
+            ),
+            ("metadata[event_id]", event.id.to_string()),
+            ("metadata[user_id]", user_id.to_string()),
+        ];
+
+        let response = self
+            .client
+            .post(format!("{}/checkout/sessions", STRIPE_API))
+            .bearer_auth(&self.secret_key)
+            .form(&form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(PaymentError(format!(
+                "Stripe refused the checkout ({}): {}",
+                status, body
+            )));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Check a webhook call came from Stripe and parse it. Returns None for
+    /// calls with a bad or stale signature.
+    pub fn parse_webhook(&self, signature: &str, payload: &[u8]) -> Option<WebhookEvent> {
+        if !verify_signature(
+            &self.webhook_secret,
+            signature,
+            payload,
+            Utc::now().timestamp(),
+        ) {
+            return None;
+        }
+        serde_json::from_slice(payload).ok()
+    }
+}
+
+/// Check a `Stripe-Signature` header (`t=<timestamp>,v1=<hex>,...`): an
+/// HMAC-SHA256 of `<timestamp>.<payload>` keyed by the webhook secret
+fn verify_signature(secret: &str, header: &str, payload: &[u8], now: i64) -> bool {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        return false;
+    };
+    if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+        return false;
+    }
+
+    signatures.iter().any(|signature| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+        mac.verify_slice(signature).is_ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNED_AT: i64 = 1_700_000_000;
+
+    fn sign(secret: &str, timestamp: i64, payload: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.", timestamp).as_bytes());
+        mac.update(payload);
+        let signature = hex::encode(mac.finalize().into_bytes());
+        format!("t={},v1={}", timestamp, signature)
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let payload = br#"{"type":"checkout.session.completed"}"#;
+        let header = sign("whsec_test", SIGNED_AT, payload);
+        let now = SIGNED_AT + 10;
+
+        assert!(verify_signature("whsec_test", &header, payload, now));
+        assert!(!verify_signature("whsec_other", &header, payload, now));
+        assert!(!verify_signature("whsec_test", &header, b"{}", now));
+        assert!(!verify_signature("whsec_test", "v1=00", payload, now));
+    }
+
+    #[test]
+    fn test_stale_signature_refused() {
+        let payload = b"{}";
+        let header = sign("whsec_test", SIGNED_AT, payload);
+        let now = SIGNED_AT + SIGNATURE_TOLERANCE_SECS + 1;
+
+        assert!(!verify_signature("whsec_test", &header, payload, now));
+    }
+}
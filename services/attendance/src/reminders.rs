@@ -0,0 +1,181 @@
+//! Reminders before events. A chat reminder is posted a day ahead unless
+//! the event sets its own lead time, and members who marked themselves
+//! available are reminded on a schedule of hours before the start
+//! (`MEMBER_REMINDER_HOURS`, or the event's own schedule), by email and in
+//! the app as their notification preferences allow.
+
+use common::{Notification, NotificationKind};
+use lettre::message::Mailbox;
+use std::sync::Arc;
+
+use crate::{
+    mailer::Mail,
+    models::{Event, MemberReminder},
+    AppState,
+};
+
+/// How far ahead of an event its reminder is posted, unless the event sets
+/// its own lead time
+const LEAD_TIME_HOURS: i32 = 24;
+/// How often to look for events entering the reminder window
+const POLL_INTERVAL_SECS: u64 = 300;
+/// Longest time before an event a member reminder may go out (two weeks)
+pub const MAX_REMINDER_HOURS: i32 = 336;
+/// Most reminders one event may send each member
+pub const MAX_REMINDERS: usize = 5;
+
+/// Start the reminder loop, on one replica at a time. Chat reminders go out
+/// only when enabled with a connector configured; member reminders always
+/// run, since events can set their own schedule even when the default is
+/// off.
+pub fn spawn(state: Arc<AppState>) {
+    let chat = state.notifier.is_enabled(NotificationKind::EventReminder);
+
+    common::leader::spawn(
+        state.db.pool().clone(),
+        "attendance.reminders",
+        std::time::Duration::from_secs(POLL_INTERVAL_SECS),
+        move || {
+            let state = state.clone();
+            async move {
+                if chat {
+                    if let Err(e) = send_due_reminders(&state).await {
+                        tracing::warn!("Failed to send event reminders: {}", e);
+                    }
+                }
+                if let Err(e) = send_member_reminders(&state).await {
+                    tracing::warn!("Failed to send member reminders: {}", e);
+                }
+            }
+        },
+    );
+}
+
+/// Check a schedule of hours before the start, returning it longest lead
+/// first without repeats
+pub fn normalize_schedule(hours: &[i32]) -> Result<Vec<i32>, String> {
+    if let Some(bad) = hours
+        .iter()
+        .find(|h| !(1..=MAX_REMINDER_HOURS).contains(*h))
+    {
+        return Err(format!(
+            "Reminder hours must be between 1 and {}, not {}",
+            MAX_REMINDER_HOURS, bad
+        ));
+    }
+
+    let mut schedule = hours.to_vec();
+    schedule.sort_unstable_by(|a, b| b.cmp(a));
+    schedule.dedup();
+    if schedule.len() > MAX_REMINDERS {
+        return Err(format!("At most {} reminders per event", MAX_REMINDERS));
+    }
+    Ok(schedule)
+}
+
+/// Parse a comma-separated schedule (`MEMBER_REMINDER_HOURS`); `none`
+/// turns default member reminders off
+pub fn parse_schedule(list: &str) -> Result<Vec<i32>, String> {
+    if list.trim().eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    let hours = list
+        .split(',')
+        .map(str::trim)
+        .filter(|hours| !hours.is_empty())
+        .map(|hours| {
+            hours.parse().map_err(|_| {
+                format!(
+                    "Invalid MEMBER_REMINDER_HOURS: '{}' is not a number of hours",
+                    hours
+                )
+            })
+        })
+        .collect::<Result<Vec<i32>, _>>()?;
+    normalize_schedule(&hours).map_err(|e| format!("Invalid MEMBER_REMINDER_HOURS: {}", e))
+}
+
+/// Claim every event starting within its lead time and post its reminder
+pub async fn send_due_reminders(state: &AppState) -> Result<usize, sqlx::Error> {
+    let events = state
+        .db
+        .claim_events_due_for_reminder(LEAD_TIME_HOURS)
+        .await?;
+
+    for event in &events {
+        let (available, _) = state.db.get_attendance_stats(event.id).await?;
+        state.notifier.notify(reminder(event, available));
+    }
+
+    Ok(events.len())
+}
+
+/// Claim every member reminder that has come due and email it to members
+/// who want reminders by email. Claimed reminders are also what members
+/// see in the app, so one that fails to email is still shown there.
+pub async fn send_member_reminders(state: &AppState) -> Result<usize, sqlx::Error> {
+    let reminders = state
+        .db
+        .claim_due_member_reminders(&state.config.member_reminder_hours)
+        .await?;
+
+    for reminder in reminders.iter().filter(|r| r.wants_email) {
+        let Ok(to) = reminder.email.parse() else {
+            tracing::warn!(
+                "Not emailing reminder to {}: invalid address",
+                reminder.user_id
+            );
+            continue;
+        };
+        if let Err(e) = state.mailer.send(member_mail(reminder, to)).await {
+            tracing::warn!("Failed to email reminder to {}: {}", reminder.user_id, e);
+        }
+    }
+
+    Ok(reminders.len())
+}
+
+fn member_mail(reminder: &MemberReminder, to: Mailbox) -> Mail {
+    let mut body = format!(
+        "Hi {},\n\n{} starts {}",
+        reminder.username,
+        reminder.title,
+        reminder.event_date.format("%a %d %b, %H:%M UTC")
+    );
+    if let Some(location) = &reminder.location {
+        body.push_str(&format!(" at {}", location));
+    }
+    body.push_str(
+        ". You marked yourself available.\n\nIf you can no longer make it, please \
+         withdraw your availability so the organisers can plan around it.\n",
+    );
+
+    Mail {
+        to: vec![to],
+        subject: format!("Reminder: {}", reminder.title),
+        body,
+        attachments: Vec::new(),
+    }
+}
+
+fn reminder(event: &Event, available: i64) -> Notification {
+    let mut notification = Notification::new(
+        NotificationKind::EventReminder,
+        format!("Coming up: {}", event.title),
+    )
+    .inline_field(
+        "When",
+        event.event_date.format("%a %d %b, %H:%M UTC").to_string(),
+    )
+    .inline_field("Type", event.event_type.clone())
+    .inline_field("Available", available.to_string());
+
+    if let Some(description) = &event.description {
+        notification = notification.description(description.clone());
+    }
+    if let Some(location) = &event.location {
+        notification = notification.inline_field("Where", location.clone());
+    }
+
+    notification
+}
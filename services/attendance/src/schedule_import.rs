@@ -0,0 +1,303 @@
+//! Term schedules uploaded in one go, instead of creating each event by
+//! hand at the start of term.
+//!
+//! A schedule is a CSV whose first row names the columns, or a JSON body
+//! `{"events": [...]}` with the same fields. `title`, `type` (or
+//! `event_type`) and `date` (or `event_date`) are required; `location`,
+//! `description` and `duration_minutes` are optional, and other columns are
+//! ignored. Dates are RFC 3339, or `YYYY-MM-DD HH:MM` in UTC.
+//!
+//! Every row is checked before anything is created, and every problem is
+//! reported at once. An event with the same title on the same day as an
+//! existing event, or as an earlier row, is a duplicate and is skipped.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::models::EventType;
+
+/// One row as uploaded, before it is checked
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScheduleRow {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, alias = "type")]
+    pub event_type: String,
+    #[serde(default, alias = "date")]
+    pub event_date: String,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub duration_minutes: Option<i32>,
+}
+
+/// JSON form of a schedule
+#[derive(Debug, Deserialize)]
+pub struct ScheduleUpload {
+    pub events: Vec<ScheduleRow>,
+}
+
+/// A row that passed every check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduledEvent {
+    /// Line in the CSV, or position in the JSON list counting from 1
+    pub line: u64,
+    pub title: String,
+    pub event_type: EventType,
+    pub event_date: DateTime<Utc>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub duration_minutes: Option<i32>,
+}
+
+/// A row skipped because the event already exists
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Duplicate {
+    pub line: u64,
+    pub title: String,
+    pub event_date: DateTime<Utc>,
+    /// The earlier row it repeats, when it is not an existing event
+    pub repeats_line: Option<u64>,
+}
+
+/// Read a CSV schedule into numbered rows
+pub fn parse_csv(body: &str) -> Result<Vec<(u64, ScheduleRow)>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Could not read the header row: {}", e))?
+        .iter()
+        .map(str::to_ascii_lowercase)
+        .collect::<csv::StringRecord>();
+    for (required, alias) in [
+        ("title", "title"),
+        ("type", "event_type"),
+        ("date", "event_date"),
+    ] {
+        if !headers.iter().any(|h| h == required || h == alias) {
+            return Err(format!("A {} column is required", required));
+        }
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Could not read the CSV: {}", e))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let row = record
+            .deserialize(Some(&headers))
+            .map_err(|e| format!("Line {}: {}", line, e))?;
+        rows.push((line, row));
+    }
+    Ok(rows)
+}
+
+/// Number the rows of a JSON schedule from 1
+pub fn number_rows(upload: ScheduleUpload) -> Vec<(u64, ScheduleRow)> {
+    (1..).zip(upload.events).collect()
+}
+
+/// Parse an RFC 3339 time, or a UTC `YYYY-MM-DD HH:MM`
+pub fn parse_date(raw: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(date.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .map(|date| date.and_utc())
+        .ok_or_else(|| format!("'{}' is not a date and time", raw))
+}
+
+/// Check every row, returning the events or every problem found
+pub fn check_rows(rows: Vec<(u64, ScheduleRow)>) -> Result<Vec<ScheduledEvent>, Vec<String>> {
+    let mut events = Vec::new();
+    let mut problems = Vec::new();
+
+    for (line, row) in rows {
+        let mut problem = |message: String| problems.push(format!("Line {}: {}", line, message));
+
+        let title = row.title.trim().to_string();
+        if title.is_empty() {
+            problem("title is empty".to_string());
+        } else if title.chars().count() > 255 {
+            problem("title is longer than 255 characters".to_string());
+        }
+        let event_type = row.event_type.trim().parse::<EventType>();
+        if let Err(e) = &event_type {
+            problem(e.clone());
+        }
+        let event_date = parse_date(row.event_date.trim());
+        if let Err(e) = &event_date {
+            problem(e.clone());
+        }
+        let location = row
+            .location
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty());
+        if location.as_ref().is_some_and(|l| l.chars().count() > 255) {
+            problem("location is longer than 255 characters".to_string());
+        }
+        if row
+            .duration_minutes
+            .is_some_and(|d| !(1..=1440).contains(&d))
+        {
+            problem("duration_minutes must be between 1 and 1440".to_string());
+        }
+
+        if let (Ok(event_type), Ok(event_date)) = (event_type, event_date) {
+            events.push(ScheduledEvent {
+                line,
+                title,
+                event_type,
+                event_date,
+                location,
+                description: row
+                    .description
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty()),
+                duration_minutes: row.duration_minutes,
+            });
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(events)
+    } else {
+        Err(problems)
+    }
+}
+
+/// Split events into those to create and duplicates of `existing` events
+/// (title and start) or of earlier rows
+pub fn split_duplicates(
+    events: Vec<ScheduledEvent>,
+    existing: &[(String, DateTime<Utc>)],
+) -> (Vec<ScheduledEvent>, Vec<Duplicate>) {
+    let key = |title: &str, date: &DateTime<Utc>| (title.trim().to_lowercase(), date.date_naive());
+    let existing: HashSet<_> = existing.iter().map(|(t, d)| key(t, d)).collect();
+    let mut seen = HashMap::new();
+
+    let mut fresh = Vec::new();
+    let mut duplicates = Vec::new();
+    for event in events {
+        let event_key = key(&event.title, &event.event_date);
+        let repeats_line = seen.get(&event_key).copied();
+        if existing.contains(&event_key) || repeats_line.is_some() {
+            duplicates.push(Duplicate {
+                line: event.line,
+                title: event.title,
+                event_date: event.event_date,
+                repeats_line,
+            });
+        } else {
+            seen.insert(event_key, event.line);
+            fresh.push(event);
+        }
+    }
+    (fresh, duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv(
+            "Title,Type,Date,Location,Notes\n\
+             Novice Open, tournament ,2026-02-07 09:00,Hall A,bring timers\n\
+             ,,,,\n\
+             Weekly Match,weekly_match,2026-02-10T17:00:00+05:00,,\n",
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].0, 2);
+        assert_eq!(rows[0].1.title, "Novice Open");
+        assert_eq!(rows[0].1.location.as_deref(), Some("Hall A"));
+        assert_eq!(rows[1].0, 4);
+        assert_eq!(rows[1].1.location, None);
+
+        assert_eq!(
+            parse_csv("title,date\nx,2026-01-01 10:00\n").unwrap_err(),
+            "A type column is required"
+        );
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let expected = Utc.with_ymd_and_hms(2026, 2, 10, 12, 0, 0).unwrap();
+        assert_eq!(parse_date("2026-02-10T17:00:00+05:00"), Ok(expected));
+        assert_eq!(parse_date("2026-02-10 12:00"), Ok(expected));
+        assert!(parse_date("2026-02-10").is_err());
+    }
+
+    #[test]
+    fn test_check_rows_reports_every_problem() {
+        let rows = vec![
+            (
+                2,
+                ScheduleRow {
+                    title: "Open".to_string(),
+                    event_type: "social".to_string(),
+                    event_date: "soon".to_string(),
+                    ..Default::default()
+                },
+            ),
+            (
+                3,
+                ScheduleRow {
+                    title: " ".to_string(),
+                    event_type: "meeting".to_string(),
+                    event_date: "2026-02-10 12:00".to_string(),
+                    duration_minutes: Some(0),
+                    ..Default::default()
+                },
+            ),
+        ];
+        assert_eq!(
+            check_rows(rows).unwrap_err(),
+            vec![
+                "Line 2: Invalid event type: social",
+                "Line 2: 'soon' is not a date and time",
+                "Line 3: title is empty",
+                "Line 3: duration_minutes must be between 1 and 1440",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_are_split_out() {
+        let date = Utc.with_ymd_and_hms(2026, 2, 10, 12, 0, 0).unwrap();
+        let event = |line, title: &str| ScheduledEvent {
+            line,
+            title: title.to_string(),
+            event_type: EventType::Meeting,
+            event_date: date,
+            location: None,
+            description: None,
+            duration_minutes: None,
+        };
+        let existing = vec![("committee meeting".to_string(), date)];
+
+        let (fresh, duplicates) = split_duplicates(
+            vec![
+                event(1, "Committee Meeting"),
+                event(2, "Training"),
+                event(3, "training "),
+            ],
+            &existing,
+        );
+        assert_eq!(fresh, vec![event(2, "Training")]);
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(duplicates[0].repeats_line, None);
+        assert_eq!(duplicates[1].line, 3);
+        assert_eq!(duplicates[1].repeats_line, Some(2));
+    }
+}
@@ -0,0 +1,62 @@
+//! Event staffing. Admins put conveners and volunteers on an event so
+//! members know who is running the desk; staff may check members in at
+//! that event. Members are emailed when they are put on the staff, as their
+//! allocation notification preferences allow.
+
+use lettre::message::Mailbox;
+
+use crate::{
+    mailer::Mail,
+    models::{Event, StaffAssignment},
+    AppState,
+};
+
+/// Tell a member they are on the event's staff. Failures are only logged:
+/// the assignment stands either way.
+pub async fn notify(state: &AppState, event: &Event, assignment: &StaffAssignment) {
+    if !assignment.wants_email {
+        return;
+    }
+    let Ok(to) = assignment.email.parse() else {
+        tracing::warn!(
+            "Not emailing staff assignment to {}: invalid address",
+            assignment.staff.user_id
+        );
+        return;
+    };
+    if let Err(e) = state
+        .mailer
+        .send(assignment_mail(event, assignment, to))
+        .await
+    {
+        tracing::warn!(
+            "Failed to email staff assignment to {}: {}",
+            assignment.staff.user_id,
+            e
+        );
+    }
+}
+
+fn assignment_mail(event: &Event, assignment: &StaffAssignment, to: Mailbox) -> Mail {
+    let mut body = format!(
+        "Hi {},\n\nYou are a {} for {} on {}",
+        assignment.staff.username,
+        assignment.staff.role,
+        event.title,
+        event.event_date.format("%a %d %b, %H:%M UTC")
+    );
+    if let Some(location) = &event.location {
+        body.push_str(&format!(" at {}", location));
+    }
+    body.push_str(
+        ".\n\nYou can check members in at the event from the attendance page. \
+         If you cannot make it, please let the organisers know.\n",
+    );
+
+    Mail {
+        to: vec![to],
+        subject: format!("You're helping run {}", event.title),
+        body,
+        attachments: Vec::new(),
+    }
+}
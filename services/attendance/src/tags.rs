@@ -0,0 +1,57 @@
+//! Free-form event tags. Tags are compared after normalising, so "Social",
+//! " social " and "SOCIAL" are one tag, and spaces or underscores become
+//! hyphens ("British Parliamentary" is "british-parliamentary").
+
+/// Most tags one event can carry
+pub const MAX_TAGS_PER_EVENT: usize = 20;
+/// Longest tag, in characters (the `event_tags.tag` column)
+pub const MAX_TAG_LENGTH: usize = 40;
+
+/// The stored form of a tag, or why it cannot be one
+pub fn normalize(tag: &str) -> Result<String, String> {
+    let mut normalized = String::new();
+    for c in tag.trim().chars() {
+        if c.is_alphanumeric() {
+            normalized.extend(c.to_lowercase());
+        } else if matches!(c, '-' | '_' | ' ') {
+            if !normalized.is_empty() && !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            return Err(format!(
+                "Invalid tag '{}': use letters, digits, spaces and hyphens",
+                tag
+            ));
+        }
+    }
+
+    let normalized = normalized.trim_end_matches('-').to_string();
+    if normalized.is_empty() {
+        return Err("Tags cannot be empty".to_string());
+    }
+    if normalized.chars().count() > MAX_TAG_LENGTH {
+        return Err(format!(
+            "Invalid tag '{}': tags are at most {} characters",
+            tag, MAX_TAG_LENGTH
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Normalise an event's tags, dropping duplicates
+pub fn normalize_all(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized = tags
+        .iter()
+        .map(|tag| normalize(tag))
+        .collect::<Result<Vec<_>, _>>()?;
+    normalized.sort();
+    normalized.dedup();
+
+    if normalized.len() > MAX_TAGS_PER_EVENT {
+        return Err(format!(
+            "An event can have at most {} tags",
+            MAX_TAGS_PER_EVENT
+        ));
+    }
+    Ok(normalized)
+}
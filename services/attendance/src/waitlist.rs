@@ -0,0 +1,30 @@
+//! Waitlists for events with a seat limit. Members who mark themselves
+//! available once every seat is taken wait in line and are promoted, in
+//! order, as seats free up. Each promotion is posted to chat.
+
+use common::{Notification, NotificationKind};
+
+use crate::{models::Event, AppState};
+
+/// Fill the event's free seats from its waitlist and announce who got in
+pub async fn promote(state: &AppState, event: &Event) -> Result<usize, sqlx::Error> {
+    let promoted = state.db.promote_from_waitlist(event.id).await?;
+
+    for record in &promoted {
+        state.notifier.notify(promotion(event, &record.username));
+    }
+
+    Ok(promoted.len())
+}
+
+fn promotion(event: &Event, username: &str) -> Notification {
+    Notification::new(
+        NotificationKind::WaitlistPromoted,
+        format!("Off the waitlist: {}", event.title),
+    )
+    .description(format!("{} now has a seat.", username))
+    .inline_field(
+        "When",
+        event.event_date.format("%a %d %b, %H:%M UTC").to_string(),
+    )
+}
@@ -0,0 +1,50 @@
+//! Walk-ins: guests registered at the desk on the day, who join the event's
+//! allocation pool as guests. Guests who leave an email are invited to sign
+//! up so they can take part as members next time.
+
+use lettre::message::Mailbox;
+
+use crate::{
+    mailer::Mail,
+    models::{Event, WalkIn},
+    AppState,
+};
+
+/// Email a walk-in an invitation to sign up, recording when it was sent.
+/// Failures are only logged: the guest is registered either way.
+pub async fn invite(state: &AppState, event: &Event, walk_in: &mut WalkIn) {
+    let Some(email) = &walk_in.email else {
+        return;
+    };
+    let Ok(to) = email.parse() else {
+        tracing::warn!("Not inviting walk-in {}: invalid address", walk_in.id);
+        return;
+    };
+    if let Err(e) = state
+        .mailer
+        .send(invitation(event, walk_in, to, &state.config.frontend_url))
+        .await
+    {
+        tracing::warn!("Failed to invite walk-in {}: {}", walk_in.id, e);
+        return;
+    }
+
+    match state.db.mark_walk_in_invited(walk_in.id).await {
+        Ok(()) => walk_in.invited_at = Some(chrono::Utc::now()),
+        Err(e) => tracing::warn!("Failed to record walk-in invite {}: {}", walk_in.id, e),
+    }
+}
+
+fn invitation(event: &Event, walk_in: &WalkIn, to: Mailbox, frontend_url: &str) -> Mail {
+    Mail {
+        to: vec![to],
+        subject: format!("Thanks for joining us at {}", event.title),
+        body: format!(
+            "Hi {},\n\nThanks for taking part in {}. Sign up at {}/signup to mark \
+             your availability for future events, check in yourself and keep \
+             track of your results.\n",
+            walk_in.name, event.title, frontend_url
+        ),
+        attachments: Vec::new(),
+    }
+}
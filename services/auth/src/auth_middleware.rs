@@ -3,85 +3,99 @@ use axum::{
     http::{HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
-    Json,
 };
-use serde_json::json;
+use common::{
+    auth_middleware::{check_suspended_cached, request_token, AuthState},
+    error::{api_error, db_error},
+    ApiError,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::AppState;
+use crate::{
+    policies::{pending_header, PendingPolicies, PENDING_POLICIES_HEADER},
+    AppState,
+};
 
-/// Middleware to authenticate requests using JWT access tokens
-pub async fn auth_middleware(
-    State(state): State<Arc<AppState>>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
+/// Lets the shared middleware (maintenance mode) check admins against the
+/// database directly
+impl AuthState for AppState {
+    fn jwt_secret(&self) -> &str {
+        &self.config.jwt_secret
+    }
 
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
+    async fn is_admin(&self, user_id: Uuid, _auth_header: &str) -> Result<bool, ApiError> {
+        self.db.is_user_admin(user_id).await.map_err(db_error)
     }
 
-    let token = &auth_header[7..]; // Remove "Bearer " prefix
+    async fn is_suspended(&self, user_id: Uuid) -> Result<bool, ApiError> {
+        check_suspended_cached(&self.kv, user_id, async {
+            Ok(self
+                .db
+                .active_suspension(user_id)
+                .await
+                .map_err(db_error)?
+                .is_some())
+        })
+        .await
+    }
+}
+
+/// Validate the access token (bearer or cookie) and make sure the user it
+/// names still exists and is not suspended. CSRF is checked for every request by the CSRF
+/// middleware, so cookies need no extra care here.
+async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<(Uuid, String), ApiError> {
+    let token = request_token(headers)?;
 
     // Validate the access token
     let claims = state
         .jwt_service
-        .validate_access_token(token)
-        .map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid or expired token"})),
-            )
-        })?;
+        .validate_access_token(token.token)
+        .map_err(|_| api_error(StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
 
     // Parse user_id from claims
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| api_error(StatusCode::UNAUTHORIZED, "Invalid user ID in token"))?;
 
     // Verify user exists
     let user = state
         .db
         .find_user_by_id(user_id)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "User not found"})),
-            )
-        })?;
+        .map_err(db_error)?
+        .ok_or_else(|| api_error(StatusCode::UNAUTHORIZED, "User not found"))?;
+
+    if state.is_suspended(user_id).await? {
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            "Your account is suspended",
+        ));
+    }
+
+    Ok((user_id, user.username))
+}
+
+/// Middleware to authenticate requests using JWT access tokens. Members
+/// with policies left to accept are flagged with `PENDING_POLICIES_HEADER`.
+pub async fn auth_middleware(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, username) = authenticate(&state, request.headers()).await?;
+    let pending = state.db.pending_policies(user_id).await.map_err(db_error)?;
+    let flag = pending_header(&pending);
 
     // Add user_id to request extensions for use in handlers
     request.extensions_mut().insert(user_id);
-    request.extensions_mut().insert(user.username.clone());
+    request.extensions_mut().insert(username);
+    request.extensions_mut().insert(PendingPolicies(pending));
 
-    Ok(next.run(request).await)
+    let mut response = next.run(request).await;
+    if let Some(flag) = flag {
+        response.headers_mut().insert(PENDING_POLICIES_HEADER, flag);
+    }
+    Ok(response)
 }
 
 /// Middleware to authenticate requests and verify admin privileges
@@ -89,85 +103,19 @@ pub async fn admin_middleware(
     State(state): State<Arc<AppState>>,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
-    }
-
-    let token = &auth_header[7..]; // Remove "Bearer " prefix
-
-    // Validate the access token
-    let claims = state
-        .jwt_service
-        .validate_access_token(token)
-        .map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid or expired token"})),
-            )
-        })?;
-
-    // Parse user_id from claims
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
-
-    // Verify user exists
-    let user = state
-        .db
-        .find_user_by_id(user_id)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "User not found"})),
-            )
-        })?;
+) -> Result<Response, ApiError> {
+    let (user_id, username) = authenticate(&state, request.headers()).await?;
 
     // Check if user is an admin
-    let is_admin = state.db.is_user_admin(user_id).await.map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Database error"})),
-        )
-    })?;
+    let is_admin = state.db.is_user_admin(user_id).await.map_err(db_error)?;
 
     if !is_admin {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Admin access required"})),
-        ));
+        return Err(api_error(StatusCode::FORBIDDEN, "Admin access required"));
     }
 
     // Add user_id to request extensions for use in handlers
     request.extensions_mut().insert(user_id);
-    request.extensions_mut().insert(user.username.clone());
+    request.extensions_mut().insert(username);
 
     Ok(next.run(request).await)
 }
@@ -0,0 +1,29 @@
+//! Hourly removal of expired refresh tokens and one-time codes, on one
+//! replica at a time (see `common::leader`)
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::AppState;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Start the cleanup loop
+pub fn spawn(state: Arc<AppState>) {
+    common::leader::spawn(
+        state.db.pool().clone(),
+        "auth.token_cleanup",
+        CLEANUP_INTERVAL,
+        move || {
+            let state = state.clone();
+            async move {
+                if let Err(e) = state.db.cleanup_expired_refresh_tokens().await {
+                    tracing::warn!("Failed to clean up expired refresh tokens: {}", e);
+                }
+                if let Err(e) = state.db.cleanup_expired_verification_tokens().await {
+                    tracing::warn!("Failed to clean up expired verification codes: {}", e);
+                }
+            }
+        },
+    );
+}
@@ -1,20 +1,77 @@
+//! CSRF protection for state-changing requests.
+//!
+//! Tokens are signed rather than stored, so nothing accumulates in the
+//! database; see `common::csrf` for their format.
+//!
+//! Two modes are supported (`CSRF_MODE`):
+//! - `session`: the token must belong to the session of the access token
+//!   the request carries. Sessions get a fresh token on every refresh.
+//! - `double_submit`: the token is also set as a cookie, and the header
+//!   must repeat the cookie's value.
+
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::Response,
     Json,
 };
+use chrono::Utc;
+use common::{
+    auth_middleware::request_token,
+    csrf::{
+        cookie_value, has_valid_csrf_token, sign_csrf_token, verify_csrf_token, CSRF_TOKEN_HEADER,
+    },
+};
 use rand::{distributions::Alphanumeric, Rng};
 use serde_json::json;
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::{database::Database, AppState};
+use crate::{config::Config, AppState};
 
-const CSRF_TOKEN_HEADER: &str = "X-CSRF-Token";
 const CSRF_TOKEN_LENGTH: usize = 32;
 
+/// Cookie holding the token in double-submit mode; readable by scripts so
+/// they can copy it into the header
+pub const CSRF_COOKIE: &str = "csrf_token";
+
+/// How CSRF tokens are tied to the client (`CSRF_MODE`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsrfMode {
+    /// The token must belong to the request's session
+    #[default]
+    Session,
+    /// The header must match the `csrf_token` cookie
+    DoubleSubmit,
+}
+
+impl FromStr for CsrfMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "session" => Ok(CsrfMode::Session),
+            "double_submit" => Ok(CsrfMode::DoubleSubmit),
+            other => Err(format!(
+                "Invalid CSRF_MODE '{}': expected session or double_submit",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CsrfMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsrfMode::Session => write!(f, "session"),
+            CsrfMode::DoubleSubmit => write!(f, "double_submit"),
+        }
+    }
+}
+
 /// Generate a random CSRF token
 pub fn generate_csrf_token() -> String {
     rand::thread_rng()
@@ -24,6 +81,45 @@ pub fn generate_csrf_token() -> String {
         .collect()
 }
 
+/// Issue a token for a session, or for no session (anonymous clients)
+pub fn issue_csrf_token(secret: &str, session_id: Option<Uuid>, expiry_seconds: i64) -> String {
+    sign_csrf_token(
+        secret,
+        Utc::now().timestamp() + expiry_seconds,
+        session_id,
+        &generate_csrf_token(),
+    )
+}
+
+/// Headers handing a new token to the client: the double-submit cookie,
+/// or nothing in session mode where the token is only in the body
+pub fn csrf_cookie_headers(config: &Config, token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if config.csrf_mode == CsrfMode::DoubleSubmit {
+        let mut cookie = format!(
+            "{}={}; Path=/; Max-Age={}; SameSite=Strict",
+            CSRF_COOKIE, token, config.csrf_token_expiry
+        );
+        if config.csrf_cookie_secure {
+            cookie.push_str("; Secure");
+        }
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            headers.insert(header::SET_COOKIE, value);
+        }
+    }
+    headers
+}
+
+/// The session of the access token a request carries, if any
+pub fn request_session(state: &AppState, headers: &HeaderMap) -> Option<Uuid> {
+    let token = request_token(headers).ok()?;
+    state
+        .jwt_service
+        .validate_access_token(token.token)
+        .ok()?
+        .sid
+}
+
 /// Middleware to validate CSRF tokens on state-changing requests
 pub async fn csrf_protection_middleware(
     State(state): State<Arc<AppState>>,
@@ -38,7 +134,9 @@ pub async fn csrf_protection_middleware(
         return Ok(next.run(request).await);
     }
 
-    // Skip CSRF check for login and register endpoints
+    // Skip CSRF check for login and register endpoints, and for /refresh:
+    // a cross-site page can at most make it rotate the caller's own tokens,
+    // which it cannot read (and the session's CSRF token may have expired)
     let path = request.uri().path();
     if path.ends_with("/register")
         || path.ends_with("/login")
@@ -48,6 +146,7 @@ pub async fn csrf_protection_middleware(
         || path.ends_with("/request-password-reset")
         || path.ends_with("/reset-password")
         || path.ends_with("/csrf-token")
+        || path.ends_with("/refresh")
     {
         return Ok(next.run(request).await);
     }
@@ -63,18 +162,19 @@ pub async fn csrf_protection_middleware(
             )
         })?;
 
-    // Validate CSRF token
-    let is_valid = state
-        .db
-        .validate_csrf_token(csrf_token)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to validate CSRF token"})),
-            )
-        })?
-        .is_some();
+    let is_valid = match state.config.csrf_mode {
+        CsrfMode::Session => has_valid_csrf_token(
+            &state.config.jwt_secret,
+            &headers,
+            request_session(&state, &headers),
+        ),
+        // The signature already rules out forged cookies, so comparing the
+        // two copies needs no constant-time care
+        CsrfMode::DoubleSubmit => {
+            verify_csrf_token(&state.config.jwt_secret, csrf_token).is_some()
+                && cookie_value(&headers, CSRF_COOKIE) == Some(csrf_token)
+        }
+    };
 
     if !is_valid {
         return Err((
@@ -86,18 +186,6 @@ pub async fn csrf_protection_middleware(
     Ok(next.run(request).await)
 }
 
-/// Create a CSRF token for a user
-pub async fn create_csrf_token(
-    db: &Database,
-    user_id: Option<Uuid>,
-    expiry_seconds: i64,
-) -> Result<String, sqlx::Error> {
-    let token = generate_csrf_token();
-    db.create_csrf_token(&token, user_id, expiry_seconds)
-        .await?;
-    Ok(token)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
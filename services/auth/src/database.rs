@@ -1,6 +1,19 @@
-use crate::models::{CsrfToken, EmailVerificationToken, PasswordResetToken, RefreshToken, User};
+use crate::email_verification::VerificationPurpose;
+use crate::login_history::{ClientInfo, LoginFailure, LoginOrigins};
+use crate::models::{
+    AccountAction, AccountAuditEntry, EmailVerificationToken, LoginHistoryEntry,
+    MemberRequirements, PendingPolicy, PolicyAcceptance, PolicyAcceptanceRecord, PolicyDocument,
+    RefreshToken, Suspension, User, DELETED_MEMBER_PREFIX,
+};
+use crate::policies::PolicyKind;
 use chrono::{DateTime, Duration, Utc};
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use common::{
+    api_keys::ApiKeyScope,
+    preferences::{preference_matrix, PreferenceMatrix},
+    stats::{ACTIVE_DAYS, STATS_WEEKS},
+    PeriodCount, ReadReplica, Role,
+};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool};
 use uuid::Uuid;
 
 /// Parameters for creating a new user
@@ -9,14 +22,26 @@ pub struct CreateUserParams<'a> {
     pub email: &'a str,
     pub password_hash: &'a str,
     pub salt: &'a str,
+    pub pepper_version: i32,
     pub reg_number: &'a str,
     pub year_joined: i32,
     pub phone_number: &'a str,
 }
 
+/// What came of a demotion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demotion {
+    Demoted,
+    /// The user was not an admin (any more)
+    NotAdmin,
+    /// The user is the only admin, who must stay
+    LastAdmin,
+}
+
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
+    replica: Option<ReadReplica>,
 }
 
 impl Database {
@@ -26,15 +51,30 @@ impl Database {
             .connect(database_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            replica: None,
+        })
+    }
+
+    /// Serve the reads of `GET` requests from `replica` while it is reachable
+    pub fn with_replica(mut self, replica: ReadReplica) -> Self {
+        self.replica = Some(replica);
+        self
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
 
+    /// Pool for read-only queries: the replica during `GET` requests, the
+    /// primary otherwise
+    fn reader(&self) -> &PgPool {
+        common::replica::reader(&self.pool, self.replica.as_ref())
+    }
+
     /// Run database migrations
-    pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+    pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
         sqlx::migrate!("../migrations").run(&self.pool).await?;
         Ok(())
     }
@@ -43,9 +83,9 @@ impl Database {
     pub async fn create_user(&self, params: CreateUserParams<'_>) -> Result<User, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            INSERT INTO users (id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, false, $9, $10)
-            RETURNING id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            INSERT INTO users (id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, false, $10, $11)
+            RETURNING id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             "#,
         )
         .bind(Uuid::new_v4())
@@ -53,6 +93,7 @@ impl Database {
         .bind(params.email)
         .bind(params.password_hash)
         .bind(params.salt)
+        .bind(params.pepper_version)
         .bind(params.reg_number)
         .bind(params.year_joined)
         .bind(params.phone_number)
@@ -68,9 +109,9 @@ impl Database {
     pub async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            SELECT id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             FROM users
-            WHERE username = $1
+            WHERE username = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(username)
@@ -84,9 +125,9 @@ impl Database {
     pub async fn find_user_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            SELECT id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             FROM users
-            WHERE email = $1
+            WHERE email = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(email)
@@ -103,9 +144,9 @@ impl Database {
     ) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            SELECT id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             FROM users
-            WHERE phone_number = $1
+            WHERE phone_number = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(phone_number)
@@ -122,9 +163,9 @@ impl Database {
     ) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            SELECT id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             FROM users
-            WHERE reg_number = $1
+            WHERE reg_number = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(reg_number)
@@ -138,9 +179,9 @@ impl Database {
     pub async fn find_user_by_id(&self, user_id: Uuid) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password_hash, salt, reg_number, year_joined, phone_number, email_verified, email_verified_at, created_at, updated_at
+            SELECT id, username, email, password_hash, salt, pepper_version, reg_number, year_joined, phone_number, email_verified, email_verified_at, is_novice, is_esl, created_at, updated_at
             FROM users
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(user_id)
@@ -150,6 +191,144 @@ impl Database {
         Ok(user)
     }
 
+    /// Delete a member while keeping everything attributed to them: their
+    /// personal details become placeholders, they appear as
+    /// "Deleted member #N", and their sessions, tokens and privileges go.
+    /// Returns N, or None if there is no such member.
+    pub async fn soft_delete_user(&self, user_id: Uuid) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let number: i64 = sqlx::query_scalar("SELECT nextval('deleted_member_number_seq')")
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET
+                username = $3 || $2,
+                email = 'deleted-member-' || $2 || '@deleted.invalid',
+                reg_number = 'deleted-' || $2,
+                phone_number = 'deleted-' || $2,
+                password_hash = '',
+                salt = '',
+                email_verified = false,
+                email_verified_at = NULL,
+                is_novice = false,
+                is_esl = false,
+                deleted_number = $2,
+                deleted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(number as i32)
+        .bind(DELETED_MEMBER_PREFIX)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            // Leaves a gap in the numbering, which is harmless
+            return Ok(None);
+        }
+
+        // Acceptances stay as proof of consent, without where they came from
+        sqlx::query(
+            "UPDATE policy_acceptances SET ip_address = NULL, user_agent = NULL WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for table in [
+            "refresh_tokens",
+            "email_verification_tokens",
+            "admin_users",
+            "user_roles",
+            "login_history",
+            "username_history",
+            "event_managers",
+            "event_staff",
+            "member_requirements",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(number))
+    }
+
+    /// The member who last gave up `username`, if anyone has
+    pub async fn former_username_owner(&self, username: &str) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT user_id FROM username_history
+            WHERE old_username = $1
+            ORDER BY changed_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// When the member's username last changed
+    pub async fn last_username_change(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar("SELECT MAX(changed_at) FROM username_history WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    /// Rename a member, keeping the old name in their history. Returns the
+    /// old name, or None if the member does not exist.
+    pub async fn change_username(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        changed_by: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let old: Option<String> = sqlx::query_scalar(
+            "SELECT username FROM users WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(old) = old else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE users SET username = $2, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .bind(username)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO username_history (user_id, old_username, new_username, changed_by)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&old)
+        .bind(username)
+        .bind(changed_by)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(old))
+    }
+
     /// Delete a user by ID (for cleaning up unverified registrations)
     pub async fn delete_user_by_id(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query(
@@ -165,18 +344,26 @@ impl Database {
         Ok(())
     }
 
-    /// Store a refresh token - uses parameterized queries
+    /// Store a refresh token, bound to the client's device if it sent an
+    /// identifier. A new login replaces the device's previous session, so
+    /// logging in again does not pile up sessions - uses parameterized queries
     pub async fn store_refresh_token(
         &self,
         user_id: Uuid,
         token_hash: &str,
         expires_at: DateTime<Utc>,
+        client: &ClientInfo,
     ) -> Result<RefreshToken, sqlx::Error> {
         let refresh_token = sqlx::query_as::<_, RefreshToken>(
             r#"
-            INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at, created_at)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, user_id, token_hash, expires_at, created_at
+            WITH replaced AS (
+                DELETE FROM refresh_tokens WHERE user_id = $2 AND device_id = $6
+            )
+            INSERT INTO refresh_tokens
+                (id, user_id, token_hash, expires_at, created_at, device_id, device, ip_address)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, token_hash, expires_at, created_at,
+                device_id, device, ip_address, last_used_at
             "#,
         )
         .bind(Uuid::new_v4())
@@ -184,12 +371,83 @@ impl Database {
         .bind(token_hash)
         .bind(expires_at)
         .bind(Utc::now())
+        .bind(&client.device_id)
+        .bind(client.device())
+        .bind(&client.ip_address)
         .fetch_one(&self.pool)
         .await?;
 
         Ok(refresh_token)
     }
 
+    /// Replace a refresh token with its successor in the same session,
+    /// binding a not yet bound session to the client's device. Returns
+    /// false if the old token was already used or revoked.
+    pub async fn rotate_refresh_token(
+        &self,
+        old_token_hash: &str,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+        client: &ClientInfo,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET token_hash = $2,
+                expires_at = $3,
+                last_used_at = NOW(),
+                ip_address = $4,
+                device = CASE WHEN device_id IS NULL THEN $6 ELSE device END,
+                device_id = COALESCE(device_id, $5)
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(old_token_hash)
+        .bind(new_token_hash)
+        .bind(expires_at)
+        .bind(&client.ip_address)
+        .bind(&client.device_id)
+        .bind(client.device())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The user's unexpired refresh tokens, most recently used first
+    pub async fn list_user_refresh_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<RefreshToken>, sqlx::Error> {
+        sqlx::query_as::<_, RefreshToken>(
+            r#"
+            SELECT id, user_id, token_hash, expires_at, created_at,
+                device_id, device, ip_address, last_used_at
+            FROM refresh_tokens
+            WHERE user_id = $1 AND expires_at > NOW()
+            ORDER BY COALESCE(last_used_at, created_at) DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Revoke one of the user's refresh tokens by id
+    pub async fn delete_user_refresh_token(
+        &self,
+        user_id: Uuid,
+        token_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE id = $1 AND user_id = $2")
+            .bind(token_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Find a refresh token by hash - uses parameterized queries
     pub async fn find_refresh_token(
         &self,
@@ -197,7 +455,8 @@ impl Database {
     ) -> Result<Option<RefreshToken>, sqlx::Error> {
         let token = sqlx::query_as::<_, RefreshToken>(
             r#"
-            SELECT id, user_id, token_hash, expires_at, created_at
+            SELECT id, user_id, token_hash, expires_at, created_at,
+                device_id, device, ip_address, last_used_at
             FROM refresh_tokens
             WHERE token_hash = $1 AND expires_at > $2
             "#,
@@ -240,388 +499,367 @@ impl Database {
         Ok(())
     }
 
-    /// Clean up expired refresh tokens - uses parameterized queries
+    /// Clean up expired refresh tokens - uses parameterized queries. Tokens
+    /// issued in the last `ACTIVE_DAYS` days are kept for the active user
+    /// count in the admin stats.
     pub async fn cleanup_expired_refresh_tokens(&self) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
             DELETE FROM refresh_tokens
             WHERE expires_at < $1
+              AND created_at < $1 - make_interval(days => $2)
             "#,
         )
         .bind(Utc::now())
+        .bind(ACTIVE_DAYS)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
+}
 
-    /// Create a CSRF token - uses parameterized queries
-    pub async fn create_csrf_token(
+// Email verification methods
+impl Database {
+    /// Store a new code for the purpose, replacing any earlier one
+    pub async fn create_verification_otp(
         &self,
-        token: &str,
-        user_id: Option<Uuid>,
+        user_id: Uuid,
+        purpose: VerificationPurpose,
+        email: &str,
+        otp: &str,
         expiry_seconds: i64,
-    ) -> Result<CsrfToken, sqlx::Error> {
+    ) -> Result<EmailVerificationToken, sqlx::Error> {
         let expires_at = Utc::now() + Duration::seconds(expiry_seconds);
 
-        let csrf_token = sqlx::query_as::<_, CsrfToken>(
+        sqlx::query_as::<_, EmailVerificationToken>(
             r#"
-            INSERT INTO csrf_tokens (id, token, user_id, expires_at, created_at)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, token, user_id, expires_at, created_at
+            INSERT INTO email_verification_tokens
+                (user_id, purpose, email, otp, attempts, expires_at, created_at, last_sent_at)
+            VALUES ($1, $2, $3, $4, 0, $5, NOW(), NOW())
+            ON CONFLICT (user_id, purpose) DO UPDATE SET
+                email = EXCLUDED.email,
+                otp = EXCLUDED.otp,
+                attempts = 0,
+                expires_at = EXCLUDED.expires_at,
+                created_at = EXCLUDED.created_at,
+                last_sent_at = EXCLUDED.last_sent_at
+            RETURNING id, user_id, purpose, email, otp, attempts, expires_at, created_at, last_sent_at
             "#,
         )
-        .bind(Uuid::new_v4())
-        .bind(token)
         .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(email)
+        .bind(otp)
         .bind(expires_at)
-        .bind(Utc::now())
         .fetch_one(&self.pool)
-        .await?;
-
-        Ok(csrf_token)
+        .await
     }
 
-    /// Validate a CSRF token - uses parameterized queries
-    pub async fn validate_csrf_token(&self, token: &str) -> Result<Option<CsrfToken>, sqlx::Error> {
-        let csrf_token = sqlx::query_as::<_, CsrfToken>(
+    /// The user's unexpired code for the purpose
+    pub async fn find_verification_otp(
+        &self,
+        user_id: Uuid,
+        purpose: VerificationPurpose,
+    ) -> Result<Option<EmailVerificationToken>, sqlx::Error> {
+        sqlx::query_as::<_, EmailVerificationToken>(
             r#"
-            SELECT id, token, user_id, expires_at, created_at
-            FROM csrf_tokens
-            WHERE token = $1 AND expires_at > $2
+            SELECT id, user_id, purpose, email, otp, attempts, expires_at, created_at, last_sent_at
+            FROM email_verification_tokens
+            WHERE user_id = $1 AND purpose = $2 AND expires_at > NOW()
             "#,
         )
-        .bind(token)
-        .bind(Utc::now())
+        .bind(user_id)
+        .bind(purpose.as_str())
         .fetch_optional(&self.pool)
-        .await?;
+        .await
+    }
+
+    /// Count a wrong guess, returning the attempts made so far
+    pub async fn increment_verification_attempts(
+        &self,
+        token_id: Uuid,
+    ) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            UPDATE email_verification_tokens
+            SET attempts = attempts + 1
+            WHERE id = $1
+            RETURNING attempts
+            "#,
+        )
+        .bind(token_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Use up a code. Returns false if it was already used, so a code
+    /// cannot be redeemed twice.
+    pub async fn consume_verification_otp(&self, token_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM email_verification_tokens WHERE id = $1")
+            .bind(token_id)
+            .execute(&self.pool)
+            .await?;
 
-        Ok(csrf_token)
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Delete a CSRF token - uses parameterized queries
-    pub async fn delete_csrf_token(&self, token: &str) -> Result<(), sqlx::Error> {
+    /// Move the user to a new, verified address
+    pub async fn change_user_email(&self, user_id: Uuid, email: &str) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            DELETE FROM csrf_tokens
-            WHERE token = $1
+            UPDATE users
+            SET email = $1, email_verified = true, email_verified_at = NOW(), updated_at = NOW()
+            WHERE id = $2
             "#,
         )
-        .bind(token)
+        .bind(email)
+        .bind(user_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// Clean up expired CSRF tokens - uses parameterized queries
-    pub async fn cleanup_expired_csrf_tokens(&self) -> Result<(), sqlx::Error> {
+    /// Mark user email as verified
+    pub async fn verify_user_email(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            DELETE FROM csrf_tokens
-            WHERE expires_at < $1
+            UPDATE users
+            SET email_verified = true, email_verified_at = $1, updated_at = $2
+            WHERE id = $3
             "#,
         )
         .bind(Utc::now())
+        .bind(Utc::now())
+        .bind(user_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
-}
 
-// Email verification and password reset methods
-impl Database {
-    /// Create or update an email verification OTP
-    pub async fn create_email_verification_otp(
+    /// Mark a member's email verified on an admin's word, dropping any
+    /// registration code still pending. Returns None if the member does
+    /// not exist or is already verified.
+    pub async fn admin_verify_email(
         &self,
         user_id: Uuid,
-        otp: &str,
-        expiry_seconds: i64,
-    ) -> Result<EmailVerificationToken, sqlx::Error> {
-        let expires_at = Utc::now() + Duration::seconds(expiry_seconds);
+        admin_id: Uuid,
+    ) -> Result<Option<AccountAuditEntry>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
 
-        // Delete any existing OTP for this user
-        sqlx::query(
+        let result = sqlx::query(
             r#"
-            DELETE FROM email_verification_tokens
-            WHERE user_id = $1
+            UPDATE users
+            SET email_verified = true, email_verified_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND NOT email_verified AND deleted_at IS NULL
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
 
-        // Create new OTP
-        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
-            r#"
-            INSERT INTO email_verification_tokens (id, user_id, otp, attempts, expires_at, created_at, last_sent_at)
-            VALUES ($1, $2, $3, 0, $4, $5, $6)
-            RETURNING id, user_id, otp, attempts, expires_at, created_at, last_sent_at
-            "#,
+        sqlx::query("DELETE FROM email_verification_tokens WHERE user_id = $1 AND purpose = $2")
+            .bind(user_id)
+            .bind(VerificationPurpose::Registration.as_str())
+            .execute(&mut *tx)
+            .await?;
+        let entry = insert_account_audit(
+            &mut tx,
+            user_id,
+            admin_id,
+            AccountAction::EmailForceVerified,
+            None,
         )
-        .bind(Uuid::new_v4())
-        .bind(user_id)
-        .bind(otp)
-        .bind(expires_at)
-        .bind(Utc::now())
-        .bind(Utc::now())
-        .fetch_one(&self.pool)
         .await?;
 
-        Ok(verification_token)
+        tx.commit().await?;
+        Ok(Some(entry))
     }
 
-    /// Find an email verification OTP by user_id
-    pub async fn find_email_verification_otp_by_user(
+    /// Log a change an admin made to a member's account
+    pub async fn record_account_audit(
         &self,
         user_id: Uuid,
-    ) -> Result<Option<EmailVerificationToken>, sqlx::Error> {
-        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
-            r#"
-            SELECT id, user_id, otp, attempts, expires_at, created_at, last_sent_at
-            FROM email_verification_tokens
-            WHERE user_id = $1 AND expires_at > $2
-            "#,
-        )
-        .bind(user_id)
-        .bind(Utc::now())
-        .fetch_optional(&self.pool)
-        .await?;
+        admin_id: Uuid,
+        action: AccountAction,
+        note: Option<&str>,
+    ) -> Result<AccountAuditEntry, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        insert_account_audit(&mut conn, user_id, admin_id, action, note).await
+    }
 
-        Ok(verification_token)
-    }
-
-    /// Verify OTP and increment attempts
-    pub async fn verify_email_otp(&self, user_id: Uuid, otp: &str) -> Result<bool, sqlx::Error> {
-        // Get the OTP record
-        let token = self.find_email_verification_otp_by_user(user_id).await?;
-
-        match token {
-            Some(token) => {
-                if token.attempts >= 5 {
-                    // Too many attempts
-                    return Ok(false);
-                }
-
-                if token.otp == otp {
-                    // Correct OTP - mark email as verified and delete token
-                    sqlx::query(
-                        r#"
-                        UPDATE users
-                        SET email_verified = true, email_verified_at = $1
-                        WHERE id = $2
-                        "#,
-                    )
-                    .bind(Utc::now())
-                    .bind(user_id)
-                    .execute(&self.pool)
-                    .await?;
-
-                    sqlx::query(
-                        r#"
-                        DELETE FROM email_verification_tokens
-                        WHERE user_id = $1
-                        "#,
-                    )
-                    .bind(user_id)
-                    .execute(&self.pool)
-                    .await?;
-
-                    Ok(true)
-                } else {
-                    // Incorrect OTP - increment attempts
-                    sqlx::query(
-                        r#"
-                        UPDATE email_verification_tokens
-                        SET attempts = attempts + 1
-                        WHERE user_id = $1
-                        "#,
-                    )
-                    .bind(user_id)
-                    .execute(&self.pool)
-                    .await?;
-
-                    Ok(false)
-                }
-            }
-            None => Ok(false),
-        }
+    /// Events the member has been made a manager of
+    pub async fn managed_events(&self, user_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT event_id FROM event_managers WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
     }
 
-    /// Delete email verification token by user_id
-    pub async fn delete_email_verification_token_by_user(
+    /// The member's suspension in force now, if any
+    pub async fn active_suspension(
         &self,
         user_id: Uuid,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    ) -> Result<Option<Suspension>, sqlx::Error> {
+        sqlx::query_as::<_, Suspension>(
             r#"
-            DELETE FROM email_verification_tokens
-            WHERE user_id = $1
+            SELECT id, user_id, reason, suspended_by, created_at, ends_at, lifted_at, lifted_by
+            FROM account_suspensions
+            WHERE user_id = $1 AND lifted_at IS NULL AND (ends_at IS NULL OR ends_at > NOW())
+            ORDER BY created_at DESC
+            LIMIT 1
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
-    }
-
-    /// Legacy method - kept for backwards compatibility with password reset
-    pub async fn find_email_verification_token(
-        &self,
-        token: &str,
-    ) -> Result<Option<EmailVerificationToken>, sqlx::Error> {
-        // This is now only used for password reset, but keeping the signature
-        let verification_token = sqlx::query_as::<_, EmailVerificationToken>(
-            r#"
-            SELECT id, user_id, otp as token, attempts, expires_at, created_at, last_sent_at
-            FROM email_verification_tokens
-            WHERE otp = $1 AND expires_at > $2
-            "#,
-        )
-        .bind(token)
-        .bind(Utc::now())
         .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(verification_token)
+        .await
     }
 
-    /// Legacy method - delete by token
-    pub async fn delete_email_verification_token(&self, token: &str) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    /// The member's suspensions, newest first
+    pub async fn list_suspensions(&self, user_id: Uuid) -> Result<Vec<Suspension>, sqlx::Error> {
+        sqlx::query_as::<_, Suspension>(
             r#"
-            DELETE FROM email_verification_tokens
-            WHERE otp = $1
+            SELECT id, user_id, reason, suspended_by, created_at, ends_at, lifted_at, lifted_by
+            FROM account_suspensions
+            WHERE user_id = $1
+            ORDER BY created_at DESC
             "#,
         )
-        .bind(token)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
     }
 
-    /// Delete all email verification tokens for a user
-    pub async fn delete_user_verification_tokens(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    /// Suspend a member, replacing any suspension in force, and end their
+    /// sessions so they cannot refresh back in
+    pub async fn suspend_user(
+        &self,
+        user_id: Uuid,
+        admin_id: Uuid,
+        reason: &str,
+        ends_at: Option<DateTime<Utc>>,
+    ) -> Result<Suspension, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        lift_active_suspension(&mut tx, user_id, admin_id).await?;
+        let suspension = sqlx::query_as::<_, Suspension>(
             r#"
-            DELETE FROM email_verification_tokens
-            WHERE user_id = $1
+            INSERT INTO account_suspensions (user_id, reason, suspended_by, ends_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, reason, suspended_by, created_at, ends_at, lifted_at, lifted_by
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .bind(reason)
+        .bind(admin_id)
+        .bind(ends_at)
+        .fetch_one(&mut *tx)
         .await?;
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
 
-        Ok(())
+        tx.commit().await?;
+        Ok(suspension)
     }
 
-    /// Mark user email as verified
-    pub async fn verify_user_email(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
+    /// End the member's suspension early. Returns None if none is in force.
+    pub async fn lift_suspension(
+        &self,
+        user_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<Option<Suspension>, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        lift_active_suspension(&mut conn, user_id, admin_id).await
+    }
+
+    /// Record a login attempt against an existing account
+    pub async fn record_login(
+        &self,
+        user_id: Uuid,
+        failure: Option<LoginFailure>,
+        client: &ClientInfo,
+    ) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            UPDATE users
-            SET email_verified = true, email_verified_at = $1, updated_at = $2
-            WHERE id = $3
+            INSERT INTO login_history
+                (user_id, success, failure_reason, ip_address, user_agent, device, country)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#,
         )
-        .bind(Utc::now())
-        .bind(Utc::now())
         .bind(user_id)
+        .bind(failure.is_none())
+        .bind(failure.map(|f| f.as_str()))
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .bind(client.device())
+        .bind(&client.country)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// Create or update a password reset OTP
-    pub async fn create_password_reset_otp(
+    /// Whether the user has logged in successfully before, and whether from
+    /// this device and country
+    pub async fn login_origins(
         &self,
         user_id: Uuid,
-        email: &str,
-        otp_hash: &str,
-        expiry_seconds: i64,
-    ) -> Result<PasswordResetToken, sqlx::Error> {
-        let expires_at = Utc::now() + Duration::seconds(expiry_seconds);
-
-        // Delete any existing OTP for this email
-        sqlx::query("DELETE FROM password_reset_tokens WHERE email = $1")
-            .bind(email)
-            .execute(&self.pool)
-            .await?;
-
-        let reset_token = sqlx::query_as::<_, PasswordResetToken>(
+        client: &ClientInfo,
+    ) -> Result<LoginOrigins, sqlx::Error> {
+        sqlx::query_as::<_, LoginOrigins>(
             r#"
-            INSERT INTO password_reset_tokens (id, user_id, email, otp, attempts, expires_at, created_at, last_sent_at, used)
-            VALUES ($1, $2, $3, $4, 0, $5, $6, $6, false)
-            RETURNING id, user_id, email, otp, attempts, expires_at, created_at, last_sent_at, used
+            SELECT
+                COUNT(*) > 0 AS has_history,
+                COUNT(*) FILTER (WHERE device = $2) > 0 AS known_device,
+                COUNT(*) FILTER (WHERE country = $3) > 0 AS known_country
+            FROM login_history
+            WHERE user_id = $1 AND success
             "#,
         )
-        .bind(Uuid::new_v4())
         .bind(user_id)
-        .bind(email)
-        .bind(otp_hash)
-        .bind(expires_at)
-        .bind(Utc::now())
+        .bind(client.device())
+        .bind(&client.country)
         .fetch_one(&self.pool)
-        .await?;
-
-        Ok(reset_token)
+        .await
     }
 
-    /// Find a password reset token by email
-    pub async fn find_password_reset_by_email(
+    /// A page of the user's login attempts, newest first, and the total
+    pub async fn list_login_history(
         &self,
-        email: &str,
-    ) -> Result<Option<PasswordResetToken>, sqlx::Error> {
-        let reset_token = sqlx::query_as::<_, PasswordResetToken>(
-            r#"
-            SELECT id, user_id, email, otp, attempts, expires_at, created_at, last_sent_at, used
-            FROM password_reset_tokens
-            WHERE email = $1 AND expires_at > $2 AND used = false
-            "#,
-        )
-        .bind(email)
-        .bind(Utc::now())
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(reset_token)
-    }
-
-    /// Increment OTP verification attempts
-    pub async fn increment_password_reset_attempts(&self, email: &str) -> Result<i32, sqlx::Error> {
-        let row: (i32,) = sqlx::query_as(
-            r#"
-            UPDATE password_reset_tokens
-            SET attempts = attempts + 1
-            WHERE email = $1
-            RETURNING attempts
-            "#,
-        )
-        .bind(email)
-        .fetch_one(&self.pool)
-        .await?;
+        user_id: Uuid,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<LoginHistoryEntry>, i64), sqlx::Error> {
+        let offset = (page - 1) * per_page;
 
-        Ok(row.0)
-    }
+        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM login_history WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(self.reader())
+            .await?;
 
-    /// Mark a password reset token as used
-    pub async fn mark_password_reset_used(&self, email: &str) -> Result<(), sqlx::Error> {
-        sqlx::query(
+        let entries = sqlx::query_as::<_, LoginHistoryEntry>(
             r#"
-            UPDATE password_reset_tokens
-            SET used = true
-            WHERE email = $1
+            SELECT id, success, failure_reason, ip_address, user_agent, device, country, created_at
+            FROM login_history
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
             "#,
         )
-        .bind(email)
-        .execute(&self.pool)
+        .bind(user_id)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(self.reader())
         .await?;
 
-        Ok(())
+        Ok((entries, total.0))
     }
 
     /// Update user password
@@ -630,16 +868,18 @@ impl Database {
         user_id: Uuid,
         password_hash: &str,
         salt: &str,
+        pepper_version: i32,
     ) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
             UPDATE users
-            SET password_hash = $1, salt = $2, updated_at = $3
-            WHERE id = $4
+            SET password_hash = $1, salt = $2, pepper_version = $3, updated_at = $4
+            WHERE id = $5
             "#,
         )
         .bind(password_hash)
         .bind(salt)
+        .bind(pepper_version)
         .bind(Utc::now())
         .bind(user_id)
         .execute(&self.pool)
@@ -648,26 +888,39 @@ impl Database {
         Ok(())
     }
 
-    /// Clean up expired email verification tokens
-    pub async fn cleanup_expired_verification_tokens(&self) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    /// Replace a hash made with a retired pepper. Does nothing if the
+    /// password changed since it was read, so a reset is never overwritten.
+    pub async fn upgrade_password_hash(
+        &self,
+        user_id: Uuid,
+        old_hash: &str,
+        password_hash: &str,
+        salt: &str,
+        pepper_version: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
             r#"
-            DELETE FROM email_verification_tokens
-            WHERE expires_at < $1
+            UPDATE users
+            SET password_hash = $1, salt = $2, pepper_version = $3
+            WHERE id = $4 AND password_hash = $5
             "#,
         )
-        .bind(Utc::now())
+        .bind(password_hash)
+        .bind(salt)
+        .bind(pepper_version)
+        .bind(user_id)
+        .bind(old_hash)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
-    /// Clean up expired password reset tokens
-    pub async fn cleanup_expired_reset_tokens(&self) -> Result<(), sqlx::Error> {
+    /// Clean up expired email verification tokens
+    pub async fn cleanup_expired_verification_tokens(&self) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            DELETE FROM password_reset_tokens
+            DELETE FROM email_verification_tokens
             WHERE expires_at < $1
             "#,
         )
@@ -708,7 +961,7 @@ impl Database {
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(admin)
@@ -737,18 +990,46 @@ impl Database {
         Ok(admin)
     }
 
-    /// Demote an admin (remove admin privileges)
-    pub async fn demote_admin(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
-        sqlx::query(
+    /// Demote an admin (remove admin privileges), unless they are the last
+    /// one. Admin rows are locked while counting, so two demotions made at
+    /// once cannot both pass the check.
+    pub async fn demote_admin(&self, user_id: Uuid) -> Result<Demotion, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let admins: Vec<(Uuid,)> = sqlx::query_as("SELECT user_id FROM admin_users FOR UPDATE")
+            .fetch_all(&mut *tx)
+            .await?;
+        if !admins.iter().any(|(admin,)| *admin == user_id) {
+            return Ok(Demotion::NotAdmin);
+        }
+        if admins.len() <= 1 {
+            return Ok(Demotion::LastAdmin);
+        }
+
+        let result = sqlx::query(
             r#"
             DELETE FROM admin_users WHERE user_id = $1
             "#,
         )
         .bind(user_id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+        tx.commit().await?;
 
-        Ok(())
+        Ok(if result.rows_affected() > 0 {
+            Demotion::Demoted
+        } else {
+            Demotion::NotAdmin
+        })
+    }
+
+    /// Number of admins, read from the primary so a demotion just made is
+    /// counted
+    pub async fn count_admins(&self) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM admin_users")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count.0)
     }
 
     /// List all users with pagination and admin status
@@ -762,10 +1043,10 @@ impl Database {
         // Get total count
         let total: (i64,) = sqlx::query_as(
             r#"
-            SELECT COUNT(*) FROM users
+            SELECT COUNT(*) FROM users WHERE deleted_at IS NULL
             "#,
         )
-        .fetch_one(&self.pool)
+        .fetch_one(self.reader())
         .await?;
 
         // Get users with admin status
@@ -790,13 +1071,14 @@ impl Database {
                 CASE WHEN a.user_id IS NOT NULL THEN true ELSE false END as is_admin
             FROM users u
             LEFT JOIN admin_users a ON u.id = a.user_id
+            WHERE u.deleted_at IS NULL
             ORDER BY u.created_at DESC
             LIMIT $1 OFFSET $2
             "#,
         )
         .bind(per_page as i64)
         .bind(offset as i64)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         let user_responses: Vec<crate::models::UserListResponse> = users
@@ -840,13 +1122,493 @@ impl Database {
             ORDER BY created_at DESC
             "#,
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok(admins)
     }
 }
 
+// Role-related methods
+impl Database {
+    /// Roles held by a user, as stored in `user_roles.role`
+    pub async fn list_user_roles(&self, user_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT role FROM user_roles WHERE user_id = $1 ORDER BY role
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Every role assignment with the holder's username
+    pub async fn list_role_assignments(
+        &self,
+    ) -> Result<Vec<crate::models::RoleAssignment>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::RoleAssignment>(
+            r#"
+            SELECT r.user_id, u.username, r.role, r.granted_by, r.created_at
+            FROM user_roles r
+            JOIN users u ON r.user_id = u.id
+            ORDER BY r.role, u.username
+            "#,
+        )
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Grant a role. Returns false if the user already held it.
+    pub async fn grant_role(
+        &self,
+        user_id: Uuid,
+        role: Role,
+        granted_by: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_roles (user_id, role, granted_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, role) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(role.as_str())
+        .bind(granted_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke a role. Returns false if the user did not hold it.
+    pub async fn revoke_role(&self, user_id: Uuid, role: Role) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_roles WHERE user_id = $1 AND role = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(role.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// API keys
+impl Database {
+    /// Store a new key by its hash
+    pub async fn create_api_key(
+        &self,
+        name: &str,
+        key_prefix: &str,
+        key_hash: &str,
+        scopes: &[ApiKeyScope],
+        created_by: Uuid,
+    ) -> Result<crate::models::ApiKey, sqlx::Error> {
+        let scopes: Vec<&str> = scopes.iter().map(ApiKeyScope::as_str).collect();
+        sqlx::query_as::<_, crate::models::ApiKey>(
+            r#"
+            INSERT INTO api_keys (name, key_prefix, key_hash, scopes, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, name, key_prefix, scopes, created_by, created_at,
+                      last_used_at, revoked_at
+            "#,
+        )
+        .bind(name)
+        .bind(key_prefix)
+        .bind(key_hash)
+        .bind(&scopes)
+        .bind(created_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Every key, live ones first, newest first
+    pub async fn list_api_keys(&self) -> Result<Vec<crate::models::ApiKey>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ApiKey>(
+            r#"
+            SELECT id, name, key_prefix, scopes, created_by, created_at,
+                   last_used_at, revoked_at
+            FROM api_keys
+            ORDER BY revoked_at IS NOT NULL, created_at DESC
+            "#,
+        )
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Revoke a key. Returns false if there is no live key with this id.
+    pub async fn revoke_api_key(&self, key_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys SET revoked_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(key_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// Policies
+impl Database {
+    /// The latest version of each policy
+    pub async fn latest_policies(&self) -> Result<Vec<PolicyDocument>, sqlx::Error> {
+        sqlx::query_as::<_, PolicyDocument>(
+            r#"
+            SELECT DISTINCT ON (kind)
+                id, kind, version, title, body, published_by, published_at
+            FROM policy_documents
+            ORDER BY kind, version DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Get a policy version by ID
+    pub async fn find_policy(
+        &self,
+        policy_id: Uuid,
+    ) -> Result<Option<PolicyDocument>, sqlx::Error> {
+        sqlx::query_as::<_, PolicyDocument>(
+            r#"
+            SELECT id, kind, version, title, body, published_by, published_at
+            FROM policy_documents
+            WHERE id = $1
+            "#,
+        )
+        .bind(policy_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Publish the next version of a policy
+    pub async fn publish_policy(
+        &self,
+        kind: PolicyKind,
+        title: &str,
+        body: &str,
+        published_by: Uuid,
+    ) -> Result<PolicyDocument, sqlx::Error> {
+        sqlx::query_as::<_, PolicyDocument>(
+            r#"
+            INSERT INTO policy_documents (kind, version, title, body, published_by)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4
+            FROM policy_documents
+            WHERE kind = $1
+            RETURNING id, kind, version, title, body, published_by, published_at
+            "#,
+        )
+        .bind(kind.as_str())
+        .bind(title)
+        .bind(body)
+        .bind(published_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Latest policy versions the user has not accepted
+    pub async fn pending_policies(&self, user_id: Uuid) -> Result<Vec<PendingPolicy>, sqlx::Error> {
+        sqlx::query_as::<_, PendingPolicy>(
+            r#"
+            SELECT id, kind, version, title
+            FROM (
+                SELECT DISTINCT ON (kind) id, kind, version, title
+                FROM policy_documents
+                ORDER BY kind, version DESC
+            ) latest
+            WHERE NOT EXISTS (
+                SELECT 1 FROM policy_acceptances pa
+                WHERE pa.policy_id = latest.id AND pa.user_id = $1
+            )
+            ORDER BY kind
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record that the user accepted a policy version. Accepting again
+    /// keeps the first acceptance; returns when it was made.
+    pub async fn accept_policy(
+        &self,
+        user_id: Uuid,
+        policy_id: Uuid,
+        client: &ClientInfo,
+    ) -> Result<DateTime<Utc>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO policy_acceptances (user_id, policy_id, ip_address, user_agent)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, policy_id)
+                DO UPDATE SET accepted_at = policy_acceptances.accepted_at
+            RETURNING accepted_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(policy_id)
+        .bind(&client.ip_address)
+        .bind(&client.user_agent)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Every policy version the user has accepted, newest first
+    pub async fn list_user_policy_acceptances(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<PolicyAcceptance>, sqlx::Error> {
+        sqlx::query_as::<_, PolicyAcceptance>(
+            r#"
+            SELECT pa.policy_id, pd.kind, pd.version, pa.accepted_at
+            FROM policy_acceptances pa
+            JOIN policy_documents pd ON pa.policy_id = pd.id
+            WHERE pa.user_id = $1
+            ORDER BY pa.accepted_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// A page of the acceptances of a policy version, oldest first, and
+    /// the total
+    pub async fn list_policy_acceptances(
+        &self,
+        policy_id: Uuid,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<PolicyAcceptanceRecord>, i64), sqlx::Error> {
+        let offset = (page - 1) * per_page;
+
+        let total: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM policy_acceptances WHERE policy_id = $1")
+                .bind(policy_id)
+                .fetch_one(self.reader())
+                .await?;
+
+        let records = sqlx::query_as::<_, PolicyAcceptanceRecord>(
+            r#"
+            SELECT pa.user_id, u.username, pa.ip_address, pa.user_agent, pa.accepted_at
+            FROM policy_acceptances pa
+            JOIN users u ON pa.user_id = u.id
+            WHERE pa.policy_id = $1
+            ORDER BY pa.accepted_at
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(policy_id)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(self.reader())
+        .await?;
+
+        Ok((records, total.0))
+    }
+}
+
+// Notification preferences
+impl Database {
+    /// The user's notification preferences, defaults filled in
+    pub async fn get_notification_preferences(
+        &self,
+        user_id: Uuid,
+    ) -> Result<PreferenceMatrix, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String, bool)>(
+            r#"
+            SELECT category, channel, enabled
+            FROM notification_preferences
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await?;
+
+        // The table only holds values the enums parse; anything else is skipped
+        Ok(preference_matrix(rows.into_iter().filter_map(
+            |(category, channel, enabled)| {
+                Some((category.parse().ok()?, channel.parse().ok()?, enabled))
+            },
+        )))
+    }
+
+    pub async fn get_requirements(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<MemberRequirements>, sqlx::Error> {
+        sqlx::query_as::<_, MemberRequirements>(
+            "SELECT dietary, accessibility, updated_at FROM member_requirements WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Replace a member's requirements; clearing both forgets them entirely
+    pub async fn set_requirements(
+        &self,
+        user_id: Uuid,
+        dietary: Option<&str>,
+        accessibility: Option<&str>,
+    ) -> Result<MemberRequirements, sqlx::Error> {
+        if dietary.is_none() && accessibility.is_none() {
+            sqlx::query("DELETE FROM member_requirements WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+            return Ok(MemberRequirements::default());
+        }
+
+        sqlx::query_as::<_, MemberRequirements>(
+            r#"
+            INSERT INTO member_requirements (user_id, dietary, accessibility)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+                SET dietary = EXCLUDED.dietary, accessibility = EXCLUDED.accessibility,
+                    updated_at = NOW()
+            RETURNING dietary, accessibility, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(dietary)
+        .bind(accessibility)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Set the given categories and channels, leaving the rest as they are
+    pub async fn update_notification_preferences(
+        &self,
+        user_id: Uuid,
+        changes: &PreferenceMatrix,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (category, channels) in changes {
+            for (channel, enabled) in channels {
+                sqlx::query(
+                    r#"
+                    INSERT INTO notification_preferences (user_id, category, channel, enabled)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (user_id, category, channel)
+                        DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+                    "#,
+                )
+                .bind(user_id)
+                .bind(category.as_str())
+                .bind(channel.as_str())
+                .bind(enabled)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await
+    }
+}
+
+// Dashboard statistics
+impl Database {
+    /// Account totals and recent sign-ups. Logging in or refreshing a session
+    /// issues a refresh token, so recent tokens stand in for recent activity.
+    pub async fn user_stats(&self) -> Result<crate::models::UserStats, sqlx::Error> {
+        let (total_users, verified_users): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*), COUNT(*) FILTER (WHERE email_verified) FROM users
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let active_users: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(DISTINCT user_id) FROM refresh_tokens
+            WHERE created_at > NOW() - make_interval(days => $1)
+            "#,
+        )
+        .bind(ACTIVE_DAYS)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let registrations_per_week = sqlx::query_as::<_, (DateTime<Utc>, i64)>(
+            r#"
+            SELECT w.period, COUNT(u.id)
+            FROM generate_series(
+                date_trunc('week', NOW()) - make_interval(weeks => $1 - 1),
+                date_trunc('week', NOW()),
+                INTERVAL '1 week'
+            ) AS w(period)
+            LEFT JOIN users u ON date_trunc('week', u.created_at) = w.period
+            GROUP BY w.period
+            ORDER BY w.period
+            "#,
+        )
+        .bind(STATS_WEEKS)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(PeriodCount::from)
+        .collect();
+
+        Ok(crate::models::UserStats {
+            total_users,
+            verified_users,
+            active_users,
+            registrations_per_week,
+        })
+    }
+}
+
+async fn lift_active_suspension(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    admin_id: Uuid,
+) -> Result<Option<Suspension>, sqlx::Error> {
+    sqlx::query_as::<_, Suspension>(
+        r#"
+        UPDATE account_suspensions
+        SET lifted_at = NOW(), lifted_by = $2
+        WHERE user_id = $1 AND lifted_at IS NULL AND (ends_at IS NULL OR ends_at > NOW())
+        RETURNING id, user_id, reason, suspended_by, created_at, ends_at, lifted_at, lifted_by
+        "#,
+    )
+    .bind(user_id)
+    .bind(admin_id)
+    .fetch_optional(conn)
+    .await
+}
+
+async fn insert_account_audit(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    admin_id: Uuid,
+    action: AccountAction,
+    note: Option<&str>,
+) -> Result<AccountAuditEntry, sqlx::Error> {
+    sqlx::query_as::<_, AccountAuditEntry>(
+        r#"
+        INSERT INTO account_audit (user_id, admin_id, action, note)
+        VALUES ($1, $2, $3, $4)
+        RETURNING id, user_id, admin_id, action, note, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(admin_id)
+    .bind(action.as_str())
+    .bind(note)
+    .fetch_one(conn)
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -888,6 +1650,7 @@ mod tests {
                 email: &email,
                 password_hash,
                 salt,
+                pepper_version: 1,
                 reg_number: &reg_number,
                 year_joined: 2023,
                 phone_number: &phone_number,
@@ -925,6 +1688,7 @@ mod tests {
                 email: &email,
                 password_hash,
                 salt,
+                pepper_version: 1,
                 reg_number: &reg_number,
                 year_joined: 2023,
                 phone_number: &phone_number,
@@ -958,6 +1722,7 @@ mod tests {
                 email: &email,
                 password_hash: "hash",
                 salt: "salt",
+                pepper_version: 1,
                 reg_number: &reg_number,
                 year_joined: 2023,
                 phone_number: &phone_number,
@@ -968,7 +1733,7 @@ mod tests {
         let token_hash = format!("token_hash_{}", Uuid::new_v4());
         let expires_at = Utc::now() + Duration::hours(1);
 
-        db.store_refresh_token(user.id, &token_hash, expires_at)
+        db.store_refresh_token(user.id, &token_hash, expires_at, &ClientInfo::default())
             .await
             .unwrap();
 
@@ -998,6 +1763,7 @@ mod tests {
                 email: &email,
                 password_hash: "hash",
                 salt: "salt",
+                pepper_version: 1,
                 reg_number: &reg_number,
                 year_joined: 2023,
                 phone_number: &phone_number,
@@ -1008,7 +1774,7 @@ mod tests {
         let token_hash = format!("token_hash_{}", Uuid::new_v4());
         let expires_at = Utc::now() + Duration::hours(1);
 
-        db.store_refresh_token(user.id, &token_hash, expires_at)
+        db.store_refresh_token(user.id, &token_hash, expires_at, &ClientInfo::default())
             .await
             .unwrap();
         db.delete_refresh_token(&token_hash).await.unwrap();
@@ -1016,45 +1782,4 @@ mod tests {
         let found_token = db.find_refresh_token(&token_hash).await.unwrap();
         assert!(found_token.is_none());
     }
-
-    #[tokio::test]
-    #[ignore]
-    async fn test_create_and_validate_csrf_token() {
-        let db = setup_test_db().await;
-
-        // Create a user first to satisfy foreign key constraint
-        let username = format!("testuser_{}", Uuid::new_v4());
-        let email = format!("test_{}@example.com", Uuid::new_v4());
-        // Generate unique reg_number (20 + 5 digits from random number)
-        let random_suffix: u32 = rand::random::<u32>() % 100000;
-        let reg_number = format!("20{:05}", random_suffix);
-        // Generate unique phone number
-        let phone_suffix: u32 = rand::random::<u32>() % 1000000000;
-        let phone_number = format!("+92{:010}", phone_suffix);
-
-        let user = db
-            .create_user(CreateUserParams {
-                username: &username,
-                email: &email,
-                password_hash: "hash",
-                salt: "salt",
-                reg_number: &reg_number,
-                year_joined: 2023,
-                phone_number: &phone_number,
-            })
-            .await
-            .unwrap();
-
-        let token = format!("csrf_token_{}", Uuid::new_v4());
-        let user_id = Some(user.id);
-        let expiry_seconds = 3600;
-
-        db.create_csrf_token(&token, user_id, expiry_seconds)
-            .await
-            .unwrap();
-
-        let found_token = db.validate_csrf_token(&token).await.unwrap();
-        assert!(found_token.is_some());
-        assert_eq!(found_token.unwrap().user_id, user_id);
-    }
 }
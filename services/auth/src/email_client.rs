@@ -1,105 +1,333 @@
+use async_trait::async_trait;
+use common::{mail::SmtpSettings, Locale};
+use lettre::{message::Mailbox, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
 use serde::Serialize;
-use std::error::Error;
+use std::{error::Error, fmt, str::FromStr, sync::Arc, sync::Mutex};
 
-#[derive(Debug, Serialize)]
-struct SendVerificationEmailRequest {
-    to_email: String,
-    username: String,
-    otp: String,
-}
+use crate::config::Config;
+use crate::login_history::ClientInfo;
 
-#[derive(Debug, Serialize)]
-struct SendPasswordResetEmailRequest {
-    to_email: String,
-    username: String,
-    otp: String,
+pub type EmailResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// The transactional emails the auth service sends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailKind {
+    Verification {
+        otp: String,
+    },
+    PasswordReset {
+        otp: String,
+    },
+    /// Sent to the new address when a member changes their email
+    EmailChange {
+        otp: String,
+    },
+    Welcome,
+    /// A successful login from a device or country not seen before
+    NewLogin {
+        device: String,
+        ip_address: Option<String>,
+        country: Option<String>,
+    },
 }
 
-#[derive(Debug, Serialize)]
-struct SendWelcomeEmailRequest {
-    to_email: String,
-    username: String,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to_email: String,
+    pub username: String,
+    pub kind: EmailKind,
+    /// Language of the request that triggered the email
+    pub locale: Locale,
 }
 
-// #[derive(Debug, Deserialize)]
-// struct EmailResponse {
-//     success: bool,
-//     email_id: Option<String>,
-// }
+impl Email {
+    fn key(&self) -> &'static str {
+        match self.kind {
+            EmailKind::Verification { .. } => "email.verification",
+            EmailKind::PasswordReset { .. } => "email.password_reset",
+            EmailKind::EmailChange { .. } => "email.email_change",
+            EmailKind::Welcome => "email.welcome",
+            EmailKind::NewLogin { .. } => "email.new_login",
+        }
+    }
 
-pub struct EmailClient {
-    base_url: String,
-    api_key: String,
-    client: reqwest::Client,
-}
+    pub fn subject(&self) -> String {
+        self.locale
+            .text(&format!("{}.subject", self.key()))
+            .to_string()
+    }
 
-impl EmailClient {
-    pub fn new(base_url: String, api_key: String) -> Self {
-        Self {
-            base_url,
-            api_key,
-            client: reqwest::Client::new(),
+    /// Plain-text body used by backends that render emails themselves
+    pub fn body(&self) -> String {
+        let key = format!("{}.body", self.key());
+        match &self.kind {
+            EmailKind::Verification { otp }
+            | EmailKind::PasswordReset { otp }
+            | EmailKind::EmailChange { otp } => self
+                .locale
+                .format(&key, &[("username", &self.username), ("otp", otp)]),
+            EmailKind::Welcome => self.locale.format(&key, &[("username", &self.username)]),
+            EmailKind::NewLogin {
+                device,
+                ip_address,
+                country,
+            } => {
+                let mut origin = match ip_address {
+                    Some(ip) => self.locale.format(
+                        "email.new_login.origin_ip",
+                        &[("device", device), ("ip", ip)],
+                    ),
+                    None => device.clone(),
+                };
+                if let Some(country) = country {
+                    origin.push_str(&format!(" ({})", country));
+                }
+                self.locale
+                    .format(&key, &[("username", &self.username), ("origin", &origin)])
+            }
         }
     }
+}
 
-    pub async fn send_verification_email(
+// ============================================================================
+// Client trait
+// ============================================================================
+
+/// Sends auth emails. Handlers only see this trait, so tests can inject
+/// `LogEmailClient` and inspect what would have been sent.
+#[async_trait]
+pub trait EmailClient: Send + Sync {
+    async fn send(&self, email: Email) -> EmailResult;
+
+    async fn send_verification_email(
         &self,
         to_email: &str,
         username: &str,
         otp: &str,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let request = SendVerificationEmailRequest {
+        locale: Locale,
+    ) -> EmailResult {
+        self.send(Email {
             to_email: to_email.to_string(),
             username: username.to_string(),
-            otp: otp.to_string(),
-        };
+            kind: EmailKind::Verification {
+                otp: otp.to_string(),
+            },
+            locale,
+        })
+        .await
+    }
 
-        self.send_email_request("/api/send-verification-email", &request)
-            .await
+    async fn send_password_reset_email(
+        &self,
+        to_email: &str,
+        username: &str,
+        otp: &str,
+        locale: Locale,
+    ) -> EmailResult {
+        self.send(Email {
+            to_email: to_email.to_string(),
+            username: username.to_string(),
+            kind: EmailKind::PasswordReset {
+                otp: otp.to_string(),
+            },
+            locale,
+        })
+        .await
     }
 
-    pub async fn send_password_reset_email(
+    async fn send_email_change_email(
         &self,
         to_email: &str,
         username: &str,
         otp: &str,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let request = SendPasswordResetEmailRequest {
+        locale: Locale,
+    ) -> EmailResult {
+        self.send(Email {
             to_email: to_email.to_string(),
             username: username.to_string(),
-            otp: otp.to_string(),
-        };
+            kind: EmailKind::EmailChange {
+                otp: otp.to_string(),
+            },
+            locale,
+        })
+        .await
+    }
 
-        self.send_email_request("/api/send-password-reset-email", &request)
-            .await
+    async fn send_welcome_email(
+        &self,
+        to_email: &str,
+        username: &str,
+        locale: Locale,
+    ) -> EmailResult {
+        self.send(Email {
+            to_email: to_email.to_string(),
+            username: username.to_string(),
+            kind: EmailKind::Welcome,
+            locale,
+        })
+        .await
     }
 
-    pub async fn send_welcome_email(
+    async fn send_new_login_email(
         &self,
         to_email: &str,
         username: &str,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let request = SendWelcomeEmailRequest {
+        client: &ClientInfo,
+        locale: Locale,
+    ) -> EmailResult {
+        self.send(Email {
             to_email: to_email.to_string(),
             username: username.to_string(),
-        };
+            kind: EmailKind::NewLogin {
+                device: client.device(),
+                ip_address: client.ip_address.clone(),
+                country: client.country.clone(),
+            },
+            locale,
+        })
+        .await
+    }
+}
 
-        self.send_email_request("/api/send-welcome-email", &request)
-            .await
+// ============================================================================
+// Backend selection
+// ============================================================================
+
+/// Which `EmailClient` implementation to use (`EMAIL_BACKEND`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmailBackend {
+    /// POST to the email service (`EMAIL_SERVICE_URL`)
+    #[default]
+    Http,
+    /// Send directly through an SMTP relay
+    Smtp,
+    /// Log emails instead of sending them (local development)
+    Log,
+}
+
+impl FromStr for EmailBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "http" => Ok(EmailBackend::Http),
+            "smtp" => Ok(EmailBackend::Smtp),
+            "log" => Ok(EmailBackend::Log),
+            other => Err(format!(
+                "Invalid EMAIL_BACKEND '{}': expected http, smtp or log",
+                other
+            )),
+        }
     }
+}
+
+impl fmt::Display for EmailBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmailBackend::Http => write!(f, "http"),
+            EmailBackend::Smtp => write!(f, "smtp"),
+            EmailBackend::Log => write!(f, "log"),
+        }
+    }
+}
+
+/// Build the email client selected by `EMAIL_BACKEND`
+pub fn from_config(config: &Config) -> Result<Arc<dyn EmailClient>, String> {
+    Ok(match config.email_backend {
+        EmailBackend::Http => Arc::new(HttpEmailClient::new(
+            config.email_service_url.clone(),
+            config.email_service_api_key.clone(),
+        )),
+        EmailBackend::Smtp => Arc::new(SmtpEmailClient::new(&config.smtp)?),
+        EmailBackend::Log => Arc::new(LogEmailClient::new()),
+    })
+}
+
+// ============================================================================
+// HTTP backend (email service)
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+struct SendEmailRequest<'a> {
+    to_email: &'a str,
+    username: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    otp: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_address: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    country: Option<&'a str>,
+    #[serde(skip_serializing_if = "Locale::is_default")]
+    locale: Locale,
+}
+
+pub struct HttpEmailClient {
+    base_url: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl HttpEmailClient {
+    pub fn new(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl EmailClient for HttpEmailClient {
+    async fn send(&self, email: Email) -> EmailResult {
+        let mut request = SendEmailRequest {
+            to_email: &email.to_email,
+            username: &email.username,
+            otp: None,
+            device: None,
+            ip_address: None,
+            country: None,
+            locale: email.locale,
+        };
+        let endpoint = match &email.kind {
+            EmailKind::Verification { otp } => {
+                request.otp = Some(otp);
+                "/api/send-verification-email"
+            }
+            EmailKind::PasswordReset { otp } => {
+                request.otp = Some(otp);
+                "/api/send-password-reset-email"
+            }
+            EmailKind::EmailChange { otp } => {
+                request.otp = Some(otp);
+                "/api/send-email-change-email"
+            }
+            EmailKind::Welcome => "/api/send-welcome-email",
+            EmailKind::NewLogin {
+                device,
+                ip_address,
+                country,
+            } => {
+                request.device = Some(device);
+                request.ip_address = ip_address.as_deref();
+                request.country = country.as_deref();
+                "/api/send-new-login-email"
+            }
+        };
 
-    async fn send_email_request<T: Serialize>(
-        &self,
-        endpoint: &str,
-        request: &T,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
         let url = format!("{}{}", self.base_url, endpoint);
 
         let response = self
             .client
             .post(&url)
             .header("X-API-Key", &self.api_key)
-            .json(request)
+            .json(&request)
             .send()
             .await?;
 
@@ -111,3 +339,175 @@ impl EmailClient {
         Ok(())
     }
 }
+
+// ============================================================================
+// SMTP backend
+// ============================================================================
+
+pub struct SmtpEmailClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpEmailClient {
+    pub fn new(settings: &SmtpSettings) -> Result<Self, String> {
+        Ok(Self {
+            transport: settings.transport()?,
+            from: settings.sender()?,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailClient for SmtpEmailClient {
+    async fn send(&self, email: Email) -> EmailResult {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email.to_email.parse()?)
+            .subject(email.subject())
+            .body(email.body())?;
+
+        self.transport.send(message).await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Log backend (development and tests)
+// ============================================================================
+
+/// Logs emails instead of sending them and keeps a copy of each, so local
+/// development never needs an email provider and tests can read the OTPs.
+#[derive(Default)]
+pub struct LogEmailClient {
+    sent: Mutex<Vec<Email>>,
+}
+
+impl LogEmailClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every email "sent" so far, oldest first
+    pub fn sent(&self) -> Vec<Email> {
+        self.sent
+            .lock()
+            .map(|sent| sent.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl EmailClient for LogEmailClient {
+    async fn send(&self, email: Email) -> EmailResult {
+        tracing::info!(
+            "Email not sent (EMAIL_BACKEND=log)\nTo: {}\nSubject: {}\n\n{}",
+            email.to_email,
+            email.subject(),
+            email.body()
+        );
+
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push(email);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::mail::SmtpTls;
+
+    #[test]
+    fn test_backend_from_str() {
+        assert_eq!("http".parse::<EmailBackend>(), Ok(EmailBackend::Http));
+        assert_eq!(" SMTP ".parse::<EmailBackend>(), Ok(EmailBackend::Smtp));
+        assert_eq!("log".parse::<EmailBackend>(), Ok(EmailBackend::Log));
+        assert!("carrier-pigeon".parse::<EmailBackend>().is_err());
+    }
+
+    #[test]
+    fn test_http_request_shape() {
+        let with_otp = SendEmailRequest {
+            to_email: "a@example.com",
+            username: "alice",
+            otp: Some("123456"),
+            device: None,
+            ip_address: None,
+            country: None,
+            locale: Locale::En,
+        };
+        let without_otp = SendEmailRequest {
+            otp: None,
+            ..with_otp
+        };
+        let new_login = SendEmailRequest {
+            otp: None,
+            device: Some("Firefox on Linux"),
+            ip_address: Some("203.0.113.7"),
+            locale: Locale::Ur,
+            ..with_otp
+        };
+
+        assert_eq!(
+            serde_json::to_value(&with_otp).unwrap(),
+            serde_json::json!({"to_email": "a@example.com", "username": "alice", "otp": "123456"})
+        );
+        assert_eq!(
+            serde_json::to_value(&without_otp).unwrap(),
+            serde_json::json!({"to_email": "a@example.com", "username": "alice"})
+        );
+        assert_eq!(
+            serde_json::to_value(&new_login).unwrap(),
+            serde_json::json!({
+                "to_email": "a@example.com",
+                "username": "alice",
+                "device": "Firefox on Linux",
+                "ip_address": "203.0.113.7",
+                "locale": "ur"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_client_records_emails() {
+        let client = LogEmailClient::new();
+        client
+            .send_verification_email("a@example.com", "alice", "123456", Locale::En)
+            .await
+            .unwrap();
+        client
+            .send_welcome_email("a@example.com", "alice", Locale::Ur)
+            .await
+            .unwrap();
+
+        let sent = client.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(
+            sent[0].kind,
+            EmailKind::Verification {
+                otp: "123456".to_string()
+            }
+        );
+        assert!(sent[0].body().contains("123456"));
+        assert_eq!(sent[1].subject(), "Tabrela میں خوش آمدید");
+        assert!(sent[1].body().contains("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_smtp_client_requires_host() {
+        let mut settings = SmtpSettings {
+            host: None,
+            port: 587,
+            username: None,
+            password: None,
+            from: "Tabrela <no-reply@example.com>".to_string(),
+            tls: SmtpTls::StartTls,
+        };
+        assert!(SmtpEmailClient::new(&settings).is_err());
+
+        settings.host = Some("smtp.example.com".to_string());
+        assert!(SmtpEmailClient::new(&settings).is_ok());
+    }
+}
@@ -0,0 +1,72 @@
+//! One-time codes emailed to prove control of an address. Each purpose
+//! (registration, email change, account recovery) has its own live code
+//! per user and its own lifetime, while the throttling below is shared.
+//! Members can ask for registration and recovery codes by SMS instead.
+
+use serde::Deserialize;
+use std::fmt;
+
+use crate::config::Config;
+
+/// Wrong guesses allowed before a code must be requested again
+pub const MAX_OTP_ATTEMPTS: i32 = 5;
+
+/// Minimum wait before another code for the same purpose is sent
+pub const OTP_RESEND_INTERVAL_SECS: i64 = 60;
+
+/// What a verification code proves control of an address for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationPurpose {
+    /// Confirming the address given at registration
+    Registration,
+    /// Moving an account to a new address; the code goes to the new one
+    EmailChange,
+    /// Regaining access by resetting the password
+    AccountRecovery,
+}
+
+impl VerificationPurpose {
+    /// Value stored in `email_verification_tokens.purpose`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationPurpose::Registration => "registration",
+            VerificationPurpose::EmailChange => "email_change",
+            VerificationPurpose::AccountRecovery => "account_recovery",
+        }
+    }
+
+    /// How long a code for this purpose stays valid, in seconds
+    pub fn expiry(&self, config: &Config) -> i64 {
+        match self {
+            VerificationPurpose::Registration => config.email_verification_expiry,
+            VerificationPurpose::EmailChange => config.email_change_expiry,
+            VerificationPurpose::AccountRecovery => config.password_reset_expiry,
+        }
+    }
+}
+
+impl fmt::Display for VerificationPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a code reaches the member, chosen per request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OtpChannel {
+    #[default]
+    Email,
+    /// To the phone number given at registration
+    Sms,
+}
+
+impl OtpChannel {
+    /// Where to tell the member to look for the code
+    pub fn destination(&self) -> &'static str {
+        match self {
+            OtpChannel::Email => "email",
+            OtpChannel::Sms => "phone",
+        }
+    }
+}
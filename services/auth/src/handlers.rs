@@ -1,113 +1,262 @@
 use axum::{
-    extract::{Extension, State},
-    http::StatusCode,
+    extract::{Extension, Path, State},
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use chrono::{Duration, Utc};
+use common::{
+    api_keys::{hash_api_key, API_KEY_DISPLAY_LEN, API_KEY_PREFIX},
+    auth_middleware::{suspension_key, SUSPENSION_CACHE_TTL},
+    csrf::cookie_value,
+    error::{api_error, db_error},
+    features::Feature,
+    maintenance,
+    pending_actions::{self, ProtectedAction},
+    preferences::PreferenceMatrix,
+    Locale, Pagination,
+};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::{Validate, ValidationErrors};
 
 use crate::{
-    csrf::create_csrf_token,
-    database::CreateUserParams,
+    csrf::{csrf_cookie_headers, issue_csrf_token, request_session},
+    database::{CreateUserParams, Demotion},
+    email_verification::{
+        OtpChannel, VerificationPurpose, MAX_OTP_ATTEMPTS, OTP_RESEND_INTERVAL_SECS,
+    },
+    login_history::{ClientInfo, LoginFailure},
     models::{
-        AuthResponse, LoginRequest, RefreshTokenRequest, RegisterRequest,
-        RequestPasswordResetRequest, ResendVerificationRequest, ResetPasswordRequest, UserResponse,
-        VerifyEmailRequest,
+        AccountAction, AdminResendVerificationRequest, AuthResponse, ChangeEmailRequest,
+        ChangeUsernameRequest, ConfirmEmailChangeRequest, DeleteAccountRequest, LoginRequest,
+        RefreshTokenRequest, RegisterRequest, RequestPasswordResetRequest,
+        ResendVerificationRequest, ResetPasswordRequest, SessionResponse, SetFeatureRequest,
+        StartMaintenanceRequest, SuspendUserRequest, Suspension, UpdateRequirementsRequest, User,
+        UserResponse, VerifyEmailRequest, DELETED_MEMBER_PREFIX,
     },
-    security::{self, hash_password, verify_password},
+    policies::PendingPolicies,
+    security::{self, hash_password, verify_password_versioned, PasswordCheck},
+    session_cookies::{TokenDelivery, REFRESH_TOKEN_COOKIE},
+    sms_client::Sms,
     AppState,
 };
 
-/// Format validation errors into human-readable messages with expected formats
-fn format_validation_error(errors: &ValidationErrors) -> String {
+/// Format validation errors into human-readable messages with expected
+/// formats, in the client's language
+fn format_validation_error(errors: &ValidationErrors, locale: Locale) -> String {
     let mut messages = Vec::new();
 
     for (field, field_errors) in errors.field_errors() {
         for error in field_errors {
-            let message = match field {
+            let key = match field {
                 "username" => match error.code.as_ref() {
-                    "length" => "Username must be between 3 and 50 characters".to_string(),
-                    _ => "Invalid username format".to_string(),
+                    "length" => "validation.username.length",
+                    _ => "validation.username.format",
                 },
-                "email" => "Invalid email format. Expected: user@example.com".to_string(),
-                "password" => "Password must be at least 8 characters long".to_string(),
-                "reg_number" => "Invalid registration number. Expected format: 20XXXXX (e.g., 2012345)".to_string(),
-                "year_joined" => "Year joined must be between 2000 and 2099. Expected format: 20XX (e.g., 2023)".to_string(),
-                "phone_number" => "Invalid phone number format. Expected: +[country code][number] (e.g., +923001234567)".to_string(),
-                "username_or_email" => "Username or email is required".to_string(),
-                "otp" => "OTP must be exactly 6 digits".to_string(),
-                "new_password" => "New password must be at least 8 characters long".to_string(),
-                _ => format!("Invalid value for field '{}'", field),
+                "email" | "new_email" => "validation.email",
+                "password" => "validation.password",
+                "reg_number" => "validation.reg_number",
+                "year_joined" => "validation.year_joined",
+                "phone_number" => "validation.phone_number",
+                "username_or_email" => "validation.username_or_email",
+                "otp" => "validation.otp",
+                "new_password" => "validation.new_password",
+                "name" => "validation.name",
+                "scopes" => "validation.scopes",
+                "reason" => "validation.reason",
+                "dietary" => "validation.dietary",
+                "accessibility" => "validation.accessibility",
+                "title" => "validation.title",
+                "body" => "validation.body",
+                "message" => "validation.message",
+                _ => {
+                    messages.push(locale.format("validation.field", &[("field", field)]));
+                    continue;
+                }
             };
-            messages.push(message);
+            messages.push(locale.text(key).to_string());
         }
     }
 
     if messages.is_empty() {
-        "Validation error".to_string()
+        locale.text("validation.generic").to_string()
     } else {
         messages.join(". ")
     }
 }
 
-/// Format database errors into human-readable messages
-fn format_database_error(error: &sqlx::Error) -> String {
-    match error {
+/// Format database errors into human-readable messages in the client's
+/// language
+fn format_database_error(error: &sqlx::Error, locale: Locale) -> String {
+    let key = match error {
         sqlx::Error::Database(db_err) => {
             let constraint = db_err.constraint().unwrap_or("");
             let message = db_err.message();
-
-            // Check for constraint violations
-            if constraint.contains("users_username_key") || message.contains("users_username_key") {
-                return "Username already exists. Please choose a different username.".to_string();
-            }
-            if constraint.contains("users_email_key") || message.contains("users_email_key") {
-                return "Email already exists. Please use a different email address.".to_string();
+            let violates = |name: &str| constraint.contains(name) || message.contains(name);
+            let fails_check = |column: &str| message.contains(column) && message.contains("check");
+
+            if violates("users_username_key") {
+                "database.username_taken"
+            } else if violates("users_email_key") {
+                "database.email_taken"
+            } else if violates("users_phone_number_unique") {
+                "database.phone_number_taken"
+            } else if violates("users_reg_number_unique") {
+                "database.reg_number_taken"
+            } else if fails_check("year_joined") {
+                "validation.year_joined"
+            } else if fails_check("reg_number") {
+                "database.reg_number_format"
+            } else if fails_check("phone_number") {
+                "validation.phone_number"
+            } else {
+                "database.error"
             }
-            if constraint.contains("users_phone_number_unique")
-                || message.contains("users_phone_number_unique")
-            {
-                return "Phone number already exists. Please use a different phone number."
-                    .to_string();
+        }
+        _ => "database.error",
+    };
+    locale.text(key).to_string()
+}
+
+/// Reject a new password that breaks the configured policy, listing every
+/// problem so the user can fix them in one go
+async fn enforce_password_policy(
+    state: &AppState,
+    password: &str,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let policy = &state.config.password_policy;
+    let mut problems = policy.violations(password);
+    if problems.is_empty() && policy.is_breached(&state.http_client, password).await {
+        problems.push(
+            "Password has appeared in a known data breach. Please choose a different one"
+                .to_string(),
+        );
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": problems.join(". "), "problems": problems})),
+        ))
+    }
+}
+
+/// Add a login attempt to the user's history. After a successful login from
+/// a device or country the user has not logged in from before, they are
+/// emailed in the background. Failures here never fail the login itself.
+async fn record_login(
+    state: &Arc<AppState>,
+    user: &User,
+    failure: Option<LoginFailure>,
+    client: ClientInfo,
+    locale: Locale,
+) {
+    let unfamiliar = failure.is_none()
+        && state.config.login_alerts
+        && match state.db.login_origins(user.id, &client).await {
+            Ok(origins) => origins.is_unfamiliar(&client),
+            Err(e) => {
+                tracing::warn!("Failed to load login origins for {}: {}", user.id, e);
+                false
             }
-            if constraint.contains("users_reg_number_unique")
-                || message.contains("users_reg_number_unique")
+        };
+
+    if let Err(e) = state.db.record_login(user.id, failure, &client).await {
+        tracing::warn!("Failed to record login for {}: {}", user.id, e);
+    }
+
+    if unfamiliar {
+        let email_client = state.email_client.clone();
+        let (email, username) = (user.email.clone(), user.username.clone());
+        tokio::spawn(async move {
+            if let Err(e) = email_client
+                .send_new_login_email(&email, &username, &client, locale)
+                .await
             {
-                return "Registration number already exists. Please check your registration number.".to_string();
-            }
-            if message.contains("year_joined") && message.contains("check") {
-                return "Year joined must be between 2000 and 2099. Expected format: 20XX (e.g., 2023)".to_string();
-            }
-            if message.contains("reg_number") && message.contains("check") {
-                return "Invalid registration number format. Expected: 20XXXXX (e.g., 2012345)"
-                    .to_string();
-            }
-            if message.contains("phone_number") && message.contains("check") {
-                return "Invalid phone number format. Expected: +[country code][number] (e.g., +923001234567)".to_string();
+                tracing::warn!("Failed to send new login email to {}: {}", username, e);
             }
+        });
+    }
+}
 
-            "Database error occurred. Please try again.".to_string()
-        }
-        _ => "Database error occurred. Please try again.".to_string(),
+/// Check a user's password against the pepper their hash was made with.
+/// A hash made with a retired pepper is replaced in the background, so the
+/// retired pepper can be dropped once every active user has logged in.
+fn check_password(
+    state: &Arc<AppState>,
+    user: &User,
+    password: &str,
+) -> Result<bool, (StatusCode, Json<Value>)> {
+    let check = verify_password_versioned(
+        password,
+        &user.password_hash,
+        user.pepper_version,
+        &state.config.password_peppers,
+    )
+    .map_err(|e| {
+        tracing::error!("Password verification failed for {}: {}", user.id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Password verification failed"})),
+        )
+    })?;
+
+    if check == PasswordCheck::MatchOutdated {
+        let state = state.clone();
+        let (user_id, old_hash, password) =
+            (user.id, user.password_hash.clone(), password.to_string());
+        tokio::spawn(async move {
+            let peppers = &state.config.password_peppers;
+            let upgraded = match hash_password(&password, peppers.current()) {
+                Ok((hash, salt)) => state
+                    .db
+                    .upgrade_password_hash(
+                        user_id,
+                        &old_hash,
+                        &hash,
+                        &salt,
+                        peppers.current_version(),
+                    )
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(e) = upgraded {
+                tracing::warn!("Failed to rehash password for {}: {}", user_id, e);
+            }
+        });
     }
+
+    Ok(check != PasswordCheck::Mismatch)
+}
+
+/// Handler for the password rules new passwords must meet
+pub async fn password_policy(State(state): State<Arc<AppState>>) -> Json<Value> {
+    Json(json!(state.config.password_policy))
 }
 
 /// Handler for user registration
 pub async fn register(
     State(state): State<Arc<AppState>>,
+    locale: Locale,
     Json(payload): Json<RegisterRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
     // Validate input
     payload.validate().map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
+            Json(json!({"error": format_validation_error(&e, locale)})),
         )
     })?;
 
+    require_channel(&state, payload.channel)?;
+    enforce_password_policy(&state, &payload.password).await?;
+
+    reject_reserved_username(&payload.username)?;
+
     // Check if username already exists
     if let Some(existing_user) = state
         .db
@@ -141,6 +290,25 @@ pub async fn register(
             })?;
     }
 
+    // Old links to a renamed member's profile still lead to them
+    if state
+        .db
+        .former_username_owner(&payload.username)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .is_some()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Username already exists"})),
+        ));
+    }
+
     // Check if email already exists
     if let Some(existing_user) =
         state
@@ -240,8 +408,9 @@ pub async fn register(
     }
 
     // Hash password with salt and pepper
-    let (password_hash, salt) = hash_password(&payload.password, &state.config.password_pepper)
-        .map_err(|_| {
+    let peppers = &state.config.password_peppers;
+    let (password_hash, salt) =
+        hash_password(&payload.password, peppers.current()).map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Failed to hash password"})),
@@ -256,6 +425,7 @@ pub async fn register(
             email: &payload.email,
             password_hash: &password_hash,
             salt: &salt,
+            pepper_version: peppers.current_version(),
             reg_number: &payload.reg_number,
             year_joined: payload.year_joined,
             phone_number: &payload.phone_number,
@@ -264,37 +434,41 @@ pub async fn register(
         .map_err(|e| {
             (
                 StatusCode::BAD_REQUEST,
-                Json(json!({"error": format_database_error(&e)})),
-            )
-        })?;
-
-    // Generate OTP for email verification
-    let otp = security::generate_otp();
-    state
-        .db
-        .create_email_verification_otp(user.id, &otp, state.config.email_verification_expiry)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create verification OTP"})),
+                Json(json!({"error": format_database_error(&e, locale)})),
             )
         })?;
 
-    // Send verification email (don't fail registration if email fails)
-    if let Err(e) = state
-        .email_client
-        .send_verification_email(&user.email, &user.username, &otp)
+    // Send verification code (don't fail registration if sending fails)
+    let otp = issue_otp(
+        &state,
+        user.id,
+        VerificationPurpose::Registration,
+        &user.email,
+    )
+    .await?;
+    if let Some(otp) = otp {
+        if let Err(e) = send_otp(
+            &state,
+            &user,
+            VerificationPurpose::Registration,
+            payload.channel,
+            &otp,
+            locale,
+        )
         .await
-    {
-        tracing::error!("Failed to send verification email: {}", e);
+        {
+            tracing::error!("Failed to send verification code: {}", e);
+        }
     }
 
     // Return success without tokens - user must verify email first
     Ok((
         StatusCode::CREATED,
         Json(json!({
-            "message": "Registration successful. Please check your email for the verification code.",
+            "message": format!(
+                "Registration successful. Please check your {} for the verification code.",
+                payload.channel.destination()
+            ),
             "email": user.email,
         })),
     ))
@@ -303,13 +477,15 @@ pub async fn register(
 /// Handler for user login
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    locale: Locale,
     Json(payload): Json<LoginRequest>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+) -> Result<(StatusCode, HeaderMap, Json<Value>), (StatusCode, Json<Value>)> {
     // Validate input
     payload.validate().map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
+            Json(json!({"error": format_validation_error(&e, locale)})),
         )
     })?;
 
@@ -346,20 +522,18 @@ pub async fn login(
         )
     })?;
 
+    let client = ClientInfo::from_headers(&headers, state.config.login_country_header.as_deref());
+
     // Verify password
-    let is_valid = verify_password(
-        &payload.password,
-        &user.password_hash,
-        &state.config.password_pepper,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Password verification failed"})),
+    if !check_password(&state, &user, &payload.password)? {
+        record_login(
+            &state,
+            &user,
+            Some(LoginFailure::InvalidPassword),
+            client,
+            locale,
         )
-    })?;
-
-    if !is_valid {
+        .await;
         return Err((
             StatusCode::UNAUTHORIZED,
             Json(json!({"error": "Invalid credentials"})),
@@ -368,23 +542,257 @@ pub async fn login(
 
     // Check if email is verified
     if !user.email_verified {
+        record_login(
+            &state,
+            &user,
+            Some(LoginFailure::EmailNotVerified),
+            client,
+            locale,
+        )
+        .await;
         return Err((
             StatusCode::FORBIDDEN,
             Json(json!({"error": "Please verify your email before logging in"})),
         ));
     }
 
-    // Generate tokens
-    let access_token = state
-        .jwt_service
-        .create_access_token(&user.id.to_string(), &user.username)
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create access token"})),
-            )
-        })?;
+    if let Some(suspension) = active_suspension(&state, user.id).await? {
+        record_login(&state, &user, Some(LoginFailure::Suspended), client, locale).await;
+        return Err(suspended(&suspension));
+    }
+
+    let (response, csrf_token) = start_session(&state, &user, &client).await?;
+    let (cookies, auth) = deliver_session(&state, response, &csrf_token);
+
+    record_login(&state, &user, None, client, locale).await;
+
+    Ok((
+        StatusCode::OK,
+        cookies,
+        Json(json!({
+            "user": UserResponse::from(user),
+            "auth": auth,
+            "csrf_token": csrf_token,
+        })),
+    ))
+}
+
+/// Events the member manages, for their access token
+async fn managed_events(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<Vec<Uuid>, (StatusCode, Json<Value>)> {
+    state.db.managed_events(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })
+}
+
+/// The member's suspension in force, if any
+async fn active_suspension(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<Option<Suspension>, (StatusCode, Json<Value>)> {
+    state.db.active_suspension(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })
+}
+
+/// Refusal telling a suspended member why, and until when
+fn suspended(suspension: &Suspension) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({
+            "error": "Your account is suspended",
+            "reason": suspension.reason,
+            "suspended_until": suspension.ends_at,
+        })),
+    )
+}
+
+/// Overwrite the cached answer to whether the member is suspended, so
+/// services sharing the KV store turn them away (or back in) at once
+async fn publish_suspension(state: &AppState, user_id: Uuid, suspended: bool) {
+    if let Err(e) = state
+        .kv
+        .set(
+            &suspension_key(user_id),
+            &suspended.to_string(),
+            SUSPENSION_CACHE_TTL,
+        )
+        .await
+    {
+        tracing::warn!("Failed to publish suspension of user {}: {}", user_id, e);
+    }
+}
+
+/// Store a fresh code for the purpose, to be emailed to `email`. Returns
+/// None when one was sent less than `OTP_RESEND_INTERVAL_SECS` ago.
+async fn issue_otp(
+    state: &AppState,
+    user_id: Uuid,
+    purpose: VerificationPurpose,
+    email: &str,
+) -> Result<Option<String>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to create verification OTP"})),
+        )
+    };
+
+    if let Some(existing) = state
+        .db
+        .find_verification_otp(user_id, purpose)
+        .await
+        .map_err(db_error)?
+    {
+        let since_last = Utc::now().signed_duration_since(existing.last_sent_at);
+        if since_last.num_seconds() < OTP_RESEND_INTERVAL_SECS {
+            return Ok(None);
+        }
+    }
+
+    let otp = security::generate_otp();
+    state
+        .db
+        .create_verification_otp(user_id, purpose, email, &otp, purpose.expiry(&state.config))
+        .await
+        .map_err(db_error)?;
+
+    Ok(Some(otp))
+}
+
+/// Deleted members are shown with a reserved prefix no one may take
+fn reject_reserved_username(username: &str) -> Result<(), (StatusCode, Json<Value>)> {
+    if username
+        .to_lowercase()
+        .starts_with(&DELETED_MEMBER_PREFIX.to_lowercase())
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "That username is reserved"})),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject a request for an SMS code when no SMS backend is configured
+fn require_channel(state: &AppState, channel: OtpChannel) -> Result<(), (StatusCode, Json<Value>)> {
+    if channel == OtpChannel::Sms && state.sms_client.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "SMS delivery is not available"})),
+        ));
+    }
+    Ok(())
+}
+
+/// Send a registration or recovery code to the member's email address, or
+/// by SMS to the phone number they registered with
+async fn send_otp(
+    state: &AppState,
+    user: &User,
+    purpose: VerificationPurpose,
+    channel: OtpChannel,
+    otp: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let (OtpChannel::Sms, Some(sms_client)) = (channel, &state.sms_client) {
+        return sms_client
+            .send(Sms::otp(&user.phone_number, purpose, otp))
+            .await;
+    }
+    match purpose {
+        VerificationPurpose::AccountRecovery => {
+            state
+                .email_client
+                .send_password_reset_email(&user.email, &user.username, otp, locale)
+                .await
+        }
+        _ => {
+            state
+                .email_client
+                .send_verification_email(&user.email, &user.username, otp, locale)
+                .await
+        }
+    }
+}
+
+/// Check a code for the purpose and use it up, returning the address it
+/// was sent to. Wrong guesses count towards `MAX_OTP_ATTEMPTS`.
+async fn redeem_otp(
+    state: &AppState,
+    user_id: Uuid,
+    purpose: VerificationPurpose,
+    otp: &str,
+) -> Result<String, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to verify OTP"})),
+        )
+    };
+    let invalid = || {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Invalid or expired OTP"})),
+        )
+    };
+
+    let token = state
+        .db
+        .find_verification_otp(user_id, purpose)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(invalid)?;
+
+    if token.attempts >= MAX_OTP_ATTEMPTS {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({"error": "Too many failed attempts. Please request a new OTP."})),
+        ));
+    }
+
+    if token.otp != otp {
+        let attempts = state
+            .db
+            .increment_verification_attempts(token.id)
+            .await
+            .map_err(db_error)?;
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid OTP",
+                "attempts_remaining": (MAX_OTP_ATTEMPTS - attempts).max(0)
+            })),
+        ));
+    }
+
+    if !state
+        .db
+        .consume_verification_otp(token.id)
+        .await
+        .map_err(db_error)?
+    {
+        return Err(invalid());
+    }
+
+    Ok(token.email)
+}
 
+/// Open a session for a user who just proved who they are: a refresh token
+/// bound to the client's device, an access token and a CSRF token for it
+async fn start_session(
+    state: &Arc<AppState>,
+    user: &User,
+    client: &ClientInfo,
+) -> Result<(AuthResponse, String), (StatusCode, Json<Value>)> {
     let refresh_token = state
         .jwt_service
         .create_refresh_token(&user.id.to_string(), &user.username)
@@ -405,9 +813,9 @@ pub async fn login(
         })?;
 
     let expires_at = Utc::now() + Duration::seconds(state.config.jwt_refresh_token_expiry);
-    state
+    let session = state
         .db
-        .store_refresh_token(user.id, &refresh_token_hash, expires_at)
+        .store_refresh_token(user.id, &refresh_token_hash, expires_at, client)
         .await
         .map_err(|_| {
             (
@@ -416,16 +824,28 @@ pub async fn login(
             )
         })?;
 
-    // Generate CSRF token
-    let csrf_token = create_csrf_token(&state.db, Some(user.id), state.config.csrf_token_expiry)
-        .await
+    let managed_events = managed_events(state, user.id).await?;
+    let access_token = state
+        .jwt_service
+        .create_manager_access_token(
+            &user.id.to_string(),
+            &user.username,
+            session.id,
+            managed_events,
+        )
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create CSRF token"})),
+                Json(json!({"error": "Failed to create access token"})),
             )
         })?;
 
+    let csrf_token = issue_csrf_token(
+        &state.config.jwt_secret,
+        Some(session.id),
+        state.config.csrf_token_expiry,
+    );
+
     let response = AuthResponse {
         access_token,
         refresh_token,
@@ -433,25 +853,52 @@ pub async fn login(
         expires_in: state.config.jwt_access_token_expiry,
     };
 
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "user": UserResponse::from(user),
-            "auth": response,
-            "csrf_token": csrf_token,
-        })),
-    ))
+    Ok((response, csrf_token))
+}
+
+/// Hand a session's tokens to the client: in the body, or as httpOnly
+/// cookies that scripts cannot read. The CSRF token stays in the body
+/// either way, since scripts have to send it back.
+fn deliver_session(state: &AppState, auth: AuthResponse, csrf_token: &str) -> (HeaderMap, Value) {
+    let mut headers = csrf_cookie_headers(&state.config, csrf_token);
+    let cookies = &state.config.session_cookies;
+    match cookies.delivery {
+        TokenDelivery::Body => (headers, json!(auth)),
+        TokenDelivery::Cookie => {
+            cookies.set(
+                &mut headers,
+                (&auth.access_token, state.config.jwt_access_token_expiry),
+                (&auth.refresh_token, state.config.jwt_refresh_token_expiry),
+            );
+            (
+                headers,
+                json!({"token_type": "Cookie", "expires_in": auth.expires_in}),
+            )
+        }
+    }
 }
 
 /// Handler for refreshing access token
 pub async fn refresh(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<RefreshTokenRequest>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    headers: HeaderMap,
+    payload: Option<Json<RefreshTokenRequest>>,
+) -> Result<(StatusCode, HeaderMap, Json<Value>), (StatusCode, Json<Value>)> {
+    // Clients holding their tokens in cookies post no body
+    let refresh_token = payload
+        .map(|Json(payload)| payload.refresh_token)
+        .or_else(|| cookie_value(&headers, REFRESH_TOKEN_COOKIE).map(str::to_string))
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Missing refresh token"})),
+            )
+        })?;
+
     // Validate refresh token
     let claims = state
         .jwt_service
-        .validate_refresh_token(&payload.refresh_token)
+        .validate_refresh_token(&refresh_token)
         .map_err(|_| {
             (
                 StatusCode::UNAUTHORIZED,
@@ -460,18 +907,16 @@ pub async fn refresh(
         })?;
 
     // Hash the refresh token to check against stored hash
-    let refresh_token_hash =
-        security::hash_token(&payload.refresh_token, &state.config.password_pepper).map_err(
-            |_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Failed to hash refresh token"})),
-                )
-            },
-        )?;
+    let refresh_token_hash = security::hash_token(&refresh_token, &state.config.password_pepper)
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to hash refresh token"})),
+            )
+        })?;
 
     // Verify refresh token exists in database
-    let _stored_token = state
+    let stored_token = state
         .db
         .find_refresh_token(&refresh_token_hash)
         .await
@@ -514,22 +959,39 @@ pub async fn refresh(
             )
         })?;
 
-    // Delete old refresh token
-    state
-        .db
-        .delete_refresh_token(&refresh_token_hash)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to delete old refresh token"})),
-            )
-        })?;
+    if let Some(suspension) = active_suspension(&state, user.id).await? {
+        if let Err(e) = state.db.delete_refresh_token(&refresh_token_hash).await {
+            tracing::warn!("Failed to revoke session {}: {}", stored_token.id, e);
+        }
+        return Err(suspended(&suspension));
+    }
+
+    // A token bound to another device has leaked: end that session
+    let client = ClientInfo::from_headers(&headers, state.config.login_country_header.as_deref());
+    if !stored_token.accepts(&client) {
+        tracing::warn!(
+            "Refresh token for {} presented by another device; revoking the session",
+            user.id
+        );
+        if let Err(e) = state.db.delete_refresh_token(&refresh_token_hash).await {
+            tracing::warn!("Failed to revoke session {}: {}", stored_token.id, e);
+        }
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Refresh token was issued to a different device"})),
+        ));
+    }
 
     // Generate new tokens
+    let managed_events = managed_events(&state, user.id).await?;
     let access_token = state
         .jwt_service
-        .create_access_token(&user.id.to_string(), &user.username)
+        .create_manager_access_token(
+            &user.id.to_string(),
+            &user.username,
+            stored_token.id,
+            managed_events,
+        )
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -556,10 +1018,17 @@ pub async fn refresh(
             )
         })?;
 
+    // Replace the old token in the same session; losing a race with another
+    // refresh of the same token means it was already used
     let expires_at = Utc::now() + Duration::seconds(state.config.jwt_refresh_token_expiry);
-    state
+    let rotated = state
         .db
-        .store_refresh_token(user.id, &new_refresh_token_hash, expires_at)
+        .rotate_refresh_token(
+            &refresh_token_hash,
+            &new_refresh_token_hash,
+            expires_at,
+            &client,
+        )
         .await
         .map_err(|_| {
             (
@@ -567,6 +1036,12 @@ pub async fn refresh(
                 Json(json!({"error": "Failed to store refresh token"})),
             )
         })?;
+    if !rotated {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Refresh token not found or expired"})),
+        ));
+    }
 
     let response = AuthResponse {
         access_token,
@@ -575,14 +1050,23 @@ pub async fn refresh(
         expires_in: state.config.jwt_access_token_expiry,
     };
 
-    Ok((StatusCode::OK, Json(json!(response))))
+    // Renew the session's CSRF token along with its access token
+    let csrf_token = issue_csrf_token(
+        &state.config.jwt_secret,
+        Some(stored_token.id),
+        state.config.csrf_token_expiry,
+    );
+    let (cookies, mut body) = deliver_session(&state, response, &csrf_token);
+    body["csrf_token"] = json!(csrf_token);
+
+    Ok((StatusCode::OK, cookies, Json(body)))
 }
 
 /// Handler for user logout
 pub async fn logout(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+) -> Result<(StatusCode, HeaderMap, Json<Value>), (StatusCode, Json<Value>)> {
     // Delete all refresh tokens for the user
     state
         .db
@@ -595,17 +1079,31 @@ pub async fn logout(
             )
         })?;
 
+    let mut cookies = HeaderMap::new();
+    state.config.session_cookies.clear(&mut cookies);
+
     Ok((
         StatusCode::OK,
+        cookies,
         Json(json!({"message": "Logged out successfully"})),
     ))
 }
 
-/// Handler to get current user info
-pub async fn me(
+/// Handler for deleting one's own account. The member is anonymized rather
+/// than removed, so results attributed to them stay in the tabs.
+pub async fn delete_my_account(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<DeleteAccountRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
     let user = state
         .db
         .find_user_by_id(user_id)
@@ -623,47 +1121,43 @@ pub async fn me(
             )
         })?;
 
-    Ok((StatusCode::OK, Json(json!(UserResponse::from(user)))))
-}
-
-/// Handler to get a new CSRF token
-pub async fn get_csrf_token(
-    State(state): State<Arc<AppState>>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let csrf_token = create_csrf_token(&state.db, None, state.config.csrf_token_expiry)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create CSRF token"})),
-            )
-        })?;
+    if !check_password(&state, &user, &payload.password)? {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid password"})),
+        ));
+    }
 
-    Ok((StatusCode::OK, Json(json!({"csrf_token": csrf_token}))))
+    soft_delete(&state, user_id).await
 }
 
-/// Handler to verify email address
-pub async fn verify_email(
-    State(state): State<Arc<AppState>>,
-    Json(payload): Json<VerifyEmailRequest>,
+/// Anonymize a member, refusing admins so the last one can't vanish by
+/// accident
+async fn soft_delete(
+    state: &AppState,
+    user_id: Uuid,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Validate input
-    payload.validate().map_err(|e| {
+    if state.db.is_user_admin(user_id).await.map_err(|_| {
         (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
         )
-    })?;
+    })? {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Admins must be demoted before their account is deleted"})),
+        ));
+    }
 
-    // Find user by email
-    let user = state
+    let number = state
         .db
-        .find_user_by_email(&payload.email)
+        .soft_delete_user(user_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to delete user: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to delete account"})),
             )
         })?
         .ok_or_else(|| {
@@ -673,157 +1167,157 @@ pub async fn verify_email(
             )
         })?;
 
-    // Verify the OTP
-    let result = state
-        .db
-        .verify_email_otp(user.id, &payload.otp)
-        .await
-        .map_err(|e| {
-            let error_msg = e.to_string();
-            if error_msg.contains("expired") {
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": "OTP has expired. Please request a new one."})),
-                )
-            } else if error_msg.contains("attempts") {
-                (
-                    StatusCode::TOO_MANY_REQUESTS,
-                    Json(json!({"error": "Too many failed attempts. Please request a new OTP."})),
-                )
-            } else if error_msg.contains("Invalid") {
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({"error": "Invalid OTP"})),
-                )
-            } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Failed to verify OTP"})),
-                )
-            }
-        })?;
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Account deleted",
+            "user_id": user_id,
+            "shown_as": format!("{}{}", DELETED_MEMBER_PREFIX, number),
+        })),
+    ))
+}
+
+/// Handler for deleting a member's account (admin only)
+pub async fn admin_delete_user(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    soft_delete(&state, user_id).await
+}
 
-    if !result {
+/// Handler for suspending a member until a date, or until lifted (admin
+/// only). Their sessions end at once.
+pub async fn admin_suspend_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+    locale: Locale,
+    Json(payload): Json<SuspendUserRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+    if payload.until.is_some_and(|until| until <= Utc::now()) {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Invalid OTP"})),
+            Json(json!({"error": "A suspension must end in the future"})),
         ));
     }
 
-    // Get the updated user
-    let user = state
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    state
         .db
-        .find_user_by_id(user.id)
+        .find_user_by_id(user_id)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
+        .map_err(db_error)?
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
                 Json(json!({"error": "User not found"})),
             )
         })?;
-
-    // Send welcome email (don't fail if email fails)
-    if let Err(e) = state
-        .email_client
-        .send_welcome_email(&user.email, &user.username)
-        .await
-    {
-        tracing::error!("Failed to send welcome email: {}", e);
+    if state.db.is_user_admin(user_id).await.map_err(db_error)? {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Admins must be demoted before they are suspended"})),
+        ));
     }
 
-    // Generate tokens for the verified user
-    let access_token = state
-        .jwt_service
-        .create_access_token(&user.id.to_string(), &user.username)
-        .map_err(|_| {
+    let suspension = state
+        .db
+        .suspend_user(user_id, admin_user_id, &payload.reason, payload.until)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to suspend user: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create access token"})),
+                Json(json!({"error": "Failed to suspend user"})),
             )
         })?;
 
-    let refresh_token = state
-        .jwt_service
-        .create_refresh_token(&user.id.to_string(), &user.username)
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create refresh token"})),
-            )
-        })?;
+    publish_suspension(&state, user_id, true).await;
+    tracing::info!("Admin {} suspended user {}", admin_user_id, user_id);
 
-    // Hash and store refresh token
-    let refresh_token_hash = security::hash_token(&refresh_token, &state.config.password_pepper)
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to hash refresh token"})),
-            )
-        })?;
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "User suspended",
+            "suspension": suspension,
+        })),
+    ))
+}
 
-    let expires_at = Utc::now() + Duration::seconds(state.config.jwt_refresh_token_expiry);
-    state
+/// Handler for ending a member's suspension early (admin only)
+pub async fn admin_lift_suspension(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let suspension = state
         .db
-        .store_refresh_token(user.id, &refresh_token_hash, expires_at)
+        .lift_suspension(user_id, admin_user_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to lift suspension: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to store refresh token"})),
+                Json(json!({"error": "Failed to lift suspension"})),
             )
-        })?;
-
-    // Generate CSRF token
-    let csrf_token = create_csrf_token(&state.db, Some(user.id), state.config.csrf_token_expiry)
-        .await
-        .map_err(|_| {
+        })?
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create CSRF token"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User is not suspended"})),
             )
         })?;
 
-    let response = AuthResponse {
-        access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: state.config.jwt_access_token_expiry,
-    };
+    publish_suspension(&state, user_id, false).await;
+    tracing::info!(
+        "Admin {} lifted the suspension of user {}",
+        admin_user_id,
+        user_id
+    );
 
     Ok((
         StatusCode::OK,
         Json(json!({
-            "message": "Email verified successfully",
-            "user": UserResponse::from(user),
-            "auth": response,
-            "csrf_token": csrf_token,
+            "message": "Suspension lifted",
+            "suspension": suspension,
         })),
     ))
 }
 
-/// Handler to resend verification email
-pub async fn resend_verification(
+/// Handler for a member's suspensions, current and past (admin only)
+pub async fn admin_list_suspensions(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<ResendVerificationRequest>,
+    Path(user_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Validate input
-    payload.validate().map_err(|e| {
+    let suspensions = state.db.list_suspensions(user_id).await.map_err(|_| {
         (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
         )
     })?;
 
-    // Find user by email
+    Ok((StatusCode::OK, Json(json!({ "suspensions": suspensions }))))
+}
+
+/// Look up a member an admin is acting on who has not verified their email
+async fn find_unverified_user(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<User, (StatusCode, Json<Value>)> {
     let user = state
         .db
-        .find_user_by_email(&payload.email)
+        .find_user_by_id(user_id)
         .await
         .map_err(|_| {
             (
@@ -837,398 +1331,1794 @@ pub async fn resend_verification(
                 Json(json!({"error": "User not found"})),
             )
         })?;
-
-    // Check if already verified
     if user.email_verified {
-        return Ok((
-            StatusCode::OK,
-            Json(json!({"message": "Email already verified"})),
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Email is already verified"})),
         ));
     }
+    Ok(user)
+}
 
-    // Check if there's a recent OTP (rate limiting)
-    if let Ok(Some(existing_otp)) = state.db.find_email_verification_otp_by_user(user.id).await {
-        let time_since_last = Utc::now().signed_duration_since(existing_otp.last_sent_at);
-        if time_since_last.num_seconds() < 60 {
-            return Err((
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(json!({"error": "Please wait before requesting a new OTP"})),
-            ));
-        }
-    }
+/// Handler for marking a member's email verified without a code, for when
+/// the code never reached them (admin only)
+pub async fn admin_force_verify(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    find_unverified_user(&state, user_id).await?;
 
-    // Generate new OTP
-    let otp = security::generate_otp();
-    state
+    let entry = state
         .db
-        .create_email_verification_otp(user.id, &otp, state.config.email_verification_expiry)
+        .admin_verify_email(user_id, admin_user_id)
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to force-verify email: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create verification OTP"})),
+                Json(json!({"error": "Failed to verify email"})),
             )
-        })?;
-
-    // Send verification email
-    state
-        .email_client
-        .send_verification_email(&user.email, &user.username, &otp)
-        .await
-        .map_err(|_| {
+        })?
+        // Verified since the lookup
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to send verification email"})),
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Email is already verified"})),
             )
         })?;
 
+    tracing::info!(
+        "Admin {} verified the email of user {}",
+        admin_user_id,
+        user_id
+    );
+
     Ok((
         StatusCode::OK,
-        Json(json!({"message": "Verification OTP sent"})),
+        Json(json!({
+            "message": "Email verified",
+            "audit": entry,
+        })),
     ))
 }
 
-/// Handler to request password reset
-pub async fn request_password_reset(
+/// Handler for sending a member a fresh registration code, skipping the
+/// resend cooldown (admin only)
+pub async fn admin_resend_verification(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<RequestPasswordResetRequest>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+    locale: Locale,
+    payload: Option<Json<AdminResendVerificationRequest>>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Validate input
-    payload.validate().map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
-        )
-    })?;
+    let channel = payload.map(|Json(p)| p.channel).unwrap_or_default();
+    require_channel(&state, channel)?;
+    let user = find_unverified_user(&state, user_id).await?;
 
-    // Find user by email
-    let user = state
+    let purpose = VerificationPurpose::Registration;
+    let otp = security::generate_otp();
+    state
         .db
-        .find_user_by_email(&payload.email)
+        .create_verification_otp(
+            user.id,
+            purpose,
+            &user.email,
+            &otp,
+            purpose.expiry(&state.config),
+        )
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to create verification OTP"})),
             )
         })?;
 
-    // Always return success even if user doesn't exist (security best practice)
-    if user.is_none() {
-        return Ok((
-            StatusCode::OK,
-            Json(json!({"message": "If the email exists, a password reset OTP has been sent"})),
+    if let Err(e) = send_otp(&state, &user, purpose, channel, &otp, locale).await {
+        tracing::error!("Failed to resend verification code: {:?}", e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to send verification code"})),
         ));
     }
 
-    let user = user.unwrap();
-
-    // Generate password reset OTP (6 digits, stored directly)
-    let otp = security::generate_otp();
-
-    state
+    let note = format!("Sent to their {}", channel.destination());
+    let entry = state
         .db
-        .create_password_reset_otp(
+        .record_account_audit(
             user.id,
-            &user.email,
-            &otp,
-            state.config.password_reset_expiry,
+            admin_user_id,
+            AccountAction::VerificationResent,
+            Some(&note),
         )
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to record account audit: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to create reset OTP"})),
+                Json(json!({"error": "Database error"})),
             )
         })?;
 
-    // Send password reset email (don't fail if email fails)
-    if let Err(e) = state
-        .email_client
-        .send_password_reset_email(&user.email, &user.username, &otp)
-        .await
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": format!("Verification code sent to their {}", channel.destination()),
+            "audit": entry,
+        })),
+    ))
+}
+
+/// Handler to get current user info
+pub async fn me(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Extension(pending): Extension<PendingPolicies>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let user = state
+        .db
+        .find_user_by_id(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    let mut body = json!(UserResponse::from(user));
+    body["pending_policies"] = json!(pending.0);
+
+    Ok((StatusCode::OK, Json(body)))
+}
+
+/// Handler to start moving the current user to a new email address. A
+/// code is sent to the new address; the change happens once it is entered.
+pub async fn change_email(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<ChangeEmailRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let user = state
+        .db
+        .find_user_by_id(user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    if !check_password(&state, &user, &payload.password)? {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid password"})),
+        ));
+    }
+
+    if payload.new_email.eq_ignore_ascii_case(&user.email) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "This is already your email address"})),
+        ));
+    }
+    if state
+        .db
+        .find_user_by_email(&payload.new_email)
+        .await
+        .map_err(db_error)?
+        .is_some()
     {
-        tracing::error!("Failed to send password reset email: {}", e);
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Email already registered"})),
+        ));
     }
 
+    let otp = issue_otp(
+        &state,
+        user.id,
+        VerificationPurpose::EmailChange,
+        &payload.new_email,
+    )
+    .await?
+    .ok_or_else(|| {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({"error": "Please wait before requesting a new OTP"})),
+        )
+    })?;
+
+    state
+        .email_client
+        .send_email_change_email(&payload.new_email, &user.username, &otp, locale)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to send verification email"})),
+            )
+        })?;
+
     Ok((
         StatusCode::OK,
-        Json(json!({"message": "If the email exists, a password reset OTP has been sent"})),
+        Json(json!({
+            "message": "Check your new email address for the verification code",
+            "email": payload.new_email
+        })),
     ))
 }
 
-/// Handler to reset password with OTP
-pub async fn reset_password(
+/// Handler to complete an email change with the code sent to the new
+/// address
+pub async fn confirm_email_change(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<ConfirmEmailChangeRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
+    let email = redeem_otp(
+        &state,
+        user_id,
+        VerificationPurpose::EmailChange,
+        &payload.otp,
+    )
+    .await?;
+
+    state
+        .db
+        .change_user_email(user_id, &email)
+        .await
+        .map_err(|e| {
+            // Someone else may have taken the address since the code was sent
+            let status = match e.as_database_error() {
+                Some(db_err) if db_err.is_unique_violation() => StatusCode::CONFLICT,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (
+                status,
+                Json(json!({"error": format_database_error(&e, locale)})),
+            )
+        })?;
+
+    tracing::info!("User {} changed their email address", user_id);
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Email changed successfully",
+            "email": email
+        })),
+    ))
+}
+
+/// Rename a member after checking the name is free: not anyone else's
+/// current username, nor one they gave up and may still be linked by.
+/// Members renaming themselves are held to the cooldown; admins are not.
+async fn rename_user(
+    state: &AppState,
+    user_id: Uuid,
+    payload: ChangeUsernameRequest,
+    changed_by: Uuid,
+    locale: Locale,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+    reject_reserved_username(&payload.username)?;
+
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let taken = || {
+        (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Username already exists"})),
+        )
+    };
+
+    if changed_by == user_id {
+        let cooldown = Duration::days(state.config.username_change_cooldown_days);
+        if let Some(last) = state
+            .db
+            .last_username_change(user_id)
+            .await
+            .map_err(db_error)?
+        {
+            let next_change = last + cooldown;
+            if next_change > Utc::now() {
+                return Err((
+                    StatusCode::TOO_MANY_REQUESTS,
+                    Json(json!({
+                        "error": format!(
+                            "Usernames can be changed once every {} days",
+                            state.config.username_change_cooldown_days
+                        ),
+                        "next_change_at": next_change,
+                    })),
+                ));
+            }
+        }
+    }
+
+    match state
+        .db
+        .find_user_by_username(&payload.username)
+        .await
+        .map_err(db_error)?
+    {
+        Some(existing) if existing.id == user_id => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "That is already the username"})),
+            ));
+        }
+        Some(_) => return Err(taken()),
+        None => {}
+    }
+    if let Some(owner) = state
+        .db
+        .former_username_owner(&payload.username)
+        .await
+        .map_err(db_error)?
+    {
+        if owner != user_id {
+            return Err(taken());
+        }
+    }
+
+    let previous = state
+        .db
+        .change_username(user_id, &payload.username, changed_by)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            // Taken since the check
+            Some(db_err) if db_err.is_unique_violation() => taken(),
+            _ => {
+                tracing::error!("Failed to change username: {:?}", e);
+                db_error(e)
+            }
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    tracing::info!("User {} renamed by {}", user_id, changed_by);
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Username changed successfully",
+            "username": payload.username,
+            "previous_username": previous,
+        })),
+    ))
+}
+
+/// Handler for changing the current user's username. Links to the old
+/// name keep working.
+pub async fn change_username(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<ChangeUsernameRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    rename_user(&state, user_id, payload, user_id, locale).await
+}
+
+/// Handler for renaming a member (admin only)
+pub async fn admin_change_username(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+    locale: Locale,
+    Json(payload): Json<ChangeUsernameRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    rename_user(&state, user_id, payload, admin_user_id, locale).await
+}
+
+/// Handler for the current user's login attempts, newest first
+pub async fn my_login_history(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    axum::extract::Query(params): axum::extract::Query<ListUsersParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let (logins, total) = state
+        .db
+        .list_login_history(user_id, page, per_page)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch login history"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "logins": logins,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "total_pages": pagination.total_pages(total)
+        })),
+    ))
+}
+
+/// Handler for the current user's signed-in devices. The requesting device
+/// is marked as current when it sends its device identifier.
+pub async fn my_sessions(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let client = ClientInfo::from_headers(&headers, None);
+    let sessions: Vec<SessionResponse> = state
+        .db
+        .list_user_refresh_tokens(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch sessions"})),
+            )
+        })?
+        .into_iter()
+        .map(|token| SessionResponse::new(token, &client))
+        .collect();
+
+    Ok((StatusCode::OK, Json(json!({ "sessions": sessions }))))
+}
+
+/// Handler for signing out one of the current user's devices
+pub async fn revoke_my_session(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(session_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let revoked = state
+        .db
+        .delete_user_refresh_token(user_id, session_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    if !revoked {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Session not found"})),
+        ));
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Session signed out"})),
+    ))
+}
+
+/// Handler to get a new CSRF token, tied to the caller's session when the
+/// request carries an access token
+pub async fn get_csrf_token(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> (StatusCode, HeaderMap, Json<Value>) {
+    let csrf_token = issue_csrf_token(
+        &state.config.jwt_secret,
+        request_session(&state, &headers),
+        state.config.csrf_token_expiry,
+    );
+
+    (
+        StatusCode::OK,
+        csrf_cookie_headers(&state.config, &csrf_token),
+        Json(json!({"csrf_token": csrf_token})),
+    )
+}
+
+/// Handler to verify email address
+pub async fn verify_email(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    locale: Locale,
+    Json(payload): Json<VerifyEmailRequest>,
+) -> Result<(StatusCode, HeaderMap, Json<Value>), (StatusCode, Json<Value>)> {
+    // Validate input
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
+    // Find user by email
+    let user = state
+        .db
+        .find_user_by_email(&payload.email)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    redeem_otp(
+        &state,
+        user.id,
+        VerificationPurpose::Registration,
+        &payload.otp,
+    )
+    .await?;
+    state.db.verify_user_email(user.id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to verify email"})),
+        )
+    })?;
+
+    // Get the updated user
+    let user = state
+        .db
+        .find_user_by_id(user.id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    // Send welcome email (don't fail if email fails)
+    if let Err(e) = state
+        .email_client
+        .send_welcome_email(&user.email, &user.username, locale)
+        .await
+    {
+        tracing::error!("Failed to send welcome email: {}", e);
+    }
+
+    // Sign the verified user in
+    let client = ClientInfo::from_headers(&headers, state.config.login_country_header.as_deref());
+    let (response, csrf_token) = start_session(&state, &user, &client).await?;
+    let (cookies, auth) = deliver_session(&state, response, &csrf_token);
+
+    Ok((
+        StatusCode::OK,
+        cookies,
+        Json(json!({
+            "message": "Email verified successfully",
+            "user": UserResponse::from(user),
+            "auth": auth,
+            "csrf_token": csrf_token,
+        })),
+    ))
+}
+
+/// Handler to resend verification email
+pub async fn resend_verification(
+    State(state): State<Arc<AppState>>,
+    locale: Locale,
+    Json(payload): Json<ResendVerificationRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Validate input
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+    require_channel(&state, payload.channel)?;
+
+    // Find user by email
+    let user = state
+        .db
+        .find_user_by_email(&payload.email)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    // Check if already verified
+    if user.email_verified {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({"message": "Email already verified"})),
+        ));
+    }
+
+    let otp = issue_otp(
+        &state,
+        user.id,
+        VerificationPurpose::Registration,
+        &user.email,
+    )
+    .await?
+    .ok_or_else(|| {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({"error": "Please wait before requesting a new OTP"})),
+        )
+    })?;
+
+    send_otp(
+        &state,
+        &user,
+        VerificationPurpose::Registration,
+        payload.channel,
+        &otp,
+        locale,
+    )
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to send verification code"})),
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Verification OTP sent"})),
+    ))
+}
+
+/// Handler to request password reset
+pub async fn request_password_reset(
+    State(state): State<Arc<AppState>>,
+    locale: Locale,
+    Json(payload): Json<RequestPasswordResetRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Validate input
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+    require_channel(&state, payload.channel)?;
+
+    // Find user by email
+    let user = state
+        .db
+        .find_user_by_email(&payload.email)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    // Always return success even if user doesn't exist (security best practice)
+    if user.is_none() {
+        return Ok((
+            StatusCode::OK,
+            Json(json!({"message": "If the email exists, a password reset OTP has been sent"})),
+        ));
+    }
+
+    let user = user.unwrap();
+
+    // A code sent moments ago is still on its way; answering the same way
+    // keeps the throttle from revealing which addresses have accounts
+    let otp = issue_otp(
+        &state,
+        user.id,
+        VerificationPurpose::AccountRecovery,
+        &user.email,
+    )
+    .await?;
+
+    // Send password reset code (don't fail if sending fails)
+    if let Some(otp) = otp {
+        if let Err(e) = send_otp(
+            &state,
+            &user,
+            VerificationPurpose::AccountRecovery,
+            payload.channel,
+            &otp,
+            locale,
+        )
+        .await
+        {
+            tracing::error!("Failed to send password reset code: {}", e);
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "If the email exists, a password reset OTP has been sent"})),
+    ))
+}
+
+/// Handler to reset password with OTP
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    locale: Locale,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Validate input
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
+    enforce_password_policy(&state, &payload.new_password).await?;
+
+    let invalid = || {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Invalid or expired OTP"})),
+        )
+    };
+    let user = state
+        .db
+        .find_user_by_email(&payload.email)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(invalid)?;
+
+    redeem_otp(
+        &state,
+        user.id,
+        VerificationPurpose::AccountRecovery,
+        &payload.otp,
+    )
+    .await?;
+
+    // Hash new password
+    let peppers = &state.config.password_peppers;
+    let (new_password_hash, new_salt) = hash_password(&payload.new_password, peppers.current())
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to hash password"})),
+            )
+        })?;
+
+    // Update user password
+    state
+        .db
+        .update_user_password(
+            user.id,
+            &new_password_hash,
+            &new_salt,
+            peppers.current_version(),
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update password"})),
+            )
+        })?;
+
+    // Delete all refresh tokens for the user (log them out everywhere)
+    state
+        .db
+        .delete_user_refresh_tokens(user.id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to delete refresh tokens"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Password reset successfully"})),
+    ))
+}
+
+// ============================================================================
+// Admin Handlers
+// ============================================================================
+
+/// Handler for listing all users (admin only)
+pub async fn admin_list_users(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(params): axum::extract::Query<ListUsersParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let (users, total) = state.db.list_all_users(page, per_page).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch users"})),
+        )
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "users": users,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "total_pages": pagination.total_pages(total)
+        })),
+    ))
+}
+
+/// Handler for promoting a user to admin (admin only)
+pub async fn admin_promote_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Json(payload): Json<crate::models::PromoteToAdminRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Check if the target user exists
+    let target_user = state
+        .db
+        .find_user_by_id(payload.user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    // Check if user is already an admin
+    if state.db.is_user_admin(payload.user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })? {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "User is already an admin"})),
+        ));
+    }
+
+    // Promote user to admin
+    let admin_record = state
+        .db
+        .promote_to_admin(payload.user_id, admin_user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to promote user: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to promote user to admin"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "User promoted to admin successfully",
+            "admin": {
+                "id": admin_record.id,
+                "user_id": admin_record.user_id,
+                "username": target_user.username,
+                "granted_by": admin_record.granted_by,
+                "created_at": admin_record.created_at
+            }
+        })),
+    ))
+}
+
+/// Handler for demoting an admin (admin only)
+pub async fn admin_demote_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Json(payload): Json<crate::models::PromoteToAdminRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Prevent self-demotion
+    if payload.user_id == admin_user_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot demote yourself"})),
+        ));
+    }
+
+    // Check if the target user is actually an admin
+    if !state.db.is_user_admin(payload.user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })? {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User is not an admin"})),
+        ));
+    }
+
+    // Leaving a single admin needs a second admin's say-so
+    let admins = state.db.count_admins().await.map_err(db_error)?;
+    if admins <= 2 {
+        let target = state
+            .db
+            .find_user_by_id(payload.user_id)
+            .await
+            .map_err(db_error)?
+            .map(|user| user.username)
+            .unwrap_or_else(|| payload.user_id.to_string());
+        let pending = state
+            .pending_actions
+            .request(
+                ProtectedAction::DemoteAdmin,
+                payload.user_id,
+                &format!("Revoke admin from {}, leaving a single admin", target),
+                admin_user_id,
+            )
+            .await
+            .map_err(db_error)?;
+        return Ok(pending_actions::awaiting_confirmation(&pending));
+    }
+
+    demote_admin(&state, payload.user_id).await
+}
+
+/// Revoke admin from a user, once any confirmation has been given
+async fn demote_admin(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Checked again here: pending demotions confirmed one after another
+    // could otherwise remove every admin
+    let demotion = state.db.demote_admin(user_id).await.map_err(|e| {
+        tracing::error!("Failed to demote admin: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to demote admin"})),
+        )
+    })?;
+    match demotion {
+        Demotion::Demoted => {}
+        Demotion::NotAdmin => {
+            return Err(api_error(StatusCode::NOT_FOUND, "User is not an admin"));
+        }
+        Demotion::LastAdmin => {
+            return Err(api_error(
+                StatusCode::CONFLICT,
+                "The last admin cannot be demoted",
+            ));
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Admin privileges revoked successfully"})),
+    ))
+}
+
+/// Handler listing destructive actions waiting for a second admin (admin
+/// only)
+pub async fn admin_list_pending_actions(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let actions = state.pending_actions.list().await.map_err(db_error)?;
+    Ok((StatusCode::OK, Json(json!({"pending_actions": actions}))))
+}
+
+/// Handler confirming another admin's pending action, which carries it out
+/// (admin only)
+pub async fn admin_confirm_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_user_id, true)
+        .await?;
+
+    let result = match action.kind() {
+        Ok(ProtectedAction::DemoteAdmin) => demote_admin(&state, action.target_id).await,
+        _ => Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Unknown action: {}", action.action),
+        )),
+    };
+    match &result {
+        // Confirming again would not help: the user is no longer an admin,
+        // or is the last one
+        Err((StatusCode::NOT_FOUND | StatusCode::CONFLICT, _)) => {
+            if let Err(e) = state.pending_actions.fail(action.id).await {
+                tracing::error!("Failed to mark pending action {} failed: {}", action.id, e);
+            }
+        }
+        Err(_) => {
+            if let Err(e) = state.pending_actions.reopen(action.id).await {
+                tracing::error!("Failed to reopen pending action {}: {}", action.id, e);
+            }
+        }
+        Ok(_) => {}
+    }
+    result
+}
+
+/// Handler rejecting a pending action, or withdrawing one's own (admin
+/// only)
+pub async fn admin_reject_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_user_id, false)
+        .await?;
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Action rejected", "pending_action": action})),
+    ))
+}
+
+/// Handler for checking if current user is admin
+pub async fn admin_check(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let is_admin = state.db.is_user_admin(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({"is_admin": is_admin}))))
+}
+
+/// Handler for the account numbers on the admin dashboard (admin only)
+pub async fn admin_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let stats = state.db.user_stats().await.map_err(|e| {
+        tracing::error!("Failed to compute user stats: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!(stats))))
+}
+
+/// Roles held by the current user
+pub async fn my_roles(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let roles = state.db.list_user_roles(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({"roles": roles}))))
+}
+
+/// Handler for listing every role assignment (admin only)
+pub async fn admin_list_roles(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let assignments = state.db.list_role_assignments().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({"roles": assignments}))))
+}
+
+/// Handler for granting a role to a user (admin only)
+pub async fn admin_grant_role(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Json(payload): Json<crate::models::RoleChangeRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let target_user = state
+        .db
+        .find_user_by_id(payload.user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    let granted = state
+        .db
+        .grant_role(payload.user_id, payload.role, admin_user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to grant role: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to grant role"})),
+            )
+        })?;
+
+    if !granted {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": format!("User already has the {} role", payload.role)})),
+        ));
+    }
+
+    tracing::info!(
+        "Admin {} granted the {} role to {}",
+        admin_user_id,
+        payload.role,
+        payload.user_id
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Role granted successfully",
+            "user_id": payload.user_id,
+            "username": target_user.username,
+            "role": payload.role
+        })),
+    ))
+}
+
+/// Handler for revoking a role from a user (admin only)
+pub async fn admin_revoke_role(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Json(payload): Json<crate::models::RoleChangeRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let revoked = state
+        .db
+        .revoke_role(payload.user_id, payload.role)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to revoke role: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to revoke role"})),
+            )
+        })?;
+
+    if !revoked {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("User does not have the {} role", payload.role)})),
+        ));
+    }
+
+    tracing::info!(
+        "Admin {} revoked the {} role from {}",
+        admin_user_id,
+        payload.role,
+        payload.user_id
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Role revoked successfully"})),
+    ))
+}
+
+/// Handler for the latest version of each policy
+pub async fn list_policies(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let policies = state.db.latest_policies().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch policies"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({ "policies": policies }))))
+}
+
+/// Handler for the policies the current user has accepted and those still
+/// pending
+pub async fn my_policies(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Extension(pending): Extension<PendingPolicies>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let accepted = state
+        .db
+        .list_user_policy_acceptances(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch policy acceptances"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "accepted": accepted,
+            "pending": pending.0
+        })),
+    ))
+}
+
+/// Handler for accepting the current version of a policy. The time and
+/// the client it came from are kept as proof of consent.
+pub async fn accept_policy(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<crate::models::AcceptPolicyRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let policies = state.db.latest_policies().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+    let policy = policies
+        .iter()
+        .find(|policy| policy.id == payload.policy_id)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "No current policy with this id"})),
+            )
+        })?;
+
+    let client = ClientInfo::from_headers(&headers, None);
+    let accepted_at = state
+        .db
+        .accept_policy(user_id, policy.id, &client)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to record policy acceptance: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to record acceptance"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": "Policy accepted",
+            "policy_id": policy.id,
+            "kind": policy.kind,
+            "version": policy.version,
+            "accepted_at": accepted_at
+        })),
+    ))
+}
+
+/// Handler for the current user's notification preferences: for each
+/// category, whether it is sent by email, in the app and by SMS
+pub async fn my_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let preferences = state
+        .db
+        .get_notification_preferences(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch notification preferences"})),
+            )
+        })?;
+
+    Ok((StatusCode::OK, Json(json!(preferences))))
+}
+
+/// Handler for changing notification preferences. Only the categories and
+/// channels given change; returns every preference afterwards.
+pub async fn update_my_notification_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(changes): Json<PreferenceMatrix>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    state
+        .db
+        .update_notification_preferences(user_id, &changes)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update notification preferences: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update notification preferences"})),
+            )
+        })?;
+
+    my_notification_preferences(State(state), Extension(user_id)).await
+}
+
+/// Handler for the dietary and accessibility requirements the current user
+/// has shared
+pub async fn my_requirements(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let requirements = state
+        .db
+        .get_requirements(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to fetch requirements"})),
+            )
+        })?
+        .unwrap_or_default();
+
+    Ok((StatusCode::OK, Json(json!(requirements))))
+}
+
+/// Handler for replacing the current user's requirements. They are only
+/// shown to the welfare role, in reports on events the member attends.
+pub async fn update_my_requirements(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<ResetPasswordRequest>,
+    Extension(user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<UpdateRequirementsRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Validate input
     payload.validate().map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
-            Json(json!({"error": format_validation_error(&e)})),
+            Json(json!({"error": format_validation_error(&e, locale)})),
         )
     })?;
 
-    // Find the reset token by email
-    let token_record = state
+    let blank_to_none = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let requirements = state
         .db
-        .find_password_reset_by_email(&payload.email)
+        .set_requirements(
+            user_id,
+            blank_to_none(payload.dietary).as_deref(),
+            blank_to_none(payload.accessibility).as_deref(),
+        )
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to update requirements: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(json!({"error": "Invalid or expired OTP"})),
+                Json(json!({"error": "Failed to update requirements"})),
             )
         })?;
 
-    // Check if too many attempts
-    if token_record.attempts >= 5 {
-        return Err((
-            StatusCode::TOO_MANY_REQUESTS,
-            Json(json!({"error": "Too many attempts. Please request a new OTP."})),
-        ));
-    }
-
-    // Verify OTP (direct comparison, no hashing)
-    if payload.otp != token_record.otp {
-        // Increment attempts
-        let new_attempts = state
-            .db
-            .increment_password_reset_attempts(&payload.email)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error"})),
-                )
-            })?;
+    Ok((StatusCode::OK, Json(json!(requirements))))
+}
 
-        let remaining = 5 - new_attempts;
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "error": "Invalid OTP",
-                "attempts_remaining": remaining.max(0)
-            })),
-        ));
-    }
+/// Handler for publishing a new version of a policy (admin only). Every
+/// member has to accept the new version.
+pub async fn admin_publish_policy(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<crate::models::PublishPolicyRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
 
-    // Hash new password
-    let (new_password_hash, new_salt) =
-        hash_password(&payload.new_password, &state.config.password_pepper).map_err(|_| {
+    let policy = state
+        .db
+        .publish_policy(payload.kind, &payload.title, &payload.body, admin_user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to publish policy: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to hash password"})),
+                Json(json!({"error": "Failed to publish policy"})),
             )
         })?;
 
-    // Update user password
-    state
+    tracing::info!(
+        "Admin {} published version {} of the {} policy",
+        admin_user_id,
+        policy.version,
+        policy.kind
+    );
+
+    Ok((StatusCode::CREATED, Json(json!(policy))))
+}
+
+/// Handler for who accepted a policy version and when (admin only)
+pub async fn admin_list_policy_acceptances(
+    State(state): State<Arc<AppState>>,
+    Path(policy_id): Path<Uuid>,
+    axum::extract::Query(params): axum::extract::Query<ListUsersParams>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(params.page, params.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let policy = state
         .db
-        .update_user_password(token_record.user_id, &new_password_hash, &new_salt)
+        .find_policy(policy_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to update password"})),
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Policy not found"})),
             )
         })?;
 
-    // Mark OTP as used
-    state
+    let (acceptances, total) = state
         .db
-        .mark_password_reset_used(&payload.email)
+        .list_policy_acceptances(policy_id, page, per_page)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to mark OTP as used"})),
+                Json(json!({"error": "Failed to fetch policy acceptances"})),
             )
         })?;
 
-    // Delete all refresh tokens for the user (log them out everywhere)
-    state
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "policy_id": policy.id,
+            "kind": policy.kind,
+            "version": policy.version,
+            "acceptances": acceptances,
+            "total": total,
+            "page": page,
+            "per_page": per_page,
+            "total_pages": pagination.total_pages(total)
+        })),
+    ))
+}
+
+/// Handler for creating a read-only API key (admin only). The key is only
+/// ever returned here; just its hash is stored.
+pub async fn admin_create_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    locale: Locale,
+    Json(payload): Json<crate::models::CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
+        )
+    })?;
+
+    let key = format!("{}{}", API_KEY_PREFIX, security::generate_token());
+    let api_key = state
         .db
-        .delete_user_refresh_tokens(token_record.user_id)
+        .create_api_key(
+            &payload.name,
+            &key[..API_KEY_DISPLAY_LEN],
+            &hash_api_key(&key),
+            &payload.scopes,
+            admin_user_id,
+        )
         .await
-        .map_err(|_| {
+        .map_err(|e| {
+            tracing::error!("Failed to create API key: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to delete refresh tokens"})),
+                Json(json!({"error": "Failed to create API key"})),
             )
         })?;
 
+    tracing::info!(
+        "Admin {} created API key {} ({})",
+        admin_user_id,
+        api_key.id,
+        api_key.name
+    );
+
     Ok((
-        StatusCode::OK,
-        Json(json!({"message": "Password reset successfully"})),
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "API key created. Copy it now; it will not be shown again.",
+            "key": key,
+            "api_key": api_key
+        })),
     ))
 }
 
-// ============================================================================
-// Admin Handlers
-// ============================================================================
-
-/// Handler for listing all users (admin only)
-pub async fn admin_list_users(
+/// Handler for listing API keys (admin only)
+pub async fn admin_list_api_keys(
     State(state): State<Arc<AppState>>,
-    axum::extract::Query(params): axum::extract::Query<ListUsersParams>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let page = params.page.unwrap_or(1).max(1);
-    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
-
-    let (users, total) = state.db.list_all_users(page, per_page).await.map_err(|_| {
+    let api_keys = state.db.list_api_keys().await.map_err(|_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to fetch users"})),
+            Json(json!({"error": "Failed to fetch API keys"})),
         )
     })?;
 
-    Ok((
-        StatusCode::OK,
-        Json(json!({
-            "users": users,
-            "total": total,
-            "page": page,
-            "per_page": per_page,
-            "total_pages": (total as f64 / per_page as f64).ceil() as i64
-        })),
-    ))
+    Ok((StatusCode::OK, Json(json!({ "api_keys": api_keys }))))
 }
 
-/// Handler for promoting a user to admin (admin only)
-pub async fn admin_promote_user(
+/// Handler for revoking an API key (admin only)
+pub async fn admin_revoke_api_key(
     State(state): State<Arc<AppState>>,
     Extension(admin_user_id): Extension<Uuid>,
-    Json(payload): Json<crate::models::PromoteToAdminRequest>,
+    Path(key_id): Path<Uuid>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Check if the target user exists
-    let target_user = state
-        .db
-        .find_user_by_id(payload.user_id)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "User not found"})),
-            )
-        })?;
-
-    // Check if user is already an admin
-    if state.db.is_user_admin(payload.user_id).await.map_err(|_| {
+    let revoked = state.db.revoke_api_key(key_id).await.map_err(|e| {
+        tracing::error!("Failed to revoke API key: {:?}", e);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Database error"})),
+            Json(json!({"error": "Failed to revoke API key"})),
         )
-    })? {
+    })?;
+
+    if !revoked {
         return Err((
-            StatusCode::CONFLICT,
-            Json(json!({"error": "User is already an admin"})),
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "API key not found or already revoked"})),
         ));
     }
 
-    // Promote user to admin
-    let admin_record = state
-        .db
-        .promote_to_admin(payload.user_id, admin_user_id)
+    tracing::info!("Admin {} revoked API key {}", admin_user_id, key_id);
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "API key revoked successfully"})),
+    ))
+}
+
+/// Query parameters for listing users
+#[derive(Debug, serde::Deserialize)]
+pub struct ListUsersParams {
+    pub page: Option<i32>,
+    pub per_page: Option<i32>,
+}
+
+/// Handler for the feature flags in effect, so frontends can hide what is
+/// turned off
+pub async fn list_features(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let features = state.features.all().await.map_err(|e| {
+        tracing::error!("Failed to read feature flags: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch features"})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({ "features": features }))))
+}
+
+/// Handler for overriding a feature flag at runtime (admin only)
+pub async fn admin_set_feature(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(feature): Path<String>,
+    Json(payload): Json<SetFeatureRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let feature: Feature = feature
+        .parse()
+        .map_err(|e: String| (StatusCode::NOT_FOUND, Json(json!({ "error": e }))))?;
+
+    state
+        .features
+        .set_override(feature, payload.enabled, admin_user_id)
         .await
         .map_err(|e| {
-            tracing::error!("Failed to promote user: {:?}", e);
+            tracing::error!("Failed to set feature flag: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to promote user to admin"})),
+                Json(json!({"error": "Failed to update feature"})),
             )
         })?;
 
+    tracing::info!(
+        "Admin {} set the {} feature to {:?}",
+        admin_user_id,
+        feature,
+        payload.enabled
+    );
+
+    let features = state.features.all().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to fetch features"})),
+        )
+    })?;
+
     Ok((
         StatusCode::OK,
         Json(json!({
-            "message": "User promoted to admin successfully",
-            "admin": {
-                "id": admin_record.id,
-                "user_id": admin_record.user_id,
-                "username": target_user.username,
-                "granted_by": admin_record.granted_by,
-                "created_at": admin_record.created_at
-            }
+            "feature": feature,
+            "state": features.get(&feature)
         })),
     ))
 }
 
-/// Handler for demoting an admin (admin only)
-pub async fn admin_demote_user(
+/// Handler for the maintenance windows in force, so frontends can show a
+/// banner before members hit a 503
+pub async fn maintenance_status(
     State(state): State<Arc<AppState>>,
-    Extension(admin_user_id): Extension<Uuid>,
-    Json(payload): Json<crate::models::PromoteToAdminRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Prevent self-demotion
-    if payload.user_id == admin_user_id {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Cannot demote yourself"})),
-        ));
-    }
-
-    // Check if the target user is actually an admin
-    if !state.db.is_user_admin(payload.user_id).await.map_err(|_| {
+    let windows = state.maintenance.list().await.map_err(|e| {
+        tracing::error!("Failed to read maintenance mode: {:?}", e);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Database error"})),
+            Json(json!({"error": "Failed to fetch maintenance status"})),
         )
-    })? {
+    })?;
+
+    Ok((StatusCode::OK, Json(json!({ "maintenance": windows }))))
+}
+
+/// Handler for starting maintenance on one service, or `all` (admin only).
+/// Starting it again updates the message and expected end.
+pub async fn admin_start_maintenance(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(scope): Path<String>,
+    locale: Locale,
+    Json(payload): Json<StartMaintenanceRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if !maintenance::is_valid_scope(&scope) {
         return Err((
             StatusCode::NOT_FOUND,
-            Json(json!({"error": "User is not an admin"})),
+            Json(json!({"error": format!("Unknown service: {}", scope)})),
         ));
     }
-
-    // Demote the admin
-    state.db.demote_admin(payload.user_id).await.map_err(|e| {
-        tracing::error!("Failed to demote admin: {:?}", e);
+    payload.validate().map_err(|e| {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to demote admin"})),
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format_validation_error(&e, locale)})),
         )
     })?;
 
-    Ok((
-        StatusCode::OK,
-        Json(json!({"message": "Admin privileges revoked successfully"})),
-    ))
+    let window = state
+        .maintenance
+        .start(
+            &scope,
+            &payload.message,
+            payload.expected_end,
+            admin_user_id,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to start maintenance: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to start maintenance"})),
+            )
+        })?;
+
+    tracing::warn!("Admin {} put {} into maintenance", admin_user_id, scope);
+
+    Ok((StatusCode::OK, Json(json!({ "maintenance": window }))))
 }
 
-/// Handler for checking if current user is admin
-pub async fn admin_check(
+/// Handler for ending maintenance (admin only)
+pub async fn admin_end_maintenance(
     State(state): State<Arc<AppState>>,
-    Extension(user_id): Extension<Uuid>,
+    Extension(admin_user_id): Extension<Uuid>,
+    Path(scope): Path<String>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    let is_admin = state.db.is_user_admin(user_id).await.map_err(|_| {
+    let ended = state.maintenance.end(&scope).await.map_err(|e| {
+        tracing::error!("Failed to end maintenance: {:?}", e);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Database error"})),
+            Json(json!({"error": "Failed to end maintenance"})),
         )
     })?;
 
-    Ok((StatusCode::OK, Json(json!({"is_admin": is_admin}))))
-}
+    if !ended {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("{} is not in maintenance", scope)})),
+        ));
+    }
 
-/// Query parameters for listing users
-#[derive(Debug, serde::Deserialize)]
-pub struct ListUsersParams {
-    pub page: Option<i32>,
-    pub per_page: Option<i32>,
+    tracing::warn!("Admin {} took {} out of maintenance", admin_user_id, scope);
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Maintenance ended"})),
+    ))
 }
 
 #[cfg(test)]
@@ -1244,6 +3134,7 @@ mod tests {
             reg_number: "2012345".to_string(), // Valid format: 20XXXXX
             year_joined: 2023,                 // Valid year between 2000-2099
             phone_number: "+923001234567".to_string(), // Valid format with country code
+            channel: OtpChannel::Email,
         };
         assert!(valid.validate().is_ok());
 
@@ -1254,6 +3145,7 @@ mod tests {
             reg_number: "2012345".to_string(),
             year_joined: 2023,
             phone_number: "+923001234567".to_string(),
+            channel: OtpChannel::Email,
         };
         assert!(invalid_email.validate().is_err());
     }
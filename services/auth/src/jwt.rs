@@ -2,6 +2,7 @@ use crate::models::{Claims, TokenType};
 use chrono::Utc;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use std::fmt;
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub enum JwtError {
@@ -35,8 +36,25 @@ impl JwtService {
         }
     }
 
-    /// Create an access token
-    pub fn create_access_token(&self, user_id: &str, username: &str) -> Result<String, JwtError> {
+    /// Create an access token for a session
+    pub fn create_access_token(
+        &self,
+        user_id: &str,
+        username: &str,
+        session_id: Uuid,
+    ) -> Result<String, JwtError> {
+        self.create_manager_access_token(user_id, username, session_id, Vec::new())
+    }
+
+    /// Create an access token for a session of a member who manages
+    /// `managed_events`
+    pub fn create_manager_access_token(
+        &self,
+        user_id: &str,
+        username: &str,
+        session_id: Uuid,
+        managed_events: Vec<Uuid>,
+    ) -> Result<String, JwtError> {
         let now = Utc::now().timestamp();
         let expires_at = now + self.access_token_expiry;
 
@@ -45,8 +63,10 @@ impl JwtService {
             username: username.to_string(),
             exp: expires_at,
             iat: now,
-            jti: uuid::Uuid::new_v4().to_string(),
+            jti: Uuid::new_v4().to_string(),
             token_type: TokenType::Access,
+            sid: Some(session_id),
+            managed_events,
         };
 
         encode(
@@ -67,8 +87,10 @@ impl JwtService {
             username: username.to_string(),
             exp: expires_at,
             iat: now,
-            jti: uuid::Uuid::new_v4().to_string(),
+            jti: Uuid::new_v4().to_string(),
             token_type: TokenType::Refresh,
+            sid: None,
+            managed_events: Vec::new(),
         };
 
         encode(
@@ -133,7 +155,7 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let result = jwt_service.create_access_token(user_id, username);
+        let result = jwt_service.create_access_token(user_id, username, Uuid::new_v4());
         assert!(result.is_ok());
 
         let token = result.unwrap();
@@ -159,7 +181,9 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let token = jwt_service.create_access_token(user_id, username).unwrap();
+        let token = jwt_service
+            .create_access_token(user_id, username, Uuid::new_v4())
+            .unwrap();
         let result = jwt_service.validate_token(&token);
 
         assert!(result.is_ok());
@@ -176,7 +200,9 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let token = jwt_service.create_access_token(user_id, username).unwrap();
+        let token = jwt_service
+            .create_access_token(user_id, username, Uuid::new_v4())
+            .unwrap();
         let result = jwt_service.validate_access_token(&token);
 
         assert!(result.is_ok());
@@ -212,7 +238,9 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let token = jwt_service.create_access_token(user_id, username).unwrap();
+        let token = jwt_service
+            .create_access_token(user_id, username, Uuid::new_v4())
+            .unwrap();
         let result = jwt_service.validate_refresh_token(&token);
 
         assert!(result.is_err());
@@ -235,7 +263,9 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let token = jwt_service1.create_access_token(user_id, username).unwrap();
+        let token = jwt_service1
+            .create_access_token(user_id, username, Uuid::new_v4())
+            .unwrap();
         let result = jwt_service2.validate_token(&token);
 
         assert!(result.is_err());
@@ -247,12 +277,25 @@ mod tests {
         let user_id = "123e4567-e89b-12d3-a456-426614174000";
         let username = "testuser";
 
-        let token = jwt_service.create_access_token(user_id, username).unwrap();
+        let session_id = Uuid::new_v4();
+
+        let token = jwt_service
+            .create_access_token(user_id, username, session_id)
+            .unwrap();
         let claims = jwt_service.validate_token(&token).unwrap();
 
         assert_eq!(claims.sub, user_id);
         assert_eq!(claims.username, username);
         assert_eq!(claims.token_type, TokenType::Access);
+        assert_eq!(claims.sid, Some(session_id));
         assert!(claims.exp > claims.iat);
+        assert!(claims.managed_events.is_empty());
+
+        let event_id = Uuid::new_v4();
+        let token = jwt_service
+            .create_manager_access_token(user_id, username, session_id, vec![event_id])
+            .unwrap();
+        let claims = jwt_service.validate_token(&token).unwrap();
+        assert_eq!(claims.managed_events, vec![event_id]);
     }
 }
@@ -1,36 +1,71 @@
 pub mod auth_middleware;
+pub mod cleanup;
 pub mod config;
 pub mod csrf;
 pub mod database;
 pub mod email_client;
+pub mod email_verification;
 pub mod handlers;
 pub mod jwt;
+pub mod login_history;
 pub mod models;
+pub mod password_policy;
+pub mod policies;
 pub mod security;
+pub mod session_cookies;
+pub mod sms_client;
 
+pub use common::startup::StartupError;
 pub use config::Config;
 pub use database::Database;
-pub use email_client::EmailClient;
+pub use email_client::{EmailClient, LogEmailClient};
 pub use jwt::JwtService;
+pub use sms_client::{LogSmsClient, SmsClient};
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
+use common::{
+    config::ConfigError, rate_limit::RateLimiter, versioning::CURRENT_VERSION, Features, KvStore,
+    Maintenance, PendingActions,
+};
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
+
+/// Routes left open during maintenance so admins can still sign in and
+/// frontends can explain what is going on
+const MAINTENANCE_EXEMPT: &[&str] = &["/login", "/refresh", "/csrf-token", "/maintenance"];
 
 pub struct AppState {
     pub db: Database,
     pub jwt_service: JwtService,
-    pub email_client: EmailClient,
+    pub email_client: Arc<dyn EmailClient>,
+    /// None when `SMS_BACKEND=none`; codes can then only be emailed
+    pub sms_client: Option<Arc<dyn SmsClient>>,
     pub config: Config,
+    /// Client for outbound lookups such as the password breach check
+    pub http_client: reqwest::Client,
+    /// Rate limit counters, shared between replicas when Redis is set up
+    pub kv: KvStore,
+    /// Feature flags, with admins' overrides from the database
+    pub features: Features,
+    /// Whether non-admins are being turned away
+    pub maintenance: Maintenance,
+    /// Destructive admin actions waiting for a second admin
+    pub pending_actions: PendingActions,
 }
 
-pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
-    let config = Config::from_env()?;
+/// Connect to the database, run migrations and build the shared state.
+/// Tests can skip this and construct `AppState` with their own database or
+/// email client.
+pub async fn build_state(config: Config) -> Result<Arc<AppState>, StartupError> {
     let db = Database::new(&config.database_url).await?;
+    let db = match &config.database_replica_url {
+        Some(url) => db.with_replica(common::ReadReplica::connect(url, 5)?),
+        None => db,
+    };
     db.migrate().await?;
 
     let jwt_service = JwtService::new(
@@ -39,25 +74,52 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
         config.jwt_refresh_token_expiry,
     );
 
-    let email_client = EmailClient::new(
-        config.email_service_url.clone(),
-        config.email_service_api_key.clone(),
-    );
+    let email_client = email_client::from_config(&config).map_err(|problem| ConfigError {
+        problems: vec![problem],
+    })?;
+    let sms_client = sms_client::from_config(&config);
+    let kv = KvStore::new(&config.kv);
+    let features = Features::new(db.pool().clone(), &config.features);
+    let maintenance = Maintenance::new(db.pool().clone(), "auth").exempt(MAINTENANCE_EXEMPT);
+    let pending_actions = PendingActions::new(db.pool().clone(), "auth");
 
-    let state = Arc::new(AppState {
+    Ok(Arc::new(AppState {
         db,
         jwt_service,
         email_client,
-        config: config.clone(),
-    });
+        sms_client,
+        config,
+        http_client: reqwest::Client::new(),
+        kv,
+        features,
+        maintenance,
+        pending_actions,
+    }))
+}
 
-    let cors = configure_cors(&config);
+/// Assemble the service's routes around already-built state
+pub fn create_app(state: Arc<AppState>) -> Router {
+    // Browsers only let the web app read the policy flag if it is exposed
+    let mut cors_settings = state.config.cors.clone();
+    cors_settings
+        .expose_headers
+        .push(policies::PENDING_POLICIES_HEADER.to_string());
+    let cors = common::configure_cors(
+        &cors_settings,
+        &[http::HeaderName::from_static(
+            login_history::DEVICE_ID_HEADER,
+        )],
+    );
 
-    let public_routes = Router::new()
+    // Routes worth guessing at or flooding are limited per client
+    let limiter = RateLimiter {
+        store: state.kv.clone(),
+        limit: state.config.rate_limit.clone(),
+        scope: "auth",
+    };
+    let limited_routes = Router::new()
         .route("/register", post(handlers::register))
         .route("/login", post(handlers::login))
-        .route("/refresh", post(handlers::refresh))
-        .route("/csrf-token", get(handlers::get_csrf_token))
         .route("/verify-email", post(handlers::verify_email))
         .route("/verify-otp", post(handlers::verify_email)) // Alias for frontend compatibility
         .route("/resend-verification", post(handlers::resend_verification))
@@ -66,12 +128,46 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             post(handlers::request_password_reset),
         )
         .route("/reset-password", post(handlers::reset_password))
+        .route_layer(middleware::from_fn_with_state(
+            limiter,
+            common::rate_limit::rate_limit,
+        ))
+        .with_state(state.clone());
+
+    let public_routes = Router::new()
+        .route("/refresh", post(handlers::refresh))
+        .route("/csrf-token", get(handlers::get_csrf_token))
+        .route("/password-policy", get(handlers::password_policy))
+        .route("/policies", get(handlers::list_policies))
+        .route("/features", get(handlers::list_features))
+        .route("/maintenance", get(handlers::maintenance_status))
         .with_state(state.clone());
 
     let protected_routes = Router::new()
         .route("/logout", post(handlers::logout))
-        .route("/me", get(handlers::me))
+        .route("/me", get(handlers::me).delete(handlers::delete_my_account))
         .route("/admin/check", get(handlers::admin_check))
+        .route("/me/email", post(handlers::change_email))
+        .route("/me/email/verify", post(handlers::confirm_email_change))
+        .route("/me/username", post(handlers::change_username))
+        .route("/me/login-history", get(handlers::my_login_history))
+        .route("/me/sessions", get(handlers::my_sessions))
+        .route(
+            "/me/sessions/:session_id",
+            delete(handlers::revoke_my_session),
+        )
+        .route("/roles", get(handlers::my_roles))
+        .route("/me/policies", get(handlers::my_policies))
+        .route(
+            "/me/notification-preferences",
+            get(handlers::my_notification_preferences)
+                .patch(handlers::update_my_notification_preferences),
+        )
+        .route(
+            "/me/requirements",
+            get(handlers::my_requirements).put(handlers::update_my_requirements),
+        )
+        .route("/policies/accept", post(handlers::accept_policy))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware::auth_middleware,
@@ -81,86 +177,97 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
     // Admin routes - require admin privileges
     let admin_routes = Router::new()
         .route("/admin/users", get(handlers::admin_list_users))
+        .route("/admin/users/:user_id", delete(handlers::admin_delete_user))
+        .route(
+            "/admin/users/:user_id/username",
+            post(handlers::admin_change_username),
+        )
+        .route(
+            "/admin/users/:user_id/suspension",
+            get(handlers::admin_list_suspensions)
+                .post(handlers::admin_suspend_user)
+                .delete(handlers::admin_lift_suspension),
+        )
+        .route(
+            "/admin/users/:user_id/force-verify",
+            post(handlers::admin_force_verify),
+        )
+        .route(
+            "/admin/users/:user_id/resend-verification",
+            post(handlers::admin_resend_verification),
+        )
         .route("/admin/promote", post(handlers::admin_promote_user))
         .route("/admin/demote", post(handlers::admin_demote_user))
+        .route(
+            "/admin/pending-actions",
+            get(handlers::admin_list_pending_actions),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/confirm",
+            post(handlers::admin_confirm_pending_action),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/reject",
+            post(handlers::admin_reject_pending_action),
+        )
+        .route("/admin/roles", get(handlers::admin_list_roles))
+        .route("/admin/roles/grant", post(handlers::admin_grant_role))
+        .route("/admin/roles/revoke", post(handlers::admin_revoke_role))
+        .route(
+            "/admin/stats",
+            get(handlers::admin_stats).layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route("/admin/policies", post(handlers::admin_publish_policy))
+        .route(
+            "/admin/policies/:policy_id/acceptances",
+            get(handlers::admin_list_policy_acceptances),
+        )
+        .route(
+            "/admin/api-keys",
+            get(handlers::admin_list_api_keys).post(handlers::admin_create_api_key),
+        )
+        .route(
+            "/admin/api-keys/:key_id",
+            delete(handlers::admin_revoke_api_key),
+        )
+        .route("/admin/features/:feature", put(handlers::admin_set_feature))
+        .route(
+            "/admin/maintenance/:scope",
+            put(handlers::admin_start_maintenance).delete(handlers::admin_end_maintenance),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware::admin_middleware,
         ))
         .with_state(state.clone());
 
-    let app = Router::new()
+    let api = Router::new()
+        .merge(limited_routes)
         .merge(public_routes)
         .merge(protected_routes)
-        .merge(admin_routes)
+        .merge(admin_routes);
+
+    // New clients use /v1; the unversioned paths stay for app releases
+    // that predate it, announcing their deprecation
+    common::Versions::new()
+        .mount(CURRENT_VERSION, api.clone())
+        .unversioned(api, state.config.unversioned.clone())
+        .into_router()
         .route("/health", get(|| async { "OK" }))
+        .layer(middleware::from_fn_with_state(
+            (state.clone(), state.maintenance.clone()),
+            common::maintenance::maintenance_mode::<AppState>,
+        ))
+        .layer(middleware::from_fn(common::i18n::negotiate_locale))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             csrf::csrf_protection_middleware,
         ))
-        .layer(cors);
-
-    Ok(app)
-}
-
-fn configure_cors(config: &Config) -> CorsLayer {
-    if config.cors_strict_mode {
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([
-                http::header::CONTENT_TYPE,
-                http::header::AUTHORIZATION,
-                http::HeaderName::from_static("x-csrf-token"),
-            ])
-            .allow_credentials(true)
-    } else if config.allowed_origins.contains(&"*".to_string()) {
-        // Development mode - allow all origins with all methods and headers
-        CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers(Any)
-            .allow_credentials(false) // Cannot use credentials with wildcard origin
-    } else {
-        // Specific origins mode (non-strict) - allow listed origins only
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers(Any)
-    }
+        .layer(DefaultBodyLimit::max(state.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.config.compression.layer())
+        .layer(cors)
 }
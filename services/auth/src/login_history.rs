@@ -0,0 +1,234 @@
+//! Where a login came from, for the login history, new-device alerts and
+//! the device refresh tokens are bound to.
+//!
+//! The client address comes from the headers set by the nginx gateway, and
+//! the country from a header set by a geo-aware proxy such as Cloudflare
+//! (`LOGIN_COUNTRY_HEADER`), when one is configured.
+
+use axum::http::HeaderMap;
+use std::fmt;
+
+/// Longest user agent kept, so a client cannot bloat the history table
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Header carrying the identifier a client generates once and keeps
+pub const DEVICE_ID_HEADER: &str = "x-device-id";
+
+/// Device identifiers must be long enough not to be guessable
+const DEVICE_ID_LEN: std::ops::RangeInclusive<usize> = 16..=128;
+
+/// Why a login against an existing account failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailure {
+    InvalidPassword,
+    EmailNotVerified,
+    Suspended,
+}
+
+impl LoginFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginFailure::InvalidPassword => "invalid_password",
+            LoginFailure::EmailNotVerified => "email_not_verified",
+            LoginFailure::Suspended => "account_suspended",
+        }
+    }
+}
+
+impl fmt::Display for LoginFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The client making a request, as far as the headers tell
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    /// ISO 3166-1 alpha-2 code, when a proxy resolved one
+    pub country: Option<String>,
+    /// Client-generated device identifier, when well formed
+    pub device_id: Option<String>,
+}
+
+impl ClientInfo {
+    pub fn from_headers(headers: &HeaderMap, country_header: Option<&str>) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+
+        let ip_address = header("x-real-ip")
+            .or_else(|| header("x-forwarded-for").and_then(|chain| chain.split(',').next()))
+            .map(|ip| ip.trim().to_string());
+        let user_agent =
+            header("user-agent").map(|agent| agent.chars().take(MAX_USER_AGENT_LEN).collect());
+        let country = country_header
+            .and_then(header)
+            .map(str::to_uppercase)
+            // XX and T1 are Cloudflare's "unknown" and "Tor"
+            .filter(|code| {
+                code.len() == 2
+                    && code.chars().all(|c| c.is_ascii_uppercase())
+                    && code != "XX"
+                    && code != "T1"
+            });
+        let device_id = header(DEVICE_ID_HEADER)
+            .filter(|id| {
+                DEVICE_ID_LEN.contains(&id.len())
+                    && id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+            .map(str::to_string);
+
+        Self {
+            ip_address,
+            user_agent,
+            country,
+            device_id,
+        }
+    }
+
+    /// Browser and operating system, e.g. "Firefox on Linux". Versions are
+    /// left out so that a browser update does not look like a new device.
+    pub fn device(&self) -> String {
+        let Some(agent) = self.user_agent.as_deref() else {
+            return "Unknown device".to_string();
+        };
+
+        // Order matters: Edge and Opera also claim to be Chrome, and Chrome
+        // also claims to be Safari
+        let browser = [
+            ("Edg/", "Edge"),
+            ("OPR/", "Opera"),
+            ("Firefox/", "Firefox"),
+            ("Chrome/", "Chrome"),
+            ("CriOS/", "Chrome"),
+            ("Safari/", "Safari"),
+            ("curl/", "curl"),
+        ]
+        .iter()
+        .find(|(token, _)| agent.contains(token))
+        .map_or("Unknown browser", |(_, name)| name);
+
+        let os = [
+            ("Android", "Android"),
+            ("iPhone", "iOS"),
+            ("iPad", "iPadOS"),
+            ("Windows", "Windows"),
+            ("Mac OS X", "macOS"),
+            ("CrOS", "ChromeOS"),
+            ("Linux", "Linux"),
+        ]
+        .iter()
+        .find(|(token, _)| agent.contains(token))
+        .map(|(_, name)| name);
+
+        match os {
+            Some(os) => format!("{} on {}", browser, os),
+            None => browser.to_string(),
+        }
+    }
+}
+
+/// What is already known about where a user logs in from
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct LoginOrigins {
+    pub has_history: bool,
+    pub known_device: bool,
+    pub known_country: bool,
+}
+
+impl LoginOrigins {
+    /// Whether a successful login deserves an alert. The very first login
+    /// never does, and country only counts when it could be resolved.
+    pub fn is_unfamiliar(&self, client: &ClientInfo) -> bool {
+        self.has_history
+            && (!self.known_device || (client.country.is_some() && !self.known_country))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const FIREFOX_LINUX: &str =
+        "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+    const EDGE_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+    fn client(user_agent: &str) -> ClientInfo {
+        ClientInfo {
+            user_agent: Some(user_agent.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_device_ignores_versions() {
+        assert_eq!(client(FIREFOX_LINUX).device(), "Firefox on Linux");
+        assert_eq!(client(EDGE_WINDOWS).device(), "Edge on Windows");
+        assert_eq!(client(SAFARI_IPHONE).device(), "Safari on iOS");
+        assert_eq!(client("curl/8.5.0").device(), "curl");
+        assert_eq!(ClientInfo::default().device(), "Unknown device");
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        headers.insert("user-agent", HeaderValue::from_static(FIREFOX_LINUX));
+        headers.insert("cf-ipcountry", HeaderValue::from_static("pk"));
+
+        let info = ClientInfo::from_headers(&headers, Some("CF-IPCountry"));
+        assert_eq!(info.ip_address.as_deref(), Some("203.0.113.7"));
+        assert_eq!(info.country.as_deref(), Some("PK"));
+        assert_eq!(ClientInfo::from_headers(&headers, None).country, None);
+
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.2"));
+        headers.insert("cf-ipcountry", HeaderValue::from_static("XX"));
+        let info = ClientInfo::from_headers(&headers, Some("CF-IPCountry"));
+        assert_eq!(info.ip_address.as_deref(), Some("198.51.100.2"));
+        assert_eq!(info.country, None);
+        assert_eq!(info.device_id, None);
+
+        headers.insert(
+            DEVICE_ID_HEADER,
+            HeaderValue::from_static("3f2b8c1e-5d7a-4e9b-8c6f-1a2b3c4d5e6f"),
+        );
+        let info = ClientInfo::from_headers(&headers, None);
+        assert_eq!(
+            info.device_id.as_deref(),
+            Some("3f2b8c1e-5d7a-4e9b-8c6f-1a2b3c4d5e6f")
+        );
+
+        headers.insert(DEVICE_ID_HEADER, HeaderValue::from_static("short"));
+        assert_eq!(ClientInfo::from_headers(&headers, None).device_id, None);
+    }
+
+    #[test]
+    fn test_unfamiliar_logins() {
+        let mut from_pk = client(FIREFOX_LINUX);
+        from_pk.country = Some("PK".to_string());
+        let known = |device, country| LoginOrigins {
+            has_history: true,
+            known_device: device,
+            known_country: country,
+        };
+
+        assert!(!LoginOrigins::default().is_unfamiliar(&from_pk));
+        assert!(!known(true, true).is_unfamiliar(&from_pk));
+        assert!(known(false, true).is_unfamiliar(&from_pk));
+        assert!(known(true, false).is_unfamiliar(&from_pk));
+        assert!(!known(true, false).is_unfamiliar(&client(FIREFOX_LINUX)));
+    }
+}
@@ -1,8 +1,13 @@
 use chrono::{DateTime, Utc};
+use common::{api_keys::ApiKeyScope, PeriodCount, Role};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::email_verification::OtpChannel;
+use crate::login_history::ClientInfo;
+use crate::policies::PolicyKind;
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
     pub id: Uuid,
@@ -10,11 +15,14 @@ pub struct User {
     pub email: String,
     pub password_hash: String,
     pub salt: String,
+    pub pepper_version: i32,
     pub reg_number: String,
     pub year_joined: i32,
     pub phone_number: String,
     pub email_verified: bool,
     pub email_verified_at: Option<DateTime<Utc>>,
+    pub is_novice: bool,
+    pub is_esl: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -26,17 +34,74 @@ pub struct RefreshToken {
     pub token_hash: String,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    /// Device identifier the token is bound to, if any
+    pub device_id: Option<String>,
+    /// Browser and operating system the token was issued to
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct CsrfToken {
+impl RefreshToken {
+    /// Whether the token may be used by this client. Tokens issued before
+    /// device binding, or to clients that sent no device identifier, are
+    /// accepted from anywhere and bound on their next use.
+    pub fn accepts(&self, client: &ClientInfo) -> bool {
+        let Some(device_id) = &self.device_id else {
+            return true;
+        };
+        client.device_id.as_ref() == Some(device_id)
+            && self
+                .device
+                .as_ref()
+                .is_none_or(|device| *device == client.device())
+    }
+}
+
+/// A signed-in device, as listed to its owner
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
     pub id: Uuid,
-    pub token: String,
-    pub user_id: Option<Uuid>,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
     pub expires_at: DateTime<Utc>,
+    /// Whether this is the device making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn new(token: RefreshToken, client: &ClientInfo) -> Self {
+        Self {
+            current: token.device_id.is_some() && token.device_id == client.device_id,
+            id: token.id,
+            device: token.device,
+            ip_address: token.ip_address,
+            created_at: token.created_at,
+            last_used_at: token.last_used_at,
+            expires_at: token.expires_at,
+        }
+    }
+}
+
+/// One login attempt, as shown in a user's login history
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct LoginHistoryEntry {
+    pub id: Uuid,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub device: String,
+    pub country: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Deleted members are shown as this followed by their number, so no one
+/// may register a username starting with it
+pub const DELETED_MEMBER_PREFIX: &str = "Deleted member #";
+
 // Regex validators - defined here but used as string literals in validation
 lazy_static::lazy_static! {
     pub static ref RE_REG_NUMBER: regex::Regex = regex::Regex::new(r"^20\d{5}$").unwrap();
@@ -57,6 +122,9 @@ pub struct RegisterRequest {
     pub year_joined: i32,
     #[validate(regex(path = *RE_PHONE))]
     pub phone_number: String,
+    /// Where to send the verification code
+    #[serde(default)]
+    pub channel: OtpChannel,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -89,6 +157,9 @@ pub struct UserResponse {
     pub year_joined: i32,
     pub phone_number: String,
     pub email_verified: bool,
+    /// Speaker categories, set by admins
+    pub is_novice: bool,
+    pub is_esl: bool,
     pub created_at: DateTime<Utc>,
 }
 
@@ -102,6 +173,8 @@ impl From<User> for UserResponse {
             year_joined: user.year_joined,
             phone_number: user.phone_number,
             email_verified: user.email_verified,
+            is_novice: user.is_novice,
+            is_esl: user.is_esl,
             created_at: user.created_at,
         }
     }
@@ -115,6 +188,15 @@ pub struct Claims {
     pub iat: i64,
     pub jti: String, // JWT ID - unique identifier for each token
     pub token_type: TokenType,
+    /// Session (refresh token) an access token belongs to; absent from
+    /// refresh tokens and from access tokens issued before sessions were
+    /// tracked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Events an admin has made the user a manager of, so other services
+    /// can let them run those events without asking who they are
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub managed_events: Vec<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -136,11 +218,14 @@ mod tests {
             email: "test@example.com".to_string(),
             password_hash: "hash".to_string(),
             salt: "salt".to_string(),
+            pepper_version: 1,
             reg_number: "REG123".to_string(),
             year_joined: 2023,
             phone_number: "1234567890".to_string(),
             email_verified: false,
             email_verified_at: None,
+            is_novice: true,
+            is_esl: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -151,6 +236,38 @@ mod tests {
         assert_eq!(response.id, user_id);
         assert_eq!(response.username, "testuser");
         assert_eq!(response.email, "test@example.com");
+        assert!(response.is_novice && !response.is_esl);
+    }
+
+    #[test]
+    fn test_refresh_token_device_binding() {
+        let client = |device_id: Option<&str>, user_agent: &str| ClientInfo {
+            device_id: device_id.map(str::to_string),
+            user_agent: Some(user_agent.to_string()),
+            ..Default::default()
+        };
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        let mut token = RefreshToken {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            token_hash: "hash".to_string(),
+            expires_at: Utc::now(),
+            created_at: Utc::now(),
+            device_id: None,
+            device: None,
+            ip_address: None,
+            last_used_at: None,
+        };
+
+        // Unbound tokens work anywhere
+        assert!(token.accepts(&client(None, "curl/8.5.0")));
+
+        token.device_id = Some("device-one-0123456789".to_string());
+        token.device = Some("Firefox on Linux".to_string());
+        assert!(token.accepts(&client(Some("device-one-0123456789"), firefox)));
+        assert!(!token.accepts(&client(Some("device-two-0123456789"), firefox)));
+        assert!(!token.accepts(&client(None, firefox)));
+        assert!(!token.accepts(&client(Some("device-one-0123456789"), "curl/8.5.0")));
     }
 
     #[test]
@@ -174,6 +291,7 @@ mod tests {
             reg_number: "2012345".to_string(), // Valid format: 20XXXXX
             year_joined: 2023,                 // Valid year between 2000-2099
             phone_number: "+923001234567".to_string(), // Valid format with country code
+            channel: OtpChannel::Email,
         };
         assert!(valid_request.validate().is_ok());
 
@@ -184,6 +302,7 @@ mod tests {
             reg_number: "2012345".to_string(),
             year_joined: 2023,
             phone_number: "+923001234567".to_string(),
+            channel: OtpChannel::Email,
         };
         assert!(invalid_username.validate().is_err());
 
@@ -194,6 +313,7 @@ mod tests {
             reg_number: "2012345".to_string(),
             year_joined: 2023,
             phone_number: "+923001234567".to_string(),
+            channel: OtpChannel::Email,
         };
         assert!(invalid_email.validate().is_err());
 
@@ -204,6 +324,7 @@ mod tests {
             reg_number: "2012345".to_string(),
             year_joined: 2023,
             phone_number: "+923001234567".to_string(),
+            channel: OtpChannel::Email,
         };
         assert!(invalid_password.validate().is_err());
     }
@@ -224,11 +345,14 @@ mod tests {
     }
 }
 
-// Email verification and password reset models
+/// A live one-time code sent to `email` for one purpose
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct EmailVerificationToken {
     pub id: Uuid,
     pub user_id: Uuid,
+    pub purpose: String,
+    /// Address the code was sent to, and whose control it proves
+    pub email: String,
     pub otp: String,
     pub attempts: i32,
     pub expires_at: DateTime<Utc>,
@@ -236,17 +360,45 @@ pub struct EmailVerificationToken {
     pub last_sent_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
-pub struct PasswordResetToken {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub email: String,
-    pub otp: String, // Hashed OTP
-    pub attempts: i32,
-    pub expires_at: DateTime<Utc>,
-    pub created_at: DateTime<Utc>,
-    pub last_sent_at: DateTime<Utc>,
-    pub used: bool,
+/// Request to move one's account to a new address, confirmed with the
+/// password
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeEmailRequest {
+    #[validate(email)]
+    pub new_email: String,
+    #[validate(length(min = 1))]
+    pub password: String,
+}
+
+/// The code sent to the new address, completing an email change
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmEmailChangeRequest {
+    #[validate(length(equal = 6))]
+    pub otp: String,
+}
+
+/// A new username, for oneself or (by an admin) for another member
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeUsernameRequest {
+    #[validate(length(min = 3, max = 50))]
+    pub username: String,
+}
+
+/// Dietary and accessibility requirements a member has shared. Both are
+/// free text; leaving one blank clears it.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRequirementsRequest {
+    #[validate(length(max = 500))]
+    pub dietary: Option<String>,
+    #[validate(length(max = 1000))]
+    pub accessibility: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, sqlx::FromRow)]
+pub struct MemberRequirements {
+    pub dietary: Option<String>,
+    pub accessibility: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -267,6 +419,8 @@ pub struct ResendOtpRequest {
 pub struct RequestPasswordResetRequest {
     #[validate(email)]
     pub email: String,
+    #[serde(default)]
+    pub channel: OtpChannel,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -283,6 +437,8 @@ pub struct ResetPasswordRequest {
 pub struct ResendVerificationRequest {
     #[validate(email)]
     pub email: String,
+    #[serde(default)]
+    pub channel: OtpChannel,
 }
 
 // Admin-related models
@@ -313,6 +469,61 @@ impl From<AdminUser> for AdminUserResponse {
     }
 }
 
+/// A change an admin made to a member's account, logged in `account_audit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountAction {
+    EmailForceVerified,
+    VerificationResent,
+}
+
+impl AccountAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountAction::EmailForceVerified => "email_force_verified",
+            AccountAction::VerificationResent => "verification_resent",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct AccountAuditEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub action: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Resend a member's verification code (admin only); the body is optional
+#[derive(Debug, Default, Deserialize)]
+pub struct AdminResendVerificationRequest {
+    #[serde(default)]
+    pub channel: OtpChannel,
+}
+
+/// Suspend a member (admin only). Without `until` the suspension lasts
+/// until it is lifted.
+#[derive(Debug, Deserialize, Validate)]
+pub struct SuspendUserRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub reason: String,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Suspension {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub reason: String,
+    pub suspended_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// None for an indefinite suspension
+    pub ends_at: Option<DateTime<Utc>>,
+    pub lifted_at: Option<DateTime<Utc>>,
+    pub lifted_by: Option<Uuid>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UserListResponse {
     pub id: Uuid,
@@ -326,11 +537,140 @@ pub struct UserListResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Request to delete one's own account, confirmed with the password
+#[derive(Debug, Deserialize, Validate)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 1))]
+    pub password: String,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct PromoteToAdminRequest {
     pub user_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RoleChangeRequest {
+    pub user_id: Uuid,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct RoleAssignment {
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A published version of a policy
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PolicyDocument {
+    pub id: Uuid,
+    pub kind: String,
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub published_by: Option<Uuid>,
+    pub published_at: DateTime<Utc>,
+}
+
+/// The latest version of a policy a member has not accepted yet
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PendingPolicy {
+    pub id: Uuid,
+    pub kind: String,
+    pub version: i32,
+    pub title: String,
+}
+
+/// A member's acceptance of a policy version
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PolicyAcceptance {
+    pub policy_id: Uuid,
+    pub kind: String,
+    pub version: i32,
+    pub accepted_at: DateTime<Utc>,
+}
+
+/// An acceptance as recorded for admins: who, when and from where
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PolicyAcceptanceRecord {
+    pub user_id: Uuid,
+    pub username: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub accepted_at: DateTime<Utc>,
+}
+
+/// Request to publish a new version of a policy
+#[derive(Debug, Deserialize, Validate)]
+pub struct PublishPolicyRequest {
+    pub kind: PolicyKind,
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(min = 1))]
+    pub body: String,
+}
+
+/// Request to accept a policy version
+#[derive(Debug, Deserialize)]
+pub struct AcceptPolicyRequest {
+    pub policy_id: Uuid,
+}
+
+/// Request to create a read-only API key for an integration
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    /// Services the key may read from
+    #[validate(length(min = 1))]
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+/// An API key as admins see it. The key itself is only shown on creation.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Start of the key, to tell keys apart
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Turn a feature on or off for the organisation (admin only). `null`
+/// removes the override so the deployment's configuration applies again.
+#[derive(Debug, Deserialize)]
+pub struct SetFeatureRequest {
+    pub enabled: Option<bool>,
+}
+
+/// Put a service, or `all` of them, into maintenance (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct StartMaintenanceRequest {
+    /// Shown to members while they are turned away
+    #[validate(length(min = 1, max = 500))]
+    pub message: String,
+    /// When admins expect to be done
+    pub expected_end: Option<DateTime<Utc>>,
+}
+
+/// Account numbers for the admin dashboard
+#[derive(Debug, Serialize)]
+pub struct UserStats {
+    pub total_users: i64,
+    pub verified_users: i64,
+    /// Users issued a session in the last `ACTIVE_DAYS` days
+    pub active_users: i64,
+    pub registrations_per_week: Vec<PeriodCount>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AdminListUsersResponse {
     pub users: Vec<UserListResponse>,
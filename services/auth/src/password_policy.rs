@@ -0,0 +1,276 @@
+//! Rules new passwords must meet, at registration and on reset.
+//!
+//! Passwords must be long enough, contain any required character classes,
+//! not be among the most common passwords, and optionally not appear in a
+//! known breach. Breaches are looked up in the Have I Been Pwned range API
+//! by k-anonymity: only the first five characters of the password's SHA-1
+//! hash leave the server, and the match is made locally.
+
+use common::config::EnvReader;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Longest password accepted, to bound hashing time
+pub const MAX_LENGTH: usize = 128;
+
+/// Shortest minimum length that may be configured
+const LEAST_MIN_LENGTH: usize = 8;
+
+/// How long to wait for the breach API before letting the password through
+const BREACH_TIMEOUT_SECS: u64 = 3;
+
+/// Passwords rejected outright, one per line after the header comments
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// Kinds of character a policy can require
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharacterClass {
+    Lower,
+    Upper,
+    Digit,
+    Symbol,
+}
+
+impl CharacterClass {
+    pub const ALL: &'static [CharacterClass] = &[
+        CharacterClass::Lower,
+        CharacterClass::Upper,
+        CharacterClass::Digit,
+        CharacterClass::Symbol,
+    ];
+
+    /// Name used in `PASSWORD_REQUIRED_CLASSES`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CharacterClass::Lower => "lower",
+            CharacterClass::Upper => "upper",
+            CharacterClass::Digit => "digit",
+            CharacterClass::Symbol => "symbol",
+        }
+    }
+
+    pub fn matches(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Lower => c.is_lowercase(),
+            CharacterClass::Upper => c.is_uppercase(),
+            CharacterClass::Digit => c.is_numeric(),
+            CharacterClass::Symbol => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            CharacterClass::Lower => "a lowercase letter",
+            CharacterClass::Upper => "an uppercase letter",
+            CharacterClass::Digit => "a digit",
+            CharacterClass::Symbol => "a symbol",
+        }
+    }
+}
+
+impl fmt::Display for CharacterClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CharacterClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CharacterClass::ALL
+            .iter()
+            .copied()
+            .find(|class| class.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid PASSWORD_REQUIRED_CLASSES: '{}' is not one of lower, upper, digit, symbol",
+                    s
+                )
+            })
+    }
+}
+
+/// The rules, as advertised to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub required_classes: Vec<CharacterClass>,
+    pub reject_common: bool,
+    pub check_breaches: bool,
+    /// Base URL of the breach range API
+    #[serde(skip)]
+    pub breach_api_url: String,
+}
+
+impl PasswordPolicy {
+    /// Read the `PASSWORD_*` policy variables, recording problems on `env`
+    pub fn read(env: &mut EnvReader) -> Self {
+        let min_length: usize = env.parse("PASSWORD_MIN_LENGTH");
+        if !(LEAST_MIN_LENGTH..=MAX_LENGTH).contains(&min_length) {
+            env.check::<(), _>(Err(format!(
+                "PASSWORD_MIN_LENGTH must be between {} and {}",
+                LEAST_MIN_LENGTH, MAX_LENGTH
+            )));
+        }
+
+        let mut required_classes = Vec::new();
+        for name in env
+            .string("PASSWORD_REQUIRED_CLASSES")
+            .split(',')
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty())
+        {
+            if let Some(class) = env.check(name.parse::<CharacterClass>()) {
+                if !required_classes.contains(&class) {
+                    required_classes.push(class);
+                }
+            }
+        }
+
+        Self {
+            min_length,
+            max_length: MAX_LENGTH,
+            required_classes,
+            reject_common: env.parse("PASSWORD_REJECT_COMMON"),
+            check_breaches: env.parse("PASSWORD_BREACH_CHECK"),
+            breach_api_url: env
+                .string("PASSWORD_BREACH_API_URL")
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+
+    /// Every rule the password breaks, apart from appearing in a breach
+    pub fn violations(&self, password: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            problems.push(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            ));
+        }
+        if length > self.max_length {
+            problems.push(format!(
+                "Password must be at most {} characters",
+                self.max_length
+            ));
+        }
+        for class in &self.required_classes {
+            if !password.chars().any(|c| class.matches(c)) {
+                problems.push(format!("Password must contain {}", class.description()));
+            }
+        }
+        if self.reject_common && is_common(password) {
+            problems.push("Password is too common".to_string());
+        }
+
+        problems
+    }
+
+    /// Whether the password appears in a known breach. Lookups that fail
+    /// count as not breached, so an outage never blocks sign-ups.
+    pub async fn is_breached(&self, client: &reqwest::Client, password: &str) -> bool {
+        if !self.check_breaches {
+            return false;
+        }
+
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+        let response = client
+            .get(format!("{}/{}", self.breach_api_url, prefix))
+            .header("Add-Padding", "true")
+            .timeout(Duration::from_secs(BREACH_TIMEOUT_SECS))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        let body = match response {
+            Ok(response) => response.text().await,
+            Err(e) => Err(e),
+        };
+        match body {
+            Ok(body) => range_contains(&body, suffix),
+            Err(e) => {
+                tracing::warn!("Password breach check failed: {}", e);
+                false
+            }
+        }
+    }
+}
+
+fn is_common(password: &str) -> bool {
+    let password = password.to_lowercase();
+    COMMON_PASSWORDS
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .any(|line| line.trim() == password)
+}
+
+/// Whether a range API response lists `suffix` as seen at least once.
+/// Padding entries carry a count of 0.
+fn range_contains(body: &str, suffix: &str) -> bool {
+    body.lines().any(|line| {
+        line.trim()
+            .split_once(':')
+            .is_some_and(|(candidate, count)| {
+                candidate.eq_ignore_ascii_case(suffix)
+                    && count.trim().parse::<u64>().unwrap_or(0) > 0
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(required_classes: Vec<CharacterClass>) -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            max_length: MAX_LENGTH,
+            required_classes,
+            reject_common: true,
+            check_breaches: false,
+            breach_api_url: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_length_and_classes() {
+        let policy = policy(vec![CharacterClass::Upper, CharacterClass::Symbol]);
+        assert_eq!(
+            policy.violations("short"),
+            [
+                "Password must be at least 10 characters",
+                "Password must contain an uppercase letter",
+                "Password must contain a symbol",
+            ]
+        );
+        assert!(policy.violations("Long enough!").is_empty());
+        assert_eq!(policy.violations(&"A!".repeat(65)).len(), 1);
+    }
+
+    #[test]
+    fn test_common_passwords_are_rejected_in_any_case() {
+        let policy = policy(Vec::new());
+        assert_eq!(policy.violations("QwertyUIOP"), ["Password is too common"]);
+        assert!(policy.violations("correct horse battery").is_empty());
+        assert!(!is_common(
+            "# Passwords too common to allow, one per line, compared case-insensitively."
+        ));
+    }
+
+    #[test]
+    fn test_range_matches_suffix_but_not_padding() {
+        let body =
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n00D4F6E8FA6EECAD2A3AA415EEC418D38EC:0\r\n";
+        assert!(range_contains(body, "0018a45c4d1def81644b54ab7f969b88d65"));
+        assert!(!range_contains(body, "00D4F6E8FA6EECAD2A3AA415EEC418D38EC"));
+        assert!(!range_contains(body, "FFFFF6E8FA6EECAD2A3AA415EEC418D38EC"));
+    }
+}
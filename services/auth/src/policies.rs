@@ -0,0 +1,108 @@
+//! Versioned policy documents (terms of service, privacy and media
+//! policies) and members' acceptance of them.
+//!
+//! Publishing a new version of a policy leaves everyone needing to accept
+//! it again. The auth middleware flags members with outstanding policies on
+//! every response, and `/me` lists them, so the web app can ask for consent.
+
+use axum::http::{HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::models::PendingPolicy;
+
+/// Response header listing the kinds of policy the member has yet to
+/// accept, e.g. `terms, media`
+pub const PENDING_POLICIES_HEADER: HeaderName = HeaderName::from_static("x-pending-policies");
+
+/// Policies the authenticated member has yet to accept, added to the
+/// request extensions by the auth middleware
+#[derive(Debug, Clone, Default)]
+pub struct PendingPolicies(pub Vec<PendingPolicy>);
+
+/// A kind of policy members consent to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyKind {
+    /// Terms of service
+    Terms,
+    /// How members' personal data is handled
+    Privacy,
+    /// Consent to photos and recordings being published
+    Media,
+}
+
+impl PolicyKind {
+    pub const ALL: &'static [PolicyKind] =
+        &[PolicyKind::Terms, PolicyKind::Privacy, PolicyKind::Media];
+
+    /// Value stored in `policy_documents.kind`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyKind::Terms => "terms",
+            PolicyKind::Privacy => "privacy",
+            PolicyKind::Media => "media",
+        }
+    }
+}
+
+impl fmt::Display for PolicyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PolicyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PolicyKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| format!("Unknown policy kind: {}", s))
+    }
+}
+
+/// Value of `PENDING_POLICIES_HEADER`, or None when nothing is outstanding
+pub fn pending_header(pending: &[PendingPolicy]) -> Option<HeaderValue> {
+    if pending.is_empty() {
+        return None;
+    }
+    let kinds: Vec<&str> = pending.iter().map(|policy| policy.kind.as_str()).collect();
+    HeaderValue::from_str(&kinds.join(", ")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_round_trip() {
+        for kind in PolicyKind::ALL {
+            assert_eq!(kind.as_str().parse::<PolicyKind>().unwrap(), *kind);
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                serde_json::json!(kind.as_str())
+            );
+        }
+        assert!("cookies".parse::<PolicyKind>().is_err());
+    }
+
+    #[test]
+    fn test_pending_header() {
+        assert_eq!(pending_header(&[]), None);
+
+        let pending = |kind: &str| PendingPolicy {
+            id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            version: 2,
+            title: "Policy".to_string(),
+        };
+        assert_eq!(
+            pending_header(&[pending("terms"), pending("media")]).unwrap(),
+            "terms, media"
+        );
+    }
+}
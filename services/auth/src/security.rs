@@ -4,7 +4,7 @@ use argon2::{
 };
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -12,6 +12,7 @@ type HmacSha256 = Hmac<Sha256>;
 pub enum SecurityError {
     HashingError(String),
     VerificationError,
+    UnknownPepper(i32),
 }
 
 impl fmt::Display for SecurityError {
@@ -19,6 +20,9 @@ impl fmt::Display for SecurityError {
         match self {
             SecurityError::HashingError(msg) => write!(f, "Hashing error: {}", msg),
             SecurityError::VerificationError => write!(f, "Verification failed"),
+            SecurityError::UnknownPepper(version) => {
+                write!(f, "No pepper configured for version {}", version)
+            }
         }
     }
 }
@@ -57,6 +61,94 @@ pub fn verify_password(password: &str, hash: &str, pepper: &str) -> Result<bool,
     }
 }
 
+/// Password peppers by version: the current one, used for new hashes, and
+/// retired ones kept so that older hashes still verify until rehashed
+#[derive(Debug, Clone)]
+pub struct Peppers {
+    current_version: i32,
+    by_version: BTreeMap<i32, String>,
+}
+
+impl Peppers {
+    pub fn new(current_version: i32, current: impl Into<String>) -> Self {
+        Self {
+            current_version,
+            by_version: BTreeMap::from([(current_version, current.into())]),
+        }
+    }
+
+    /// Keep a retired pepper for verifying hashes made with it
+    pub fn add_legacy(&mut self, version: i32, pepper: impl Into<String>) -> Result<(), String> {
+        if self.by_version.contains_key(&version) {
+            return Err(format!("Pepper version {} is configured twice", version));
+        }
+        self.by_version.insert(version, pepper.into());
+        Ok(())
+    }
+
+    /// Parse `PASSWORD_LEGACY_PEPPERS`: comma-separated `version:pepper` pairs
+    pub fn parse_legacy(raw: &str) -> Result<Vec<(i32, String)>, String> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (version, pepper) = entry
+                    .split_once(':')
+                    .filter(|(_, pepper)| !pepper.is_empty())
+                    .ok_or_else(|| {
+                        "Invalid PASSWORD_LEGACY_PEPPERS: expected version:pepper pairs".to_string()
+                    })?;
+                let version = version.trim().parse().map_err(|_| {
+                    format!(
+                        "Invalid PASSWORD_LEGACY_PEPPERS: '{}' is not a version number",
+                        version
+                    )
+                })?;
+                Ok((version, pepper.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn current_version(&self) -> i32 {
+        self.current_version
+    }
+
+    pub fn current(&self) -> &str {
+        &self.by_version[&self.current_version]
+    }
+
+    pub fn get(&self, version: i32) -> Option<&str> {
+        self.by_version.get(&version).map(String::as_str)
+    }
+}
+
+/// Outcome of checking a password against a versioned hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordCheck {
+    Mismatch,
+    Match,
+    /// Correct, but hashed with a retired pepper and due for a rehash
+    MatchOutdated,
+}
+
+/// Verify a password against a hash made with the pepper of `pepper_version`
+pub fn verify_password_versioned(
+    password: &str,
+    hash: &str,
+    pepper_version: i32,
+    peppers: &Peppers,
+) -> Result<PasswordCheck, SecurityError> {
+    let pepper = peppers
+        .get(pepper_version)
+        .ok_or(SecurityError::UnknownPepper(pepper_version))?;
+
+    Ok(match verify_password(password, hash, pepper)? {
+        false => PasswordCheck::Mismatch,
+        true if pepper_version == peppers.current_version() => PasswordCheck::Match,
+        true => PasswordCheck::MatchOutdated,
+    })
+}
+
 /// Hash a username with a salt and pepper for storage
 pub fn hash_username(username: &str, pepper: &str) -> Result<(String, String), SecurityError> {
     let salt = SaltString::generate(&mut OsRng);
@@ -229,4 +321,41 @@ mod tests {
         // Different peppers should produce different hashes
         assert_ne!(hash1, hash2);
     }
+
+    #[test]
+    fn test_verify_password_versioned_after_rotation() {
+        let password = "test_password_123";
+        let (old_hash, _) = hash_password(password, "old_pepper").unwrap();
+        let (new_hash, _) = hash_password(password, "new_pepper").unwrap();
+
+        let mut peppers = Peppers::new(2, "new_pepper");
+        peppers.add_legacy(1, "old_pepper").unwrap();
+
+        let check = |password, hash, version| {
+            verify_password_versioned(password, hash, version, &peppers).unwrap()
+        };
+        assert_eq!(check(password, &new_hash, 2), PasswordCheck::Match);
+        assert_eq!(check(password, &old_hash, 1), PasswordCheck::MatchOutdated);
+        assert_eq!(
+            check("wrong_password", &old_hash, 1),
+            PasswordCheck::Mismatch
+        );
+        assert_eq!(check(password, &old_hash, 2), PasswordCheck::Mismatch);
+        assert!(matches!(
+            verify_password_versioned(password, &old_hash, 3, &peppers),
+            Err(SecurityError::UnknownPepper(3))
+        ));
+    }
+
+    #[test]
+    fn test_parse_legacy_peppers() {
+        assert_eq!(
+            Peppers::parse_legacy("1:first, 2:se:cond").unwrap(),
+            vec![(1, "first".to_string()), (2, "se:cond".to_string())]
+        );
+        assert!(Peppers::parse_legacy("").unwrap().is_empty());
+        assert!(Peppers::parse_legacy("first").is_err());
+        assert!(Peppers::parse_legacy("one:first").is_err());
+        assert!(Peppers::new(1, "a").add_legacy(1, "b").is_err());
+    }
 }
@@ -0,0 +1,203 @@
+//! Delivering access and refresh tokens as httpOnly cookies instead of in
+//! response bodies (`TOKEN_DELIVERY=cookie`), so browser scripts never see
+//! them. The other services read the access token cookie through
+//! `common::auth_middleware`.
+
+use axum::http::{header, HeaderMap, HeaderValue};
+use common::config::EnvReader;
+use std::fmt;
+use std::str::FromStr;
+
+pub use common::auth_middleware::ACCESS_TOKEN_COOKIE;
+
+/// Cookie holding the refresh token
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+
+/// Where login, email verification and refresh put the tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenDelivery {
+    /// In the JSON response, for clients to store and send as bearer tokens
+    #[default]
+    Body,
+    /// In httpOnly cookies the browser sends back on its own
+    Cookie,
+}
+
+impl FromStr for TokenDelivery {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "body" => Ok(TokenDelivery::Body),
+            "cookie" => Ok(TokenDelivery::Cookie),
+            other => Err(format!(
+                "Invalid TOKEN_DELIVERY '{}': expected body or cookie",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TokenDelivery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenDelivery::Body => write!(f, "body"),
+            TokenDelivery::Cookie => write!(f, "cookie"),
+        }
+    }
+}
+
+/// The cookies' `SameSite` attribute (`AUTH_COOKIE_SAMESITE`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SameSite {
+    /// Sent on top-level navigation from other sites, but not on their
+    /// requests
+    #[default]
+    Lax,
+    /// Never sent on requests started by other sites
+    Strict,
+    /// Always sent; needed when the web app and API are on different sites
+    None,
+}
+
+impl FromStr for SameSite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "lax" => Ok(SameSite::Lax),
+            "strict" => Ok(SameSite::Strict),
+            "none" => Ok(SameSite::None),
+            other => Err(format!(
+                "Invalid AUTH_COOKIE_SAMESITE '{}': expected lax, strict or none",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SameSite::Lax => write!(f, "Lax"),
+            SameSite::Strict => write!(f, "Strict"),
+            SameSite::None => write!(f, "None"),
+        }
+    }
+}
+
+/// How session tokens reach the client
+#[derive(Debug, Clone, Default)]
+pub struct SessionCookies {
+    pub delivery: TokenDelivery,
+    pub secure: bool,
+    pub same_site: SameSite,
+    /// Shared parent domain, when the services are on sibling subdomains
+    pub domain: Option<String>,
+}
+
+impl SessionCookies {
+    pub fn read(env: &mut EnvReader) -> Self {
+        let cookies = Self {
+            delivery: env.parse("TOKEN_DELIVERY"),
+            secure: env.parse("AUTH_COOKIE_SECURE"),
+            same_site: env.parse("AUTH_COOKIE_SAMESITE"),
+            domain: env.optional("AUTH_COOKIE_DOMAIN"),
+        };
+        if cookies.same_site == SameSite::None && !cookies.secure {
+            env.check::<(), _>(Err(
+                "AUTH_COOKIE_SAMESITE=none requires AUTH_COOKIE_SECURE=true",
+            ));
+        }
+        cookies
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age: i64) -> Option<HeaderValue> {
+        let mut cookie = format!(
+            "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite={}",
+            name, value, max_age, self.same_site
+        );
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; Domain={}", domain));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        HeaderValue::from_str(&cookie).ok()
+    }
+
+    /// Set the session's token cookies; does nothing when tokens are
+    /// delivered in the body
+    pub fn set(
+        &self,
+        headers: &mut HeaderMap,
+        (access_token, access_expiry): (&str, i64),
+        (refresh_token, refresh_expiry): (&str, i64),
+    ) {
+        if self.delivery != TokenDelivery::Cookie {
+            return;
+        }
+        let cookies = [
+            self.cookie(ACCESS_TOKEN_COOKIE, access_token, access_expiry),
+            self.cookie(REFRESH_TOKEN_COOKIE, refresh_token, refresh_expiry),
+        ];
+        for cookie in cookies.into_iter().flatten() {
+            headers.append(header::SET_COOKIE, cookie);
+        }
+    }
+
+    /// Expire the token cookies, e.g. on logout
+    pub fn clear(&self, headers: &mut HeaderMap) {
+        if self.delivery != TokenDelivery::Cookie {
+            return;
+        }
+        for name in [ACCESS_TOKEN_COOKIE, REFRESH_TOKEN_COOKIE] {
+            if let Some(cookie) = self.cookie(name, "", 0) {
+                headers.append(header::SET_COOKIE, cookie);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_cookies(headers: &HeaderMap) -> Vec<&str> {
+        headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_cookies_only_in_cookie_mode() {
+        let mut headers = HeaderMap::new();
+        let body = SessionCookies::default();
+        body.set(&mut headers, ("access", 900), ("refresh", 604800));
+        body.clear(&mut headers);
+        assert!(headers.is_empty());
+
+        let cookies = SessionCookies {
+            delivery: TokenDelivery::Cookie,
+            secure: true,
+            same_site: SameSite::Strict,
+            domain: Some("example.com".to_string()),
+        };
+        cookies.set(&mut headers, ("access", 900), ("refresh", 604800));
+        assert_eq!(
+            set_cookies(&headers),
+            [
+                "access_token=access; Path=/; Max-Age=900; HttpOnly; SameSite=Strict; Domain=example.com; Secure",
+                "refresh_token=refresh; Path=/; Max-Age=604800; HttpOnly; SameSite=Strict; Domain=example.com; Secure",
+            ]
+        );
+
+        let mut headers = HeaderMap::new();
+        cookies.clear(&mut headers);
+        assert!(set_cookies(&headers)
+            .iter()
+            .all(|cookie| cookie.contains("=; Path=/; Max-Age=0;")));
+    }
+}
@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use common::config::EnvReader;
+use std::{error::Error, fmt, str::FromStr, sync::Arc, sync::Mutex};
+
+use crate::{config::Config, email_verification::VerificationPurpose};
+
+pub type SmsResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// A text message to the phone number a member registered with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sms {
+    /// E.164 number, e.g. +923001234567
+    pub to: String,
+    pub body: String,
+}
+
+impl Sms {
+    /// A one-time code for the purpose
+    pub fn otp(to: &str, purpose: VerificationPurpose, otp: &str) -> Self {
+        let body = match purpose {
+            VerificationPurpose::Registration => {
+                format!("Your Tabrela verification code is {}.", otp)
+            }
+            VerificationPurpose::EmailChange => format!(
+                "Your code to confirm your new Tabrela email address is {}.",
+                otp
+            ),
+            VerificationPurpose::AccountRecovery => format!(
+                "Your Tabrela password reset code is {}. If you did not ask to reset your password, ignore this message.",
+                otp
+            ),
+        };
+        Self {
+            to: to.to_string(),
+            body,
+        }
+    }
+}
+
+// ============================================================================
+// Client trait
+// ============================================================================
+
+/// Sends text messages. Handlers only see this trait, so tests can inject
+/// `LogSmsClient` and inspect what would have been sent.
+#[async_trait]
+pub trait SmsClient: Send + Sync {
+    async fn send(&self, sms: Sms) -> SmsResult;
+}
+
+// ============================================================================
+// Backend selection
+// ============================================================================
+
+/// Which `SmsClient` implementation to use (`SMS_BACKEND`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmsBackend {
+    /// No SMS; codes can only be emailed
+    #[default]
+    None,
+    /// POST to a Twilio-compatible messages API (`SMS_API_URL`)
+    Twilio,
+    /// Log messages instead of sending them (local development)
+    Log,
+}
+
+impl FromStr for SmsBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(SmsBackend::None),
+            "twilio" => Ok(SmsBackend::Twilio),
+            "log" => Ok(SmsBackend::Log),
+            other => Err(format!(
+                "Invalid SMS_BACKEND '{}': expected none, twilio or log",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SmsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SmsBackend::None => write!(f, "none"),
+            SmsBackend::Twilio => write!(f, "twilio"),
+            SmsBackend::Log => write!(f, "log"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SmsSettings {
+    pub backend: SmsBackend,
+    pub api_url: String,
+    pub account_sid: Option<String>,
+    pub auth_token: Option<String>,
+    /// Sender number or messaging service name
+    pub from: Option<String>,
+}
+
+impl SmsSettings {
+    /// Read the `SMS_*` variables, recording problems on `env`. Credentials
+    /// are only required when the Twilio backend is selected.
+    pub fn read(env: &mut EnvReader) -> Self {
+        let backend = env.string("SMS_BACKEND");
+        let backend = env.check(backend.parse()).unwrap_or_default();
+
+        let settings = Self {
+            backend,
+            api_url: env.string("SMS_API_URL"),
+            account_sid: env.optional("SMS_ACCOUNT_SID"),
+            auth_token: env.optional("SMS_AUTH_TOKEN"),
+            from: env.optional("SMS_FROM"),
+        };
+        if backend == SmsBackend::Twilio
+            && (settings.account_sid.is_none()
+                || settings.auth_token.is_none()
+                || settings.from.is_none())
+        {
+            env.check::<(), _>(Err(
+                "SMS_ACCOUNT_SID, SMS_AUTH_TOKEN and SMS_FROM must be set when SMS_BACKEND=twilio",
+            ));
+        }
+        settings
+    }
+}
+
+/// Build the SMS client selected by `SMS_BACKEND`, or None when SMS is off
+pub fn from_config(config: &Config) -> Option<Arc<dyn SmsClient>> {
+    match config.sms.backend {
+        SmsBackend::None => None,
+        SmsBackend::Twilio => Some(Arc::new(TwilioSmsClient::new(&config.sms))),
+        SmsBackend::Log => Some(Arc::new(LogSmsClient::new())),
+    }
+}
+
+// ============================================================================
+// Twilio-compatible backend
+// ============================================================================
+
+pub struct TwilioSmsClient {
+    messages_url: String,
+    account_sid: String,
+    auth_token: String,
+    from: String,
+    client: reqwest::Client,
+}
+
+impl TwilioSmsClient {
+    pub fn new(settings: &SmsSettings) -> Self {
+        let account_sid = settings.account_sid.clone().unwrap_or_default();
+        Self {
+            messages_url: format!(
+                "{}/2010-04-01/Accounts/{}/Messages.json",
+                settings.api_url.trim_end_matches('/'),
+                account_sid
+            ),
+            account_sid,
+            auth_token: settings.auth_token.clone().unwrap_or_default(),
+            from: settings.from.clone().unwrap_or_default(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SmsClient for TwilioSmsClient {
+    async fn send(&self, sms: Sms) -> SmsResult {
+        let response = self
+            .client
+            .post(&self.messages_url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&[
+                ("To", sms.to.as_str()),
+                ("From", self.from.as_str()),
+                ("Body", sms.body.as_str()),
+            ])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            return Err(format!("Failed to send SMS: {}", error_text).into());
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Log backend (development and tests)
+// ============================================================================
+
+/// Logs messages instead of sending them and keeps a copy of each
+#[derive(Default)]
+pub struct LogSmsClient {
+    sent: Mutex<Vec<Sms>>,
+}
+
+impl LogSmsClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message "sent" so far, oldest first
+    pub fn sent(&self) -> Vec<Sms> {
+        self.sent
+            .lock()
+            .map(|sent| sent.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl SmsClient for LogSmsClient {
+    async fn send(&self, sms: Sms) -> SmsResult {
+        tracing::info!(
+            "SMS not sent (SMS_BACKEND=log)\nTo: {}\n\n{}",
+            sms.to,
+            sms.body
+        );
+
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push(sms);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_from_str() {
+        assert_eq!("none".parse::<SmsBackend>(), Ok(SmsBackend::None));
+        assert_eq!(" Twilio ".parse::<SmsBackend>(), Ok(SmsBackend::Twilio));
+        assert_eq!("log".parse::<SmsBackend>(), Ok(SmsBackend::Log));
+        assert!("semaphore".parse::<SmsBackend>().is_err());
+    }
+
+    #[test]
+    fn test_twilio_messages_url() {
+        let client = TwilioSmsClient::new(&SmsSettings {
+            backend: SmsBackend::Twilio,
+            api_url: "https://api.twilio.com/".to_string(),
+            account_sid: Some("AC123".to_string()),
+            auth_token: Some("token".to_string()),
+            from: Some("+15005550006".to_string()),
+        });
+        assert_eq!(
+            client.messages_url,
+            "https://api.twilio.com/2010-04-01/Accounts/AC123/Messages.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_client_records_messages() {
+        let client = LogSmsClient::new();
+        client
+            .send(Sms::otp(
+                "+923001234567",
+                VerificationPurpose::AccountRecovery,
+                "123456",
+            ))
+            .await
+            .unwrap();
+
+        let sent = client.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "+923001234567");
+        assert!(sent[0].body.contains("123456"));
+    }
+}
@@ -75,6 +75,11 @@ async fn get_database_pool() -> PgPool {
 
 // Helper to create test server
 async fn create_test_server() -> TestServer {
+    create_test_server_with(|_| {}).await
+}
+
+// Helper to create test server with adjusted configuration
+async fn create_test_server_with(configure: impl FnOnce(&mut auth::Config)) -> TestServer {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
 
@@ -98,7 +103,12 @@ async fn create_test_server() -> TestServer {
     }
 
     // Create the app
-    let app = auth::create_app().await.expect("Failed to create app");
+    let mut config = auth::Config::from_env().expect("Invalid test configuration");
+    configure(&mut config);
+    let state = auth::build_state(config)
+        .await
+        .expect("Failed to build app state");
+    let app = auth::create_app(state);
 
     TestServer::new(app).expect("Failed to create test server")
 }
@@ -106,10 +116,6 @@ async fn create_test_server() -> TestServer {
 // Helper to clean database
 #[allow(dead_code)]
 async fn clean_database(pool: &PgPool) {
-    sqlx::query("DELETE FROM csrf_tokens")
-        .execute(pool)
-        .await
-        .unwrap();
     sqlx::query("DELETE FROM refresh_tokens")
         .execute(pool)
         .await
@@ -287,6 +293,31 @@ async fn test_register_short_password() {
     assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_register_common_password() {
+    let server = create_test_server().await;
+
+    let response = server
+        .post("/register")
+        .json(&json!({
+            "username": format!("testuser_{}", Uuid::new_v4()),
+            "email": format!("test_{}@example.com", Uuid::new_v4()),
+            "password": "Password123",
+            "reg_number": "2012345",
+            "year_joined": 2023,
+            "phone_number": "+923001234567"
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["problems"], json!(["Password is too common"]));
+
+    let policy: serde_json::Value = server.get("/password-policy").await.json();
+    assert_eq!(policy["reject_common"], true);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_login_success() {
@@ -452,6 +483,70 @@ async fn test_refresh_token() {
     assert_ne!(body["refresh_token"].as_str().unwrap(), refresh_token);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_refresh_token_bound_to_device() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let device = |id: &'static str| {
+        (
+            HeaderName::from_static("x-device-id"),
+            HeaderValue::from_static(id),
+        )
+    };
+    let (name, laptop) = device("laptop-0123456789abcdef");
+    let (_, phone) = device("phone-0123456789abcdef");
+
+    let login_response = server
+        .post("/login")
+        .add_header(name.clone(), laptop.clone())
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await;
+
+    let login_body: Value = login_response.json();
+    let refresh_token = login_body["auth"]["refresh_token"].as_str().unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+
+    // Another device cannot use the token, and the attempt ends the session
+    let stolen = server
+        .post("/refresh")
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+        .add_header(name.clone(), phone)
+        .json(&json!({ "refresh_token": refresh_token }))
+        .await;
+    assert_eq!(stolen.status_code(), StatusCode::UNAUTHORIZED);
+
+    let original = server
+        .post("/refresh")
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token)
+        .add_header(name, laptop)
+        .json(&json!({ "refresh_token": refresh_token }))
+        .await;
+    assert_eq!(original.status_code(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_refresh_token_invalid() {
@@ -609,6 +704,90 @@ async fn test_logout() {
     assert_eq!(response.status_code(), StatusCode::OK);
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_delete_account_anonymizes_user() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login_response = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await;
+
+    let login_body: Value = login_response.json();
+    let access_token = login_body["auth"]["access_token"].as_str().unwrap();
+    let csrf_token = login_body["csrf_token"].as_str().unwrap();
+    let authorization = HeaderValue::from_str(&format!("Bearer {}", access_token)).unwrap();
+
+    let response = server
+        .delete("/me")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .add_header(
+            HeaderName::from_static("x-csrf-token"),
+            HeaderValue::from_str(csrf_token).unwrap(),
+        )
+        .json(&json!({ "password": password }))
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: Value = response.json();
+    assert!(body["shown_as"]
+        .as_str()
+        .unwrap()
+        .starts_with("Deleted member #"));
+
+    // The row stays for historical results, without the personal details
+    let (stored_username, stored_email): (String, String) =
+        sqlx::query_as("SELECT username, email FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(stored_username, body["shown_as"].as_str().unwrap());
+    assert_ne!(stored_email, email);
+
+    // Neither the old password nor the old token works any more
+    let login_response = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await;
+    assert_eq!(login_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let response = server
+        .get("/me")
+        .add_header(HeaderName::from_static("authorization"), authorization)
+        .await;
+    assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 #[ignore]
 async fn test_csrf_token_endpoint() {
@@ -671,3 +850,1064 @@ async fn test_csrf_protection() {
 
     assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_login_history_records_attempts() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let failed = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": "wrongpassword123"
+        }))
+        .await;
+    assert_eq!(failed.status_code(), StatusCode::UNAUTHORIZED);
+
+    let login_response = server
+        .post("/login")
+        .add_header(
+            HeaderName::from_static("user-agent"),
+            HeaderValue::from_static("Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0"),
+        )
+        .add_header(
+            HeaderName::from_static("x-real-ip"),
+            HeaderValue::from_static("203.0.113.7"),
+        )
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await;
+
+    let login_body: Value = login_response.json();
+    let access_token = login_body["auth"]["access_token"].as_str().unwrap();
+
+    let response = server
+        .get("/me/login-history")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            HeaderValue::from_str(&format!("Bearer {}", access_token)).unwrap(),
+        )
+        .await;
+
+    assert_eq!(response.status_code(), StatusCode::OK);
+    let body: Value = response.json();
+    assert_eq!(body["total"], 2);
+    let logins = body["logins"].as_array().unwrap();
+    assert_eq!(logins[0]["success"], true);
+    assert_eq!(logins[0]["device"], "Firefox on Linux");
+    assert_eq!(logins[0]["ip_address"], "203.0.113.7");
+    assert_eq!(logins[1]["success"], false);
+    assert_eq!(logins[1]["failure_reason"], "invalid_password");
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_cookie_token_delivery() {
+    let server = create_test_server_with(|config| {
+        config.session_cookies.delivery = auth::session_cookies::TokenDelivery::Cookie;
+    })
+    .await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login_response = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await;
+    assert_eq!(login_response.status_code(), StatusCode::OK);
+
+    // Tokens only travel in httpOnly cookies
+    let login_body: Value = login_response.json();
+    assert!(login_body["auth"]["access_token"].is_null());
+    let cookies: Vec<String> = login_response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(cookies.len(), 2);
+    assert!(cookies.iter().all(|cookie| cookie.contains("HttpOnly")));
+    let cookie_header = cookies
+        .iter()
+        .map(|cookie| cookie.split(';').next().unwrap())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let cookie_header = HeaderValue::from_str(&cookie_header).unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+
+    let me = server
+        .get("/me")
+        .add_header(HeaderName::from_static("cookie"), cookie_header.clone())
+        .await;
+    assert_eq!(me.status_code(), StatusCode::OK);
+
+    // The refresh token cookie is enough to refresh
+    let refreshed = server
+        .post("/refresh")
+        .add_header(HeaderName::from_static("cookie"), cookie_header.clone())
+        .await;
+    assert_eq!(refreshed.status_code(), StatusCode::OK);
+    assert_eq!(refreshed.headers().get_all("set-cookie").iter().count(), 2);
+
+    // Cookie-authenticated requests still need the CSRF token
+    let forged = server
+        .post("/logout")
+        .add_header(HeaderName::from_static("cookie"), cookie_header.clone())
+        .await;
+    assert_eq!(forged.status_code(), StatusCode::FORBIDDEN);
+
+    let logout = server
+        .post("/logout")
+        .add_header(HeaderName::from_static("cookie"), cookie_header)
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token)
+        .await;
+    assert_eq!(logout.status_code(), StatusCode::OK);
+    assert!(logout
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .all(|cookie| cookie.to_str().unwrap().contains("Max-Age=0")));
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_admin_api_keys() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+    sqlx::query("INSERT INTO admin_users (id, user_id, granted_by) VALUES ($1, $2, $2)")
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+
+    let rejected = server
+        .post("/admin/api-keys")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+        .json(&json!({"name": "Projector", "scopes": []}))
+        .await;
+    assert_eq!(rejected.status_code(), StatusCode::BAD_REQUEST);
+
+    let created = server
+        .post("/admin/api-keys")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+        .json(&json!({"name": "Projector", "scopes": ["tabulation"]}))
+        .await;
+    assert_eq!(created.status_code(), StatusCode::CREATED);
+    let created: Value = created.json();
+    let key = created["key"].as_str().unwrap();
+    let key_id = created["api_key"]["id"].as_str().unwrap();
+    assert!(key.starts_with(common::api_keys::API_KEY_PREFIX));
+    assert_eq!(created["api_key"]["scopes"], json!(["tabulation"]));
+
+    // Only the hash is stored
+    let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = $1")
+        .bind(Uuid::parse_str(key_id).unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, common::api_keys::hash_api_key(key));
+
+    let listed: Value = server
+        .get("/admin/api-keys")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await
+        .json();
+    let listed = listed["api_keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|api_key| api_key["id"] == key_id)
+        .unwrap();
+    assert_eq!(listed["key_prefix"], &key[..12]);
+    assert!(listed.get("key_hash").is_none());
+
+    let revoke = |server: &TestServer| {
+        server
+            .delete(&format!("/admin/api-keys/{}", key_id))
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+    };
+    assert_eq!(revoke(&server).await.status_code(), StatusCode::OK);
+    assert_eq!(revoke(&server).await.status_code(), StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM api_keys WHERE id = $1")
+        .bind(Uuid::parse_str(key_id).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_admin_force_verify() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let mut user_ids = Vec::new();
+    for _ in 0..2 {
+        let user_id = create_verified_user(
+            &pool,
+            &format!("testuser_{}", Uuid::new_v4()),
+            &format!("test_{}@example.com", Uuid::new_v4()),
+            "securepassword123",
+            &format!("20{:05}", rand::random::<u32>() % 100000),
+            2023,
+            &format!("+9230{:08}", rand::random::<u32>() % 100000000),
+        )
+        .await
+        .expect("Failed to create verified user");
+        user_ids.push(user_id);
+    }
+    let (admin_id, member_id) = (user_ids[0], user_ids[1]);
+    sqlx::query("INSERT INTO admin_users (id, user_id, granted_by) VALUES ($1, $2, $2)")
+        .bind(Uuid::new_v4())
+        .bind(admin_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET email_verified = false, email_verified_at = NULL WHERE id = $1")
+        .bind(member_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let admin_username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+        .bind(admin_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": admin_username,
+            "password": "securepassword123"
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+    let admin_post = |path: String| {
+        server
+            .post(&path)
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+    };
+
+    let resent = admin_post(format!("/admin/users/{}/resend-verification", member_id)).await;
+    assert_eq!(resent.status_code(), StatusCode::OK);
+    let resent: Value = resent.json();
+    assert_eq!(resent["audit"]["action"], "verification_resent");
+
+    let verified = admin_post(format!("/admin/users/{}/force-verify", member_id)).await;
+    assert_eq!(verified.status_code(), StatusCode::OK);
+    let verified: Value = verified.json();
+    assert_eq!(verified["audit"]["action"], "email_force_verified");
+    assert_eq!(verified["audit"]["admin_id"], json!(admin_id));
+
+    // The pending code is gone along with the need for it
+    let (email_verified, pending): (bool, i64) = sqlx::query_as(
+        "SELECT email_verified, (SELECT COUNT(*) FROM email_verification_tokens WHERE user_id = $1) FROM users WHERE id = $1",
+    )
+    .bind(member_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(email_verified);
+    assert_eq!(pending, 0);
+
+    let again = admin_post(format!("/admin/users/{}/force-verify", member_id)).await;
+    assert_eq!(again.status_code(), StatusCode::CONFLICT);
+    let unknown = admin_post(format!("/admin/users/{}/force-verify", Uuid::new_v4())).await;
+    assert_eq!(unknown.status_code(), StatusCode::NOT_FOUND);
+
+    for user_id in user_ids {
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_change_username() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let password = "securepassword123";
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &format!("test_{}@example.com", Uuid::new_v4()),
+        password,
+        &format!("20{:05}", rand::random::<u32>() % 100000),
+        2023,
+        &format!("+9230{:08}", rand::random::<u32>() % 100000000),
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+    let rename = |new_username: &str| {
+        server
+            .post("/me/username")
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+            .json(&json!({"username": new_username}))
+    };
+
+    let new_username = format!("renamed_{}", Uuid::new_v4());
+    let renamed = rename(&new_username).await;
+    assert_eq!(renamed.status_code(), StatusCode::OK);
+    let renamed: Value = renamed.json();
+    assert_eq!(renamed["previous_username"], username.as_str());
+
+    // Once per cooldown
+    let again = rename(&format!("renamed_{}", Uuid::new_v4())).await;
+    assert_eq!(again.status_code(), StatusCode::TOO_MANY_REQUESTS);
+
+    // The old name stays with its former owner
+    let register = server
+        .post("/register")
+        .json(&json!({
+            "username": username,
+            "email": format!("test_{}@example.com", Uuid::new_v4()),
+            "password": password,
+            "reg_number": format!("20{:05}", rand::random::<u32>() % 100000),
+            "year_joined": 2023,
+            "phone_number": format!("+9230{:08}", rand::random::<u32>() % 100000000)
+        }))
+        .await;
+    assert_eq!(register.status_code(), StatusCode::CONFLICT);
+
+    let history: Vec<(String, String)> = sqlx::query_as(
+        "SELECT old_username, new_username FROM username_history WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(history, vec![(username.clone(), new_username.clone())]);
+
+    sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_suspension() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let password = "securepassword123";
+    let mut users = Vec::new();
+    for _ in 0..2 {
+        let username = format!("testuser_{}", Uuid::new_v4());
+        let user_id = create_verified_user(
+            &pool,
+            &username,
+            &format!("test_{}@example.com", Uuid::new_v4()),
+            password,
+            &format!("20{:05}", rand::random::<u32>() % 100000),
+            2023,
+            &format!("+9230{:08}", rand::random::<u32>() % 100000000),
+        )
+        .await
+        .expect("Failed to create verified user");
+        users.push((user_id, username));
+    }
+    let (admin_id, admin_username) = users[0].clone();
+    let (member_id, member_username) = users[1].clone();
+    sqlx::query("INSERT INTO admin_users (id, user_id, granted_by) VALUES ($1, $2, $2)")
+        .bind(Uuid::new_v4())
+        .bind(admin_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let login = |username: &str| {
+        server.post("/login").json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+    };
+    let admin_login: Value = login(&admin_username).await.json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        admin_login["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(admin_login["csrf_token"].as_str().unwrap()).unwrap();
+    let suspension = |method: axum::http::Method, user_id: Uuid| {
+        server
+            .method(method, &format!("/admin/users/{}/suspension", user_id))
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+    };
+
+    // A member already logged in loses their session
+    let member_login: Value = login(&member_username).await.json();
+    let refresh_token = member_login["auth"]["refresh_token"].as_str().unwrap();
+    let member_me = || {
+        server.get("/me").add_header(
+            HeaderName::from_static("authorization"),
+            HeaderValue::from_str(&format!(
+                "Bearer {}",
+                member_login["auth"]["access_token"].as_str().unwrap()
+            ))
+            .unwrap(),
+        )
+    };
+
+    let suspended = suspension(axum::http::Method::POST, member_id)
+        .json(&json!({"reason": "Conduct review pending"}))
+        .await;
+    assert_eq!(suspended.status_code(), StatusCode::OK);
+
+    let refused = login(&member_username).await;
+    assert_eq!(refused.status_code(), StatusCode::FORBIDDEN);
+    let refused: Value = refused.json();
+    assert_eq!(refused["reason"], "Conduct review pending");
+    assert!(refused["suspended_until"].is_null());
+    let refresh = server
+        .post("/refresh")
+        .json(&json!({"refresh_token": refresh_token}))
+        .await;
+    assert_eq!(refresh.status_code(), StatusCode::UNAUTHORIZED);
+    // Their access token stops working too, without waiting to expire
+    assert_eq!(member_me().await.status_code(), StatusCode::FORBIDDEN);
+
+    // Admins are demoted first
+    let admin_suspended = suspension(axum::http::Method::POST, admin_id)
+        .json(&json!({"reason": "Testing"}))
+        .await;
+    assert_eq!(admin_suspended.status_code(), StatusCode::CONFLICT);
+
+    let lifted = suspension(axum::http::Method::DELETE, member_id).await;
+    assert_eq!(lifted.status_code(), StatusCode::OK);
+    assert_eq!(login(&member_username).await.status_code(), StatusCode::OK);
+    assert_eq!(member_me().await.status_code(), StatusCode::OK);
+    let history: Value = suspension(axum::http::Method::GET, member_id).await.json();
+    assert_eq!(history["suspensions"][0]["lifted_by"], json!(admin_id));
+
+    for (user_id, _) in users {
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_policy_acceptance() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+    sqlx::query("INSERT INTO admin_users (id, user_id, granted_by) VALUES ($1, $2, $2)")
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+
+    let published = server
+        .post("/admin/policies")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+        .json(&json!({
+            "kind": "media",
+            "title": "Media policy",
+            "body": "Photos from events may be published."
+        }))
+        .await;
+    assert_eq!(published.status_code(), StatusCode::CREATED);
+    let policy: Value = published.json();
+    let policy_id = policy["id"].as_str().unwrap().to_string();
+
+    // The new version is flagged until accepted
+    let me = server
+        .get("/me")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await;
+    assert!(me.headers()["x-pending-policies"]
+        .to_str()
+        .unwrap()
+        .contains("media"));
+    let me: Value = me.json();
+    assert!(me["pending_policies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|pending| pending["id"] == policy_id.as_str()));
+
+    let accepted = server
+        .post("/policies/accept")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+        .add_header(
+            HeaderName::from_static("x-real-ip"),
+            HeaderValue::from_static("203.0.113.7"),
+        )
+        .json(&json!({"policy_id": policy_id}))
+        .await;
+    assert_eq!(accepted.status_code(), StatusCode::OK);
+
+    let mine: Value = server
+        .get("/me/policies")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await
+        .json();
+    assert_eq!(mine["accepted"][0]["policy_id"], policy_id.as_str());
+    assert!(mine["pending"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|pending| pending["kind"] != "media"));
+
+    // Admins can show who consented, when and from where
+    let record: Value = server
+        .get(&format!("/admin/policies/{}/acceptances", policy_id))
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await
+        .json();
+    assert_eq!(record["total"], 1);
+    assert_eq!(record["acceptances"][0]["username"], username.as_str());
+    assert_eq!(record["acceptances"][0]["ip_address"], "203.0.113.7");
+
+    sqlx::query("DELETE FROM policy_documents WHERE id = $1")
+        .bind(Uuid::parse_str(&policy_id).unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+/// The live code for a user and purpose
+async fn get_otp(pool: &PgPool, user_id: Uuid, purpose: &str) -> String {
+    sqlx::query_scalar(
+        "SELECT otp FROM email_verification_tokens WHERE user_id = $1 AND purpose = $2",
+    )
+    .bind(user_id)
+    .bind(purpose)
+    .fetch_one(pool)
+    .await
+    .expect("No verification code stored")
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_change_email() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let new_email = format!("test_{}@example.com", Uuid::new_v4());
+    let password = "securepassword123";
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &email,
+        password,
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+    let post = |path: &str, body: Value| {
+        server
+            .post(path)
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+            .json(&body)
+    };
+
+    let wrong_password = post(
+        "/me/email",
+        json!({"new_email": new_email, "password": "wrongpassword"}),
+    )
+    .await;
+    assert_eq!(wrong_password.status_code(), StatusCode::UNAUTHORIZED);
+
+    let requested = post(
+        "/me/email",
+        json!({"new_email": new_email, "password": password}),
+    )
+    .await;
+    assert_eq!(requested.status_code(), StatusCode::OK);
+
+    // Codes for the same purpose are throttled
+    let again = post(
+        "/me/email",
+        json!({"new_email": new_email, "password": password}),
+    )
+    .await;
+    assert_eq!(again.status_code(), StatusCode::TOO_MANY_REQUESTS);
+
+    let otp = get_otp(&pool, user_id, "email_change").await;
+    let wrong = if otp == "000000" { "111111" } else { "000000" };
+    let rejected = post("/me/email/verify", json!({"otp": wrong})).await;
+    assert_eq!(rejected.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(rejected.json::<Value>()["attempts_remaining"], 4);
+
+    let confirmed = post("/me/email/verify", json!({"otp": otp})).await;
+    assert_eq!(confirmed.status_code(), StatusCode::OK);
+
+    let me: Value = server
+        .get("/me")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await
+        .json();
+    assert_eq!(me["email"], new_email.as_str());
+    assert_eq!(me["email_verified"], true);
+
+    // The code is used up
+    let reused = post("/me/email/verify", json!({"otp": otp})).await;
+    assert_eq!(reused.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_password_reset_with_recovery_code() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &email,
+        "securepassword123",
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let requested = server
+        .post("/request-password-reset")
+        .json(&json!({"email": email}))
+        .await;
+    assert_eq!(requested.status_code(), StatusCode::OK);
+
+    let otp = get_otp(&pool, user_id, "account_recovery").await;
+    let reset = server
+        .post("/reset-password")
+        .json(&json!({
+            "email": email,
+            "otp": otp,
+            "new_password": "anothersecurepassword456"
+        }))
+        .await;
+    assert_eq!(reset.status_code(), StatusCode::OK);
+
+    let login = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": "anothersecurepassword456"
+        }))
+        .await;
+    assert_eq!(login.status_code(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_password_reset_by_sms() {
+    let pool = get_database_pool().await;
+
+    let email = format!("test_{}@example.com", Uuid::new_v4());
+    let reg_number = format!("20{:05}", rand::random::<u32>() % 100000);
+    let phone_number = format!("+9230{:08}", rand::random::<u32>() % 100000000);
+
+    let user_id = create_verified_user(
+        &pool,
+        &format!("testuser_{}", Uuid::new_v4()),
+        &email,
+        "securepassword123",
+        &reg_number,
+        2023,
+        &phone_number,
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    // Without an SMS backend the channel is refused outright
+    let server = create_test_server().await;
+    let refused = server
+        .post("/request-password-reset")
+        .json(&json!({"email": email, "channel": "sms"}))
+        .await;
+    assert_eq!(refused.status_code(), StatusCode::BAD_REQUEST);
+
+    let server = create_test_server_with(|config| {
+        config.sms.backend = auth::sms_client::SmsBackend::Log;
+    })
+    .await;
+    let requested = server
+        .post("/request-password-reset")
+        .json(&json!({"email": email, "channel": "sms"}))
+        .await;
+    assert_eq!(requested.status_code(), StatusCode::OK);
+
+    let otp = get_otp(&pool, user_id, "account_recovery").await;
+    let reset = server
+        .post("/reset-password")
+        .json(&json!({
+            "email": email,
+            "otp": otp,
+            "new_password": "anothersecurepassword456"
+        }))
+        .await;
+    assert_eq!(reset.status_code(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[ignore] // Requires database
+async fn test_event_manager_tokens() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let password = "securepassword123";
+    let user_id = create_verified_user(
+        &pool,
+        &username,
+        &format!("test_{}@example.com", Uuid::new_v4()),
+        password,
+        &format!("20{:05}", rand::random::<u32>() % 100000),
+        2023,
+        &format!("+9230{:08}", rand::random::<u32>() % 100000000),
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login = || {
+        server.post("/login").json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+    };
+    let jwt = auth::jwt::JwtService::new(std::env::var("JWT_SECRET").unwrap(), 900, 604800);
+    let managed_events = |access_token: &Value| {
+        jwt.validate_access_token(access_token.as_str().unwrap())
+            .unwrap()
+            .managed_events
+    };
+
+    // A normal member's token carries no event scope
+    let before: Value = login().await.json();
+    assert!(managed_events(&before["auth"]["access_token"]).is_empty());
+
+    let event_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO events (title, event_type, event_date, created_by)
+         VALUES ('Practice', 'practice', NOW(), $1) RETURNING id",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO event_managers (event_id, user_id, granted_by) VALUES ($1, $2, $2)")
+        .bind(event_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // The next token names the event they manage, and refreshes keep it
+    let after: Value = login().await.json();
+    assert_eq!(
+        managed_events(&after["auth"]["access_token"]),
+        vec![event_id]
+    );
+
+    let refreshed = server
+        .post("/refresh")
+        .add_header(
+            HeaderName::from_static("x-csrf-token"),
+            HeaderValue::from_str(after["csrf_token"].as_str().unwrap()).unwrap(),
+        )
+        .json(&json!({"refresh_token": after["auth"]["refresh_token"]}))
+        .await;
+    assert_eq!(refreshed.status_code(), StatusCode::OK);
+    let refreshed: Value = refreshed.json();
+    assert_eq!(managed_events(&refreshed["access_token"]), vec![event_id]);
+
+    sqlx::query("DELETE FROM events WHERE id = $1")
+        .bind(event_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore] // Requires database
+async fn test_member_requirements() {
+    let server = create_test_server().await;
+    let pool = get_database_pool().await;
+
+    let username = format!("testuser_{}", Uuid::new_v4());
+    let password = "securepassword123";
+    create_verified_user(
+        &pool,
+        &username,
+        &format!("test_{}@example.com", Uuid::new_v4()),
+        password,
+        &format!("20{:05}", rand::random::<u32>() % 100000),
+        2023,
+        &format!("+9230{:08}", rand::random::<u32>() % 100000000),
+    )
+    .await
+    .expect("Failed to create verified user");
+
+    let login_body: Value = server
+        .post("/login")
+        .json(&json!({
+            "username_or_email": username,
+            "password": password
+        }))
+        .await
+        .json();
+    let authorization = HeaderValue::from_str(&format!(
+        "Bearer {}",
+        login_body["auth"]["access_token"].as_str().unwrap()
+    ))
+    .unwrap();
+    let csrf_token = HeaderValue::from_str(login_body["csrf_token"].as_str().unwrap()).unwrap();
+    let update = |requirements: Value| {
+        server
+            .put("/me/requirements")
+            .add_header(
+                HeaderName::from_static("authorization"),
+                authorization.clone(),
+            )
+            .add_header(HeaderName::from_static("x-csrf-token"), csrf_token.clone())
+            .json(&requirements)
+    };
+
+    let updated =
+        update(json!({"dietary": " Vegetarian ", "accessibility": "Step-free access"})).await;
+    assert_eq!(updated.status_code(), StatusCode::OK);
+    let updated: Value = updated.json();
+    assert_eq!(updated["dietary"], "Vegetarian");
+    assert_eq!(updated["accessibility"], "Step-free access");
+
+    // Blank fields clear, and clearing both forgets them
+    let cleared: Value = update(json!({"dietary": "", "accessibility": null}))
+        .await
+        .json();
+    assert!(cleared["dietary"].is_null());
+    assert!(cleared["updated_at"].is_null());
+
+    let fetched: Value = server
+        .get("/me/requirements")
+        .add_header(
+            HeaderName::from_static("authorization"),
+            authorization.clone(),
+        )
+        .await
+        .json();
+    assert!(fetched["accessibility"].is_null());
+
+    let too_long = update(json!({"dietary": "x".repeat(501)})).await;
+    assert_eq!(too_long.status_code(), StatusCode::BAD_REQUEST);
+}
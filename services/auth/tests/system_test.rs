@@ -18,7 +18,7 @@ async fn get_otp_from_db(pool: &PgPool, email: &str) -> Result<String, sqlx::Err
         SELECT evt.otp 
         FROM email_verification_tokens evt
         JOIN users u ON evt.user_id = u.id
-        WHERE u.email = $1
+        WHERE u.email = $1 AND evt.purpose = 'registration'
         "#,
         email
     )
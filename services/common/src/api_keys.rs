@@ -0,0 +1,100 @@
+//! Read-only API keys for integrations such as results dashboards and the
+//! projector display, which need data without a user account.
+//!
+//! Admins manage keys through the auth service. Only a SHA-256 hash of
+//! each key is stored, in the shared `api_keys` table, and each key is
+//! scoped to the services it may read. Services accept keys on the routes
+//! they put behind `read_access_middleware`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fmt, str::FromStr};
+use uuid::Uuid;
+
+/// Header integrations send their key in
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Start of every key, so leaked keys are easy to recognise
+pub const API_KEY_PREFIX: &str = "tbk_";
+
+/// Characters of a key kept in the clear to tell keys apart in listings
+pub const API_KEY_DISPLAY_LEN: usize = 12;
+
+/// A service an API key can read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Events and their attendance
+    Attendance,
+    /// Series, matches, venues and institutions
+    Tabulation,
+}
+
+impl ApiKeyScope {
+    pub const ALL: &'static [ApiKeyScope] = &[ApiKeyScope::Attendance, ApiKeyScope::Tabulation];
+
+    /// Value stored in `api_keys.scopes`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::Attendance => "attendance",
+            ApiKeyScope::Tabulation => "tabulation",
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ApiKeyScope::ALL
+            .iter()
+            .copied()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("Unknown API key scope: {}", s))
+    }
+}
+
+/// An integration authenticated by API key, added to the request
+/// extensions in place of a user id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiClient {
+    pub key_id: Uuid,
+    pub name: String,
+}
+
+/// Hash stored for a key. Keys are long and random, so a plain hash is
+/// enough to make a leaked table useless.
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for scope in ApiKeyScope::ALL {
+            assert_eq!(scope.as_str().parse::<ApiKeyScope>().unwrap(), *scope);
+            assert_eq!(
+                serde_json::to_value(scope).unwrap(),
+                serde_json::json!(scope.as_str())
+            );
+        }
+        assert!("merit".parse::<ApiKeyScope>().is_err());
+    }
+
+    #[test]
+    fn test_hash_api_key() {
+        let hash = hash_api_key("tbk_example");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_api_key("tbk_example"));
+        assert_ne!(hash, hash_api_key("tbk_other"));
+    }
+}
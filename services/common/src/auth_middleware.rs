@@ -0,0 +1,767 @@
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::api_keys::{hash_api_key, ApiClient, API_KEY_HEADER};
+use crate::csrf::{cookie_value, has_valid_csrf_token};
+use crate::error::{api_error, ApiError};
+use crate::kv::KvStore;
+use crate::replica::primary;
+
+/// Cookie the auth service puts the access token in when it delivers
+/// tokens as cookies (`TOKEN_DELIVERY=cookie`)
+pub const ACCESS_TOKEN_COOKIE: &str = "access_token";
+
+/// How long a service trusts its cached answer to whether a user is
+/// suspended. The auth service overwrites the answer when it suspends or
+/// reinstates someone, so services sharing Redis with it see that at once.
+pub const SUSPENSION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// JWT claims issued by the auth service
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String, // user_id
+    pub username: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub jti: String,
+    pub token_type: String,
+    /// Login session the token belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Events an admin has made the user a manager of
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub managed_events: Vec<Uuid>,
+}
+
+/// Per-service hooks the shared middleware needs from application state
+pub trait AuthState: Send + Sync + 'static {
+    /// Secret used to verify access tokens
+    fn jwt_secret(&self) -> &str;
+
+    /// Whether `user_id` is an admin. `auth_header` is the raw
+    /// `Authorization` header for services that ask the auth service.
+    fn is_admin(
+        &self,
+        user_id: Uuid,
+        auth_header: &str,
+    ) -> impl Future<Output = Result<bool, ApiError>> + Send;
+
+    /// The live API key with this hash, if it is scoped to this service
+    /// and its owner is not suspended. Services without routes open to API
+    /// keys accept none.
+    fn find_api_key(
+        &self,
+        _key_hash: &str,
+    ) -> impl Future<Output = Result<Option<ApiClient>, ApiError>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Whether `user_id` is suspended now, which ends their access even
+    /// with a token issued before the suspension
+    fn is_suspended(&self, _user_id: Uuid) -> impl Future<Output = Result<bool, ApiError>> + Send {
+        async { Ok(false) }
+    }
+}
+
+/// Extract the `Authorization` header and the bearer token inside it
+pub fn bearer_token(headers: &HeaderMap) -> Result<(&str, &str), ApiError> {
+    let auth_header = headers
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| api_error(StatusCode::UNAUTHORIZED, "Missing authorization header"))?;
+
+    let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
+        api_error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid authorization header format",
+        )
+    })?;
+
+    Ok((auth_header, token))
+}
+
+/// An access token taken from a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestToken<'a> {
+    pub token: &'a str,
+    /// Whether it came from the access token cookie. Browsers send cookies
+    /// on their own, so such requests need CSRF protection.
+    pub from_cookie: bool,
+}
+
+impl RequestToken<'_> {
+    /// `Authorization` header carrying the token, for calls to the auth
+    /// service on the user's behalf
+    pub fn auth_header(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+/// The access token a request carries: the bearer token if there is an
+/// `Authorization` header, otherwise the access token cookie
+pub fn request_token(headers: &HeaderMap) -> Result<RequestToken<'_>, ApiError> {
+    if headers.contains_key("Authorization") {
+        return bearer_token(headers).map(|(_, token)| RequestToken {
+            token,
+            from_cookie: false,
+        });
+    }
+
+    cookie_value(headers, ACCESS_TOKEN_COOKIE)
+        .map(|token| RequestToken {
+            token,
+            from_cookie: true,
+        })
+        .ok_or_else(|| api_error(StatusCode::UNAUTHORIZED, "Missing authorization header"))
+}
+
+/// Authenticate a request by its access token. State-changing requests
+/// authenticated by cookie must also carry a CSRF token for the session.
+pub fn authenticate<'a>(
+    secret: &str,
+    method: &Method,
+    headers: &'a HeaderMap,
+) -> Result<(Uuid, Claims, RequestToken<'a>), ApiError> {
+    let token = request_token(headers)?;
+    let (user_id, claims) = decode_access_token(secret, token.token)?;
+
+    let state_changing = !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    if token.from_cookie && state_changing && !has_valid_csrf_token(secret, headers, claims.sid) {
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            "Invalid or missing CSRF token",
+        ));
+    }
+
+    Ok((user_id, claims, token))
+}
+
+/// [`authenticate`], also turning away users suspended since their token
+/// was issued
+pub async fn authenticate_active<'a, S: AuthState>(
+    state: &S,
+    method: &Method,
+    headers: &'a HeaderMap,
+) -> Result<(Uuid, Claims, RequestToken<'a>), ApiError> {
+    let (user_id, claims, token) = authenticate(state.jwt_secret(), method, headers)?;
+
+    if primary(state.is_suspended(user_id)).await? {
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            "Your account is suspended",
+        ));
+    }
+
+    Ok((user_id, claims, token))
+}
+
+/// Validate an access token and return the user it belongs to
+pub fn decode_access_token(secret: &str, token: &str) -> Result<(Uuid, Claims), ApiError> {
+    let mut validation = Validation::default();
+    validation.validate_exp = true;
+
+    let claims = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map_err(|_| api_error(StatusCode::UNAUTHORIZED, "Invalid or expired token"))?
+    .claims;
+
+    if claims.token_type != "access" {
+        return Err(api_error(StatusCode::UNAUTHORIZED, "Invalid token type"));
+    }
+
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| api_error(StatusCode::UNAUTHORIZED, "Invalid user ID in token"))?;
+
+    Ok((user_id, claims))
+}
+
+/// Middleware to authenticate requests using JWT access tokens
+pub async fn auth_middleware<S: AuthState>(
+    State(state): State<Arc<S>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, _) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    // Add user_id and username to request extensions
+    request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(claims.username);
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware for read-only routes that integrations may use too: a
+/// request with an `X-API-Key` header gets the `ApiClient` it belongs to,
+/// anything else needs a signed-in user as with `auth_middleware`
+pub async fn read_access_middleware<S: AuthState>(
+    State(state): State<Arc<S>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(API_KEY_HEADER) else {
+        return auth_middleware(State(state), request, next).await;
+    };
+
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return Err(api_error(StatusCode::FORBIDDEN, "API keys are read-only"));
+    }
+
+    let key_hash = key
+        .to_str()
+        .map(hash_api_key)
+        .map_err(|_| api_error(StatusCode::UNAUTHORIZED, "Invalid API key"))?;
+    let client = primary(state.find_api_key(&key_hash))
+        .await?
+        .ok_or_else(|| api_error(StatusCode::UNAUTHORIZED, "Invalid or revoked API key"))?;
+
+    request.extensions_mut().insert(client);
+
+    Ok(next.run(request).await)
+}
+
+/// Optional auth middleware - extracts user info if valid token present, but allows anonymous access
+pub async fn optional_auth_middleware<S: AuthState>(
+    State(state): State<Arc<S>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let user = authenticate_active(&*state, request.method(), request.headers()).await;
+
+    if let Ok((user_id, claims, _)) = user {
+        request.extensions_mut().insert(user_id);
+        request.extensions_mut().insert(claims.username);
+    }
+
+    // Always continue with the request, whether authenticated or not
+    next.run(request).await
+}
+
+/// Admin middleware - requires authenticated user to be an admin
+pub async fn admin_middleware<S: AuthState>(
+    State(state): State<Arc<S>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, token) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    if !primary(state.is_admin(user_id, &token.auth_header())).await? {
+        return Err(api_error(StatusCode::FORBIDDEN, "Admin access required"));
+    }
+
+    request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(claims.username);
+
+    Ok(next.run(request).await)
+}
+
+/// Which events a request through [`event_admin_middleware`] may manage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventAccess {
+    /// An admin, who manages every event
+    All,
+    /// A member managing these events on the admins' behalf
+    Events(Vec<Uuid>),
+}
+
+impl EventAccess {
+    pub fn allows(&self, event_id: Uuid) -> bool {
+        match self {
+            EventAccess::All => true,
+            EventAccess::Events(events) => events.contains(&event_id),
+        }
+    }
+
+    /// Refuse a request about an event the user does not manage
+    pub fn require(&self, event_id: Uuid) -> Result<(), ApiError> {
+        if self.allows(event_id) {
+            Ok(())
+        } else {
+            Err(api_error(
+                StatusCode::FORBIDDEN,
+                "Admin access to this event required",
+            ))
+        }
+    }
+}
+
+/// Admin middleware that also lets in event managers, whose events are
+/// listed in their token. Routes with an `:event_id` are checked here;
+/// handlers of other routes must check the event they act on against the
+/// [`EventAccess`] this adds to the request.
+pub async fn event_admin_middleware<S: AuthState>(
+    State(state): State<Arc<S>>,
+    params: Option<Path<HashMap<String, String>>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let (user_id, claims, token) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    let access = if primary(state.is_admin(user_id, &token.auth_header())).await? {
+        EventAccess::All
+    } else if !claims.managed_events.is_empty() {
+        EventAccess::Events(claims.managed_events)
+    } else {
+        return Err(api_error(StatusCode::FORBIDDEN, "Admin access required"));
+    };
+
+    if let Some(event_id) = params
+        .as_ref()
+        .and_then(|Path(params)| params.get("event_id"))
+    {
+        let event_id = Uuid::parse_str(event_id)
+            .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid event ID"))?;
+        access.require(event_id)?;
+    }
+
+    request.extensions_mut().insert(access);
+    request.extensions_mut().insert(user_id);
+    request.extensions_mut().insert(claims.username);
+
+    Ok(next.run(request).await)
+}
+
+/// KV key holding whether `user_id` is suspended
+pub fn suspension_key(user_id: Uuid) -> String {
+    format!("suspended:{}", user_id)
+}
+
+/// Whether `user_id` is suspended, from `store` if it was asked in the last
+/// [`SUSPENSION_CACHE_TTL`] and from `lookup` otherwise, so the check on
+/// every request seldom reaches the database
+pub async fn check_suspended_cached(
+    store: &KvStore,
+    user_id: Uuid,
+    lookup: impl Future<Output = Result<bool, ApiError>>,
+) -> Result<bool, ApiError> {
+    let key = suspension_key(user_id);
+    if let Ok(Some(cached)) = store.get(&key).await {
+        return Ok(cached == "true");
+    }
+
+    let suspended = lookup.await?;
+    if let Err(e) = store
+        .set(&key, &suspended.to_string(), SUSPENSION_CACHE_TTL)
+        .await
+    {
+        tracing::warn!("Failed to cache suspension check: {}", e);
+    }
+    Ok(suspended)
+}
+
+/// Ask the auth service whether the bearer of `auth_header` is an admin
+pub async fn check_admin_with_auth_service(
+    auth_service_url: &str,
+    auth_header: &str,
+) -> Result<bool, ApiError> {
+    #[derive(Deserialize)]
+    struct AdminCheckResponse {
+        is_admin: bool,
+    }
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/admin/check", auth_service_url))
+        .header("Authorization", auth_header)
+        .send()
+        .await
+        .map_err(|_| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to verify admin status",
+            )
+        })?;
+
+    if !response.status().is_success() {
+        return Ok(false);
+    }
+
+    let admin_response: AdminCheckResponse = response.json().await.map_err(|_| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to parse admin status",
+        )
+    })?;
+
+    Ok(admin_response.is_admin)
+}
+
+/// [`check_admin_with_auth_service`], remembering the answer for each token
+/// for `ttl` so a page of admin requests costs one round trip. A demoted
+/// admin keeps access for up to `ttl`; zero asks every time.
+pub async fn check_admin_cached(
+    store: &KvStore,
+    ttl: Duration,
+    auth_service_url: &str,
+    auth_header: &str,
+) -> Result<bool, ApiError> {
+    if ttl.is_zero() {
+        return check_admin_with_auth_service(auth_service_url, auth_header).await;
+    }
+
+    let key = format!(
+        "admin:{}",
+        hex::encode(Sha256::digest(auth_header.as_bytes()))
+    );
+    if let Ok(Some(cached)) = store.get(&key).await {
+        return Ok(cached == "true");
+    }
+
+    let is_admin = check_admin_with_auth_service(auth_service_url, auth_header).await?;
+    if let Err(e) = store.set(&key, &is_admin.to_string(), ttl).await {
+        tracing::warn!("Failed to cache admin check: {}", e);
+    }
+    Ok(is_admin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    const SECRET: &str = "test-secret";
+
+    fn token(token_type: &str, sub: &str, exp_offset: i64) -> String {
+        let now = jsonwebtoken::get_current_timestamp() as i64;
+        let claims = Claims {
+            sub: sub.to_string(),
+            username: "alice".to_string(),
+            exp: now + exp_offset,
+            iat: now,
+            jti: Uuid::new_v4().to_string(),
+            token_type: token_type.to_string(),
+            sid: None,
+            managed_events: Vec::new(),
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            bearer_token(&headers).unwrap_err().0,
+            StatusCode::UNAUTHORIZED
+        );
+
+        headers.insert("Authorization", "Basic abc".parse().unwrap());
+        assert!(bearer_token(&headers).is_err());
+
+        headers.insert("Authorization", "Bearer abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers).unwrap(), ("Bearer abc", "abc"));
+    }
+
+    #[test]
+    fn test_cookie_auth_needs_csrf_token() {
+        let user_id = Uuid::new_v4();
+        let session = Uuid::new_v4();
+        let now = jsonwebtoken::get_current_timestamp() as i64;
+        let claims = Claims {
+            sub: user_id.to_string(),
+            username: "alice".to_string(),
+            exp: now + 900,
+            iat: now,
+            jti: Uuid::new_v4().to_string(),
+            token_type: "access".to_string(),
+            sid: Some(session),
+            managed_events: Vec::new(),
+        };
+        let access_token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Cookie",
+            format!("{}={}", ACCESS_TOKEN_COOKIE, access_token)
+                .parse()
+                .unwrap(),
+        );
+        let (decoded, _, token) = authenticate(SECRET, &Method::GET, &headers).unwrap();
+        assert_eq!(decoded, user_id);
+        assert!(token.from_cookie);
+        assert_eq!(token.auth_header(), format!("Bearer {}", access_token));
+
+        let err = authenticate(SECRET, &Method::POST, &headers).unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+        let csrf_token = crate::csrf::sign_csrf_token(SECRET, now + 60, Some(session), "n");
+        headers.insert("X-CSRF-Token", csrf_token.parse().unwrap());
+        assert!(authenticate(SECRET, &Method::POST, &headers).is_ok());
+
+        // Bearer tokens are not sent by the browser on its own
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Authorization",
+            format!("Bearer {}", access_token).parse().unwrap(),
+        );
+        assert!(!request_token(&headers).unwrap().from_cookie);
+        assert!(authenticate(SECRET, &Method::POST, &headers).is_ok());
+    }
+
+    struct KeyState;
+
+    impl AuthState for KeyState {
+        fn jwt_secret(&self) -> &str {
+            SECRET
+        }
+
+        async fn is_admin(&self, _user_id: Uuid, _auth_header: &str) -> Result<bool, ApiError> {
+            Ok(false)
+        }
+
+        async fn find_api_key(&self, key_hash: &str) -> Result<Option<ApiClient>, ApiError> {
+            Ok(
+                (key_hash == hash_api_key("tbk_projector")).then(|| ApiClient {
+                    key_id: Uuid::nil(),
+                    name: "Projector".to_string(),
+                }),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_access_with_api_key() {
+        use axum::{body::Body, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(client): Extension<ApiClient>| async move { client.name })
+                    .post(|| async { "written" }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(KeyState),
+                read_access_middleware::<KeyState>,
+            ));
+        let status = |method: Method, key: Option<&str>| {
+            let mut request = Request::builder().method(method).uri("/");
+            if let Some(key) = key {
+                request = request.header(API_KEY_HEADER, key);
+            }
+            let app = app.clone();
+            async move {
+                app.oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+
+        assert_eq!(
+            status(Method::GET, Some("tbk_projector")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(Method::GET, Some("tbk_revoked")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(Method::POST, Some("tbk_projector")).await,
+            StatusCode::FORBIDDEN
+        );
+        // Without a key a user is needed
+        assert_eq!(status(Method::GET, None).await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_event_managers_reach_only_their_events() {
+        use axum::{body::Body, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        let managed = Uuid::new_v4();
+        let now = jsonwebtoken::get_current_timestamp() as i64;
+        let manager_token = encode(
+            &Header::default(),
+            &Claims {
+                sub: Uuid::new_v4().to_string(),
+                username: "volunteer".to_string(),
+                exp: now + 900,
+                iat: now,
+                jti: Uuid::new_v4().to_string(),
+                token_type: "access".to_string(),
+                sid: None,
+                managed_events: vec![managed],
+            },
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap();
+        let member_token = token("access", &Uuid::new_v4().to_string(), 900);
+
+        let app = Router::new()
+            .route("/events/:event_id/check-in", get(|| async { "checked in" }))
+            .route(
+                "/allocations",
+                get(
+                    move |Extension(access): Extension<EventAccess>| async move {
+                        access.require(managed).map(|_| "allocated")
+                    },
+                ),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(KeyState),
+                event_admin_middleware::<KeyState>,
+            ));
+        let status = |uri: String, token: &str| {
+            let request = Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        let check_in = |event_id: Uuid| format!("/events/{}/check-in", event_id);
+        assert_eq!(
+            status(check_in(managed), &manager_token).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(check_in(Uuid::new_v4()), &manager_token).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status("/allocations".to_string(), &manager_token).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(check_in(managed), &member_token).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    struct SuspendedState;
+
+    impl AuthState for SuspendedState {
+        fn jwt_secret(&self) -> &str {
+            SECRET
+        }
+
+        async fn is_admin(&self, _user_id: Uuid, _auth_header: &str) -> Result<bool, ApiError> {
+            Ok(true)
+        }
+
+        async fn is_suspended(&self, _user_id: Uuid) -> Result<bool, ApiError> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suspended_users_lose_access_with_live_tokens() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let state = Arc::new(SuspendedState);
+        let status = |app: Router| async move {
+            let request = Request::builder()
+                .uri("/")
+                .header(
+                    "Authorization",
+                    format!(
+                        "Bearer {}",
+                        token("access", &Uuid::new_v4().to_string(), 900)
+                    ),
+                )
+                .body(Body::empty())
+                .unwrap();
+            app.oneshot(request).await.unwrap().status()
+        };
+        let signed_in = Router::new()
+            .route("/", get(|| async { "signed in" }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                auth_middleware::<SuspendedState>,
+            ));
+        assert_eq!(status(signed_in).await, StatusCode::FORBIDDEN);
+
+        let admin = Router::new()
+            .route("/", get(|| async { "admin" }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                admin_middleware::<SuspendedState>,
+            ));
+        assert_eq!(status(admin).await, StatusCode::FORBIDDEN);
+
+        // Public pages still load, without the user
+        let public = Router::new()
+            .route(
+                "/",
+                get(|user: Option<axum::Extension<Uuid>>| async move {
+                    if user.is_some() {
+                        StatusCode::IM_A_TEAPOT
+                    } else {
+                        StatusCode::OK
+                    }
+                }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state,
+                optional_auth_middleware::<SuspendedState>,
+            ));
+        assert_eq!(status(public).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_suspension_checks_are_cached() {
+        let store = KvStore::memory();
+        let user_id = Uuid::new_v4();
+
+        assert!(
+            !check_suspended_cached(&store, user_id, async { Ok(false) })
+                .await
+                .unwrap()
+        );
+        // The cached answer stands until it expires or is overwritten
+        assert!(!check_suspended_cached(&store, user_id, async { Ok(true) })
+            .await
+            .unwrap());
+        store
+            .set(&suspension_key(user_id), "true", SUSPENSION_CACHE_TTL)
+            .await
+            .unwrap();
+        assert!(check_suspended_cached(&store, user_id, async { Ok(false) })
+            .await
+            .unwrap());
+    }
+
+    #[test]
+    fn test_decode_access_token() {
+        let user_id = Uuid::new_v4();
+        let (decoded, claims) =
+            decode_access_token(SECRET, &token("access", &user_id.to_string(), 900)).unwrap();
+        assert_eq!(decoded, user_id);
+        assert_eq!(claims.username, "alice");
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_tokens() {
+        let user_id = Uuid::new_v4().to_string();
+        assert!(decode_access_token(SECRET, &token("refresh", &user_id, 900)).is_err());
+        assert!(decode_access_token(SECRET, &token("access", &user_id, -3600)).is_err());
+        assert!(decode_access_token(SECRET, &token("access", "not-a-uuid", 900)).is_err());
+        assert!(decode_access_token("other", &token("access", &user_id, 900)).is_err());
+    }
+}
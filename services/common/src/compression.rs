@@ -0,0 +1,180 @@
+//! Response compression. Large JSON responses such as the attendance matrix
+//! and ballot listings shrink several times over with gzip or Brotli, which
+//! matters on venue Wi-Fi and mobile data. The client picks the encoding
+//! through `Accept-Encoding`; responses it cannot decode are sent as is.
+
+use tower_http::compression::{
+    predicate::{NotForContentType, Predicate, SizeAbove},
+    CompressionLayer, CompressionLevel, DefaultPredicate,
+};
+
+use crate::config::{ConfigVar, EnvReader};
+
+/// Environment variables read by [`CompressionSettings::read`]
+pub const COMPRESSION_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "COMPRESSION",
+        "br,gzip",
+        "Comma-separated response encodings to offer (br, gzip), or off",
+    ),
+    ConfigVar::default(
+        "COMPRESSION_MIN_SIZE",
+        "1024",
+        "Smallest response in bytes worth compressing",
+    ),
+    ConfigVar::default(
+        "COMPRESSION_LEVEL",
+        "fastest",
+        "fastest, default, best, or a number (gzip 1-9, Brotli 0-11)",
+    ),
+];
+
+/// Which encodings responses may use, and how hard to compress
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionSettings {
+    pub gzip: bool,
+    pub br: bool,
+    /// Responses of a known size below this are sent uncompressed
+    pub min_size: u16,
+    pub level: CompressionLevel,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        Self {
+            gzip: true,
+            br: true,
+            min_size: 1024,
+            level: CompressionLevel::Fastest,
+        }
+    }
+}
+
+impl CompressionSettings {
+    /// Read the variables in [`COMPRESSION_SCHEMA`], recording any problems
+    /// on `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let encodings = reader.string("COMPRESSION");
+        let (gzip, br) = reader
+            .check(parse_encodings(&encodings))
+            .unwrap_or((false, false));
+        let level = reader.string("COMPRESSION_LEVEL");
+
+        Self {
+            gzip,
+            br,
+            min_size: reader.parse("COMPRESSION_MIN_SIZE"),
+            level: reader.check(parse_level(&level)).unwrap_or_default(),
+        }
+    }
+
+    /// Layer compressing responses as configured. Images, PDFs and other
+    /// already compressed formats are left alone.
+    pub fn layer(&self) -> CompressionLayer<impl Predicate> {
+        CompressionLayer::new()
+            .gzip(self.gzip)
+            .br(self.br)
+            .quality(self.level)
+            .compress_when(
+                DefaultPredicate::new()
+                    .and(SizeAbove::new(self.min_size))
+                    .and(NotForContentType::const_new("application/pdf"))
+                    .and(NotForContentType::const_new("application/zip")),
+            )
+    }
+}
+
+/// Parse `COMPRESSION` into whether gzip and Brotli are enabled
+fn parse_encodings(raw: &str) -> Result<(bool, bool), String> {
+    let (mut gzip, mut br) = (false, false);
+    for encoding in raw.split(',').map(|e| e.trim().to_lowercase()) {
+        match encoding.as_str() {
+            "" | "off" | "none" => {}
+            "gzip" => gzip = true,
+            "br" | "brotli" => br = true,
+            other => {
+                return Err(format!(
+                    "COMPRESSION must list br and/or gzip, or be off, got {}",
+                    other
+                ))
+            }
+        }
+    }
+    Ok((gzip, br))
+}
+
+fn parse_level(raw: &str) -> Result<CompressionLevel, String> {
+    match raw.trim().to_lowercase().as_str() {
+        "fastest" => Ok(CompressionLevel::Fastest),
+        "default" => Ok(CompressionLevel::Default),
+        "best" => Ok(CompressionLevel::Best),
+        other => other
+            .parse()
+            .map(CompressionLevel::Precise)
+            .map_err(|_| format!("Invalid COMPRESSION_LEVEL: {}", raw)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Json, Router};
+    use http::{header, Request};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_parse_encodings() {
+        assert_eq!(parse_encodings("br,gzip"), Ok((true, true)));
+        assert_eq!(parse_encodings(" GZIP "), Ok((true, false)));
+        assert_eq!(parse_encodings("off"), Ok((false, false)));
+        assert!(parse_encodings("zstd").is_err());
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("Best"), Ok(CompressionLevel::Best));
+        assert_eq!(parse_level("4"), Ok(CompressionLevel::Precise(4)));
+        assert!(parse_level("max").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_large_responses_are_compressed() {
+        let rows: Vec<String> = (0..200).map(|i| format!("member {}", i)).collect();
+        let app = |settings: CompressionSettings| {
+            let rows = rows.clone();
+            Router::new()
+                .route("/matrix", get(move || async move { Json(rows) }))
+                .route("/health", get(|| async { "OK" }))
+                .layer(settings.layer())
+        };
+        let request = |uri: &str, encoding: &str| {
+            Request::builder()
+                .uri(uri)
+                .header(header::ACCEPT_ENCODING, encoding)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let encoding = |response: http::Response<_>| {
+            response
+                .headers()
+                .get(header::CONTENT_ENCODING)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        let on = app(CompressionSettings::default());
+        let response = on.clone().oneshot(request("/matrix", "gzip, br")).await;
+        assert_eq!(encoding(response.unwrap()).as_deref(), Some("br"));
+        let response = on.clone().oneshot(request("/matrix", "gzip")).await;
+        assert_eq!(encoding(response.unwrap()).as_deref(), Some("gzip"));
+        let response = on.oneshot(request("/health", "gzip")).await;
+        assert_eq!(encoding(response.unwrap()), None);
+
+        let off = app(CompressionSettings {
+            gzip: false,
+            br: false,
+            ..Default::default()
+        });
+        let response = off.oneshot(request("/matrix", "gzip, br")).await;
+        assert_eq!(encoding(response.unwrap()), None);
+    }
+}
@@ -0,0 +1,293 @@
+use std::{any::type_name, env, fmt, str::FromStr};
+
+/// Load `.env` files. Variables already set in the environment win, then the
+/// `TABRELA_ENV` overlay (e.g. `.env.production`), then the plain `.env`;
+/// service-local files take precedence over the repository root.
+pub fn load_dotenv() {
+    if let Ok(profile) = env::var("TABRELA_ENV") {
+        dotenvy::from_filename(format!(".env.{}", profile)).ok();
+        dotenvy::from_filename(format!("../../.env.{}", profile)).ok();
+    }
+    dotenvy::dotenv().ok();
+    dotenvy::from_filename("../../.env").ok();
+}
+
+/// How a variable behaves when it is not set
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarKind {
+    Required,
+    Default(&'static str),
+    Optional,
+}
+
+/// One environment variable read by a service
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigVar {
+    pub name: &'static str,
+    /// Fallback names checked, in order, when `name` is unset
+    pub aliases: &'static [&'static str],
+    pub kind: VarKind,
+    pub description: &'static str,
+}
+
+impl ConfigVar {
+    pub const fn required(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            kind: VarKind::Required,
+            description,
+        }
+    }
+
+    pub const fn default(
+        name: &'static str,
+        default: &'static str,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            kind: VarKind::Default(default),
+            description,
+        }
+    }
+
+    pub const fn optional(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            kind: VarKind::Optional,
+            description,
+        }
+    }
+
+    pub const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+}
+
+/// Every problem found while loading a service's configuration
+#[derive(Debug, PartialEq)]
+pub struct ConfigError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration:")?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Reads variables declared in one or more schemas, collecting every
+/// missing or invalid value instead of stopping at the first.
+pub struct EnvReader<'a> {
+    schemas: &'a [&'a [ConfigVar]],
+    problems: Vec<String>,
+}
+
+impl<'a> EnvReader<'a> {
+    pub fn new(schemas: &'a [&'a [ConfigVar]]) -> Self {
+        Self {
+            schemas,
+            problems: Vec::new(),
+        }
+    }
+
+    fn var(&self, name: &str) -> ConfigVar {
+        *self
+            .schemas
+            .iter()
+            .flat_map(|schema| schema.iter())
+            .find(|var| var.name == name)
+            .unwrap_or_else(|| panic!("{} is not declared in the config schema", name))
+    }
+
+    /// Raw value from the environment or the declared default
+    pub fn optional(&mut self, name: &str) -> Option<String> {
+        let var = self.var(name);
+        let value = std::iter::once(var.name)
+            .chain(var.aliases.iter().copied())
+            .find_map(|key| env::var(key).ok().filter(|v| !v.trim().is_empty()));
+
+        match (value, var.kind) {
+            (Some(value), _) => Some(value),
+            (None, VarKind::Default(default)) => Some(default.to_string()),
+            (None, VarKind::Required) => {
+                self.problems.push(format!("{} must be set", name));
+                None
+            }
+            (None, VarKind::Optional) => None,
+        }
+    }
+
+    /// String value; empty when a required variable is missing
+    pub fn string(&mut self, name: &str) -> String {
+        self.optional(name).unwrap_or_default()
+    }
+
+    /// Parse an optional value, recording a problem if it does not parse
+    pub fn parse_optional<T: FromStr>(&mut self, name: &str) -> Option<T> {
+        let raw = self.optional(name)?;
+        match raw.trim().parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.problems.push(format!(
+                    "Invalid {}: '{}' is not a valid {}",
+                    name,
+                    raw,
+                    type_name::<T>()
+                ));
+                None
+            }
+        }
+    }
+
+    /// Parse a value, falling back to `T::default()` after recording a problem
+    pub fn parse<T: FromStr + Default>(&mut self, name: &str) -> T {
+        self.parse_optional(name).unwrap_or_default()
+    }
+
+    /// Record a problem found by a caller-side check
+    pub fn check<T, E: fmt::Display>(&mut self, result: Result<T, E>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.problems.push(e.to_string());
+                None
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<(), ConfigError> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError {
+                problems: self.problems,
+            })
+        }
+    }
+}
+
+/// Render schemas as an annotated `.env` template
+pub fn render_schema(title: &str, schemas: &[&[ConfigVar]]) -> String {
+    let mut out = format!("# {}\n", title);
+    for var in schemas.iter().flat_map(|schema| schema.iter()) {
+        out.push('\n');
+        out.push_str(&format!("# {}\n", var.description));
+        if !var.aliases.is_empty() {
+            out.push_str(&format!("# Also read from: {}\n", var.aliases.join(", ")));
+        }
+        match var.kind {
+            VarKind::Required => out.push_str(&format!("# Required\n{}=\n", var.name)),
+            VarKind::Default(default) => out.push_str(&format!("{}={}\n", var.name, default)),
+            VarKind::Optional => out.push_str(&format!("# {}=\n", var.name)),
+        }
+    }
+    out
+}
+
+/// Split a comma-separated `ALLOWED_ORIGINS` value, dropping empty entries
+pub fn parse_allowed_origins(raw: &str) -> Vec<String> {
+    let origins: Vec<String> = raw
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if origins.is_empty() {
+        vec!["*".to_string()]
+    } else {
+        origins
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &[ConfigVar] = &[
+        ConfigVar::required("COMMON_TEST_URL", "Database URL"),
+        ConfigVar::default("COMMON_TEST_PORT", "8080", "Listen port")
+            .aliases(&["COMMON_TEST_ALT_PORT"]),
+        ConfigVar::optional("COMMON_TEST_MAX_AGE", "Cache lifetime"),
+        ConfigVar::required("COMMON_TEST_SECRET", "Signing secret"),
+    ];
+
+    #[test]
+    fn test_parse_allowed_origins() {
+        assert_eq!(parse_allowed_origins("*"), vec!["*"]);
+        assert_eq!(
+            parse_allowed_origins("https://a.example, https://b.example ,"),
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert_eq!(parse_allowed_origins(" , "), vec!["*"]);
+    }
+
+    #[test]
+    fn test_reader_collects_every_problem() {
+        env::remove_var("COMMON_TEST_URL");
+        env::remove_var("COMMON_TEST_SECRET");
+        env::remove_var("COMMON_TEST_PORT");
+        env::set_var("COMMON_TEST_ALT_PORT", "not-a-port");
+        env::remove_var("COMMON_TEST_MAX_AGE");
+
+        let mut reader = EnvReader::new(&[SCHEMA]);
+        assert_eq!(reader.string("COMMON_TEST_URL"), "");
+        assert_eq!(reader.parse::<u16>("COMMON_TEST_PORT"), 0);
+        assert_eq!(reader.parse_optional::<u64>("COMMON_TEST_MAX_AGE"), None);
+        reader.string("COMMON_TEST_SECRET");
+
+        let err = reader.finish().unwrap_err();
+        assert_eq!(
+            err.problems,
+            vec![
+                "COMMON_TEST_URL must be set",
+                "Invalid COMMON_TEST_PORT: 'not-a-port' is not a valid u16",
+                "COMMON_TEST_SECRET must be set",
+            ]
+        );
+        assert!(err
+            .to_string()
+            .contains("\n  - COMMON_TEST_SECRET must be set"));
+    }
+
+    #[test]
+    fn test_reader_defaults_and_aliases() {
+        env::set_var("COMMON_DEFAULTS_URL", "postgres://localhost/test");
+        const DEFAULTS: &[ConfigVar] = &[
+            ConfigVar::required("COMMON_DEFAULTS_URL", "Database URL"),
+            ConfigVar::default("COMMON_DEFAULTS_PORT", "8080", "Listen port")
+                .aliases(&["COMMON_DEFAULTS_ALT_PORT"]),
+        ];
+
+        let mut reader = EnvReader::new(&[DEFAULTS]);
+        assert_eq!(reader.parse::<u16>("COMMON_DEFAULTS_PORT"), 8080);
+        env::set_var("COMMON_DEFAULTS_ALT_PORT", "9000");
+        assert_eq!(reader.parse::<u16>("COMMON_DEFAULTS_PORT"), 9000);
+        assert_eq!(
+            reader.string("COMMON_DEFAULTS_URL"),
+            "postgres://localhost/test"
+        );
+        assert!(reader.finish().is_ok());
+    }
+
+    #[test]
+    fn test_render_schema() {
+        let rendered = render_schema("Test service", &[SCHEMA]);
+        assert!(rendered.starts_with("# Test service\n"));
+        assert!(rendered.contains("# Required\nCOMMON_TEST_URL=\n"));
+        assert!(
+            rendered.contains("# Also read from: COMMON_TEST_ALT_PORT\nCOMMON_TEST_PORT=8080\n")
+        );
+        assert!(rendered.contains("# COMMON_TEST_MAX_AGE=\n"));
+    }
+}
@@ -0,0 +1,502 @@
+use http::{header::HeaderValue, request::Parts, HeaderName, Method};
+use std::{fmt, time::Duration};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use crate::config::{parse_allowed_origins, ConfigVar, EnvReader};
+use crate::versioning::strip_version;
+
+const ALLOWED_METHODS: [Method; 6] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+];
+
+#[derive(Debug, PartialEq)]
+pub enum CorsConfigError {
+    InvalidOrigin(String),
+    InvalidHeader(String),
+    InvalidRouteOverride(String),
+    NoOriginsInStrictMode,
+    WildcardWithCredentials,
+}
+
+impl fmt::Display for CorsConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorsConfigError::InvalidOrigin(o) => write!(f, "Invalid CORS origin: {}", o),
+            CorsConfigError::InvalidHeader(h) => write!(f, "Invalid CORS header name: {}", h),
+            CorsConfigError::InvalidRouteOverride(r) => {
+                write!(f, "Invalid CORS_ROUTE_ORIGINS entry: {}", r)
+            }
+            CorsConfigError::NoOriginsInStrictMode => {
+                write!(f, "CORS_STRICT_MODE requires at least one explicit origin")
+            }
+            CorsConfigError::WildcardWithCredentials => write!(
+                f,
+                "CORS credentials cannot be combined with a wildcard origin"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CorsConfigError {}
+
+/// Origins allowed for every path under `prefix` (relative to the service)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsRouteOverride {
+    pub prefix: String,
+    pub allowed_origins: Vec<String>,
+}
+
+/// CORS settings shared by every service
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsSettings {
+    pub allowed_origins: Vec<String>,
+    pub strict_mode: bool,
+    /// `None` enables credentials only when origins are explicitly listed
+    pub allow_credentials: Option<bool>,
+    /// Seconds browsers may cache preflight responses
+    pub max_age: Option<u64>,
+    pub expose_headers: Vec<String>,
+    pub route_overrides: Vec<CorsRouteOverride>,
+}
+
+impl Default for CorsSettings {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".to_string()],
+            strict_mode: false,
+            allow_credentials: None,
+            max_age: None,
+            expose_headers: Vec::new(),
+            route_overrides: Vec::new(),
+        }
+    }
+}
+
+/// Environment variables read by [`CorsSettings::read`]
+pub const CORS_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "ALLOWED_ORIGINS",
+        "*",
+        "Comma-separated CORS origins; * allows any origin without credentials",
+    ),
+    ConfigVar::default(
+        "CORS_STRICT_MODE",
+        "false",
+        "Ignore * and only accept the listed origins",
+    ),
+    ConfigVar::optional(
+        "CORS_ALLOW_CREDENTIALS",
+        "Force credentials on or off (default: on for explicit origins, off for *)",
+    ),
+    ConfigVar::optional(
+        "CORS_MAX_AGE",
+        "Seconds browsers may cache preflight responses",
+    ),
+    ConfigVar::optional(
+        "CORS_EXPOSE_HEADERS",
+        "Comma-separated response headers readable by the frontend",
+    ),
+    ConfigVar::optional(
+        "CORS_ROUTE_ORIGINS",
+        "Per-route origins relative to the service, e.g. /admin=https://a.example;/public=*",
+    ),
+];
+
+impl CorsSettings {
+    /// Read the variables in [`CORS_SCHEMA`] and validate the result,
+    /// recording any problems on `reader`.
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let route_overrides =
+            parse_route_overrides(&reader.optional("CORS_ROUTE_ORIGINS").unwrap_or_default());
+
+        let settings = Self {
+            allowed_origins: parse_allowed_origins(&reader.string("ALLOWED_ORIGINS")),
+            strict_mode: reader.parse("CORS_STRICT_MODE"),
+            allow_credentials: reader.parse_optional("CORS_ALLOW_CREDENTIALS"),
+            max_age: reader.parse_optional("CORS_MAX_AGE"),
+            expose_headers: split_list(&reader.optional("CORS_EXPOSE_HEADERS").unwrap_or_default()),
+            route_overrides: reader.check(route_overrides).unwrap_or_default(),
+        };
+
+        reader.check(settings.validate());
+        settings
+    }
+
+    /// Whether the default origin list accepts any origin
+    pub fn is_wildcard(&self) -> bool {
+        !self.strict_mode && self.allowed_origins.iter().any(|o| o == "*")
+    }
+
+    /// Whether responses carry `Access-Control-Allow-Credentials`
+    pub fn allows_credentials(&self) -> bool {
+        self.allow_credentials.unwrap_or(!self.is_wildcard())
+    }
+
+    /// Reject settings that browsers would refuse or that tower-http panics on
+    pub fn validate(&self) -> Result<(), CorsConfigError> {
+        let origin_lists = std::iter::once(&self.allowed_origins)
+            .chain(self.route_overrides.iter().map(|r| &r.allowed_origins));
+        for origin in origin_lists.flatten() {
+            if origin != "*" && !is_valid_origin(origin) {
+                return Err(CorsConfigError::InvalidOrigin(origin.clone()));
+            }
+        }
+
+        if self.strict_mode && self.allowed_origins.iter().all(|o| o == "*") {
+            return Err(CorsConfigError::NoOriginsInStrictMode);
+        }
+
+        if self.allows_credentials() {
+            let override_wildcard = self
+                .route_overrides
+                .iter()
+                .any(|r| r.allowed_origins.iter().any(|o| o == "*"));
+            if self.is_wildcard() || override_wildcard {
+                return Err(CorsConfigError::WildcardWithCredentials);
+            }
+        }
+
+        for header in &self.expose_headers {
+            if header.parse::<HeaderName>().is_err() {
+                return Err(CorsConfigError::InvalidHeader(header.clone()));
+            }
+        }
+
+        for route in &self.route_overrides {
+            if !route.prefix.starts_with('/') || route.allowed_origins.is_empty() {
+                return Err(CorsConfigError::InvalidRouteOverride(route.prefix.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse `CORS_ROUTE_ORIGINS`, e.g. `/admin=https://a.example,https://b.example;/public=*`
+pub fn parse_route_overrides(raw: &str) -> Result<Vec<CorsRouteOverride>, CorsConfigError> {
+    raw.split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (prefix, origins) = entry
+                .split_once('=')
+                .ok_or_else(|| CorsConfigError::InvalidRouteOverride(entry.to_string()))?;
+            let prefix = prefix.trim();
+            let prefix = match prefix.trim_end_matches('/') {
+                "" if prefix.starts_with('/') => "/",
+                trimmed => trimmed,
+            };
+            Ok(CorsRouteOverride {
+                prefix: prefix.to_string(),
+                allowed_origins: split_list(origins),
+            })
+        })
+        .collect()
+}
+
+fn split_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn is_valid_origin(origin: &str) -> bool {
+    (origin.starts_with("http://") || origin.starts_with("https://"))
+        && !origin.ends_with('/')
+        && origin.parse::<HeaderValue>().is_ok()
+}
+
+/// Origins the layer should accept: `None` means any origin (development
+/// wildcard), otherwise the parsed list. A `*` entry only counts as a
+/// wildcard outside strict mode; unparseable entries are skipped.
+pub fn resolve_origins(allowed_origins: &[String], strict_mode: bool) -> Option<Vec<HeaderValue>> {
+    if !strict_mode && allowed_origins.iter().any(|o| o == "*") {
+        return None;
+    }
+
+    Some(
+        allowed_origins
+            .iter()
+            .filter(|o| o.as_str() != "*")
+            .filter_map(|o| match o.parse::<HeaderValue>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid CORS origin: {}", o);
+                    None
+                }
+            })
+            .collect(),
+    )
+}
+
+type ResolvedOverride = (String, Option<Vec<HeaderValue>>);
+
+/// Longest route override whose prefix matches `path`
+fn matching_override<'a>(
+    overrides: &'a [ResolvedOverride],
+    path: &str,
+) -> Option<&'a Option<Vec<HeaderValue>>> {
+    overrides
+        .iter()
+        .filter(|(prefix, _)| {
+            prefix == "/"
+                || path == prefix
+                || path
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, origins)| origins)
+}
+
+/// Build the CORS layer used by every service.
+///
+/// With a wildcard origin the layer allows any origin and header but never
+/// credentials. With explicit origins, only those origins are allowed and only
+/// `Content-Type`, `Authorization` and `extra_headers` may be sent. Route
+/// overrides replace the origin list for paths under their prefix. Settings
+/// are expected to have passed [`CorsSettings::validate`].
+pub fn configure_cors(settings: &CorsSettings, extra_headers: &[HeaderName]) -> CorsLayer {
+    let default_origins = resolve_origins(&settings.allowed_origins, settings.strict_mode);
+
+    let allow_origin = if settings.route_overrides.is_empty() {
+        match default_origins {
+            None => AllowOrigin::from(Any),
+            Some(origins) => AllowOrigin::list(origins),
+        }
+    } else {
+        let overrides: Vec<ResolvedOverride> = settings
+            .route_overrides
+            .iter()
+            .map(|r| {
+                (
+                    r.prefix.clone(),
+                    resolve_origins(&r.allowed_origins, settings.strict_mode),
+                )
+            })
+            .collect();
+
+        AllowOrigin::predicate(move |origin: &HeaderValue, parts: &Parts| {
+            let origins = matching_override(&overrides, strip_version(parts.uri.path()))
+                .unwrap_or(&default_origins);
+            origins.as_ref().is_none_or(|list| list.contains(origin))
+        })
+    };
+
+    let mut layer = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(ALLOWED_METHODS);
+
+    layer = if settings.is_wildcard() {
+        layer.allow_headers(Any).allow_credentials(false) // Cannot use credentials with wildcard origin
+    } else {
+        // Every service checks CSRF tokens on requests authenticated by
+        // cookie, and integrations may send API keys from the browser
+        let mut headers = vec![
+            http::header::CONTENT_TYPE,
+            http::header::AUTHORIZATION,
+            http::HeaderName::from_static("x-csrf-token"),
+            http::HeaderName::from_static(crate::api_keys::API_KEY_HEADER),
+        ];
+        headers.extend_from_slice(extra_headers);
+        layer
+            .allow_headers(headers)
+            .allow_credentials(settings.allows_credentials())
+    };
+
+    let expose: Vec<HeaderName> = settings
+        .expose_headers
+        .iter()
+        .filter_map(|h| h.parse().ok())
+        .collect();
+    if !expose.is_empty() {
+        layer = layer.expose_headers(expose);
+    }
+
+    if let Some(secs) = settings.max_age {
+        layer = layer.max_age(Duration::from_secs(secs));
+    }
+
+    layer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    fn origins(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn settings(list: &[&str]) -> CorsSettings {
+        CorsSettings {
+            allowed_origins: origins(list),
+            ..CorsSettings::default()
+        }
+    }
+
+    async fn preflight(settings: &CorsSettings, path: &str, origin: &str) -> Response<Body> {
+        let service = tower::Layer::layer(
+            &configure_cors(settings, &[]),
+            service_fn(|_req: Request<Body>| async {
+                Ok::<_, std::convert::Infallible>(Response::new(Body::empty()))
+            }),
+        );
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri(path)
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "GET")
+            .body(Body::empty())
+            .unwrap();
+        service.oneshot(request).await.unwrap()
+    }
+
+    fn allowed_origin(response: &Response<Body>) -> Option<&HeaderValue> {
+        response.headers().get("access-control-allow-origin")
+    }
+
+    #[test]
+    fn test_wildcard_outside_strict_mode() {
+        assert!(resolve_origins(&origins(&["*"]), false).is_none());
+        assert!(resolve_origins(&origins(&["https://a.example", "*"]), false).is_none());
+    }
+
+    #[test]
+    fn test_strict_mode_ignores_wildcard() {
+        let resolved = resolve_origins(&origins(&["*", "https://a.example"]), true).unwrap();
+        assert_eq!(
+            resolved,
+            vec![HeaderValue::from_static("https://a.example")]
+        );
+    }
+
+    #[test]
+    fn test_all_listed_origins_are_kept() {
+        let resolved =
+            resolve_origins(&origins(&["https://a.example", "https://b.example"]), false).unwrap();
+        assert_eq!(resolved.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_rejects_contradictions() {
+        let mut s = settings(&["*"]);
+        assert!(s.validate().is_ok());
+        assert!(!s.allows_credentials());
+
+        s.allow_credentials = Some(true);
+        assert_eq!(s.validate(), Err(CorsConfigError::WildcardWithCredentials));
+
+        let mut s = settings(&["*"]);
+        s.strict_mode = true;
+        assert_eq!(s.validate(), Err(CorsConfigError::NoOriginsInStrictMode));
+
+        let s = settings(&["a.example"]);
+        assert!(matches!(
+            s.validate(),
+            Err(CorsConfigError::InvalidOrigin(_))
+        ));
+
+        let mut s = settings(&["https://a.example"]);
+        s.expose_headers = vec!["bad header".to_string()];
+        assert!(matches!(
+            s.validate(),
+            Err(CorsConfigError::InvalidHeader(_))
+        ));
+
+        let mut s = settings(&["https://a.example"]);
+        s.route_overrides = parse_route_overrides("/public=*").unwrap();
+        assert_eq!(s.validate(), Err(CorsConfigError::WildcardWithCredentials));
+
+        let mut s = settings(&["https://a.example"]);
+        s.route_overrides = parse_route_overrides("/admin=").unwrap();
+        assert!(matches!(
+            s.validate(),
+            Err(CorsConfigError::InvalidRouteOverride(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_route_overrides() {
+        let parsed =
+            parse_route_overrides(" /admin/=https://a.example, https://b.example ; /public=* ;")
+                .unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                CorsRouteOverride {
+                    prefix: "/admin".to_string(),
+                    allowed_origins: origins(&["https://a.example", "https://b.example"]),
+                },
+                CorsRouteOverride {
+                    prefix: "/public".to_string(),
+                    allowed_origins: origins(&["*"]),
+                },
+            ]
+        );
+        assert!(parse_route_overrides("/admin").is_err());
+    }
+
+    #[test]
+    fn test_configure_cors_layers_cleanly() {
+        // tower-http panics at layer time when credentials are combined with
+        // wildcard origins or headers
+        let mut with_overrides = settings(&["*"]);
+        with_overrides.route_overrides = parse_route_overrides("/admin=https://a.example").unwrap();
+        let layers = [
+            configure_cors(&settings(&["*"]), &[]),
+            configure_cors(
+                &settings(&["https://a.example"]),
+                &[HeaderName::from_static("x-csrf-token")],
+            ),
+            configure_cors(&with_overrides, &[]),
+        ];
+        for layer in layers {
+            let _ = tower::Layer::layer(&layer, ());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_age_and_credentials() {
+        let mut s = settings(&["https://a.example"]);
+        s.max_age = Some(600);
+        s.expose_headers = vec!["x-total-count".to_string()];
+
+        let response = preflight(&s, "/events", "https://a.example").await;
+        let headers = response.headers();
+        assert_eq!(headers["access-control-max-age"], "600");
+        assert_eq!(headers["access-control-allow-origin"], "https://a.example");
+        assert_eq!(headers["access-control-allow-credentials"], "true");
+    }
+
+    #[tokio::test]
+    async fn test_route_override_restricts_origin() {
+        let mut s = settings(&["*"]);
+        s.route_overrides = parse_route_overrides("/admin=https://admin.example").unwrap();
+
+        let response = preflight(&s, "/events", "https://x.example").await;
+        assert_eq!(allowed_origin(&response).unwrap(), "https://x.example");
+
+        let response = preflight(&s, "/admin/users", "https://x.example").await;
+        assert!(allowed_origin(&response).is_none());
+
+        let response = preflight(&s, "/admin/users", "https://admin.example").await;
+        assert_eq!(allowed_origin(&response).unwrap(), "https://admin.example");
+
+        let response = preflight(&s, "/administer", "https://x.example").await;
+        assert!(allowed_origin(&response).is_some());
+
+        // Overrides cover the same routes in every API version
+        let response = preflight(&s, "/v1/admin/users", "https://x.example").await;
+        assert!(allowed_origin(&response).is_none());
+    }
+}
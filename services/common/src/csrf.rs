@@ -0,0 +1,144 @@
+//! Signed CSRF tokens, checked by every service for requests authenticated
+//! by cookie.
+//!
+//! The auth service issues the tokens. A token is
+//! `expires.session.nonce.signature`, where the signature is an HMAC over
+//! the other parts keyed by the JWT secret all services share, so any
+//! service can check one without a database.
+
+use axum::http::{header, HeaderMap};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+/// Header clients send the token in
+pub const CSRF_TOKEN_HEADER: &str = "X-CSRF-Token";
+
+fn mac(secret: &str, payload: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(b"csrf:");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Sign a token for a session, or for no session (anonymous clients)
+pub fn sign_csrf_token(
+    secret: &str,
+    expires_at: i64,
+    session_id: Option<Uuid>,
+    nonce: &str,
+) -> String {
+    let session = session_id.map(|id| id.to_string()).unwrap_or_default();
+    let payload = format!("{}.{}.{}", expires_at, session, nonce);
+    let signature = hex::encode(mac(secret, &payload).finalize().into_bytes());
+    format!("{}.{}", payload, signature)
+}
+
+/// Check a token's signature and expiry, returning the session it was
+/// issued to (`None` for anonymous tokens)
+pub fn verify_csrf_token(secret: &str, token: &str) -> Option<Option<Uuid>> {
+    let (payload, signature) = token.rsplit_once('.')?;
+    mac(secret, payload)
+        .verify_slice(&hex::decode(signature).ok()?)
+        .ok()?;
+
+    let mut parts = payload.splitn(3, '.');
+    let expires_at: i64 = parts.next()?.parse().ok()?;
+    if expires_at <= Utc::now().timestamp() {
+        return None;
+    }
+    match parts.next()? {
+        "" => Some(None),
+        session => Uuid::parse_str(session).ok().map(Some),
+    }
+}
+
+/// Whether a token issued to `issued_to` is acceptable for a request made
+/// in `session_id`. Requests without a session, or with an access token
+/// issued before sessions were tracked, accept any token.
+pub fn token_matches_session(issued_to: Option<Uuid>, session_id: Option<Uuid>) -> bool {
+    session_id.is_none() || issued_to == session_id
+}
+
+/// Whether the request's `X-CSRF-Token` header holds a valid token for the
+/// session
+pub fn has_valid_csrf_token(secret: &str, headers: &HeaderMap, session_id: Option<Uuid>) -> bool {
+    headers
+        .get(CSRF_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|token| verify_csrf_token(secret, token))
+        .is_some_and(|issued_to| token_matches_session(issued_to, session_id))
+}
+
+/// Value of a request cookie
+pub fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn token(session: Option<Uuid>, ttl: i64) -> String {
+        sign_csrf_token("secret", Utc::now().timestamp() + ttl, session, "nonce")
+    }
+
+    #[test]
+    fn test_signed_tokens() {
+        let session = Uuid::new_v4();
+        let signed = token(Some(session), 60);
+        assert_eq!(verify_csrf_token("secret", &signed), Some(Some(session)));
+        assert_eq!(verify_csrf_token("other-secret", &signed), None);
+        assert_eq!(verify_csrf_token("secret", &token(None, 60)), Some(None));
+
+        // Tampering with any part breaks the signature
+        let forged = signed.replacen(&session.to_string(), &Uuid::new_v4().to_string(), 1);
+        assert_eq!(verify_csrf_token("secret", &forged), None);
+        assert_eq!(verify_csrf_token("secret", "not-a-token"), None);
+
+        assert_eq!(verify_csrf_token("secret", &token(Some(session), -1)), None);
+    }
+
+    #[test]
+    fn test_tokens_bound_to_session() {
+        let session = Uuid::new_v4();
+        assert!(token_matches_session(Some(session), Some(session)));
+        assert!(!token_matches_session(Some(Uuid::new_v4()), Some(session)));
+        assert!(!token_matches_session(None, Some(session)));
+        assert!(token_matches_session(Some(session), None));
+
+        let mut headers = HeaderMap::new();
+        assert!(!has_valid_csrf_token("secret", &headers, Some(session)));
+        headers.insert(
+            CSRF_TOKEN_HEADER,
+            HeaderValue::from_str(&token(Some(session), 60)).unwrap(),
+        );
+        assert!(has_valid_csrf_token("secret", &headers, Some(session)));
+        assert!(!has_valid_csrf_token(
+            "secret",
+            &headers,
+            Some(Uuid::new_v4())
+        ));
+    }
+
+    #[test]
+    fn test_cookie_value() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; csrf_token=abc.def; other=1"),
+        );
+        assert_eq!(cookie_value(&headers, "csrf_token"), Some("abc.def"));
+        assert_eq!(cookie_value(&headers, "missing"), None);
+    }
+}
@@ -0,0 +1,36 @@
+use axum::{http::StatusCode, Json};
+use serde_json::{json, Value};
+
+/// Error shape returned by every handler and middleware: a status code and a
+/// `{"error": "..."}` body.
+pub type ApiError = (StatusCode, Json<Value>);
+
+/// Build an `ApiError` with the given status and message
+pub fn api_error(status: StatusCode, message: impl Into<String>) -> ApiError {
+    (status, Json(json!({ "error": message.into() })))
+}
+
+/// Log a database failure and return a generic 500 without leaking details
+pub fn db_error<E: std::fmt::Debug>(e: E) -> ApiError {
+    tracing::error!("Database error: {:?}", e);
+    api_error(StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_body() {
+        let (status, Json(body)) = api_error(StatusCode::NOT_FOUND, "Event not found");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body, json!({"error": "Event not found"}));
+    }
+
+    #[test]
+    fn test_db_error_hides_details() {
+        let (status, Json(body)) = db_error("connection refused");
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, json!({"error": "Database error"}));
+    }
+}
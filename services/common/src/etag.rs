@@ -0,0 +1,148 @@
+//! Conditional GETs for read endpoints that clients poll. Before doing the
+//! work, an endpoint looks up a cheap version of what it would return: the
+//! `updated_at` and row counts of the rows behind it. The version, the
+//! viewer and the query string are hashed into a weak ETag, and a request
+//! whose `If-None-Match` lists that tag gets `304 Not Modified` without the
+//! handler running.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// SQL expression summarising the rows of `from`, which must alias the
+/// table as `t`: how many there are and when the latest changed. Adding,
+/// removing or editing a row changes it.
+pub fn rows_version(from: &str) -> String {
+    format!(
+        "(SELECT COUNT(*) || '@' || COALESCE(MAX(t.updated_at)::text, '') FROM {})",
+        from
+    )
+}
+
+/// Weak ETag for `version` as seen by `viewer` with the request's `query`,
+/// which can change the representation (e.g. `?fields=`)
+pub fn etag(version: &str, viewer: Option<Uuid>, query: Option<&str>) -> HeaderValue {
+    let mut hasher = Sha256::new();
+    hasher.update(version.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(viewer.map(|id| id.to_string()).unwrap_or_default());
+    hasher.update(b"\0");
+    hasher.update(query.unwrap_or_default().as_bytes());
+    let digest = hex::encode(&hasher.finalize()[..16]);
+    HeaderValue::from_str(&format!("W/\"{}\"", digest)).expect("hex digest is a valid header")
+}
+
+/// Whether `If-None-Match` lists `etag`, compared weakly
+pub fn is_fresh(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// Answer a GET with `304 Not Modified` when the client already holds
+/// `version`, otherwise run the handler and tag a successful response.
+/// Without a version (e.g. the resource does not exist) the handler runs
+/// untouched.
+pub async fn conditional_get(
+    request: Request,
+    next: Next,
+    version: Option<String>,
+    viewer: Option<Uuid>,
+) -> Response {
+    let Some(version) = version else {
+        return next.run(request).await;
+    };
+    let tag = etag(&version, viewer, request.uri().query());
+
+    if is_fresh(request.headers(), &tag) {
+        let mut response = (StatusCode::NOT_MODIFIED, Body::empty()).into_response();
+        response.headers_mut().insert(header::ETAG, tag);
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    if response.status().is_success() {
+        let headers = response.headers_mut();
+        headers.insert(header::ETAG, tag);
+        // Let caches keep the response but check back every time
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_etag_varies_with_viewer_and_query() {
+        let viewer = Some(Uuid::nil());
+        let base = etag("v1", None, None);
+        assert!(base.to_str().unwrap().starts_with("W/\""));
+        assert_eq!(base, etag("v1", None, None));
+        assert_ne!(base, etag("v2", None, None));
+        assert_ne!(base, etag("v1", viewer, None));
+        assert_ne!(base, etag("v1", None, Some("fields=id")));
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let tag = etag("v1", None, None);
+        let strong = tag.to_str().unwrap().trim_start_matches("W/").to_string();
+        let mut headers = HeaderMap::new();
+        assert!(!is_fresh(&headers, &tag));
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", {}", strong)).unwrap(),
+        );
+        assert!(is_fresh(&headers, &tag));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(is_fresh(&headers, &tag));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_get_skips_the_handler() {
+        let app = Router::new().route(
+            "/matches/1",
+            get(|| async { "match" }).layer(middleware::from_fn(|request, next| {
+                conditional_get(request, next, Some("v1".to_string()), None)
+            })),
+        );
+        let request = |tag: Option<&HeaderValue>| {
+            let mut builder = Request::builder().uri("/matches/1");
+            if let Some(tag) = tag {
+                builder = builder.header(header::IF_NONE_MATCH, tag);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let response = app.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let tag = response.headers()[header::ETAG].clone();
+
+        let response = app.clone().oneshot(request(Some(&tag))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], tag);
+
+        let stale = HeaderValue::from_static("W/\"stale\"");
+        let response = app.oneshot(request(Some(&stale))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,265 @@
+//! Fan-out of domain events. Each service's outbox relay publishes the
+//! events it delivers to the bus, and anything interested in them (chat
+//! bridges, cache invalidation, other services) subscribes instead of
+//! polling the shared database.
+//!
+//! The default bus is in-process: subscribers only see events published by
+//! the same process, which covers every service when they run in the
+//! gateway binary. Deployments that run services on separate hosts set
+//! `EVENT_BUS=nats`, and events are published to a NATS server on
+//! `<prefix>.<service>.<kind>` with the event id as `Nats-Msg-Id`, so a
+//! JetStream stream over the subjects drops the repeats a relay retry can
+//! cause.
+
+use futures::stream::{BoxStream, StreamExt};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::config::{ConfigVar, EnvReader};
+use crate::outbox::OutboxEvent;
+
+/// Environment variables read by [`EventBusSettings::read`]
+pub const EVENT_BUS_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "EVENT_BUS",
+        "local",
+        "Where domain events are published: local (within this process) or nats",
+    ),
+    ConfigVar::optional(
+        "NATS_URL",
+        "NATS server to publish domain events to when EVENT_BUS=nats (e.g. nats://nats:4222)",
+    ),
+    ConfigVar::default(
+        "NATS_SUBJECT_PREFIX",
+        "tabrela",
+        "First token of the subjects domain events are published on",
+    ),
+];
+
+/// Events kept for in-process subscribers that are slow to read
+const LOCAL_CAPACITY: usize = 256;
+/// How long a publish may wait for the NATS server
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BusBackend {
+    #[default]
+    Local,
+    Nats,
+}
+
+impl FromStr for BusBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "local" => Ok(BusBackend::Local),
+            "nats" => Ok(BusBackend::Nats),
+            other => Err(format!("EVENT_BUS must be local or nats, got {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventBusSettings {
+    pub backend: BusBackend,
+    pub nats_url: Option<String>,
+    pub subject_prefix: String,
+}
+
+impl Default for EventBusSettings {
+    fn default() -> Self {
+        Self {
+            backend: BusBackend::Local,
+            nats_url: None,
+            subject_prefix: "tabrela".to_string(),
+        }
+    }
+}
+
+impl EventBusSettings {
+    /// Read the variables in [`EVENT_BUS_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let backend = reader.string("EVENT_BUS");
+        let backend = reader.check(backend.parse()).unwrap_or_default();
+        let nats_url = reader.optional("NATS_URL");
+        if backend == BusBackend::Nats {
+            match &nats_url {
+                None => reader.check::<(), _>(Err("NATS_URL must be set when EVENT_BUS=nats")),
+                Some(url) => reader.check(
+                    url.parse::<async_nats::ServerAddr>()
+                        .map(|_| ())
+                        .map_err(|e| format!("Invalid NATS_URL: {}", e)),
+                ),
+            };
+        }
+
+        Self {
+            backend,
+            nats_url,
+            subject_prefix: reader.string("NATS_SUBJECT_PREFIX"),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Transport {
+    Local(broadcast::Sender<OutboxEvent>),
+    Nats(async_nats::Client),
+}
+
+/// Publishes domain events and hands them to subscribers
+#[derive(Clone)]
+pub struct EventBus {
+    transport: Transport,
+    prefix: String,
+}
+
+impl EventBus {
+    /// The in-process bus, shared by every service in the process
+    pub fn local() -> Self {
+        static LOCAL: OnceLock<broadcast::Sender<OutboxEvent>> = OnceLock::new();
+        let sender = LOCAL.get_or_init(|| broadcast::channel(LOCAL_CAPACITY).0);
+        Self {
+            transport: Transport::Local(sender.clone()),
+            prefix: EventBusSettings::default().subject_prefix,
+        }
+    }
+
+    /// The bus configured by `settings`. A NATS server that cannot be
+    /// reached yet does not stop the service starting; the client keeps
+    /// reconnecting and publishes fail, to be retried by the relay, until
+    /// it succeeds.
+    pub async fn connect(settings: &EventBusSettings) -> Self {
+        let url = match (settings.backend, &settings.nats_url) {
+            (BusBackend::Nats, Some(url)) => url,
+            _ => return Self::local(),
+        };
+
+        let client = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .event_callback(|event| async move {
+                match event {
+                    async_nats::Event::Connected => tracing::info!("Connected to NATS"),
+                    async_nats::Event::Disconnected => tracing::warn!("Disconnected from NATS"),
+                    _ => {}
+                }
+            })
+            .connect(url.as_str())
+            .await;
+        match client {
+            Ok(client) => Self {
+                transport: Transport::Nats(client),
+                prefix: settings.subject_prefix.clone(),
+            },
+            Err(e) => {
+                tracing::error!("Could not set up NATS, publishing events locally: {}", e);
+                Self::local()
+            }
+        }
+    }
+
+    /// Subject `event` is published on
+    pub fn subject(&self, event: &OutboxEvent) -> String {
+        format!("{}.{}.{}", self.prefix, event.service, event.kind)
+    }
+
+    /// Publish `event`. With NATS this returns once the server has it.
+    pub async fn publish(&self, event: &OutboxEvent) -> Result<(), String> {
+        match &self.transport {
+            Transport::Local(sender) => {
+                // No subscribers is not a failure
+                let _ = sender.send(event.clone());
+                Ok(())
+            }
+            Transport::Nats(client) => {
+                let body = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+                let mut headers = async_nats::HeaderMap::new();
+                headers.insert("Nats-Msg-Id", event.id.to_string().as_str());
+                let publish = async {
+                    client
+                        .publish_with_headers(self.subject(event), headers, body.into())
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    client.flush().await.map_err(|e| e.to_string())
+                };
+                tokio::time::timeout(PUBLISH_TIMEOUT, publish)
+                    .await
+                    .map_err(|_| "NATS did not answer in time".to_string())?
+            }
+        }
+    }
+
+    /// Events published from now on, by every service on the bus. A local
+    /// subscriber that falls behind skips what it missed.
+    pub async fn subscribe(&self) -> Result<BoxStream<'static, OutboxEvent>, String> {
+        match &self.transport {
+            Transport::Local(sender) => {
+                let events = futures::stream::unfold(sender.subscribe(), |mut receiver| async {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(event) => return Some((event, receiver)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        }
+                    }
+                });
+                Ok(events.boxed())
+            }
+            Transport::Nats(client) => {
+                let subscriber = client
+                    .subscribe(format!("{}.>", self.prefix))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(subscriber
+                    .filter_map(|message| async move {
+                        serde_json::from_slice(&message.payload)
+                            .inspect_err(|e| {
+                                tracing::warn!("Unreadable event on {}: {}", message.subject, e)
+                            })
+                            .ok()
+                    })
+                    .boxed())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_parse_backend() {
+        assert_eq!("local".parse(), Ok(BusBackend::Local));
+        assert_eq!(" NATS ".parse(), Ok(BusBackend::Nats));
+        assert!("rabbitmq".parse::<BusBackend>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_subscribers_receive_published_events() {
+        let bus = EventBus::local();
+        let mut events = bus.subscribe().await.unwrap();
+        let event = OutboxEvent {
+            id: Uuid::new_v4(),
+            service: "attendance".to_string(),
+            kind: "user_checked_in".to_string(),
+            payload: json!({"username": "sana"}),
+            created_at: Utc::now(),
+            attempts: 0,
+            delivered_to: Vec::new(),
+        };
+
+        assert_eq!(bus.subject(&event), "tabrela.attendance.user_checked_in");
+        bus.publish(&event).await.unwrap();
+        let received = events.next().await.unwrap();
+        assert_eq!(received.id, event.id);
+        assert_eq!(received.payload, event.payload);
+    }
+}
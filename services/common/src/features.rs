@@ -0,0 +1,294 @@
+//! Feature flags for subsystems that are shipped before every deployment
+//! wants them. `FEATURES` sets which are on for a deployment; admins can
+//! then turn each on or off for their organisation at runtime through the
+//! auth service, which stores the override in the shared `feature_flags`
+//! table so every service and replica sees it. Frontends read the flags in
+//! effect from the auth service's `GET /features`.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::{collections::BTreeMap, collections::BTreeSet, fmt, str::FromStr};
+use uuid::Uuid;
+
+use crate::config::{ConfigVar, EnvReader};
+use crate::error::{api_error, ApiError};
+
+/// Environment variables read by [`FeatureSettings::read`]
+pub const FEATURES_SCHEMA: &[ConfigVar] = &[ConfigVar::default(
+    "FEATURES",
+    "payments,webhooks,public_tab",
+    "Comma-separated features on for this deployment (payments, webhooks, public_tab), or none; admins can override each at runtime",
+)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Event fees: setting them, recording payments and paying online
+    Payments,
+    /// Domain events posted to `EVENT_WEBHOOK_URL`
+    Webhooks,
+    /// Released tabs and breaks readable by members and the public
+    PublicTab,
+}
+
+impl Feature {
+    pub const ALL: &'static [Feature] = &[Feature::Payments, Feature::Webhooks, Feature::PublicTab];
+
+    /// Value used in `FEATURES` and stored in `feature_flags.feature`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::Payments => "payments",
+            Feature::Webhooks => "webhooks",
+            Feature::PublicTab => "public_tab",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .iter()
+            .copied()
+            .find(|feature| feature.as_str() == s)
+            .ok_or_else(|| format!("Unknown feature: {}", s))
+    }
+}
+
+/// Features on for the deployment before any runtime override
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureSettings {
+    pub enabled: BTreeSet<Feature>,
+}
+
+impl Default for FeatureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: Feature::ALL.iter().copied().collect(),
+        }
+    }
+}
+
+impl FeatureSettings {
+    /// Read the variables in [`FEATURES_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let raw = reader.string("FEATURES");
+        Self {
+            enabled: reader.check(parse_features(&raw)).unwrap_or_default(),
+        }
+    }
+}
+
+/// Parse a comma-separated `FEATURES` value; `none` turns every feature off
+pub fn parse_features(raw: &str) -> Result<BTreeSet<Feature>, String> {
+    if raw.trim().eq_ignore_ascii_case("none") {
+        return Ok(BTreeSet::new());
+    }
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.to_ascii_lowercase()
+                .parse()
+                .map_err(|e| format!("Invalid FEATURES: {}", e))
+        })
+        .collect()
+}
+
+/// One feature as reported to frontends and admins
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeatureState {
+    pub enabled: bool,
+    /// What the deployment's configuration says
+    pub configured: bool,
+    /// An admin's runtime override, when there is one
+    pub overridden: Option<bool>,
+}
+
+/// Apply runtime overrides to the configured features
+pub fn resolve(
+    settings: &FeatureSettings,
+    overrides: &BTreeMap<Feature, bool>,
+) -> BTreeMap<Feature, FeatureState> {
+    Feature::ALL
+        .iter()
+        .map(|feature| {
+            let configured = settings.enabled.contains(feature);
+            let overridden = overrides.get(feature).copied();
+            let state = FeatureState {
+                enabled: overridden.unwrap_or(configured),
+                configured,
+                overridden,
+            };
+            (*feature, state)
+        })
+        .collect()
+}
+
+/// The flags in effect, read from the configuration and the
+/// `feature_flags` overrides
+#[derive(Clone)]
+pub struct Features {
+    pool: PgPool,
+    settings: FeatureSettings,
+}
+
+impl Features {
+    pub fn new(pool: PgPool, settings: &FeatureSettings) -> Self {
+        Self {
+            pool,
+            settings: settings.clone(),
+        }
+    }
+
+    /// Whether `feature` is on. Falls back to the configuration when the
+    /// overrides cannot be read.
+    pub async fn is_enabled(&self, feature: Feature) -> bool {
+        let overridden: Result<Option<bool>, sqlx::Error> =
+            sqlx::query_scalar("SELECT enabled FROM feature_flags WHERE feature = $1")
+                .bind(feature.as_str())
+                .fetch_optional(&self.pool)
+                .await;
+        match overridden {
+            Ok(overridden) => overridden.unwrap_or(self.settings.enabled.contains(&feature)),
+            Err(e) => {
+                tracing::warn!("Failed to read the {} feature flag: {}", feature, e);
+                self.settings.enabled.contains(&feature)
+            }
+        }
+    }
+
+    /// Every feature with where its state comes from
+    pub async fn all(&self) -> Result<BTreeMap<Feature, FeatureState>, sqlx::Error> {
+        let rows: Vec<(String, bool)> =
+            sqlx::query_as("SELECT feature, enabled FROM feature_flags")
+                .fetch_all(&self.pool)
+                .await?;
+        // Overrides for features since removed are ignored
+        let overrides = rows
+            .into_iter()
+            .filter_map(|(feature, enabled)| Some((feature.parse().ok()?, enabled)))
+            .collect();
+        Ok(resolve(&self.settings, &overrides))
+    }
+
+    /// Override `feature` for the organisation, or go back to the
+    /// configuration when `enabled` is `None`
+    pub async fn set_override(
+        &self,
+        feature: Feature,
+        enabled: Option<bool>,
+        updated_by: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        match enabled {
+            Some(enabled) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO feature_flags (feature, enabled, updated_by)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (feature) DO UPDATE
+                    SET enabled = EXCLUDED.enabled, updated_by = EXCLUDED.updated_by,
+                        updated_at = NOW()
+                    "#,
+                )
+                .bind(feature.as_str())
+                .bind(enabled)
+                .bind(updated_by)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM feature_flags WHERE feature = $1")
+                    .bind(feature.as_str())
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `Ok` when `feature` is on, otherwise the 404 its routes answer with
+    pub async fn require(&self, feature: Feature) -> Result<(), ApiError> {
+        if self.is_enabled(feature).await {
+            Ok(())
+        } else {
+            Err(api_error(
+                StatusCode::NOT_FOUND,
+                format!("The {} feature is not enabled", feature),
+            ))
+        }
+    }
+}
+
+/// Middleware answering 404 while a feature is off, for routes that belong
+/// wholly to it
+pub async fn require_feature(
+    State((features, feature)): State<(Features, Feature)>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    features.require(feature).await?;
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for feature in Feature::ALL {
+            assert_eq!(feature.as_str().parse::<Feature>(), Ok(*feature));
+        }
+        assert!("stripe".parse::<Feature>().is_err());
+    }
+
+    #[test]
+    fn test_parse_features() {
+        assert_eq!(
+            parse_features("payments, PUBLIC_TAB,"),
+            Ok(BTreeSet::from([Feature::Payments, Feature::PublicTab]))
+        );
+        assert_eq!(parse_features("none"), Ok(BTreeSet::new()));
+        assert_eq!(parse_features(""), Ok(BTreeSet::new()));
+        assert_eq!(
+            parse_features("payments,stripe"),
+            Err("Invalid FEATURES: Unknown feature: stripe".to_string())
+        );
+    }
+
+    #[test]
+    fn test_overrides_win_over_configuration() {
+        let settings = FeatureSettings {
+            enabled: BTreeSet::from([Feature::Payments, Feature::Webhooks]),
+        };
+        let overrides = BTreeMap::from([(Feature::Webhooks, false), (Feature::PublicTab, true)]);
+        let flags = resolve(&settings, &overrides);
+
+        assert_eq!(
+            flags[&Feature::Payments],
+            FeatureState {
+                enabled: true,
+                configured: true,
+                overridden: None
+            }
+        );
+        assert!(!flags[&Feature::Webhooks].enabled);
+        assert!(flags[&Feature::PublicTab].enabled);
+        assert!(!flags[&Feature::PublicTab].configured);
+    }
+}
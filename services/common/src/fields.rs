@@ -0,0 +1,197 @@
+//! Sparse fieldsets: a `?fields=` query parameter on heavy endpoints keeps
+//! only the listed fields of the JSON response, so clients on poor
+//! connections download just what they show.
+//!
+//! Fields are dot-separated paths from the top of the response. Arrays are
+//! looked through, and a field keeps everything below it, so
+//! `fields=matches.id,matches.teams,total` keeps each match's id and teams
+//! and the total. Fields the response does not have are ignored.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Query, Request},
+    middleware::Next,
+    response::Response,
+};
+use http::header;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Most fields a request may list; more is almost certainly a mistake
+pub const MAX_FIELDS: usize = 100;
+
+/// Fields kept in a response, as a tree of path segments
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldSelection {
+    /// Keep everything below this point
+    all: bool,
+    children: BTreeMap<String, FieldSelection>,
+}
+
+impl FieldSelection {
+    /// Parse a comma-separated list of paths. `None` when it names no
+    /// fields, meaning the response is sent whole.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut selection = FieldSelection::default();
+        let paths = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .take(MAX_FIELDS);
+        for path in paths {
+            let mut node = &mut selection;
+            for segment in path.split('.').filter(|s| !s.is_empty()) {
+                node = node.children.entry(segment.to_string()).or_default();
+            }
+            node.all = true;
+        }
+
+        if selection.children.is_empty() {
+            None
+        } else {
+            Some(selection)
+        }
+    }
+
+    /// Drop every field of `value` that is not selected
+    pub fn apply(&self, value: &mut Value) {
+        if self.all {
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, _| self.children.contains_key(key));
+                for (key, child) in map.iter_mut() {
+                    self.children[key].apply(child);
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply(item)),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Middleware pruning successful JSON responses to the request's `fields`.
+/// Routes opt in with `get(handler).layer(middleware::from_fn(sparse_fieldsets))`.
+pub async fn sparse_fieldsets(request: Request, next: Next) -> Response {
+    let selection = Query::<FieldsQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|query| query.0.fields)
+        .and_then(|raw| FieldSelection::parse(&raw));
+    let response = next.run(request).await;
+    let Some(selection) = selection else {
+        return response;
+    };
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to read response for field selection: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    selection.apply(&mut value);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Json, Router};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    fn sample() -> Value {
+        json!({
+            "matches": [
+                {"id": 1, "motion": "THW", "teams": [{"team_name": "A", "speakers": []}]},
+                {"id": 2, "motion": "THBT", "teams": []}
+            ],
+            "total": 2,
+            "page": 1
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(FieldSelection::parse(""), None);
+        assert_eq!(FieldSelection::parse(" , ."), None);
+        let selection = FieldSelection::parse("matches.id, total").unwrap();
+        assert!(selection.children["total"].all);
+        assert!(selection.children["matches"].children["id"].all);
+        assert!(!selection.children["matches"].all);
+    }
+
+    #[test]
+    fn test_apply_looks_through_arrays() {
+        let mut value = sample();
+        FieldSelection::parse("matches.id,matches.teams.team_name,total,missing")
+            .unwrap()
+            .apply(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "matches": [
+                    {"id": 1, "teams": [{"team_name": "A"}]},
+                    {"id": 2, "teams": []}
+                ],
+                "total": 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_field_keeps_everything_below_it() {
+        let mut value = sample();
+        FieldSelection::parse("matches,matches.id")
+            .unwrap()
+            .apply(&mut value);
+        assert_eq!(value["matches"], sample()["matches"]);
+        assert!(value.get("total").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_prunes_json_responses() {
+        let app = Router::new().route(
+            "/matches",
+            get(|| async { Json(sample()) }).layer(middleware::from_fn(sparse_fieldsets)),
+        );
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(request("/matches?fields=total&page=3"))
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).unwrap(),
+            json!({"total": 2})
+        );
+
+        let response = app.oneshot(request("/matches")).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap(), sample());
+    }
+}
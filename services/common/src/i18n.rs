@@ -0,0 +1,205 @@
+//! Translations of user-facing text: validation errors, emails and the
+//! like. Clients say which language they want with `Accept-Language`;
+//! anything not translated into it falls back to English.
+//!
+//! Messages live in `locales/<code>.json`, one flat object of keys to
+//! text per language, compiled into the binary. Text can name arguments in
+//! braces, such as `{username}`, filled in by [`Locale::format`].
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, VARY},
+        request::Parts,
+        HeaderMap, HeaderValue,
+    },
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::{collections::HashMap, convert::Infallible, fmt, str::FromStr, sync::OnceLock};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    En,
+    Ur,
+}
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::En, Locale::Ur];
+
+    /// BCP 47 language code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ur => "ur",
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Locale::default()
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.json"),
+            Locale::Ur => include_str!("../locales/ur.json"),
+        }
+    }
+
+    /// The message for `key`, in English when this language lacks it, or
+    /// the key itself when no language has it
+    pub fn text<'a>(&self, key: &'a str) -> &'a str {
+        let catalogs = catalogs();
+        catalogs[self]
+            .get(key)
+            .or_else(|| catalogs[&Locale::En].get(key))
+            .map(String::as_str)
+            .unwrap_or_else(|| {
+                tracing::warn!("No translation for {}", key);
+                key
+            })
+    }
+
+    /// The message for `key` with each `{name}` replaced by its argument
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.text(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), value)
+            })
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts a language code with or without a region (`ur-PK`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or_default();
+        Locale::ALL
+            .iter()
+            .copied()
+            .find(|locale| locale.as_str().eq_ignore_ascii_case(language))
+            .ok_or_else(|| format!("Unsupported language: {}", s))
+    }
+}
+
+fn catalogs() -> &'static HashMap<Locale, HashMap<String, String>> {
+    static CATALOGS: OnceLock<HashMap<Locale, HashMap<String, String>>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        Locale::ALL
+            .iter()
+            .map(|locale| {
+                let messages = serde_json::from_str(locale.source())
+                    .unwrap_or_else(|e| panic!("locales/{}.json is invalid: {}", locale, e));
+                (*locale, messages)
+            })
+            .collect()
+    })
+}
+
+/// The supported language a client prefers most, by the `q` weights of its
+/// `Accept-Language` header; English when it names none we have
+pub fn negotiate(accept_language: &str) -> Locale {
+    let mut best: Option<(Locale, f32)> = None;
+    for range in accept_language.split(',') {
+        let mut parts = range.split(';').map(str::trim);
+        let tag = parts.next().unwrap_or_default();
+        let weight = parts
+            .find_map(|p| p.strip_prefix("q="))
+            .and_then(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        let Ok(locale) = tag.parse::<Locale>() else {
+            continue;
+        };
+        if weight > 0.0 && best.is_none_or(|(_, w)| weight > w) {
+            best = Some((locale, weight));
+        }
+    }
+    best.map(|(locale, _)| locale).unwrap_or_default()
+}
+
+/// The language a request asks for
+pub fn request_locale(headers: &HeaderMap) -> Locale {
+    headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .map(negotiate)
+        .unwrap_or_default()
+}
+
+/// Handlers take a `Locale` argument to answer in the client's language
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Locale>()
+            .copied()
+            .unwrap_or_else(|| request_locale(&parts.headers)))
+    }
+}
+
+/// Middleware negotiating the request's language once and labelling the
+/// response with it, so caches keep each language apart
+pub async fn negotiate_locale(mut request: Request, next: Next) -> Response {
+    let locale = request_locale(request.headers());
+    request.extensions_mut().insert(locale);
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    headers.append(VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("ur-PK,ur;q=0.9,en;q=0.8"), Locale::Ur);
+        assert_eq!(negotiate("en-GB, ur;q=0.5"), Locale::En);
+        assert_eq!(negotiate("fr, ur;q=0.3"), Locale::Ur);
+        assert_eq!(negotiate("fr, de"), Locale::En);
+        assert_eq!(negotiate("ur;q=0, en;q=0.1"), Locale::En);
+        assert_eq!(negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn test_every_language_has_every_message() {
+        let catalogs = catalogs();
+        let english = &catalogs[&Locale::En];
+        for locale in Locale::ALL {
+            let messages = &catalogs[locale];
+            for key in english.keys() {
+                assert!(messages.contains_key(key), "{} lacks {}", locale, key);
+            }
+            for key in messages.keys() {
+                assert!(english.contains_key(key), "{} has unknown {}", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_and_fallback() {
+        assert_eq!(
+            Locale::En.format("validation.field", &[("field", "colour")]),
+            "Invalid value for field 'colour'"
+        );
+        assert_ne!(Locale::Ur.text("validation.generic"), "Validation error");
+        assert_eq!(Locale::Ur.text("no.such.key"), "no.such.key");
+    }
+}
@@ -0,0 +1,173 @@
+//! Minimal iCalendar (RFC 5545) writer for the calendar feeds
+
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+
+/// Longest content line allowed before folding, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// One VEVENT
+#[derive(Debug, Clone)]
+pub struct CalendarEntry {
+    /// Stable across feed refreshes so calendar apps update instead of duplicating
+    pub uid: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub summary: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A VCALENDAR with its entries
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    pub name: String,
+    pub entries: Vec<CalendarEntry>,
+}
+
+impl Calendar {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, entry: CalendarEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        write_line(&mut out, "BEGIN:VCALENDAR");
+        write_line(&mut out, "VERSION:2.0");
+        write_line(&mut out, "PRODID:-//Tabrela//Calendar//EN");
+        write_line(&mut out, "CALSCALE:GREGORIAN");
+        write_line(&mut out, "METHOD:PUBLISH");
+        write_line(&mut out, &format!("X-WR-CALNAME:{}", escape(&self.name)));
+
+        let now = Utc::now();
+        for entry in &self.entries {
+            write_line(&mut out, "BEGIN:VEVENT");
+            write_line(&mut out, &format!("UID:{}", entry.uid));
+            write_line(&mut out, &format!("DTSTAMP:{}", timestamp(now)));
+            write_line(&mut out, &format!("DTSTART:{}", timestamp(entry.start)));
+            write_line(&mut out, &format!("DTEND:{}", timestamp(entry.end)));
+            write_line(
+                &mut out,
+                &format!("LAST-MODIFIED:{}", timestamp(entry.updated_at)),
+            );
+            write_line(&mut out, &format!("SUMMARY:{}", escape(&entry.summary)));
+            if let Some(description) = &entry.description {
+                write_line(&mut out, &format!("DESCRIPTION:{}", escape(description)));
+            }
+            if let Some(location) = &entry.location {
+                write_line(&mut out, &format!("LOCATION:{}", escape(location)));
+            }
+            write_line(&mut out, "END:VEVENT");
+        }
+
+        write_line(&mut out, "END:VCALENDAR");
+        out
+    }
+}
+
+impl IntoResponse for Calendar {
+    fn into_response(self) -> Response {
+        (
+            [
+                (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+                (header::CACHE_CONTROL, "private, max-age=300"),
+            ],
+            self.render(),
+        )
+            .into_response()
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append a content line, folding it at 75 octets without splitting a
+/// UTF-8 character
+fn write_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry() -> CalendarEntry {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 14, 0, 0).unwrap();
+        CalendarEntry {
+            uid: "event-1@tabrela".to_string(),
+            start,
+            end: start + chrono::Duration::hours(2),
+            summary: "Round 1; Room A, upstairs".to_string(),
+            description: Some("Bring\nballots".to_string()),
+            location: None,
+            updated_at: start,
+        }
+    }
+
+    #[test]
+    fn test_render_entry() {
+        let mut calendar = Calendar::new("Tabrela");
+        calendar.push(entry());
+        let rendered = calendar.render();
+
+        assert!(rendered.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(rendered.ends_with("END:VCALENDAR\r\n"));
+        assert!(rendered.contains("\r\nDTSTART:20250301T140000Z\r\n"));
+        assert!(rendered.contains("\r\nDTEND:20250301T160000Z\r\n"));
+        assert!(rendered.contains("\r\nSUMMARY:Round 1\\; Room A\\, upstairs\r\n"));
+        assert!(rendered.contains("\r\nDESCRIPTION:Bring\\nballots\r\n"));
+        assert!(!rendered.contains("LOCATION"));
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut out = String::new();
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        write_line(&mut out, &line);
+
+        for physical in out.trim_end_matches("\r\n").split("\r\n") {
+            assert!(physical.len() <= MAX_LINE_OCTETS);
+        }
+        let unfolded = out.trim_end_matches("\r\n").replace("\r\n ", "");
+        assert_eq!(unfolded, line);
+    }
+}
@@ -0,0 +1,302 @@
+//! Short-lived shared state: rate limit counters, cached responses and
+//! cached lookups. Without `REDIS_URL` it is kept in process memory, which
+//! is only correct while a service runs as a single instance; with Redis,
+//! every replica of a service sees the same counters and cache.
+//!
+//! Every key expires. When Redis cannot be reached, operations fail and
+//! callers carry on without the store (limits are not enforced, caches
+//! miss) rather than failing the request.
+
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+use crate::config::{ConfigVar, EnvReader};
+
+/// Environment variables read by [`KvSettings::read`]
+pub const KV_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::optional(
+        "REDIS_URL",
+        "Redis for rate limits and caches shared between replicas (e.g. redis://redis:6379); kept in memory when unset",
+    ),
+    ConfigVar::default(
+        "REDIS_KEY_PREFIX",
+        "tabrela",
+        "Prefix of every Redis key, to share a Redis between deployments",
+    ),
+];
+
+/// How long a Redis connection attempt or command may take
+const REDIS_TIMEOUT: Duration = Duration::from_secs(2);
+/// Memory entries held before expired ones are swept out
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KvSettings {
+    pub redis_url: Option<String>,
+    pub key_prefix: String,
+}
+
+impl Default for KvSettings {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            key_prefix: "tabrela".to_string(),
+        }
+    }
+}
+
+impl KvSettings {
+    /// Read the variables in [`KV_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let redis_url = reader.optional("REDIS_URL");
+        if let Some(url) = &redis_url {
+            reader.check(
+                redis::Client::open(url.as_str())
+                    .map(|_| ())
+                    .map_err(|e| format!("Invalid REDIS_URL: {}", e)),
+            );
+        }
+
+        Self {
+            redis_url,
+            key_prefix: reader.string("REDIS_KEY_PREFIX"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KvError(String);
+
+impl fmt::Display for KvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Shared state unavailable: {}", self.0)
+    }
+}
+
+impl std::error::Error for KvError {}
+
+impl From<redis::RedisError> for KvError {
+    fn from(e: redis::RedisError) -> Self {
+        KvError(e.to_string())
+    }
+}
+
+struct Entry {
+    value: String,
+    expires_at: Instant,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Memory(Arc<Mutex<HashMap<String, Entry>>>),
+    /// Connected on first use, so a Redis that is down does not stop the
+    /// service starting
+    Redis {
+        client: redis::Client,
+        connection: Arc<OnceCell<ConnectionManager>>,
+    },
+}
+
+#[derive(Clone)]
+pub struct KvStore {
+    backend: Backend,
+    prefix: String,
+}
+
+impl KvStore {
+    pub fn new(settings: &KvSettings) -> Self {
+        let backend = match settings
+            .redis_url
+            .as_deref()
+            .map(redis::Client::open)
+            .transpose()
+        {
+            Ok(Some(client)) => Backend::Redis {
+                client,
+                connection: Arc::new(OnceCell::new()),
+            },
+            // Checked when the settings were read
+            Ok(None) | Err(_) => Backend::Memory(Arc::default()),
+        };
+        Self {
+            backend,
+            prefix: settings.key_prefix.clone(),
+        }
+    }
+
+    /// A store kept in process memory
+    pub fn memory() -> Self {
+        Self::new(&KvSettings::default())
+    }
+
+    /// Whether other instances of the service see the same state
+    pub fn is_shared(&self) -> bool {
+        matches!(self.backend, Backend::Redis { .. })
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}:{}", self.prefix, key)
+    }
+
+    async fn redis(
+        client: &redis::Client,
+        connection: &OnceCell<ConnectionManager>,
+    ) -> Result<ConnectionManager, KvError> {
+        let config = ConnectionManagerConfig::new()
+            .set_connection_timeout(REDIS_TIMEOUT)
+            .set_response_timeout(REDIS_TIMEOUT)
+            .set_number_of_retries(1);
+        let manager = connection
+            .get_or_try_init(|| client.get_connection_manager_with_config(config))
+            .await?;
+        Ok(manager.clone())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<String>, KvError> {
+        let key = self.key(key);
+        match &self.backend {
+            Backend::Memory(entries) => {
+                let entries = entries.lock().expect("kv lock poisoned");
+                Ok(entries
+                    .get(&key)
+                    .filter(|entry| entry.expires_at > Instant::now())
+                    .map(|entry| entry.value.clone()))
+            }
+            Backend::Redis { client, connection } => {
+                let mut redis = Self::redis(client, connection).await?;
+                Ok(redis::cmd("GET").arg(key).query_async(&mut redis).await?)
+            }
+        }
+    }
+
+    /// Store `value` under `key` for `ttl`
+    pub async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), KvError> {
+        let key = self.key(key);
+        match &self.backend {
+            Backend::Memory(entries) => {
+                let mut entries = entries.lock().expect("kv lock poisoned");
+                sweep(&mut entries);
+                entries.insert(
+                    key,
+                    Entry {
+                        value: value.to_string(),
+                        expires_at: Instant::now() + ttl,
+                    },
+                );
+                Ok(())
+            }
+            Backend::Redis { client, connection } => {
+                let mut redis = Self::redis(client, connection).await?;
+                redis::cmd("SET")
+                    .arg(key)
+                    .arg(value)
+                    .arg("PX")
+                    .arg(ttl.as_millis().max(1) as u64)
+                    .query_async::<()>(&mut redis)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Add one to the counter at `key` and return the new count. A counter
+    /// that does not exist yet starts at zero and expires after `ttl`.
+    pub async fn incr(&self, key: &str, ttl: Duration) -> Result<u64, KvError> {
+        let key = self.key(key);
+        match &self.backend {
+            Backend::Memory(entries) => {
+                let mut entries = entries.lock().expect("kv lock poisoned");
+                sweep(&mut entries);
+                let now = Instant::now();
+                let entry = entries
+                    .entry(key)
+                    .and_modify(|entry| {
+                        if entry.expires_at <= now {
+                            *entry = Entry {
+                                value: "0".to_string(),
+                                expires_at: now + ttl,
+                            };
+                        }
+                    })
+                    .or_insert_with(|| Entry {
+                        value: "0".to_string(),
+                        expires_at: now + ttl,
+                    });
+                let count = entry.value.parse::<u64>().unwrap_or(0) + 1;
+                entry.value = count.to_string();
+                Ok(count)
+            }
+            Backend::Redis { client, connection } => {
+                let mut redis = Self::redis(client, connection).await?;
+                let (count,): (u64,) = redis::pipe()
+                    .atomic()
+                    .cmd("SET")
+                    .arg(&key)
+                    .arg(0)
+                    .arg("PX")
+                    .arg(ttl.as_millis().max(1) as u64)
+                    .arg("NX")
+                    .ignore()
+                    .cmd("INCR")
+                    .arg(&key)
+                    .query_async(&mut redis)
+                    .await?;
+                Ok(count)
+            }
+        }
+    }
+}
+
+/// Drop expired entries once the map has grown large
+fn sweep(entries: &mut HashMap<String, Entry>) {
+    if entries.len() >= SWEEP_THRESHOLD {
+        let now = Instant::now();
+        entries.retain(|_, entry| entry.expires_at > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_values_expire() {
+        let store = KvStore::memory();
+        assert!(!store.is_shared());
+        store
+            .set("admin:abc", "true", Duration::from_millis(20))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("admin:abc").await.unwrap().as_deref(),
+            Some("true")
+        );
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(store.get("admin:abc").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_memory_counters_restart_after_ttl() {
+        let store = KvStore::memory();
+        let ttl = Duration::from_millis(20);
+        assert_eq!(store.incr("hits", ttl).await.unwrap(), 1);
+        assert_eq!(store.incr("hits", ttl).await.unwrap(), 2);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(store.incr("hits", ttl).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_fails_instead_of_hanging() {
+        let store = KvStore::new(&KvSettings {
+            redis_url: Some("redis://127.0.0.1:1".to_string()),
+            ..Default::default()
+        });
+        assert!(store.is_shared());
+        assert!(store.get("anything").await.is_err());
+    }
+}
@@ -0,0 +1,115 @@
+//! Background jobs that must run on one instance at a time. When a service
+//! runs as several replicas, each job is led by whichever replica holds its
+//! Postgres advisory lock; the others keep trying each period and take over
+//! once the leader stops or loses its database connection, which releases
+//! the lock.
+//!
+//! The lock is held on a connection of its own rather than one borrowed
+//! from the pool, so leading a job does not take a connection away from
+//! requests.
+
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, PgConnection, PgPool};
+use std::future::Future;
+use std::time::Duration;
+
+/// How long the leader's connection may take to answer before the lock is
+/// given up
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The advisory lock key for `job`. Keys are namespaced so they do not
+/// collide with locks taken for other reasons.
+pub fn lock_key(job: &str) -> i64 {
+    let digest = Sha256::digest(format!("tabrela:job:{}", job).as_bytes());
+    let mut key = [0u8; 8];
+    key.copy_from_slice(&digest[..8]);
+    i64::from_be_bytes(key)
+}
+
+/// One instance's claim on leading a job
+pub struct Leadership {
+    pool: PgPool,
+    job: &'static str,
+    /// Connection holding the lock while this instance leads
+    connection: Option<PgConnection>,
+}
+
+impl Leadership {
+    pub fn new(pool: PgPool, job: &'static str) -> Self {
+        Self {
+            pool,
+            job,
+            connection: None,
+        }
+    }
+
+    /// Whether this instance leads the job, trying to take it over when
+    /// it does not
+    pub async fn is_leader(&mut self) -> bool {
+        if let Some(connection) = &mut self.connection {
+            let check = sqlx::query("SELECT 1").execute(&mut *connection);
+            if matches!(tokio::time::timeout(CHECK_TIMEOUT, check).await, Ok(Ok(_))) {
+                return true;
+            }
+            tracing::warn!(
+                "Lost the lock on {}; another instance may take over",
+                self.job
+            );
+            self.connection = None;
+        }
+
+        let mut connection = match PgConnection::connect_with(&self.pool.connect_options()).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Could not connect to lock {}: {}", self.job, e);
+                return false;
+            }
+        };
+        let locked = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+            .bind(lock_key(self.job))
+            .fetch_one(&mut connection)
+            .await;
+        match locked {
+            Ok(true) => {
+                tracing::info!("Running {} on this instance", self.job);
+                self.connection = Some(connection);
+                true
+            }
+            // Led elsewhere; dropping the connection is all there is to do
+            Ok(false) => false,
+            Err(e) => {
+                tracing::warn!("Could not lock {}: {}", self.job, e);
+                false
+            }
+        }
+    }
+}
+
+/// Run `job` every `period` on whichever instance leads it
+pub fn spawn<F, Fut>(pool: PgPool, name: &'static str, period: Duration, mut job: F)
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    tokio::spawn(async move {
+        let mut leadership = Leadership::new(pool, name);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if leadership.is_leader().await {
+                job().await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_keys_are_stable_and_distinct() {
+        assert_eq!(lock_key("merit.decay"), lock_key("merit.decay"));
+        assert_ne!(lock_key("merit.decay"), lock_key("attendance.reminders"));
+    }
+}
@@ -0,0 +1,66 @@
+//! Infrastructure shared by every Tabrela service: CORS setup, response
+//! compression, request limits, JWT middleware, config parsing helpers,
+//! pagination, rate limits, Redis-backed shared state, single-instance
+//! background jobs, maintenance mode, two-person confirmation of
+//! destructive admin actions, log redaction, chat notifications, a
+//! transactional outbox and event bus, iCalendar feeds, PDF reports, SMTP
+//! settings, file storage, read replicas, startup errors, user roles,
+//! notification preferences, feature flags, translations, season filters,
+//! admin stats shapes, API versioning, sparse fieldsets, ETags and JSON
+//! error plumbing.
+
+pub mod api_keys;
+pub mod auth_middleware;
+pub mod compression;
+pub mod config;
+pub mod cors;
+pub mod csrf;
+pub mod error;
+pub mod etag;
+pub mod event_bus;
+pub mod features;
+pub mod fields;
+pub mod i18n;
+pub mod ics;
+pub mod kv;
+pub mod leader;
+pub mod limits;
+pub mod mail;
+pub mod maintenance;
+pub mod notify;
+pub mod outbox;
+pub mod pagination;
+pub mod pdf;
+pub mod pending_actions;
+pub mod preferences;
+pub mod rate_limit;
+pub mod redact;
+pub mod replica;
+pub mod roles;
+pub mod season;
+pub mod startup;
+pub mod stats;
+pub mod storage;
+pub mod versioning;
+
+pub use auth_middleware::AuthState;
+pub use compression::CompressionSettings;
+pub use cors::{configure_cors, CorsSettings};
+pub use error::ApiError;
+pub use event_bus::EventBus;
+pub use features::{Feature, Features};
+pub use i18n::Locale;
+pub use ics::{Calendar, CalendarEntry};
+pub use kv::KvStore;
+pub use limits::RequestLimits;
+pub use maintenance::Maintenance;
+pub use notify::{Notification, NotificationKind, Notifier};
+pub use outbox::OutboxSettings;
+pub use pagination::Pagination;
+pub use pending_actions::PendingActions;
+pub use replica::ReadReplica;
+pub use roles::Role;
+pub use startup::StartupError;
+pub use stats::PeriodCount;
+pub use storage::Storage;
+pub use versioning::Versions;
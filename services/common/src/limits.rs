@@ -0,0 +1,177 @@
+//! Limits on how much a single request may cost. Bodies are capped so an
+//! oversized or malformed upload is cut off once it passes the limit rather
+//! than read to the end, and a request still running after the timeout is
+//! dropped. Both answer with the usual `{"error": ...}` body.
+//!
+//! The body limit applies to every route that reads a body; routes that take
+//! uploads or bulk imports raise it with their own `DefaultBodyLimit` layer.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{header, StatusCode};
+use std::time::Duration;
+
+use crate::config::{ConfigVar, EnvReader};
+use crate::error::api_error;
+
+/// Environment variables read by [`RequestLimits::read`]
+pub const LIMITS_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "REQUEST_BODY_LIMIT_BYTES",
+        "1048576",
+        "Largest request body most routes accept",
+    ),
+    ConfigVar::default(
+        "BULK_BODY_LIMIT_BYTES",
+        "10485760",
+        "Largest request body accepted by bulk imports",
+    ),
+    ConfigVar::default(
+        "REQUEST_TIMEOUT_SECS",
+        "60",
+        "Seconds a request may take, uploads included, before it is dropped with a 408",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLimits {
+    pub body_bytes: usize,
+    pub bulk_body_bytes: usize,
+    pub timeout: Duration,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            body_bytes: 1024 * 1024,
+            bulk_body_bytes: 10 * 1024 * 1024,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Read the variables in [`LIMITS_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let limits = Self {
+            body_bytes: reader.parse("REQUEST_BODY_LIMIT_BYTES"),
+            bulk_body_bytes: reader.parse("BULK_BODY_LIMIT_BYTES"),
+            timeout: Duration::from_secs(reader.parse("REQUEST_TIMEOUT_SECS")),
+        };
+        if limits.timeout.is_zero() {
+            reader.check::<(), _>(Err("REQUEST_TIMEOUT_SECS must be at least 1"));
+        }
+        limits
+    }
+}
+
+/// Middleware dropping requests that outlast the timeout with a 408, and
+/// giving the plain-text 413 axum sends for an oversized body the JSON
+/// error shape. Services add it outside their routes together with
+/// `DefaultBodyLimit::max(limits.body_bytes)`.
+pub async fn enforce_limits(
+    State(limits): State<RequestLimits>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let Ok(response) = tokio::time::timeout(limits.timeout, next.run(request)).await else {
+        tracing::warn!("{} {} timed out after {:?}", method, path, limits.timeout);
+        return api_error(StatusCode::REQUEST_TIMEOUT, "Request took too long").into_response();
+    };
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return api_error(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large")
+            .into_response();
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::DefaultBodyLimit,
+        middleware,
+        routing::post,
+        Json, Router,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let limits = RequestLimits {
+            body_bytes: 64,
+            bulk_body_bytes: 1024,
+            timeout: Duration::from_millis(50),
+        };
+        Router::new()
+            .route(
+                "/ballots",
+                post(|Json(body): Json<Value>| async { Json(body) }),
+            )
+            .route(
+                "/bulk",
+                post(|body: String| async move { body.len().to_string() })
+                    .layer(DefaultBodyLimit::max(limits.bulk_body_bytes)),
+            )
+            .route(
+                "/slow",
+                post(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "done"
+                }),
+            )
+            .layer(DefaultBodyLimit::max(limits.body_bytes))
+            .layer(middleware::from_fn_with_state(limits, enforce_limits))
+    }
+
+    async fn send(uri: &str, body: String) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_get_a_json_413() {
+        let (status, _) = send("/ballots", json!({"rank": 1}).to_string()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let big = json!({"comments": "x".repeat(100)}).to_string();
+        let (status, body) = send("/ballots", big.clone()).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            serde_json::from_str::<Value>(&body).unwrap(),
+            json!({"error": "Request body is too large"})
+        );
+
+        // Routes may raise the limit for themselves
+        let (status, _) = send("/bulk", big).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_slow_requests_time_out() {
+        let (status, body) = send("/slow", String::new()).await;
+        assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+        assert!(body.contains("Request took too long"));
+    }
+}
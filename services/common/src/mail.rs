@@ -0,0 +1,168 @@
+//! SMTP relay settings for services that send email themselves
+
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    Tokio1Executor,
+};
+use std::str::FromStr;
+
+use crate::config::{ConfigVar, EnvReader};
+
+pub const SMTP_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::optional(
+        "SMTP_HOST",
+        "SMTP relay host (required when emails are sent through SMTP)",
+    ),
+    ConfigVar::default("SMTP_PORT", "587", "SMTP relay port"),
+    ConfigVar::optional("SMTP_USERNAME", "SMTP login"),
+    ConfigVar::optional("SMTP_PASSWORD", "SMTP password"),
+    ConfigVar::default(
+        "SMTP_FROM",
+        "Tabrela <no-reply@tabrela.local>",
+        "Sender address for SMTP emails",
+    ),
+    ConfigVar::default(
+        "SMTP_TLS",
+        "starttls",
+        "SMTP connection security: starttls, tls or none",
+    ),
+];
+
+/// How to secure the SMTP connection (`SMTP_TLS`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    #[default]
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+    /// No encryption; only for local catch-all servers such as MailHog
+    None,
+}
+
+impl FromStr for SmtpTls {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::StartTls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            other => Err(format!(
+                "Invalid SMTP_TLS '{}': expected starttls, tls or none",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpSettings {
+    pub host: Option<String>,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub tls: SmtpTls,
+}
+
+impl SmtpSettings {
+    /// Read `SMTP_SCHEMA` variables, recording problems on `env`. Services
+    /// check `host` themselves, as only their SMTP backend needs it.
+    pub fn read(env: &mut EnvReader) -> Self {
+        let host = env.optional("SMTP_HOST");
+
+        let from = env.string("SMTP_FROM");
+        if from.parse::<Mailbox>().is_err() {
+            env.check::<(), _>(Err(format!(
+                "Invalid SMTP_FROM: '{}' is not an email address",
+                from
+            )));
+        }
+
+        let tls = env.string("SMTP_TLS");
+        let tls = env.check(tls.parse::<SmtpTls>()).unwrap_or_default();
+
+        Self {
+            host,
+            port: env.parse("SMTP_PORT"),
+            username: env.optional("SMTP_USERNAME"),
+            password: env.optional("SMTP_PASSWORD"),
+            from,
+            tls,
+        }
+    }
+
+    /// Connection pool to the relay, failing when no host is set
+    pub fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+        let host = self.host.as_deref().ok_or("SMTP_HOST is not set")?;
+
+        let mut builder = match self.tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| format!("Invalid SMTP_HOST '{}': {}", host, e))?
+        .port(self.port);
+
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(builder.build())
+    }
+
+    /// The `SMTP_FROM` address
+    pub fn sender(&self) -> Result<Mailbox, String> {
+        self.from
+            .parse()
+            .map_err(|e| format!("Invalid SMTP_FROM '{}': {}", self.from, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> SmtpSettings {
+        SmtpSettings {
+            host: None,
+            port: 587,
+            username: None,
+            password: None,
+            from: "Tabrela <no-reply@example.com>".to_string(),
+            tls: SmtpTls::StartTls,
+        }
+    }
+
+    #[test]
+    fn test_tls_from_str() {
+        assert_eq!("starttls".parse::<SmtpTls>(), Ok(SmtpTls::StartTls));
+        assert_eq!(" TLS ".parse::<SmtpTls>(), Ok(SmtpTls::Tls));
+        assert_eq!("none".parse::<SmtpTls>(), Ok(SmtpTls::None));
+        assert!("ssl".parse::<SmtpTls>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transport_requires_host() {
+        let mut settings = settings();
+        assert!(settings.transport().is_err());
+
+        settings.host = Some("smtp.example.com".to_string());
+        assert!(settings.transport().is_ok());
+    }
+
+    #[test]
+    fn test_sender() {
+        let mut settings = settings();
+        assert_eq!(
+            settings.sender().unwrap().email.to_string(),
+            "no-reply@example.com"
+        );
+
+        settings.from = "not an address".to_string();
+        assert!(settings.sender().is_err());
+    }
+}
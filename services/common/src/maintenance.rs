@@ -0,0 +1,296 @@
+//! Maintenance mode, for data fixes that must not race with members'
+//! changes (mid-tournament repairs, for one). Admins start it for one
+//! service or for all of them through the auth service; while it is on,
+//! every other request gets a 503 with `Retry-After` and the admins'
+//! message, and admins carry on as usual.
+//!
+//! The switch lives in the shared `maintenance_modes` table so every
+//! replica agrees. Each instance rereads it every few seconds rather than
+//! on every request.
+
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use crate::auth_middleware::{decode_access_token, request_token, AuthState};
+
+/// Scope that puts every service into maintenance
+pub const ALL_SERVICES: &str = "all";
+
+/// Services that can be put into maintenance on their own
+pub const SERVICES: &[&str] = &["auth", "attendance", "merit", "tabulation"];
+
+/// `Retry-After` sent when admins have not said when they expect to finish
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 300;
+
+/// How long an instance trusts what it last read from the database
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A maintenance window as stored in `maintenance_modes`
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct MaintenanceWindow {
+    /// `all`, or the one service it covers
+    pub scope: String,
+    pub message: String,
+    /// When admins expect to be done, for `Retry-After`
+    pub expected_end: Option<DateTime<Utc>>,
+    pub started_by: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Whether `scope` names something that can be put into maintenance
+pub fn is_valid_scope(scope: &str) -> bool {
+    scope == ALL_SERVICES || SERVICES.contains(&scope)
+}
+
+/// Seconds a client should wait before trying again
+pub fn retry_after_secs(expected_end: Option<DateTime<Utc>>, now: DateTime<Utc>) -> u64 {
+    match expected_end {
+        Some(end) => (end - now).num_seconds().max(1) as u64,
+        None => DEFAULT_RETRY_AFTER_SECS,
+    }
+}
+
+/// What an instance last read, and when
+type Cached = Option<(Instant, Option<MaintenanceWindow>)>;
+
+/// One service's view of maintenance mode
+#[derive(Clone)]
+pub struct Maintenance {
+    pool: PgPool,
+    service: &'static str,
+    /// Paths (without the version prefix) that stay open to everyone, so
+    /// admins can still sign in
+    exempt: &'static [&'static str],
+    cached: Arc<Mutex<Cached>>,
+}
+
+impl Maintenance {
+    pub fn new(pool: PgPool, service: &'static str) -> Self {
+        Self {
+            pool,
+            service,
+            exempt: &[],
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Keep `paths` open to non-admins during maintenance
+    pub fn exempt(mut self, paths: &'static [&'static str]) -> Self {
+        self.exempt = paths;
+        self
+    }
+
+    /// The window covering this service, if any. A global window wins over
+    /// a service's own, so its message is the one shown.
+    pub async fn current(&self) -> Option<MaintenanceWindow> {
+        if let Some((read_at, window)) = self
+            .cached
+            .lock()
+            .expect("maintenance lock poisoned")
+            .as_ref()
+        {
+            if read_at.elapsed() < REFRESH_INTERVAL {
+                return window.clone();
+            }
+        }
+
+        let window = match self.read().await {
+            Ok(window) => window,
+            Err(e) => {
+                // Keep the last known state rather than flapping
+                tracing::warn!("Failed to read maintenance mode: {}", e);
+                return self
+                    .cached
+                    .lock()
+                    .expect("maintenance lock poisoned")
+                    .as_ref()
+                    .and_then(|(_, window)| window.clone());
+            }
+        };
+        *self.cached.lock().expect("maintenance lock poisoned") =
+            Some((Instant::now(), window.clone()));
+        window
+    }
+
+    async fn read(&self) -> Result<Option<MaintenanceWindow>, sqlx::Error> {
+        sqlx::query_as::<_, MaintenanceWindow>(
+            r#"
+            SELECT scope, message, expected_end, started_by, started_at
+            FROM maintenance_modes
+            WHERE scope = $1 OR scope = $2
+            ORDER BY scope = $1 DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(ALL_SERVICES)
+        .bind(self.service)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Every window in force
+    pub async fn list(&self) -> Result<Vec<MaintenanceWindow>, sqlx::Error> {
+        sqlx::query_as::<_, MaintenanceWindow>(
+            r#"
+            SELECT scope, message, expected_end, started_by, started_at
+            FROM maintenance_modes
+            ORDER BY started_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Start maintenance for `scope`, or change the message of a window
+    /// already in force
+    pub async fn start(
+        &self,
+        scope: &str,
+        message: &str,
+        expected_end: Option<DateTime<Utc>>,
+        started_by: Uuid,
+    ) -> Result<MaintenanceWindow, sqlx::Error> {
+        let window = sqlx::query_as::<_, MaintenanceWindow>(
+            r#"
+            INSERT INTO maintenance_modes (scope, message, expected_end, started_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (scope) DO UPDATE
+            SET message = EXCLUDED.message, expected_end = EXCLUDED.expected_end
+            RETURNING scope, message, expected_end, started_by, started_at
+            "#,
+        )
+        .bind(scope)
+        .bind(message)
+        .bind(expected_end)
+        .bind(started_by)
+        .fetch_one(&self.pool)
+        .await?;
+        self.forget();
+        Ok(window)
+    }
+
+    /// End maintenance for `scope`, returning whether it was on
+    pub async fn end(&self, scope: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM maintenance_modes WHERE scope = $1")
+            .bind(scope)
+            .execute(&self.pool)
+            .await?;
+        self.forget();
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Drop the cached state so this instance sees a change at once
+    fn forget(&self) {
+        *self.cached.lock().expect("maintenance lock poisoned") = None;
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        let path = path
+            .strip_prefix(&format!("/{}", crate::versioning::CURRENT_VERSION))
+            .unwrap_or(path);
+        path == "/health" || self.exempt.contains(&path)
+    }
+}
+
+/// 503 for a request turned away during `window`
+pub fn unavailable(window: &MaintenanceWindow) -> Response {
+    let retry_after = retry_after_secs(window.expected_end, Utc::now());
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(RETRY_AFTER, retry_after.to_string())],
+        Json(json!({
+            "error": window.message,
+            "maintenance": true,
+            "expected_end": window.expected_end,
+            "retry_after": retry_after
+        })),
+    )
+        .into_response()
+}
+
+/// Middleware turning away everyone but admins while the service is in
+/// maintenance
+pub async fn maintenance_mode<S: AuthState>(
+    State((state, maintenance)): State<(Arc<S>, Maintenance)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if maintenance.is_exempt(request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Some(window) = maintenance.current().await else {
+        return next.run(request).await;
+    };
+
+    let admin = match request_token(request.headers()) {
+        Ok(token) => match decode_access_token(state.jwt_secret(), token.token) {
+            Ok((user_id, _)) => state
+                .is_admin(user_id, &token.auth_header())
+                .await
+                .unwrap_or(false),
+            Err(_) => false,
+        },
+        Err(_) => false,
+    };
+    if admin {
+        next.run(request).await
+    } else {
+        unavailable(&window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_scopes() {
+        assert!(is_valid_scope("all"));
+        assert!(is_valid_scope("tabulation"));
+        assert!(!is_valid_scope("gateway"));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(retry_after_secs(None, now), DEFAULT_RETRY_AFTER_SECS);
+        assert_eq!(
+            retry_after_secs(Some(now + chrono::Duration::minutes(10)), now),
+            600
+        );
+        // Overrunning admins still get clients to back off a little
+        assert_eq!(
+            retry_after_secs(Some(now - chrono::Duration::minutes(1)), now),
+            1
+        );
+    }
+
+    #[test]
+    fn test_unavailable_response() {
+        let window = MaintenanceWindow {
+            scope: "all".to_string(),
+            message: "Fixing round 3 ballots".to_string(),
+            expected_end: None,
+            started_by: None,
+            started_at: Utc::now(),
+        };
+        let response = unavailable(&window);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[RETRY_AFTER], "300");
+    }
+}
@@ -0,0 +1,470 @@
+//! Chat notifications. Selected events are posted to Discord and Slack
+//! channels, formatted as each platform's native embeds/blocks.
+
+use serde_json::{json, Value};
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::config::{ConfigVar, EnvReader};
+
+pub const NOTIFY_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::optional(
+        "DISCORD_WEBHOOK_URL",
+        "Discord channel webhook that receives notifications",
+    ),
+    ConfigVar::optional(
+        "SLACK_WEBHOOK_URL",
+        "Slack incoming webhook that receives notifications",
+    ),
+    ConfigVar::default(
+        "NOTIFY_EVENTS",
+        "draw_published,results_released,event_reminder,waitlist_promoted",
+        "Events posted to chat: draw_published, results_released, event_reminder, waitlist_promoted, ballot_confirmed, merit_changed, user_checked_in",
+    ),
+];
+
+/// Discord rejects embeds with more fields than this
+const DISCORD_MAX_FIELDS: usize = 25;
+/// Discord rejects embed field values longer than this
+const DISCORD_MAX_FIELD_LEN: usize = 1024;
+/// Slack rejects section blocks with more fields than this
+const SLACK_MAX_SECTION_FIELDS: usize = 10;
+
+// ============================================================================
+// Notifications
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    DrawPublished,
+    ResultsReleased,
+    EventReminder,
+    WaitlistPromoted,
+    BallotConfirmed,
+    MeritChanged,
+    UserCheckedIn,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 7] = [
+        NotificationKind::DrawPublished,
+        NotificationKind::ResultsReleased,
+        NotificationKind::EventReminder,
+        NotificationKind::WaitlistPromoted,
+        NotificationKind::BallotConfirmed,
+        NotificationKind::MeritChanged,
+        NotificationKind::UserCheckedIn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::DrawPublished => "draw_published",
+            NotificationKind::ResultsReleased => "results_released",
+            NotificationKind::EventReminder => "event_reminder",
+            NotificationKind::WaitlistPromoted => "waitlist_promoted",
+            NotificationKind::BallotConfirmed => "ballot_confirmed",
+            NotificationKind::MeritChanged => "merit_changed",
+            NotificationKind::UserCheckedIn => "user_checked_in",
+        }
+    }
+
+    /// Embed accent colour (0xRRGGBB)
+    fn color(&self) -> u32 {
+        match self {
+            NotificationKind::DrawPublished => 0x3B82F6,
+            NotificationKind::ResultsReleased => 0x22C55E,
+            NotificationKind::EventReminder => 0xF59E0B,
+            NotificationKind::WaitlistPromoted => 0x8B5CF6,
+            NotificationKind::BallotConfirmed => 0x14B8A6,
+            NotificationKind::MeritChanged => 0xEAB308,
+            NotificationKind::UserCheckedIn => 0x64748B,
+        }
+    }
+}
+
+impl FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s.trim())
+            .ok_or_else(|| format!("Invalid NOTIFY_EVENTS entry: {}", s.trim()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationField {
+    pub name: String,
+    pub value: String,
+    /// Render side by side with neighbouring inline fields where supported
+    pub inline: bool,
+}
+
+/// A platform-neutral message; each connector decides how to render it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub description: String,
+    pub fields: Vec<NotificationField>,
+}
+
+impl Notification {
+    pub fn new(kind: NotificationKind, title: impl Into<String>) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            description: String::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(NotificationField {
+            name: name.into(),
+            value: value.into(),
+            inline: false,
+        });
+        self
+    }
+
+    pub fn inline_field(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(NotificationField {
+            name: name.into(),
+            value: value.into(),
+            inline: true,
+        });
+        self
+    }
+}
+
+// ============================================================================
+// Connectors
+// ============================================================================
+
+/// A chat platform notifications can be posted to
+pub trait Connector: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn webhook_url(&self) -> &str;
+    /// Request body for the platform's webhook API
+    fn payload(&self, notification: &Notification) -> Value;
+}
+
+pub struct Discord {
+    pub webhook_url: String,
+}
+
+impl Connector for Discord {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn webhook_url(&self) -> &str {
+        &self.webhook_url
+    }
+
+    fn payload(&self, notification: &Notification) -> Value {
+        let fields: Vec<Value> = notification
+            .fields
+            .iter()
+            .take(DISCORD_MAX_FIELDS)
+            .map(|field| {
+                json!({
+                    "name": field.name,
+                    "value": truncate(&field.value, DISCORD_MAX_FIELD_LEN),
+                    "inline": field.inline,
+                })
+            })
+            .collect();
+
+        let mut embed = json!({
+            "title": notification.title,
+            "color": notification.kind.color(),
+            "fields": fields,
+        });
+        if !notification.description.is_empty() {
+            embed["description"] = json!(notification.description);
+        }
+
+        json!({ "embeds": [embed] })
+    }
+}
+
+pub struct Slack {
+    pub webhook_url: String,
+}
+
+impl Connector for Slack {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn webhook_url(&self) -> &str {
+        &self.webhook_url
+    }
+
+    fn payload(&self, notification: &Notification) -> Value {
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": notification.title },
+        })];
+
+        if !notification.description.is_empty() {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": notification.description },
+            }));
+        }
+
+        for chunk in notification.fields.chunks(SLACK_MAX_SECTION_FIELDS) {
+            let fields: Vec<Value> = chunk
+                .iter()
+                .map(|field| {
+                    json!({
+                        "type": "mrkdwn",
+                        "text": format!("*{}*\n{}", field.name, field.value),
+                    })
+                })
+                .collect();
+            blocks.push(json!({ "type": "section", "fields": fields }));
+        }
+
+        // `text` is the fallback shown in push notifications
+        json!({ "text": notification.title, "blocks": blocks })
+    }
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        return value.to_string();
+    }
+    let mut truncated: String = value.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+// ============================================================================
+// Settings and dispatch
+// ============================================================================
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NotificationSettings {
+    pub discord_webhook_url: Option<String>,
+    pub slack_webhook_url: Option<String>,
+    pub events: Vec<NotificationKind>,
+}
+
+impl NotificationSettings {
+    /// Read `NOTIFY_SCHEMA` variables, recording problems on `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let events = reader
+            .string("NOTIFY_EVENTS")
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<NotificationKind>, _>>();
+
+        Self {
+            discord_webhook_url: reader.optional("DISCORD_WEBHOOK_URL"),
+            slack_webhook_url: reader.optional("SLACK_WEBHOOK_URL"),
+            events: reader.check(events).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NotifyError {
+    /// One entry per connector that failed
+    pub failures: Vec<String>,
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Notification failed: {}", self.failures.join("; "))
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+/// Posts notifications to every configured connector
+#[derive(Clone)]
+pub struct Notifier {
+    events: Vec<NotificationKind>,
+    connectors: Arc<Vec<Box<dyn Connector>>>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(settings: &NotificationSettings) -> Self {
+        let mut connectors: Vec<Box<dyn Connector>> = Vec::new();
+        if let Some(url) = &settings.discord_webhook_url {
+            connectors.push(Box::new(Discord {
+                webhook_url: url.clone(),
+            }));
+        }
+        if let Some(url) = &settings.slack_webhook_url {
+            connectors.push(Box::new(Slack {
+                webhook_url: url.clone(),
+            }));
+        }
+
+        Self {
+            events: settings.events.clone(),
+            connectors: Arc::new(connectors),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Whether `kind` would be posted anywhere. Check this before doing any
+    /// work to build a notification.
+    pub fn is_enabled(&self, kind: NotificationKind) -> bool {
+        !self.connectors.is_empty() && self.events.contains(&kind)
+    }
+
+    /// Post in the background so request handlers never wait on chat
+    /// platforms. Failures are logged.
+    pub fn notify(&self, notification: Notification) {
+        if !self.is_enabled(notification.kind) {
+            return;
+        }
+
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.send(&notification).await {
+                tracing::warn!("{}", e);
+            }
+        });
+    }
+
+    /// Post to every connector, reporting each one that failed
+    pub async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut failures = Vec::new();
+
+        for connector in self.connectors.iter() {
+            let result = self
+                .client
+                .post(connector.webhook_url())
+                .json(&connector.payload(notification))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            if let Err(e) = result {
+                failures.push(format!("{}: {}", connector.name(), e));
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(NotifyError { failures })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Notification {
+        Notification::new(NotificationKind::DrawPublished, "Draw published: Round 1")
+            .description("Tabrela Open · Room 101")
+            .inline_field("Opening Government", "alice, bob")
+            .field("Adjudicators", "carol (chair)")
+    }
+
+    #[test]
+    fn test_kind_round_trip() {
+        for kind in NotificationKind::ALL {
+            assert_eq!(kind.as_str().parse::<NotificationKind>(), Ok(kind));
+        }
+        assert!("draw".parse::<NotificationKind>().is_err());
+    }
+
+    #[test]
+    fn test_discord_payload() {
+        let payload = Discord {
+            webhook_url: String::new(),
+        }
+        .payload(&sample());
+
+        let embed = &payload["embeds"][0];
+        assert_eq!(embed["title"], "Draw published: Round 1");
+        assert_eq!(embed["description"], "Tabrela Open · Room 101");
+        assert_eq!(embed["color"], 0x3B82F6);
+        assert_eq!(embed["fields"][0]["name"], "Opening Government");
+        assert_eq!(embed["fields"][0]["inline"], true);
+        assert_eq!(embed["fields"][1]["inline"], false);
+    }
+
+    #[test]
+    fn test_discord_payload_respects_limits() {
+        let mut notification = Notification::new(NotificationKind::ResultsReleased, "Results");
+        for i in 0..30 {
+            notification = notification.field(format!("Field {}", i), "x".repeat(2000));
+        }
+
+        let payload = Discord {
+            webhook_url: String::new(),
+        }
+        .payload(&notification);
+
+        let fields = payload["embeds"][0]["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), DISCORD_MAX_FIELDS);
+        assert_eq!(
+            fields[0]["value"].as_str().unwrap().chars().count(),
+            DISCORD_MAX_FIELD_LEN
+        );
+        assert!(payload["embeds"][0].get("description").is_none());
+    }
+
+    #[test]
+    fn test_slack_payload() {
+        let payload = Slack {
+            webhook_url: String::new(),
+        }
+        .payload(&sample());
+
+        assert_eq!(payload["text"], "Draw published: Round 1");
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[1]["text"]["text"], "Tabrela Open · Room 101");
+        assert_eq!(
+            blocks[2]["fields"][0]["text"],
+            "*Opening Government*\nalice, bob"
+        );
+    }
+
+    #[test]
+    fn test_notifier_enabled_only_with_connectors() {
+        let mut settings = NotificationSettings {
+            events: vec![NotificationKind::DrawPublished],
+            ..Default::default()
+        };
+        assert!(!Notifier::new(&settings).is_enabled(NotificationKind::DrawPublished));
+
+        settings.slack_webhook_url = Some("https://hooks.slack.com/services/T/B/X".to_string());
+        let notifier = Notifier::new(&settings);
+        assert!(notifier.is_enabled(NotificationKind::DrawPublished));
+        assert!(!notifier.is_enabled(NotificationKind::EventReminder));
+    }
+
+    #[test]
+    fn test_settings_reject_unknown_events() {
+        std::env::set_var("NOTIFY_EVENTS", "draw_published, nonsense");
+        let mut reader = EnvReader::new(&[NOTIFY_SCHEMA]);
+        let settings = NotificationSettings::read(&mut reader);
+        std::env::remove_var("NOTIFY_EVENTS");
+
+        assert!(settings.events.is_empty());
+        assert_eq!(
+            reader.finish().unwrap_err().problems,
+            vec!["Invalid NOTIFY_EVENTS entry: nonsense"]
+        );
+    }
+}
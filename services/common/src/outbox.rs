@@ -0,0 +1,432 @@
+//! Transactional outbox. A change that should have side effects outside the
+//! database (a chat post, a webhook to an integration) records an event in
+//! the same transaction with [`enqueue`], so the event exists exactly when
+//! the change commits. Each service runs a [`Relay`] that delivers its
+//! events (to chat, a webhook and the [`EventBus`]) and marks them
+//! published.
+//!
+//! Events are claimed for a few minutes at a time, so several instances of
+//! a service never deliver the same event together, and each destination
+//! reached is recorded, so a retry only repeats the ones that failed.
+//! Webhook requests carry the event id in `X-Tabrela-Event-Id`; a consumer
+//! that sees an id twice (after a crash between sending and recording)
+//! should drop the repeat.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::config::{ConfigVar, EnvReader};
+use crate::event_bus::EventBus;
+use crate::features::{Feature, Features};
+use crate::notify::{Notification, Notifier};
+
+/// Environment variables read by [`OutboxSettings::read`]
+pub const OUTBOX_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::optional(
+        "EVENT_WEBHOOK_URL",
+        "URL that receives every domain event (ballot_confirmed, merit_changed, user_checked_in) as a JSON POST",
+    ),
+    ConfigVar::optional(
+        "EVENT_WEBHOOK_SECRET",
+        "Secret signing event webhook bodies, sent as X-Tabrela-Signature: sha256=<hex HMAC>",
+    ),
+    ConfigVar::default(
+        "OUTBOX_POLL_SECS",
+        "2",
+        "Seconds between checks for undelivered events",
+    ),
+];
+
+pub const EVENT_ID_HEADER: &str = "x-tabrela-event-id";
+pub const SIGNATURE_HEADER: &str = "x-tabrela-signature";
+
+/// Failed deliveries before an event is given up on. Retries back off to an
+/// hour apart, so this is about a day of trying.
+pub const MAX_ATTEMPTS: i32 = 30;
+
+/// Events claimed per run
+const BATCH_SIZE: i64 = 20;
+/// How long a claimed event is left to the relay that claimed it
+const CLAIM_SECS: i64 = 600;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Published events are kept this long for inspection
+const RETENTION_DAYS: i32 = 7;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Destinations recorded in `outbox_events.delivered_to`
+const WEBHOOK: &str = "webhook";
+const CHAT: &str = "chat";
+const BUS: &str = "bus";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxSettings {
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub poll_interval: Duration,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            webhook_secret: None,
+            poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+impl OutboxSettings {
+    /// Read the variables in [`OUTBOX_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let poll_secs: u64 = reader.parse("OUTBOX_POLL_SECS");
+        if poll_secs == 0 {
+            reader.check::<(), _>(Err("OUTBOX_POLL_SECS must be at least 1"));
+        }
+
+        Self {
+            webhook_url: reader.optional("EVENT_WEBHOOK_URL"),
+            webhook_secret: reader.optional("EVENT_WEBHOOK_SECRET"),
+            poll_interval: Duration::from_secs(poll_secs.max(1)),
+        }
+    }
+}
+
+/// A domain event as stored and as sent to the webhook
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub service: String,
+    pub kind: String,
+    pub payload: Value,
+    #[serde(rename = "occurred_at")]
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub attempts: i32,
+    #[serde(skip)]
+    pub delivered_to: Vec<String>,
+}
+
+/// Record an event on `conn`, which should be the transaction making the
+/// change, so it is delivered once that commits and never if it rolls back
+pub async fn enqueue(
+    conn: &mut PgConnection,
+    service: &str,
+    kind: &str,
+    payload: &Value,
+) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        "INSERT INTO outbox_events (service, kind, payload) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(service)
+    .bind(kind)
+    .bind(payload)
+    .fetch_one(conn)
+    .await
+}
+
+/// Seconds to wait before retrying an event that has failed `attempts` times
+pub fn backoff_secs(attempts: i32) -> i64 {
+    2i64.pow(attempts.clamp(0, 12) as u32).min(3600)
+}
+
+/// `X-Tabrela-Signature` value for `body`
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Builds the chat message for an event, or `None` for events not posted
+pub type ChatFormat = fn(&OutboxEvent) -> Option<Notification>;
+
+/// Delivers one service's events
+#[derive(Clone)]
+pub struct Relay {
+    pool: PgPool,
+    service: &'static str,
+    settings: OutboxSettings,
+    chat: Option<(Notifier, ChatFormat)>,
+    bus: Option<EventBus>,
+    features: Option<Features>,
+    client: reqwest::Client,
+}
+
+impl Relay {
+    pub fn new(pool: PgPool, service: &'static str, settings: &OutboxSettings) -> Self {
+        Self {
+            pool,
+            service,
+            settings: settings.clone(),
+            chat: None,
+            bus: None,
+            features: None,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Also post events to chat, as built by `format`, when their
+    /// notification kind is enabled
+    pub fn with_chat(mut self, notifier: Notifier, format: ChatFormat) -> Self {
+        self.chat = Some((notifier, format));
+        self
+    }
+
+    /// Also publish events to `bus` for its subscribers
+    pub fn with_bus(mut self, bus: EventBus) -> Self {
+        self.bus = Some(bus);
+        self
+    }
+
+    /// Only post to the webhook while the webhooks feature is on
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Start delivering in the background
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.settings.poll_interval);
+            let mut last_prune = Instant::now();
+            loop {
+                interval.tick().await;
+                match self.relay_due().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::debug!("Published {} {} events", count, self.service),
+                    Err(e) => tracing::warn!("Failed to relay {} events: {}", self.service, e),
+                }
+                if last_prune.elapsed() >= PRUNE_INTERVAL {
+                    last_prune = Instant::now();
+                    if let Err(e) = self.prune().await {
+                        tracing::warn!("Failed to prune {} events: {}", self.service, e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Claim and deliver the events that are due, returning how many were
+    /// published
+    pub async fn relay_due(&self) -> Result<usize, sqlx::Error> {
+        // RETURNING does not keep the subquery's order
+        let mut events = sqlx::query_as::<_, OutboxEvent>(
+            r#"
+            UPDATE outbox_events SET next_attempt_at = NOW() + make_interval(secs => $4)
+            WHERE id IN (
+                SELECT id FROM outbox_events
+                WHERE service = $1 AND published_at IS NULL
+                    AND next_attempt_at <= NOW() AND attempts < $2
+                ORDER BY created_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, service, kind, payload, created_at, attempts, delivered_to
+            "#,
+        )
+        .bind(self.service)
+        .bind(MAX_ATTEMPTS)
+        .bind(BATCH_SIZE)
+        .bind(CLAIM_SECS as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        events.sort_by_key(|event| event.created_at);
+
+        let mut published = 0;
+        for event in &events {
+            let (delivered_to, error) = self.deliver(event).await;
+            match error {
+                None => {
+                    sqlx::query(
+                        r#"
+                        UPDATE outbox_events
+                        SET published_at = NOW(), delivered_to = $2, last_error = NULL
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(event.id)
+                    .bind(&delivered_to)
+                    .execute(&self.pool)
+                    .await?;
+                    published += 1;
+                }
+                Some(error) => {
+                    let attempts = event.attempts + 1;
+                    if attempts >= MAX_ATTEMPTS {
+                        tracing::error!(
+                            "Giving up on {} event {} after {} attempts: {}",
+                            event.kind,
+                            event.id,
+                            attempts,
+                            error
+                        );
+                    } else {
+                        tracing::warn!(
+                            "Failed to deliver {} event {}: {}",
+                            event.kind,
+                            event.id,
+                            error
+                        );
+                    }
+                    sqlx::query(
+                        r#"
+                        UPDATE outbox_events
+                        SET attempts = $2, delivered_to = $3, last_error = $4,
+                            next_attempt_at = NOW() + make_interval(secs => $5)
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(event.id)
+                    .bind(attempts)
+                    .bind(&delivered_to)
+                    .bind(&error)
+                    .bind(backoff_secs(attempts) as f64)
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(published)
+    }
+
+    /// Send `event` to each destination it has not reached yet. Returns
+    /// every destination reached so far and what failed, if anything.
+    async fn deliver(&self, event: &OutboxEvent) -> (Vec<String>, Option<String>) {
+        let mut delivered_to = event.delivered_to.clone();
+        let mut failures = Vec::new();
+
+        if let Some(url) = self.webhook_url().await {
+            if !delivered_to.iter().any(|d| d == WEBHOOK) {
+                match self.post_webhook(url, event).await {
+                    Ok(()) => delivered_to.push(WEBHOOK.to_string()),
+                    Err(e) => failures.push(format!("webhook: {}", e)),
+                }
+            }
+        }
+
+        if let Some((notifier, format)) = &self.chat {
+            if !delivered_to.iter().any(|d| d == CHAT) {
+                let notification = format(event).filter(|n| notifier.is_enabled(n.kind));
+                let sent = match notification {
+                    Some(notification) => notifier.send(&notification).await,
+                    None => Ok(()),
+                };
+                match sent {
+                    Ok(()) => delivered_to.push(CHAT.to_string()),
+                    Err(e) => failures.push(format!("chat: {}", e)),
+                }
+            }
+        }
+
+        if let Some(bus) = &self.bus {
+            if !delivered_to.iter().any(|d| d == BUS) {
+                match bus.publish(event).await {
+                    Ok(()) => delivered_to.push(BUS.to_string()),
+                    Err(e) => failures.push(format!("bus: {}", e)),
+                }
+            }
+        }
+
+        let error = (!failures.is_empty()).then(|| failures.join("; "));
+        (delivered_to, error)
+    }
+
+    /// The webhook to post to, unless none is set or the webhooks feature
+    /// is off
+    async fn webhook_url(&self) -> Option<&str> {
+        let url = self.settings.webhook_url.as_deref()?;
+        match &self.features {
+            Some(features) if !features.is_enabled(Feature::Webhooks).await => None,
+            _ => Some(url),
+        }
+    }
+
+    async fn post_webhook(&self, url: &str, event: &OutboxEvent) -> Result<(), String> {
+        let body = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+        let mut request = self
+            .client
+            .post(url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, event.id.to_string());
+        if let Some(secret) = &self.settings.webhook_secret {
+            request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+        }
+
+        let response = request.body(body).send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("answered {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Delete published events past the retention period
+    async fn prune(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM outbox_events
+            WHERE service = $1 AND published_at < NOW() - make_interval(days => $2)
+            "#,
+        )
+        .bind(self.service)
+        .bind(RETENTION_DAYS)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_backoff_grows_to_an_hour() {
+        assert_eq!(backoff_secs(0), 1);
+        assert_eq!(backoff_secs(1), 2);
+        assert_eq!(backoff_secs(5), 32);
+        assert_eq!(backoff_secs(12), 3600);
+        assert_eq!(backoff_secs(MAX_ATTEMPTS), 3600);
+    }
+
+    #[test]
+    fn test_signature() {
+        // HMAC-SHA256 test vector from RFC 4231, case 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_webhook_body() {
+        let event = OutboxEvent {
+            id: Uuid::nil(),
+            service: "merit".to_string(),
+            kind: "merit_changed".to_string(),
+            payload: json!({"change_amount": 5}),
+            created_at: DateTime::from_timestamp(0, 0).unwrap(),
+            attempts: 3,
+            delivered_to: vec![CHAT.to_string()],
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "id": Uuid::nil(),
+                "service": "merit",
+                "kind": "merit_changed",
+                "payload": {"change_amount": 5},
+                "occurred_at": "1970-01-01T00:00:00Z"
+            })
+        );
+    }
+}
@@ -0,0 +1,71 @@
+/// Largest page size any list endpoint will return
+pub const MAX_PER_PAGE: i32 = 100;
+
+/// Resolved `page` / `per_page` query parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub page: i32,
+    pub per_page: i32,
+}
+
+impl Pagination {
+    /// Resolve optional query parameters, clamping to `1..=MAX_PER_PAGE`
+    pub fn new(page: Option<i32>, per_page: Option<i32>, default_per_page: i32) -> Self {
+        Self {
+            page: page.unwrap_or(1).max(1),
+            per_page: per_page.unwrap_or(default_per_page).clamp(1, MAX_PER_PAGE),
+        }
+    }
+
+    /// Row offset for the current page
+    pub fn offset(&self) -> i64 {
+        (self.page as i64 - 1) * self.per_page as i64
+    }
+
+    /// Number of pages needed to show `total` rows
+    pub fn total_pages(&self, total: i64) -> i64 {
+        if total <= 0 {
+            return 0;
+        }
+        (total + self.per_page as i64 - 1) / self.per_page as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let p = Pagination::new(None, None, 20);
+        assert_eq!(
+            p,
+            Pagination {
+                page: 1,
+                per_page: 20
+            }
+        );
+        assert_eq!(p.offset(), 0);
+    }
+
+    #[test]
+    fn test_clamping() {
+        let p = Pagination::new(Some(0), Some(1000), 20);
+        assert_eq!(p.page, 1);
+        assert_eq!(p.per_page, MAX_PER_PAGE);
+
+        let p = Pagination::new(Some(-3), Some(0), 20);
+        assert_eq!(p.page, 1);
+        assert_eq!(p.per_page, 1);
+    }
+
+    #[test]
+    fn test_offset_and_total_pages() {
+        let p = Pagination::new(Some(3), Some(25), 20);
+        assert_eq!(p.offset(), 50);
+        assert_eq!(p.total_pages(0), 0);
+        assert_eq!(p.total_pages(25), 1);
+        assert_eq!(p.total_pages(26), 2);
+        assert_eq!(p.total_pages(100), 4);
+    }
+}
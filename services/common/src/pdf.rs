@@ -0,0 +1,380 @@
+//! Minimal PDF writer for generated reports: A4 pages of left-aligned text
+//! in the standard Helvetica and Courier fonts, so nothing is embedded.
+//! Characters outside Latin-1 are printed as `?`.
+
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// Line height as a multiple of the font size
+const LEADING: f32 = 1.4;
+
+/// Font resource names, in the order the font objects are written
+const FONTS: [(&str, &str); 3] = [
+    ("F1", "Helvetica"),
+    ("F2", "Helvetica-Bold"),
+    ("F3", "Courier"),
+];
+
+/// Objects written before the pages: catalog, page tree, info, fonts
+const FIRST_PAGE_OBJECT: usize = 4 + FONTS.len();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    Heading,
+    Body,
+    /// Fixed width, for lining up table columns with spaces
+    Mono,
+}
+
+impl TextStyle {
+    fn font(self) -> &'static str {
+        match self {
+            TextStyle::Body => "F1",
+            TextStyle::Heading => "F2",
+            TextStyle::Mono => "F3",
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            TextStyle::Heading => 16.0,
+            TextStyle::Body => 11.0,
+            TextStyle::Mono => 9.0,
+        }
+    }
+
+    /// Characters that fit across the page. Courier is 0.6 em wide;
+    /// Helvetica averages less, so this errs towards wrapping early.
+    fn columns(self) -> usize {
+        ((PAGE_WIDTH - 2.0 * MARGIN) / (self.size() * 0.6)) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    style: TextStyle,
+    text: String,
+    /// Start a new page with this line
+    new_page: bool,
+}
+
+/// A text document laid out top to bottom, starting new pages as needed
+#[derive(Debug, Clone)]
+pub struct PdfDocument {
+    title: String,
+    filename: String,
+    lines: Vec<Line>,
+    /// The next line starts a new page
+    page_break: bool,
+}
+
+impl PdfDocument {
+    /// `filename` is suggested to browsers that download the document
+    pub fn new(title: impl Into<String>, filename: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            filename: filename.into(),
+            lines: Vec::new(),
+            page_break: false,
+        }
+    }
+
+    /// Add text, wrapped at word boundaries to fit the page
+    pub fn push(&mut self, style: TextStyle, text: &str) {
+        let new_page = std::mem::take(&mut self.page_break);
+        for (i, line) in wrap(text, style.columns()).into_iter().enumerate() {
+            self.lines.push(Line {
+                style,
+                text: line,
+                new_page: new_page && i == 0,
+            });
+        }
+    }
+
+    pub fn blank_line(&mut self) {
+        self.push(TextStyle::Body, "");
+    }
+
+    /// Start the next line on a new page, unless the document is empty
+    pub fn page_break(&mut self) {
+        self.page_break = !self.lines.is_empty();
+    }
+
+    pub fn render(&self) -> Vec<u8> {
+        let pages = self.paginate();
+
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+
+        let kids = (0..pages.len())
+            .map(|i| format!("{} 0 R", FIRST_PAGE_OBJECT + 2 * i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        objects.push(
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()).into_bytes(),
+        );
+
+        let mut info = b"<< /Title ".to_vec();
+        info.extend(string_literal(&self.title));
+        info.extend(b" /Producer (Tabrela) >>");
+        objects.push(info);
+
+        for (_, base_font) in FONTS {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    base_font
+                )
+                .into_bytes(),
+            );
+        }
+
+        let fonts = FONTS
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| format!("/{} {} 0 R", name, 4 + i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        for (i, page) in pages.iter().enumerate() {
+            let content_object = FIRST_PAGE_OBJECT + 2 * i + 1;
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH, PAGE_HEIGHT, fonts, content_object
+                )
+                .into_bytes(),
+            );
+
+            let content = page_content(page);
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+            out.extend(object);
+            out.extend(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            out.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .into_bytes(),
+        );
+        out
+    }
+
+    /// Lines grouped into pages by the height they take up
+    fn paginate(&self) -> Vec<Vec<&Line>> {
+        let mut pages = vec![Vec::new()];
+        let mut used = 0.0;
+        for line in &self.lines {
+            let height = line.style.size() * LEADING;
+            if (line.new_page || used + height > PAGE_HEIGHT - 2.0 * MARGIN) && used > 0.0 {
+                pages.push(Vec::new());
+                used = 0.0;
+            }
+            used += height;
+            pages.last_mut().expect("at least one page").push(line);
+        }
+        pages
+    }
+}
+
+impl IntoResponse for PdfDocument {
+    fn into_response(self) -> Response {
+        (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", self.filename),
+                ),
+            ],
+            self.render(),
+        )
+            .into_response()
+    }
+}
+
+/// Content stream placing each line below the last
+fn page_content(lines: &[&Line]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in lines {
+        y -= line.style.size() * LEADING;
+        if line.text.is_empty() {
+            continue;
+        }
+        out.extend(
+            format!(
+                "BT /{} {} Tf {} {} Td ",
+                line.style.font(),
+                line.style.size(),
+                MARGIN,
+                y
+            )
+            .into_bytes(),
+        );
+        out.extend(string_literal(&line.text));
+        out.extend(b" Tj ET\n");
+    }
+    out
+}
+
+/// A PDF string literal in WinAnsiEncoding, which matches Latin-1 for
+/// printable characters
+fn string_literal(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            ' '..='~' | '\u{a0}'..='\u{ff}' => out.push(c as u32 as u8),
+            c if c.is_control() => {}
+            _ => out.push(b'?'),
+        }
+    }
+    out.push(b')');
+    out
+}
+
+/// Split text into lines of at most `columns` characters, breaking at
+/// spaces where possible
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let mut word = word.to_string();
+            loop {
+                let needed = if line.is_empty() {
+                    word.chars().count()
+                } else {
+                    line.chars().count() + 1 + word.chars().count()
+                };
+                if needed <= columns {
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&word);
+                    break;
+                }
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                    continue;
+                }
+                // A word longer than a whole line is split across lines
+                let split = word
+                    .char_indices()
+                    .nth(columns)
+                    .map_or(word.len(), |(i, _)| i);
+                lines.push(word[..split].to_string());
+                word = word[split..].to_string();
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object_offset(pdf: &[u8], number: usize) -> usize {
+        let needle = format!("\n{} 0 obj\n", number);
+        pdf.windows(needle.len())
+            .position(|w| w == needle.as_bytes())
+            .unwrap()
+            + 1
+    }
+
+    #[test]
+    fn test_render_structure() {
+        let mut doc = PdfDocument::new("Report (draft)", "report.pdf");
+        doc.push(TextStyle::Heading, "Weekly match");
+        doc.push(TextStyle::Mono, "alice     yes");
+        let pdf = doc.render();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Title (Report \\(draft\\))"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains("(Weekly match) Tj"));
+
+        // Every xref entry points at its object
+        let xref = text.find("\nxref\n").unwrap() + 1;
+        let entries: Vec<&str> = text[xref..].lines().skip(3).take(8).collect();
+        assert_eq!(entries.len(), 8);
+        for (i, entry) in entries.iter().enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert_eq!(offset, object_offset(&pdf, i + 1), "object {}", i + 1);
+        }
+        let startxref: usize = text.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert_eq!(startxref, xref);
+    }
+
+    #[test]
+    fn test_new_pages() {
+        let mut doc = PdfDocument::new("Long", "long.pdf");
+        for i in 0..100 {
+            doc.push(TextStyle::Body, &format!("Line {}", i));
+        }
+        let text = String::from_utf8_lossy(&doc.render()).to_string();
+
+        assert!(text.contains("/Count 3"));
+        assert!(text.contains("/Kids [7 0 R 9 0 R 11 0 R]"));
+    }
+
+    #[test]
+    fn test_page_breaks() {
+        let mut doc = PdfDocument::new("Ballots", "ballots.pdf");
+        doc.page_break();
+        doc.push(TextStyle::Heading, "Room 1");
+        doc.page_break();
+        doc.page_break();
+        doc.push(TextStyle::Heading, "Room 2");
+        let text = String::from_utf8_lossy(&doc.render()).to_string();
+
+        // A break before any text, or twice in a row, adds no empty page
+        assert!(text.contains("/Count 2"));
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(string_literal("a(b)\\c"), b"(a\\(b\\)\\\\c)".to_vec());
+        assert_eq!(string_literal("café\t✓"), b"(caf\xe9?)".to_vec());
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+}
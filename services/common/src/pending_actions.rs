@@ -0,0 +1,362 @@
+//! The two-person rule for admin actions that cannot be undone: demoting
+//! an admin when only one would be left, deleting an event that has
+//! ballots, and erasing a series. Instead of acting at once, the service
+//! records a pending action and answers 202; a second admin then confirms
+//! it, which carries it out, or any admin rejects it. The admin who asked
+//! can reject (withdraw) their own request but never confirm it.
+//!
+//! Pending actions live in the shared `pending_actions` table. Each service
+//! lists, confirms and rejects only its own, since only it can carry them
+//! out.
+
+use axum::{http::StatusCode, Json};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::{fmt, str::FromStr};
+use uuid::Uuid;
+
+use crate::error::{api_error, db_error, ApiError};
+
+/// How long a request waits for a second admin before it lapses
+pub const PENDING_ACTION_TTL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectedAction {
+    /// Revoking admin from one of the last two admins (auth)
+    DemoteAdmin,
+    /// Deleting an event with ballots (attendance)
+    DeleteEvent,
+    /// Deleting a series with its matches and ballots (tabulation)
+    DeleteSeries,
+}
+
+impl ProtectedAction {
+    pub const ALL: &'static [ProtectedAction] = &[
+        ProtectedAction::DemoteAdmin,
+        ProtectedAction::DeleteEvent,
+        ProtectedAction::DeleteSeries,
+    ];
+
+    /// Value stored in `pending_actions.action`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtectedAction::DemoteAdmin => "demote_admin",
+            ProtectedAction::DeleteEvent => "delete_event",
+            ProtectedAction::DeleteSeries => "delete_series",
+        }
+    }
+
+    /// The service that carries the action out
+    pub fn service(&self) -> &'static str {
+        match self {
+            ProtectedAction::DemoteAdmin => "auth",
+            ProtectedAction::DeleteEvent => "attendance",
+            ProtectedAction::DeleteSeries => "tabulation",
+        }
+    }
+}
+
+impl fmt::Display for ProtectedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProtectedAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ProtectedAction::ALL
+            .iter()
+            .copied()
+            .find(|action| action.as_str() == s)
+            .ok_or_else(|| format!("Unknown action: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "pending_action_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PendingStatus {
+    Pending,
+    Confirmed,
+    Rejected,
+    /// Confirmed, but it could not be carried out
+    Failed,
+}
+
+/// A destructive action waiting for, or decided by, a second admin
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct PendingAction {
+    pub id: Uuid,
+    pub action: String,
+    /// The admin, event or series acted on
+    pub target_id: Uuid,
+    /// What will happen, in words, for the confirming admin
+    pub summary: String,
+    pub requested_by: Uuid,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub status: PendingStatus,
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl PendingAction {
+    pub fn kind(&self) -> Result<ProtectedAction, String> {
+        self.action.parse()
+    }
+}
+
+/// Why an admin may not confirm or reject `action`, if they may not
+pub fn decision_error(
+    action: &PendingAction,
+    admin_id: Uuid,
+    confirm: bool,
+    now: DateTime<Utc>,
+) -> Option<ApiError> {
+    if action.status != PendingStatus::Pending {
+        return Some(api_error(
+            StatusCode::CONFLICT,
+            "This action has already been decided",
+        ));
+    }
+    if action.expires_at <= now {
+        return Some(api_error(
+            StatusCode::GONE,
+            "This action has lapsed; request it again",
+        ));
+    }
+    if confirm && action.requested_by == admin_id {
+        return Some(api_error(
+            StatusCode::FORBIDDEN,
+            "A different admin must confirm this action",
+        ));
+    }
+    None
+}
+
+/// Expiry of a request made at `requested_at`
+pub fn expires_at(requested_at: DateTime<Utc>) -> DateTime<Utc> {
+    requested_at + Duration::hours(PENDING_ACTION_TTL_HOURS)
+}
+
+/// The 202 answering a request that now waits for a second admin
+pub fn awaiting_confirmation(action: &PendingAction) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "message": "This action needs a second admin to confirm it",
+            "pending_action": action
+        })),
+    )
+}
+
+/// One service's pending actions
+#[derive(Clone)]
+pub struct PendingActions {
+    pool: PgPool,
+    service: &'static str,
+}
+
+impl PendingActions {
+    pub fn new(pool: PgPool, service: &'static str) -> Self {
+        Self { pool, service }
+    }
+
+    /// Ask for `action` on `target_id`. Asking again while a request for
+    /// the same thing is pending returns that request.
+    pub async fn request(
+        &self,
+        action: ProtectedAction,
+        target_id: Uuid,
+        summary: &str,
+        requested_by: Uuid,
+    ) -> Result<PendingAction, sqlx::Error> {
+        let now = Utc::now();
+        if let Some(existing) = sqlx::query_as::<_, PendingAction>(
+            r#"
+            SELECT * FROM pending_actions
+            WHERE action = $1 AND target_id = $2 AND status = 'pending' AND expires_at > NOW()
+            "#,
+        )
+        .bind(action.as_str())
+        .bind(target_id)
+        .fetch_optional(&self.pool)
+        .await?
+        {
+            return Ok(existing);
+        }
+
+        sqlx::query_as::<_, PendingAction>(
+            r#"
+            INSERT INTO pending_actions
+                (id, service, action, target_id, summary, requested_by, requested_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(action.service())
+        .bind(action.as_str())
+        .bind(target_id)
+        .bind(summary)
+        .bind(requested_by)
+        .bind(now)
+        .bind(expires_at(now))
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// This service's requests still waiting for a second admin
+    pub async fn list(&self) -> Result<Vec<PendingAction>, sqlx::Error> {
+        sqlx::query_as::<_, PendingAction>(
+            r#"
+            SELECT * FROM pending_actions
+            WHERE service = $1 AND status = 'pending' AND expires_at > NOW()
+            ORDER BY requested_at
+            "#,
+        )
+        .bind(self.service)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Record `admin_id`'s decision on pending action `id`. Only one
+    /// decision is ever recorded, however many admins answer at once.
+    pub async fn decide(
+        &self,
+        id: Uuid,
+        admin_id: Uuid,
+        confirm: bool,
+    ) -> Result<PendingAction, ApiError> {
+        let action = sqlx::query_as::<_, PendingAction>(
+            "SELECT * FROM pending_actions WHERE id = $1 AND service = $2",
+        )
+        .bind(id)
+        .bind(self.service)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Pending action not found"))?;
+        if let Some(error) = decision_error(&action, admin_id, confirm, Utc::now()) {
+            return Err(error);
+        }
+
+        let status = if confirm {
+            PendingStatus::Confirmed
+        } else {
+            PendingStatus::Rejected
+        };
+        sqlx::query_as::<_, PendingAction>(
+            r#"
+            UPDATE pending_actions
+            SET status = $3, decided_by = $2, decided_at = NOW()
+            WHERE id = $1 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(admin_id)
+        .bind(status)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| api_error(StatusCode::CONFLICT, "This action has already been decided"))
+    }
+
+    /// Mark a confirmed action that can no longer be carried out as failed,
+    /// so it is neither shown as done nor left waiting
+    pub async fn fail(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE pending_actions SET status = 'failed' WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Put a confirmed action back to pending when carrying it out failed,
+    /// so it can be confirmed again
+    pub async fn reopen(&self, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE pending_actions
+            SET status = 'pending', decided_by = NULL, decided_at = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(requested_by: Uuid, now: DateTime<Utc>) -> PendingAction {
+        PendingAction {
+            id: Uuid::new_v4(),
+            action: ProtectedAction::DeleteSeries.as_str().to_string(),
+            target_id: Uuid::new_v4(),
+            summary: "Delete series Round 3".to_string(),
+            requested_by,
+            requested_at: now,
+            expires_at: expires_at(now),
+            status: PendingStatus::Pending,
+            decided_by: None,
+            decided_at: None,
+        }
+    }
+
+    fn status(error: Option<ApiError>) -> Option<StatusCode> {
+        error.map(|(status, Json(_))| status)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for action in ProtectedAction::ALL {
+            assert_eq!(action.as_str().parse::<ProtectedAction>(), Ok(*action));
+        }
+        assert!("drop_database".parse::<ProtectedAction>().is_err());
+    }
+
+    #[test]
+    fn test_requester_cannot_confirm() {
+        let (requester, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+        let action = pending(requester, now);
+
+        assert_eq!(
+            status(decision_error(&action, requester, true, now)),
+            Some(StatusCode::FORBIDDEN)
+        );
+        // Withdrawing one's own request is fine
+        assert_eq!(status(decision_error(&action, requester, false, now)), None);
+        assert_eq!(status(decision_error(&action, other, true, now)), None);
+    }
+
+    #[test]
+    fn test_decided_and_lapsed_actions() {
+        let (requester, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+        let mut action = pending(requester, now);
+
+        assert_eq!(
+            status(decision_error(&action, other, true, expires_at(now))),
+            Some(StatusCode::GONE)
+        );
+        for decided in [PendingStatus::Rejected, PendingStatus::Failed] {
+            action.status = decided;
+            assert_eq!(
+                status(decision_error(&action, other, true, now)),
+                Some(StatusCode::CONFLICT)
+            );
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Per-user notification preferences: for each category of notification,
+//! which channels a member wants it on. Only changes from the defaults are
+//! stored, in the `notification_preferences` table; the auth service edits
+//! them and the services that notify members check them with [`allows`].
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    Email,
+    InApp,
+    Sms,
+}
+
+impl NotificationChannel {
+    pub const ALL: &'static [NotificationChannel] = &[
+        NotificationChannel::Email,
+        NotificationChannel::InApp,
+        NotificationChannel::Sms,
+    ];
+
+    /// Value stored in `notification_preferences.channel`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannel::Email => "email",
+            NotificationChannel::InApp => "in_app",
+            NotificationChannel::Sms => "sms",
+        }
+    }
+}
+
+impl fmt::Display for NotificationChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotificationChannel::ALL
+            .iter()
+            .copied()
+            .find(|channel| channel.as_str() == s)
+            .ok_or_else(|| format!("Unknown notification channel: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    /// Being drawn to speak or adjudicate, or put on an event's staff
+    Allocations,
+    /// Results of matches taken part in
+    Results,
+    /// Reminders before events the member is available for
+    Reminders,
+    /// Merit awarded or taken away
+    Merit,
+}
+
+impl NotificationCategory {
+    pub const ALL: &'static [NotificationCategory] = &[
+        NotificationCategory::Allocations,
+        NotificationCategory::Results,
+        NotificationCategory::Reminders,
+        NotificationCategory::Merit,
+    ];
+
+    /// Value stored in `notification_preferences.category`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationCategory::Allocations => "allocations",
+            NotificationCategory::Results => "results",
+            NotificationCategory::Reminders => "reminders",
+            NotificationCategory::Merit => "merit",
+        }
+    }
+}
+
+impl fmt::Display for NotificationCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotificationCategory::ALL
+            .iter()
+            .copied()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| format!("Unknown notification category: {}", s))
+    }
+}
+
+/// Whether a member who has changed nothing gets `channel`. SMS costs
+/// money per message, so members opt in to it.
+pub fn default_enabled(channel: NotificationChannel) -> bool {
+    channel != NotificationChannel::Sms
+}
+
+/// SQL expression that is true when the user whose id is in `user_column`
+/// wants `category` notifications on `channel`
+pub fn allows(
+    user_column: &str,
+    category: NotificationCategory,
+    channel: NotificationChannel,
+) -> String {
+    format!(
+        "COALESCE((SELECT np.enabled FROM notification_preferences np \
+         WHERE np.user_id = {} AND np.category = '{}' AND np.channel = '{}'), {})",
+        user_column,
+        category.as_str(),
+        channel.as_str(),
+        default_enabled(channel)
+    )
+}
+
+/// Category by channel: whether each is on. Also the shape of a change,
+/// where only the entries given are touched.
+pub type PreferenceMatrix = BTreeMap<NotificationCategory, BTreeMap<NotificationChannel, bool>>;
+
+/// Every category and channel, with the stored `overrides` applied to the
+/// defaults
+pub fn preference_matrix(
+    overrides: impl IntoIterator<Item = (NotificationCategory, NotificationChannel, bool)>,
+) -> PreferenceMatrix {
+    let mut matrix: PreferenceMatrix = NotificationCategory::ALL
+        .iter()
+        .map(|&category| {
+            let channels = NotificationChannel::ALL
+                .iter()
+                .map(|&channel| (channel, default_enabled(channel)))
+                .collect();
+            (category, channels)
+        })
+        .collect();
+
+    for (category, channel, enabled) in overrides {
+        matrix.entry(category).or_default().insert(channel, enabled);
+    }
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for channel in NotificationChannel::ALL {
+            assert_eq!(
+                channel.as_str().parse::<NotificationChannel>(),
+                Ok(*channel)
+            );
+        }
+        for category in NotificationCategory::ALL {
+            assert_eq!(
+                category.as_str().parse::<NotificationCategory>(),
+                Ok(*category)
+            );
+        }
+        assert!("push".parse::<NotificationChannel>().is_err());
+    }
+
+    #[test]
+    fn test_matrix_applies_overrides_to_defaults() {
+        let matrix = preference_matrix([
+            (
+                NotificationCategory::Reminders,
+                NotificationChannel::Email,
+                false,
+            ),
+            (NotificationCategory::Merit, NotificationChannel::Sms, true),
+        ]);
+
+        let reminders = &matrix[&NotificationCategory::Reminders];
+        assert!(!reminders[&NotificationChannel::Email]);
+        assert!(reminders[&NotificationChannel::InApp]);
+        assert!(!reminders[&NotificationChannel::Sms]);
+        assert!(matrix[&NotificationCategory::Merit][&NotificationChannel::Sms]);
+        assert_eq!(matrix.len(), NotificationCategory::ALL.len());
+    }
+
+    #[test]
+    fn test_matrix_serializes_with_snake_case_keys() {
+        let json = serde_json::to_value(preference_matrix([])).unwrap();
+        assert_eq!(json["allocations"]["in_app"], serde_json::json!(true));
+        assert_eq!(json["results"]["sms"], serde_json::json!(false));
+    }
+
+    #[test]
+    fn test_allows_falls_back_to_the_default() {
+        let condition = allows(
+            "u.id",
+            NotificationCategory::Reminders,
+            NotificationChannel::Sms,
+        );
+        assert!(condition.contains("np.user_id = u.id"));
+        assert!(condition.contains("np.category = 'reminders'"));
+        assert!(condition.ends_with(", false)"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Per-client rate limits for routes worth guessing at, such as login and
+//! one-time codes. Requests are counted per client address in fixed
+//! windows in the [`KvStore`], so with Redis every replica of a service
+//! shares the count.
+//!
+//! The client address is taken from `X-Real-IP` or the first
+//! `X-Forwarded-For` entry set by the reverse proxy, then from the
+//! connection.
+//! Requests whose address is unknown are not limited.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::{header, HeaderMap, HeaderValue, StatusCode};
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{ConfigVar, EnvReader};
+use crate::error::api_error;
+use crate::kv::KvStore;
+
+/// Environment variables read by [`RateLimit::read`]
+pub const RATE_LIMIT_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "RATE_LIMIT_REQUESTS",
+        "30",
+        "Requests a client may make to rate-limited routes per window (0 disables limits)",
+    ),
+    ConfigVar::default(
+        "RATE_LIMIT_WINDOW_SECS",
+        "60",
+        "Length of the rate limit window in seconds",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    /// 0 turns the limit off
+    pub requests: u64,
+    pub window: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests: 30,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RateLimit {
+    /// Read the variables in [`RATE_LIMIT_SCHEMA`], recording any problems
+    /// on `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let window: u64 = reader.parse("RATE_LIMIT_WINDOW_SECS");
+        if window == 0 {
+            reader.check::<(), _>(Err("RATE_LIMIT_WINDOW_SECS must be at least 1"));
+        }
+
+        Self {
+            requests: reader.parse("RATE_LIMIT_REQUESTS"),
+            window: Duration::from_secs(window.max(1)),
+        }
+    }
+}
+
+/// A rate limit applied to one group of routes
+#[derive(Clone)]
+pub struct RateLimiter {
+    pub store: KvStore,
+    pub limit: RateLimit,
+    /// Routes sharing a scope share a count
+    pub scope: &'static str,
+}
+
+/// Address of the client making the request, as described in the module
+/// docs
+pub fn client_ip(headers: &HeaderMap, peer: Option<SocketAddr>) -> Option<String> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    header("x-real-ip")
+        .or_else(|| {
+            header("x-forwarded-for").and_then(|chain| chain.split(',').next().map(str::trim))
+        })
+        .map(str::to_string)
+        .or_else(|| peer.map(|addr| addr.ip().to_string()))
+}
+
+/// Middleware answering `429 Too Many Requests` once a client has used up
+/// its requests for the current window. If the store cannot be reached the
+/// request is let through.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limiter.limit.requests == 0 {
+        return next.run(request).await;
+    }
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0);
+    let Some(ip) = client_ip(request.headers(), peer) else {
+        return next.run(request).await;
+    };
+
+    let window = limiter.limit.window.as_secs().max(1);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let key = format!("ratelimit:{}:{}:{}", limiter.scope, ip, now / window);
+
+    match limiter.store.incr(&key, limiter.limit.window).await {
+        Ok(count) if count > limiter.limit.requests => {
+            let retry_after = window - now % window;
+            let mut response = api_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests, try again later",
+            )
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+        Ok(_) => next.run(request).await,
+        Err(e) => {
+            tracing::warn!("Not rate limiting {}: {}", limiter.scope, e);
+            next.run(request).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_client_ip() {
+        let peer: SocketAddr = "10.0.0.9:4000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, None), None);
+        assert_eq!(client_ip(&headers, Some(peer)).as_deref(), Some("10.0.0.9"));
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        assert_eq!(
+            client_ip(&headers, Some(peer)).as_deref(),
+            Some("203.0.113.7")
+        );
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.2"));
+        assert_eq!(
+            client_ip(&headers, Some(peer)).as_deref(),
+            Some("198.51.100.2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clients_are_limited_separately() {
+        let limiter = RateLimiter {
+            store: KvStore::memory(),
+            limit: RateLimit {
+                requests: 2,
+                window: Duration::from_secs(60),
+            },
+            scope: "login",
+        };
+        let app = Router::new()
+            .route("/login", post(|| async { "OK" }))
+            .layer(middleware::from_fn_with_state(limiter, rate_limit));
+        let login = |ip: &'static str| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/login")
+                .header("x-real-ip", ip)
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        assert_eq!(login("203.0.113.7").await.unwrap().status(), StatusCode::OK);
+        assert_eq!(login("203.0.113.7").await.unwrap().status(), StatusCode::OK);
+        let limited = login("203.0.113.7").await.unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(
+            login("198.51.100.2").await.unwrap().status(),
+            StatusCode::OK
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Redaction of personal data from logs. Services write their logs through
+//! [`Redactor::writer`], which replaces email addresses, phone numbers and
+//! one-time codes in every line, whether they come from the service's own
+//! messages or from errors passed up by the database or a mail provider.
+//!
+//! `LOG_REDACT_ALLOW` leaves chosen kinds readable, such as `otp` when
+//! developing with `EMAIL_BACKEND=log`.
+
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::str::FromStr;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::config::{load_dotenv, ConfigVar, EnvReader};
+
+/// Environment variables read by [`RedactSettings::read`]
+pub const REDACT_SCHEMA: &[ConfigVar] = &[ConfigVar::optional(
+    "LOG_REDACT_ALLOW",
+    "Comma-separated kinds of personal data left readable in logs: email, phone, otp (e.g. otp locally, to read the codes EMAIL_BACKEND=log prints)",
+)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Otp,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 3] = [PiiKind::Email, PiiKind::Phone, PiiKind::Otp];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PiiKind::Email => "email",
+            PiiKind::Phone => "phone",
+            PiiKind::Otp => "otp",
+        }
+    }
+
+    /// What stands in for a redacted value
+    fn placeholder(&self) -> String {
+        format!("[{}]", self.as_str())
+    }
+
+    fn pattern(&self) -> Regex {
+        let pattern = match self {
+            PiiKind::Email => r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            // Phone numbers are stored in international format
+            PiiKind::Phone => r"\+\d{7,18}\b",
+            // A code shortly after the word "code" or "otp", as in emails,
+            // texts and request bodies
+            PiiKind::Otp => r"(?i)(\b(?:otp|code)\b[^\n]{0,60}?)\b\d{4,8}\b",
+        };
+        Regex::new(pattern).expect("redaction pattern is valid")
+    }
+}
+
+impl FromStr for PiiKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        PiiKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid LOG_REDACT_ALLOW: '{}' is not one of email, phone, otp",
+                    s
+                )
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedactSettings {
+    /// Kinds left readable
+    pub allow: Vec<PiiKind>,
+}
+
+impl RedactSettings {
+    /// Read the variables in [`REDACT_SCHEMA`], recording any problems on
+    /// `reader`
+    pub fn read(reader: &mut EnvReader) -> Self {
+        let list = reader.optional("LOG_REDACT_ALLOW").unwrap_or_default();
+        let allow = list
+            .split(',')
+            .filter(|kind| !kind.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<PiiKind>, _>>();
+        Self {
+            allow: reader.check(allow).unwrap_or_default(),
+        }
+    }
+
+    /// Settings for logging, which starts before the service reads the rest
+    /// of its config. Problems are left for that config check to report;
+    /// until then everything is redacted.
+    pub fn from_env() -> Self {
+        load_dotenv();
+        Self::read(&mut EnvReader::new(&[REDACT_SCHEMA]))
+    }
+}
+
+pub struct Redactor {
+    patterns: Vec<(PiiKind, Regex)>,
+}
+
+impl Redactor {
+    pub fn new(settings: &RedactSettings) -> Self {
+        Self {
+            patterns: PiiKind::ALL
+                .into_iter()
+                .filter(|kind| !settings.allow.contains(kind))
+                .map(|kind| (kind, kind.pattern()))
+                .collect(),
+        }
+    }
+
+    /// `text` with every kind not allowed replaced by a placeholder
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for (kind, pattern) in &self.patterns {
+            let placeholder = kind.placeholder();
+            let replaced = pattern.replace_all(&text, |caps: &Captures| match caps.get(1) {
+                Some(context) => format!("{}{}", context.as_str(), placeholder),
+                None => placeholder.clone(),
+            });
+            if let Cow::Owned(replaced) = replaced {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    /// Writer for `tracing_subscriber::fmt` that redacts each line before
+    /// passing it to `make_writer`
+    pub fn writer<W>(self, make_writer: W) -> RedactingWriter<W> {
+        RedactingWriter {
+            make_writer,
+            redactor: self,
+        }
+    }
+}
+
+pub struct RedactingWriter<W> {
+    make_writer: W,
+    redactor: Redactor,
+}
+
+impl<'a, W: MakeWriter<'a>> MakeWriter<'a> for RedactingWriter<W> {
+    type Writer = RedactedLine<'a, W::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine {
+            inner: self.make_writer.make_writer(),
+            redactor: &self.redactor,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Collects one formatted event, so values split across writes are still
+/// found, and writes it out redacted when flushed or dropped
+pub struct RedactedLine<'a, W: Write> {
+    inner: W,
+    redactor: &'a Redactor,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> RedactedLine<'_, W> {
+    fn write_out(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&self.buffer);
+        self.inner
+            .write_all(self.redactor.redact(&line).as_bytes())?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for RedactedLine<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_out()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactedLine<'_, W> {
+    fn drop(&mut self) {
+        let _ = self.write_out();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_redacts_personal_data() {
+        let redactor = Redactor::new(&RedactSettings::default());
+        assert_eq!(
+            redactor.redact(
+                "Email not sent (EMAIL_BACKEND=log)\nTo: sana.k+club@example.edu.pk\nSubject: Verify\n\nHi sana,\n\nYour code to confirm this as your new email address is 482913."
+            ),
+            "Email not sent (EMAIL_BACKEND=log)\nTo: [email]\nSubject: Verify\n\nHi sana,\n\nYour code to confirm this as your new email address is [otp]."
+        );
+        assert_eq!(
+            redactor.redact(
+                r#"Key (phone_number)=(+923001234567) already exists; body {"otp": "123456"}"#
+            ),
+            r#"Key (phone_number)=([phone]) already exists; body {"otp": "[otp]"}"#
+        );
+        // Timestamps, addresses and status codes are left alone
+        let plain = "2026-10-16T18:16:31Z request from 203.0.113.7 took 1500ms, status code 404";
+        assert_eq!(redactor.redact(plain), plain);
+    }
+
+    #[test]
+    fn test_allowed_kinds_stay_readable() {
+        assert_eq!(" OTP ".parse(), Ok(PiiKind::Otp));
+        assert!("name".parse::<PiiKind>().is_err());
+
+        let redactor = Redactor::new(&RedactSettings {
+            allow: vec![PiiKind::Otp],
+        });
+        assert_eq!(
+            redactor.redact("To: a@example.com\nYour verification code is 482913."),
+            "To: [email]\nYour verification code is 482913."
+        );
+    }
+
+    #[test]
+    fn test_writer_redacts_whole_lines() {
+        let writer = Redactor::new(&RedactSettings::default()).writer(Mutex::new(Vec::new()));
+        {
+            let mut line = writer.make_writer();
+            line.write_all(b"Login alert for ali").unwrap();
+            line.write_all(b"@example.com\n").unwrap();
+        }
+        let written = writer.make_writer.lock().unwrap().clone();
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "Login alert for [email]\n"
+        );
+    }
+}
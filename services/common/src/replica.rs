@@ -0,0 +1,165 @@
+//! Read replicas. A service given a replica connection string sends the
+//! reads of its public tab and statistics routes to it, so tab browsing
+//! during a result release does not compete with ballot writes on the
+//! primary.
+//!
+//! Everything else stays on the primary: writes, reads made while handling
+//! a write (which must see the latest data), other reads, background jobs,
+//! and the session, admin and API key lookups that authenticate a request,
+//! which must not act on a lagging replica after a demotion or suspension.
+//! A task checks the replica every few seconds, and while it cannot be
+//! reached reads go to the primary as if no replica were configured.
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use http::Method;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::future::Future;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+/// How often the replica is checked
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a check or a connection attempt may take before the replica
+/// counts as down
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+tokio::task_local! {
+    /// Set while a request that may read from the replica is handled
+    static READ_FROM_REPLICA: bool;
+}
+
+#[derive(Clone)]
+pub struct ReadReplica {
+    pool: PgPool,
+    healthy: Arc<AtomicBool>,
+}
+
+impl ReadReplica {
+    /// Pool for the replica at `url`, connected lazily so a replica that is
+    /// down does not stop the service starting, and a task keeping track of
+    /// whether it can be reached. Must be called inside the Tokio runtime.
+    pub fn connect(url: &str, max_connections: u32) -> Result<Self, sqlx::Error> {
+        let pool = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(CHECK_TIMEOUT)
+            .connect_lazy(url)?;
+        let replica = Self {
+            pool,
+            healthy: Arc::new(AtomicBool::new(false)),
+        };
+        tokio::spawn(replica.clone().watch());
+        Ok(replica)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    async fn watch(self) {
+        let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+        let mut first = true;
+        loop {
+            interval.tick().await;
+            let check = sqlx::query("SELECT 1").execute(&self.pool);
+            let healthy = matches!(tokio::time::timeout(CHECK_TIMEOUT, check).await, Ok(Ok(_)));
+            let was_healthy = self.healthy.swap(healthy, Ordering::Relaxed);
+            if healthy && !was_healthy {
+                tracing::info!("Read replica is reachable; GET requests read from it");
+            } else if !healthy && (was_healthy || first) {
+                tracing::warn!("Read replica is unreachable; reading from the primary");
+            }
+            first = false;
+        }
+    }
+}
+
+/// The pool a read should use: the replica when the current request may
+/// read from it and it is reachable, the primary otherwise
+pub fn reader<'a>(primary: &'a PgPool, replica: Option<&'a ReadReplica>) -> &'a PgPool {
+    match replica {
+        Some(replica) if reads_from_replica() && replica.is_healthy() => &replica.pool,
+        _ => primary,
+    }
+}
+
+fn reads_from_replica() -> bool {
+    READ_FROM_REPLICA
+        .try_with(|allowed| *allowed)
+        .unwrap_or(false)
+}
+
+/// Middleware letting `GET` and `HEAD` requests read from the replica.
+/// Services put it only on routes that can stand the replica's lag.
+pub async fn replica_reads(request: Request, next: Next) -> Response {
+    let allowed = matches!(*request.method(), Method::GET | Method::HEAD);
+    READ_FROM_REPLICA.scope(allowed, next.run(request)).await
+}
+
+/// Run `f` with all of its reads on the primary, even inside a request
+/// that may read from the replica
+pub async fn primary<F: Future>(f: F) -> F::Output {
+    READ_FROM_REPLICA.scope(false, f).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn handled(method: Method) -> String {
+        let app = Router::new()
+            .route(
+                "/tab",
+                get(|| async { reads_from_replica().to_string() })
+                    .post(|| async { reads_from_replica().to_string() }),
+            )
+            .layer(middleware::from_fn(replica_reads));
+        let request = Request::builder()
+            .method(method)
+            .uri("/tab")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_safe_requests_read_from_the_replica() {
+        assert_eq!(handled(Method::GET).await, "true");
+        assert_eq!(handled(Method::POST).await, "false");
+        // Background jobs run outside any request
+        assert!(!reads_from_replica());
+    }
+
+    #[tokio::test]
+    async fn test_primary_overrides_the_request() {
+        let pinned = READ_FROM_REPLICA
+            .scope(true, primary(async { reads_from_replica() }))
+            .await;
+        assert!(!pinned);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_replica_falls_back_to_the_primary() {
+        let primary = PgPoolOptions::new()
+            .connect_lazy("postgres://primary.invalid/tabrela")
+            .unwrap();
+        let replica = ReadReplica::connect("postgres://replica.invalid/tabrela", 1).unwrap();
+        let chosen = READ_FROM_REPLICA
+            .scope(true, async { reader(&primary, Some(&replica)).clone() })
+            .await;
+        assert!(!replica.is_healthy());
+        assert_eq!(
+            chosen.connect_options().get_host(),
+            primary.connect_options().get_host()
+        );
+    }
+}
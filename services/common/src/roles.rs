@@ -0,0 +1,69 @@
+//! Roles granted to users on top of (and independently of) admin rights.
+//! Assignments live in the `user_roles` table and are managed by admins
+//! through the auth service.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Handles code-of-conduct reports; the only role that can read them
+    Equity,
+    /// Runs training sessions; reads the ballots from training series
+    Trainer,
+    /// Plans catering and venues; the only role that can read members'
+    /// dietary and accessibility requirements
+    Welfare,
+    /// Collects event fees; marks members paid and reconciles payments
+    Treasurer,
+}
+
+impl Role {
+    pub const ALL: &'static [Role] = &[Role::Equity, Role::Trainer, Role::Welfare, Role::Treasurer];
+
+    /// Value stored in `user_roles.role`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Equity => "equity",
+            Role::Trainer => "trainer",
+            Role::Welfare => "welfare",
+            Role::Treasurer => "treasurer",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Role::ALL
+            .iter()
+            .copied()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| format!("Unknown role: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for role in Role::ALL {
+            assert_eq!(role.as_str().parse::<Role>().unwrap(), *role);
+            assert_eq!(
+                serde_json::to_value(role).unwrap(),
+                serde_json::json!(role.as_str())
+            );
+        }
+        assert!("admin".parse::<Role>().is_err());
+    }
+}
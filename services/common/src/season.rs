@@ -0,0 +1,31 @@
+//! Seasons: named spans of dates ("2024-25", "Spring term") that merit,
+//! awards, performance and standings can be filtered to with `season_id`.
+//! The merit service manages them; the other services only read the
+//! `seasons` table through [`in_season`].
+
+/// SQL condition that holds when the timestamp `column` falls on one of the
+/// days of the season whose id is bound at `param` (e.g. `"$2"`), or always
+/// when that parameter is NULL. Days are UTC dates, ends inclusive.
+pub fn in_season(column: &str, param: &str) -> String {
+    format!(
+        "({param}::uuid IS NULL OR EXISTS (SELECT 1 FROM seasons season \
+         WHERE season.id = {param}::uuid \
+         AND ({column} AT TIME ZONE 'UTC')::date BETWEEN season.starts_on AND season.ends_on))",
+        param = param,
+        column = column
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_season_binds_column_and_parameter() {
+        let condition = in_season("mh.created_at", "$2");
+        assert!(condition.starts_with("($2::uuid IS NULL OR EXISTS"));
+        assert!(condition.contains("season.id = $2::uuid"));
+        assert!(condition.contains("(mh.created_at AT TIME ZONE 'UTC')::date BETWEEN"));
+        assert!(condition.ends_with("))"));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config::ConfigError;
+use sqlx::migrate::MigrateError;
+use std::fmt;
+
+/// Why a service could not start. Each variant exits with its own code so
+/// supervisors and deploy logs can tell the failures apart.
+#[derive(Debug)]
+pub enum StartupError {
+    Config(ConfigError),
+    Database(sqlx::Error),
+    Migration(MigrateError),
+}
+
+impl StartupError {
+    /// Process exit code for this failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StartupError::Config(_) => 2,
+            StartupError::Database(_) => 3,
+            StartupError::Migration(_) => 4,
+        }
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupError::Config(e) => write!(f, "{}", e),
+            StartupError::Database(e) => write!(
+                f,
+                "Could not connect to the database: {}. Check DATABASE_URL and that Postgres is reachable.",
+                e
+            ),
+            StartupError::Migration(e) => write!(
+                f,
+                "Database migration failed: {}. Earlier migrations stay applied; fix the failing one and restart.",
+                e
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StartupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StartupError::Config(e) => Some(e),
+            StartupError::Database(e) => Some(e),
+            StartupError::Migration(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConfigError> for StartupError {
+    fn from(e: ConfigError) -> Self {
+        StartupError::Config(e)
+    }
+}
+
+impl From<sqlx::Error> for StartupError {
+    fn from(e: sqlx::Error) -> Self {
+        StartupError::Database(e)
+    }
+}
+
+impl From<MigrateError> for StartupError {
+    fn from(e: MigrateError) -> Self {
+        StartupError::Migration(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let config = StartupError::from(ConfigError {
+            problems: vec!["JWT_SECRET must be set".to_string()],
+        });
+        let database = StartupError::from(sqlx::Error::PoolTimedOut);
+        let migration = StartupError::from(MigrateError::VersionMissing(1));
+
+        assert_eq!(config.exit_code(), 2);
+        assert_eq!(database.exit_code(), 3);
+        assert_eq!(migration.exit_code(), 4);
+
+        assert!(config.to_string().contains("JWT_SECRET must be set"));
+        assert!(database.to_string().contains("DATABASE_URL"));
+        assert!(migration
+            .to_string()
+            .starts_with("Database migration failed"));
+    }
+}
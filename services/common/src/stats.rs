@@ -0,0 +1,27 @@
+//! Shapes shared by each service's `/admin/stats` report, so the gateway can
+//! combine them into one response.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Weeks of sign-ups a report covers, including the current one
+pub const STATS_WEEKS: i32 = 12;
+
+/// Months of events and merit changes a report covers, including the current one
+pub const STATS_MONTHS: i32 = 12;
+
+/// How recently a user must have signed in to count as active
+pub const ACTIVE_DAYS: i32 = 30;
+
+/// Rows counted in the week or month starting at `period`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeriodCount {
+    pub period: DateTime<Utc>,
+    pub count: i64,
+}
+
+impl From<(DateTime<Utc>, i64)> for PeriodCount {
+    fn from((period, count): (DateTime<Utc>, i64)) -> Self {
+        Self { period, count }
+    }
+}
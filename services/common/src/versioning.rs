@@ -0,0 +1,303 @@
+//! API versions. Each service serves its routes under a version prefix such
+//! as `/v1`, and can serve several versions side by side while clients move
+//! over. Routes kept only for older clients, the original unversioned paths
+//! among them, answer with `Deprecation` (RFC 9745) and `Sunset` (RFC 8594)
+//! headers and a `Link` to the same route in the version that replaces them.
+
+use axum::{
+    extract::{OriginalUri, Request},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use chrono::NaiveDate;
+use http::{header, HeaderMap, HeaderName, HeaderValue};
+use std::sync::Arc;
+
+use crate::config::{ConfigVar, EnvReader};
+
+/// Version new clients should use
+pub const CURRENT_VERSION: &str = "v1";
+
+pub const DEPRECATION_HEADER: &str = "deprecation";
+pub const SUNSET_HEADER: &str = "sunset";
+
+/// Environment variables read by [`Deprecation::read_unversioned`]
+pub const VERSION_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default(
+        "API_UNVERSIONED_DEPRECATED",
+        "2026-10-16",
+        "Date (YYYY-MM-DD) the unversioned API paths were deprecated in favour of /v1",
+    ),
+    ConfigVar::optional(
+        "API_UNVERSIONED_SUNSET",
+        "Date (YYYY-MM-DD) the unversioned API paths stop being served, announced in Sunset headers",
+    ),
+    ConfigVar::optional(
+        "API_DEPRECATION_URL",
+        "Page describing API changes, linked from deprecated routes",
+    ),
+];
+
+/// When routes were deprecated, and when they will be removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    pub since: NaiveDate,
+    pub sunset: Option<NaiveDate>,
+    /// Page explaining the change, sent as a `rel="deprecation"` link
+    pub info_url: Option<String>,
+}
+
+impl Deprecation {
+    /// Read the variables in [`VERSION_SCHEMA`], which describe the
+    /// unversioned paths, recording any problems on `reader`
+    pub fn read_unversioned(reader: &mut EnvReader) -> Self {
+        let since = reader.string("API_UNVERSIONED_DEPRECATED");
+        let since = reader.check(parse_date("API_UNVERSIONED_DEPRECATED", &since));
+        let sunset = reader
+            .optional("API_UNVERSIONED_SUNSET")
+            .and_then(|raw| reader.check(parse_date("API_UNVERSIONED_SUNSET", &raw)));
+        let info_url = reader.optional("API_DEPRECATION_URL");
+        if let Some(url) = &info_url {
+            reader.check(
+                HeaderValue::from_str(url)
+                    .map(|_| ())
+                    .map_err(|_| format!("API_DEPRECATION_URL is not a valid URL: {}", url)),
+            );
+        }
+
+        Self {
+            since: since.unwrap_or_default(),
+            sunset,
+            info_url,
+        }
+    }
+
+    /// Headers announcing the deprecation, linking to `successor` if known
+    pub fn headers(&self, successor: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let since = self.since.and_hms_opt(0, 0, 0).unwrap_or_default();
+        insert(
+            &mut headers,
+            HeaderName::from_static(DEPRECATION_HEADER),
+            format!("@{}", since.and_utc().timestamp()),
+        );
+        if let Some(sunset) = self.sunset {
+            insert(
+                &mut headers,
+                HeaderName::from_static(SUNSET_HEADER),
+                sunset.format("%a, %d %b %Y 00:00:00 GMT").to_string(),
+            );
+        }
+
+        let links: Vec<String> = self
+            .info_url
+            .iter()
+            .map(|url| format!("<{}>; rel=\"deprecation\"", url))
+            .chain(successor.map(|path| format!("<{}>; rel=\"successor-version\"", path)))
+            .collect();
+        if !links.is_empty() {
+            insert(&mut headers, header::LINK, links.join(", "));
+        }
+        headers
+    }
+}
+
+fn parse_date(name: &str, raw: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("{} must be a date like 2027-01-31, got {}", name, raw))
+}
+
+fn insert(headers: &mut HeaderMap, name: HeaderName, value: String) {
+    if let Ok(value) = HeaderValue::from_str(&value) {
+        headers.insert(name, value);
+    }
+}
+
+/// The routes a service serves, one router per version
+#[derive(Default)]
+pub struct Versions {
+    router: Router,
+}
+
+impl Versions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `routes` under `/{version}`
+    pub fn mount(mut self, version: &str, routes: Router) -> Self {
+        self.router = self.router.nest(&format!("/{}", version), routes);
+        self
+    }
+
+    /// Serve `routes` under `/{version}` for older clients, pointing them
+    /// at the same path under `/{successor}`
+    pub fn mount_deprecated(
+        self,
+        version: &str,
+        routes: Router,
+        deprecation: Deprecation,
+        successor: &str,
+    ) -> Self {
+        let from = format!("/{}", version);
+        let routes = deprecate(routes, deprecation, from, format!("/{}", successor));
+        self.mount(version, routes)
+    }
+
+    /// Keep serving `routes` at the service root, as before versioning,
+    /// pointing clients at the same path under the current version
+    pub fn unversioned(mut self, routes: Router, deprecation: Deprecation) -> Self {
+        let routes = deprecate(
+            routes,
+            deprecation,
+            String::new(),
+            format!("/{}", CURRENT_VERSION),
+        );
+        self.router = self.router.merge(routes);
+        self
+    }
+
+    pub fn into_router(self) -> Router {
+        self.router
+    }
+}
+
+fn deprecate(routes: Router, deprecation: Deprecation, from: String, to: String) -> Router {
+    let deprecation = Arc::new(deprecation);
+    let from = Arc::new(from);
+    let to = Arc::new(to);
+    routes.layer(middleware::from_fn(move |request: Request, next: Next| {
+        let (deprecation, from, to) = (deprecation.clone(), from.clone(), to.clone());
+        async move {
+            let path = request.uri().path().to_string();
+            let original = request
+                .extensions()
+                .get::<OriginalUri>()
+                .map(|uri| uri.0.clone())
+                .unwrap_or_else(|| request.uri().clone());
+            let successor =
+                successor_path(original.path(), &path, &from, &to).map(|successor| match original
+                    .query()
+                {
+                    Some(query) => format!("{}?{}", successor, query),
+                    None => successor,
+                });
+
+            let mut response: Response = next.run(request).await;
+            for (name, value) in &deprecation.headers(successor.as_deref()) {
+                response.headers_mut().insert(name, value.clone());
+            }
+            response
+        }
+    }))
+}
+
+/// The full path of a request to `path` (relative to where the version was
+/// mounted, at `from`) in the version mounted at `to`. `original` is the
+/// path as the client sent it, including any gateway prefix.
+pub fn successor_path(original: &str, path: &str, from: &str, to: &str) -> Option<String> {
+    let base = original.strip_suffix(&format!("{}{}", from, path))?;
+    Some(format!("{}{}{}", base, to, path))
+}
+
+/// `path` without a leading version segment such as `/v1`, so settings
+/// written against unversioned paths apply to every version
+pub fn strip_version(path: &str) -> &str {
+    let Some(rest) = path.strip_prefix("/v") else {
+        return path;
+    };
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return path;
+    }
+    match &rest[digits..] {
+        "" => "/",
+        remainder if remainder.starts_with('/') => remainder,
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get};
+    use http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    fn deprecation() -> Deprecation {
+        Deprecation {
+            since: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+            sunset: Some(NaiveDate::from_ymd_opt(2027, 4, 1).unwrap()),
+            info_url: None,
+        }
+    }
+
+    async fn get_headers(app: &Router, uri: &str) -> (StatusCode, HeaderMap) {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        (response.status(), response.headers().clone())
+    }
+
+    #[test]
+    fn test_headers() {
+        let mut d = deprecation();
+        d.info_url = Some("https://docs.example/api".to_string());
+        let headers = d.headers(Some("/v1/events"));
+        assert_eq!(headers[DEPRECATION_HEADER], "@1792108800");
+        assert_eq!(headers[SUNSET_HEADER], "Thu, 01 Apr 2027 00:00:00 GMT");
+        assert_eq!(
+            headers[header::LINK],
+            "<https://docs.example/api>; rel=\"deprecation\", </v1/events>; rel=\"successor-version\""
+        );
+    }
+
+    #[test]
+    fn test_successor_path() {
+        assert_eq!(
+            successor_path("/events/1", "/events/1", "", "/v1").as_deref(),
+            Some("/v1/events/1")
+        );
+        assert_eq!(
+            successor_path("/api/merit/v1/awards", "/awards", "/v1", "/v2").as_deref(),
+            Some("/api/merit/v2/awards")
+        );
+        assert_eq!(successor_path("/other", "/awards", "", "/v1"), None);
+    }
+
+    #[test]
+    fn test_strip_version() {
+        assert_eq!(strip_version("/v1/admin/users"), "/admin/users");
+        assert_eq!(strip_version("/v12"), "/");
+        assert_eq!(strip_version("/admin"), "/admin");
+        assert_eq!(strip_version("/venues"), "/venues");
+        assert_eq!(strip_version("/v1beta/x"), "/v1beta/x");
+    }
+
+    #[tokio::test]
+    async fn test_only_old_routes_are_deprecated() {
+        let routes = Router::new().route("/events", get(|| async { "events" }));
+        let app = Router::new().nest(
+            "/api/attendance",
+            Versions::new()
+                .mount(CURRENT_VERSION, routes.clone())
+                .unversioned(routes, deprecation())
+                .into_router(),
+        );
+
+        let (status, headers) = get_headers(&app, "/api/attendance/v1/events").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(headers.get(DEPRECATION_HEADER).is_none());
+
+        let (status, headers) = get_headers(&app, "/api/attendance/events?page=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(headers.get(SUNSET_HEADER).is_some());
+        assert_eq!(
+            headers[header::LINK],
+            "</api/attendance/v1/events?page=2>; rel=\"successor-version\""
+        );
+    }
+}
@@ -1,4 +1,5 @@
 from flask import Flask, request, jsonify
+from markupsafe import escape
 from flask_cors import CORS
 import resend
 import os
@@ -11,7 +12,9 @@ from pydantic import ValidationError
 from models import (
     VerificationEmailRequest,
     PasswordResetEmailRequest,
+    EmailChangeEmailRequest,
     WelcomeEmailRequest,
+    NewLoginEmailRequest,
     EmailResponse,
     ErrorResponse,
     HealthResponse
@@ -200,6 +203,72 @@ def send_password_reset_email():
         return jsonify(error.model_dump()), 500
 
 
+@app.route("/api/send-email-change-email", methods=["POST"])
+def send_email_change_email():
+    """Send the OTP confirming a new email address"""
+    if not verify_api_key():
+        error = ErrorResponse(error="Unauthorized")
+        return jsonify(error.model_dump()), 401
+
+    try:
+        # Validate request data using Pydantic
+        data = request.json
+        validated_data = EmailChangeEmailRequest(**data)
+
+        to_email = validated_data.to_email
+        username = escape(validated_data.username)
+        otp = validated_data.otp
+
+        html = f"""
+        <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto;">
+            <div style="background: linear-gradient(135deg, #667eea 0%, #764ba2 100%); color: white; padding: 30px; text-align: center; border-radius: 10px 10px 0 0;">
+                <h1 style="margin: 0;">Confirm Your New Email</h1>
+            </div>
+            <div style="background: #f9fafb; padding: 30px; border-radius: 0 0 10px 10px;">
+                <h2 style="color: #333;">Hi {username},</h2>
+                <p style="color: #333; line-height: 1.6;">You asked to use this address for your Tabrela account. Enter the code below to confirm it:</p>
+
+                <div style="background: white; border: 2px dashed #667eea; padding: 20px; text-align: center; border-radius: 10px; margin: 20px 0;">
+                    <div style="font-size: 36px; font-weight: bold; letter-spacing: 8px; color: #667eea; font-family: 'Courier New', monospace;">{otp}</div>
+                </div>
+
+                <p style="color: #333;">If you didn't ask to change your email, you can safely ignore this email.</p>
+
+                <div style="text-align: center; margin-top: 30px; color: #6b7280; font-size: 12px;">
+                    <p>&copy; 2025 Tabrela. All rights reserved.</p>
+                </div>
+            </div>
+        </div>
+        """
+
+        response = resend.Emails.send({
+            "from": FROM_EMAIL,
+            "to": to_email,
+            "subject": "Confirm Your New Email Address - OTP Code",
+            "html": html
+        })
+
+        logger.info(f"Email change OTP sent to {to_email}")
+        email_response = EmailResponse(
+            success=True,
+            email_id=response.get("id"),
+            message="Email change email sent successfully"
+        )
+        return jsonify(email_response.model_dump()), 200
+
+    except ValidationError as e:
+        logger.error(f"Validation error: {e.errors()}")
+        error = ErrorResponse(
+            error="Validation error",
+            details={"errors": e.errors()}
+        )
+        return jsonify(error.model_dump()), 400
+    except Exception as e:
+        logger.error(f"Error sending email change email: {str(e)}")
+        error = ErrorResponse(error=str(e))
+        return jsonify(error.model_dump()), 500
+
+
 @app.route("/api/send-welcome-email", methods=["POST"])
 def send_welcome_email():
     """Send welcome email after email verification"""
@@ -264,6 +333,76 @@ def send_welcome_email():
         return jsonify(error.model_dump()), 500
 
 
+@app.route("/api/send-new-login-email", methods=["POST"])
+def send_new_login_email():
+    """Send an alert about a login from a new device or country"""
+    if not verify_api_key():
+        error = ErrorResponse(error="Unauthorized")
+        return jsonify(error.model_dump()), 401
+
+    try:
+        # Validate request data using Pydantic
+        data = request.json
+        validated_data = NewLoginEmailRequest(**data)
+
+        to_email = validated_data.to_email
+        username = escape(validated_data.username)
+        details = f"<li><strong>Device:</strong> {escape(validated_data.device)}</li>"
+        if validated_data.ip_address:
+            details += f"<li><strong>IP address:</strong> {escape(validated_data.ip_address)}</li>"
+        if validated_data.country:
+            details += f"<li><strong>Country:</strong> {validated_data.country}</li>"
+
+        html = f"""
+        <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto;">
+            <div style="background: linear-gradient(135deg, #667eea 0%, #764ba2 100%); color: white; padding: 30px; text-align: center; border-radius: 10px 10px 0 0;">
+                <h1 style="margin: 0;">New Sign-in</h1>
+            </div>
+            <div style="background: #f9fafb; padding: 30px; border-radius: 0 0 10px 10px;">
+                <h2 style="color: #333;">Hi {username},</h2>
+                <p style="color: #333; line-height: 1.6;">Your Tabrela account was just signed in to from a device or location we haven't seen before:</p>
+                <ul style="color: #333; line-height: 1.6;">{details}</ul>
+                <p style="color: #333;">If this was you, there is nothing to do.</p>
+                <p style="color: #333;"><strong>If it wasn't, reset your password now</strong> and review your login history.</p>
+                <div style="text-align: center; margin: 20px 0;">
+                    <a href="{FRONTEND_URL}" style="display: inline-block; background: #667eea; color: white; padding: 15px 30px; text-decoration: none; border-radius: 5px;">Go to Tabrela</a>
+                </div>
+
+                <div style="text-align: center; margin-top: 30px; color: #6b7280; font-size: 12px;">
+                    <p>&copy; 2025 Tabrela. All rights reserved.</p>
+                </div>
+            </div>
+        </div>
+        """
+
+        response = resend.Emails.send({
+            "from": FROM_EMAIL,
+            "to": to_email,
+            "subject": "New sign-in to your Tabrela account",
+            "html": html
+        })
+
+        logger.info(f"New login email sent to {to_email}")
+        email_response = EmailResponse(
+            success=True,
+            email_id=response.get("id"),
+            message="New login email sent successfully"
+        )
+        return jsonify(email_response.model_dump()), 200
+
+    except ValidationError as e:
+        logger.error(f"Validation error: {e.errors()}")
+        error = ErrorResponse(
+            error="Validation error",
+            details={"errors": e.errors()}
+        )
+        return jsonify(error.model_dump()), 400
+    except Exception as e:
+        logger.error(f"Error sending new login email: {str(e)}")
+        error = ErrorResponse(error=str(e))
+        return jsonify(error.model_dump()), 500
+
+
 if __name__ == "__main__":
     port = int(os.getenv("PORT", 5000))
     debug = os.getenv("DEBUG", "False").lower() == "true"
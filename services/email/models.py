@@ -150,6 +150,68 @@ class WelcomeEmailRequest(BaseModel):
     }
 
 
+class EmailChangeEmailRequest(VerificationEmailRequest):
+    """Model for the code sent to a new address when a user changes their email"""
+
+
+class NewLoginEmailRequest(BaseModel):
+    """Model for new device or country login alert request"""
+    to_email: EmailStr = Field(
+        ...,
+        description="Recipient email address",
+        examples=["user@example.com"]
+    )
+    username: str = Field(
+        ...,
+        min_length=3,
+        max_length=50,
+        description="Username of the recipient",
+        examples=["johndoe"]
+    )
+    device: str = Field(
+        ...,
+        min_length=1,
+        max_length=64,
+        description="Browser and operating system of the login",
+        examples=["Firefox on Linux"]
+    )
+    ip_address: Optional[str] = Field(
+        default=None,
+        max_length=64,
+        description="Client IP address, when known",
+        examples=["203.0.113.7"]
+    )
+    country: Optional[str] = Field(
+        default=None,
+        pattern=r"^[A-Z]{2}$",
+        description="ISO 3166-1 alpha-2 country code, when known",
+        examples=["PK"]
+    )
+
+    @field_validator('username')
+    @classmethod
+    def validate_username(cls, v: str) -> str:
+        """Validate username format"""
+        if not v.strip():
+            raise ValueError("Username cannot be empty or whitespace only")
+        return v.strip()
+
+    model_config = {
+        "str_strip_whitespace": True,
+        "json_schema_extra": {
+            "examples": [
+                {
+                    "to_email": "user@example.com",
+                    "username": "johndoe",
+                    "device": "Firefox on Linux",
+                    "ip_address": "203.0.113.7",
+                    "country": "PK"
+                }
+            ]
+        }
+    }
+
+
 class EmailResponse(BaseModel):
     """Model for successful email response"""
     success: bool = Field(
@@ -6,7 +6,9 @@ Run this to verify all models work correctly with strict type checking.
 from models import (
     VerificationEmailRequest,
     PasswordResetEmailRequest,
+    EmailChangeEmailRequest,
     WelcomeEmailRequest,
+    NewLoginEmailRequest,
     EmailResponse,
     ErrorResponse,
     HealthResponse
@@ -102,6 +104,33 @@ def test_password_reset_email_request():
         print(f"✗ Unexpected validation error: {e}")
 
 
+def test_email_change_email_request():
+    """Test EmailChangeEmailRequest validation"""
+    print("\n=== Testing EmailChangeEmailRequest ===")
+    
+    # Valid request
+    try:
+        valid = EmailChangeEmailRequest(
+            to_email="new@example.com",
+            username="johndoe",
+            otp="123456"
+        )
+        print(f"✓ Valid request: {valid.model_dump()}")
+    except ValidationError as e:
+        print(f"✗ Unexpected validation error: {e}")
+    
+    # Invalid OTP
+    try:
+        invalid = EmailChangeEmailRequest(
+            to_email="new@example.com",
+            username="johndoe",
+            otp="12ab56"
+        )
+        print(f"✗ Should have failed: invalid OTP")
+    except ValidationError as e:
+        print(f"✓ Caught invalid OTP: {e.error_count()} error(s)")
+
+
 def test_welcome_email_request():
     """Test WelcomeEmailRequest validation"""
     print("\n=== Testing WelcomeEmailRequest ===")
@@ -126,6 +155,34 @@ def test_welcome_email_request():
         print(f"✓ Caught missing field: {e.error_count()} error(s)")
 
 
+def test_new_login_email_request():
+    """Test NewLoginEmailRequest validation"""
+    print("\n=== Testing NewLoginEmailRequest ===")
+    
+    # Valid request without the optional fields
+    try:
+        valid = NewLoginEmailRequest(
+            to_email="user@example.com",
+            username="johndoe",
+            device="Firefox on Linux"
+        )
+        print(f"✓ Valid request: {valid.model_dump()}")
+    except ValidationError as e:
+        print(f"✗ Unexpected validation error: {e}")
+    
+    # Invalid country code
+    try:
+        invalid = NewLoginEmailRequest(
+            to_email="user@example.com",
+            username="johndoe",
+            device="Firefox on Linux",
+            country="Pakistan"
+        )
+        print(f"✗ Should have failed: invalid country")
+    except ValidationError as e:
+        print(f"✓ Caught invalid country: {e.error_count()} error(s)")
+
+
 def test_email_response():
     """Test EmailResponse model"""
     print("\n=== Testing EmailResponse ===")
@@ -208,7 +265,9 @@ if __name__ == "__main__":
     
     test_verification_email_request()
     test_password_reset_email_request()
+    test_email_change_email_request()
     test_welcome_email_request()
+    test_new_login_email_request()
     test_email_response()
     test_error_response()
     test_health_response()
@@ -0,0 +1,41 @@
+use common::config::{load_dotenv, render_schema, ConfigError, ConfigVar, EnvReader};
+
+/// Environment variables the gateway itself reads; each mounted service
+/// still reads its own.
+pub const SCHEMA: &[ConfigVar] = &[
+    ConfigVar::default("GATEWAY_HOST", "0.0.0.0", "Address to listen on").aliases(&["HOST"]),
+    ConfigVar::default("GATEWAY_PORT", "8080", "Port to listen on").aliases(&["PORT"]),
+];
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        load_dotenv();
+
+        let mut env = EnvReader::new(&[SCHEMA]);
+        let config = Config {
+            host: env.string("GATEWAY_HOST"),
+            port: env.parse("GATEWAY_PORT"),
+        };
+        env.finish()?;
+
+        Ok(config)
+    }
+
+    /// Annotated `.env` template covering the gateway and every mounted service
+    pub fn schema_doc() -> String {
+        [
+            render_schema("Gateway", &[SCHEMA]),
+            auth::Config::schema_doc(),
+            attendance::Config::schema_doc(),
+            merit::Config::schema_doc(),
+            tabulation::Config::schema_doc(),
+        ]
+        .join("\n")
+    }
+}
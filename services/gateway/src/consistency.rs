@@ -0,0 +1,263 @@
+//! Cross-service referential integrity checks.
+//!
+//! Every service shares one schema, but many columns naming another
+//! service's rows (users above all) have no foreign key, and those that do
+//! cascade differently. Each `Reference` below names such a column; a row is
+//! an orphan when the column is set but its target row is gone. Checks of
+//! columns that do have a foreign key confirm the constraint still holds.
+//!
+//! Repairs are deliberately conservative. Rows that count towards tabs or
+//! form an audit trail are only ever reported, for an admin to resolve by
+//! hand; only rows that mean nothing without their target are deleted, and
+//! optional columns are cleared.
+
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use common::{error::db_error, ApiError};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::GatewayState;
+
+/// Orphaned row ids listed for each check
+pub const SAMPLE_SIZE: i64 = 20;
+
+/// What a repair does to a check's orphans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Repair {
+    /// Delete the orphaned rows
+    Delete,
+    /// Clear the dangling column
+    SetNull,
+    /// Leave them for an admin
+    ReportOnly,
+}
+
+/// A column holding the id of a row in another table
+#[derive(Debug, Clone, Copy)]
+pub struct Reference {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub target: &'static str,
+    pub repair: Repair,
+}
+
+const fn reference(
+    table: &'static str,
+    column: &'static str,
+    target: &'static str,
+    repair: Repair,
+) -> Reference {
+    Reference {
+        table,
+        column,
+        target,
+        repair,
+    }
+}
+
+/// Every reference checked, grouped by the service that owns the table
+pub const REFERENCES: &[Reference] = &[
+    // Attendance
+    reference("events", "created_by", "users", Repair::ReportOnly),
+    reference("events", "archived_by", "users", Repair::ReportOnly),
+    reference("attendance_records", "event_id", "events", Repair::Delete),
+    reference("attendance_records", "user_id", "users", Repair::Delete),
+    reference(
+        "attendance_records",
+        "checked_in_by",
+        "users",
+        Repair::SetNull,
+    ),
+    // Merit
+    reference("merit_history", "admin_id", "users", Repair::SetNull),
+    reference("awards", "awarded_by", "users", Repair::SetNull),
+    reference("award_history", "admin_id", "users", Repair::SetNull),
+    // Tabulation
+    reference("match_series", "created_by", "users", Repair::ReportOnly),
+    reference("allocations", "user_id", "users", Repair::ReportOnly),
+    reference("allocations", "allocated_by", "users", Repair::ReportOnly),
+    reference("ballots", "match_id", "matches", Repair::Delete),
+    reference("ballots", "adjudicator_id", "users", Repair::ReportOnly),
+    reference("allocation_history", "user_id", "users", Repair::ReportOnly),
+    reference(
+        "allocation_history",
+        "changed_by",
+        "users",
+        Repair::ReportOnly,
+    ),
+];
+
+impl Reference {
+    /// `table.column`, as reported
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.table, self.column)
+    }
+
+    /// Condition on rows of `table`, aliased `t`, that are orphans
+    fn orphan_condition(&self) -> String {
+        format!(
+            "t.{column} IS NOT NULL AND NOT EXISTS (SELECT 1 FROM {target} x WHERE x.id = t.{column})",
+            column = self.column,
+            target = self.target
+        )
+    }
+
+    /// Counts the orphans and lists up to $1 of their ids
+    pub fn check_sql(&self) -> String {
+        format!(
+            "SELECT COUNT(*), (ARRAY_AGG(t.id ORDER BY t.id))[1:$1] FROM {} t WHERE {}",
+            self.table,
+            self.orphan_condition()
+        )
+    }
+
+    /// Statement that repairs the orphans, if this check repairs any
+    pub fn repair_sql(&self) -> Option<String> {
+        match self.repair {
+            Repair::Delete => Some(format!(
+                "DELETE FROM {} t WHERE {}",
+                self.table,
+                self.orphan_condition()
+            )),
+            Repair::SetNull => Some(format!(
+                "UPDATE {table} t SET {column} = NULL WHERE {condition}",
+                table = self.table,
+                column = self.column,
+                condition = self.orphan_condition()
+            )),
+            Repair::ReportOnly => None,
+        }
+    }
+}
+
+/// The outcome of one check
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub check: String,
+    pub references: &'static str,
+    pub orphans: i64,
+    /// Ids of some of the orphaned rows
+    pub sample_ids: Vec<Uuid>,
+    pub repair: Repair,
+    /// Rows repaired, when a repair was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repaired: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+async fn check(pool: &PgPool, reference: &Reference) -> Result<Finding, sqlx::Error> {
+    let (orphans, sample_ids): (i64, Option<Vec<Uuid>>) = sqlx::query_as(&reference.check_sql())
+        .bind(SAMPLE_SIZE)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(Finding {
+        check: reference.name(),
+        references: reference.target,
+        orphans,
+        sample_ids: sample_ids.unwrap_or_default(),
+        repair: reference.repair,
+        repaired: None,
+        error: None,
+    })
+}
+
+/// Repair one check's orphans. A failure, such as an orphan in an archived
+/// event, is recorded against the check and leaves the others to run.
+async fn repair(pool: &PgPool, reference: &Reference, finding: &mut Finding) {
+    let Some(sql) = reference.repair_sql() else {
+        return;
+    };
+    if finding.orphans == 0 {
+        finding.repaired = Some(0);
+        return;
+    }
+
+    match sqlx::query(&sql).execute(pool).await {
+        Ok(result) => finding.repaired = Some(result.rows_affected()),
+        Err(e) => {
+            tracing::error!("Repairing {} failed: {:?}", finding.check, e);
+            finding.repaired = Some(0);
+            finding.error = Some("Repair failed; the rows may belong to an archived event".into());
+        }
+    }
+}
+
+fn report(checked_at: DateTime<Utc>, findings: Vec<Finding>) -> Json<Value> {
+    let total_orphans: i64 = findings.iter().map(|f| f.orphans).sum();
+    Json(json!({
+        "checked_at": checked_at,
+        "total_orphans": total_orphans,
+        "findings": findings,
+    }))
+}
+
+/// Orphans of every reference (admin only)
+pub async fn consistency_report(
+    State(state): State<GatewayState>,
+) -> Result<Json<Value>, ApiError> {
+    let pool = state.auth.db.pool();
+    let mut findings = Vec::with_capacity(REFERENCES.len());
+    for reference in REFERENCES {
+        findings.push(check(pool, reference).await.map_err(db_error)?);
+    }
+
+    Ok(report(Utc::now(), findings))
+}
+
+/// Repair what can be repaired, then report each check as it stood before
+/// with the rows repaired (admin only)
+pub async fn repair_orphans(State(state): State<GatewayState>) -> Result<Json<Value>, ApiError> {
+    let pool = state.auth.db.pool();
+    let mut findings = Vec::with_capacity(REFERENCES.len());
+    for reference in REFERENCES {
+        let mut finding = check(pool, reference).await.map_err(db_error)?;
+        repair(pool, reference, &mut finding).await;
+        findings.push(finding);
+    }
+
+    tracing::info!(
+        "Repaired {} orphaned rows",
+        findings.iter().filter_map(|f| f.repaired).sum::<u64>()
+    );
+    Ok(report(Utc::now(), findings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repairs_touch_only_orphans() {
+        let delete = reference("attendance_records", "user_id", "users", Repair::Delete);
+        assert_eq!(
+            delete.repair_sql().unwrap(),
+            "DELETE FROM attendance_records t WHERE t.user_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM users x WHERE x.id = t.user_id)"
+        );
+
+        let clear = reference("awards", "awarded_by", "users", Repair::SetNull);
+        assert!(clear
+            .repair_sql()
+            .unwrap()
+            .starts_with("UPDATE awards t SET awarded_by = NULL WHERE t.awarded_by IS NOT NULL"));
+
+        let report = reference("ballots", "adjudicator_id", "users", Repair::ReportOnly);
+        assert_eq!(report.repair_sql(), None);
+    }
+
+    #[test]
+    fn test_results_and_audit_trails_are_never_repaired() {
+        for reference in REFERENCES {
+            if matches!(reference.table, "allocations" | "allocation_history")
+                || reference.name() == "ballots.adjudicator_id"
+            {
+                assert_eq!(reference.repair, Repair::ReportOnly, "{}", reference.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+pub mod config;
+pub mod consistency;
+pub mod profile;
+pub mod startup;
+pub mod stats;
+
+pub use config::Config;
+pub use startup::StartupError;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+
+/// Path prefixes each service is mounted under. These match the nginx
+/// gateway (docker/nginx.conf) so frontends work against either deployment.
+pub const AUTH_PREFIX: &str = "/api/auth";
+pub const ATTENDANCE_PREFIX: &str = "/api/attendance";
+pub const MERIT_PREFIX: &str = "/api/merit";
+pub const TABULATION_PREFIX: &str = "/api/tabulation";
+
+/// Reports that combine every service, outside any one service's prefix
+pub const ADMIN_STATS_PATH: &str = "/api/admin/stats";
+pub const CONSISTENCY_PATH: &str = "/api/admin/consistency";
+pub const CONSISTENCY_REPAIR_PATH: &str = "/api/admin/consistency/repair";
+pub const FULL_PROFILE_PATH: &str = "/api/users/:username/full-profile";
+
+/// State for every mounted service. Each keeps its own database pool and
+/// configuration, exactly as when run standalone.
+#[derive(Clone)]
+pub struct GatewayState {
+    pub auth: Arc<auth::AppState>,
+    pub attendance: Arc<attendance::AppState>,
+    pub merit: Arc<merit::AppState>,
+    pub tabulation: Arc<tabulation::AppState>,
+}
+
+/// Load each service's configuration and build its state
+pub async fn build_state() -> Result<GatewayState, StartupError> {
+    let auth = async { auth::build_state(auth::Config::from_env()?).await }
+        .await
+        .map_err(StartupError::Auth)?;
+    let attendance = async { attendance::build_state(attendance::Config::from_env()?).await }
+        .await
+        .map_err(StartupError::Attendance)?;
+    let merit = async { merit::build_state(merit::Config::from_env()?).await }
+        .await
+        .map_err(StartupError::Merit)?;
+    let tabulation = async { tabulation::build_state(tabulation::Config::from_env()?).await }
+        .await
+        .map_err(StartupError::Tabulation)?;
+
+    Ok(GatewayState {
+        auth,
+        attendance,
+        merit,
+        tabulation,
+    })
+}
+
+/// Build a single router serving all four services
+pub fn create_app(state: GatewayState) -> Router {
+    // Signed-in admins are checked by the auth service, as on its own routes
+    let admin_routes = Router::new()
+        .route(ADMIN_STATS_PATH, get(stats::admin_stats))
+        .route(CONSISTENCY_PATH, get(consistency::consistency_report))
+        .route(CONSISTENCY_REPAIR_PATH, post(consistency::repair_orphans))
+        .route_layer(middleware::from_fn_with_state(
+            state.auth.clone(),
+            auth::auth_middleware::admin_middleware,
+        ))
+        .layer(DefaultBodyLimit::max(state.auth.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.auth.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.auth.config.compression.layer())
+        .layer(common::configure_cors(&state.auth.config.cors, &[]))
+        .with_state(state.clone());
+
+    // Signing in is optional; each section decides who may see it
+    let profile_routes = Router::new()
+        .route(FULL_PROFILE_PATH, get(profile::full_profile))
+        .route_layer(middleware::from_fn_with_state(
+            state.tabulation.clone(),
+            tabulation::auth_middleware::optional_auth_middleware::<tabulation::AppState>,
+        ))
+        .layer(DefaultBodyLimit::max(state.auth.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.auth.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.auth.config.compression.layer())
+        .layer(common::configure_cors(&state.auth.config.cors, &[]))
+        .with_state(state.clone());
+
+    Router::new()
+        .merge(admin_routes)
+        .merge(profile_routes)
+        .nest(AUTH_PREFIX, auth::create_app(state.auth))
+        .nest(ATTENDANCE_PREFIX, attendance::create_app(state.attendance))
+        .nest(MERIT_PREFIX, merit::create_app(state.merit))
+        .nest(TABULATION_PREFIX, tabulation::create_app(state.tabulation))
+        .route("/health", get(|| async { "OK" }))
+}
@@ -0,0 +1,77 @@
+use common::redact::{RedactSettings, Redactor};
+use gateway::{build_state, create_app, Config, StartupError};
+use std::net::SocketAddr;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == "--print-config-schema") {
+        print!("{}", Config::schema_doc());
+        return Ok(());
+    }
+
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                "gateway=debug,auth=debug,attendance=debug,merit=debug,tabulation=debug,tower_http=debug"
+                    .into()
+            }),
+        )
+        // Emails, phone numbers and codes are redacted unless LOG_REDACT_ALLOW
+        // says otherwise
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(Redactor::new(&RedactSettings::from_env()).writer(std::io::stdout)),
+        )
+        .init();
+
+    // Load configuration
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => exit_on_startup_error(e.into()),
+    };
+
+    // Create application (all services under one router)
+    let state = match build_state().await {
+        Ok(state) => state,
+        Err(e) => exit_on_startup_error(e),
+    };
+    auth::cleanup::spawn(state.auth.clone());
+    attendance::reminders::spawn(state.attendance.clone());
+    merit::decay::spawn(state.merit.clone());
+    attendance::outbox::spawn(state.attendance.clone());
+    merit::outbox::spawn(state.merit.clone());
+    tabulation::outbox::spawn(state.tabulation.clone());
+    tabulation::undo::spawn(state.tabulation.clone());
+    let app = create_app(state);
+
+    // Start server
+    let addr = format!("{}:{}", config.host, config.port);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+
+    tracing::info!("Gateway listening on {}", addr);
+    tracing::info!(
+        "Mounted services: {}, {}, {}, {}",
+        gateway::AUTH_PREFIX,
+        gateway::ATTENDANCE_PREFIX,
+        gateway::MERIT_PREFIX,
+        gateway::TABULATION_PREFIX
+    );
+
+    // Connection addresses identify clients to the rate limiter when no
+    // proxy header does
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Log why startup failed and exit with the code for that failure
+fn exit_on_startup_error(e: StartupError) -> ! {
+    tracing::error!("Startup failed: {}", e);
+    std::process::exit(e.exit_code());
+}
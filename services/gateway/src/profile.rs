@@ -0,0 +1,128 @@
+//! A member's whole profile from every service in one response.
+//!
+//! Each section is shown only to those allowed to see it, and is `null`
+//! otherwise:
+//!
+//! - `user`: everyone; contact details only to the member and admins
+//! - `merit`: the member, admins, and everyone if the member shows it
+//! - `awards`: everyone; only featured awards when the member curates them,
+//!   except to the member and admins
+//! - `attendance`: the member and admins
+//! - `performance`: anyone signed in
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use common::{
+    error::{api_error, db_error},
+    ApiError,
+};
+use merit::models::{AwardResponse, AwardVisibility};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::GatewayState;
+
+/// Everything the viewer may see of the member named `username`
+pub async fn full_profile(
+    State(state): State<GatewayState>,
+    viewer: Option<Extension<Uuid>>,
+    Path(username): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let user = state
+        .auth
+        .db
+        .find_user_by_username(&username)
+        .await
+        .map_err(db_error)?
+        .filter(|user| user.email_verified)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "User not found"))?;
+
+    let viewer = viewer.map(|Extension(id)| id);
+    let is_self = viewer == Some(user.id);
+    let is_admin = match viewer {
+        Some(id) if !is_self => state.auth.db.is_user_admin(id).await.map_err(db_error)?,
+        _ => false,
+    };
+    let sees_everything = is_self || is_admin;
+
+    let (merit, privacy) = tokio::try_join!(
+        state.merit.db.get_user_merit(user.id),
+        state.merit.db.get_privacy_settings(user.id),
+    )
+    .map_err(db_error)?;
+
+    let featured_only = !sees_everything && privacy.award_visibility == AwardVisibility::Curated;
+    let awards: Vec<AwardResponse> = state
+        .merit
+        .db
+        .get_user_awards(user.id, featured_only, None)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|a| AwardResponse {
+            id: a.id,
+            title: a.title,
+            description: a.description,
+            tier: a.tier,
+            awarded_at: a.awarded_at,
+        })
+        .collect();
+
+    let merit = (sees_everything || privacy.show_merit).then(|| {
+        json!({
+            "merit_points": merit.map(|m| m.merit_points).unwrap_or(0),
+        })
+    });
+
+    let attendance = if sees_everything {
+        Some(
+            state
+                .attendance
+                .db
+                .user_attendance_summary(user.id)
+                .await
+                .map_err(db_error)?,
+        )
+    } else {
+        None
+    };
+
+    let performance = match viewer {
+        Some(_) => Some(
+            tabulation::handlers::user_performance(
+                &state.tabulation,
+                user.id,
+                user.username.clone(),
+                None,
+                None,
+            )
+            .await,
+        ),
+        None => None,
+    };
+
+    let user = if sees_everything {
+        json!(auth::models::UserResponse::from(user))
+    } else {
+        json!({
+            "id": user.id,
+            "username": user.username,
+            "year_joined": user.year_joined,
+            "created_at": user.created_at,
+        })
+    };
+
+    Ok(Json(json!({
+        "user": user,
+        "merit": merit,
+        "awards": {
+            "total": awards.len(),
+            "awards": awards,
+        },
+        "attendance": attendance,
+        "performance": performance,
+    })))
+}
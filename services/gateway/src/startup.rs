@@ -0,0 +1,56 @@
+use common::{config::ConfigError, StartupError as ServiceError};
+use std::fmt;
+
+/// Why the gateway could not start, keeping the failing service's own error
+/// (and exit code) intact
+#[derive(Debug)]
+pub enum StartupError {
+    Config(ConfigError),
+    Auth(ServiceError),
+    Attendance(ServiceError),
+    Merit(ServiceError),
+    Tabulation(ServiceError),
+}
+
+impl StartupError {
+    /// Process exit code for this failure, matching the standalone services
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StartupError::Config(_) => 2,
+            StartupError::Auth(e) => e.exit_code(),
+            StartupError::Attendance(e) => e.exit_code(),
+            StartupError::Merit(e) => e.exit_code(),
+            StartupError::Tabulation(e) => e.exit_code(),
+        }
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartupError::Config(e) => write!(f, "{}", e),
+            StartupError::Auth(e) => write!(f, "auth service: {}", e),
+            StartupError::Attendance(e) => write!(f, "attendance service: {}", e),
+            StartupError::Merit(e) => write!(f, "merit service: {}", e),
+            StartupError::Tabulation(e) => write!(f, "tabulation service: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StartupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StartupError::Config(e) => Some(e),
+            StartupError::Auth(e) => Some(e),
+            StartupError::Attendance(e) => Some(e),
+            StartupError::Merit(e) => Some(e),
+            StartupError::Tabulation(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConfigError> for StartupError {
+    fn from(e: ConfigError) -> Self {
+        StartupError::Config(e)
+    }
+}
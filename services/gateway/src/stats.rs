@@ -0,0 +1,25 @@
+//! The committee's termly numbers from every service in one response.
+
+use axum::{extract::State, Json};
+use common::{error::db_error, ApiError};
+use serde_json::{json, Value};
+
+use crate::GatewayState;
+
+/// Each service's `/admin/stats` report, keyed by service (admin only)
+pub async fn admin_stats(State(state): State<GatewayState>) -> Result<Json<Value>, ApiError> {
+    let (users, events, merit, ballots) = tokio::try_join!(
+        state.auth.db.user_stats(),
+        state.attendance.db.event_stats(),
+        state.merit.db.merit_stats(),
+        state.tabulation.db.ballot_stats(),
+    )
+    .map_err(db_error)?;
+
+    Ok(Json(json!({
+        "auth": users,
+        "attendance": events,
+        "merit": merit,
+        "tabulation": ballots
+    })))
+}
@@ -0,0 +1,121 @@
+//! CSV uploads for bulk merit adjustments.
+//!
+//! The first row names the columns: `change_amount`, `reason`, and either
+//! `user_id` or `username` for whose merit changes. Other columns are
+//! ignored, so a spreadsheet kept with extra notes can be uploaded as is.
+
+use uuid::Uuid;
+
+/// Whose merit a CSV row changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserRef {
+    Id(Uuid),
+    Username(String),
+}
+
+/// One data row of an uploaded CSV
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvChange {
+    /// Line in the file, for error messages
+    pub line: u64,
+    pub user: UserRef,
+    pub change_amount: i32,
+    pub reason: String,
+}
+
+/// Parse an uploaded CSV into changes, failing on the first bad row
+pub fn parse_changes(body: &str) -> Result<Vec<CsvChange>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("Could not read the header row: {}", e))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+
+    let user_column = match (column("user_id"), column("username")) {
+        (Some(i), _) => (i, true),
+        (None, Some(i)) => (i, false),
+        (None, None) => return Err("A user_id or username column is required".to_string()),
+    };
+    let amount_column =
+        column("change_amount").ok_or("A change_amount column is required".to_string())?;
+    let reason_column = column("reason").ok_or("A reason column is required".to_string())?;
+
+    let mut changes = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Could not read the CSV: {}", e))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let field = |i: usize| record.get(i).unwrap_or_default();
+
+        let user = match user_column {
+            (i, true) => UserRef::Id(
+                field(i)
+                    .parse()
+                    .map_err(|_| format!("Line {}: '{}' is not a user id", line, field(i)))?,
+            ),
+            (i, false) if !field(i).is_empty() => UserRef::Username(field(i).to_string()),
+            _ => return Err(format!("Line {}: username is empty", line)),
+        };
+        let change_amount = field(amount_column).parse().map_err(|_| {
+            format!(
+                "Line {}: '{}' is not a whole number",
+                line,
+                field(amount_column)
+            )
+        })?;
+
+        changes.push(CsvChange {
+            line,
+            user,
+            change_amount,
+            reason: field(reason_column).to_string(),
+        });
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_by_username() {
+        let changes = parse_changes(
+            "Username,Change_Amount,Reason,Notes\n ali , 5, Won the open,x\n,,,\nsara,-2,Late,\n",
+        )
+        .unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].user, UserRef::Username("ali".to_string()));
+        assert_eq!(changes[0].change_amount, 5);
+        assert_eq!(changes[0].reason, "Won the open");
+        assert_eq!(changes[1].line, 4);
+        assert_eq!(changes[1].change_amount, -2);
+    }
+
+    #[test]
+    fn test_rows_by_user_id() {
+        let id = Uuid::from_u128(7);
+        let changes =
+            parse_changes(&format!("user_id,change_amount,reason\n{id},3,Judged\n")).unwrap();
+        assert_eq!(changes[0].user, UserRef::Id(id));
+    }
+
+    #[test]
+    fn test_errors_name_the_problem() {
+        assert!(parse_changes("username,reason\nali,x\n")
+            .unwrap_err()
+            .contains("change_amount"));
+        assert_eq!(
+            parse_changes("username,change_amount,reason\nali,five,x\n").unwrap_err(),
+            "Line 2: 'five' is not a whole number"
+        );
+        assert!(parse_changes("user_id,change_amount,reason\nali,5,x\n").is_err());
+    }
+}
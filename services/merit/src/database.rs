@@ -1,14 +1,22 @@
+use crate::decay::{UserCategory, DECAY_KIND};
 use crate::models::{
-    Award, AwardHistory, AwardHistoryWithAdmin, AwardTier, AwardWithAdmin, MeritHistory,
-    MeritHistoryWithAdmin, UserMerit, UserMeritInfo,
+    AttendanceChampion, Award, AwardHistory, AwardHistoryWithAdmin, AwardTier, AwardWithAdmin,
+    DecayCandidate, DecayExemption, ImprovedSpeaker, MeritChange, MeritHistory,
+    MeritHistoryWithAdmin, MeritMonth, MeritStats, PrivacySettings, Season, SeasonAward,
+    SeasonStanding, TopSpeaker, UpdatePrivacyRequest, UserMerit, UserMeritInfo,
 };
-use chrono::Utc;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use crate::outbox;
+use chrono::{NaiveDate, Utc};
+use common::season::in_season;
+use common::stats::STATS_MONTHS;
+use common::ReadReplica;
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
+    replica: Option<ReadReplica>,
 }
 
 impl Database {
@@ -18,14 +26,29 @@ impl Database {
             .connect(database_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            replica: None,
+        })
+    }
+
+    /// Serve the reads of `GET` requests from `replica` while it is reachable
+    pub fn with_replica(mut self, replica: ReadReplica) -> Self {
+        self.replica = Some(replica);
+        self
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
 
-    pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+    /// Pool for read-only queries: the replica during `GET` requests, the
+    /// primary otherwise
+    fn reader(&self) -> &PgPool {
+        common::replica::reader(&self.pool, self.replica.as_ref())
+    }
+
+    pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
         sqlx::migrate!("../migrations").run(&self.pool).await?;
         Ok(())
     }
@@ -44,7 +67,7 @@ impl Database {
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(merit)
@@ -78,7 +101,53 @@ impl Database {
         reason: &str,
     ) -> Result<(UserMerit, MeritHistory), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        let result =
+            Self::apply_merit_change(&mut tx, user_id, admin_id, change_amount, reason, None)
+                .await?;
+        tx.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Apply many merit changes (admin action) as one batch: either all of
+    /// them are recorded, under one batch id, or none are
+    pub async fn apply_merit_batch(
+        &self,
+        admin_id: Uuid,
+        changes: &[MeritChange],
+    ) -> Result<(Uuid, Vec<(UserMerit, MeritHistory)>), sqlx::Error> {
+        let batch_id = Uuid::new_v4();
+        let mut tx = self.pool.begin().await?;
+
+        let mut results = Vec::with_capacity(changes.len());
+        for change in changes {
+            results.push(
+                Self::apply_merit_change(
+                    &mut tx,
+                    change.user_id,
+                    admin_id,
+                    change.change_amount,
+                    &change.reason,
+                    Some(batch_id),
+                )
+                .await?,
+            );
+        }
+        tx.commit().await?;
+
+        Ok((batch_id, results))
+    }
 
+    /// Change one user's merit within a transaction and record it in the
+    /// history, with a `merit_changed` event
+    async fn apply_merit_change(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        admin_id: Uuid,
+        change_amount: i32,
+        reason: &str,
+        batch_id: Option<Uuid>,
+    ) -> Result<(UserMerit, MeritHistory), sqlx::Error> {
         // Get current merit or initialize if not exists
         let current_merit = sqlx::query_as::<_, UserMerit>(
             r#"
@@ -90,7 +159,7 @@ impl Database {
         )
         .bind(user_id)
         .bind(Utc::now())
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&mut **tx)
         .await?;
 
         let current_merit = match current_merit {
@@ -104,7 +173,7 @@ impl Database {
                     "#,
                 )
                 .bind(user_id)
-                .fetch_one(&mut *tx)
+                .fetch_one(&mut **tx)
                 .await?
             }
         };
@@ -124,15 +193,15 @@ impl Database {
         .bind(user_id)
         .bind(new_total)
         .bind(Utc::now())
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
 
         // Create history record
         let history = sqlx::query_as::<_, MeritHistory>(
             r#"
-            INSERT INTO merit_history (user_id, admin_id, change_amount, previous_total, new_total, reason, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_id, admin_id, change_amount, previous_total, new_total, reason, created_at
+            INSERT INTO merit_history (user_id, admin_id, change_amount, previous_total, new_total, reason, batch_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, user_id, admin_id, change_amount, previous_total, new_total, reason, kind, batch_id, created_at
             "#,
         )
         .bind(user_id)
@@ -141,30 +210,52 @@ impl Database {
         .bind(previous_total)
         .bind(new_total)
         .bind(reason)
+        .bind(batch_id)
         .bind(Utc::now())
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut **tx)
         .await?;
-
-        tx.commit().await?;
+        Self::enqueue_merit_changed(tx, &history).await?;
 
         Ok((updated_merit, history))
     }
 
-    /// Get merit history for a user with pagination
+    /// Record a `merit_changed` event for `history` on the transaction that
+    /// wrote it
+    async fn enqueue_merit_changed(
+        conn: &mut PgConnection,
+        history: &MeritHistory,
+    ) -> Result<(), sqlx::Error> {
+        let username: Option<String> =
+            sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+                .bind(history.user_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        let payload = outbox::merit_changed(history, username.as_deref());
+        common::outbox::enqueue(conn, outbox::SERVICE, outbox::MERIT_CHANGED, &payload).await?;
+        Ok(())
+    }
+
+    /// Get merit history for a user with pagination, optionally only from
+    /// one season
     pub async fn get_merit_history(
         &self,
         user_id: Uuid,
+        season_id: Option<Uuid>,
         page: i32,
         per_page: i32,
     ) -> Result<(Vec<MeritHistoryWithAdmin>, i64), sqlx::Error> {
         let offset = (page - 1) * per_page;
 
-        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM merit_history WHERE user_id = $1")
-            .bind(user_id)
-            .fetch_one(&self.pool)
-            .await?;
+        let total: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM merit_history mh WHERE mh.user_id = $1 AND {}",
+            in_season("mh.created_at", "$2")
+        ))
+        .bind(user_id)
+        .bind(season_id)
+        .fetch_one(self.reader())
+        .await?;
 
-        let history = sqlx::query_as::<_, MeritHistoryWithAdmin>(
+        let history = sqlx::query_as::<_, MeritHistoryWithAdmin>(&format!(
             r#"
             SELECT 
                 mh.id,
@@ -175,26 +266,33 @@ impl Database {
                 mh.previous_total,
                 mh.new_total,
                 mh.reason,
+                mh.kind,
+                mh.batch_id,
                 mh.created_at
             FROM merit_history mh
             LEFT JOIN users u ON mh.admin_id = u.id
-            WHERE mh.user_id = $1
+            WHERE mh.user_id = $1 AND {}
             ORDER BY mh.created_at DESC
-            LIMIT $2 OFFSET $3
+            LIMIT $3 OFFSET $4
             "#,
-        )
+            in_season("mh.created_at", "$2")
+        ))
         .bind(user_id)
+        .bind(season_id)
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok((history, total.0))
     }
 
-    /// List all users with their merit (admin only) with pagination
+    /// List all users with their merit (admin only) with pagination. For a
+    /// season, each user's net change within it is included and the list is
+    /// ranked by that instead.
     pub async fn list_all_user_merits(
         &self,
+        season_id: Option<Uuid>,
         page: i32,
         per_page: i32,
     ) -> Result<(Vec<UserMeritInfo>, i64), sqlx::Error> {
@@ -208,34 +306,371 @@ impl Database {
             WHERE u.email_verified = true
             "#,
         )
-        .fetch_one(&self.pool)
+        .fetch_one(self.reader())
         .await?;
 
-        let users = sqlx::query_as::<_, UserMeritInfo>(
+        let users = sqlx::query_as::<_, UserMeritInfo>(&format!(
             r#"
             SELECT 
                 um.user_id,
                 u.username,
-                um.merit_points
+                um.merit_points,
+                season.merit AS season_merit
             FROM user_merit um
             INNER JOIN users u ON um.user_id = u.id
+            LEFT JOIN LATERAL (
+                SELECT COALESCE(SUM(mh.change_amount), 0)::INT AS merit
+                FROM merit_history mh
+                WHERE mh.user_id = um.user_id AND {}
+            ) season ON $3::uuid IS NOT NULL
             WHERE u.email_verified = true
-            ORDER BY um.merit_points DESC, u.username ASC
+            ORDER BY season.merit DESC NULLS LAST, um.merit_points DESC, u.username ASC
             LIMIT $1 OFFSET $2
             "#,
-        )
+            in_season("mh.created_at", "$3")
+        ))
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .bind(season_id)
+        .fetch_all(self.reader())
         .await?;
 
         Ok((users, total.0))
     }
 
+    /// Averages of every merit change, overall and for each recent month
+    pub async fn merit_stats(&self) -> Result<MeritStats, sqlx::Error> {
+        let (total_changes, average_change, average_award, average_deduction): (
+            i64,
+            Option<f64>,
+            Option<f64>,
+            Option<f64>,
+        ) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*),
+                   AVG(change_amount)::FLOAT8,
+                   (AVG(change_amount) FILTER (WHERE change_amount > 0))::FLOAT8,
+                   (AVG(change_amount) FILTER (WHERE change_amount < 0))::FLOAT8
+            FROM merit_history
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let average_merit: Option<f64> = sqlx::query_scalar(
+            r#"
+            SELECT AVG(um.merit_points)::FLOAT8
+            FROM user_merit um
+            INNER JOIN users u ON um.user_id = u.id
+            WHERE u.email_verified = true
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let changes_per_month = sqlx::query_as::<_, MeritMonth>(
+            r#"
+            SELECT m.period,
+                   COUNT(h.id) AS changes,
+                   AVG(h.change_amount)::FLOAT8 AS average_change
+            FROM generate_series(
+                date_trunc('month', NOW()) - make_interval(months => $1 - 1),
+                date_trunc('month', NOW()),
+                INTERVAL '1 month'
+            ) AS m(period)
+            LEFT JOIN merit_history h ON date_trunc('month', h.created_at) = m.period
+            GROUP BY m.period
+            ORDER BY m.period
+            "#,
+        )
+        .bind(STATS_MONTHS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(MeritStats {
+            total_changes,
+            average_change,
+            average_award,
+            average_deduction,
+            average_merit,
+            changes_per_month,
+        })
+    }
+
+    // ========================================================================
+    // Privacy Methods
+    // ========================================================================
+
+    /// A member's privacy settings, or the defaults if they never chose
+    pub async fn get_privacy_settings(
+        &self,
+        user_id: Uuid,
+    ) -> Result<PrivacySettings, sqlx::Error> {
+        let row: Option<(bool, String)> = sqlx::query_as(
+            "SELECT show_merit, award_visibility FROM merit_privacy_settings WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(self.reader())
+        .await?;
+
+        Ok(row
+            .map(|(show_merit, award_visibility)| PrivacySettings {
+                show_merit,
+                award_visibility: award_visibility.parse().unwrap_or_default(),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Change a member's privacy settings, keeping any not given
+    pub async fn update_privacy_settings(
+        &self,
+        user_id: Uuid,
+        request: &UpdatePrivacyRequest,
+    ) -> Result<PrivacySettings, sqlx::Error> {
+        let defaults = PrivacySettings::default();
+        let (show_merit, award_visibility): (bool, String) = sqlx::query_as(
+            r#"
+            INSERT INTO merit_privacy_settings (user_id, show_merit, award_visibility)
+            VALUES ($1, COALESCE($2, $4), COALESCE($3, $5))
+            ON CONFLICT (user_id) DO UPDATE SET
+                show_merit = COALESCE($2, merit_privacy_settings.show_merit),
+                award_visibility = COALESCE($3, merit_privacy_settings.award_visibility),
+                updated_at = NOW()
+            RETURNING show_merit, award_visibility
+            "#,
+        )
+        .bind(user_id)
+        .bind(request.show_merit)
+        .bind(request.award_visibility.map(|v| v.as_str()))
+        .bind(defaults.show_merit)
+        .bind(defaults.award_visibility.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(PrivacySettings {
+            show_merit,
+            award_visibility: award_visibility.parse().unwrap_or_default(),
+        })
+    }
+
+    /// Ids of the awards a member features
+    pub async fn featured_award_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT id FROM awards WHERE user_id = $1 AND featured ORDER BY awarded_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Feature one of a member's own awards, or stop featuring it. Returns
+    /// false if they hold no such award.
+    pub async fn set_award_featured(
+        &self,
+        user_id: Uuid,
+        award_id: Uuid,
+        featured: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE awards SET featured = $3 WHERE id = $1 AND user_id = $2")
+            .bind(award_id)
+            .bind(user_id)
+            .bind(featured)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Merit Decay Methods
+    // ========================================================================
+
+    /// User categories exempted from decay
+    pub async fn list_decay_exemptions(&self) -> Result<Vec<DecayExemption>, sqlx::Error> {
+        sqlx::query_as::<_, DecayExemption>(
+            r#"
+            SELECT category, exempted_by, created_at
+            FROM merit_decay_exemptions
+            ORDER BY category
+            "#,
+        )
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Exempt a category from decay. Returns false if it already was.
+    pub async fn add_decay_exemption(
+        &self,
+        category: UserCategory,
+        admin_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO merit_decay_exemptions (category, exempted_by)
+            VALUES ($1, $2)
+            ON CONFLICT (category) DO NOTHING
+            "#,
+        )
+        .bind(category.as_str())
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Subject a category to decay again. Returns false if it was not exempt.
+    pub async fn remove_decay_exemption(
+        &self,
+        category: UserCategory,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM merit_decay_exemptions WHERE category = $1")
+            .bind(category.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Verified members holding merit with no activity or decay in the last
+    /// `inactive_days` days, outside the exempt categories
+    pub async fn list_decay_candidates(
+        &self,
+        inactive_days: i32,
+        exempt: &[UserCategory],
+    ) -> Result<Vec<DecayCandidate>, sqlx::Error> {
+        let exempt_condition = if exempt.is_empty() {
+            String::new()
+        } else {
+            let conditions: Vec<String> = exempt.iter().map(|c| c.sql_condition("u")).collect();
+            format!("AND NOT ({})", conditions.join(" OR "))
+        };
+
+        sqlx::query_as::<_, DecayCandidate>(&format!(
+            r#"
+            SELECT user_id, username, merit_points, last_active_at, last_decayed_at
+            FROM (
+                SELECT um.user_id, u.username, um.merit_points,
+                    GREATEST(
+                        u.created_at,
+                        (SELECT MAX(h.created_at) FROM merit_history h
+                         WHERE h.user_id = um.user_id AND h.kind <> $2),
+                        (SELECT MAX(ar.checked_in_at) FROM attendance_records ar
+                         WHERE ar.user_id = um.user_id AND ar.is_checked_in),
+                        (SELECT MAX(e.event_date) FROM absence_excuses ax
+                         INNER JOIN events e ON ax.event_id = e.id
+                         WHERE ax.user_id = um.user_id AND ax.status = 'approved')
+                    ) AS last_active_at,
+                    (SELECT MAX(h.created_at) FROM merit_history h
+                     WHERE h.user_id = um.user_id AND h.kind = $2) AS last_decayed_at
+                FROM user_merit um
+                INNER JOIN users u ON um.user_id = u.id
+                WHERE u.email_verified = true AND um.merit_points > 0 {}
+            ) c
+            WHERE GREATEST(last_active_at, last_decayed_at) < NOW() - make_interval(days => $1)
+            ORDER BY merit_points DESC, username ASC
+            "#,
+            exempt_condition
+        ))
+        .bind(inactive_days)
+        .bind(DECAY_KIND)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Take `amount` points from a member as decay, provided they still hold
+    /// `expected_points`. Returns the history entry, or None if their merit
+    /// changed in the meantime.
+    pub async fn record_decay(
+        &self,
+        user_id: Uuid,
+        expected_points: i32,
+        amount: i32,
+        reason: &str,
+    ) -> Result<Option<MeritHistory>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query_as::<_, UserMerit>(
+            r#"
+            UPDATE user_merit
+            SET merit_points = merit_points - $3, updated_at = $4
+            WHERE user_id = $1 AND merit_points = $2
+            RETURNING id, user_id, merit_points, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(expected_points)
+        .bind(amount)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(updated) = updated else {
+            return Ok(None);
+        };
+
+        let history = sqlx::query_as::<_, MeritHistory>(
+            r#"
+            INSERT INTO merit_history (user_id, admin_id, change_amount, previous_total, new_total, reason, kind, created_at)
+            VALUES ($1, NULL, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, admin_id, change_amount, previous_total, new_total, reason, kind, batch_id, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(-amount)
+        .bind(expected_points)
+        .bind(updated.merit_points)
+        .bind(reason)
+        .bind(DECAY_KIND)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+        Self::enqueue_merit_changed(&mut tx, &history).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(history))
+    }
+
     // ========================================================================
     // User Profile Methods (read from users table)
     // ========================================================================
 
+    /// Changes whenever anything shown on the user's profile does, for
+    /// conditional GETs
+    pub async fn profile_version(&self, username: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT concat_ws('|', u.updated_at, um.updated_at, p.updated_at, a.created_at)
+            FROM users u
+            LEFT JOIN user_merit um ON u.id = um.user_id
+            LEFT JOIN merit_privacy_settings p ON u.id = p.user_id
+            LEFT JOIN admin_users a ON u.id = a.user_id
+            WHERE u.username = $1 AND u.email_verified = true
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// The current username of the member who last gave up `username`
+    pub async fn current_username(&self, username: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT u.username
+            FROM username_history h
+            JOIN users u ON u.id = h.user_id
+            WHERE h.old_username = $1 AND u.email_verified = true
+            ORDER BY h.changed_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(username)
+        .fetch_optional(self.reader())
+        .await
+    }
+
     /// Get public user profile by username
     pub async fn get_user_by_username(
         &self,
@@ -261,7 +696,7 @@ impl Database {
             "#,
         )
         .bind(username)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(user)
@@ -292,12 +727,34 @@ impl Database {
             "#,
         )
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(user)
     }
 
+    /// Usernames of the given users that exist
+    pub async fn find_usernames(
+        &self,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        sqlx::query_as("SELECT id, username FROM users WHERE id = ANY($1)")
+            .bind(user_ids)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Ids of the given usernames that exist
+    pub async fn find_user_ids(
+        &self,
+        usernames: &[String],
+    ) -> Result<Vec<(String, Uuid)>, sqlx::Error> {
+        sqlx::query_as("SELECT username, id FROM users WHERE username = ANY($1)")
+            .bind(usernames)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     /// Check if a user is an admin
     pub async fn is_user_admin(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
         let result: Option<(Uuid,)> =
@@ -309,6 +766,14 @@ impl Database {
         Ok(result.is_some())
     }
 
+    /// Whether the member is suspended by the auth service now
+    pub async fn is_user_suspended(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT user_is_suspended($1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
     // ========================================================================
     // Award Methods
     // ========================================================================
@@ -446,7 +911,7 @@ impl Database {
             "SELECT id, user_id, title, description, tier, awarded_by, awarded_at, created_at, updated_at FROM awards WHERE id = $1",
         )
         .bind(award_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(award)
@@ -471,19 +936,25 @@ impl Database {
             "#,
         )
         .bind(award_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
         Ok(award)
     }
 
-    /// Get all awards for a user (public - for profile display)
-    pub async fn get_user_awards(&self, user_id: Uuid) -> Result<Vec<Award>, sqlx::Error> {
-        let awards = sqlx::query_as::<_, Award>(
+    /// Get all awards for a user (public - for profile display), or only
+    /// those they feature, optionally only from one season
+    pub async fn get_user_awards(
+        &self,
+        user_id: Uuid,
+        featured_only: bool,
+        season_id: Option<Uuid>,
+    ) -> Result<Vec<Award>, sqlx::Error> {
+        let awards = sqlx::query_as::<_, Award>(&format!(
             r#"
             SELECT id, user_id, title, description, tier, awarded_by, awarded_at, created_at, updated_at
             FROM awards
-            WHERE user_id = $1
+            WHERE user_id = $1 AND (NOT $2 OR featured) AND {}
             ORDER BY 
                 CASE tier 
                     WHEN 'gold' THEN 1 
@@ -492,9 +963,12 @@ impl Database {
                 END,
                 awarded_at DESC
             "#,
-        )
+            in_season("awarded_at", "$3")
+        ))
         .bind(user_id)
-        .fetch_all(&self.pool)
+        .bind(featured_only)
+        .bind(season_id)
+        .fetch_all(self.reader())
         .await?;
 
         Ok(awards)
@@ -518,7 +992,7 @@ impl Database {
             "#,
         )
         .bind(award_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok(history)
@@ -584,19 +1058,21 @@ impl Database {
             "#,
         )
         .bind(user_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok(history)
     }
 
-    /// List all awards with pagination (admin)
+    /// List all awards with pagination (admin), optionally only from one
+    /// season
     pub async fn list_all_awards(
         &self,
+        season_id: Option<Uuid>,
         limit: i32,
         offset: i32,
     ) -> Result<(Vec<AwardWithAdmin>, i64), sqlx::Error> {
-        let awards = sqlx::query_as::<_, AwardWithAdmin>(
+        let awards = sqlx::query_as::<_, AwardWithAdmin>(&format!(
             r#"
             SELECT 
                 a.id,
@@ -613,21 +1089,410 @@ impl Database {
             FROM awards a
             JOIN users u ON u.id = a.user_id
             LEFT JOIN users u2 ON u2.id = a.awarded_by
+            WHERE {}
             ORDER BY a.created_at DESC
             LIMIT $1 OFFSET $2
             "#,
-        )
+            in_season("a.awarded_at", "$3")
+        ))
         .bind(limit)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .bind(season_id)
+        .fetch_all(self.reader())
         .await?;
 
-        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM awards")
-            .fetch_one(&self.pool)
-            .await?;
+        let total: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM awards a WHERE {}",
+            in_season("a.awarded_at", "$1")
+        ))
+        .bind(season_id)
+        .fetch_one(self.reader())
+        .await?;
 
         Ok((awards, total.0))
     }
+
+    // ========================================================================
+    // Season Methods
+    // ========================================================================
+
+    /// Every season, latest first
+    pub async fn list_seasons(&self) -> Result<Vec<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>(&format!(
+            "SELECT {} FROM seasons ORDER BY starts_on DESC",
+            SEASON_COLUMNS
+        ))
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn get_season(&self, season_id: Uuid) -> Result<Option<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>(&format!(
+            "SELECT {} FROM seasons WHERE id = $1",
+            SEASON_COLUMNS
+        ))
+        .bind(season_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    pub async fn create_season(
+        &self,
+        name: &str,
+        starts_on: NaiveDate,
+        ends_on: NaiveDate,
+        admin_id: Uuid,
+    ) -> Result<Season, sqlx::Error> {
+        sqlx::query_as::<_, Season>(&format!(
+            r#"
+            INSERT INTO seasons (name, starts_on, ends_on, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            SEASON_COLUMNS
+        ))
+        .bind(name)
+        .bind(starts_on)
+        .bind(ends_on)
+        .bind(admin_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Rename an open season or move its dates, keeping any not given.
+    /// Returns None if there is no such open season.
+    pub async fn update_season(
+        &self,
+        season_id: Uuid,
+        name: Option<&str>,
+        starts_on: Option<NaiveDate>,
+        ends_on: Option<NaiveDate>,
+    ) -> Result<Option<Season>, sqlx::Error> {
+        sqlx::query_as::<_, Season>(&format!(
+            r#"
+            UPDATE seasons SET
+                name = COALESCE($2, name),
+                starts_on = COALESCE($3, starts_on),
+                ends_on = COALESCE($4, ends_on),
+                updated_at = NOW()
+            WHERE id = $1 AND closed_at IS NULL
+            RETURNING {}
+            "#,
+            SEASON_COLUMNS
+        ))
+        .bind(season_id)
+        .bind(name)
+        .bind(starts_on)
+        .bind(ends_on)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Delete an open season. Returns false if there is no such open season.
+    pub async fn delete_season(&self, season_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM seasons WHERE id = $1 AND closed_at IS NULL")
+            .bind(season_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Close a season and snapshot its standings. Returns None if there is
+    /// no such open season.
+    pub async fn close_season(
+        &self,
+        season_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<Option<Season>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let season = sqlx::query_as::<_, Season>(&format!(
+            r#"
+            UPDATE seasons SET closed_at = NOW(), closed_by = $2, updated_at = NOW()
+            WHERE id = $1 AND closed_at IS NULL
+            RETURNING {}
+            "#,
+            SEASON_COLUMNS
+        ))
+        .bind(season_id)
+        .bind(admin_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(season) = season else {
+            return Ok(None);
+        };
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO season_standings (
+                season_id, user_id, username, rank, merit_earned, awards,
+                wins, losses, average_speaker_score
+            )
+            SELECT $1, user_id, username, rank, merit_earned, awards,
+                wins, losses, average_speaker_score
+            FROM ({}) standings
+            "#,
+            season_standings_query()
+        ))
+        .bind(season_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(season))
+    }
+
+    /// A season's standings: the snapshot once it is closed, worked out
+    /// from merit changes, awards and ballots while it is open
+    pub async fn season_standings(
+        &self,
+        season: &Season,
+        page: i32,
+        per_page: i32,
+    ) -> Result<(Vec<SeasonStanding>, i64), sqlx::Error> {
+        let offset = (page - 1) * per_page;
+        let standings = if season.closed_at.is_some() {
+            r#"
+            SELECT rank, user_id, username, merit_earned, awards, wins, losses,
+                average_speaker_score::FLOAT8 AS average_speaker_score
+            FROM season_standings
+            WHERE season_id = $1
+            "#
+            .to_string()
+        } else {
+            season_standings_query()
+        };
+
+        let total: (i64,) =
+            sqlx::query_as(&format!("SELECT COUNT(*) FROM ({}) standings", standings))
+                .bind(season.id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let rows = sqlx::query_as::<_, SeasonStanding>(&format!(
+            r#"
+            SELECT * FROM ({}) standings
+            ORDER BY rank, username
+            LIMIT $2 OFFSET $3
+            "#,
+            standings
+        ))
+        .bind(season.id)
+        .bind(per_page)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((rows, total.0))
+    }
+
+    // ========================================================================
+    // Season Report Methods
+    // ========================================================================
+
+    /// Best average speaker scores over a season, among verified members
+    /// who gave at least `min_speeches` speeches in it
+    pub async fn season_top_speakers(
+        &self,
+        season_id: Uuid,
+        min_speeches: i32,
+        limit: i32,
+    ) -> Result<Vec<TopSpeaker>, sqlx::Error> {
+        sqlx::query_as::<_, TopSpeaker>(&format!(
+            r#"
+            SELECT p.user_id, u.username,
+                (SUM(p.speaker_score_total) / SUM(p.speaker_score_count))::FLOAT8
+                    AS average_speaker_score,
+                SUM(p.speaker_score_count)::INT AS speeches
+            FROM user_event_performance p
+            INNER JOIN events e ON p.event_id = e.id
+            INNER JOIN users u ON u.id = p.user_id
+            WHERE {} AND u.email_verified = true
+            GROUP BY p.user_id, u.username
+            HAVING SUM(p.speaker_score_count) >= $2
+            ORDER BY average_speaker_score DESC, speeches DESC, u.username
+            LIMIT $3
+            "#,
+            in_season("e.event_date", "$1")
+        ))
+        .bind(season_id)
+        .bind(min_speeches)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Members whose average speaker score in a season rose the most over
+    /// their average from every event before it. Both averages need at
+    /// least `min_speeches` speeches behind them.
+    pub async fn season_most_improved(
+        &self,
+        season_id: Uuid,
+        min_speeches: i32,
+        limit: i32,
+    ) -> Result<Vec<ImprovedSpeaker>, sqlx::Error> {
+        sqlx::query_as::<_, ImprovedSpeaker>(&format!(
+            r#"
+            WITH during AS (
+                SELECT p.user_id,
+                    SUM(p.speaker_score_total) / NULLIF(SUM(p.speaker_score_count), 0)
+                        AS average,
+                    SUM(p.speaker_score_count) AS speeches
+                FROM user_event_performance p
+                INNER JOIN events e ON p.event_id = e.id
+                WHERE {}
+                GROUP BY p.user_id
+            ),
+            earlier AS (
+                SELECT p.user_id,
+                    SUM(p.speaker_score_total) / NULLIF(SUM(p.speaker_score_count), 0)
+                        AS average,
+                    SUM(p.speaker_score_count) AS speeches
+                FROM user_event_performance p
+                INNER JOIN events e ON p.event_id = e.id
+                INNER JOIN seasons season ON season.id = $1
+                WHERE (e.event_date AT TIME ZONE 'UTC')::date < season.starts_on
+                GROUP BY p.user_id
+            )
+            SELECT d.user_id, u.username,
+                b.average::FLOAT8 AS previous_average,
+                d.average::FLOAT8 AS season_average,
+                (d.average - b.average)::FLOAT8 AS improvement
+            FROM during d
+            INNER JOIN earlier b ON b.user_id = d.user_id
+            INNER JOIN users u ON u.id = d.user_id
+            WHERE d.speeches >= $2 AND b.speeches >= $2
+                AND d.average > b.average
+                AND u.email_verified = true
+            ORDER BY improvement DESC, u.username
+            LIMIT $3
+            "#,
+            in_season("e.event_date", "$1")
+        ))
+        .bind(season_id)
+        .bind(min_speeches)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Verified members who checked in to the most of a season's events
+    pub async fn season_attendance_champions(
+        &self,
+        season_id: Uuid,
+        limit: i32,
+    ) -> Result<Vec<AttendanceChampion>, sqlx::Error> {
+        sqlx::query_as::<_, AttendanceChampion>(&format!(
+            r#"
+            SELECT ar.user_id, u.username, COUNT(*)::INT AS events_attended
+            FROM attendance_records ar
+            INNER JOIN events e ON ar.event_id = e.id
+            INNER JOIN users u ON u.id = ar.user_id
+            WHERE ar.is_checked_in = true AND {} AND u.email_verified = true
+            GROUP BY ar.user_id, u.username
+            ORDER BY events_attended DESC, u.username
+            LIMIT $2
+            "#,
+            in_season("e.event_date", "$1")
+        ))
+        .bind(season_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Number of events held during a season
+    pub async fn season_event_count(&self, season_id: Uuid) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM events e WHERE {}",
+            in_season("e.event_date", "$1")
+        ))
+        .bind(season_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count.0)
+    }
+
+    /// Every award given during a season, in the order they were given
+    pub async fn season_awards(&self, season_id: Uuid) -> Result<Vec<SeasonAward>, sqlx::Error> {
+        sqlx::query_as::<_, SeasonAward>(&format!(
+            r#"
+            SELECT a.user_id, u.username, a.title, a.tier, a.awarded_at
+            FROM awards a
+            INNER JOIN users u ON u.id = a.user_id
+            WHERE {}
+            ORDER BY a.awarded_at, u.username
+            "#,
+            in_season("a.awarded_at", "$1")
+        ))
+        .bind(season_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+const SEASON_COLUMNS: &str =
+    "id, name, starts_on, ends_on, closed_at, closed_by, created_by, created_at, updated_at";
+
+/// Standings for the season bound at `$1`: every verified member who
+/// gained or lost merit, won an award or had a ballot result in it, ranked
+/// by merit earned and then by wins
+fn season_standings_query() -> String {
+    format!(
+        r#"
+        WITH merit AS (
+            SELECT mh.user_id, SUM(mh.change_amount)::INT AS merit_earned
+            FROM merit_history mh
+            WHERE {}
+            GROUP BY mh.user_id
+        ),
+        season_awards AS (
+            SELECT a.user_id, COUNT(*)::INT AS awards
+            FROM awards a
+            WHERE {}
+            GROUP BY a.user_id
+        ),
+        results AS (
+            SELECT p.user_id,
+                SUM(p.wins)::INT AS wins,
+                SUM(p.losses)::INT AS losses,
+                SUM(p.speaker_score_total) / NULLIF(SUM(p.speaker_score_count), 0)
+                    AS average_speaker_score
+            FROM user_event_performance p
+            INNER JOIN events e ON p.event_id = e.id
+            WHERE {}
+            GROUP BY p.user_id
+        ),
+        members AS (
+            SELECT user_id FROM merit
+            UNION SELECT user_id FROM season_awards
+            UNION SELECT user_id FROM results
+        )
+        SELECT
+            RANK() OVER (
+                ORDER BY COALESCE(m.merit_earned, 0) DESC, COALESCE(r.wins, 0) DESC
+            )::INT AS rank,
+            u.id AS user_id,
+            u.username,
+            COALESCE(m.merit_earned, 0) AS merit_earned,
+            COALESCE(a.awards, 0) AS awards,
+            COALESCE(r.wins, 0) AS wins,
+            COALESCE(r.losses, 0) AS losses,
+            r.average_speaker_score::FLOAT8 AS average_speaker_score
+        FROM members
+        INNER JOIN users u ON members.user_id = u.id
+        LEFT JOIN merit m ON m.user_id = u.id
+        LEFT JOIN season_awards a ON a.user_id = u.id
+        LEFT JOIN results r ON r.user_id = u.id
+        WHERE u.email_verified = true
+        "#,
+        in_season("mh.created_at", "$1"),
+        in_season("a.awarded_at", "$1"),
+        in_season("e.event_date", "$1")
+    )
 }
 
 /// Internal row type for user profile queries
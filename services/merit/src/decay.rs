@@ -0,0 +1,232 @@
+//! Merit decay for long-inactive members.
+//!
+//! Once a member has gone `inactive_days` without checking in to an event
+//! (an approved absence excuse counts as attending), or having their merit
+//! changed by an admin, they lose `percent` of their
+//! merit, and again after each further period without activity. Decay is
+//! recorded in the merit history with kind `decay`, and never counts as
+//! activity. Admins can exempt whole categories of users.
+
+use common::{config::EnvReader, Role};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::{models::DecayCandidate, AppState};
+
+/// `merit_history.kind` of changes made by decay
+pub const DECAY_KIND: &str = "decay";
+
+/// How often to look for members due to decay
+const POLL_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// How much merit inactive members lose, and after how long
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DecayPolicy {
+    /// Share of merit lost per period; 0 turns decay off
+    pub percent: i32,
+    /// Days without activity before each decay
+    pub inactive_days: i32,
+}
+
+impl DecayPolicy {
+    /// Read `MERIT_DECAY_PERCENT` and `MERIT_DECAY_INACTIVE_DAYS`
+    pub fn read(env: &mut EnvReader) -> Self {
+        let policy = DecayPolicy {
+            percent: env.parse("MERIT_DECAY_PERCENT"),
+            inactive_days: env.parse("MERIT_DECAY_INACTIVE_DAYS"),
+        };
+        if !(0..=100).contains(&policy.percent) {
+            env.check::<(), _>(Err("MERIT_DECAY_PERCENT must be between 0 and 100"));
+        }
+        if policy.inactive_days < 1 {
+            env.check::<(), _>(Err("MERIT_DECAY_INACTIVE_DAYS must be at least 1"));
+        }
+        policy
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.percent > 0
+    }
+
+    /// Points a member holding `merit_points` loses, rounded down so small
+    /// totals are left alone rather than wiped out
+    pub fn amount(&self, merit_points: i32) -> i32 {
+        if merit_points <= 0 {
+            return 0;
+        }
+        (merit_points as i64 * self.percent as i64 / 100) as i32
+    }
+
+    /// History reason recorded with each decay
+    pub fn reason(&self) -> String {
+        format!(
+            "Decay: {}% for {} days without activity",
+            self.percent, self.inactive_days
+        )
+    }
+}
+
+/// Groups of users that can be exempted from decay as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserCategory {
+    Admin,
+    Novice,
+    Esl,
+    Equity,
+}
+
+impl UserCategory {
+    pub const ALL: &'static [UserCategory] = &[
+        UserCategory::Admin,
+        UserCategory::Novice,
+        UserCategory::Esl,
+        UserCategory::Equity,
+    ];
+
+    /// Value stored in `merit_decay_exemptions.category`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserCategory::Admin => "admin",
+            UserCategory::Novice => "novice",
+            UserCategory::Esl => "esl",
+            UserCategory::Equity => "equity",
+        }
+    }
+
+    /// SQL condition selecting rows of `users` aliased as `alias` in this
+    /// category
+    pub fn sql_condition(self, alias: &str) -> String {
+        match self {
+            UserCategory::Admin => format!(
+                "EXISTS (SELECT 1 FROM admin_users au WHERE au.user_id = {}.id)",
+                alias
+            ),
+            UserCategory::Novice => format!("{}.is_novice", alias),
+            UserCategory::Esl => format!("{}.is_esl", alias),
+            UserCategory::Equity => format!(
+                "EXISTS (SELECT 1 FROM user_roles r WHERE r.user_id = {}.id AND r.role = '{}')",
+                alias,
+                Role::Equity.as_str()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for UserCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for UserCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UserCategory::ALL
+            .iter()
+            .copied()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| format!("Unknown user category: {}", s))
+    }
+}
+
+/// Members due to decay now under the configured policy, skipping
+/// exempted categories
+pub async fn due_for_decay(state: &AppState) -> Result<Vec<DecayCandidate>, sqlx::Error> {
+    let exempt = state.db.list_decay_exemptions().await?;
+    let exempt: Vec<UserCategory> = exempt
+        .iter()
+        .filter_map(|e| e.category.parse().ok())
+        .collect();
+    state
+        .db
+        .list_decay_candidates(state.config.decay.inactive_days, &exempt)
+        .await
+}
+
+/// Start the decay loop, on one replica at a time. Does nothing unless a
+/// decay percentage is set.
+pub fn spawn(state: Arc<AppState>) {
+    if !state.config.decay.is_enabled() {
+        return;
+    }
+
+    common::leader::spawn(
+        state.db.pool().clone(),
+        "merit.decay",
+        std::time::Duration::from_secs(POLL_INTERVAL_SECS),
+        move || {
+            let state = state.clone();
+            async move {
+                match apply_due_decay(&state).await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("Decayed merit of {} inactive members", count),
+                    Err(e) => tracing::warn!("Failed to apply merit decay: {}", e),
+                }
+            }
+        },
+    );
+}
+
+/// Decay the merit of every member due. Members whose merit changed since
+/// they were listed are left for the next run.
+pub async fn apply_due_decay(state: &AppState) -> Result<usize, sqlx::Error> {
+    let policy = state.config.decay;
+    let reason = policy.reason();
+    let mut applied = 0;
+
+    for candidate in due_for_decay(state).await? {
+        let amount = policy.amount(candidate.merit_points);
+        if amount == 0 {
+            continue;
+        }
+        let decayed = state
+            .db
+            .record_decay(candidate.user_id, candidate.merit_points, amount, &reason)
+            .await?;
+        if decayed.is_some() {
+            applied += 1;
+        }
+    }
+
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(percent: i32) -> DecayPolicy {
+        DecayPolicy {
+            percent,
+            inactive_days: 182,
+        }
+    }
+
+    #[test]
+    fn test_amount_rounds_down() {
+        assert_eq!(policy(10).amount(100), 10);
+        assert_eq!(policy(10).amount(19), 1);
+        assert_eq!(policy(10).amount(9), 0);
+        assert_eq!(policy(100).amount(42), 42);
+    }
+
+    #[test]
+    fn test_nothing_to_lose() {
+        assert_eq!(policy(10).amount(0), 0);
+        assert_eq!(policy(10).amount(-50), 0);
+        assert!(!policy(0).is_enabled());
+    }
+
+    #[test]
+    fn test_category_round_trip() {
+        for category in UserCategory::ALL {
+            assert_eq!(
+                category.as_str().parse::<UserCategory>().unwrap(),
+                *category
+            );
+        }
+        assert!("alumni".parse::<UserCategory>().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! Conditional GETs for public profiles. What a profile shows depends on
+//! who is looking, so each viewer gets their own ETag.
+
+use axum::{
+    extract::{Path, Request, State},
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use common::etag::conditional_get;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub async fn profile_etag(
+    State(state): State<Arc<AppState>>,
+    viewer: Option<Extension<Uuid>>,
+    Path(username): Path<String>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let version = state.db.profile_version(&username).await.ok().flatten();
+    conditional_get(request, next, version, viewer.map(|Extension(id)| id)).await
+}
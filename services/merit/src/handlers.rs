@@ -1,20 +1,32 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Extension, Json,
 };
+use common::Pagination;
 use serde_json::{json, Value};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
+    bulk::{self, UserRef},
+    database::UserProfileRow,
+    decay::{self, UserCategory},
     models::{
         AdminMeritListResponse, AdminProfileResponse, AwardHistoryResponse, AwardListResponse,
-        AwardResponse, CreateAwardRequest, EditAwardRequest, MeritHistoryQuery,
-        MeritHistoryResponse, MeritResponse, PrivateProfileResponse, PublicProfileResponse,
-        UpdateMeritRequest, UpgradeAwardRequest,
+        AwardResponse, AwardVisibility, BulkMeritRequest, CreateAwardRequest, CreateSeasonRequest,
+        EditAwardRequest, FeatureAwardRequest, MeritChange, MeritHistoryQuery,
+        MeritHistoryResponse, MeritResponse, MeritStats, PrivacyResponse, PrivacySettings,
+        PrivateProfileResponse, PublicProfileResponse, SeasonQuery, SeasonStandingsResponse,
+        TransferMeritRequest, UpdateMeritRequest, UpdatePrivacyRequest, UpdateSeasonRequest,
+        UpgradeAwardRequest,
     },
+    season_report::{
+        ReportFormat, SeasonReport, SeasonReportQuery, LEADERBOARD_SIZE, MIN_SPEECHES,
+    },
+    verification::{self, AwardCredential, SharedCredential},
     AppState,
 };
 
@@ -22,29 +34,59 @@ use crate::{
 // Profile Handlers
 // ============================================================================
 
+/// Find a member by username, following renames. Returns the profile and
+/// whether `username` is a name they have since given up.
+async fn find_profile(
+    state: &AppState,
+    username: &str,
+) -> Result<(UserProfileRow, bool), (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+
+    if let Some(profile) = state
+        .db
+        .get_user_by_username(username)
+        .await
+        .map_err(db_error)?
+    {
+        return Ok((profile, false));
+    }
+    if let Some(current) = state
+        .db
+        .current_username(username)
+        .await
+        .map_err(db_error)?
+    {
+        if let Some(profile) = state
+            .db
+            .get_user_by_username(&current)
+            .await
+            .map_err(db_error)?
+        {
+            return Ok((profile, true));
+        }
+    }
+    Err((
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "User not found"})),
+    ))
+}
+
 /// Get public profile by username - accessible by anyone (including unauthenticated users)
-/// Returns limited info; merit is only visible to self or admins
+/// Returns limited info; merit is only visible to self or admins, unless the
+/// member has chosen to show it. Found by a former username, the profile
+/// carries `redirect_to` with the current one.
 pub async fn get_profile_by_username(
     State(state): State<Arc<AppState>>,
     current_user_id: Option<Extension<Uuid>>,
     Path(username): Path<String>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    let profile = state
-        .db
-        .get_user_by_username(&username)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "User not found"})),
-            )
-        })?;
+    let (profile, renamed) = find_profile(&state, &username).await?;
+    let redirect_to = renamed.then(|| profile.username.clone());
 
     // Check if we have an authenticated user
     let current_user_id = current_user_id.map(|Extension(id)| id);
@@ -58,9 +100,9 @@ pub async fn get_profile_by_username(
         None => false,
     };
 
-    if is_admin {
+    let mut body = if is_admin {
         // Admin can see everything including merit and admin status
-        Ok(Json(json!(AdminProfileResponse {
+        json!(AdminProfileResponse {
             id: profile.id,
             username: profile.username,
             email: profile.email,
@@ -71,10 +113,10 @@ pub async fn get_profile_by_username(
             merit_points: profile.merit_points,
             is_admin: profile.is_admin,
             created_at: profile.created_at,
-        })))
+        })
     } else if is_own_profile {
         // User viewing their own profile - can see their own merit
-        Ok(Json(json!(PrivateProfileResponse {
+        json!(PrivateProfileResponse {
             id: profile.id,
             username: profile.username,
             email: profile.email,
@@ -84,16 +126,91 @@ pub async fn get_profile_by_username(
             email_verified: profile.email_verified,
             merit_points: profile.merit_points,
             created_at: profile.created_at,
-        })))
+        })
     } else {
-        // Someone else viewing the profile - public info only, no merit
-        Ok(Json(json!(PublicProfileResponse {
+        // Someone else viewing the profile - public info only, with merit
+        // if the member shows it
+        let privacy = state
+            .db
+            .get_privacy_settings(profile.id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?;
+
+        json!(PublicProfileResponse {
             id: profile.id,
             username: profile.username,
             year_joined: profile.year_joined,
+            merit_points: privacy.show_merit.then_some(profile.merit_points),
             created_at: profile.created_at,
-        })))
+        })
+    };
+
+    if let Some(current) = redirect_to {
+        body["redirect_to"] = json!(current);
     }
+    Ok(Json(body))
+}
+
+// ============================================================================
+// Privacy Handlers
+// ============================================================================
+
+async fn privacy_response(
+    state: &AppState,
+    user_id: Uuid,
+    settings: PrivacySettings,
+) -> Result<Json<PrivacyResponse>, (StatusCode, Json<Value>)> {
+    let featured_award_ids = state.db.featured_award_ids(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(PrivacyResponse {
+        settings,
+        featured_award_ids,
+    }))
+}
+
+/// Get my privacy settings
+pub async fn get_my_privacy(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<PrivacyResponse>, (StatusCode, Json<Value>)> {
+    let settings = state.db.get_privacy_settings(user_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    privacy_response(&state, user_id, settings).await
+}
+
+/// Choose whether my merit is public and which of my awards others see
+pub async fn update_my_privacy(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<UpdatePrivacyRequest>,
+) -> Result<Json<PrivacyResponse>, (StatusCode, Json<Value>)> {
+    let settings = state
+        .db
+        .update_privacy_settings(user_id, &payload)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    privacy_response(&state, user_id, settings).await
 }
 
 // ============================================================================
@@ -141,12 +258,13 @@ pub async fn get_my_merit_history(
     Extension(user_id): Extension<Uuid>,
     Query(query): Query<MeritHistoryQuery>,
 ) -> Result<Json<MeritHistoryResponse>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    ensure_season_exists(&state, query.season_id).await?;
+    let pagination = Pagination::new(query.page, query.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
 
     let (history, total) = state
         .db
-        .get_merit_history(user_id, page, per_page)
+        .get_merit_history(user_id, query.season_id, page, per_page)
         .await
         .map_err(|_| {
             (
@@ -155,7 +273,7 @@ pub async fn get_my_merit_history(
             )
         })?;
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(MeritHistoryResponse {
         history,
@@ -241,6 +359,227 @@ pub async fn admin_update_merit(
     ))
 }
 
+/// Apply many merit changes in one transaction (admin only)
+pub async fn admin_bulk_update_merit(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Json(payload): Json<BulkMeritRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    apply_batch(
+        &state,
+        admin_id,
+        payload.changes,
+        "Merit updated successfully",
+    )
+    .await
+}
+
+/// Apply the changes in an uploaded CSV in one transaction (admin only).
+/// See `bulk` for the columns.
+pub async fn admin_bulk_update_merit_csv(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    body: String,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let rows = bulk::parse_changes(&body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+
+    // Look up every username in one go and report all unknown ones together
+    let mut usernames: Vec<String> = rows
+        .iter()
+        .filter_map(|row| match &row.user {
+            UserRef::Username(name) => Some(name.clone()),
+            UserRef::Id(_) => None,
+        })
+        .collect();
+    usernames.sort();
+    usernames.dedup();
+    let ids: HashMap<String, Uuid> = state
+        .db
+        .find_user_ids(&usernames)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .into_iter()
+        .collect();
+    let unknown: Vec<String> = rows
+        .iter()
+        .filter_map(|row| match &row.user {
+            UserRef::Username(name) if !ids.contains_key(name) => {
+                Some(format!("Line {}: {}", row.line, name))
+            }
+            _ => None,
+        })
+        .collect();
+    if !unknown.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Users not found", "users": unknown})),
+        ));
+    }
+
+    let payload = BulkMeritRequest {
+        changes: rows
+            .into_iter()
+            .map(|row| MeritChange {
+                user_id: match row.user {
+                    UserRef::Id(id) => id,
+                    UserRef::Username(name) => ids[&name],
+                },
+                change_amount: row.change_amount,
+                reason: row.reason,
+            })
+            .collect(),
+    };
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    apply_batch(
+        &state,
+        admin_id,
+        payload.changes,
+        "Merit updated successfully",
+    )
+    .await
+}
+
+/// Move merit from one user to another as a single batch (admin only)
+pub async fn admin_transfer_merit(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Json(payload): Json<TransferMeritRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    if payload.from_user_id == payload.to_user_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot transfer merit to the same user"})),
+        ));
+    }
+
+    let changes = vec![
+        MeritChange {
+            user_id: payload.from_user_id,
+            change_amount: -payload.amount,
+            reason: payload.reason.clone(),
+        },
+        MeritChange {
+            user_id: payload.to_user_id,
+            change_amount: payload.amount,
+            reason: payload.reason,
+        },
+    ];
+
+    apply_batch(&state, admin_id, changes, "Merit transferred successfully").await
+}
+
+/// Check a batch of changes and apply them all or none, answering with each
+/// user's new total
+async fn apply_batch(
+    state: &AppState,
+    admin_id: Uuid,
+    changes: Vec<MeritChange>,
+    message: &str,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Prevent changing own merit
+    if changes.iter().any(|c| c.user_id == admin_id) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Cannot modify your own merit points"})),
+        ));
+    }
+
+    // Verify every target user exists
+    let user_ids: Vec<Uuid> = changes.iter().map(|c| c.user_id).collect();
+    let usernames: HashMap<Uuid, String> = state
+        .db
+        .find_usernames(&user_ids)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .into_iter()
+        .collect();
+    let mut missing: Vec<Uuid> = user_ids
+        .into_iter()
+        .filter(|id| !usernames.contains_key(id))
+        .collect();
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Users not found", "user_ids": missing})),
+        ));
+    }
+
+    let (batch_id, results) = state
+        .db
+        .apply_merit_batch(admin_id, &changes)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to apply merit batch: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update merit"})),
+            )
+        })?;
+
+    let applied: Vec<Value> = results
+        .into_iter()
+        .map(|(merit, history)| {
+            json!({
+                "user_id": history.user_id,
+                "username": usernames.get(&history.user_id),
+                "previous_merit": history.previous_total,
+                "new_merit": merit.merit_points,
+                "change_amount": history.change_amount,
+                "reason": history.reason
+            })
+        })
+        .collect();
+
+    tracing::info!(
+        "Admin {} applied merit batch {} ({} changes)",
+        admin_id,
+        batch_id,
+        applied.len()
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({
+            "message": message,
+            "batch_id": batch_id,
+            "changes": applied
+        })),
+    ))
+}
+
 /// Get merit for any user (admin only)
 pub async fn admin_get_user_merit(
     State(state): State<Arc<AppState>>,
@@ -295,12 +634,13 @@ pub async fn admin_get_user_merit_history(
             )
         })?;
 
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    ensure_season_exists(&state, query.season_id).await?;
+    let pagination = Pagination::new(query.page, query.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
 
     let (history, total) = state
         .db
-        .get_merit_history(user_id, page, per_page)
+        .get_merit_history(user_id, query.season_id, page, per_page)
         .await
         .map_err(|_| {
             (
@@ -309,7 +649,7 @@ pub async fn admin_get_user_merit_history(
             )
         })?;
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(MeritHistoryResponse {
         history,
@@ -325,12 +665,13 @@ pub async fn admin_list_all_merits(
     State(state): State<Arc<AppState>>,
     Query(query): Query<MeritHistoryQuery>,
 ) -> Result<Json<AdminMeritListResponse>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(50).clamp(1, 100);
+    ensure_season_exists(&state, query.season_id).await?;
+    let pagination = Pagination::new(query.page, query.per_page, 50);
+    let (page, per_page) = (pagination.page, pagination.per_page);
 
     let (users, total) = state
         .db
-        .list_all_user_merits(page, per_page)
+        .list_all_user_merits(query.season_id, page, per_page)
         .await
         .map_err(|_| {
             (
@@ -339,7 +680,7 @@ pub async fn admin_list_all_merits(
             )
         })?;
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(AdminMeritListResponse {
         users,
@@ -350,39 +691,207 @@ pub async fn admin_list_all_merits(
     }))
 }
 
+/// Merit change averages for the committee's termly report (admin only)
+pub async fn admin_merit_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<MeritStats>, (StatusCode, Json<Value>)> {
+    let stats = state.db.merit_stats().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(stats))
+}
+
 // ============================================================================
-// Award Handlers
+// Merit Decay Handlers
 // ============================================================================
 
-/// Get all awards for a user (public - shown on profile)
-pub async fn get_user_awards(
+/// Parse a user category from the path, rejecting unknown ones
+fn parse_category(category: &str) -> Result<UserCategory, (StatusCode, Json<Value>)> {
+    category.parse().map_err(|e: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": e,
+                "categories": UserCategory::ALL
+            })),
+        )
+    })
+}
+
+/// The decay policy and which user categories are exempt (admin only)
+pub async fn admin_get_decay_policy(
     State(state): State<Arc<AppState>>,
-    Path(username): Path<String>,
-) -> Result<Json<AwardListResponse>, (StatusCode, Json<Value>)> {
-    // Find user by username
-    let user = state
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let exemptions = state.db.list_decay_exemptions().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!({
+        "policy": state.config.decay,
+        "enabled": state.config.decay.is_enabled(),
+        "categories": UserCategory::ALL,
+        "exemptions": exemptions
+    })))
+}
+
+/// Members the next decay run would reach and what each would lose
+/// (admin only)
+pub async fn admin_preview_decay(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let policy = state.config.decay;
+    let candidates = decay::due_for_decay(&state).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    let members: Vec<Value> = candidates
+        .into_iter()
+        .map(|c| (policy.amount(c.merit_points), c))
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, c)| {
+            json!({
+                "user_id": c.user_id,
+                "username": c.username,
+                "merit_points": c.merit_points,
+                "decay": amount,
+                "new_total": c.merit_points - amount,
+                "last_active_at": c.last_active_at,
+                "last_decayed_at": c.last_decayed_at
+            })
+        })
+        .collect();
+    let total_decay: i64 = members.iter().filter_map(|m| m["decay"].as_i64()).sum();
+
+    Ok(Json(json!({
+        "policy": policy,
+        "enabled": policy.is_enabled(),
+        "members": members,
+        "total_decay": total_decay
+    })))
+}
+
+/// Exempt every user in a category from decay (admin only)
+pub async fn admin_exempt_category(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(category): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = parse_category(&category)?;
+    let added = state
         .db
-        .get_user_by_username(&username)
+        .add_decay_exemption(category, admin_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
-        })?
-        .ok_or_else(|| {
+        })?;
+
+    if !added {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": format!("Category {} is already exempt", category)})),
+        ));
+    }
+
+    tracing::info!("Admin {} exempted {} from merit decay", admin_id, category);
+
+    Ok(Json(json!({
+        "message": "Category exempted from decay",
+        "category": category
+    })))
+}
+
+/// Make a category subject to decay again (admin only)
+pub async fn admin_remove_exemption(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(category): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = parse_category(&category)?;
+    let removed = state
+        .db
+        .remove_decay_exemption(category)
+        .await
+        .map_err(|_| {
             (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "User not found"})),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
             )
         })?;
 
-    let awards = state.db.get_user_awards(user.id).await.map_err(|_| {
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Category {} is not exempt", category)})),
+        ));
+    }
+
+    tracing::info!(
+        "Admin {} made {} subject to merit decay",
+        admin_id,
+        category
+    );
+
+    Ok(Json(json!({
+        "message": "Category is subject to decay again",
+        "category": category
+    })))
+}
+
+// ============================================================================
+// Award Handlers
+// ============================================================================
+
+/// Get all awards for a user (public - shown on profile)
+/// Get a user's awards. Members who curate their awards show others only
+/// the ones they feature; they and admins still see every award.
+pub async fn get_user_awards(
+    State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
+    Path(username): Path<String>,
+) -> Result<Json<AwardListResponse>, (StatusCode, Json<Value>)> {
+    // Find user by username, including one they have since changed
+    let (user, _) = find_profile(&state, &username).await?;
+
+    let db_error = |_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({"error": "Database error"})),
         )
-    })?;
+    };
+
+    let current_user_id = current_user_id.map(|Extension(id)| id);
+    let sees_everything = match current_user_id {
+        Some(id) if id == user.id => true,
+        Some(id) => state.db.is_user_admin(id).await.unwrap_or(false),
+        None => false,
+    };
+    let featured_only = !sees_everything
+        && state
+            .db
+            .get_privacy_settings(user.id)
+            .await
+            .map_err(db_error)?
+            .award_visibility
+            == AwardVisibility::Curated;
+
+    let awards = state
+        .db
+        .get_user_awards(user.id, featured_only, None)
+        .await
+        .map_err(db_error)?;
 
     let award_responses: Vec<AwardResponse> = awards
         .into_iter()
@@ -739,22 +1248,132 @@ pub async fn admin_delete_award(
 /// Get user awards - public endpoint (alias for get_user_awards)
 pub async fn get_user_awards_public(
     State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
     Path(username): Path<String>,
 ) -> Result<Json<AwardListResponse>, (StatusCode, Json<Value>)> {
-    get_user_awards(State(state), Path(username)).await
+    get_user_awards(State(state), current_user_id, Path(username)).await
+}
+
+/// Signed credential for an award, for recipients to share. Browsers and
+/// link previews asking for HTML get a page with Open Graph tags instead.
+pub async fn get_award_verification(
+    State(state): State<Arc<AppState>>,
+    Path(award_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let award = state
+        .db
+        .get_award_with_admin(award_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Award not found"})),
+            )
+        })?;
+
+    let credential = AwardCredential::from(&award);
+    let signature = verification::sign(&state.config.award_signing_secret, &credential);
+    let share_url = state
+        .config
+        .public_url
+        .as_ref()
+        .map(|base| format!("{}/awards/{}/verify", base, award_id));
+
+    let wants_html = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"));
+    if wants_html {
+        let page = verification::share_page(&credential, &signature, share_url.as_deref());
+        return Ok(([(header::CONTENT_TYPE, "text/html; charset=utf-8")], page).into_response());
+    }
+
+    Ok(Json(json!({
+        "credential": credential,
+        "signature": signature,
+        "algorithm": verification::ALGORITHM,
+        "share_url": share_url,
+    }))
+    .into_response())
+}
+
+/// Check a shared credential: that it was signed here, and that the award
+/// still stands as the credential describes it
+pub async fn verify_award(
+    State(state): State<Arc<AppState>>,
+    Json(shared): Json<SharedCredential>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if !verification::verify(
+        &state.config.award_signing_secret,
+        &shared.credential,
+        &shared.signature,
+    ) {
+        return Ok(Json(json!({
+            "valid": false,
+            "current": false,
+            "reason": "Signature does not match the credential",
+        })));
+    }
+
+    let award = state
+        .db
+        .get_award_with_admin(shared.credential.award_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let Some(award) = award else {
+        return Ok(Json(json!({
+            "valid": false,
+            "current": false,
+            "reason": "Award has been revoked",
+        })));
+    };
+
+    if AwardCredential::from(&award) != shared.credential {
+        return Ok(Json(json!({
+            "valid": true,
+            "current": false,
+            "reason": "Award has changed since this credential was issued",
+            "credential": AwardCredential::from(&award),
+        })));
+    }
+
+    Ok(Json(json!({
+        "valid": true,
+        "current": true,
+        "credential": shared.credential,
+    })))
 }
 
-/// Get my own awards (authenticated user)
+/// Get my own awards (authenticated user), optionally only from one season
 pub async fn get_my_awards(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
+    Query(query): Query<SeasonQuery>,
 ) -> Result<Json<AwardListResponse>, (StatusCode, Json<Value>)> {
-    let awards = state.db.get_user_awards(user_id).await.map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Database error"})),
-        )
-    })?;
+    ensure_season_exists(&state, query.season_id).await?;
+    let awards = state
+        .db
+        .get_user_awards(user_id, false, query.season_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
 
     let award_responses: Vec<AwardResponse> = awards
         .into_iter()
@@ -774,6 +1393,37 @@ pub async fn get_my_awards(
     }))
 }
 
+/// Feature one of my awards on my curated profile, or stop featuring it
+pub async fn set_my_award_featured(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(award_id): Path<Uuid>,
+    Json(payload): Json<FeatureAwardRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let updated = state
+        .db
+        .set_award_featured(user_id, award_id, payload.featured)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Award not found"})),
+        ));
+    }
+
+    Ok(Json(json!({
+        "award_id": award_id,
+        "featured": payload.featured,
+    })))
+}
+
 /// Get my own award history (authenticated user)
 pub async fn get_my_awards_history(
     State(state): State<Arc<AppState>>,
@@ -801,13 +1451,14 @@ pub async fn admin_list_all_awards(
     State(state): State<Arc<AppState>>,
     Query(query): Query<MeritHistoryQuery>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    ensure_season_exists(&state, query.season_id).await?;
+    let pagination = Pagination::new(query.page, query.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
     let offset = (page - 1) * per_page;
 
     let (awards, total) = state
         .db
-        .list_all_awards(per_page, offset)
+        .list_all_awards(query.season_id, per_page, offset)
         .await
         .map_err(|_| {
             (
@@ -816,7 +1467,7 @@ pub async fn admin_list_all_awards(
             )
         })?;
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(json!({
         "awards": awards,
@@ -826,3 +1477,343 @@ pub async fn admin_list_all_awards(
         "total_pages": total_pages
     })))
 }
+
+// ============================================================================
+// Season Handlers
+// ============================================================================
+
+fn season_not_found() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({"error": "Season not found"})),
+    )
+}
+
+/// Reject a `season_id` filter naming no season
+async fn ensure_season_exists(
+    state: &AppState,
+    season_id: Option<Uuid>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let Some(season_id) = season_id else {
+        return Ok(());
+    };
+    state
+        .db
+        .get_season(season_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(season_not_found)?;
+    Ok(())
+}
+
+/// Map a failed season write, explaining clashes with other seasons
+fn season_write_error(e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    let clash = match e.as_database_error() {
+        Some(db_err) if db_err.is_unique_violation() => {
+            Some("A season with that name already exists")
+        }
+        Some(db_err) if db_err.code().as_deref() == Some("23P01") => {
+            Some("Seasons cannot overlap another season's dates")
+        }
+        _ => None,
+    };
+    match clash {
+        Some(error) => (StatusCode::CONFLICT, Json(json!({"error": error}))),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to save season"})),
+        ),
+    }
+}
+
+fn season_dates_reversed() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "A season cannot end before it starts"})),
+    )
+}
+
+/// List every season, latest first (authenticated user)
+pub async fn list_seasons(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let seasons = state.db.list_seasons().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!({ "seasons": seasons })))
+}
+
+/// Create a season (admin only)
+pub async fn admin_create_season(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Json(payload): Json<CreateSeasonRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+    if payload.ends_on < payload.starts_on {
+        return Err(season_dates_reversed());
+    }
+
+    let season = state
+        .db
+        .create_season(
+            payload.name.trim(),
+            payload.starts_on,
+            payload.ends_on,
+            admin_id,
+        )
+        .await
+        .map_err(season_write_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Season created successfully",
+            "season": season
+        })),
+    ))
+}
+
+/// Rename an open season or move its dates (admin only)
+pub async fn admin_update_season(
+    State(state): State<Arc<AppState>>,
+    Path(season_id): Path<Uuid>,
+    Json(payload): Json<UpdateSeasonRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    let existing = state
+        .db
+        .get_season(season_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(season_not_found)?;
+    if existing.closed_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A closed season cannot be changed"})),
+        ));
+    }
+    let starts_on = payload.starts_on.unwrap_or(existing.starts_on);
+    let ends_on = payload.ends_on.unwrap_or(existing.ends_on);
+    if ends_on < starts_on {
+        return Err(season_dates_reversed());
+    }
+
+    let season = state
+        .db
+        .update_season(
+            season_id,
+            payload.name.as_deref().map(str::trim),
+            payload.starts_on,
+            payload.ends_on,
+        )
+        .await
+        .map_err(season_write_error)?
+        .ok_or_else(season_not_found)?;
+
+    Ok(Json(json!({
+        "message": "Season updated successfully",
+        "season": season
+    })))
+}
+
+/// Delete an open season (admin only)
+pub async fn admin_delete_season(
+    State(state): State<Arc<AppState>>,
+    Path(season_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let existing = state
+        .db
+        .get_season(season_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(season_not_found)?;
+    if existing.closed_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A closed season cannot be deleted"})),
+        ));
+    }
+
+    if !state.db.delete_season(season_id).await.map_err(db_error)? {
+        return Err(season_not_found());
+    }
+
+    Ok(Json(json!({"message": "Season deleted successfully"})))
+}
+
+/// Close a season that has ended, snapshotting its standings (admin only)
+pub async fn admin_close_season(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(season_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let existing = state
+        .db
+        .get_season(season_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(season_not_found)?;
+    if existing.closed_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Season is already closed"})),
+        ));
+    }
+    if existing.ends_on >= chrono::Utc::now().date_naive() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "A season can only be closed after its last day"})),
+        ));
+    }
+
+    let season = state
+        .db
+        .close_season(season_id, admin_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to close season"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Season is already closed"})),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "message": "Season closed and standings recorded",
+        "season": season
+    })))
+}
+
+/// A season's standings, ranked by merit earned and then by wins (admin
+/// only)
+pub async fn admin_season_standings(
+    State(state): State<Arc<AppState>>,
+    Path(season_id): Path<Uuid>,
+    Query(query): Query<MeritHistoryQuery>,
+) -> Result<Json<SeasonStandingsResponse>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let season = state
+        .db
+        .get_season(season_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(season_not_found)?;
+
+    let pagination = Pagination::new(query.page, query.per_page, 50);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+    let (standings, total) = state
+        .db
+        .season_standings(&season, page, per_page)
+        .await
+        .map_err(db_error)?;
+
+    let total_pages = pagination.total_pages(total) as i32;
+
+    Ok(Json(SeasonStandingsResponse {
+        season,
+        standings,
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
+
+/// Generate a season's end-of-season report, as JSON or as a PDF with
+/// `?format=pdf` (admin only). Open seasons are reported as they stand.
+pub async fn admin_season_report(
+    State(state): State<Arc<AppState>>,
+    Path(season_id): Path<Uuid>,
+    Query(query): Query<SeasonReportQuery>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Failed to build the report for season {}: {}", season_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let db = &state.db;
+    let season = db
+        .get_season(season_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(season_not_found)?;
+
+    let (merit_leaders, _) = db
+        .season_standings(&season, 1, LEADERBOARD_SIZE)
+        .await
+        .map_err(db_error)?;
+    let report = SeasonReport {
+        events_held: db.season_event_count(season_id).await.map_err(db_error)?,
+        merit_leaders,
+        top_speakers: db
+            .season_top_speakers(season_id, MIN_SPEECHES, LEADERBOARD_SIZE)
+            .await
+            .map_err(db_error)?,
+        most_improved: db
+            .season_most_improved(season_id, MIN_SPEECHES, LEADERBOARD_SIZE)
+            .await
+            .map_err(db_error)?,
+        attendance_champions: db
+            .season_attendance_champions(season_id, LEADERBOARD_SIZE)
+            .await
+            .map_err(db_error)?,
+        awards: db.season_awards(season_id).await.map_err(db_error)?,
+        season,
+        generated_at: chrono::Utc::now(),
+    };
+
+    Ok(match query.format {
+        ReportFormat::Json => Json(report).into_response(),
+        ReportFormat::Pdf => report.pdf().into_response(),
+    })
+}
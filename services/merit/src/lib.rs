@@ -1,48 +1,96 @@
 pub mod auth_middleware;
+pub mod bulk;
 pub mod config;
 pub mod database;
+pub mod decay;
+pub mod etags;
 pub mod handlers;
 pub mod models;
+pub mod outbox;
+pub mod season_report;
+pub mod verification;
 
+pub use common::startup::StartupError;
 pub use config::Config;
 pub use database::Database;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{get, post},
     Router,
 };
+use common::{versioning::CURRENT_VERSION, EventBus, Features, KvStore, Maintenance, Notifier};
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
 
 pub struct AppState {
     pub db: Database,
     pub config: Config,
+    pub notifier: Notifier,
+    pub events: EventBus,
+    /// Cached admin and suspension checks, shared between replicas when
+    /// Redis is set up
+    pub kv: KvStore,
+    /// Feature flags, with admins' overrides from the database
+    pub features: Features,
+    /// Whether non-admins are being turned away
+    pub maintenance: Maintenance,
 }
 
-pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
-    let config = Config::from_env()?;
+/// Connect to the database, run migrations and build the shared state.
+/// Tests can skip this and construct `AppState` around their own database.
+pub async fn build_state(config: Config) -> Result<Arc<AppState>, StartupError> {
     let db = Database::new(&config.database_url).await?;
+    let db = match &config.database_replica_url {
+        Some(url) => db.with_replica(common::ReadReplica::connect(url, 5)?),
+        None => db,
+    };
     db.migrate().await?;
 
-    let state = Arc::new(AppState {
+    let notifier = Notifier::new(&config.notifications);
+    let events = EventBus::connect(&config.event_bus).await;
+    let kv = KvStore::new(&config.kv);
+    let features = Features::new(db.pool().clone(), &config.features);
+    let maintenance = Maintenance::new(db.pool().clone(), outbox::SERVICE);
+
+    Ok(Arc::new(AppState {
         db,
-        config: config.clone(),
-    });
+        config,
+        notifier,
+        events,
+        kv,
+        features,
+        maintenance,
+    }))
+}
 
-    let cors = configure_cors(&config);
+/// Assemble the service's routes around already-built state
+pub fn create_app(state: Arc<AppState>) -> Router {
+    let cors = common::configure_cors(&state.config.cors, &[]);
 
     // Truly public routes (optional authentication - extracts user if logged in)
     let unauthenticated_routes = Router::new()
         // Public profiles - shareable with anyone
-        .route("/users/:username", get(handlers::get_profile_by_username))
+        .route(
+            "/users/:username",
+            get(handlers::get_profile_by_username).layer(middleware::from_fn_with_state(
+                state.clone(),
+                etags::profile_etag,
+            )),
+        )
         .route(
             "/users/:username/awards",
             get(handlers::get_user_awards_public),
         )
+        // Award credentials - shareable and checkable by anyone
+        .route(
+            "/awards/:award_id/verify",
+            get(handlers::get_award_verification),
+        )
+        .route("/awards/verify", post(handlers::verify_award))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::optional_auth_middleware,
+            auth_middleware::optional_auth_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
@@ -54,9 +102,20 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
         // Own awards routes
         .route("/awards/me", get(handlers::get_my_awards))
         .route("/awards/me/history", get(handlers::get_my_awards_history))
+        .route(
+            "/awards/me/:award_id/featured",
+            axum::routing::put(handlers::set_my_award_featured),
+        )
+        // Seasons
+        .route("/seasons", get(handlers::list_seasons))
+        // Privacy settings
+        .route(
+            "/privacy/me",
+            get(handlers::get_my_privacy).put(handlers::update_my_privacy),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::auth_middleware,
+            auth_middleware::auth_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
@@ -69,6 +128,35 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/admin/merit/:user_id/history",
             get(handlers::admin_get_user_merit_history),
         )
+        .route(
+            "/admin/merit/bulk",
+            post(handlers::admin_bulk_update_merit)
+                .layer(DefaultBodyLimit::max(state.config.limits.bulk_body_bytes)),
+        )
+        .route(
+            "/admin/merit/bulk/csv",
+            post(handlers::admin_bulk_update_merit_csv)
+                .layer(DefaultBodyLimit::max(state.config.limits.bulk_body_bytes)),
+        )
+        .route(
+            "/admin/merit/transfer",
+            post(handlers::admin_transfer_merit),
+        )
+        .route(
+            "/admin/stats",
+            get(handlers::admin_merit_stats)
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        // Merit decay
+        .route("/admin/merit/decay", get(handlers::admin_get_decay_policy))
+        .route(
+            "/admin/merit/decay/preview",
+            get(handlers::admin_preview_decay),
+        )
+        .route(
+            "/admin/merit/decay/exemptions/:category",
+            post(handlers::admin_exempt_category).delete(handlers::admin_remove_exemption),
+        )
         // Admin awards routes
         .route("/admin/awards", get(handlers::admin_list_all_awards))
         .route("/admin/awards", post(handlers::admin_create_award))
@@ -84,77 +172,52 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/admin/awards/:user_id/history",
             get(handlers::admin_get_award_history),
         )
+        // Seasons
+        .route("/admin/seasons", post(handlers::admin_create_season))
+        .route(
+            "/admin/seasons/:season_id",
+            axum::routing::patch(handlers::admin_update_season)
+                .delete(handlers::admin_delete_season),
+        )
+        .route(
+            "/admin/seasons/:season_id/close",
+            post(handlers::admin_close_season),
+        )
+        .route(
+            "/admin/seasons/:season_id/standings",
+            get(handlers::admin_season_standings),
+        )
+        .route(
+            "/admin/seasons/:season_id/report",
+            post(handlers::admin_season_report),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::admin_middleware,
+            auth_middleware::admin_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
-    let app = Router::new()
+    let api = Router::new()
         .merge(unauthenticated_routes)
         .merge(authenticated_routes)
-        .merge(admin_routes)
-        .route("/health", get(|| async { "OK" }))
-        .layer(cors);
+        .merge(admin_routes);
 
-    Ok(app)
-}
-
-fn configure_cors(config: &Config) -> CorsLayer {
-    if config.cors_strict_mode {
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
-    } else if config.allowed_origins.contains(&"*".to_string()) {
-        // Development mode - allow all origins with all methods and headers
-        CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers(Any)
-            .allow_credentials(false) // Cannot use credentials with wildcard origin
-    } else {
-        // Specific origins mode (non-strict) - allow listed origins only
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
-    }
+    // New clients use /v1; the unversioned paths stay for app releases
+    // that predate it, announcing their deprecation
+    common::Versions::new()
+        .mount(CURRENT_VERSION, api.clone())
+        .unversioned(api, state.config.unversioned.clone())
+        .into_router()
+        .route("/health", get(|| async { "OK" }))
+        .layer(middleware::from_fn_with_state(
+            (state.clone(), state.maintenance.clone()),
+            common::maintenance::maintenance_mode::<AppState>,
+        ))
+        .layer(DefaultBodyLimit::max(state.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.config.compression.layer())
+        .layer(cors)
 }
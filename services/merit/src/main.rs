@@ -1,22 +1,42 @@
-use merit::{create_app, Config};
+use common::redact::{RedactSettings, Redactor};
+use merit::{build_state, create_app, Config, StartupError};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == "--print-config-schema") {
+        print!("{}", Config::schema_doc());
+        return Ok(());
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "merit=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        // Emails, phone numbers and codes are redacted unless LOG_REDACT_ALLOW
+        // says otherwise
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(Redactor::new(&RedactSettings::from_env()).writer(std::io::stdout)),
+        )
         .init();
 
     // Load configuration
-    let config = Config::from_env().expect("Failed to load configuration");
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => exit_on_startup_error(e.into()),
+    };
 
     // Create application
-    let app = create_app().await?;
+    let state = match build_state(config.clone()).await {
+        Ok(state) => state,
+        Err(e) => exit_on_startup_error(e),
+    };
+    merit::decay::spawn(state.clone());
+    merit::outbox::spawn(state.clone());
+    let app = create_app(state);
 
     // Start server
     let addr = format!("{}:{}", config.host, config.port);
@@ -28,3 +48,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Log why startup failed and exit with the code for that failure
+fn exit_on_startup_error(e: StartupError) -> ! {
+    tracing::error!("Startup failed: {}", e);
+    std::process::exit(e.exit_code());
+}
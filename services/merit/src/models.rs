@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
@@ -27,6 +27,10 @@ pub struct MeritHistory {
     pub previous_total: i32,
     pub new_total: i32,
     pub reason: String,
+    /// `manual` for admin changes, `decay` for inactivity decay
+    pub kind: String,
+    /// Shared by changes applied together in one bulk adjustment or transfer
+    pub batch_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -41,21 +45,95 @@ pub struct MeritHistoryWithAdmin {
     pub previous_total: i32,
     pub new_total: i32,
     pub reason: String,
+    pub kind: String,
+    pub batch_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
-// ============================================================================
-// JWT Claims (for validating tokens from auth service)
-// ============================================================================
+/// A member whose merit is due to decay
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DecayCandidate {
+    pub user_id: Uuid,
+    pub username: String,
+    pub merit_points: i32,
+    /// Latest event check-in or admin merit change, or sign-up if neither
+    pub last_active_at: DateTime<Utc>,
+    pub last_decayed_at: Option<DateTime<Utc>>,
+}
+
+/// A user category exempted from merit decay
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DecayExemption {
+    pub category: String,
+    pub exempted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A named span of dates that stats can be filtered to
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Season {
+    pub id: Uuid,
+    pub name: String,
+    pub starts_on: NaiveDate,
+    /// Last day of the season, inclusive
+    pub ends_on: NaiveDate,
+    /// Set once an admin closes the season and its standings are snapshotted
+    pub closed_at: Option<DateTime<Utc>>,
+    pub closed_by: Option<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String, // user_id
+/// A member's place in a season's standings, ranked by merit earned and
+/// then by wins
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SeasonStanding {
+    pub rank: i32,
+    pub user_id: Uuid,
+    pub username: String,
+    pub merit_earned: i32,
+    pub awards: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub average_speaker_score: Option<f64>,
+}
+
+/// A member's average speaker score over a season
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TopSpeaker {
+    pub user_id: Uuid,
     pub username: String,
-    pub exp: i64,
-    pub iat: i64,
-    pub jti: String,
-    pub token_type: String,
+    pub average_speaker_score: f64,
+    pub speeches: i32,
+}
+
+/// A member whose season average beat their average from before the season
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ImprovedSpeaker {
+    pub user_id: Uuid,
+    pub username: String,
+    pub previous_average: f64,
+    pub season_average: f64,
+    pub improvement: f64,
+}
+
+/// How many of a season's events a member checked in to
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AttendanceChampion {
+    pub user_id: Uuid,
+    pub username: String,
+    pub events_attended: i32,
+}
+
+/// An award given during a season, as listed in the season report
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SeasonAward {
+    pub user_id: Uuid,
+    pub username: String,
+    pub title: String,
+    pub tier: AwardTier,
+    pub awarded_at: DateTime<Utc>,
 }
 
 // ============================================================================
@@ -78,11 +156,84 @@ pub struct UpdateMeritRequest {
     pub reason: String,
 }
 
+/// One change in a bulk merit adjustment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Validate)]
+pub struct MeritChange {
+    pub user_id: Uuid,
+    /// Amount to change (positive to add, negative to remove)
+    pub change_amount: i32,
+    #[validate(length(
+        min = 3,
+        max = 500,
+        message = "Reason must be between 3 and 500 characters"
+    ))]
+    pub reason: String,
+}
+
+/// Request to apply many merit changes at once (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkMeritRequest {
+    #[validate(
+        length(min = 1, max = 500, message = "Give between 1 and 500 changes"),
+        nested
+    )]
+    pub changes: Vec<MeritChange>,
+}
+
+/// Request to move merit from one user to another (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct TransferMeritRequest {
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    #[validate(range(min = 1, message = "Amount must be positive"))]
+    pub amount: i32,
+    #[validate(length(
+        min = 3,
+        max = 500,
+        message = "Reason must be between 3 and 500 characters"
+    ))]
+    pub reason: String,
+}
+
 /// Query parameters for listing merit history
 #[derive(Debug, Deserialize)]
 pub struct MeritHistoryQuery {
     pub page: Option<i32>,
     pub per_page: Option<i32>,
+    /// Only count merit changes and awards from this season
+    pub season_id: Option<Uuid>,
+}
+
+/// Query parameters for filtering to a season
+#[derive(Debug, Deserialize)]
+pub struct SeasonQuery {
+    pub season_id: Option<Uuid>,
+}
+
+/// Request to create a season (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSeasonRequest {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+}
+
+/// Request to rename a season or move its dates (admin only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateSeasonRequest {
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Name must be between 1 and 50 characters"
+    ))]
+    pub name: Option<String>,
+    pub starts_on: Option<NaiveDate>,
+    pub ends_on: Option<NaiveDate>,
 }
 
 // ============================================================================
@@ -95,6 +246,9 @@ pub struct PublicProfileResponse {
     pub id: Uuid,
     pub username: String,
     pub year_joined: i32,
+    /// Only present when the member has chosen to show their merit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merit_points: Option<i32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -151,6 +305,21 @@ pub struct UserMeritInfo {
     pub user_id: Uuid,
     pub username: String,
     pub merit_points: i32,
+    /// Net merit change within the season, when the list is for a season
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season_merit: Option<i32>,
+}
+
+/// A season's standings, live while it is open and from the snapshot once
+/// it is closed
+#[derive(Debug, Serialize)]
+pub struct SeasonStandingsResponse {
+    pub season: Season,
+    pub standings: Vec<SeasonStanding>,
+    pub total: i64,
+    pub page: i32,
+    pub per_page: i32,
+    pub total_pages: i32,
 }
 
 /// Admin merit list response
@@ -163,6 +332,28 @@ pub struct AdminMeritListResponse {
     pub total_pages: i32,
 }
 
+/// Merit changes made in one month
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MeritMonth {
+    pub period: DateTime<Utc>,
+    pub changes: i64,
+    pub average_change: Option<f64>,
+}
+
+/// Merit change averages for the admin stats report (admin only)
+#[derive(Debug, Serialize)]
+pub struct MeritStats {
+    pub total_changes: i64,
+    pub average_change: Option<f64>,
+    /// Average of the changes that added points
+    pub average_award: Option<f64>,
+    /// Average of the changes that took points away
+    pub average_deduction: Option<f64>,
+    /// Average current total across verified users
+    pub average_merit: Option<f64>,
+    pub changes_per_month: Vec<MeritMonth>,
+}
+
 // ============================================================================
 // Award Models
 // ============================================================================
@@ -356,3 +547,77 @@ pub struct AwardHistoryResponse {
     pub history: Vec<AwardHistoryWithAdmin>,
     pub total: i64,
 }
+
+// ============================================================================
+// Privacy Models
+// ============================================================================
+
+/// Which of a member's awards the public sees
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AwardVisibility {
+    /// Every award
+    #[default]
+    All,
+    /// Only the awards the member features
+    Curated,
+}
+
+impl AwardVisibility {
+    pub const ALL: &'static [AwardVisibility] = &[AwardVisibility::All, AwardVisibility::Curated];
+
+    /// Value stored in `merit_privacy_settings.award_visibility`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AwardVisibility::All => "all",
+            AwardVisibility::Curated => "curated",
+        }
+    }
+}
+
+impl std::fmt::Display for AwardVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AwardVisibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AwardVisibility::ALL
+            .iter()
+            .copied()
+            .find(|visibility| visibility.as_str() == s)
+            .ok_or_else(|| format!("Unknown award visibility: {}", s))
+    }
+}
+
+/// What a member lets the public see. Members who never chose keep the
+/// defaults: merit hidden, every award shown.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PrivacySettings {
+    pub show_merit: bool,
+    pub award_visibility: AwardVisibility,
+}
+
+/// Request to change privacy settings; omitted fields are left as they are
+#[derive(Debug, Deserialize)]
+pub struct UpdatePrivacyRequest {
+    pub show_merit: Option<bool>,
+    pub award_visibility: Option<AwardVisibility>,
+}
+
+/// Request to feature an award on a curated profile, or stop featuring it
+#[derive(Debug, Deserialize)]
+pub struct FeatureAwardRequest {
+    pub featured: bool,
+}
+
+/// Privacy settings with the awards currently featured
+#[derive(Debug, Serialize)]
+pub struct PrivacyResponse {
+    #[serde(flatten)]
+    pub settings: PrivacySettings,
+    pub featured_award_ids: Vec<Uuid>,
+}
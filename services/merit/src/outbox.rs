@@ -0,0 +1,101 @@
+//! Domain events the merit service publishes through the outbox (see
+//! `common::outbox`)
+
+use std::sync::Arc;
+
+use common::outbox::{OutboxEvent, Relay};
+use common::{Notification, NotificationKind};
+use serde_json::{json, Value};
+
+use crate::models::MeritHistory;
+use crate::AppState;
+
+pub const SERVICE: &str = "merit";
+
+/// A member's merit went up or down, by an admin or through decay
+pub const MERIT_CHANGED: &str = "merit_changed";
+
+/// `merit_changed` payload: the history entry plus the member's username
+pub fn merit_changed(history: &MeritHistory, username: Option<&str>) -> Value {
+    json!({
+        "history_id": history.id,
+        "user_id": history.user_id,
+        "username": username,
+        "admin_id": history.admin_id,
+        "change_amount": history.change_amount,
+        "previous_total": history.previous_total,
+        "new_total": history.new_total,
+        "reason": history.reason,
+        "kind": history.kind,
+        "batch_id": history.batch_id,
+        "changed_at": history.created_at,
+    })
+}
+
+/// Start delivering the service's events in the background
+pub fn spawn(state: Arc<AppState>) {
+    Relay::new(state.db.pool().clone(), SERVICE, &state.config.outbox)
+        .with_chat(state.notifier.clone(), notification)
+        .with_bus(state.events.clone())
+        .with_features(state.features.clone())
+        .spawn();
+}
+
+fn notification(event: &OutboxEvent) -> Option<Notification> {
+    if event.kind != MERIT_CHANGED {
+        return None;
+    }
+    let payload = &event.payload;
+    let username = payload["username"].as_str().unwrap_or("A member");
+    let change = payload["change_amount"].as_i64()?;
+    let total = payload["new_total"].as_i64()?;
+
+    Some(
+        Notification::new(
+            NotificationKind::MeritChanged,
+            format!("Merit changed: {}", username),
+        )
+        .description(payload["reason"].as_str().unwrap_or_default())
+        .inline_field("Change", format!("{:+}", change))
+        .inline_field("Total", total.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_merit_changed_notification() {
+        let history = MeritHistory {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            admin_id: None,
+            change_amount: -4,
+            previous_total: 40,
+            new_total: 36,
+            reason: "Inactive this semester".to_string(),
+            kind: "decay".to_string(),
+            batch_id: None,
+            created_at: Utc::now(),
+        };
+        let event = OutboxEvent {
+            id: Uuid::nil(),
+            service: SERVICE.to_string(),
+            kind: MERIT_CHANGED.to_string(),
+            payload: merit_changed(&history, Some("bilal")),
+            created_at: Utc::now(),
+            attempts: 0,
+            delivered_to: Vec::new(),
+        };
+
+        let notification = notification(&event).unwrap();
+        assert_eq!(notification.title, "Merit changed: bilal");
+        assert_eq!(notification.description, "Inactive this semester");
+        assert_eq!(notification.fields[0].value, "-4");
+        assert_eq!(notification.fields[1].value, "36");
+        assert_eq!(event.payload["kind"], "decay");
+    }
+}
@@ -0,0 +1,286 @@
+//! End-of-season summary for the AGM: merit leaders, top speakers, the most
+//! improved speakers, attendance champions and every award given. Admins
+//! fetch it as JSON or as a PDF.
+
+use chrono::{DateTime, Utc};
+use common::pdf::{PdfDocument, TextStyle};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    AttendanceChampion, ImprovedSpeaker, Season, SeasonAward, SeasonStanding, TopSpeaker,
+};
+
+/// Members listed in each ranking
+pub const LEADERBOARD_SIZE: i32 = 10;
+/// Speeches a member needs in a season (and, for most improved, before it)
+/// before their average is ranked, so one lucky round does not top the table
+pub const MIN_SPEECHES: i32 = 4;
+
+/// Longest username shown in full in the PDF tables
+const MAX_NAME_COLUMN: usize = 28;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Pdf,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonReportQuery {
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeasonReport {
+    pub season: Season,
+    /// Events held during the season
+    pub events_held: i64,
+    pub merit_leaders: Vec<SeasonStanding>,
+    pub top_speakers: Vec<TopSpeaker>,
+    pub most_improved: Vec<ImprovedSpeaker>,
+    pub attendance_champions: Vec<AttendanceChampion>,
+    pub awards: Vec<SeasonAward>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl SeasonReport {
+    pub fn title(&self) -> String {
+        format!("Season report: {}", self.season.name)
+    }
+
+    /// `season-report-<name>.<extension>`, safe to use as a filename
+    pub fn filename(&self, extension: &str) -> String {
+        let mut slug = String::new();
+        for c in self.season.name.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        format!("season-report-{}.{}", slug.trim_end_matches('-'), extension)
+    }
+
+    pub fn pdf(&self) -> PdfDocument {
+        let mut doc = PdfDocument::new(self.title(), self.filename("pdf"));
+        doc.push(TextStyle::Heading, &self.title());
+        let status = if self.season.closed_at.is_some() {
+            "closed"
+        } else {
+            "still open; figures may change"
+        };
+        doc.push(
+            TextStyle::Body,
+            &format!(
+                "{} to {} ({})",
+                self.season.starts_on.format("%d %b %Y"),
+                self.season.ends_on.format("%d %b %Y"),
+                status
+            ),
+        );
+        doc.push(
+            TextStyle::Body,
+            &format!("Events held: {}", self.events_held),
+        );
+
+        section(
+            &mut doc,
+            "Merit leaders",
+            &["Merit", "Awards", "W-L"],
+            self.merit_leaders.iter().map(|row| {
+                (
+                    row.rank.to_string(),
+                    row.username.as_str(),
+                    vec![
+                        row.merit_earned.to_string(),
+                        row.awards.to_string(),
+                        format!("{}-{}", row.wins, row.losses),
+                    ],
+                )
+            }),
+        );
+        section(
+            &mut doc,
+            "Top speakers",
+            &["Average", "Speeches"],
+            self.top_speakers.iter().enumerate().map(|(i, row)| {
+                (
+                    (i + 1).to_string(),
+                    row.username.as_str(),
+                    vec![
+                        format!("{:.2}", row.average_speaker_score),
+                        row.speeches.to_string(),
+                    ],
+                )
+            }),
+        );
+        section(
+            &mut doc,
+            "Most improved",
+            &["Before", "Season", "Change"],
+            self.most_improved.iter().enumerate().map(|(i, row)| {
+                (
+                    (i + 1).to_string(),
+                    row.username.as_str(),
+                    vec![
+                        format!("{:.2}", row.previous_average),
+                        format!("{:.2}", row.season_average),
+                        format!("+{:.2}", row.improvement),
+                    ],
+                )
+            }),
+        );
+        section(
+            &mut doc,
+            "Attendance champions",
+            &["Attended"],
+            self.attendance_champions
+                .iter()
+                .enumerate()
+                .map(|(i, row)| {
+                    (
+                        (i + 1).to_string(),
+                        row.username.as_str(),
+                        vec![format!("{} of {}", row.events_attended, self.events_held)],
+                    )
+                }),
+        );
+
+        doc.blank_line();
+        doc.push(TextStyle::Heading, "Awards");
+        if self.awards.is_empty() {
+            doc.push(TextStyle::Body, "None.");
+        }
+        for award in &self.awards {
+            doc.push(
+                TextStyle::Body,
+                &format!(
+                    "{}: {} ({}), {}",
+                    award.username,
+                    award.title,
+                    award.tier,
+                    award.awarded_at.format("%d %b %Y")
+                ),
+            );
+        }
+
+        doc.blank_line();
+        doc.push(
+            TextStyle::Body,
+            &format!(
+                "Speaker averages need at least {} speeches; most improved compares \
+                 the season with every earlier event.",
+                MIN_SPEECHES
+            ),
+        );
+        doc.push(
+            TextStyle::Body,
+            &format!(
+                "Generated {}",
+                self.generated_at.format("%d %b %Y, %H:%M UTC")
+            ),
+        );
+        doc
+    }
+}
+
+/// A ranked table: place, member and one column per heading
+fn section<'a>(
+    doc: &mut PdfDocument,
+    heading: &str,
+    columns: &[&str],
+    rows: impl Iterator<Item = (String, &'a str, Vec<String>)>,
+) {
+    doc.blank_line();
+    doc.push(TextStyle::Heading, heading);
+
+    let rows: Vec<_> = rows.collect();
+    if rows.is_empty() {
+        doc.push(TextStyle::Body, "Nobody qualified.");
+        return;
+    }
+
+    let name_width = rows
+        .iter()
+        .map(|(_, name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp("Member".len(), MAX_NAME_COLUMN);
+    let mut header = format!("{:<4}  {:<name_width$}", "#", "Member");
+    for column in columns {
+        header.push_str(&format!("  {:>10}", column));
+    }
+    doc.push(TextStyle::Mono, &header);
+
+    for (place, name, values) in rows {
+        let mut line = format!("{:<4}  {:<name_width$}", place, fit(name, name_width));
+        for value in values {
+            line.push_str(&format!("  {:>10}", value));
+        }
+        doc.push(TextStyle::Mono, &line);
+    }
+}
+
+/// `name` cut to `width` characters, marking the cut with `~`
+fn fit(name: &str, width: usize) -> String {
+    let mut fitted: String = name.chars().take(width).collect();
+    if name.chars().count() > width {
+        fitted.pop();
+        fitted.push('~');
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn report(name: &str) -> SeasonReport {
+        let now = Utc::now();
+        SeasonReport {
+            season: Season {
+                id: Uuid::new_v4(),
+                name: name.to_string(),
+                starts_on: NaiveDate::from_ymd_opt(2025, 9, 1).unwrap(),
+                ends_on: NaiveDate::from_ymd_opt(2026, 6, 30).unwrap(),
+                closed_at: None,
+                closed_by: None,
+                created_by: None,
+                created_at: now,
+                updated_at: now,
+            },
+            events_held: 0,
+            merit_leaders: Vec::new(),
+            top_speakers: Vec::new(),
+            most_improved: Vec::new(),
+            attendance_champions: Vec::new(),
+            awards: Vec::new(),
+            generated_at: now,
+        }
+    }
+
+    #[test]
+    fn filenames_are_slugs_of_the_season_name() {
+        assert_eq!(
+            report("2025/26 Season").filename("pdf"),
+            "season-report-2025-26-season.pdf"
+        );
+    }
+
+    #[test]
+    fn long_names_are_cut_with_a_marker() {
+        assert_eq!(fit("abcdef", 4), "abc~");
+        assert_eq!(fit("abc", 4), "abc");
+    }
+
+    #[test]
+    fn empty_seasons_still_render() {
+        let pdf = report("Quiet").pdf().render();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Signed award credentials that recipients can share and anyone can check.
+//!
+//! A credential states who received which award, and is signed with
+//! HMAC-SHA256 under the service's award signing secret. The signature covers
+//! the credential's JSON exactly as this module serializes it, so anyone
+//! holding a credential can post it back to be checked, and a screenshot is
+//! no longer the only proof.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::models::{AwardTier, AwardWithAdmin};
+
+/// Named as the issuer in every credential
+pub const ISSUER: &str = "Tabrela";
+
+/// Algorithm reported alongside signatures
+pub const ALGORITHM: &str = "HMAC-SHA256";
+
+/// What a shared award claims
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AwardCredential {
+    pub award_id: Uuid,
+    pub recipient: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub tier: AwardTier,
+    pub awarded_at: DateTime<Utc>,
+    pub issuer: String,
+}
+
+/// A credential as shared, posted back to be checked
+#[derive(Debug, Deserialize)]
+pub struct SharedCredential {
+    pub credential: AwardCredential,
+    pub signature: String,
+}
+
+impl From<&AwardWithAdmin> for AwardCredential {
+    fn from(award: &AwardWithAdmin) -> Self {
+        Self {
+            award_id: award.id,
+            recipient: award.username.clone(),
+            title: award.title.clone(),
+            description: award.description.clone(),
+            tier: award.tier,
+            awarded_at: award.awarded_at,
+            issuer: ISSUER.to_string(),
+        }
+    }
+}
+
+fn mac(secret: &str, credential: &AwardCredential) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(&serde_json::to_vec(credential).expect("credentials always serialize"));
+    mac
+}
+
+/// Hex signature of a credential
+pub fn sign(secret: &str, credential: &AwardCredential) -> String {
+    hex::encode(mac(secret, credential).finalize().into_bytes())
+}
+
+/// Whether `signature` was made for this credential under `secret`
+pub fn verify(secret: &str, credential: &AwardCredential, signature: &str) -> bool {
+    match hex::decode(signature.trim()) {
+        Ok(bytes) => mac(secret, credential).verify_slice(&bytes).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// A page for link previews on social sites, with the credential in its
+/// Open Graph tags and the signature to check it by
+pub fn share_page(credential: &AwardCredential, signature: &str, url: Option<&str>) -> String {
+    let title = format!(
+        "{} award: {}",
+        capitalize(&credential.tier.to_string()),
+        credential.title
+    );
+    let summary = format!(
+        "Awarded to {} by {} on {}",
+        credential.recipient,
+        credential.issuer,
+        credential.awarded_at.format("%-d %B %Y")
+    );
+    let og_url = url
+        .map(|url| format!("<meta property=\"og:url\" content=\"{}\">\n", escape(url)))
+        .unwrap_or_default();
+    let description = credential
+        .description
+        .as_deref()
+        .map(|d| format!("<p>{}</p>\n", escape(d)))
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<meta property="og:type" content="website">
+<meta property="og:site_name" content="{issuer}">
+<meta property="og:title" content="{title}">
+<meta property="og:description" content="{summary}">
+{og_url}</head>
+<body>
+<h1>{title}</h1>
+<p>{summary}</p>
+{description}<p>Award {award_id}, signed by {issuer} ({algorithm}): <code>{signature}</code></p>
+</body>
+</html>
+"#,
+        title = escape(&title),
+        issuer = escape(&credential.issuer),
+        summary = escape(&summary),
+        og_url = og_url,
+        description = description,
+        award_id = credential.award_id,
+        algorithm = ALGORITHM,
+        signature = signature,
+    )
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Escape text for HTML content and attribute values
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn credential() -> AwardCredential {
+        AwardCredential {
+            award_id: Uuid::from_u128(1),
+            recipient: "ali".to_string(),
+            title: "Best Speaker".to_string(),
+            description: Some("Top of the <open> tab".to_string()),
+            tier: AwardTier::Gold,
+            awarded_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            issuer: ISSUER.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign("secret", &credential());
+        assert!(verify("secret", &credential(), &signature));
+        assert!(!verify("other-secret", &credential(), &signature));
+        assert!(!verify("secret", &credential(), "not hex"));
+    }
+
+    #[test]
+    fn test_any_change_breaks_the_signature() {
+        let signature = sign("secret", &credential());
+        let mut upgraded = credential();
+        upgraded.tier = AwardTier::Silver;
+        assert!(!verify("secret", &upgraded, &signature));
+
+        // Survives a trip through JSON, as a shared credential would
+        let shared: AwardCredential =
+            serde_json::from_str(&serde_json::to_string(&credential()).unwrap()).unwrap();
+        assert!(verify("secret", &shared, &signature));
+    }
+
+    #[test]
+    fn test_share_page_escapes_award_text() {
+        let page = share_page(&credential(), "abc", Some("https://example.com/a?b=1&c=2"));
+        assert!(page.contains("<meta property=\"og:title\" content=\"Gold award: Best Speaker\">"));
+        assert!(page.contains("Top of the &lt;open&gt; tab"));
+        assert!(page.contains("content=\"https://example.com/a?b=1&amp;c=2\""));
+        assert!(page.contains("Awarded to ali by Tabrela on 1 March 2026"));
+    }
+}
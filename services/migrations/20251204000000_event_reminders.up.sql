@@ -0,0 +1,5 @@
+-- Migration: Track when the chat reminder for an event was posted
+-- NULL means no reminder has gone out yet; cleared when the event is rescheduled
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS reminder_sent_at TIMESTAMPTZ;
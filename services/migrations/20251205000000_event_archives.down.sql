@@ -0,0 +1,23 @@
+DROP TRIGGER IF EXISTS trigger_prevent_archived_event_changes ON events;
+DROP FUNCTION IF EXISTS prevent_archived_event_row_changes();
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'match_series', 'matches', 'match_teams', 'allocations', 'allocation_history',
+        'ballots', 'speaker_scores', 'team_rankings', 'attendance_records'
+    ] LOOP
+        EXECUTE format('DROP TRIGGER IF EXISTS trigger_prevent_archived_changes ON %I', tbl);
+    END LOOP;
+END;
+$$;
+
+DROP FUNCTION IF EXISTS prevent_archived_event_changes();
+
+ALTER TABLE events
+    DROP COLUMN IF EXISTS archive_sha256,
+    DROP COLUMN IF EXISTS archive_key,
+    DROP COLUMN IF EXISTS archived_by,
+    DROP COLUMN IF EXISTS archived_at;
@@ -0,0 +1,108 @@
+-- Migration: Event archival
+-- An archived event has an immutable export in object storage and becomes
+-- read-only: the triggers below reject any change to the event's rounds,
+-- rooms, allocations, ballots and attendance.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS archived_by UUID,
+    ADD COLUMN IF NOT EXISTS archive_key TEXT,
+    ADD COLUMN IF NOT EXISTS archive_sha256 VARCHAR(64);
+
+-- Resolve the event a row belongs to and refuse the change if it is archived
+CREATE OR REPLACE FUNCTION prevent_archived_event_changes()
+RETURNS TRIGGER AS $$
+DECLARE
+    row_data JSONB;
+    target_event UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        row_data := to_jsonb(OLD);
+    ELSE
+        row_data := to_jsonb(NEW);
+    END IF;
+
+    target_event := CASE TG_TABLE_NAME
+        WHEN 'match_series' THEN (row_data->>'event_id')::uuid
+        WHEN 'attendance_records' THEN (row_data->>'event_id')::uuid
+        WHEN 'matches' THEN (
+            SELECT event_id FROM match_series WHERE id = (row_data->>'series_id')::uuid
+        )
+        WHEN 'ballots' THEN (
+            SELECT s.event_id FROM matches m
+            JOIN match_series s ON m.series_id = s.id
+            WHERE m.id = (row_data->>'match_id')::uuid
+        )
+        WHEN 'speaker_scores' THEN (
+            SELECT s.event_id FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            WHERE b.id = (row_data->>'ballot_id')::uuid
+        )
+        WHEN 'team_rankings' THEN (
+            SELECT s.event_id FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            WHERE b.id = (row_data->>'ballot_id')::uuid
+        )
+        ELSE (
+            -- match_teams, allocations, allocation_history
+            SELECT s.event_id FROM matches m
+            JOIN match_series s ON m.series_id = s.id
+            WHERE m.id = (row_data->>'match_id')::uuid
+        )
+    END;
+
+    IF EXISTS (SELECT 1 FROM events WHERE id = target_event AND archived_at IS NOT NULL) THEN
+        RAISE EXCEPTION 'Event % is archived and read-only', target_event
+            USING ERRCODE = 'object_not_in_prerequisite_state';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    tbl TEXT;
+BEGIN
+    FOREACH tbl IN ARRAY ARRAY[
+        'match_series', 'matches', 'match_teams', 'allocations', 'allocation_history',
+        'ballots', 'speaker_scores', 'team_rankings', 'attendance_records'
+    ] LOOP
+        EXECUTE format(
+            'CREATE OR REPLACE TRIGGER trigger_prevent_archived_changes
+                BEFORE INSERT OR UPDATE OR DELETE ON %I
+                FOR EACH ROW EXECUTE FUNCTION prevent_archived_event_changes()',
+            tbl
+        );
+    END LOOP;
+END;
+$$;
+
+-- The event row itself may only be touched while its archive is being
+-- written (archive_sha256 still NULL); after that it is frozen
+CREATE OR REPLACE FUNCTION prevent_archived_event_row_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF OLD.archived_at IS NOT NULL AND (TG_OP = 'DELETE' OR OLD.archive_sha256 IS NOT NULL) THEN
+        RAISE EXCEPTION 'Event % is archived and read-only', OLD.id
+            USING ERRCODE = 'object_not_in_prerequisite_state';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER trigger_prevent_archived_event_changes
+    BEFORE UPDATE OR DELETE ON events
+    FOR EACH ROW EXECUTE FUNCTION prevent_archived_event_row_changes();
+
+COMMENT ON COLUMN events.archive_key IS 'Storage key of the archive zip (see STORAGE_BACKEND)';
+COMMENT ON COLUMN events.archive_sha256 IS 'Hex SHA-256 of the archive zip; set once the archive is stored';
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS trigger_prevent_archived_changes ON attachments;
+DROP FUNCTION IF EXISTS prevent_archived_attachment_changes();
+DROP TABLE IF EXISTS attachments;
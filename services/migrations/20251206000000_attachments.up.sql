@@ -0,0 +1,59 @@
+-- Migration: Attachments
+-- Files attached to an event (info packs) or a match (info slides). The file
+-- itself lives in object storage under storage_key; uploaded_at is set once
+-- the upload has been confirmed.
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id UUID PRIMARY KEY,
+    event_id UUID REFERENCES events(id) ON DELETE CASCADE,
+    match_id UUID REFERENCES matches(id) ON DELETE CASCADE,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT,
+    storage_key TEXT NOT NULL UNIQUE,
+    uploaded_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    uploaded_at TIMESTAMPTZ,
+    CONSTRAINT attachment_has_one_owner CHECK ((event_id IS NULL) <> (match_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_event_id ON attachments(event_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_match_id ON attachments(match_id);
+
+COMMENT ON TABLE attachments IS 'Files attached to events and matches; contents are kept in object storage';
+COMMENT ON COLUMN attachments.uploaded_at IS 'NULL until the upload is confirmed; pending attachments are hidden';
+
+-- Attachments of an archived event are read-only like the rest of the event
+CREATE OR REPLACE FUNCTION prevent_archived_attachment_changes()
+RETURNS TRIGGER AS $$
+DECLARE
+    target attachments%ROWTYPE;
+    target_event UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD;
+    ELSE
+        target := NEW;
+    END IF;
+
+    target_event := COALESCE(target.event_id, (
+        SELECT s.event_id FROM matches m
+        JOIN match_series s ON m.series_id = s.id
+        WHERE m.id = target.match_id
+    ));
+
+    IF EXISTS (SELECT 1 FROM events WHERE id = target_event AND archived_at IS NOT NULL) THEN
+        RAISE EXCEPTION 'Event % is archived and read-only', target_event
+            USING ERRCODE = 'object_not_in_prerequisite_state';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER trigger_prevent_archived_changes
+    BEFORE INSERT OR UPDATE OR DELETE ON attachments
+    FOR EACH ROW EXECUTE FUNCTION prevent_archived_attachment_changes();
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS trigger_prevent_conduct_audit_changes ON conduct_report_audit;
+DROP FUNCTION IF EXISTS prevent_conduct_audit_changes();
+DROP TABLE IF EXISTS conduct_report_audit;
+DROP TABLE IF EXISTS conduct_reports;
+DROP TABLE IF EXISTS user_roles;
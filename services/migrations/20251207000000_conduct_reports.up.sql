@@ -0,0 +1,72 @@
+-- Migration: Roles and code-of-conduct reports
+-- Reports are confidential: only users holding the equity role can read
+-- them, and every read and change is written to an append-only audit log.
+
+CREATE TABLE IF NOT EXISTS user_roles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(50) NOT NULL,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_user_role UNIQUE (user_id, role),
+    CONSTRAINT valid_role CHECK (role IN ('equity'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_user_id ON user_roles(user_id);
+
+COMMENT ON TABLE user_roles IS 'Roles granted to users independently of admin rights';
+
+CREATE TABLE IF NOT EXISTS conduct_reports (
+    id UUID PRIMARY KEY,
+    -- NULL for anonymous reports; nothing else links them to the reporter
+    reporter_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    is_anonymous BOOLEAN NOT NULL,
+    event_id UUID REFERENCES events(id) ON DELETE SET NULL,
+    match_id UUID REFERENCES matches(id) ON DELETE SET NULL,
+    description TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT valid_report_status CHECK (status IN ('open', 'investigating', 'resolved')),
+    CONSTRAINT anonymous_reports_have_no_reporter CHECK (NOT is_anonymous OR reporter_id IS NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conduct_reports_status ON conduct_reports(status);
+CREATE INDEX IF NOT EXISTS idx_conduct_reports_event_id ON conduct_reports(event_id);
+CREATE INDEX IF NOT EXISTS idx_conduct_reports_reporter_id ON conduct_reports(reporter_id);
+
+CREATE TABLE IF NOT EXISTS conduct_report_audit (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    report_id UUID NOT NULL REFERENCES conduct_reports(id),
+    -- NULL when an anonymous reporter filed the report
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(30) NOT NULL,
+    from_status VARCHAR(20),
+    to_status VARCHAR(20),
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT valid_audit_action CHECK (action IN ('created', 'viewed', 'status_changed', 'note_added'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_conduct_report_audit_report_id ON conduct_report_audit(report_id);
+
+COMMENT ON TABLE conduct_report_audit IS 'Append-only log of every access to and change of a conduct report';
+
+-- The audit log can only grow. The one permitted change is the actor being
+-- cleared when their account is deleted.
+CREATE OR REPLACE FUNCTION prevent_conduct_audit_changes()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' AND NEW.actor_id IS NULL
+        AND to_jsonb(NEW) - 'actor_id' = to_jsonb(OLD) - 'actor_id' THEN
+        RETURN NEW;
+    END IF;
+
+    RAISE EXCEPTION 'conduct_report_audit is append-only'
+        USING ERRCODE = 'insufficient_privilege';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER trigger_prevent_conduct_audit_changes
+    BEFORE UPDATE OR DELETE ON conduct_report_audit
+    FOR EACH ROW EXECUTE FUNCTION prevent_conduct_audit_changes();
@@ -0,0 +1,33 @@
+-- Migration: Announcements
+-- Admin posts shown to members, optionally limited to an event's attendees
+-- or to holders of a role, and hidden until publish_at.
+
+CREATE TABLE IF NOT EXISTS announcements (
+    id UUID PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    -- Only members available for this event see the announcement
+    event_id UUID REFERENCES events(id) ON DELETE CASCADE,
+    -- Only holders of this role see the announcement
+    audience_role VARCHAR(50),
+    is_important BOOLEAN NOT NULL DEFAULT FALSE,
+    publish_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT valid_audience_role CHECK (audience_role IS NULL OR audience_role IN ('equity'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcements_publish_at ON announcements(publish_at DESC);
+CREATE INDEX IF NOT EXISTS idx_announcements_event_id ON announcements(event_id);
+
+CREATE TABLE IF NOT EXISTS announcement_reads (
+    announcement_id UUID NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    read_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (announcement_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_announcement_reads_user_id ON announcement_reads(user_id);
+
+COMMENT ON COLUMN announcements.publish_at IS 'Announcements are hidden from members until this time';
@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS trigger_prevent_archived_changes ON event_team_registrations;
+DROP FUNCTION IF EXISTS prevent_archived_registration_changes();
+DROP INDEX IF EXISTS idx_match_teams_registered_team;
+ALTER TABLE match_teams DROP COLUMN IF EXISTS registered_team_id;
+DROP TABLE IF EXISTS event_team_registrations;
+DROP TABLE IF EXISTS registered_team_members;
+DROP TABLE IF EXISTS registered_teams;
+ALTER TABLE events DROP COLUMN IF EXISTS team_entry;
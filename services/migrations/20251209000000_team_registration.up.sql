@@ -0,0 +1,75 @@
+-- Migration: Team registration
+-- Events in team-entry mode (inter-varsity tournaments) take entries from
+-- persistent teams that members form themselves. Whole teams are then
+-- assigned to match_teams, instead of allocating speakers one by one.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS team_entry BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS registered_teams (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    institution VARCHAR(255),
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS registered_team_members (
+    team_id UUID NOT NULL REFERENCES registered_teams(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Speaking order used when the team is assigned to a match
+    speaker_order INTEGER NOT NULL,
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (team_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_registered_team_members_user_id ON registered_team_members(user_id);
+
+CREATE TABLE IF NOT EXISTS event_team_registrations (
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    team_id UUID NOT NULL REFERENCES registered_teams(id) ON DELETE CASCADE,
+    registered_by UUID NOT NULL REFERENCES users(id),
+    registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, team_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_team_registrations_team_id ON event_team_registrations(team_id);
+
+ALTER TABLE match_teams
+    ADD COLUMN IF NOT EXISTS registered_team_id UUID REFERENCES registered_teams(id) ON DELETE SET NULL;
+
+-- A registered team occupies at most one side per match
+CREATE UNIQUE INDEX IF NOT EXISTS idx_match_teams_registered_team
+    ON match_teams(match_id, registered_team_id) WHERE registered_team_id IS NOT NULL;
+
+COMMENT ON COLUMN events.team_entry IS 'Entries are registered teams assigned whole, rather than individual speakers';
+COMMENT ON COLUMN match_teams.registered_team_id IS 'Registered team occupying this side, in team-entry events';
+
+-- Registrations for an archived event are read-only like the rest of the event
+CREATE OR REPLACE FUNCTION prevent_archived_registration_changes()
+RETURNS TRIGGER AS $$
+DECLARE
+    target_event UUID;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target_event := OLD.event_id;
+    ELSE
+        target_event := NEW.event_id;
+    END IF;
+
+    IF EXISTS (SELECT 1 FROM events WHERE id = target_event AND archived_at IS NOT NULL) THEN
+        RAISE EXCEPTION 'Event % is archived and read-only', target_event
+            USING ERRCODE = 'object_not_in_prerequisite_state';
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RETURN OLD;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER trigger_prevent_archived_changes
+    BEFORE INSERT OR UPDATE OR DELETE ON event_team_registrations
+    FOR EACH ROW EXECUTE FUNCTION prevent_archived_registration_changes();
@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS trigger_sync_team_institution ON allocations;
+DROP FUNCTION IF EXISTS sync_team_institution_on_allocation();
+DROP FUNCTION IF EXISTS sync_match_team_institution(UUID);
+ALTER TABLE match_teams DROP COLUMN IF EXISTS institution_id;
+DROP INDEX IF EXISTS idx_users_institution_id;
+ALTER TABLE users
+    DROP COLUMN IF EXISTS is_esl,
+    DROP COLUMN IF EXISTS is_novice,
+    DROP COLUMN IF EXISTS institution_id;
+DROP TABLE IF EXISTS institutions;
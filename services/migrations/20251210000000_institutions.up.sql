@@ -0,0 +1,72 @@
+-- Migration: Institutions and speaker eligibility
+-- Institutions replace the free-text institution typed per team per match.
+-- Each user records their institution and whether they are eligible for
+-- the novice and ESL categories; tab calculations filter on these.
+
+CREATE TABLE IF NOT EXISTS institutions (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    short_code VARCHAR(20) UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS institution_id UUID REFERENCES institutions(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS is_novice BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS is_esl BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_users_institution_id ON users(institution_id);
+
+ALTER TABLE match_teams
+    ADD COLUMN IF NOT EXISTS institution_id UUID REFERENCES institutions(id) ON DELETE SET NULL;
+
+COMMENT ON COLUMN users.is_novice IS 'Eligible for the novice break';
+COMMENT ON COLUMN users.is_esl IS 'Eligible for the ESL category';
+COMMENT ON COLUMN match_teams.institution_id IS 'Set from the speakers while institution was not typed in by hand';
+
+-- Keep a team's institution in step with its speakers: when every speaker
+-- with an institution shares the same one, the team takes it; otherwise it
+-- has none. A team whose institution was typed in by hand (institution set,
+-- institution_id NULL) is left alone.
+CREATE OR REPLACE FUNCTION sync_match_team_institution(target_team UUID)
+RETURNS VOID AS $$
+DECLARE
+    common UUID;
+BEGIN
+    IF target_team IS NULL THEN
+        RETURN;
+    END IF;
+
+    SELECT CASE WHEN COUNT(DISTINCT u.institution_id) = 1 THEN MIN(u.institution_id::text)::uuid END
+    INTO common
+    FROM allocations a
+    JOIN users u ON a.user_id = u.id
+    WHERE a.team_id = target_team AND a.role = 'speaker' AND u.institution_id IS NOT NULL;
+
+    UPDATE match_teams t SET
+        institution_id = common,
+        institution = (SELECT name FROM institutions WHERE id = common),
+        updated_at = NOW()
+    WHERE t.id = target_team
+      AND (t.institution_id IS NOT NULL OR t.institution IS NULL)
+      AND t.institution_id IS DISTINCT FROM common;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION sync_team_institution_on_allocation()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        PERFORM sync_match_team_institution(OLD.team_id);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        PERFORM sync_match_team_institution(NEW.team_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER trigger_sync_team_institution
+    AFTER INSERT OR UPDATE OF team_id, role, user_id OR DELETE ON allocations
+    FOR EACH ROW EXECUTE FUNCTION sync_team_institution_on_allocation();
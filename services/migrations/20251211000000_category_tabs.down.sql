@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS trigger_prevent_archived_changes ON event_tab_categories;
+DROP TABLE IF EXISTS event_tab_categories;
@@ -0,0 +1,21 @@
+-- Migration: Category tabs
+-- Each event keeps one settings row per speaker category (open, novice,
+-- ESL): how many teams break in that category and which parts of its tab
+-- are public. Events without a row have nothing released and no break.
+
+CREATE TABLE IF NOT EXISTS event_tab_categories (
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    category VARCHAR(20) NOT NULL CHECK (category IN ('open', 'novice', 'esl')),
+    break_size INTEGER NOT NULL DEFAULT 0 CHECK (break_size >= 0),
+    team_tab_released BOOLEAN NOT NULL DEFAULT FALSE,
+    speaker_tab_released BOOLEAN NOT NULL DEFAULT FALSE,
+    break_released BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, category)
+);
+
+-- Settings of an archived event are frozen with the rest of it
+CREATE OR REPLACE TRIGGER trigger_prevent_archived_changes
+    BEFORE INSERT OR UPDATE OR DELETE ON event_tab_categories
+    FOR EACH ROW EXECUTE FUNCTION prevent_archived_registration_changes();
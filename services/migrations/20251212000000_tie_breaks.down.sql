@@ -0,0 +1,3 @@
+ALTER TABLE events DROP CONSTRAINT IF EXISTS events_tie_breaks_check;
+ALTER TABLE events DROP COLUMN IF EXISTS tie_break_seed;
+ALTER TABLE events DROP COLUMN IF EXISTS tie_breaks;
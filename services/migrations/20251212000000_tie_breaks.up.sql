@@ -0,0 +1,23 @@
+-- Migration: Configurable tie-break rules
+-- Each event orders its team standings by a chain of rules. Teams level on
+-- the first rule are separated by the next, and so on. The seed fixes the
+-- random order used by the 'draw' rule so standings are reproducible.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS tie_breaks VARCHAR(20)[] NOT NULL
+        DEFAULT ARRAY['points', 'speaks']::VARCHAR(20)[],
+    ADD COLUMN IF NOT EXISTS tie_break_seed BIGINT;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'events_tie_breaks_check') THEN
+        ALTER TABLE events ADD CONSTRAINT events_tie_breaks_check CHECK (
+            cardinality(tie_breaks) > 0
+            AND tie_breaks <@ ARRAY['points', 'wins', 'speaks', 'head_to_head',
+                                    'average_margin', 'draw']::VARCHAR(20)[]
+        );
+    END IF;
+END $$;
+
+COMMENT ON COLUMN events.tie_breaks IS 'Ordered tie-break rules for team standings';
+COMMENT ON COLUMN events.tie_break_seed IS 'Seed of the random order used by the draw rule';
@@ -0,0 +1,3 @@
+ALTER TABLE ballots DROP COLUMN IF EXISTS low_point_win;
+ALTER TABLE team_rankings DROP COLUMN IF EXISTS margin;
+ALTER TABLE team_rankings DROP COLUMN IF EXISTS total_speaks;
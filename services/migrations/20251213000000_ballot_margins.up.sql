@@ -0,0 +1,16 @@
+-- Migration: Ballot margins and low-point wins
+-- Each ranking on a ballot records the team's total speaks on that ballot
+-- and its margin over the average of the other teams. A ballot that ranks a
+-- team above one with more speaks (a low-point win) is flagged; the
+-- adjudicator has to confirm it before it is accepted.
+
+ALTER TABLE team_rankings
+    ADD COLUMN IF NOT EXISTS total_speaks DECIMAL(6,2),
+    ADD COLUMN IF NOT EXISTS margin DECIMAL(6,2);
+
+ALTER TABLE ballots
+    ADD COLUMN IF NOT EXISTS low_point_win BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN team_rankings.total_speaks IS 'Sum of the team''s speaker scores on this ballot';
+COMMENT ON COLUMN team_rankings.margin IS 'total_speaks less the average of the other teams on this ballot';
+COMMENT ON COLUMN ballots.low_point_win IS 'Ranks a team above one with more speaks; confirmed by the adjudicator';
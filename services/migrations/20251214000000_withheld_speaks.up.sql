@@ -0,0 +1,9 @@
+-- Migration: Withhold speaker scores until the end of the tournament
+-- While set, released matches show team rankings and results but no
+-- speaker scores or speaker point totals, and the speaker tab stays closed,
+-- whatever the per-match release flags say. Admins still see everything.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS speaks_withheld BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN events.speaks_withheld IS 'Hide speaker scores from non-admins until the end of the tournament';
@@ -0,0 +1 @@
+ALTER TABLE match_series DROP COLUMN IF EXISTS is_silent;
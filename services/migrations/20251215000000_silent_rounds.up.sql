@@ -0,0 +1,9 @@
+-- Migration: Silent rounds
+-- Results of a silent series stay hidden from everyone but admins, whatever
+-- its matches' release flags say: match views, performance stats, tabs and
+-- result notifications all leave them out until an admin lifts the silence.
+
+ALTER TABLE match_series
+    ADD COLUMN IF NOT EXISTS is_silent BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN match_series.is_silent IS 'Hide results from non-admins regardless of match release flags';
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_matches_venue_slot;
+DROP INDEX IF EXISTS idx_matches_venue_id;
+ALTER TABLE matches DROP COLUMN IF EXISTS venue_id;
+DROP TABLE IF EXISTS venues;
@@ -0,0 +1,29 @@
+-- Migration: Venues
+-- Rooms become records with a capacity and accessibility details. A match
+-- assigned a venue keeps its name in room_name, so everything that shows the
+-- room keeps working; matches typed in by hand simply have no venue. Two live
+-- matches of one series can't hold the same venue at the same time.
+
+CREATE TABLE IF NOT EXISTS venues (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    building VARCHAR(255),
+    capacity INTEGER CHECK (capacity > 0),
+    is_accessible BOOLEAN NOT NULL DEFAULT FALSE,
+    accessibility_notes TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE matches
+    ADD COLUMN IF NOT EXISTS venue_id UUID REFERENCES venues(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_matches_venue_id ON matches(venue_id);
+
+-- Matches without a time all count as starting together with their round
+CREATE UNIQUE INDEX IF NOT EXISTS idx_matches_venue_slot
+    ON matches (series_id, venue_id, COALESCE(scheduled_time, '-infinity'::timestamptz))
+    WHERE venue_id IS NOT NULL AND status <> 'cancelled';
+
+COMMENT ON TABLE venues IS 'Rooms matches can be held in';
+COMMENT ON COLUMN matches.venue_id IS 'Venue the match is held in; room_name follows its name';
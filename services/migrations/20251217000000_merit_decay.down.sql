@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS merit_decay_exemptions;
+DROP INDEX IF EXISTS idx_merit_history_user_kind;
+ALTER TABLE merit_history DROP CONSTRAINT IF EXISTS merit_history_kind_check;
+ALTER TABLE merit_history DROP COLUMN IF EXISTS kind;
+COMMENT ON TABLE merit_history IS 'Audit log of all merit point changes made by admins.';
@@ -0,0 +1,26 @@
+-- Migration: Merit decay
+-- Members who stop taking part lose a share of their merit for each period
+-- of inactivity, so the merit table reflects current members. The scheduler
+-- records every decay in merit_history with kind 'decay' and no admin.
+-- Admins can exempt whole categories of users (e.g. novices) from decay.
+
+ALTER TABLE merit_history
+    ADD COLUMN IF NOT EXISTS kind VARCHAR(20) NOT NULL DEFAULT 'manual';
+
+ALTER TABLE merit_history DROP CONSTRAINT IF EXISTS merit_history_kind_check;
+ALTER TABLE merit_history
+    ADD CONSTRAINT merit_history_kind_check CHECK (kind IN ('manual', 'decay'));
+
+CREATE INDEX IF NOT EXISTS idx_merit_history_user_kind
+    ON merit_history(user_id, kind, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS merit_decay_exemptions (
+    category VARCHAR(20) PRIMARY KEY
+        CHECK (category IN ('admin', 'novice', 'esl', 'equity')),
+    exempted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE merit_history IS 'Audit log of all merit point changes, by admins or by decay.';
+COMMENT ON COLUMN merit_history.kind IS 'manual for admin changes, decay for inactivity decay';
+COMMENT ON TABLE merit_decay_exemptions IS 'User categories whose merit never decays';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_merit_history_batch_id;
+ALTER TABLE merit_history DROP COLUMN IF EXISTS batch_id;
@@ -0,0 +1,11 @@
+-- Migration: Merit batches
+-- Merit changes made together (a bulk adjustment after an event, or a
+-- transfer between two members) share a batch id, so the history shows which
+-- entries were applied as one.
+
+ALTER TABLE merit_history ADD COLUMN IF NOT EXISTS batch_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_merit_history_batch_id
+    ON merit_history(batch_id) WHERE batch_id IS NOT NULL;
+
+COMMENT ON COLUMN merit_history.batch_id IS 'Shared by changes applied together in one bulk adjustment or transfer';
@@ -0,0 +1,2 @@
+ALTER TABLE awards DROP COLUMN IF EXISTS featured;
+DROP TABLE IF EXISTS merit_privacy_settings;
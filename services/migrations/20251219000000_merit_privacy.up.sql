@@ -0,0 +1,17 @@
+-- Migration: Merit privacy
+-- Members choose whether their merit appears on their public profile, and
+-- whether the public sees all of their awards or only those they feature.
+-- Members without a row keep the defaults: merit hidden, all awards shown.
+
+CREATE TABLE IF NOT EXISTS merit_privacy_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    show_merit BOOLEAN NOT NULL DEFAULT FALSE,
+    award_visibility VARCHAR(20) NOT NULL DEFAULT 'all'
+        CHECK (award_visibility IN ('all', 'curated')),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE awards ADD COLUMN IF NOT EXISTS featured BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON TABLE merit_privacy_settings IS 'What of a member''s merit and awards the public may see';
+COMMENT ON COLUMN awards.featured IS 'Chosen by the recipient to show publicly when they curate their awards';
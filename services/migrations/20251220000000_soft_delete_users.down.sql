@@ -0,0 +1,13 @@
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_phone_number_format_check;
+ALTER TABLE users ADD CONSTRAINT users_phone_number_format_check
+    CHECK (phone_number ~ '^\+\d{1,3}\d{9,15}$') NOT VALID;
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_reg_number_format_check;
+ALTER TABLE users ADD CONSTRAINT users_reg_number_format_check
+    CHECK (reg_number ~ '^20\d{5}$') NOT VALID;
+
+DROP SEQUENCE IF EXISTS deleted_member_number_seq;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS deleted_number,
+    DROP COLUMN IF EXISTS deleted_at;
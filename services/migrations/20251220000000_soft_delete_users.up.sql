@@ -0,0 +1,23 @@
+-- Migration: Soft deletion of users
+-- Deleting a member keeps their row, so the ballots, scores, allocations and
+-- attendance that name them stay intact and historical tabs stay correct.
+-- Their personal details are replaced with placeholders and they appear as
+-- "Deleted member #N", numbered from deleted_member_number_seq.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deleted_number INTEGER UNIQUE;
+
+CREATE SEQUENCE IF NOT EXISTS deleted_member_number_seq;
+
+-- Placeholders need not look like real registration and phone numbers
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_reg_number_format_check;
+ALTER TABLE users ADD CONSTRAINT users_reg_number_format_check
+    CHECK (deleted_at IS NOT NULL OR reg_number ~ '^20\d{5}$');
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_phone_number_format_check;
+ALTER TABLE users ADD CONSTRAINT users_phone_number_format_check
+    CHECK (deleted_at IS NOT NULL OR phone_number ~ '^\+\d{1,3}\d{9,15}$');
+
+COMMENT ON COLUMN users.deleted_at IS 'When the member was deleted and their personal details anonymized';
+COMMENT ON COLUMN users.deleted_number IS 'N in the "Deleted member #N" placeholder shown in their place';
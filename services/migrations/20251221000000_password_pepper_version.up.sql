@@ -0,0 +1,7 @@
+-- Migration: Record which pepper each password hash was made with
+-- The pepper can then be rotated: hashes made with a retired pepper still
+-- verify against it and are rehashed with the current one at next login.
+-- Existing hashes were all made with the original pepper, version 1.
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS pepper_version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,21 @@
+-- Migration: Login history
+-- Every login attempt against an existing account, successful or not, so
+-- members can see when and from where their account was accessed. Attempts
+-- naming no account are not recorded.
+
+CREATE TABLE IF NOT EXISTS login_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    success BOOLEAN NOT NULL,
+    failure_reason VARCHAR(32),
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    -- Browser and operating system without versions, e.g. "Firefox on Linux"
+    device VARCHAR(64) NOT NULL,
+    -- ISO 3166-1 alpha-2, when a proxy resolved one
+    country CHAR(2),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_history_user_created
+    ON login_history (user_id, created_at DESC);
@@ -0,0 +1,12 @@
+-- Migration: Bind refresh tokens to the device they were issued to
+-- Clients send a random device identifier they generate once and keep.
+-- A refresh token is only accepted from the same identifier and the same
+-- browser and operating system, so a leaked token is useless elsewhere.
+-- Tokens issued before this migration have no device and are bound to the
+-- first device that refreshes them.
+
+ALTER TABLE refresh_tokens
+    ADD COLUMN IF NOT EXISTS device_id VARCHAR(128),
+    ADD COLUMN IF NOT EXISTS device VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS ip_address VARCHAR(64),
+    ADD COLUMN IF NOT EXISTS last_used_at TIMESTAMPTZ;
@@ -0,0 +1,11 @@
+-- Restore the table used by stored CSRF tokens
+CREATE TABLE IF NOT EXISTS csrf_tokens (
+    id UUID PRIMARY KEY,
+    token VARCHAR(255) UNIQUE NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_csrf_tokens_token ON csrf_tokens(token);
+CREATE INDEX IF NOT EXISTS idx_csrf_tokens_expires_at ON csrf_tokens(expires_at);
@@ -0,0 +1,2 @@
+-- CSRF tokens are now signed and tied to the session instead of stored
+DROP TABLE IF EXISTS csrf_tokens;
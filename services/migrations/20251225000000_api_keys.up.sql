@@ -0,0 +1,17 @@
+-- Migration: API keys
+-- Read-only keys for integrations such as results dashboards and the
+-- projector display. Only a SHA-256 hash of each key is kept; the prefix
+-- lets admins tell keys apart. Revoked keys are kept for the record.
+
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) UNIQUE NOT NULL,
+    -- Services the key may read from, e.g. {tabulation}
+    scopes TEXT[] NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
@@ -0,0 +1,28 @@
+-- Migration: Policy documents and acceptances
+-- Versioned terms of service and committee policies, and a record of each
+-- member accepting a version, kept as proof of consent.
+
+CREATE TABLE IF NOT EXISTS policy_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- terms, privacy or media
+    kind VARCHAR(32) NOT NULL,
+    version INTEGER NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    published_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, version)
+);
+
+CREATE TABLE IF NOT EXISTS policy_acceptances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    policy_id UUID NOT NULL REFERENCES policy_documents(id) ON DELETE CASCADE,
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    accepted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, policy_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_acceptances_policy
+    ON policy_acceptances (policy_id, accepted_at);
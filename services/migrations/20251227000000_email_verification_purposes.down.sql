@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    otp VARCHAR(64) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    used BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS idx_password_reset_email ON password_reset_tokens(email);
+CREATE INDEX IF NOT EXISTS idx_password_reset_expires ON password_reset_tokens(expires_at);
+
+DELETE FROM email_verification_tokens WHERE purpose <> 'registration';
+
+DROP INDEX IF EXISTS idx_email_verification_tokens_user_purpose;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_verification_tokens_user_id_unique
+    ON email_verification_tokens (user_id);
+
+ALTER TABLE email_verification_tokens DROP COLUMN IF EXISTS email;
+ALTER TABLE email_verification_tokens DROP COLUMN IF EXISTS purpose;
@@ -0,0 +1,23 @@
+-- Migration: Email verification purposes
+-- Verification codes now serve several purposes (registration, email
+-- change, account recovery), one live code per user and purpose, and
+-- record the address they were sent to. Password reset codes move here
+-- from their own table as account recovery codes.
+
+ALTER TABLE email_verification_tokens
+    ADD COLUMN IF NOT EXISTS purpose VARCHAR(32) NOT NULL DEFAULT 'registration';
+ALTER TABLE email_verification_tokens ADD COLUMN IF NOT EXISTS email VARCHAR(255);
+
+UPDATE email_verification_tokens evt SET email = u.email
+FROM users u
+WHERE evt.user_id = u.id AND evt.email IS NULL;
+
+ALTER TABLE email_verification_tokens ALTER COLUMN email SET NOT NULL;
+ALTER TABLE email_verification_tokens ALTER COLUMN purpose DROP DEFAULT;
+
+DROP INDEX IF EXISTS idx_email_verification_tokens_user_id_unique;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_verification_tokens_user_purpose
+    ON email_verification_tokens (user_id, purpose);
+
+-- Outstanding reset codes are short-lived; members can request new ones
+DROP TABLE IF EXISTS password_reset_tokens;
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_attendance_waitlist;
+
+ALTER TABLE attendance_records
+    DROP COLUMN IF EXISTS waitlisted_at;
+
+ALTER TABLE events
+    DROP COLUMN IF EXISTS max_participants;
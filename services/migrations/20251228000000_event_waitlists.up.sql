@@ -0,0 +1,18 @@
+-- Migration: Event capacity and waitlists
+-- An event with max_participants set gives seats to the first members to
+-- mark themselves available. Later members are waitlisted: they stay
+-- unavailable, with waitlisted_at recording their place in the queue, and
+-- are promoted in that order as seats free up.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS max_participants INTEGER CHECK (max_participants > 0);
+
+ALTER TABLE attendance_records
+    ADD COLUMN IF NOT EXISTS waitlisted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_attendance_waitlist
+    ON attendance_records(event_id, waitlisted_at)
+    WHERE waitlisted_at IS NOT NULL;
+
+COMMENT ON COLUMN events.max_participants IS 'Seats available; NULL means no limit.';
+COMMENT ON COLUMN attendance_records.waitlisted_at IS 'When the member joined the waitlist; NULL unless waiting for a seat.';
@@ -0,0 +1,30 @@
+-- Migration: Absence excuses
+-- A member who was expected at a locked event but did not check in can
+-- explain why, optionally attaching evidence (a doctor's note, say). Admins
+-- approve or reject the excuse; approved absences do not count against the
+-- member's attendance rate or merit decay.
+
+CREATE TABLE IF NOT EXISTS absence_excuses (
+    id UUID PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    -- Evidence is stored outside the database under attachment_key
+    attachment_filename VARCHAR(255),
+    attachment_content_type VARCHAR(100),
+    attachment_key TEXT,
+    attachment_size_bytes BIGINT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_excuse UNIQUE (event_id, user_id),
+    CONSTRAINT valid_excuse_status CHECK (status IN ('pending', 'approved', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_absence_excuses_user_id ON absence_excuses(user_id);
+CREATE INDEX IF NOT EXISTS idx_absence_excuses_status ON absence_excuses(status);
+
+COMMENT ON TABLE absence_excuses IS 'Members'' explanations for missing locked events, reviewed by admins';
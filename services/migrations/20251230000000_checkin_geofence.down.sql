@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_attendance_checkin_flagged;
+
+ALTER TABLE attendance_records
+    DROP COLUMN IF EXISTS checkin_flagged,
+    DROP COLUMN IF EXISTS checkin_distance_m;
+
+ALTER TABLE events
+    DROP CONSTRAINT IF EXISTS geofence_complete,
+    DROP COLUMN IF EXISTS checkin_radius_m,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude;
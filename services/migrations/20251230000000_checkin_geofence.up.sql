@@ -0,0 +1,33 @@
+-- Migration: Self check-in geofences
+-- An event can define a circle members must be inside to check themselves
+-- in. Only the distance from the venue is kept for each self check-in, not
+-- the member's coordinates. Check-ins from outside the circle are either
+-- rejected or accepted and flagged for an admin to review, depending on
+-- the service's configuration.
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION CHECK (latitude BETWEEN -90 AND 90),
+    ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION CHECK (longitude BETWEEN -180 AND 180),
+    ADD COLUMN IF NOT EXISTS checkin_radius_m INTEGER CHECK (checkin_radius_m > 0);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'geofence_complete') THEN
+        ALTER TABLE events ADD CONSTRAINT geofence_complete CHECK (
+            (latitude IS NULL) = (longitude IS NULL)
+            AND (latitude IS NULL) = (checkin_radius_m IS NULL)
+        );
+    END IF;
+END $$;
+
+ALTER TABLE attendance_records
+    ADD COLUMN IF NOT EXISTS checkin_distance_m DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS checkin_flagged BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX IF NOT EXISTS idx_attendance_checkin_flagged
+    ON attendance_records(event_id)
+    WHERE checkin_flagged;
+
+COMMENT ON COLUMN events.checkin_radius_m IS 'Metres from (latitude, longitude) within which members may check themselves in; NULL means no geofence.';
+COMMENT ON COLUMN attendance_records.checkin_distance_m IS 'Distance from the venue reported by a self check-in; NULL for admin check-ins.';
+COMMENT ON COLUMN attendance_records.checkin_flagged IS 'Self check-in from outside the geofence, awaiting admin review.';
@@ -0,0 +1,35 @@
+-- Migration: Event categories and tags
+-- event_type only says what kind of session an event is. Categories are an
+-- admin-managed hierarchy (Training > Workshops, say) each event may be
+-- filed under, and tags are free-form labels such as "social" or
+-- "external" that an event can have any number of.
+
+CREATE TABLE IF NOT EXISTS event_categories (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    parent_id UUID REFERENCES event_categories(id) ON DELETE RESTRICT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT category_not_own_parent CHECK (parent_id IS DISTINCT FROM id)
+);
+
+-- Sibling categories need distinct names; top-level ones count as siblings
+CREATE UNIQUE INDEX IF NOT EXISTS idx_event_categories_sibling_name
+    ON event_categories (COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'::uuid), LOWER(name));
+
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS category_id UUID REFERENCES event_categories(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_events_category_id ON events(category_id);
+
+CREATE TABLE IF NOT EXISTS event_tags (
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    -- Lowercase, hyphenated; normalised by the attendance service
+    tag VARCHAR(40) NOT NULL,
+    PRIMARY KEY (event_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_tags_tag ON event_tags(tag);
+
+COMMENT ON TABLE event_categories IS 'Admin-managed hierarchy events can be filed under';
+COMMENT ON TABLE event_tags IS 'Free-form labels on events';
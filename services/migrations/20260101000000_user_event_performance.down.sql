@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS refresh_user_event_performance(UUID);
+DROP TABLE IF EXISTS user_event_performance;
@@ -0,0 +1,78 @@
+-- Migration: Per-event performance read model
+-- A member's ballot results at each event (speaks, wins, losses and how
+-- often they placed 1st-4th), kept up to date as ballots are submitted so
+-- the performance page sums a few rows instead of re-aggregating every
+-- ballot. Silent rounds are left out, as they are everywhere else.
+
+CREATE TABLE IF NOT EXISTS user_event_performance (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    speaker_score_total NUMERIC NOT NULL DEFAULT 0,
+    speaker_score_count INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    firsts INTEGER NOT NULL DEFAULT 0,
+    seconds INTEGER NOT NULL DEFAULT 0,
+    thirds INTEGER NOT NULL DEFAULT 0,
+    fourths INTEGER NOT NULL DEFAULT 0,
+    refreshed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_event_performance_event_id ON user_event_performance(event_id);
+
+COMMENT ON TABLE user_event_performance IS 'Ballot results per member per event, rebuilt by refresh_user_event_performance';
+COMMENT ON COLUMN user_event_performance.speaker_score_total IS 'Sum of submitted speaker scores; divide by speaker_score_count for the average';
+
+-- Rebuild an event's rows from its submitted ballots
+CREATE OR REPLACE FUNCTION refresh_user_event_performance(target_event UUID)
+RETURNS VOID AS $$
+BEGIN
+    DELETE FROM user_event_performance WHERE event_id = target_event;
+
+    INSERT INTO user_event_performance (
+        user_id, event_id, speaker_score_total, speaker_score_count,
+        wins, losses, firsts, seconds, thirds, fourths
+    )
+    WITH speaks AS (
+        SELECT a.user_id, SUM(ss.score) AS total, COUNT(*) AS scores
+        FROM speaker_scores ss
+        JOIN ballots b ON ss.ballot_id = b.id
+        JOIN allocations a ON ss.allocation_id = a.id
+        JOIN matches m ON a.match_id = m.id
+        JOIN match_series ms ON m.series_id = ms.id
+        WHERE ms.event_id = target_event AND b.is_submitted AND NOT ms.is_silent
+          AND a.user_id IS NOT NULL
+        GROUP BY a.user_id
+    ),
+    results AS (
+        SELECT a.user_id,
+            COUNT(*) FILTER (WHERE tr.is_winner) AS wins,
+            COUNT(*) FILTER (WHERE NOT tr.is_winner) AS losses,
+            COUNT(*) FILTER (WHERE tr.rank = 1) AS firsts,
+            COUNT(*) FILTER (WHERE tr.rank = 2) AS seconds,
+            COUNT(*) FILTER (WHERE tr.rank = 3) AS thirds,
+            COUNT(*) FILTER (WHERE tr.rank = 4) AS fourths
+        FROM allocations a
+        JOIN match_teams mt ON a.team_id = mt.id
+        JOIN team_rankings tr ON mt.id = tr.team_id
+        JOIN ballots b ON tr.ballot_id = b.id
+        JOIN matches m ON a.match_id = m.id
+        JOIN match_series ms ON m.series_id = ms.id
+        WHERE ms.event_id = target_event AND a.role = 'speaker'
+          AND b.is_voting AND b.is_submitted AND NOT ms.is_silent
+          AND a.user_id IS NOT NULL
+        GROUP BY a.user_id
+    )
+    SELECT COALESCE(s.user_id, r.user_id), target_event,
+        COALESCE(s.total, 0), COALESCE(s.scores, 0),
+        COALESCE(r.wins, 0), COALESCE(r.losses, 0),
+        COALESCE(r.firsts, 0), COALESCE(r.seconds, 0),
+        COALESCE(r.thirds, 0), COALESCE(r.fourths, 0)
+    FROM speaks s
+    FULL JOIN results r ON r.user_id = s.user_id;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Build the rows for results recorded before the table existed
+SELECT refresh_user_event_performance(id) FROM events;
@@ -0,0 +1,45 @@
+-- Migration: Seasons
+-- A season is a named span of dates (a club year such as "2024-25", or a
+-- term) that merit, awards, performance and standings can be filtered to,
+-- so clients need not know its dates. Events and ballots belong to the
+-- season their date falls in. Closing a season snapshots its standings.
+
+CREATE TABLE IF NOT EXISTS seasons (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(50) NOT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    closed_at TIMESTAMPTZ,
+    closed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT season_dates_ordered CHECK (starts_on <= ends_on),
+    CONSTRAINT seasons_do_not_overlap EXCLUDE USING gist (
+        daterange(starts_on, ends_on, '[]') WITH &&
+    )
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_seasons_name ON seasons (LOWER(name));
+
+COMMENT ON TABLE seasons IS 'Named date spans that stats can be filtered to';
+COMMENT ON COLUMN seasons.closed_at IS 'Set when an admin closes the season and its standings are snapshotted';
+
+-- Standings as they were when the season closed
+CREATE TABLE IF NOT EXISTS season_standings (
+    season_id UUID NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    username VARCHAR(50) NOT NULL,
+    rank INTEGER NOT NULL,
+    merit_earned INTEGER NOT NULL DEFAULT 0,
+    awards INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    average_speaker_score NUMERIC,
+    PRIMARY KEY (season_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_season_standings_rank ON season_standings(season_id, rank);
+
+COMMENT ON TABLE season_standings IS 'Snapshot of a closed season''s standings';
+COMMENT ON COLUMN season_standings.username IS 'Username at the time the season closed';
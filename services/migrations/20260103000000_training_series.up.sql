@@ -0,0 +1,65 @@
+-- Migration: Training series
+-- Ballots in a training series are collected as usual but are practice:
+-- they stay out of performance stats, season standings and tabs, and
+-- trainers (the new trainer role) read them through their own endpoint.
+
+ALTER TABLE match_series
+    ADD COLUMN IF NOT EXISTS is_training BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN match_series.is_training IS 'Practice round: ballots are kept out of stats, standings and tabs';
+
+ALTER TABLE user_roles DROP CONSTRAINT IF EXISTS valid_role;
+ALTER TABLE user_roles ADD CONSTRAINT valid_role CHECK (role IN ('equity', 'trainer'));
+
+-- Rebuild an event's rows from its submitted ballots, leaving out silent
+-- and training series
+CREATE OR REPLACE FUNCTION refresh_user_event_performance(target_event UUID)
+RETURNS VOID AS $$
+BEGIN
+    DELETE FROM user_event_performance WHERE event_id = target_event;
+
+    INSERT INTO user_event_performance (
+        user_id, event_id, speaker_score_total, speaker_score_count,
+        wins, losses, firsts, seconds, thirds, fourths
+    )
+    WITH speaks AS (
+        SELECT a.user_id, SUM(ss.score) AS total, COUNT(*) AS scores
+        FROM speaker_scores ss
+        JOIN ballots b ON ss.ballot_id = b.id
+        JOIN allocations a ON ss.allocation_id = a.id
+        JOIN matches m ON a.match_id = m.id
+        JOIN match_series ms ON m.series_id = ms.id
+        WHERE ms.event_id = target_event AND b.is_submitted
+          AND NOT ms.is_silent AND NOT ms.is_training
+          AND a.user_id IS NOT NULL
+        GROUP BY a.user_id
+    ),
+    results AS (
+        SELECT a.user_id,
+            COUNT(*) FILTER (WHERE tr.is_winner) AS wins,
+            COUNT(*) FILTER (WHERE NOT tr.is_winner) AS losses,
+            COUNT(*) FILTER (WHERE tr.rank = 1) AS firsts,
+            COUNT(*) FILTER (WHERE tr.rank = 2) AS seconds,
+            COUNT(*) FILTER (WHERE tr.rank = 3) AS thirds,
+            COUNT(*) FILTER (WHERE tr.rank = 4) AS fourths
+        FROM allocations a
+        JOIN match_teams mt ON a.team_id = mt.id
+        JOIN team_rankings tr ON mt.id = tr.team_id
+        JOIN ballots b ON tr.ballot_id = b.id
+        JOIN matches m ON a.match_id = m.id
+        JOIN match_series ms ON m.series_id = ms.id
+        WHERE ms.event_id = target_event AND a.role = 'speaker'
+          AND b.is_voting AND b.is_submitted
+          AND NOT ms.is_silent AND NOT ms.is_training
+          AND a.user_id IS NOT NULL
+        GROUP BY a.user_id
+    )
+    SELECT COALESCE(s.user_id, r.user_id), target_event,
+        COALESCE(s.total, 0), COALESCE(s.scores, 0),
+        COALESCE(r.wins, 0), COALESCE(r.losses, 0),
+        COALESCE(r.firsts, 0), COALESCE(r.seconds, 0),
+        COALESCE(r.thirds, 0), COALESCE(r.fourths, 0)
+    FROM speaks s
+    FULL JOIN results r ON r.user_id = s.user_id;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,2 @@
+-- The constraints are part of the ballot tables' original definition, so
+-- they stay; deleted duplicates cannot be restored.
@@ -0,0 +1,54 @@
+-- Migration: Ballot uniqueness
+-- One ballot per adjudicator per match, one score per speaker per ballot
+-- and one ranking per team per ballot. The ballot tables were declared
+-- with these constraints, but CREATE TABLE IF NOT EXISTS leaves a table
+-- that already existed alone, so databases older than the constraints can
+-- be missing them and collect duplicates from concurrent submissions.
+-- Duplicates are removed, keeping the most recently updated row (for
+-- ballots, a submitted one first), before any missing constraint is added.
+
+DELETE FROM ballots b
+USING (
+    SELECT id, ROW_NUMBER() OVER (
+        PARTITION BY match_id, adjudicator_id
+        ORDER BY is_submitted DESC, updated_at DESC, created_at DESC, id
+    ) AS position
+    FROM ballots
+) ranked
+WHERE b.id = ranked.id AND ranked.position > 1;
+
+DELETE FROM speaker_scores ss
+USING (
+    SELECT id, ROW_NUMBER() OVER (
+        PARTITION BY ballot_id, allocation_id
+        ORDER BY updated_at DESC, created_at DESC, id
+    ) AS position
+    FROM speaker_scores
+) ranked
+WHERE ss.id = ranked.id AND ranked.position > 1;
+
+DELETE FROM team_rankings tr
+USING (
+    SELECT id, ROW_NUMBER() OVER (
+        PARTITION BY ballot_id, team_id
+        ORDER BY updated_at DESC, created_at DESC, id
+    ) AS position
+    FROM team_rankings
+) ranked
+WHERE tr.id = ranked.id AND ranked.position > 1;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'unique_adjudicator_match') THEN
+        ALTER TABLE ballots
+            ADD CONSTRAINT unique_adjudicator_match UNIQUE (match_id, adjudicator_id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'unique_speaker_ballot') THEN
+        ALTER TABLE speaker_scores
+            ADD CONSTRAINT unique_speaker_ballot UNIQUE (ballot_id, allocation_id);
+    END IF;
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'unique_team_ballot') THEN
+        ALTER TABLE team_rankings
+            ADD CONSTRAINT unique_team_ballot UNIQUE (ballot_id, team_id);
+    END IF;
+END $$;
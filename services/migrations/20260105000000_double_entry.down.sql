@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS ballot_entry_rankings;
+DROP TABLE IF EXISTS ballot_entry_scores;
+DROP TABLE IF EXISTS ballot_entries;
+
+ALTER TABLE match_series DROP COLUMN IF EXISTS double_entry;
@@ -0,0 +1,47 @@
+-- Migration: Double-entry results
+-- Break rounds judged on paper can require double entry: two admins each
+-- type in the chair's paper ballot, and the result is only recorded once
+-- both entries agree. Each admin's latest entry for a room is kept here
+-- until then.
+
+ALTER TABLE match_series
+    ADD COLUMN IF NOT EXISTS double_entry BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN match_series.double_entry IS 'Break round results are entered by two admins and recorded once they agree';
+
+CREATE TABLE IF NOT EXISTS ballot_entries (
+    id UUID PRIMARY KEY,
+    match_id UUID NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    entered_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notes TEXT,
+    confirm_low_point_win BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_ballot_entry UNIQUE (match_id, entered_by)
+);
+
+CREATE TABLE IF NOT EXISTS ballot_entry_scores (
+    entry_id UUID NOT NULL REFERENCES ballot_entries(id) ON DELETE CASCADE,
+    allocation_id UUID NOT NULL REFERENCES allocations(id) ON DELETE CASCADE,
+    score NUMERIC(5,2) NOT NULL,
+    feedback TEXT,
+    PRIMARY KEY (entry_id, allocation_id)
+);
+
+CREATE TABLE IF NOT EXISTS ballot_entry_rankings (
+    entry_id UUID NOT NULL REFERENCES ballot_entries(id) ON DELETE CASCADE,
+    team_id UUID NOT NULL REFERENCES match_teams(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    is_winner BOOLEAN,
+    PRIMARY KEY (entry_id, team_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ballot_entries_match ON ballot_entries(match_id);
+
+COMMENT ON TABLE ballot_entries IS 'One admin''s typed copy of a paper ballot, awaiting a second matching entry';
+
+-- Entries for an archived event are read-only like its ballots. Scores and
+-- rankings are only written with their entry, which the trigger guards.
+CREATE OR REPLACE TRIGGER trigger_prevent_archived_changes
+    BEFORE INSERT OR UPDATE OR DELETE ON ballot_entries
+    FOR EACH ROW EXECUTE FUNCTION prevent_archived_event_changes();
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS event_template_series;
+DROP TABLE IF EXISTS event_templates;
+
+ALTER TABLE events DROP COLUMN IF EXISTS reminder_lead_hours;
+ALTER TABLE events DROP COLUMN IF EXISTS duration_minutes;
@@ -0,0 +1,46 @@
+-- Migration: Event templates
+-- A template holds everything a recurring event (the weekly internals)
+-- is set up with: its type, start time, length, seats, category, tags,
+-- reminder lead time and its series structure. Instantiating one on a date
+-- creates the event and its series in one go.
+
+-- Length of an event, for calendar feeds; NULL assumes the default
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS duration_minutes INTEGER CHECK (duration_minutes > 0);
+-- Hours before the start that the chat reminder is posted; NULL uses the default
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS reminder_lead_hours INTEGER CHECK (reminder_lead_hours > 0);
+
+CREATE TABLE IF NOT EXISTS event_templates (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    event_type VARCHAR(50) NOT NULL,
+    -- Time of day (UTC) the event starts on whichever date it is created for
+    start_time TIME NOT NULL,
+    duration_minutes INTEGER CHECK (duration_minutes > 0),
+    location VARCHAR(255),
+    max_participants INTEGER CHECK (max_participants > 0),
+    category_id UUID REFERENCES event_categories(id) ON DELETE SET NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    reminder_lead_hours INTEGER CHECK (reminder_lead_hours > 0),
+    created_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_template_name UNIQUE (name)
+);
+
+-- Series created with each event, in order
+CREATE TABLE IF NOT EXISTS event_template_series (
+    template_id UUID NOT NULL REFERENCES event_templates(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    round_number INTEGER,
+    team_format team_format NOT NULL,
+    allow_reply_speeches BOOLEAN NOT NULL DEFAULT FALSE,
+    is_break_round BOOLEAN NOT NULL DEFAULT FALSE,
+    is_training BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (template_id, position)
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS event_member_reminders;
+
+ALTER TABLE event_templates DROP COLUMN IF EXISTS reminder_schedule;
+ALTER TABLE events DROP COLUMN IF EXISTS reminder_schedule;
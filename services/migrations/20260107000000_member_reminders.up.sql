@@ -0,0 +1,23 @@
+-- Migration: Reminders to members before events
+-- Members who marked themselves available are emailed on a schedule of
+-- hours before the start (MEMBER_REMINDER_HOURS, or the event's own
+-- schedule). Each reminder sent is recorded, so it goes out once and the
+-- app can show it.
+
+-- Hours before the start to remind available members; NULL uses the
+-- default schedule, an empty array sends none
+ALTER TABLE events
+    ADD COLUMN IF NOT EXISTS reminder_schedule INTEGER[];
+ALTER TABLE event_templates
+    ADD COLUMN IF NOT EXISTS reminder_schedule INTEGER[];
+
+CREATE TABLE IF NOT EXISTS event_member_reminders (
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    hours_before INTEGER NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (event_id, user_id, hours_before)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_member_reminders_user_id
+    ON event_member_reminders(user_id);
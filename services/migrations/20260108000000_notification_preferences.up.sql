@@ -0,0 +1,15 @@
+-- Migration: Per-user notification preferences
+-- Which channels (email, in-app, SMS) each member wants each category of
+-- notification on. Only changes from the defaults are stored: SMS is off
+-- until a member turns it on, everything else is on until turned off.
+
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category VARCHAR(32) NOT NULL
+        CHECK (category IN ('allocations', 'results', 'reminders', 'merit')),
+    channel VARCHAR(16) NOT NULL
+        CHECK (channel IN ('email', 'in_app', 'sms')),
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, category, channel)
+);
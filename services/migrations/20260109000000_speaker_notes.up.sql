@@ -0,0 +1,18 @@
+-- Migration: Structured adjudicator notes per speaker
+-- Alongside a score's one-line feedback, adjudicators can leave each
+-- speaker what went well and what to work on. Speakers see notes once the
+-- match's results are released, trainers always; notes marked for the
+-- adjudication core are never shown to the speaker.
+
+CREATE TABLE IF NOT EXISTS speaker_notes (
+    ballot_id UUID NOT NULL REFERENCES ballots(id) ON DELETE CASCADE,
+    allocation_id UUID NOT NULL REFERENCES allocations(id) ON DELETE CASCADE,
+    strengths TEXT,
+    improvements TEXT,
+    adjcore_only BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (ballot_id, allocation_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_speaker_notes_allocation_id ON speaker_notes(allocation_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS judge_accreditations;
+DROP TYPE IF EXISTS accreditation_level;
@@ -0,0 +1,22 @@
+-- Migration: Judging accreditation ledger
+-- The adjudication core grades judges as trainee, accredited or
+-- chair-qualified. Every change is kept, newest last; a judge's current
+-- level is their latest entry. Allocation suggestions favour higher levels.
+
+DO $$ BEGIN
+    CREATE TYPE accreditation_level AS ENUM ('trainee', 'accredited', 'chair_qualified');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS judge_accreditations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    level accreditation_level NOT NULL,
+    note TEXT,
+    set_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_judge_accreditations_user
+    ON judge_accreditations(user_id, created_at DESC);
@@ -0,0 +1,23 @@
+-- Migration: Transactional outbox
+-- Side effects of a change (chat posts, webhooks) are recorded as events in
+-- the same transaction as the change, so they exist exactly when it
+-- commits. Each service's relay delivers its own events and marks them
+-- published; `delivered_to` lists the destinations already reached, so a
+-- retry only repeats the ones that failed.
+
+CREATE TABLE IF NOT EXISTS outbox_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    service TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_to TEXT[] NOT NULL DEFAULT '{}',
+    last_error TEXT,
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_events_pending
+    ON outbox_events(service, next_attempt_at)
+    WHERE published_at IS NULL;
@@ -0,0 +1,16 @@
+-- Changes admins make to a member's account on their behalf, such as
+-- confirming an email address whose verification code never arrived.
+
+CREATE TABLE IF NOT EXISTS account_audit (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    action VARCHAR(30) NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT valid_account_audit_action CHECK (action IN ('email_force_verified', 'verification_resent'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_audit_user_id ON account_audit(user_id, created_at DESC);
+
+COMMENT ON TABLE account_audit IS 'Log of changes admins made to members'' accounts';
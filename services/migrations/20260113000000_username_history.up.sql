@@ -0,0 +1,18 @@
+-- Usernames members have given up. Profiles are linked by username, so an
+-- old name keeps pointing at its former owner, who is the only one who may
+-- take it again.
+
+CREATE TABLE IF NOT EXISTS username_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    old_username VARCHAR(50) NOT NULL,
+    new_username VARCHAR(50) NOT NULL,
+    -- The member themselves, or the admin who renamed them
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_username_history_old_username ON username_history(old_username, changed_at DESC);
+CREATE INDEX IF NOT EXISTS idx_username_history_user_id ON username_history(user_id, changed_at DESC);
+
+COMMENT ON TABLE username_history IS 'Former usernames, for redirecting old profile links';
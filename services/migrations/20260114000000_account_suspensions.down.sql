@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS user_is_suspended(UUID);
+DROP TABLE IF EXISTS account_suspensions;
@@ -0,0 +1,31 @@
+-- Suspensions keep a member out without deleting their account and
+-- history. A suspension with no end lasts until an admin lifts it.
+
+CREATE TABLE IF NOT EXISTS account_suspensions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- Shown to the member when they try to log in
+    reason TEXT NOT NULL,
+    suspended_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ends_at TIMESTAMPTZ,
+    lifted_at TIMESTAMPTZ,
+    lifted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    CONSTRAINT suspension_ends_after_start CHECK (ends_at IS NULL OR ends_at > created_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_suspensions_user_id ON account_suspensions(user_id, created_at DESC);
+
+COMMENT ON TABLE account_suspensions IS 'Suspensions of members, current and past';
+
+-- Whether the member is suspended now, for services that only need to know
+-- that much (e.g. to flag their allocations for replacement)
+CREATE OR REPLACE FUNCTION user_is_suspended(target_user UUID)
+RETURNS BOOLEAN AS $$
+    SELECT EXISTS (
+        SELECT 1 FROM account_suspensions
+        WHERE user_id = target_user
+            AND lifted_at IS NULL
+            AND (ends_at IS NULL OR ends_at > NOW())
+    );
+$$ LANGUAGE sql STABLE;
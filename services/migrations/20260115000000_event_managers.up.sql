@@ -0,0 +1,15 @@
+-- Members an admin has trusted with running one event: its availability,
+-- check-in and allocations, without admin rights anywhere else.
+
+CREATE TABLE IF NOT EXISTS event_managers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_manager UNIQUE (event_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_managers_user_id ON event_managers(user_id);
+
+COMMENT ON TABLE event_managers IS 'Members with admin powers over a single event';
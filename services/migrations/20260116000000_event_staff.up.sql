@@ -0,0 +1,16 @@
+-- Who is running an event on the day: conveners and volunteers, who may
+-- check members in at that event and nothing more.
+
+CREATE TABLE IF NOT EXISTS event_staff (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('convener', 'volunteer')),
+    assigned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_staff UNIQUE (event_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_staff_user_id ON event_staff(user_id);
+
+COMMENT ON TABLE event_staff IS 'Conveners and volunteers staffing an event';
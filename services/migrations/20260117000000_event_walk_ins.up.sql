@@ -0,0 +1,17 @@
+-- Guests registered at the desk on the day. They join the event's
+-- allocation pool and are allocated by name, like any other guest; those
+-- who leave an email are invited to sign up.
+
+CREATE TABLE IF NOT EXISTS event_walk_ins (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL CHECK (name <> ''),
+    institution_id UUID REFERENCES institutions(id) ON DELETE SET NULL,
+    email VARCHAR(255),
+    invited_at TIMESTAMPTZ,
+    registered_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_walk_in UNIQUE (event_id, name)
+);
+
+COMMENT ON TABLE event_walk_ins IS 'Guests registered at the desk, available to the allocation pool';
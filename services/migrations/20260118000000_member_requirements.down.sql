@@ -0,0 +1,5 @@
+DELETE FROM user_roles WHERE role = 'welfare';
+ALTER TABLE user_roles DROP CONSTRAINT IF EXISTS valid_role;
+ALTER TABLE user_roles ADD CONSTRAINT valid_role CHECK (role IN ('equity', 'trainer'));
+
+DROP TABLE IF EXISTS member_requirements;
@@ -0,0 +1,15 @@
+-- Dietary and accessibility requirements members choose to share, for
+-- catering and venue planning. Only the new welfare role reads them, as
+-- per-event reports.
+
+CREATE TABLE IF NOT EXISTS member_requirements (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    dietary VARCHAR(500),
+    accessibility VARCHAR(1000),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE member_requirements IS 'Dietary and accessibility needs, readable by the welfare role only';
+
+ALTER TABLE user_roles DROP CONSTRAINT IF EXISTS valid_role;
+ALTER TABLE user_roles ADD CONSTRAINT valid_role CHECK (role IN ('equity', 'trainer', 'welfare'));
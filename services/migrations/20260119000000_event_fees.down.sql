@@ -0,0 +1,6 @@
+DELETE FROM user_roles WHERE role = 'treasurer';
+ALTER TABLE user_roles DROP CONSTRAINT IF EXISTS valid_role;
+ALTER TABLE user_roles ADD CONSTRAINT valid_role CHECK (role IN ('equity', 'trainer', 'welfare'));
+
+DROP TABLE IF EXISTS event_payments;
+DROP TABLE IF EXISTS event_fees;
@@ -0,0 +1,36 @@
+-- Fees for paid events and who has paid them. Treasurers mark payments by
+-- hand (cash, bank transfer) or members pay online through Stripe
+-- Checkout; members with no payment row have not paid.
+
+CREATE TABLE IF NOT EXISTS event_fees (
+    event_id UUID PRIMARY KEY REFERENCES events(id) ON DELETE CASCADE,
+    -- In the currency's smallest unit, as Stripe expects
+    amount_minor INTEGER NOT NULL CHECK (amount_minor > 0),
+    currency CHAR(3) NOT NULL,
+    description VARCHAR(255),
+    set_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS event_payments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- pending: a Stripe checkout was started but has not completed
+    status VARCHAR(20) NOT NULL CHECK (status IN ('pending', 'paid', 'waived')),
+    method VARCHAR(20) NOT NULL CHECK (method IN ('manual', 'stripe')),
+    amount_minor INTEGER,
+    stripe_session_id VARCHAR(255) UNIQUE,
+    note VARCHAR(500),
+    recorded_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    paid_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT unique_event_payment UNIQUE (event_id, user_id)
+);
+
+COMMENT ON TABLE event_fees IS 'What members pay to attend an event; events without a row are free';
+COMMENT ON TABLE event_payments IS 'Fee payments, marked by treasurers or completed through Stripe';
+
+ALTER TABLE user_roles DROP CONSTRAINT IF EXISTS valid_role;
+ALTER TABLE user_roles ADD CONSTRAINT valid_role CHECK (role IN ('equity', 'trainer', 'welfare', 'treasurer'));
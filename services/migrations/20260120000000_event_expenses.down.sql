@@ -0,0 +1,3 @@
+ALTER TABLE event_payments DROP COLUMN IF EXISTS currency;
+
+DROP TABLE IF EXISTS event_expenses;
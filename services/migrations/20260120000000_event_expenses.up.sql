@@ -0,0 +1,42 @@
+-- What the society spends on events. Treasurers enter expenses with a
+-- receipt and another treasurer (or an admin) approves them; approved
+-- expenses are set against the fees collected for each event.
+
+CREATE TABLE IF NOT EXISTS event_expenses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_id UUID NOT NULL REFERENCES events(id) ON DELETE CASCADE,
+    category VARCHAR(20) NOT NULL,
+    description VARCHAR(500) NOT NULL CHECK (description <> ''),
+    -- In the currency's smallest unit, like fees
+    amount_minor INTEGER NOT NULL CHECK (amount_minor > 0),
+    currency CHAR(3) NOT NULL,
+    spent_on DATE NOT NULL,
+    -- The receipt is stored outside the database under receipt_key
+    receipt_filename VARCHAR(255),
+    receipt_content_type VARCHAR(100),
+    receipt_key TEXT,
+    receipt_size_bytes BIGINT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    submitted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT valid_expense_category CHECK (
+        category IN ('venue', 'travel', 'food', 'printing', 'prizes', 'equipment', 'other')
+    ),
+    CONSTRAINT valid_expense_status CHECK (status IN ('pending', 'approved', 'rejected'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_expenses_event_id ON event_expenses(event_id);
+CREATE INDEX IF NOT EXISTS idx_event_expenses_status ON event_expenses(status);
+
+COMMENT ON TABLE event_expenses IS 'Event spending, entered and approved by treasurers';
+
+-- Payments record their currency, so income can be totalled by it even
+-- after an event's fee changes
+ALTER TABLE event_payments ADD COLUMN IF NOT EXISTS currency CHAR(3);
+UPDATE event_payments ep SET currency = ef.currency
+FROM event_fees ef
+WHERE ef.event_id = ep.event_id AND ep.currency IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS equipment_checkouts;
+DROP TABLE IF EXISTS equipment_items;
@@ -0,0 +1,42 @@
+-- The society's kit (timers, gavels, banners...) and who has it. Admins lend
+-- items to members for an event; an item is out until it is returned, and
+-- overdue once its due time passes.
+
+CREATE TABLE IF NOT EXISTS equipment_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL CHECK (name <> ''),
+    kind VARCHAR(50) NOT NULL CHECK (kind <> ''),
+    -- The label stuck on the item, if it has one
+    asset_tag VARCHAR(50) UNIQUE,
+    notes TEXT,
+    -- Retired items keep their history but can no longer be lent
+    retired_at TIMESTAMPTZ,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS equipment_checkouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    item_id UUID NOT NULL REFERENCES equipment_items(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_id UUID REFERENCES events(id) ON DELETE SET NULL,
+    checked_out_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    checked_out_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    due_at TIMESTAMPTZ NOT NULL,
+    note TEXT,
+    returned_at TIMESTAMPTZ,
+    returned_to UUID REFERENCES users(id) ON DELETE SET NULL,
+    return_note TEXT
+);
+
+-- An item is with one person at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_equipment_checkouts_open
+    ON equipment_checkouts(item_id) WHERE returned_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_equipment_checkouts_user_id ON equipment_checkouts(user_id);
+CREATE INDEX IF NOT EXISTS idx_equipment_checkouts_event_id ON equipment_checkouts(event_id);
+CREATE INDEX IF NOT EXISTS idx_equipment_checkouts_due_at
+    ON equipment_checkouts(due_at) WHERE returned_at IS NULL;
+
+COMMENT ON TABLE equipment_items IS 'Society kit that can be lent to members';
+COMMENT ON TABLE equipment_checkouts IS 'Who borrowed each item, for which event, and when it came back';
@@ -0,0 +1,12 @@
+-- Admins' runtime overrides of the features configured with FEATURES. A
+-- feature without a row follows the deployment's configuration.
+
+CREATE TABLE IF NOT EXISTS feature_flags (
+    -- payments, webhooks or public_tab
+    feature VARCHAR(50) PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE feature_flags IS 'Features an admin has turned on or off, overriding the configuration';
@@ -0,0 +1,16 @@
+-- Maintenance mode. A row puts one service (or, with scope 'all', every
+-- service) into maintenance: non-admin requests get a 503 until the row is
+-- deleted.
+
+CREATE TABLE IF NOT EXISTS maintenance_modes (
+    -- all, auth, attendance, merit or tabulation
+    scope VARCHAR(20) PRIMARY KEY,
+    -- Shown to members turned away
+    message TEXT NOT NULL CHECK (message <> ''),
+    -- When admins expect to be done; sent as Retry-After
+    expected_end TIMESTAMPTZ,
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE maintenance_modes IS 'Services in maintenance, open only to admins';
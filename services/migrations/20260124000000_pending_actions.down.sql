@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS pending_actions;
+DROP TYPE IF EXISTS pending_action_status;
@@ -0,0 +1,33 @@
+-- Two-person rule. Destructive admin actions are recorded here and only
+-- carried out once a second admin confirms them.
+
+DO $$ BEGIN
+    CREATE TYPE pending_action_status AS ENUM ('pending', 'confirmed', 'rejected');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS pending_actions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Service that carries the action out: auth, attendance or tabulation
+    service VARCHAR(20) NOT NULL,
+    -- demote_admin, delete_event or delete_series
+    action VARCHAR(50) NOT NULL,
+    -- The admin, event or series acted on
+    target_id UUID NOT NULL,
+    summary TEXT NOT NULL,
+    requested_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Unconfirmed requests lapse rather than waiting forever
+    expires_at TIMESTAMPTZ NOT NULL,
+    status pending_action_status NOT NULL DEFAULT 'pending',
+    decided_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    decided_at TIMESTAMPTZ,
+    CHECK (status = 'pending' OR decided_at IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_actions_open
+    ON pending_actions (service, requested_at)
+    WHERE status = 'pending';
+
+COMMENT ON TABLE pending_actions IS 'Destructive admin actions awaiting a second admin';
@@ -0,0 +1,21 @@
+-- Undo window for allocation deletes and swaps. The change is made at
+-- once; this row keeps the allocations as they were, so it can be undone,
+-- and the history to write once the window closes.
+
+CREATE TABLE IF NOT EXISTS staged_allocation_changes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- deleted or swapped
+    action VARCHAR(20) NOT NULL,
+    -- Allocations before the change, restored by an undo
+    snapshot JSONB NOT NULL,
+    -- allocation_history rows written when the change becomes final
+    history JSONB NOT NULL,
+    staged_by UUID NOT NULL,
+    staged_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finalize_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_staged_allocation_changes_finalize_at
+    ON staged_allocation_changes (finalize_at);
+
+COMMENT ON TABLE staged_allocation_changes IS 'Allocation deletes and swaps that can still be undone';
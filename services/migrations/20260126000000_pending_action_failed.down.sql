@@ -0,0 +1,15 @@
+UPDATE pending_actions SET status = 'rejected' WHERE status = 'failed';
+
+ALTER TYPE pending_action_status RENAME TO pending_action_status_old;
+CREATE TYPE pending_action_status AS ENUM ('pending', 'confirmed', 'rejected');
+
+DROP INDEX IF EXISTS idx_pending_actions_open;
+ALTER TABLE pending_actions ALTER COLUMN status DROP DEFAULT;
+ALTER TABLE pending_actions
+    ALTER COLUMN status TYPE pending_action_status USING status::text::pending_action_status;
+ALTER TABLE pending_actions ALTER COLUMN status SET DEFAULT 'pending';
+CREATE INDEX IF NOT EXISTS idx_pending_actions_open
+    ON pending_actions (service, requested_at)
+    WHERE status = 'pending';
+
+DROP TYPE pending_action_status_old;
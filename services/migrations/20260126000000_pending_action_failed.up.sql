@@ -0,0 +1,5 @@
+-- Confirmed actions that turn out impossible to carry out (e.g. demoting
+-- the last admin) are closed as failed, so they are not recorded as
+-- rejected by the admin who confirmed them.
+
+ALTER TYPE pending_action_status ADD VALUE IF NOT EXISTS 'failed';
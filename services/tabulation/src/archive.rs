@@ -0,0 +1,391 @@
+//! Event archival: a complete, immutable export of one event, after which the
+//! event is read-only.
+//!
+//! The archive is a zip holding every dataset twice, as `json/<name>.json`
+//! and `csv/<name>.csv`, plus a `manifest.json` with the SHA-256 of each file.
+//! All datasets are read from one snapshot. Once an event is marked archived,
+//! database triggers reject changes to its rounds, rooms, allocations, ballots
+//! and attendance (see the `event_archives` migration).
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::Write;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::database::Database;
+use common::{storage::StorageError, Storage};
+
+/// Bumped whenever datasets or columns change shape
+pub const ARCHIVE_FORMAT_VERSION: u32 = 10;
+
+/// One exported table. `{event}` in the query is replaced by the event id
+/// literal, because `COPY` cannot take bind parameters.
+struct Dataset {
+    name: &'static str,
+    query: &'static str,
+}
+
+const DATASETS: &[Dataset] = &[
+    Dataset {
+        name: "event",
+        query: "SELECT id, title, description, event_type, event_date, location, team_entry, \
+                tie_breaks, tie_break_seed, speaks_withheld, created_by, created_at, updated_at \
+                FROM events WHERE id = {event}",
+    },
+    Dataset {
+        name: "series",
+        query: "SELECT * FROM match_series WHERE event_id = {event} \
+                ORDER BY round_number NULLS LAST, created_at",
+    },
+    Dataset {
+        name: "matches",
+        query: "SELECT m.* FROM matches m JOIN match_series s ON m.series_id = s.id \
+                WHERE s.event_id = {event} \
+                ORDER BY s.round_number NULLS LAST, m.room_name, m.created_at",
+    },
+    // Venues the event's matches were held in
+    Dataset {
+        name: "venues",
+        query: "SELECT v.* FROM venues v WHERE v.id IN (SELECT m.venue_id FROM matches m \
+                JOIN match_series s ON m.series_id = s.id WHERE s.event_id = {event}) \
+                ORDER BY v.name",
+    },
+    Dataset {
+        name: "teams",
+        query: "SELECT t.* FROM match_teams t JOIN matches m ON t.match_id = m.id \
+                JOIN match_series s ON m.series_id = s.id WHERE s.event_id = {event} \
+                ORDER BY t.match_id, t.two_team_position, t.four_team_position",
+    },
+    // Registered teams as they were entered, members in speaking order
+    Dataset {
+        name: "team_registrations",
+        query: "SELECT r.team_id, t.name, t.institution, \
+                (SELECT string_agg(u.username, ';' ORDER BY m.speaker_order) \
+                    FROM registered_team_members m JOIN users u ON m.user_id = u.id \
+                    WHERE m.team_id = t.id) AS members, \
+                r.registered_by, r.registered_at \
+                FROM event_team_registrations r JOIN registered_teams t ON r.team_id = t.id \
+                WHERE r.event_id = {event} ORDER BY t.name",
+    },
+    Dataset {
+        name: "allocations",
+        query: "SELECT a.*, COALESCE(u.username, a.guest_name) AS participant \
+                FROM allocations a JOIN matches m ON a.match_id = m.id \
+                JOIN match_series s ON m.series_id = s.id LEFT JOIN users u ON a.user_id = u.id \
+                WHERE s.event_id = {event} ORDER BY a.match_id, a.role, a.allocated_at",
+    },
+    Dataset {
+        name: "allocation_history",
+        query: "SELECT h.* FROM allocation_history h JOIN matches m ON h.match_id = m.id \
+                JOIN match_series s ON m.series_id = s.id WHERE s.event_id = {event} \
+                ORDER BY h.changed_at",
+    },
+    Dataset {
+        name: "ballots",
+        query: "SELECT b.*, u.username AS adjudicator FROM ballots b \
+                JOIN matches m ON b.match_id = m.id JOIN match_series s ON m.series_id = s.id \
+                LEFT JOIN users u ON b.adjudicator_id = u.id \
+                WHERE s.event_id = {event} ORDER BY b.match_id, b.created_at",
+    },
+    Dataset {
+        name: "speaker_scores",
+        query: "SELECT ss.* FROM speaker_scores ss JOIN ballots b ON ss.ballot_id = b.id \
+                JOIN matches m ON b.match_id = m.id JOIN match_series s ON m.series_id = s.id \
+                WHERE s.event_id = {event} ORDER BY ss.ballot_id, ss.allocation_id",
+    },
+    Dataset {
+        name: "team_rankings",
+        query: "SELECT tr.* FROM team_rankings tr JOIN ballots b ON tr.ballot_id = b.id \
+                JOIN matches m ON b.match_id = m.id JOIN match_series s ON m.series_id = s.id \
+                WHERE s.event_id = {event} ORDER BY tr.ballot_id, tr.rank",
+    },
+    // Institution and eligibility of everyone who took part, as they stood
+    // when the event was archived
+    Dataset {
+        name: "participants",
+        query: "SELECT u.id AS user_id, u.username, i.name AS institution, u.is_novice, u.is_esl \
+                FROM users u LEFT JOIN institutions i ON u.institution_id = i.id \
+                WHERE u.id IN ( \
+                    SELECT user_id FROM attendance_records WHERE event_id = {event} \
+                    UNION SELECT a.user_id FROM allocations a \
+                    JOIN matches m ON a.match_id = m.id \
+                    JOIN match_series s ON m.series_id = s.id \
+                    WHERE s.event_id = {event} AND a.user_id IS NOT NULL) \
+                ORDER BY u.username",
+    },
+    Dataset {
+        name: "tab_categories",
+        query: "SELECT * FROM event_tab_categories WHERE event_id = {event} ORDER BY category",
+    },
+    Dataset {
+        name: "attendance",
+        query: "SELECT ar.*, u.username FROM attendance_records ar \
+                JOIN users u ON ar.user_id = u.id \
+                WHERE ar.event_id = {event} ORDER BY u.username",
+    },
+    // Metadata only; the files stay in storage under their keys
+    Dataset {
+        name: "attachments",
+        query: "SELECT att.* FROM attachments att LEFT JOIN matches m ON att.match_id = m.id \
+                LEFT JOIN match_series s ON m.series_id = s.id \
+                WHERE att.event_id = {event} OR s.event_id = {event} ORDER BY att.created_at",
+    },
+    // Merit is not tied to events, so this is every change to a participant's
+    // merit from the day of the event until it was archived
+    Dataset {
+        name: "merit_changes",
+        query: "SELECT h.*, u.username FROM merit_history h JOIN users u ON h.user_id = u.id \
+                WHERE h.created_at >= (SELECT date_trunc('day', event_date) FROM events \
+                    WHERE id = {event}) \
+                AND h.user_id IN ( \
+                    SELECT user_id FROM attendance_records WHERE event_id = {event} \
+                    UNION SELECT a.user_id FROM allocations a \
+                    JOIN matches m ON a.match_id = m.id \
+                    JOIN match_series s ON m.series_id = s.id \
+                    WHERE s.event_id = {event} AND a.user_id IS NOT NULL) \
+                ORDER BY h.created_at",
+    },
+];
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    EventNotFound,
+    AlreadyArchived(ArchiveRecord),
+    Database(sqlx::Error),
+    Zip(String),
+    Storage(StorageError),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::EventNotFound => write!(f, "Event not found"),
+            ArchiveError::AlreadyArchived(_) => write!(f, "Event is already archived"),
+            ArchiveError::Database(e) => write!(f, "Database error: {}", e),
+            ArchiveError::Zip(msg) => write!(f, "Failed to build archive: {}", msg),
+            ArchiveError::Storage(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<sqlx::Error> for ArchiveError {
+    fn from(e: sqlx::Error) -> Self {
+        ArchiveError::Database(e)
+    }
+}
+
+impl From<zip::result::ZipError> for ArchiveError {
+    fn from(e: zip::result::ZipError) -> Self {
+        ArchiveError::Zip(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(e: std::io::Error) -> Self {
+        ArchiveError::Zip(e.to_string())
+    }
+}
+
+/// A stored archive, as recorded on the event
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ArchiveRecord {
+    pub event_id: Uuid,
+    pub archived_at: DateTime<Utc>,
+    pub archived_by: Option<Uuid>,
+    pub archive_key: String,
+    pub archive_sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    format_version: u32,
+    event_id: Uuid,
+    event_title: &'a str,
+    archived_at: DateTime<Utc>,
+    archived_by: Uuid,
+    files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestFile {
+    path: String,
+    rows: i64,
+    bytes: usize,
+    sha256: String,
+}
+
+/// Storage key of an event's archive
+pub fn archive_key(event_id: Uuid) -> String {
+    format!("archives/{}.zip", event_id)
+}
+
+/// Mark the event read-only, export it and store the zip. If exporting or
+/// storing fails the event is made writable again and can be retried.
+pub async fn archive_event(
+    db: &Database,
+    storage: &Storage,
+    event_id: Uuid,
+    admin_id: Uuid,
+) -> Result<ArchiveRecord, ArchiveError> {
+    let Some((title, archived_at)) = db.begin_event_archive(event_id, admin_id).await? else {
+        return match db.get_event_archive(event_id).await? {
+            Some(record) => Err(ArchiveError::AlreadyArchived(record)),
+            None => Err(ArchiveError::EventNotFound),
+        };
+    };
+
+    let key = archive_key(event_id);
+    let stored = async {
+        let bundle = build_archive(db, event_id, &title, archived_at, admin_id).await?;
+        let sha256 = hex::encode(Sha256::digest(&bundle));
+        storage
+            .put(&key, bundle, "application/zip")
+            .await
+            .map_err(ArchiveError::Storage)?;
+        Ok::<_, ArchiveError>(sha256)
+    }
+    .await;
+
+    match stored {
+        Ok(sha256) => {
+            db.finish_event_archive(event_id, &key, &sha256).await?;
+            Ok(ArchiveRecord {
+                event_id,
+                archived_at,
+                archived_by: Some(admin_id),
+                archive_key: key,
+                archive_sha256: sha256,
+            })
+        }
+        Err(e) => {
+            if let Err(reset) = db.abandon_event_archive(event_id).await {
+                tracing::error!(
+                    "Failed to reopen event {} after archiving failed: {}",
+                    event_id,
+                    reset
+                );
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn build_archive(
+    db: &Database,
+    event_id: Uuid,
+    title: &str,
+    archived_at: DateTime<Utc>,
+    archived_by: Uuid,
+) -> Result<Vec<u8>, ArchiveError> {
+    let mut tx = db.pool().begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let event = format!("'{}'::uuid", event_id);
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip_timestamp(archived_at));
+    let mut files = Vec::new();
+
+    for dataset in DATASETS {
+        let query = dataset.query.replace("{event}", &event);
+
+        let (json, rows): (String, i64) = sqlx::query_as(&format!(
+            "SELECT COALESCE(json_agg(t), '[]'::json)::text, COUNT(*) FROM ({}) t",
+            query
+        ))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let csv: Vec<u8> = tx
+            .copy_out_raw(&format!(
+                "COPY ({}) TO STDOUT WITH (FORMAT csv, HEADER)",
+                query
+            ))
+            .await?
+            .try_fold(Vec::new(), |mut out, chunk| async move {
+                out.extend_from_slice(&chunk);
+                Ok(out)
+            })
+            .await?;
+
+        for (path, body) in [
+            (format!("json/{}.json", dataset.name), json.into_bytes()),
+            (format!("csv/{}.csv", dataset.name), csv),
+        ] {
+            zip.start_file(path.as_str(), options)?;
+            zip.write_all(&body)?;
+            files.push(ManifestFile {
+                path,
+                rows,
+                bytes: body.len(),
+                sha256: hex::encode(Sha256::digest(&body)),
+            });
+        }
+    }
+    tx.commit().await?;
+
+    let manifest = Manifest {
+        format_version: ARCHIVE_FORMAT_VERSION,
+        event_id,
+        event_title: title,
+        archived_at,
+        archived_by,
+        files,
+    };
+    zip.start_file("manifest.json", options)?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)
+        .map_err(|e| ArchiveError::Zip(e.to_string()))?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Zip entries carry local date/time only; archives always use UTC
+fn zip_timestamp(at: DateTime<Utc>) -> zip::DateTime {
+    zip::DateTime::from_date_and_time(
+        at.year().clamp(1980, 2107) as u16,
+        at.month() as u8,
+        at.day() as u8,
+        at.hour() as u8,
+        at.minute() as u8,
+        at.second() as u8,
+    )
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_dataset_is_scoped_to_the_event() {
+        for dataset in DATASETS {
+            assert!(
+                dataset.query.contains("{event}"),
+                "{} is not filtered by event",
+                dataset.name
+            );
+        }
+        let mut names: Vec<&str> = DATASETS.iter().map(|d| d.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), DATASETS.len());
+    }
+
+    #[test]
+    fn test_archive_key() {
+        let id = Uuid::nil();
+        assert_eq!(
+            archive_key(id),
+            "archives/00000000-0000-0000-0000-000000000000.zip"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Files attached to events (info packs) and matches (info slides).
+//!
+//! Contents never pass through the database: an admin registers the file,
+//! uploads it straight to object storage with a presigned URL (or to this
+//! service when files are kept on disk), then confirms the upload. Match
+//! attachments follow the same release rule as the motion.
+
+use chrono::{Duration, Utc};
+use common::Storage;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::models::{Attachment, AttachmentUpload};
+
+pub use common::storage::{check_content_type, content_disposition, ALLOWED_CONTENT_TYPES};
+
+/// How long a presigned upload URL stays valid
+const UPLOAD_URL_EXPIRY_MINUTES: i64 = 15;
+/// How long a presigned download URL stays valid
+const DOWNLOAD_URL_EXPIRY_MINUTES: i64 = 5;
+
+/// Storage key for an attachment. The id keeps keys unique; the filename is
+/// kept (reduced to safe characters) so stored objects are recognisable.
+pub fn storage_key(attachment_id: Uuid, filename: &str) -> String {
+    let safe: String = filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "attachments/{}/{}",
+        attachment_id,
+        safe.trim_start_matches('.')
+    )
+}
+
+/// Tell the client where to send the file
+pub fn upload_instructions(storage: &Storage, attachment: &Attachment) -> AttachmentUpload {
+    let expiry = Duration::minutes(UPLOAD_URL_EXPIRY_MINUTES);
+    let headers = BTreeMap::from([("content-type", attachment.content_type.clone())]);
+
+    match storage.presigned_put(
+        &attachment.storage_key,
+        &attachment.content_type,
+        expiry.to_std().unwrap_or_default(),
+    ) {
+        Some(url) => AttachmentUpload {
+            method: "PUT",
+            url,
+            headers,
+            expires_at: Some(Utc::now() + expiry),
+        },
+        None => AttachmentUpload {
+            method: "PUT",
+            url: format!("/admin/attachments/{}/content", attachment.id),
+            headers,
+            expires_at: None,
+        },
+    }
+}
+
+/// Short-lived direct download URL, when the storage backend supports it
+pub fn download_url(storage: &Storage, attachment: &Attachment) -> Option<String> {
+    storage.presigned_get(
+        &attachment.storage_key,
+        Duration::minutes(DOWNLOAD_URL_EXPIRY_MINUTES)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_key_is_safe() {
+        let id = Uuid::nil();
+        assert_eq!(
+            storage_key(id, "Round 1 – slide.pdf"),
+            "attachments/00000000-0000-0000-0000-000000000000/Round_1___slide.pdf"
+        );
+        assert_eq!(
+            storage_key(id, "../../etc/passwd"),
+            "attachments/00000000-0000-0000-0000-000000000000/_.._etc_passwd"
+        );
+    }
+}
@@ -1,265 +1,75 @@
+//! Token validation lives in `common::auth_middleware`; this module tells it
+//! how the tabulation service decides whether a user is an admin, and adds
+//! the trainer check that guards training ballots.
+
 use axum::{
     extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::Response,
-    Json,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use serde_json::json;
+use common::{
+    api_keys::ApiClient,
+    auth_middleware::{authenticate_active, check_suspended_cached},
+    error::{api_error, db_error},
+    ApiError, AuthState, Role,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::{models::Claims, AppState};
+use crate::AppState;
 
-/// Middleware to authenticate requests using JWT access tokens
-pub async fn auth_middleware(
-    State(state): State<Arc<AppState>>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
+pub use common::auth_middleware::{
+    admin_middleware, auth_middleware, event_admin_middleware, optional_auth_middleware,
+    read_access_middleware,
+};
 
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
+impl AuthState for AppState {
+    fn jwt_secret(&self) -> &str {
+        &self.config.jwt_secret
     }
 
-    let token = &auth_header[7..]; // Remove "Bearer " prefix
-
-    // Validate the access token
-    let mut validation = Validation::default();
-    validation.validate_exp = true;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
-        )
-    })?;
-
-    let claims = token_data.claims;
-
-    // Verify it's an access token
-    if claims.token_type != "access" {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid token type"})),
-        ));
+    /// Admin status is read straight from the shared users table
+    async fn is_admin(&self, user_id: Uuid, _auth_header: &str) -> Result<bool, ApiError> {
+        self.db.is_user_admin(user_id).await.map_err(db_error)
     }
 
-    // Parse user_id from claims
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
-
-    // Add user_id and username to request extensions
-    request.extensions_mut().insert(user_id);
-    request.extensions_mut().insert(claims.username);
-
-    Ok(next.run(request).await)
-}
-
-/// Optional auth middleware - extracts user info if valid token present, but allows anonymous access
-pub async fn optional_auth_middleware(
-    State(state): State<Arc<AppState>>,
-    mut request: Request,
-    next: Next,
-) -> Response {
-    let headers = request.headers();
-
-    // Try to extract token from Authorization header
-    if let Some(auth_header) = headers.get("Authorization").and_then(|v| v.to_str().ok()) {
-        if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            // Try to validate the token
-            let mut validation = Validation::default();
-            validation.validate_exp = true;
-
-            if let Ok(token_data) = decode::<Claims>(
-                token,
-                &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-                &validation,
-            ) {
-                let claims = token_data.claims;
-
-                // Only use if it's an access token
-                if claims.token_type == "access" {
-                    if let Ok(user_id) = Uuid::parse_str(&claims.sub) {
-                        request.extensions_mut().insert(user_id);
-                        request.extensions_mut().insert(claims.username);
-                    }
-                }
-            }
-        }
+    async fn find_api_key(&self, key_hash: &str) -> Result<Option<ApiClient>, ApiError> {
+        self.db.find_api_key(key_hash).await.map_err(db_error)
     }
 
-    next.run(request).await
-}
-
-/// Admin middleware - requires authenticated user to be an admin
-pub async fn admin_middleware(
-    State(state): State<Arc<AppState>>,
-    mut request: Request,
-    next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    // Check if it's a Bearer token
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
-    }
-
-    let token = &auth_header[7..];
-
-    // Validate the access token
-    let mut validation = Validation::default();
-    validation.validate_exp = true;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
-        )
-    })?;
-
-    let claims = token_data.claims;
-
-    if claims.token_type != "access" {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid token type"})),
-        ));
+    async fn is_suspended(&self, user_id: Uuid) -> Result<bool, ApiError> {
+        check_suspended_cached(&self.kv, user_id, async {
+            self.db.is_user_suspended(user_id).await.map_err(db_error)
+        })
+        .await
     }
-
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
-
-    // Check if user is admin
-    let is_admin = state.db.is_user_admin(user_id).await.map_err(|e| {
-        tracing::error!("Database error checking admin status: {:?}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": format!("Database error: {}", e)})),
-        )
-    })?;
-
-    if !is_admin {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Admin access required"})),
-        ));
-    }
-
-    request.extensions_mut().insert(user_id);
-    request.extensions_mut().insert(claims.username);
-
-    Ok(next.run(request).await)
 }
 
-/// Adjudicator middleware - requires user to be allocated as an adjudicator for the match
-pub async fn adjudicator_middleware(
+/// Trainer middleware - requires the trainer role or admin rights
+pub async fn trainer_middleware(
     State(state): State<Arc<AppState>>,
     mut request: Request,
     next: Next,
-) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
-    let headers = request.headers();
-
-    // Extract token from Authorization header
-    let auth_header = headers
-        .get("Authorization")
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing authorization header"})),
-            )
-        })?;
-
-    if !auth_header.starts_with("Bearer ") {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid authorization header format"})),
-        ));
+) -> Result<Response, ApiError> {
+    let (user_id, claims, _) =
+        authenticate_active(&*state, request.method(), request.headers()).await?;
+
+    let verify_error = |_| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to verify role");
+    let is_trainer = state
+        .db
+        .is_user_admin(user_id)
+        .await
+        .map_err(verify_error)?
+        || state
+            .db
+            .has_role(user_id, Role::Trainer)
+            .await
+            .map_err(verify_error)?;
+    if !is_trainer {
+        return Err(api_error(StatusCode::FORBIDDEN, "Trainer role required"));
     }
 
-    let token = &auth_header[7..];
-
-    let mut validation = Validation::default();
-    validation.validate_exp = true;
-
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
-        )
-    })?;
-
-    let claims = token_data.claims;
-
-    if claims.token_type != "access" {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid token type"})),
-        ));
-    }
-
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid user ID in token"})),
-        )
-    })?;
-
     request.extensions_mut().insert(user_id);
     request.extensions_mut().insert(claims.username);
 
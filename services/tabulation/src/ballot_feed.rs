@@ -0,0 +1,83 @@
+//! Live feed of ballot submissions for the admin dashboard.
+//!
+//! Submissions are broadcast within this process to every open stream. A
+//! stream that falls more than `FEED_CAPACITY` updates behind misses them
+//! and should fetch the room counts afresh.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Updates kept for streams that are slow to read
+pub const FEED_CAPACITY: usize = 256;
+
+/// A ballot or feedback form that has just been submitted
+#[derive(Debug, Clone, Serialize)]
+pub struct BallotUpdate {
+    pub event_id: Uuid,
+    pub match_id: Uuid,
+    pub ballot_id: Uuid,
+    pub adjudicator_id: Uuid,
+    pub is_voting: bool,
+    /// Submitted with a low-point win the adjudicator confirmed
+    pub low_point_win: bool,
+    pub submitted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BallotFeed {
+    sender: broadcast::Sender<BallotUpdate>,
+}
+
+impl Default for BallotFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BallotFeed {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(FEED_CAPACITY);
+        Self { sender }
+    }
+
+    /// Send to every open stream; nobody listening is not an error
+    pub fn publish(&self, update: BallotUpdate) {
+        let _ = self.sender.send(update);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BallotUpdate> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(event: u128) -> BallotUpdate {
+        BallotUpdate {
+            event_id: Uuid::from_u128(event),
+            match_id: Uuid::from_u128(2),
+            ballot_id: Uuid::from_u128(3),
+            adjudicator_id: Uuid::from_u128(4),
+            is_voting: true,
+            low_point_win: false,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_receives_updates() {
+        let feed = BallotFeed::new();
+        feed.publish(update(0));
+
+        let mut first = feed.subscribe();
+        let mut second = feed.subscribe();
+        feed.publish(update(1));
+
+        assert_eq!(first.recv().await.unwrap().event_id, Uuid::from_u128(1));
+        assert_eq!(second.recv().await.unwrap().event_id, Uuid::from_u128(1));
+    }
+}
@@ -1,11 +1,36 @@
+use crate::archive::ArchiveRecord;
+use crate::double_entry::EnteredBallot;
+use crate::eligibility::EligibilityFilter;
+use crate::judge_stats::GivenScore;
 use crate::models::{
-    Allocation, AllocationHistory, AllocationRole, AllocationWithUser, AttendanceInfo, Ballot,
-    EventInfo, FourTeamPosition, FourTeamSpeakerRole, Match, MatchSeries, MatchStatus, MatchTeam,
-    SpeakerScore, TeamFormat, TeamRanking, TwoTeamPosition, TwoTeamSpeakerRole, UserInfo,
+    AccreditationChange, AccreditationLevel, Allocation, AllocationHistory, AllocationRole,
+    AllocationWithUser, Attachment, AttendanceInfo, Ballot, BallotEntry, BallotEntryRanking,
+    BallotEntryScore, BallotStats, CalendarAllocation, CreateVenueRequest, EventBallotCount,
+    EventInfo, FourTeamPosition, FourTeamSpeakerRole, Institution, JudgingRounds, Match,
+    MatchSeries, MatchStatus, MatchTeam, OutstandingBallot, PerformanceTotals, ReceivedSpeakerNote,
+    RegisteredTeam, RegisteredTeamMember, RoomBallotProgress, ScheduleConflict, ScheduleEntry,
+    SpeakerEligibility, SpeakerNote, SpeakerNoteInput, SpeakerScore, TabCategory, TeamFormat,
+    TeamRanking, TeamRegistration, TieBreakSettings, TwoTeamPosition, TwoTeamSpeakerRole,
+    UpdateTabCategoryRequest, UpdateVenueRequest, UserInfo, Venue, WalkInGuest,
 };
-use chrono::Utc;
+use crate::motion_stats::MotionResult;
+use crate::outbox;
+use crate::rotation::DrawnPosition;
+use crate::rounding::RoundingPolicy;
+use crate::score_timeline::SpokenRound;
+use crate::seed::{SeedEvent, SeedUser};
+use crate::simulate::OutstandingTeam;
+use crate::suggestions::Candidate;
+use crate::tab::{SpeechResult, TeamResult, TieBreak};
+use crate::teams::LineupSlot;
+use crate::undo::StagedChange;
+use chrono::{DateTime, NaiveDate, Utc};
+use common::api_keys::{ApiClient, ApiKeyScope};
+use common::etag::rows_version;
+use common::season::in_season;
+use common::{ReadReplica, Role};
 use rust_decimal::Decimal;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{postgres::PgPoolOptions, types::Json, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 /// Parameters for updating an allocation
@@ -19,9 +44,23 @@ pub struct UpdateAllocationParams {
     pub allocated_by: Uuid,
 }
 
+/// Parameters for updating a match; unset fields are left alone
+pub struct UpdateMatchParams<'a> {
+    pub match_id: Uuid,
+    pub room_name: Option<&'a str>,
+    /// Replaces the venue when set, `Some(None)` clearing it
+    pub venue: Option<Option<Uuid>>,
+    pub motion: Option<&'a str>,
+    pub info_slide: Option<&'a str>,
+    pub status: Option<MatchStatus>,
+    pub scheduled_time: Option<DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
+    replica: Option<ReadReplica>,
+    rounding: RoundingPolicy,
 }
 
 impl Database {
@@ -31,14 +70,45 @@ impl Database {
             .connect(database_url)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Wrap an existing pool, e.g. one a test set up
+    pub fn from_pool(pool: PgPool) -> Self {
+        Self {
+            pool,
+            replica: None,
+            rounding: RoundingPolicy::default(),
+        }
+    }
+
+    /// Round aggregated scores and ranks under `rounding`
+    pub fn with_rounding(mut self, rounding: RoundingPolicy) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Serve the reads of `GET` requests from `replica` while it is reachable
+    pub fn with_replica(mut self, replica: ReadReplica) -> Self {
+        self.replica = Some(replica);
+        self
     }
 
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
 
-    pub async fn migrate(&self) -> Result<(), sqlx::Error> {
+    /// Pool for read-only queries: the replica during `GET` requests, the
+    /// primary otherwise
+    fn reader(&self) -> &PgPool {
+        common::replica::reader(&self.pool, self.replica.as_ref())
+    }
+
+    pub fn rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
         sqlx::migrate!("../migrations").run(&self.pool).await?;
         Ok(())
     }
@@ -50,10 +120,32 @@ impl Database {
     pub async fn get_user_by_id(&self, user_id: Uuid) -> Result<Option<UserInfo>, sqlx::Error> {
         sqlx::query_as::<_, UserInfo>("SELECT id, username FROM users WHERE id = $1")
             .bind(user_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    /// Whether the member is suspended by the auth service now
+    pub async fn is_user_suspended(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT user_is_suspended($1)")
+            .bind(user_id)
+            .fetch_one(&self.pool)
             .await
     }
 
+    /// Accounts whose username matches one of `usernames`, ignoring case
+    pub async fn find_users_by_usernames(
+        &self,
+        usernames: &[String],
+    ) -> Result<Vec<UserInfo>, sqlx::Error> {
+        let lowered: Vec<String> = usernames.iter().map(|u| u.to_lowercase()).collect();
+        sqlx::query_as::<_, UserInfo>(
+            "SELECT id, username FROM users WHERE LOWER(username) = ANY($1)",
+        )
+        .bind(lowered)
+        .fetch_all(&self.pool)
+        .await
+    }
+
     pub async fn is_user_admin(&self, user_id: Uuid) -> Result<bool, sqlx::Error> {
         let result: Option<(i64,)> =
             sqlx::query_as("SELECT COUNT(*) FROM admin_users WHERE user_id = $1")
@@ -64,15 +156,114 @@ impl Database {
         Ok(result.map(|(count,)| count > 0).unwrap_or(false))
     }
 
+    pub async fn has_role(&self, user_id: Uuid, role: Role) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM user_roles WHERE user_id = $1 AND role = $2)",
+        )
+        .bind(user_id)
+        .bind(role.as_str())
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Live key with this hash that may read from this service, noting
+    /// that it was used. Keys made by a suspended member stop working.
+    pub async fn find_api_key(&self, key_hash: &str) -> Result<Option<ApiClient>, sqlx::Error> {
+        let key: Option<(Uuid, String)> = sqlx::query_as(
+            r#"
+            UPDATE api_keys SET last_used_at = NOW()
+            WHERE key_hash = $1 AND revoked_at IS NULL AND $2 = ANY(scopes)
+                AND (created_by IS NULL OR NOT user_is_suspended(created_by))
+            RETURNING id, name
+            "#,
+        )
+        .bind(key_hash)
+        .bind(ApiKeyScope::Tabulation.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(key.map(|(key_id, name)| ApiClient { key_id, name }))
+    }
+
     // ========================================================================
     // Event Methods
     // ========================================================================
 
     pub async fn get_event_by_id(&self, event_id: Uuid) -> Result<Option<EventInfo>, sqlx::Error> {
-        sqlx::query_as::<_, EventInfo>("SELECT id, title, is_locked FROM events WHERE id = $1")
+        sqlx::query_as::<_, EventInfo>(
+            "SELECT id, title, is_locked, team_entry, speaks_withheld, archived_at FROM events WHERE id = $1",
+        )
+        .bind(event_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Event Archive Methods
+    // ========================================================================
+
+    /// Mark an event archived, locking it. Returns the title and timestamp, or
+    /// None if the event does not exist or already has a stored archive.
+    pub async fn begin_event_archive(
+        &self,
+        event_id: Uuid,
+        admin_id: Uuid,
+    ) -> Result<Option<(String, DateTime<Utc>)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE events
+            SET is_locked = TRUE, archived_at = NOW(), archived_by = $2, updated_at = NOW()
+            WHERE id = $1 AND (archived_at IS NULL OR archive_sha256 IS NULL)
+            RETURNING title, archived_at
+            "#,
+        )
+        .bind(event_id)
+        .bind(admin_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn finish_event_archive(
+        &self,
+        event_id: Uuid,
+        archive_key: &str,
+        archive_sha256: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE events SET archive_key = $2, archive_sha256 = $3 WHERE id = $1")
             .bind(event_id)
-            .fetch_optional(&self.pool)
-            .await
+            .bind(archive_key)
+            .bind(archive_sha256)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Undo `begin_event_archive` after the export could not be stored
+    pub async fn abandon_event_archive(&self, event_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE events SET archived_at = NULL, archived_by = NULL \
+             WHERE id = $1 AND archive_sha256 IS NULL",
+        )
+        .bind(event_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_event_archive(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Option<ArchiveRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ArchiveRecord>(
+            r#"
+            SELECT id AS event_id, archived_at, archived_by, archive_key, archive_sha256
+            FROM events
+            WHERE id = $1 AND archived_at IS NOT NULL AND archive_sha256 IS NOT NULL
+            "#,
+        )
+        .bind(event_id)
+        .fetch_optional(self.reader())
+        .await
     }
 
     // ========================================================================
@@ -83,8 +274,9 @@ impl Database {
         sqlx::query_as::<_, MatchSeries>(
             r#"
             INSERT INTO match_series (id, event_id, name, description, round_number, team_format, 
-                allow_reply_speeches, is_break_round, created_by, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                allow_reply_speeches, is_break_round, is_silent, is_training, double_entry, created_by,
+                created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#,
         )
@@ -96,6 +288,9 @@ impl Database {
         .bind(series.team_format)
         .bind(series.allow_reply_speeches)
         .bind(series.is_break_round)
+        .bind(series.is_silent)
+        .bind(series.is_training)
+        .bind(series.double_entry)
         .bind(series.created_by)
         .bind(series.created_at)
         .bind(series.updated_at)
@@ -103,13 +298,44 @@ impl Database {
         .await
     }
 
+    /// Copy every series definition of one event into another, without
+    /// matches. All or none are copied.
+    pub async fn clone_series_structure(
+        &self,
+        from_event_id: Uuid,
+        to_event_id: Uuid,
+        created_by: Uuid,
+    ) -> Result<Vec<MatchSeries>, sqlx::Error> {
+        let mut series = sqlx::query_as::<_, MatchSeries>(
+            r#"
+            INSERT INTO match_series (id, event_id, name, description, round_number, team_format,
+                allow_reply_speeches, is_break_round, is_silent, is_training, double_entry, created_by,
+                created_at, updated_at)
+            SELECT gen_random_uuid(), $2, name, description, round_number, team_format,
+                allow_reply_speeches, is_break_round, is_silent, is_training, double_entry, $3,
+                NOW(), NOW()
+            FROM match_series
+            WHERE event_id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(from_event_id)
+        .bind(to_event_id)
+        .bind(created_by)
+        .fetch_all(&self.pool)
+        .await?;
+
+        series.sort_by(|a, b| (a.round_number, &a.name).cmp(&(b.round_number, &b.name)));
+        Ok(series)
+    }
+
     pub async fn get_series_by_id(
         &self,
         series_id: Uuid,
     ) -> Result<Option<MatchSeries>, sqlx::Error> {
         sqlx::query_as::<_, MatchSeries>("SELECT * FROM match_series WHERE id = $1")
             .bind(series_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.reader())
             .await
     }
 
@@ -123,7 +349,7 @@ impl Database {
 
         let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM match_series WHERE event_id = $1")
             .bind(event_id)
-            .fetch_one(&self.pool)
+            .fetch_one(self.reader())
             .await?;
 
         let series = sqlx::query_as::<_, MatchSeries>(
@@ -137,12 +363,22 @@ impl Database {
         .bind(event_id)
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok((series, total.0))
     }
 
+    /// Every series of an event, in round order
+    pub async fn list_event_series(&self, event_id: Uuid) -> Result<Vec<MatchSeries>, sqlx::Error> {
+        sqlx::query_as::<_, MatchSeries>(
+            "SELECT * FROM match_series WHERE event_id = $1 ORDER BY round_number, created_at",
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
     pub async fn update_series(
         &self,
         series_id: Uuid,
@@ -173,6 +409,68 @@ impl Database {
         .await
     }
 
+    /// Silence a series or lift its silence
+    pub async fn set_series_silent(
+        &self,
+        series_id: Uuid,
+        is_silent: bool,
+    ) -> Result<Option<MatchSeries>, sqlx::Error> {
+        sqlx::query_as::<_, MatchSeries>(
+            "UPDATE match_series SET is_silent = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(series_id)
+        .bind(is_silent)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Require double entry of a series' results, or stop requiring it
+    pub async fn set_series_double_entry(
+        &self,
+        series_id: Uuid,
+        double_entry: bool,
+    ) -> Result<Option<MatchSeries>, sqlx::Error> {
+        sqlx::query_as::<_, MatchSeries>(
+            "UPDATE match_series SET double_entry = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(series_id)
+        .bind(double_entry)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Mark a series as training, or as a real round
+    pub async fn set_series_training(
+        &self,
+        series_id: Uuid,
+        is_training: bool,
+    ) -> Result<Option<MatchSeries>, sqlx::Error> {
+        sqlx::query_as::<_, MatchSeries>(
+            "UPDATE match_series SET is_training = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(series_id)
+        .bind(is_training)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Training series, optionally at one event, latest first
+    pub async fn list_training_series(
+        &self,
+        event_id: Option<Uuid>,
+    ) -> Result<Vec<MatchSeries>, sqlx::Error> {
+        sqlx::query_as::<_, MatchSeries>(
+            r#"
+            SELECT * FROM match_series
+            WHERE is_training AND ($1::uuid IS NULL OR event_id = $1)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
     pub async fn delete_series(&self, series_id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query("DELETE FROM match_series WHERE id = $1")
             .bind(series_id)
@@ -184,7 +482,7 @@ impl Database {
     pub async fn get_series_match_count(&self, series_id: Uuid) -> Result<i64, sqlx::Error> {
         let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM matches WHERE series_id = $1")
             .bind(series_id)
-            .fetch_one(&self.pool)
+            .fetch_one(self.reader())
             .await?;
         Ok(result.0)
     }
@@ -197,8 +495,9 @@ impl Database {
         sqlx::query_as::<_, Match>(
             r#"
             INSERT INTO matches (id, series_id, room_name, motion, info_slide, status, 
-                scheduled_time, scores_released, rankings_released, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                scheduled_time, scores_released, rankings_released, created_at, updated_at,
+                venue_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
@@ -213,6 +512,7 @@ impl Database {
         .bind(match_record.rankings_released)
         .bind(match_record.created_at)
         .bind(match_record.updated_at)
+        .bind(match_record.venue_id)
         .fetch_one(&self.pool)
         .await
     }
@@ -220,10 +520,20 @@ impl Database {
     pub async fn get_match_by_id(&self, match_id: Uuid) -> Result<Option<Match>, sqlx::Error> {
         sqlx::query_as::<_, Match>("SELECT * FROM matches WHERE id = $1")
             .bind(match_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.reader())
             .await
     }
 
+    /// Every match of a series, by room
+    pub async fn list_series_matches(&self, series_id: Uuid) -> Result<Vec<Match>, sqlx::Error> {
+        sqlx::query_as::<_, Match>(
+            "SELECT * FROM matches WHERE series_id = $1 ORDER BY room_name, created_at",
+        )
+        .bind(series_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
     pub async fn list_matches_by_series(
         &self,
         series_id: Uuid,
@@ -234,7 +544,7 @@ impl Database {
 
         let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM matches WHERE series_id = $1")
             .bind(series_id)
-            .fetch_one(&self.pool)
+            .fetch_one(self.reader())
             .await?;
 
         let matches = sqlx::query_as::<_, Match>(
@@ -248,7 +558,7 @@ impl Database {
         .bind(series_id)
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok((matches, total.0))
@@ -273,7 +583,7 @@ impl Database {
             )
             .bind(event_id)
             .bind(status)
-            .fetch_one(&self.pool)
+            .fetch_one(self.reader())
             .await?;
 
             let matches = sqlx::query_as::<_, Match>(
@@ -289,7 +599,7 @@ impl Database {
             .bind(status)
             .bind(per_page)
             .bind(offset)
-            .fetch_all(&self.pool)
+            .fetch_all(self.reader())
             .await?;
 
             (total, matches)
@@ -302,7 +612,7 @@ impl Database {
                 "#,
             )
             .bind(event_id)
-            .fetch_one(&self.pool)
+            .fetch_one(self.reader())
             .await?;
 
             let matches = sqlx::query_as::<_, Match>(
@@ -317,7 +627,7 @@ impl Database {
             .bind(event_id)
             .bind(per_page)
             .bind(offset)
-            .fetch_all(&self.pool)
+            .fetch_all(self.reader())
             .await?;
 
             (total, matches)
@@ -326,15 +636,7 @@ impl Database {
         Ok((matches, total.0))
     }
 
-    pub async fn update_match(
-        &self,
-        match_id: Uuid,
-        room_name: Option<&str>,
-        motion: Option<&str>,
-        info_slide: Option<&str>,
-        status: Option<MatchStatus>,
-        scheduled_time: Option<chrono::DateTime<Utc>>,
-    ) -> Result<Match, sqlx::Error> {
+    pub async fn update_match(&self, params: UpdateMatchParams<'_>) -> Result<Match, sqlx::Error> {
         sqlx::query_as::<_, Match>(
             r#"
             UPDATE matches SET
@@ -343,22 +645,51 @@ impl Database {
                 info_slide = COALESCE($4, info_slide),
                 status = COALESCE($5, status),
                 scheduled_time = COALESCE($6, scheduled_time),
-                updated_at = $7
+                updated_at = $7,
+                venue_id = CASE WHEN $8 THEN $9 ELSE venue_id END
             WHERE id = $1
             RETURNING *
             "#,
         )
-        .bind(match_id)
-        .bind(room_name)
-        .bind(motion)
-        .bind(info_slide)
-        .bind(status)
-        .bind(scheduled_time)
+        .bind(params.match_id)
+        .bind(params.room_name)
+        .bind(params.motion)
+        .bind(params.info_slide)
+        .bind(params.status)
+        .bind(params.scheduled_time)
         .bind(Utc::now())
+        .bind(params.venue.is_some())
+        .bind(params.venue.flatten())
         .fetch_one(&self.pool)
         .await
     }
 
+    /// A live match of the series already holding the venue at that time
+    pub async fn find_venue_conflict(
+        &self,
+        series_id: Uuid,
+        venue_id: Uuid,
+        scheduled_time: Option<DateTime<Utc>>,
+        exclude_match_id: Option<Uuid>,
+    ) -> Result<Option<Match>, sqlx::Error> {
+        sqlx::query_as::<_, Match>(
+            r#"
+            SELECT * FROM matches
+            WHERE series_id = $1 AND venue_id = $2
+              AND scheduled_time IS NOT DISTINCT FROM $3
+              AND status <> 'cancelled'
+              AND id IS DISTINCT FROM $4
+            LIMIT 1
+            "#,
+        )
+        .bind(series_id)
+        .bind(venue_id)
+        .bind(scheduled_time)
+        .bind(exclude_match_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
     pub async fn update_match_release(
         &self,
         match_id: Uuid,
@@ -399,8 +730,9 @@ impl Database {
         sqlx::query_as::<_, MatchTeam>(
             r#"
             INSERT INTO match_teams (id, match_id, two_team_position, four_team_position, 
-                team_name, institution, final_rank, total_speaker_points, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                team_name, institution, final_rank, total_speaker_points, registered_team_id,
+                institution_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
@@ -412,6 +744,8 @@ impl Database {
         .bind(&team.institution)
         .bind(team.final_rank)
         .bind(team.total_speaker_points)
+        .bind(team.registered_team_id)
+        .bind(team.institution_id)
         .bind(team.created_at)
         .bind(team.updated_at)
         .fetch_one(&self.pool)
@@ -421,7 +755,7 @@ impl Database {
     pub async fn get_team_by_id(&self, team_id: Uuid) -> Result<Option<MatchTeam>, sqlx::Error> {
         sqlx::query_as::<_, MatchTeam>("SELECT * FROM match_teams WHERE id = $1")
             .bind(team_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.reader())
             .await
     }
 
@@ -434,7 +768,7 @@ impl Database {
             "#,
         )
         .bind(match_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
@@ -449,6 +783,8 @@ impl Database {
             UPDATE match_teams SET
                 team_name = COALESCE($2, team_name),
                 institution = COALESCE($3, institution),
+                -- A typed-in institution stops the team following its speakers'
+                institution_id = CASE WHEN $3::text IS NULL THEN institution_id END,
                 updated_at = $4
             WHERE id = $1
             RETURNING *
@@ -516,6 +852,8 @@ impl Database {
                         institution: None,
                         final_rank: None,
                         total_speaker_points: None,
+                        registered_team_id: None,
+                        institution_id: None,
                         created_at: now,
                         updated_at: now,
                     };
@@ -540,6 +878,8 @@ impl Database {
                         institution: None,
                         final_rank: None,
                         total_speaker_points: None,
+                        registered_team_id: None,
+                        institution_id: None,
                         created_at: now,
                         updated_at: now,
                     };
@@ -593,7 +933,7 @@ impl Database {
     ) -> Result<Option<Allocation>, sqlx::Error> {
         sqlx::query_as::<_, Allocation>("SELECT * FROM allocations WHERE id = $1")
             .bind(allocation_id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.reader())
             .await
     }
 
@@ -607,7 +947,7 @@ impl Database {
         )
         .bind(match_id)
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await
     }
 
@@ -622,7 +962,7 @@ impl Database {
         )
         .bind(match_id)
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await
     }
 
@@ -636,7 +976,8 @@ impl Database {
                 COALESCE(u.username, a.guest_name, 'Unknown') as username, 
                 a.role, a.team_id,
                 a.two_team_speaker_role, a.four_team_speaker_role, a.is_chair,
-                a.allocated_at, a.allocated_by, a.was_checked_in
+                a.allocated_at, a.allocated_by, a.was_checked_in,
+                user_is_suspended(a.user_id) as needs_replacement
             FROM allocations a
             LEFT JOIN users u ON a.user_id = u.id
             WHERE a.match_id = $1
@@ -644,7 +985,7 @@ impl Database {
             "#,
         )
         .bind(match_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
@@ -658,7 +999,8 @@ impl Database {
                 COALESCE(u.username, a.guest_name, 'Unknown') as username, 
                 a.role, a.team_id,
                 a.two_team_speaker_role, a.four_team_speaker_role, a.is_chair,
-                a.allocated_at, a.allocated_by, a.was_checked_in
+                a.allocated_at, a.allocated_by, a.was_checked_in,
+                user_is_suspended(a.user_id) as needs_replacement
             FROM allocations a
             LEFT JOIN users u ON a.user_id = u.id
             WHERE a.team_id = $1
@@ -666,7 +1008,7 @@ impl Database {
             "#,
         )
         .bind(team_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
@@ -724,7 +1066,65 @@ impl Database {
         )
         .bind(series_id)
         .bind(user_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// The allocation of a guest, by name, in the series
+    pub async fn get_guest_allocation_in_series(
+        &self,
+        series_id: Uuid,
+        guest_name: &str,
+    ) -> Result<Option<Allocation>, sqlx::Error> {
+        sqlx::query_as::<_, Allocation>(
+            r#"
+            SELECT a.* FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            WHERE m.series_id = $1 AND a.user_id IS NULL AND a.guest_name = $2
+            LIMIT 1
+            "#,
+        )
+        .bind(series_id)
+        .bind(guest_name)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// The user's other allocations in the event that clash with a match:
+    /// any in another room of the same round, and any whose scheduled time
+    /// falls within `minutes` of `scheduled_time`. Cancelled matches and
+    /// other roles in the same match don't count.
+    pub async fn list_schedule_conflicts(
+        &self,
+        user_id: Uuid,
+        match_record: &Match,
+        event_id: Uuid,
+        minutes: i32,
+    ) -> Result<Vec<ScheduleConflict>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduleConflict>(
+            r#"
+            SELECT a.id AS allocation_id, a.role, m.id AS match_id, m.room_name,
+                   m.scheduled_time, s.id AS series_id, s.name AS series_name
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            WHERE a.user_id = $1 AND s.event_id = $2 AND m.id <> $3
+              AND m.status <> 'cancelled'
+              AND (
+                  m.series_id = $4
+                  OR (m.scheduled_time > $5 - make_interval(mins => $6)
+                      AND m.scheduled_time < $5 + make_interval(mins => $6))
+              )
+            ORDER BY m.scheduled_time NULLS LAST, s.name, m.room_name
+            "#,
+        )
+        .bind(user_id)
+        .bind(event_id)
+        .bind(match_record.id)
+        .bind(match_record.series_id)
+        .bind(match_record.scheduled_time)
+        .bind(minutes)
+        .fetch_all(self.reader())
         .await
     }
 
@@ -761,6 +1161,126 @@ impl Database {
         .await
     }
 
+    /// Keep allocations as they were before a delete or swap, with the
+    /// history to write once the change can no longer be undone
+    pub async fn stage_allocation_change(
+        &self,
+        action: &str,
+        snapshot: &[Allocation],
+        history: &[AllocationHistory],
+        staged_by: Uuid,
+        staged_at: DateTime<Utc>,
+        finalize_at: DateTime<Utc>,
+    ) -> Result<StagedChange, sqlx::Error> {
+        sqlx::query_as::<_, StagedChange>(
+            r#"
+            INSERT INTO staged_allocation_changes
+                (id, action, snapshot, history, staged_by, staged_at, finalize_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(action)
+        .bind(Json(snapshot))
+        .bind(Json(history))
+        .bind(staged_by)
+        .bind(staged_at)
+        .bind(finalize_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// A change that can still be undone
+    pub async fn get_staged_change(
+        &self,
+        change_id: Uuid,
+    ) -> Result<Option<StagedChange>, sqlx::Error> {
+        sqlx::query_as::<_, StagedChange>(
+            "SELECT * FROM staged_allocation_changes WHERE id = $1 AND finalize_at > NOW()",
+        )
+        .bind(change_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Undo a staged change, putting its allocations back as they were.
+    /// `None` when the change is already final or undone.
+    pub async fn undo_staged_change(
+        &self,
+        change_id: Uuid,
+    ) -> Result<Option<StagedChange>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let change = sqlx::query_as::<_, StagedChange>(
+            r#"
+            DELETE FROM staged_allocation_changes
+            WHERE id = $1 AND finalize_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(change_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(change) = change else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO allocations (id, match_id, user_id, guest_name, role, team_id,
+                two_team_speaker_role, four_team_speaker_role, is_chair, allocated_at,
+                allocated_by, was_checked_in, created_at, updated_at)
+            SELECT id, match_id, user_id, guest_name, role, team_id, two_team_speaker_role,
+                four_team_speaker_role, is_chair, allocated_at, allocated_by, was_checked_in,
+                created_at, NOW()
+            FROM jsonb_populate_recordset(NULL::allocations, $1)
+            ON CONFLICT (id) DO UPDATE SET
+                role = EXCLUDED.role,
+                team_id = EXCLUDED.team_id,
+                two_team_speaker_role = EXCLUDED.two_team_speaker_role,
+                four_team_speaker_role = EXCLUDED.four_team_speaker_role,
+                is_chair = EXCLUDED.is_chair,
+                allocated_at = EXCLUDED.allocated_at,
+                allocated_by = EXCLUDED.allocated_by,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(&change.snapshot)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(change))
+    }
+
+    /// Make every change whose undo window has closed final, writing its
+    /// history. Deleted allocations are recorded without their id, as
+    /// history written before a delete would have been.
+    pub async fn finalize_due_allocation_changes(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            WITH due AS (
+                DELETE FROM staged_allocation_changes
+                WHERE finalize_at <= NOW()
+                RETURNING history
+            )
+            INSERT INTO allocation_history (id, allocation_id, match_id, user_id, guest_name,
+                action, previous_role, new_role, previous_team_id, new_team_id, changed_by,
+                changed_at, notes)
+            SELECT h.id,
+                CASE WHEN EXISTS (SELECT 1 FROM allocations a WHERE a.id = h.allocation_id)
+                    THEN h.allocation_id END,
+                h.match_id, h.user_id, h.guest_name, h.action, h.previous_role, h.new_role,
+                h.previous_team_id, h.new_team_id, h.changed_by, h.changed_at, h.notes
+            FROM due, jsonb_populate_recordset(NULL::allocation_history, due.history) h
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn list_allocation_history(
         &self,
         match_id: Uuid,
@@ -772,7 +1292,7 @@ impl Database {
         let total: (i64,) =
             sqlx::query_as("SELECT COUNT(*) FROM allocation_history WHERE match_id = $1")
                 .bind(match_id)
-                .fetch_one(&self.pool)
+                .fetch_one(self.reader())
                 .await?;
 
         let history = sqlx::query_as::<_, AllocationHistory>(
@@ -786,7 +1306,7 @@ impl Database {
         .bind(match_id)
         .bind(per_page)
         .bind(offset)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await?;
 
         Ok((history, total.0))
@@ -800,8 +1320,8 @@ impl Database {
         sqlx::query_as::<_, Ballot>(
             r#"
             INSERT INTO ballots (id, match_id, adjudicator_id, is_voting, is_submitted, 
-                submitted_at, notes, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                submitted_at, notes, low_point_win, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING *
             "#,
         )
@@ -812,33 +1332,149 @@ impl Database {
         .bind(ballot.is_submitted)
         .bind(ballot.submitted_at)
         .bind(&ballot.notes)
+        .bind(ballot.low_point_win)
         .bind(ballot.created_at)
         .bind(ballot.updated_at)
         .fetch_one(&self.pool)
         .await
     }
 
-    pub async fn get_ballot_by_id(&self, ballot_id: Uuid) -> Result<Option<Ballot>, sqlx::Error> {
-        sqlx::query_as::<_, Ballot>("SELECT * FROM ballots WHERE id = $1")
-            .bind(ballot_id)
-            .fetch_optional(&self.pool)
-            .await
-    }
-
-    pub async fn get_ballot_by_adjudicator_match(
-        &self,
-        match_id: Uuid,
-        adjudicator_id: Uuid,
-    ) -> Result<Option<Ballot>, sqlx::Error> {
+    /// The adjudicator's ballot for the match, creating it from `ballot` if
+    /// there is none yet. Safe to race: whichever insert loses gets the
+    /// winner's ballot back.
+    pub async fn get_or_create_ballot(&self, ballot: &Ballot) -> Result<Ballot, sqlx::Error> {
+        sqlx::query_as::<_, Ballot>(
+            r#"
+            INSERT INTO ballots (id, match_id, adjudicator_id, is_voting, is_submitted,
+                submitted_at, notes, low_point_win, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT ON CONSTRAINT unique_adjudicator_match
+                DO UPDATE SET match_id = ballots.match_id
+            RETURNING *
+            "#,
+        )
+        .bind(ballot.id)
+        .bind(ballot.match_id)
+        .bind(ballot.adjudicator_id)
+        .bind(ballot.is_voting)
+        .bind(ballot.is_submitted)
+        .bind(ballot.submitted_at)
+        .bind(&ballot.notes)
+        .bind(ballot.low_point_win)
+        .bind(ballot.created_at)
+        .bind(ballot.updated_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_ballot_by_id(&self, ballot_id: Uuid) -> Result<Option<Ballot>, sqlx::Error> {
+        sqlx::query_as::<_, Ballot>("SELECT * FROM ballots WHERE id = $1")
+            .bind(ballot_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    pub async fn get_ballot_by_adjudicator_match(
+        &self,
+        match_id: Uuid,
+        adjudicator_id: Uuid,
+    ) -> Result<Option<Ballot>, sqlx::Error> {
         sqlx::query_as::<_, Ballot>(
             "SELECT * FROM ballots WHERE match_id = $1 AND adjudicator_id = $2",
         )
         .bind(match_id)
         .bind(adjudicator_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Ballot counts for the event's rooms still waiting on a ballot, and
+    /// for `match_id` whether or not it is
+    pub async fn list_ballot_progress(
+        &self,
+        event_id: Uuid,
+        match_id: Option<Uuid>,
+    ) -> Result<Vec<RoomBallotProgress>, sqlx::Error> {
+        sqlx::query_as::<_, RoomBallotProgress>(
+            r#"
+            SELECT m.id AS match_id, s.name AS series_name, m.room_name,
+                COUNT(*) FILTER (WHERE b.is_submitted) AS submitted,
+                COUNT(*) FILTER (WHERE NOT b.is_submitted) AS outstanding
+            FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            WHERE s.event_id = $1 AND m.status <> 'cancelled'
+            GROUP BY m.id, s.name, s.round_number, m.room_name
+            HAVING COUNT(*) FILTER (WHERE NOT b.is_submitted) > 0 OR m.id = $2
+            ORDER BY s.round_number NULLS LAST, m.room_name
+            "#,
+        )
+        .bind(event_id)
+        .bind(match_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Unsubmitted voting ballots in the event's matches that are in
+    /// progress, longest-running rooms first
+    pub async fn list_outstanding_ballots(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<OutstandingBallot>, sqlx::Error> {
+        sqlx::query_as::<_, OutstandingBallot>(
+            r#"
+            SELECT m.id AS match_id, s.name AS series_name, m.room_name, m.scheduled_time,
+                u.id AS adjudicator_id, u.username, u.email, u.phone_number,
+                COALESCE(a.is_chair, FALSE) AS is_chair
+            FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            JOIN users u ON b.adjudicator_id = u.id
+            LEFT JOIN allocations a ON a.match_id = b.match_id AND a.user_id = b.adjudicator_id
+                AND a.role = 'voting_adjudicator'
+            WHERE s.event_id = $1 AND m.status = 'in_progress'
+              AND b.is_voting AND NOT b.is_submitted
+            ORDER BY m.scheduled_time NULLS LAST, s.round_number, m.room_name, m.id,
+                a.is_chair DESC NULLS LAST, u.username
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
         .await
     }
 
+    /// Ballot counts for every event with a draw, most recent first
+    pub async fn ballot_stats(&self) -> Result<BallotStats, sqlx::Error> {
+        let ballots_per_event = sqlx::query_as::<_, EventBallotCount>(
+            r#"
+            SELECT e.id AS event_id, e.title, e.event_date,
+                COUNT(DISTINCT m.id) AS matches,
+                COUNT(b.id) AS ballots,
+                COUNT(b.id) FILTER (WHERE b.is_submitted) AS submitted
+            FROM events e
+            JOIN match_series s ON s.event_id = e.id
+            LEFT JOIN matches m ON m.series_id = s.id
+            LEFT JOIN ballots b ON b.match_id = m.id
+            GROUP BY e.id
+            ORDER BY e.event_date DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total_ballots: i64 = ballots_per_event.iter().map(|e| e.ballots).sum();
+        let submitted_ballots = ballots_per_event.iter().map(|e| e.submitted).sum();
+        let average_ballots_per_event = (!ballots_per_event.is_empty())
+            .then(|| total_ballots as f64 / ballots_per_event.len() as f64);
+
+        Ok(BallotStats {
+            total_ballots,
+            submitted_ballots,
+            average_ballots_per_event,
+            ballots_per_event,
+        })
+    }
+
     pub async fn list_ballots_by_match(&self, match_id: Uuid) -> Result<Vec<Ballot>, sqlx::Error> {
         sqlx::query_as::<_, Ballot>(
             r#"
@@ -848,14 +1484,160 @@ impl Database {
             "#,
         )
         .bind(match_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
+    /// Replace a ballot's scores and rankings and mark it submitted, in one
+    /// transaction. The ballot row is locked first, so concurrent
+    /// submissions of the same ballot apply one after the other, and rows
+    /// are upserted on their unique keys rather than deleted and re-added.
+    /// A `ballot_confirmed` event is recorded in the same transaction.
+    pub async fn save_ballot_results(
+        &self,
+        ballot_id: Uuid,
+        scores: &[SpeakerScore],
+        rankings: &[TeamRanking],
+        notes: Option<&str>,
+        low_point_win: bool,
+    ) -> Result<Ballot, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("SELECT id FROM ballots WHERE id = $1 FOR UPDATE")
+            .bind(ballot_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let allocation_ids: Vec<Uuid> = scores.iter().map(|s| s.allocation_id).collect();
+        sqlx::query("DELETE FROM speaker_scores WHERE ballot_id = $1 AND allocation_id <> ALL($2)")
+            .bind(ballot_id)
+            .bind(&allocation_ids)
+            .execute(&mut *tx)
+            .await?;
+        for score in scores {
+            sqlx::query(
+                r#"
+                INSERT INTO speaker_scores (id, ballot_id, allocation_id, score, feedback,
+                    created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT ON CONSTRAINT unique_speaker_ballot DO UPDATE SET
+                    score = EXCLUDED.score,
+                    feedback = EXCLUDED.feedback,
+                    updated_at = EXCLUDED.updated_at
+                "#,
+            )
+            .bind(score.id)
+            .bind(ballot_id)
+            .bind(score.allocation_id)
+            .bind(score.score)
+            .bind(&score.feedback)
+            .bind(score.created_at)
+            .bind(score.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let team_ids: Vec<Uuid> = rankings.iter().map(|r| r.team_id).collect();
+        sqlx::query("DELETE FROM team_rankings WHERE ballot_id = $1 AND team_id <> ALL($2)")
+            .bind(ballot_id)
+            .bind(&team_ids)
+            .execute(&mut *tx)
+            .await?;
+        for ranking in rankings {
+            sqlx::query(
+                r#"
+                INSERT INTO team_rankings (id, ballot_id, team_id, rank, is_winner, total_speaks,
+                    margin, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT ON CONSTRAINT unique_team_ballot DO UPDATE SET
+                    rank = EXCLUDED.rank,
+                    is_winner = EXCLUDED.is_winner,
+                    total_speaks = EXCLUDED.total_speaks,
+                    margin = EXCLUDED.margin,
+                    updated_at = EXCLUDED.updated_at
+                "#,
+            )
+            .bind(ranking.id)
+            .bind(ballot_id)
+            .bind(ranking.team_id)
+            .bind(ranking.rank)
+            .bind(ranking.is_winner)
+            .bind(ranking.total_speaks)
+            .bind(ranking.margin)
+            .bind(ranking.created_at)
+            .bind(ranking.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let ballot = sqlx::query_as::<_, Ballot>(
+            r#"
+            UPDATE ballots SET
+                is_submitted = true,
+                submitted_at = NOW(),
+                notes = COALESCE($2, notes),
+                low_point_win = $3,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(ballot_id)
+        .bind(notes)
+        .bind(low_point_win)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let payload: serde_json::Value = sqlx::query_scalar(
+            r#"
+            SELECT jsonb_build_object(
+                'ballot_id', b.id,
+                'match_id', m.id,
+                'series_id', s.id,
+                'series_name', s.name,
+                'event_id', s.event_id,
+                'event_title', e.title,
+                'room_name', m.room_name,
+                'adjudicator_id', b.adjudicator_id,
+                'adjudicator_username', u.username,
+                'is_voting', b.is_voting,
+                'low_point_win', b.low_point_win,
+                'submitted_at', b.submitted_at,
+                'rankings', COALESCE((
+                    SELECT jsonb_agg(jsonb_build_object(
+                        'team_id', r.team_id,
+                        'team_name', t.team_name,
+                        'rank', r.rank,
+                        'total_speaks', r.total_speaks
+                    ) ORDER BY r.rank)
+                    FROM team_rankings r
+                    JOIN match_teams t ON r.team_id = t.id
+                    WHERE r.ballot_id = b.id
+                ), '[]'::jsonb)
+            )
+            FROM ballots b
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            LEFT JOIN events e ON s.event_id = e.id
+            LEFT JOIN users u ON b.adjudicator_id = u.id
+            WHERE b.id = $1
+            "#,
+        )
+        .bind(ballot_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        common::outbox::enqueue(&mut tx, outbox::SERVICE, outbox::BALLOT_CONFIRMED, &payload)
+            .await?;
+
+        tx.commit().await?;
+        Ok(ballot)
+    }
+
     pub async fn submit_ballot(
         &self,
         ballot_id: Uuid,
         notes: Option<&str>,
+        low_point_win: bool,
     ) -> Result<Ballot, sqlx::Error> {
         sqlx::query_as::<_, Ballot>(
             r#"
@@ -863,6 +1645,7 @@ impl Database {
                 is_submitted = true,
                 submitted_at = $2,
                 notes = COALESCE($3, notes),
+                low_point_win = $4,
                 updated_at = $2
             WHERE id = $1
             RETURNING *
@@ -871,6 +1654,7 @@ impl Database {
         .bind(ballot_id)
         .bind(Utc::now())
         .bind(notes)
+        .bind(low_point_win)
         .fetch_one(&self.pool)
         .await
     }
@@ -907,19 +1691,93 @@ impl Database {
     ) -> Result<Vec<SpeakerScore>, sqlx::Error> {
         sqlx::query_as::<_, SpeakerScore>("SELECT * FROM speaker_scores WHERE ballot_id = $1")
             .bind(ballot_id)
-            .fetch_all(&self.pool)
+            .fetch_all(self.reader())
             .await
     }
 
-    pub async fn delete_speaker_scores_by_ballot(
+    // ========================================================================
+    // Speaker Note Methods
+    // ========================================================================
+
+    /// Replace the notes on a ballot with `notes`, one per speaker
+    pub async fn replace_speaker_notes(
         &self,
         ballot_id: Uuid,
-    ) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM speaker_scores WHERE ballot_id = $1")
+        notes: &[SpeakerNoteInput],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let allocation_ids: Vec<Uuid> = notes.iter().map(|n| n.allocation_id).collect();
+        sqlx::query("DELETE FROM speaker_notes WHERE ballot_id = $1 AND allocation_id <> ALL($2)")
             .bind(ballot_id)
-            .execute(&self.pool)
+            .bind(&allocation_ids)
+            .execute(&mut *tx)
             .await?;
-        Ok(result.rows_affected())
+        for note in notes {
+            sqlx::query(
+                r#"
+                INSERT INTO speaker_notes (ballot_id, allocation_id, strengths, improvements,
+                    adjcore_only)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (ballot_id, allocation_id) DO UPDATE SET
+                    strengths = EXCLUDED.strengths,
+                    improvements = EXCLUDED.improvements,
+                    adjcore_only = EXCLUDED.adjcore_only,
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(ballot_id)
+            .bind(note.allocation_id)
+            .bind(&note.strengths)
+            .bind(&note.improvements)
+            .bind(note.adjcore_only)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await
+    }
+
+    pub async fn list_speaker_notes_by_ballot(
+        &self,
+        ballot_id: Uuid,
+    ) -> Result<Vec<SpeakerNote>, sqlx::Error> {
+        sqlx::query_as::<_, SpeakerNote>("SELECT * FROM speaker_notes WHERE ballot_id = $1")
+            .bind(ballot_id)
+            .fetch_all(self.reader())
+            .await
+    }
+
+    /// Notes left for a member on submitted ballots, newest first. With
+    /// `released_only`, only notes the member may read: from matches whose
+    /// results are out (and not hidden by a silent round), and not kept
+    /// for the adjudication core.
+    pub async fn list_received_speaker_notes(
+        &self,
+        user_id: Uuid,
+        released_only: bool,
+    ) -> Result<Vec<ReceivedSpeakerNote>, sqlx::Error> {
+        sqlx::query_as::<_, ReceivedSpeakerNote>(
+            r#"
+            SELECT m.id AS match_id, e.id AS event_id, e.title AS event_title,
+                ms.name AS series_name, m.room_name, u.username AS adjudicator_username,
+                sn.strengths, sn.improvements, sn.adjcore_only, sn.updated_at
+            FROM speaker_notes sn
+            JOIN allocations a ON a.id = sn.allocation_id
+            JOIN ballots b ON b.id = sn.ballot_id
+            JOIN matches m ON m.id = b.match_id
+            JOIN match_series ms ON ms.id = m.series_id
+            JOIN events e ON e.id = ms.event_id
+            JOIN users u ON u.id = b.adjudicator_id
+            WHERE a.user_id = $1 AND b.is_submitted
+              AND (NOT $2 OR (m.rankings_released AND NOT ms.is_silent AND NOT sn.adjcore_only))
+            ORDER BY sn.updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(released_only)
+        .fetch_all(self.reader())
+        .await
     }
 
     /// Get average score for a speaker allocation from all submitted voting ballots
@@ -938,49 +1796,12 @@ impl Database {
             "#,
         )
         .bind(allocation_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.reader())
         .await?;
 
-        Ok(result.and_then(|(avg,)| avg))
-    }
-
-    pub async fn get_average_speaker_score(
-        &self,
-        user_id: Uuid,
-        event_id: Option<Uuid>,
-    ) -> Result<Option<Decimal>, sqlx::Error> {
-        let result: Option<(Option<Decimal>,)> = if let Some(event_id) = event_id {
-            sqlx::query_as(
-                r#"
-                SELECT AVG(ss.score) as avg_score
-                FROM speaker_scores ss
-                JOIN ballots b ON ss.ballot_id = b.id
-                JOIN allocations a ON ss.allocation_id = a.id
-                JOIN matches m ON a.match_id = m.id
-                JOIN match_series ms ON m.series_id = ms.id
-                WHERE a.user_id = $1 AND ms.event_id = $2 AND b.is_submitted = true
-                "#,
-            )
-            .bind(user_id)
-            .bind(event_id)
-            .fetch_optional(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as(
-                r#"
-                SELECT AVG(ss.score) as avg_score
-                FROM speaker_scores ss
-                JOIN ballots b ON ss.ballot_id = b.id
-                JOIN allocations a ON ss.allocation_id = a.id
-                WHERE a.user_id = $1 AND b.is_submitted = true
-                "#,
-            )
-            .bind(user_id)
-            .fetch_optional(&self.pool)
-            .await?
-        };
-
-        Ok(result.and_then(|(avg,)| avg))
+        Ok(result
+            .and_then(|(avg,)| avg)
+            .map(|avg| self.rounding.round(avg)))
     }
 
     // ========================================================================
@@ -993,8 +1814,9 @@ impl Database {
     ) -> Result<TeamRanking, sqlx::Error> {
         sqlx::query_as::<_, TeamRanking>(
             r#"
-            INSERT INTO team_rankings (id, ballot_id, team_id, rank, is_winner, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO team_rankings (id, ballot_id, team_id, rank, is_winner, total_speaks,
+                margin, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *
             "#,
         )
@@ -1003,6 +1825,8 @@ impl Database {
         .bind(ranking.team_id)
         .bind(ranking.rank)
         .bind(ranking.is_winner)
+        .bind(ranking.total_speaks)
+        .bind(ranking.margin)
         .bind(ranking.created_at)
         .bind(ranking.updated_at)
         .fetch_one(&self.pool)
@@ -1017,47 +1841,187 @@ impl Database {
             "SELECT * FROM team_rankings WHERE ballot_id = $1 ORDER BY rank ASC",
         )
         .bind(ballot_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
-    pub async fn delete_team_rankings_by_ballot(
-        &self,
-        ballot_id: Uuid,
-    ) -> Result<u64, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM team_rankings WHERE ballot_id = $1")
-            .bind(ballot_id)
-            .execute(&self.pool)
-            .await?;
-        Ok(result.rows_affected())
-    }
-
     /// Get aggregated team rankings from all submitted voting ballots for a match
-    /// Returns (team_id, average_rank) tuples sorted by average rank ascending
+    /// Returns (team_id, average_rank) tuples sorted by rounded average rank
+    /// ascending, teams level on it by their exact average
     pub async fn get_match_team_rankings(
         &self,
         match_id: Uuid,
-    ) -> Result<Vec<(Uuid, f64)>, sqlx::Error> {
-        let results: Vec<(Uuid, Option<f64>)> = sqlx::query_as(
+    ) -> Result<Vec<(Uuid, Decimal)>, sqlx::Error> {
+        let results: Vec<(Uuid, Option<Decimal>)> = sqlx::query_as(
             r#"
-            SELECT tr.team_id, AVG(tr.rank::float8) as avg_rank
+            SELECT tr.team_id, AVG(tr.rank::numeric) as avg_rank
             FROM team_rankings tr
             JOIN ballots b ON tr.ballot_id = b.id
             WHERE b.match_id = $1 AND b.is_submitted = true AND b.is_voting = true
             GROUP BY tr.team_id
-            ORDER BY avg_rank ASC
+            ORDER BY avg_rank ASC, tr.team_id
             "#,
         )
         .bind(match_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
+        .await?;
+
+        let mut rankings: Vec<(Uuid, Decimal)> = results
+            .into_iter()
+            .filter_map(|(team_id, avg)| avg.map(|a| (team_id, self.rounding.round(a))))
+            .collect();
+        rankings.sort_by_key(|&(_, avg)| avg);
+        Ok(rankings)
+    }
+
+    // ========================================================================
+    // Double Entry Methods
+    // ========================================================================
+
+    /// Save an admin's entry of a paper ballot, replacing their earlier
+    /// entry for the match
+    pub async fn save_ballot_entry(
+        &self,
+        entry: &BallotEntry,
+        scores: &[BallotEntryScore],
+        rankings: &[BallotEntryRanking],
+    ) -> Result<EnteredBallot, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let saved = sqlx::query_as::<_, BallotEntry>(
+            r#"
+            INSERT INTO ballot_entries (id, match_id, entered_by, notes, confirm_low_point_win,
+                created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT ON CONSTRAINT unique_ballot_entry DO UPDATE SET
+                notes = EXCLUDED.notes,
+                confirm_low_point_win = EXCLUDED.confirm_low_point_win,
+                updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(entry.id)
+        .bind(entry.match_id)
+        .bind(entry.entered_by)
+        .bind(&entry.notes)
+        .bind(entry.confirm_low_point_win)
+        .bind(entry.created_at)
+        .bind(entry.updated_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM ballot_entry_scores WHERE entry_id = $1")
+            .bind(saved.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM ballot_entry_rankings WHERE entry_id = $1")
+            .bind(saved.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut speaker_scores = Vec::new();
+        for score in scores {
+            speaker_scores.push(
+                sqlx::query_as::<_, BallotEntryScore>(
+                    r#"
+                    INSERT INTO ballot_entry_scores (entry_id, allocation_id, score, feedback)
+                    VALUES ($1, $2, $3, $4)
+                    RETURNING *
+                    "#,
+                )
+                .bind(saved.id)
+                .bind(score.allocation_id)
+                .bind(score.score)
+                .bind(&score.feedback)
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+        let mut team_rankings = Vec::new();
+        for ranking in rankings {
+            team_rankings.push(
+                sqlx::query_as::<_, BallotEntryRanking>(
+                    r#"
+                    INSERT INTO ballot_entry_rankings (entry_id, team_id, rank, is_winner)
+                    VALUES ($1, $2, $3, $4)
+                    RETURNING *
+                    "#,
+                )
+                .bind(saved.id)
+                .bind(ranking.team_id)
+                .bind(ranking.rank)
+                .bind(ranking.is_winner)
+                .fetch_one(&mut *tx)
+                .await?,
+            );
+        }
+
+        tx.commit().await?;
+        Ok(EnteredBallot {
+            entry: saved,
+            speaker_scores,
+            team_rankings,
+        })
+    }
+
+    /// A match's pending entries, oldest first
+    pub async fn list_ballot_entries(
+        &self,
+        match_id: Uuid,
+    ) -> Result<Vec<EnteredBallot>, sqlx::Error> {
+        let entries = sqlx::query_as::<_, BallotEntry>(
+            "SELECT * FROM ballot_entries WHERE match_id = $1 ORDER BY updated_at, id",
+        )
+        .bind(match_id)
+        .fetch_all(self.reader())
+        .await?;
+        let ids: Vec<Uuid> = entries.iter().map(|e| e.id).collect();
+
+        let scores = sqlx::query_as::<_, BallotEntryScore>(
+            r#"
+            SELECT s.* FROM ballot_entry_scores s
+            JOIN allocations a ON s.allocation_id = a.id
+            WHERE s.entry_id = ANY($1)
+            ORDER BY a.allocated_at, a.id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(self.reader())
+        .await?;
+        let rankings = sqlx::query_as::<_, BallotEntryRanking>(
+            "SELECT * FROM ballot_entry_rankings WHERE entry_id = ANY($1) ORDER BY rank",
+        )
+        .bind(&ids)
+        .fetch_all(self.reader())
         .await?;
 
-        Ok(results
+        Ok(entries
             .into_iter()
-            .filter_map(|(team_id, avg)| avg.map(|a| (team_id, a)))
+            .map(|entry| EnteredBallot {
+                speaker_scores: scores
+                    .iter()
+                    .filter(|s| s.entry_id == entry.id)
+                    .cloned()
+                    .collect(),
+                team_rankings: rankings
+                    .iter()
+                    .filter(|r| r.entry_id == entry.id)
+                    .cloned()
+                    .collect(),
+                entry,
+            })
             .collect())
     }
 
+    /// Clear a match's entries once its result is recorded
+    pub async fn delete_ballot_entries(&self, match_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM ballot_entries WHERE match_id = $1")
+            .bind(match_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     // ========================================================================
     // Attendance Integration (for allocation pool)
     // ========================================================================
@@ -1068,170 +2032,1649 @@ impl Database {
     ) -> Result<Vec<AttendanceInfo>, sqlx::Error> {
         sqlx::query_as::<_, AttendanceInfo>(
             r#"
-            SELECT id, event_id, user_id, is_checked_in, checked_in_at
-            FROM attendance_records
-            WHERE event_id = $1 AND is_checked_in = true
+            SELECT ar.id, ar.event_id, ar.user_id, ar.is_checked_in, ar.checked_in_at,
+                CASE WHEN EXISTS (SELECT 1 FROM event_fees WHERE event_id = $1)
+                    THEN COALESCE(ep.status, 'unpaid')
+                END AS payment_status
+            FROM attendance_records ar
+            LEFT JOIN event_payments ep ON ep.event_id = ar.event_id AND ep.user_id = ar.user_id
+            WHERE ar.event_id = $1 AND ar.is_checked_in = true
             "#,
         )
         .bind(event_id)
-        .fetch_all(&self.pool)
+        .fetch_all(self.reader())
         .await
     }
 
-    /// Check if a specific user is checked in for an event
-    pub async fn is_user_checked_in(
+    /// Guests registered at the desk for the event, in order of arrival
+    pub async fn get_walk_ins_for_event(
         &self,
         event_id: Uuid,
-        user_id: Uuid,
-    ) -> Result<bool, sqlx::Error> {
-        let result: Option<(bool,)> = sqlx::query_as(
-            "SELECT is_checked_in FROM attendance_records WHERE event_id = $1 AND user_id = $2",
+    ) -> Result<Vec<WalkInGuest>, sqlx::Error> {
+        sqlx::query_as::<_, WalkInGuest>(
+            r#"
+            SELECT id, event_id, name, institution_id, created_at
+            FROM event_walk_ins
+            WHERE event_id = $1
+            ORDER BY created_at
+            "#,
         )
         .bind(event_id)
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        .fetch_all(self.reader())
+        .await
+    }
 
-        Ok(result
-            .map(|(is_checked_in,)| is_checked_in)
-            .unwrap_or(false))
+    pub async fn get_walk_in_by_id(
+        &self,
+        walk_in_id: Uuid,
+    ) -> Result<Option<WalkInGuest>, sqlx::Error> {
+        sqlx::query_as::<_, WalkInGuest>(
+            "SELECT id, event_id, name, institution_id, created_at FROM event_walk_ins WHERE id = $1",
+        )
+        .bind(walk_in_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Checked-in users with nothing allocated yet in the series, as judge
+    /// candidates. `day` is the date whose rooms count towards their load.
+    pub async fn list_adjudicator_candidates(
+        &self,
+        event_id: Uuid,
+        series_id: Uuid,
+        day: NaiveDate,
+    ) -> Result<Vec<Candidate>, sqlx::Error> {
+        sqlx::query_as::<_, Candidate>(
+            r#"
+            SELECT u.id AS user_id, u.username, u.institution_id, i.name AS institution_name,
+                (SELECT COUNT(*) FROM ballots b
+                    WHERE b.adjudicator_id = u.id AND b.is_submitted) AS rounds_judged,
+                (SELECT COUNT(*) FROM allocations a
+                    JOIN matches m ON a.match_id = m.id
+                    JOIN match_series ms ON m.series_id = ms.id
+                    WHERE a.user_id = u.id
+                      AND a.role IN ('voting_adjudicator', 'non_voting_adjudicator')
+                      AND ms.event_id = $1
+                      AND m.status <> 'cancelled'
+                      AND COALESCE(m.scheduled_time, a.allocated_at)::date = $3) AS rooms_today,
+                (SELECT ja.level FROM judge_accreditations ja
+                    WHERE ja.user_id = u.id
+                    ORDER BY ja.created_at DESC LIMIT 1) AS accreditation
+            FROM attendance_records ar
+            JOIN users u ON ar.user_id = u.id
+            LEFT JOIN institutions i ON u.institution_id = i.id
+            WHERE ar.event_id = $1 AND ar.is_checked_in = true
+              AND NOT EXISTS (
+                SELECT 1 FROM allocations a
+                JOIN matches m ON a.match_id = m.id
+                WHERE m.series_id = $2 AND a.user_id = u.id
+              )
+            ORDER BY u.username
+            "#,
+        )
+        .bind(event_id)
+        .bind(series_id)
+        .bind(day)
+        .fetch_all(self.reader())
+        .await
     }
 
     // ========================================================================
-    // Performance/Statistics Methods
+    // Judging Record Methods
     // ========================================================================
 
-    pub async fn get_user_round_counts(
+    pub async fn get_judging_rounds(&self, user_id: Uuid) -> Result<JudgingRounds, sqlx::Error> {
+        sqlx::query_as::<_, JudgingRounds>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE a.role = 'voting_adjudicator' AND a.is_chair)
+                    AS rounds_chaired,
+                COUNT(*) FILTER (WHERE a.role = 'voting_adjudicator' AND NOT a.is_chair)
+                    AS rounds_panelled,
+                COUNT(*) FILTER (WHERE a.role = 'non_voting_adjudicator') AS rounds_as_trainee,
+                (SELECT COUNT(*) FROM ballots b
+                    WHERE b.adjudicator_id = $1 AND b.is_submitted) AS ballots_submitted
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            WHERE a.user_id = $1 AND m.status NOT IN ('draft', 'cancelled')
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(self.reader())
+        .await
+    }
+
+    /// A judge's accreditation ledger, newest first
+    pub async fn list_accreditation_history(
         &self,
         user_id: Uuid,
-        event_id: Option<Uuid>,
-    ) -> Result<(i64, i64, i64), sqlx::Error> {
-        // Returns (total_rounds, speaker_rounds, adjudicator_rounds)
-        let result: (i64, i64, i64) = if let Some(event_id) = event_id {
-            sqlx::query_as(
-                r#"
-                SELECT 
-                    COUNT(DISTINCT a.match_id) as total,
-                    COUNT(DISTINCT CASE WHEN a.role = 'speaker' THEN a.match_id END) as speaker,
-                    COUNT(DISTINCT CASE WHEN a.role IN ('voting_adjudicator', 'non_voting_adjudicator') THEN a.match_id END) as adjudicator
-                FROM allocations a
-                JOIN matches m ON a.match_id = m.id
-                JOIN match_series ms ON m.series_id = ms.id
-                WHERE a.user_id = $1 AND ms.event_id = $2
-                "#,
-            )
-            .bind(user_id)
-            .bind(event_id)
-            .fetch_one(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as(
-                r#"
-                SELECT 
-                    COUNT(DISTINCT match_id) as total,
-                    COUNT(DISTINCT CASE WHEN role = 'speaker' THEN match_id END) as speaker,
-                    COUNT(DISTINCT CASE WHEN role IN ('voting_adjudicator', 'non_voting_adjudicator') THEN match_id END) as adjudicator
-                FROM allocations
-                WHERE user_id = $1
-                "#,
+    ) -> Result<Vec<AccreditationChange>, sqlx::Error> {
+        sqlx::query_as::<_, AccreditationChange>(
+            r#"
+            SELECT ja.id, ja.level, ja.note, ja.set_by, u.username AS set_by_username,
+                ja.created_at
+            FROM judge_accreditations ja
+            LEFT JOIN users u ON ja.set_by = u.id
+            WHERE ja.user_id = $1
+            ORDER BY ja.created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Add an entry to a judge's accreditation ledger, making it their level
+    pub async fn set_accreditation(
+        &self,
+        user_id: Uuid,
+        level: AccreditationLevel,
+        note: Option<&str>,
+        set_by: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO judge_accreditations (user_id, level, note, set_by)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(user_id)
+        .bind(level)
+        .bind(note)
+        .bind(set_by)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Institutions of a match's teams and speakers, by id
+    pub async fn list_match_institutions(
+        &self,
+        match_id: Uuid,
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT i.id, i.name FROM institutions i
+            WHERE i.id IN (
+                SELECT institution_id FROM match_teams WHERE match_id = $1
+                UNION
+                SELECT u.institution_id FROM allocations a
+                JOIN users u ON a.user_id = u.id
+                WHERE a.match_id = $1 AND a.role = 'speaker'
             )
-            .bind(user_id)
-            .fetch_one(&self.pool)
-            .await?
-        };
+            ORDER BY i.name
+            "#,
+        )
+        .bind(match_id)
+        .fetch_all(self.reader())
+        .await
+    }
 
-        Ok(result)
+    /// Check if a specific user is checked in for an event
+    pub async fn is_user_checked_in(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result: Option<(bool,)> = sqlx::query_as(
+            "SELECT is_checked_in FROM attendance_records WHERE event_id = $1 AND user_id = $2",
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result
+            .map(|(is_checked_in,)| is_checked_in)
+            .unwrap_or(false))
     }
 
-    pub async fn get_user_win_loss(
+    // ========================================================================
+    // Performance/Statistics Methods
+    // ========================================================================
+
+    /// A user's ballot results from the `user_event_performance` read
+    /// model, overall, at one event or over one season
+    pub async fn get_user_performance_totals(
         &self,
         user_id: Uuid,
         event_id: Option<Uuid>,
-    ) -> Result<(i64, i64), sqlx::Error> {
-        // Returns (wins, losses)
-        let result: (i64, i64) = if let Some(event_id) = event_id {
-            sqlx::query_as(
-                r#"
-                SELECT 
-                    COUNT(CASE WHEN tr.is_winner = true THEN 1 END) as wins,
-                    COUNT(CASE WHEN tr.is_winner = false THEN 1 END) as losses
-                FROM allocations a
-                JOIN match_teams mt ON a.team_id = mt.id
-                JOIN team_rankings tr ON mt.id = tr.team_id
-                JOIN ballots b ON tr.ballot_id = b.id
-                JOIN matches m ON a.match_id = m.id
-                JOIN match_series ms ON m.series_id = ms.id
-                WHERE a.user_id = $1 AND ms.event_id = $2 AND a.role = 'speaker' AND b.is_voting = true AND b.is_submitted = true
-                "#,
-            )
-            .bind(user_id)
-            .bind(event_id)
-            .fetch_one(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as(
-                r#"
-                SELECT 
-                    COUNT(CASE WHEN tr.is_winner = true THEN 1 END) as wins,
-                    COUNT(CASE WHEN tr.is_winner = false THEN 1 END) as losses
-                FROM allocations a
-                JOIN match_teams mt ON a.team_id = mt.id
-                JOIN team_rankings tr ON mt.id = tr.team_id
-                JOIN ballots b ON tr.ballot_id = b.id
-                WHERE a.user_id = $1 AND a.role = 'speaker' AND b.is_voting = true AND b.is_submitted = true
-                "#,
-            )
-            .bind(user_id)
+        season_id: Option<Uuid>,
+    ) -> Result<PerformanceTotals, sqlx::Error> {
+        sqlx::query_as(&format!(
+            r#"
+            SELECT
+                SUM(speaker_score_total) / NULLIF(SUM(speaker_score_count), 0)
+                    AS average_speaker_score,
+                COALESCE(SUM(wins), 0)::BIGINT AS wins,
+                COALESCE(SUM(losses), 0)::BIGINT AS losses,
+                COALESCE(SUM(firsts), 0)::BIGINT AS firsts,
+                COALESCE(SUM(seconds), 0)::BIGINT AS seconds,
+                COALESCE(SUM(thirds), 0)::BIGINT AS thirds,
+                COALESCE(SUM(fourths), 0)::BIGINT AS fourths
+            FROM user_event_performance p
+            JOIN events e ON p.event_id = e.id
+            WHERE p.user_id = $1 AND ($2::uuid IS NULL OR p.event_id = $2) AND {}
+            "#,
+            in_season("e.event_date", "$3")
+        ))
+        .bind(user_id)
+        .bind(event_id)
+        .bind(season_id)
+        .fetch_one(self.reader())
+        .await
+    }
+
+    pub async fn season_exists(&self, season_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM seasons WHERE id = $1)")
+            .bind(season_id)
             .fetch_one(&self.pool)
-            .await?
-        };
+            .await
+    }
 
-        Ok(result)
+    /// Rebuild the performance read model for an event from its submitted
+    /// ballots
+    pub async fn refresh_event_performance(&self, event_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT refresh_user_event_performance($1)")
+            .bind(event_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Rebuild the performance read model for the event a match belongs to
+    pub async fn refresh_match_performance(&self, match_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT refresh_user_event_performance(ms.event_id)
+            FROM matches m
+            JOIN match_series ms ON m.series_id = ms.id
+            WHERE m.id = $1
+            "#,
+        )
+        .bind(match_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// A member's speeches whose scores are out, in the order spoken, each
+    /// with the mean score of its submitted voting ballots. Training,
+    /// silent and speaks-withheld rounds are left out.
+    pub async fn list_released_speeches(
+        &self,
+        user_id: Uuid,
+        season_id: Option<Uuid>,
+    ) -> Result<Vec<SpokenRound>, sqlx::Error> {
+        sqlx::query_as::<_, SpokenRound>(&format!(
+            r#"
+            SELECT m.id AS match_id, e.id AS event_id, e.title AS event_title,
+                ms.id AS series_id, ms.name AS series_name, ms.round_number, m.room_name,
+                COALESCE(m.scheduled_time, e.event_date) AS spoken_at,
+                AVG(ss.score) AS score
+            FROM allocations a
+            JOIN speaker_scores ss ON ss.allocation_id = a.id
+            JOIN ballots b ON ss.ballot_id = b.id AND b.is_submitted AND b.is_voting
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            JOIN events e ON ms.event_id = e.id
+            WHERE a.user_id = $1 AND a.role = 'speaker' AND m.scores_released
+              AND NOT ms.is_silent AND NOT ms.is_training AND NOT e.speaks_withheld
+              AND {}
+            GROUP BY a.id, m.id, ms.id, e.id
+            ORDER BY spoken_at, ms.round_number NULLS LAST, m.id
+            "#,
+            in_season("e.event_date", "$2")
+        ))
+        .bind(user_id)
+        .bind(season_id)
+        .fetch_all(self.reader())
+        .await
     }
 
-    pub async fn get_user_ranking_distribution(
+    pub async fn get_user_round_counts(
         &self,
         user_id: Uuid,
         event_id: Option<Uuid>,
-    ) -> Result<Vec<(i32, i64)>, sqlx::Error> {
-        // Returns vec of (rank, count) for BP format
-        let results: Vec<(i32, i64)> = if let Some(event_id) = event_id {
-            sqlx::query_as(
-                r#"
-                SELECT tr.rank, COUNT(*) as count
-                FROM allocations a
-                JOIN match_teams mt ON a.team_id = mt.id
-                JOIN team_rankings tr ON mt.id = tr.team_id
-                JOIN ballots b ON tr.ballot_id = b.id
-                JOIN matches m ON a.match_id = m.id
-                JOIN match_series ms ON m.series_id = ms.id
-                WHERE a.user_id = $1 AND ms.event_id = $2 AND a.role = 'speaker' AND b.is_voting = true AND b.is_submitted = true
-                GROUP BY tr.rank
-                ORDER BY tr.rank
-                "#,
-            )
-            .bind(user_id)
-            .bind(event_id)
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            sqlx::query_as(
+        season_id: Option<Uuid>,
+    ) -> Result<(i64, i64, i64), sqlx::Error> {
+        // Returns (total_rounds, speaker_rounds, adjudicator_rounds)
+        sqlx::query_as(&format!(
+            r#"
+            SELECT 
+                COUNT(DISTINCT a.match_id) as total,
+                COUNT(DISTINCT CASE WHEN a.role = 'speaker' THEN a.match_id END) as speaker,
+                COUNT(DISTINCT CASE WHEN a.role IN ('voting_adjudicator', 'non_voting_adjudicator') THEN a.match_id END) as adjudicator
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            JOIN events e ON ms.event_id = e.id
+            WHERE a.user_id = $1 AND ($2::uuid IS NULL OR ms.event_id = $2)
+              AND NOT ms.is_training AND {}
+            "#,
+            in_season("e.event_date", "$3")
+        ))
+        .bind(user_id)
+        .bind(event_id)
+        .bind(season_id)
+        .fetch_one(self.reader())
+        .await
+    }
+
+    /// A user's allocations in published, running or finished matches, for
+    /// their personal calendar feed. Draft and cancelled matches are left out.
+    pub async fn list_calendar_allocations(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<CalendarAllocation>, sqlx::Error> {
+        sqlx::query_as::<_, CalendarAllocation>(
+            r#"
+            SELECT a.id AS allocation_id, m.id AS match_id, a.role, a.is_chair,
+                   t.team_name, t.two_team_position, t.four_team_position,
+                   m.room_name, m.scheduled_time,
+                   s.name AS series_name, e.title AS event_title, e.event_date,
+                   e.location AS event_location,
+                   GREATEST(a.allocated_at, m.updated_at) AS updated_at
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            JOIN events e ON s.event_id = e.id
+            LEFT JOIN match_teams t ON a.team_id = t.id
+            WHERE a.user_id = $1
+              AND m.status IN ('published', 'in_progress', 'completed')
+            ORDER BY COALESCE(m.scheduled_time, e.event_date) ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// A user's allocations in one event's published draws, in the order
+    /// they happen
+    pub async fn list_schedule(
+        &self,
+        user_id: Uuid,
+        event_id: Uuid,
+    ) -> Result<Vec<ScheduleEntry>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduleEntry>(
+            r#"
+            SELECT a.id AS allocation_id, m.id AS match_id, s.id AS series_id,
+                   s.name AS series_name, s.round_number, a.role, a.is_chair, a.team_id,
+                   COALESCE(rt.name, t.team_name) AS team_name,
+                   t.two_team_position, t.four_team_position,
+                   a.two_team_speaker_role, a.four_team_speaker_role,
+                   m.room_name, m.venue_id, m.scheduled_time, m.status, m.motion, m.info_slide
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series s ON m.series_id = s.id
+            LEFT JOIN match_teams t ON a.team_id = t.id
+            LEFT JOIN registered_teams rt ON t.registered_team_id = rt.id
+            WHERE a.user_id = $1 AND s.event_id = $2
+              AND m.status IN ('published', 'in_progress', 'completed')
+            ORDER BY m.scheduled_time NULLS LAST, s.round_number NULLS LAST, m.room_name
+            "#,
+        )
+        .bind(user_id)
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Attachment Methods
+    // ========================================================================
+
+    pub async fn create_attachment(
+        &self,
+        attachment: &Attachment,
+    ) -> Result<Attachment, sqlx::Error> {
+        sqlx::query_as::<_, Attachment>(
+            r#"
+            INSERT INTO attachments (id, event_id, match_id, filename, content_type, size_bytes,
+                storage_key, uploaded_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(attachment.id)
+        .bind(attachment.event_id)
+        .bind(attachment.match_id)
+        .bind(&attachment.filename)
+        .bind(&attachment.content_type)
+        .bind(attachment.size_bytes)
+        .bind(&attachment.storage_key)
+        .bind(attachment.uploaded_by)
+        .bind(attachment.created_at)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_attachment_by_id(
+        &self,
+        attachment_id: Uuid,
+    ) -> Result<Option<Attachment>, sqlx::Error> {
+        sqlx::query_as::<_, Attachment>("SELECT * FROM attachments WHERE id = $1")
+            .bind(attachment_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    /// Confirmed attachments of an event, or of a match when `match_id` is set
+    pub async fn list_attachments(
+        &self,
+        event_id: Option<Uuid>,
+        match_id: Option<Uuid>,
+    ) -> Result<Vec<Attachment>, sqlx::Error> {
+        sqlx::query_as::<_, Attachment>(
+            r#"
+            SELECT * FROM attachments
+            WHERE uploaded_at IS NOT NULL
+              AND ($1::uuid IS NULL OR event_id = $1)
+              AND ($2::uuid IS NULL OR match_id = $2)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(event_id)
+        .bind(match_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn mark_attachment_uploaded(
+        &self,
+        attachment_id: Uuid,
+        size_bytes: i64,
+    ) -> Result<Attachment, sqlx::Error> {
+        sqlx::query_as::<_, Attachment>(
+            r#"
+            UPDATE attachments SET size_bytes = $2, uploaded_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(attachment_id)
+        .bind(size_bytes)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn delete_attachment(&self, attachment_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM attachments WHERE id = $1")
+            .bind(attachment_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Venue Methods
+    // ========================================================================
+
+    pub async fn create_venue(&self, venue: &CreateVenueRequest) -> Result<Venue, sqlx::Error> {
+        sqlx::query_as::<_, Venue>(
+            r#"
+            INSERT INTO venues (id, name, building, capacity, is_accessible, accessibility_notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&venue.name)
+        .bind(&venue.building)
+        .bind(venue.capacity)
+        .bind(venue.is_accessible)
+        .bind(&venue.accessibility_notes)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_venue(&self, venue_id: Uuid) -> Result<Option<Venue>, sqlx::Error> {
+        sqlx::query_as::<_, Venue>("SELECT * FROM venues WHERE id = $1")
+            .bind(venue_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    pub async fn list_venues(&self) -> Result<Vec<Venue>, sqlx::Error> {
+        sqlx::query_as::<_, Venue>("SELECT * FROM venues ORDER BY name")
+            .fetch_all(self.reader())
+            .await
+    }
+
+    /// Change a venue; matches held there pick up a new name
+    pub async fn update_venue(
+        &self,
+        venue_id: Uuid,
+        changes: &UpdateVenueRequest,
+    ) -> Result<Option<Venue>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let venue = sqlx::query_as::<_, Venue>(
+            r#"
+            UPDATE venues SET
+                name = COALESCE($2, name),
+                building = COALESCE($3, building),
+                capacity = COALESCE($4, capacity),
+                is_accessible = COALESCE($5, is_accessible),
+                accessibility_notes = COALESCE($6, accessibility_notes),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(venue_id)
+        .bind(&changes.name)
+        .bind(&changes.building)
+        .bind(changes.capacity)
+        .bind(changes.is_accessible)
+        .bind(&changes.accessibility_notes)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(venue) = &venue {
+            // Archived events keep the name they were archived with
+            sqlx::query(
                 r#"
-                SELECT tr.rank, COUNT(*) as count
-                FROM allocations a
-                JOIN match_teams mt ON a.team_id = mt.id
-                JOIN team_rankings tr ON mt.id = tr.team_id
-                JOIN ballots b ON tr.ballot_id = b.id
-                WHERE a.user_id = $1 AND a.role = 'speaker' AND b.is_voting = true AND b.is_submitted = true
-                GROUP BY tr.rank
-                ORDER BY tr.rank
+                UPDATE matches m SET room_name = $2, updated_at = NOW()
+                FROM match_series s, events e
+                WHERE m.series_id = s.id AND s.event_id = e.id
+                  AND m.venue_id = $1 AND m.room_name IS DISTINCT FROM $2
+                  AND e.archived_at IS NULL
                 "#,
             )
-            .bind(user_id)
-            .fetch_all(&self.pool)
-            .await?
-        };
+            .bind(venue.id)
+            .bind(&venue.name)
+            .execute(&mut *tx)
+            .await?;
+        }
 
-        Ok(results)
+        tx.commit().await?;
+        Ok(venue)
+    }
+
+    pub async fn delete_venue(&self, venue_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM venues WHERE id = $1")
+            .bind(venue_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Institution Methods
+    // ========================================================================
+
+    pub async fn create_institution(
+        &self,
+        name: &str,
+        short_code: Option<&str>,
+    ) -> Result<Institution, sqlx::Error> {
+        sqlx::query_as::<_, Institution>(
+            r#"
+            INSERT INTO institutions (id, name, short_code)
+            VALUES ($1, $2, $3)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(short_code)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_institution(
+        &self,
+        institution_id: Uuid,
+    ) -> Result<Option<Institution>, sqlx::Error> {
+        sqlx::query_as::<_, Institution>("SELECT * FROM institutions WHERE id = $1")
+            .bind(institution_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    pub async fn list_institutions(&self) -> Result<Vec<Institution>, sqlx::Error> {
+        sqlx::query_as::<_, Institution>("SELECT * FROM institutions ORDER BY name")
+            .fetch_all(self.reader())
+            .await
+    }
+
+    /// Rename an institution; teams that follow it pick up the new name
+    pub async fn update_institution(
+        &self,
+        institution_id: Uuid,
+        name: Option<&str>,
+        short_code: Option<&str>,
+    ) -> Result<Option<Institution>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let institution = sqlx::query_as::<_, Institution>(
+            r#"
+            UPDATE institutions SET
+                name = COALESCE($2, name),
+                short_code = COALESCE($3, short_code),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(institution_id)
+        .bind(name)
+        .bind(short_code)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(institution) = &institution {
+            // Archived events keep the name they were archived with
+            sqlx::query(
+                r#"
+                UPDATE match_teams t SET institution = $2, updated_at = NOW()
+                FROM matches m, match_series s, events e
+                WHERE t.match_id = m.id AND m.series_id = s.id AND s.event_id = e.id
+                  AND t.institution_id = $1 AND t.institution IS DISTINCT FROM $2
+                  AND e.archived_at IS NULL
+                "#,
+            )
+            .bind(institution.id)
+            .bind(&institution.name)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(institution)
+    }
+
+    pub async fn delete_institution(&self, institution_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM institutions WHERE id = $1")
+            .bind(institution_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_speaker_eligibility(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<SpeakerEligibility>, sqlx::Error> {
+        sqlx::query_as::<_, SpeakerEligibility>(
+            r#"
+            SELECT u.id AS user_id, u.username, u.institution_id, i.name AS institution_name,
+                u.is_novice, u.is_esl
+            FROM users u
+            LEFT JOIN institutions i ON u.institution_id = i.id
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    pub async fn update_speaker_eligibility(
+        &self,
+        user_id: Uuid,
+        institution_id: Option<Uuid>,
+        is_novice: bool,
+        is_esl: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET institution_id = $2, is_novice = $3, is_esl = $4, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(institution_id)
+        .bind(is_novice)
+        .bind(is_esl)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Users eligible for a category, optionally from one institution
+    pub async fn list_eligible_speakers(
+        &self,
+        filter: EligibilityFilter,
+        institution_id: Option<Uuid>,
+    ) -> Result<Vec<SpeakerEligibility>, sqlx::Error> {
+        sqlx::query_as::<_, SpeakerEligibility>(&format!(
+            r#"
+            SELECT u.id AS user_id, u.username, u.institution_id, i.name AS institution_name,
+                u.is_novice, u.is_esl
+            FROM users u
+            LEFT JOIN institutions i ON u.institution_id = i.id
+            WHERE {} AND ($1::uuid IS NULL OR u.institution_id = $1)
+            ORDER BY u.username
+            "#,
+            filter.sql_condition("u")
+        ))
+        .bind(institution_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    // ========================================================================
+    // Tab Methods
+    // ========================================================================
+
+    /// Placings of every team in the event's decided preliminary rooms.
+    /// Training rounds are left out, and silent rounds unless asked for.
+    pub async fn list_tab_team_results(
+        &self,
+        event_id: Uuid,
+        include_silent: bool,
+    ) -> Result<Vec<TeamResult>, sqlx::Error> {
+        sqlx::query_as::<_, TeamResult>(
+            r#"
+            SELECT mt.id AS match_team_id, mt.match_id, mt.registered_team_id,
+                COALESCE(rt.name, mt.team_name) AS team_name,
+                COALESCE(i.name, mt.institution) AS institution,
+                ms.team_format, mt.final_rank, mt.total_speaker_points AS speaker_points
+            FROM match_teams mt
+            JOIN matches m ON mt.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            LEFT JOIN registered_teams rt ON mt.registered_team_id = rt.id
+            LEFT JOIN institutions i ON mt.institution_id = i.id
+            WHERE ms.event_id = $1
+              AND ms.round_number IS NOT NULL
+              AND NOT ms.is_break_round
+              AND NOT ms.is_training
+              AND ($2 OR NOT ms.is_silent)
+              AND m.status <> 'cancelled'
+              AND mt.final_rank IS NOT NULL
+            ORDER BY ms.round_number, m.room_name, mt.created_at
+            "#,
+        )
+        .bind(event_id)
+        .bind(include_silent)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Teams in the event's preliminary rooms that are still missing a result
+    pub async fn list_outstanding_teams(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<OutstandingTeam>, sqlx::Error> {
+        sqlx::query_as::<_, OutstandingTeam>(
+            r#"
+            SELECT mt.id AS match_team_id, mt.match_id, ms.name AS series_name, m.room_name,
+                mt.registered_team_id,
+                COALESCE(rt.name, mt.team_name) AS team_name,
+                COALESCE(i.name, mt.institution) AS institution,
+                ms.team_format
+            FROM match_teams mt
+            JOIN matches m ON mt.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            LEFT JOIN registered_teams rt ON mt.registered_team_id = rt.id
+            LEFT JOIN institutions i ON mt.institution_id = i.id
+            WHERE ms.event_id = $1
+              AND ms.round_number IS NOT NULL
+              AND NOT ms.is_break_round
+              AND NOT ms.is_training
+              AND m.status <> 'cancelled'
+              AND EXISTS (
+                  SELECT 1 FROM match_teams other
+                  WHERE other.match_id = m.id AND other.final_rank IS NULL
+              )
+            ORDER BY ms.round_number, m.room_name, mt.created_at
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Every speech in the event's preliminary rooms with its averaged score
+    pub async fn list_tab_speeches(
+        &self,
+        event_id: Uuid,
+        include_silent: bool,
+    ) -> Result<Vec<SpeechResult>, sqlx::Error> {
+        sqlx::query_as::<_, SpeechResult>(
+            r#"
+            SELECT a.team_id AS match_team_id, a.user_id,
+                COALESCE(u.username, a.guest_name, 'Guest') AS name,
+                COALESCE(u.is_novice, FALSE) AS is_novice,
+                COALESCE(u.is_esl, FALSE) AS is_esl,
+                COALESCE(a.two_team_speaker_role IN ('government_reply', 'opposition_reply'), FALSE)
+                    AS is_reply,
+                (SELECT AVG(ss.score)
+                    FROM speaker_scores ss
+                    JOIN ballots b ON ss.ballot_id = b.id
+                    WHERE ss.allocation_id = a.id AND b.is_submitted AND b.is_voting) AS score
+            FROM allocations a
+            JOIN matches m ON a.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            LEFT JOIN users u ON a.user_id = u.id
+            WHERE ms.event_id = $1
+              AND ms.round_number IS NOT NULL
+              AND NOT ms.is_break_round
+              AND NOT ms.is_training
+              AND ($2 OR NOT ms.is_silent)
+              AND m.status <> 'cancelled'
+              AND a.role = 'speaker'
+              AND a.team_id IS NOT NULL
+            ORDER BY ms.round_number, a.allocated_at
+            "#,
+        )
+        .bind(event_id)
+        .bind(include_silent)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Every score on the event's submitted voting ballots, optionally for
+    /// one series
+    pub async fn list_given_scores(
+        &self,
+        event_id: Uuid,
+        series_id: Option<Uuid>,
+    ) -> Result<Vec<GivenScore>, sqlx::Error> {
+        sqlx::query_as::<_, GivenScore>(
+            r#"
+            SELECT b.adjudicator_id, COALESCE(u.username, 'Unknown') AS username,
+                m.id AS match_id, ms.name AS series_name, m.room_name,
+                ss.allocation_id, ss.score, b.low_point_win
+            FROM speaker_scores ss
+            JOIN ballots b ON ss.ballot_id = b.id
+            JOIN matches m ON b.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            LEFT JOIN users u ON b.adjudicator_id = u.id
+            WHERE ms.event_id = $1
+              AND ($2::uuid IS NULL OR ms.id = $2)
+              AND b.is_submitted AND b.is_voting
+              AND m.status <> 'cancelled'
+            ORDER BY ms.round_number, m.room_name, b.submitted_at
+            "#,
+        )
+        .bind(event_id)
+        .bind(series_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Changes whenever anything shown in the match's response does, for
+    /// conditional GETs. None if the match does not exist.
+    pub async fn match_version(&self, match_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(&format!(
+            r#"
+            SELECT concat_ws('|', m.updated_at, ms.updated_at, e.updated_at, v.updated_at,
+                {}, {}, {}, {}, {})
+            FROM matches m
+            JOIN match_series ms ON m.series_id = ms.id
+            JOIN events e ON ms.event_id = e.id
+            LEFT JOIN venues v ON m.venue_id = v.id
+            WHERE m.id = $1
+            "#,
+            rows_version("match_teams t WHERE t.match_id = m.id"),
+            rows_version("allocations t WHERE t.match_id = m.id"),
+            rows_version("ballots t WHERE t.match_id = m.id"),
+            rows_version(
+                "speaker_scores t JOIN ballots b ON t.ballot_id = b.id WHERE b.match_id = m.id"
+            ),
+            rows_version(
+                "team_rankings t JOIN ballots b ON t.ballot_id = b.id WHERE b.match_id = m.id"
+            ),
+        ))
+        .bind(match_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Changes whenever the series or its match count does
+    pub async fn series_version(&self, series_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(&format!(
+            "SELECT concat_ws('|', ms.updated_at, {}) FROM match_series ms WHERE ms.id = $1",
+            rows_version("matches t WHERE t.series_id = ms.id")
+        ))
+        .bind(series_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    /// Every side drawn in the event's rounds that count for rotation, in
+    /// round order; with `speakers`, one row per speech instead
+    pub async fn list_drawn_positions(
+        &self,
+        event_id: Uuid,
+        speakers: bool,
+    ) -> Result<Vec<DrawnPosition>, sqlx::Error> {
+        let (speaker_columns, speaker_join) = if speakers {
+            (
+                "a.user_id, u.username",
+                "JOIN allocations a ON a.team_id = t.id AND a.role = 'speaker' \
+                 JOIN users u ON a.user_id = u.id",
+            )
+        } else {
+            ("NULL::uuid AS user_id, NULL::varchar AS username", "")
+        };
+        sqlx::query_as::<_, DrawnPosition>(&format!(
+            r#"
+            SELECT m.id AS match_id, t.id AS match_team_id, t.registered_team_id,
+                t.team_name, {}, ms.name AS series_name,
+                t.two_team_position, t.four_team_position
+            FROM match_teams t
+            JOIN matches m ON t.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            {}
+            WHERE ms.event_id = $1 AND ms.round_number IS NOT NULL
+              AND NOT ms.is_break_round AND NOT ms.is_training
+              AND m.status <> 'cancelled'
+            ORDER BY ms.round_number, ms.name, m.id
+            "#,
+            speaker_columns, speaker_join
+        ))
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Final team ranks in an event's rooms with a motion and at least one
+    /// submitted voting ballot, in round order
+    pub async fn list_motion_results(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<MotionResult>, sqlx::Error> {
+        sqlx::query_as::<_, MotionResult>(
+            r#"
+            SELECT m.id AS match_id, m.motion, ms.name AS series_name,
+                mt.two_team_position, mt.four_team_position, mt.final_rank
+            FROM match_teams mt
+            JOIN matches m ON mt.match_id = m.id
+            JOIN match_series ms ON m.series_id = ms.id
+            WHERE ms.event_id = $1
+              AND m.motion IS NOT NULL AND m.status <> 'cancelled'
+              AND mt.final_rank IS NOT NULL
+              AND EXISTS (
+                SELECT 1 FROM ballots b
+                WHERE b.match_id = m.id AND b.is_submitted AND b.is_voting
+              )
+            ORDER BY ms.round_number NULLS LAST, ms.name, m.room_name, m.id
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn list_tab_categories(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<TabCategory>, sqlx::Error> {
+        sqlx::query_as::<_, TabCategory>(
+            "SELECT * FROM event_tab_categories WHERE event_id = $1 ORDER BY category",
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Create or change a category's settings; unset fields keep their
+    /// current (or default) values
+    pub async fn upsert_tab_category(
+        &self,
+        event_id: Uuid,
+        category: EligibilityFilter,
+        changes: &UpdateTabCategoryRequest,
+        updated_by: Uuid,
+    ) -> Result<TabCategory, sqlx::Error> {
+        sqlx::query_as::<_, TabCategory>(
+            r#"
+            INSERT INTO event_tab_categories (event_id, category, break_size, team_tab_released,
+                speaker_tab_released, break_released, updated_by)
+            VALUES ($1, $2, COALESCE($3, 0), COALESCE($4, FALSE), COALESCE($5, FALSE),
+                COALESCE($6, FALSE), $7)
+            ON CONFLICT (event_id, category) DO UPDATE SET
+                break_size = COALESCE($3, event_tab_categories.break_size),
+                team_tab_released = COALESCE($4, event_tab_categories.team_tab_released),
+                speaker_tab_released = COALESCE($5, event_tab_categories.speaker_tab_released),
+                break_released = COALESCE($6, event_tab_categories.break_released),
+                updated_by = $7,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(event_id)
+        .bind(category.as_str())
+        .bind(changes.break_size)
+        .bind(changes.team_tab_released)
+        .bind(changes.speaker_tab_released)
+        .bind(changes.break_released)
+        .bind(updated_by)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn get_tie_breaks(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Option<TieBreakSettings>, sqlx::Error> {
+        sqlx::query_as::<_, TieBreakSettings>(
+            "SELECT id AS event_id, tie_breaks, tie_break_seed FROM events WHERE id = $1",
+        )
+        .bind(event_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    pub async fn set_tie_breaks(
+        &self,
+        event_id: Uuid,
+        rules: &[TieBreak],
+        seed: Option<i64>,
+    ) -> Result<Option<TieBreakSettings>, sqlx::Error> {
+        let rules: Vec<&str> = rules.iter().map(|rule| rule.as_str()).collect();
+        sqlx::query_as::<_, TieBreakSettings>(
+            r#"
+            UPDATE events SET tie_breaks = $2, tie_break_seed = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id AS event_id, tie_breaks, tie_break_seed
+            "#,
+        )
+        .bind(event_id)
+        .bind(rules)
+        .bind(seed)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    // ========================================================================
+    // Team Registration Methods
+    // ========================================================================
+
+    /// Turn team-entry mode on or off. Returns false if the event is missing.
+    pub async fn set_event_team_entry(
+        &self,
+        event_id: Uuid,
+        team_entry: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("UPDATE events SET team_entry = $2, updated_at = NOW() WHERE id = $1")
+                .bind(event_id)
+                .bind(team_entry)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Withhold speaker scores from non-admins, or reveal them. Returns false
+    /// if the event is missing.
+    pub async fn set_event_speaks_withheld(
+        &self,
+        event_id: Uuid,
+        speaks_withheld: bool,
+    ) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("UPDATE events SET speaks_withheld = $2, updated_at = NOW() WHERE id = $1")
+                .bind(event_id)
+                .bind(speaks_withheld)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Create a team with its members, in speaking order
+    pub async fn create_registered_team(
+        &self,
+        name: &str,
+        institution: Option<&str>,
+        created_by: Uuid,
+        member_ids: &[Uuid],
+    ) -> Result<RegisteredTeam, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let team = sqlx::query_as::<_, RegisteredTeam>(
+            r#"
+            INSERT INTO registered_teams (id, name, institution, created_by)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(institution)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for (order, user_id) in member_ids.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO registered_team_members (team_id, user_id, speaker_order)
+                VALUES ($1, $2, $3)
+                "#,
+            )
+            .bind(team.id)
+            .bind(user_id)
+            .bind(order as i32)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(team)
+    }
+
+    pub async fn get_registered_team(
+        &self,
+        team_id: Uuid,
+    ) -> Result<Option<RegisteredTeam>, sqlx::Error> {
+        sqlx::query_as::<_, RegisteredTeam>("SELECT * FROM registered_teams WHERE id = $1")
+            .bind(team_id)
+            .fetch_optional(self.reader())
+            .await
+    }
+
+    pub async fn list_registered_teams_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<RegisteredTeam>, sqlx::Error> {
+        sqlx::query_as::<_, RegisteredTeam>(
+            r#"
+            SELECT t.* FROM registered_teams t
+            JOIN registered_team_members m ON m.team_id = t.id
+            WHERE m.user_id = $1
+            ORDER BY t.name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn list_registered_team_members(
+        &self,
+        team_id: Uuid,
+    ) -> Result<Vec<RegisteredTeamMember>, sqlx::Error> {
+        sqlx::query_as::<_, RegisteredTeamMember>(
+            r#"
+            SELECT m.user_id, u.username, m.speaker_order, m.joined_at
+            FROM registered_team_members m
+            JOIN users u ON m.user_id = u.id
+            WHERE m.team_id = $1
+            ORDER BY m.speaker_order
+            "#,
+        )
+        .bind(team_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    pub async fn update_registered_team(
+        &self,
+        team_id: Uuid,
+        name: Option<&str>,
+        institution: Option<&str>,
+    ) -> Result<Option<RegisteredTeam>, sqlx::Error> {
+        sqlx::query_as::<_, RegisteredTeam>(
+            r#"
+            UPDATE registered_teams SET
+                name = COALESCE($2, name),
+                institution = COALESCE($3, institution),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(team_id)
+        .bind(name)
+        .bind(institution)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Add a member as the team's last speaker. Returns false if they are
+    /// already on the team.
+    pub async fn add_registered_team_member(
+        &self,
+        team_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO registered_team_members (team_id, user_id, speaker_order)
+            SELECT $1, $2, COALESCE(MAX(speaker_order) + 1, 0)
+            FROM registered_team_members WHERE team_id = $1
+            ON CONFLICT (team_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(team_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn remove_registered_team_member(
+        &self,
+        team_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM registered_team_members WHERE team_id = $1 AND user_id = $2")
+                .bind(team_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Events the team is registered for
+    pub async fn list_team_registration_events(
+        &self,
+        team_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT event_id FROM event_team_registrations WHERE team_id = $1",
+        )
+        .bind(team_id)
+        .fetch_all(self.reader())
+        .await
+    }
+
+    /// Usernames among `user_ids` who already speak for a different team
+    /// registered for one of `event_ids`
+    pub async fn find_registration_conflicts(
+        &self,
+        team_id: Uuid,
+        user_ids: &[Uuid],
+        event_ids: &[Uuid],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT u.username
+            FROM event_team_registrations r
+            JOIN registered_team_members m ON m.team_id = r.team_id
+            JOIN users u ON m.user_id = u.id
+            WHERE r.team_id <> $1 AND m.user_id = ANY($2) AND r.event_id = ANY($3)
+            ORDER BY u.username
+            "#,
+        )
+        .bind(team_id)
+        .bind(user_ids)
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Register a team for an event. Returns None if it already is.
+    pub async fn register_team(
+        &self,
+        event_id: Uuid,
+        team_id: Uuid,
+        registered_by: Uuid,
+    ) -> Result<Option<TeamRegistration>, sqlx::Error> {
+        sqlx::query_as::<_, TeamRegistration>(
+            r#"
+            INSERT INTO event_team_registrations (event_id, team_id, registered_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (event_id, team_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(event_id)
+        .bind(team_id)
+        .bind(registered_by)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn withdraw_team(&self, event_id: Uuid, team_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM event_team_registrations WHERE event_id = $1 AND team_id = $2",
+        )
+        .bind(event_id)
+        .bind(team_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_team_registration(
+        &self,
+        event_id: Uuid,
+        team_id: Uuid,
+    ) -> Result<Option<TeamRegistration>, sqlx::Error> {
+        sqlx::query_as::<_, TeamRegistration>(
+            "SELECT * FROM event_team_registrations WHERE event_id = $1 AND team_id = $2",
+        )
+        .bind(event_id)
+        .bind(team_id)
+        .fetch_optional(self.reader())
+        .await
+    }
+
+    pub async fn list_event_registrations(
+        &self,
+        event_id: Uuid,
+    ) -> Result<Vec<(TeamRegistration, RegisteredTeam)>, sqlx::Error> {
+        let registrations = sqlx::query_as::<_, TeamRegistration>(
+            r#"
+            SELECT r.* FROM event_team_registrations r
+            JOIN registered_teams t ON r.team_id = t.id
+            WHERE r.event_id = $1
+            ORDER BY t.name
+            "#,
+        )
+        .bind(event_id)
+        .fetch_all(self.reader())
+        .await?;
+
+        let mut result = Vec::with_capacity(registrations.len());
+        for registration in registrations {
+            if let Some(team) = self.get_registered_team(registration.team_id).await? {
+                result.push((registration, team));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Whether the team has been drawn into any match of the event
+    pub async fn is_team_drawn_in_event(
+        &self,
+        event_id: Uuid,
+        team_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM match_teams t
+                JOIN matches m ON t.match_id = m.id
+                JOIN match_series s ON m.series_id = s.id
+                WHERE s.event_id = $1 AND t.registered_team_id = $2
+            )
+            "#,
+        )
+        .bind(event_id)
+        .bind(team_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Whether the team already occupies a side other than `side_id` in
+    /// the series
+    pub async fn is_team_drawn_in_series(
+        &self,
+        series_id: Uuid,
+        team_id: Uuid,
+        side_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM match_teams t
+                JOIN matches m ON t.match_id = m.id
+                WHERE m.series_id = $1 AND t.registered_team_id = $2 AND t.id <> $3
+            )
+            "#,
+        )
+        .bind(series_id)
+        .bind(team_id)
+        .bind(side_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Put a registered team on a side, replacing the side's speakers with
+    /// the team's lineup, or clear the side when `team` is None. All changes
+    /// are recorded in the allocation history.
+    pub async fn assign_registered_team(
+        &self,
+        event_id: Uuid,
+        side: &MatchTeam,
+        team: Option<(&RegisteredTeam, &[LineupSlot])>,
+        admin_id: Uuid,
+    ) -> Result<MatchTeam, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+
+        let replaced = sqlx::query_as::<_, Allocation>(
+            "SELECT * FROM allocations WHERE team_id = $1 AND role = 'speaker'",
+        )
+        .bind(side.id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for allocation in &replaced {
+            Self::insert_assignment_history(
+                &mut tx,
+                allocation,
+                "deleted",
+                admin_id,
+                now,
+                "Replaced by team assignment",
+            )
+            .await?;
+            sqlx::query("DELETE FROM allocations WHERE id = $1")
+                .bind(allocation.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let updated = sqlx::query_as::<_, MatchTeam>(
+            r#"
+            UPDATE match_teams SET
+                registered_team_id = $2, team_name = $3, institution = $4, updated_at = $5
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(side.id)
+        .bind(team.map(|(t, _)| t.id))
+        .bind(team.map(|(t, _)| t.name.as_str()))
+        .bind(team.and_then(|(t, _)| t.institution.as_deref()))
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for slot in team.map(|(_, lineup)| lineup).unwrap_or_default() {
+            let allocation = sqlx::query_as::<_, Allocation>(
+                r#"
+                INSERT INTO allocations (id, match_id, user_id, role, team_id,
+                    two_team_speaker_role, four_team_speaker_role, is_chair,
+                    allocated_at, allocated_by, was_checked_in, created_at, updated_at)
+                VALUES ($1, $2, $3, 'speaker', $4, $5, $6, false, $7, $8,
+                    COALESCE((SELECT is_checked_in FROM attendance_records
+                        WHERE event_id = $9 AND user_id = $3), false),
+                    $7, $7)
+                RETURNING *
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(side.match_id)
+            .bind(slot.user_id)
+            .bind(side.id)
+            .bind(slot.two_team_speaker_role)
+            .bind(slot.four_team_speaker_role)
+            .bind(now)
+            .bind(admin_id)
+            .bind(event_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            Self::insert_assignment_history(
+                &mut tx,
+                &allocation,
+                "created",
+                admin_id,
+                now,
+                "Team assignment",
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn insert_assignment_history(
+        tx: &mut Transaction<'_, Postgres>,
+        allocation: &Allocation,
+        action: &str,
+        changed_by: Uuid,
+        changed_at: DateTime<Utc>,
+        notes: &str,
+    ) -> Result<(), sqlx::Error> {
+        let (previous, new) = if action == "deleted" {
+            (Some(allocation), None)
+        } else {
+            (None, Some(allocation))
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO allocation_history (id, allocation_id, match_id, user_id, guest_name,
+                action, previous_role, new_role, previous_team_id, new_team_id, changed_by,
+                changed_at, notes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(allocation.id)
+        .bind(allocation.match_id)
+        .bind(allocation.user_id)
+        .bind(&allocation.guest_name)
+        .bind(action)
+        .bind(previous.map(|a| a.role))
+        .bind(new.map(|a| a.role))
+        .bind(previous.and_then(|a| a.team_id))
+        .bind(new.and_then(|a| a.team_id))
+        .bind(changed_by)
+        .bind(changed_at)
+        .bind(notes)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Seed Methods (demo/dev data, see crate::seed)
+    // ========================================================================
+
+    /// Count users whose username matches a `LIKE` pattern
+    pub async fn count_users_like(&self, pattern: &str) -> Result<i64, sqlx::Error> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE username LIKE $1")
+            .bind(pattern)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Delete users matching a `LIKE` pattern along with the events they created.
+    /// Series, matches, allocations and ballots go with the events via cascades.
+    pub async fn delete_users_like(&self, pattern: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM events WHERE created_by IN (SELECT id FROM users WHERE username LIKE $1)",
+        )
+        .bind(pattern)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM users WHERE username LIKE $1")
+            .bind(pattern)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    }
+
+    /// Insert an already-verified user
+    pub async fn insert_seed_user(
+        &self,
+        user: &SeedUser,
+        password_hash: &str,
+        salt: &str,
+        pepper_version: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, email, password_hash, salt, pepper_version,
+                reg_number, year_joined, phone_number, email_verified, email_verified_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, true, NOW())
+            "#,
+        )
+        .bind(user.id)
+        .bind(&user.username)
+        .bind(&user.email)
+        .bind(password_hash)
+        .bind(salt)
+        .bind(pepper_version)
+        .bind(&user.reg_number)
+        .bind(user.year_joined)
+        .bind(&user.phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn grant_admin(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO admin_users (user_id) VALUES ($1) ON CONFLICT DO NOTHING")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert_seed_event(&self, event: &SeedEvent) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO events (id, title, description, event_type, event_date, location, created_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.id)
+        .bind(&event.title)
+        .bind(&event.description)
+        .bind(event.event_type)
+        .bind(event.event_date)
+        .bind(&event.location)
+        .bind(event.created_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a user available for an event, optionally checked in by `checked_in_by`
+    pub async fn insert_seed_attendance(
+        &self,
+        event_id: Uuid,
+        user_id: Uuid,
+        checked_in_by: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO attendance_records (event_id, user_id, is_available, is_checked_in,
+                checked_in_by, checked_in_at)
+            VALUES ($1, $2, true, $3, $4, CASE WHEN $3 THEN NOW() END)
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .bind(checked_in_by.is_some())
+        .bind(checked_in_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 }
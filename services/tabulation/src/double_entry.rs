@@ -0,0 +1,241 @@
+//! Double entry of paper ballots in break rounds.
+//!
+//! Two admins each type in the chair's paper ballot. Nothing is recorded
+//! until the latest two entries, from different admins, agree on every
+//! speaker's score and every team's rank; until then their differences are
+//! listed so the admins can check the paper and correct their entry.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::models::{
+    BallotEntry, BallotEntryRanking, BallotEntryScore, SpeakerScoreInput, SubmitBallotRequest,
+    TeamRankingInput,
+};
+
+/// An admin's entry with its scores and rankings
+#[derive(Debug, Clone, Serialize)]
+pub struct EnteredBallot {
+    #[serde(flatten)]
+    pub entry: BallotEntry,
+    pub speaker_scores: Vec<BallotEntryScore>,
+    pub team_rankings: Vec<BallotEntryRanking>,
+}
+
+/// Something two entries disagree on; `None` where an entry leaves it out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum Discrepancy {
+    Score {
+        allocation_id: Uuid,
+        first: Option<Decimal>,
+        second: Option<Decimal>,
+    },
+    Rank {
+        team_id: Uuid,
+        first: Option<i32>,
+        second: Option<i32>,
+    },
+    Winner {
+        team_id: Uuid,
+        first: Option<bool>,
+        second: Option<bool>,
+    },
+}
+
+impl EnteredBallot {
+    fn score(&self, allocation_id: Uuid) -> Option<Decimal> {
+        self.speaker_scores
+            .iter()
+            .find(|s| s.allocation_id == allocation_id)
+            .map(|s| s.score)
+    }
+
+    fn ranking(&self, team_id: Uuid) -> Option<&BallotEntryRanking> {
+        self.team_rankings.iter().find(|r| r.team_id == team_id)
+    }
+
+    /// Where `second` differs from this entry, speakers first, in entry order
+    pub fn discrepancies(&self, second: &EnteredBallot) -> Vec<Discrepancy> {
+        let mut found = Vec::new();
+
+        let mut speakers: Vec<Uuid> = Vec::new();
+        for score in self.speaker_scores.iter().chain(&second.speaker_scores) {
+            if !speakers.contains(&score.allocation_id) {
+                speakers.push(score.allocation_id);
+            }
+        }
+        for allocation_id in speakers {
+            let (first, second) = (self.score(allocation_id), second.score(allocation_id));
+            if first != second {
+                found.push(Discrepancy::Score {
+                    allocation_id,
+                    first,
+                    second,
+                });
+            }
+        }
+
+        let mut teams: Vec<Uuid> = Vec::new();
+        for ranking in self.team_rankings.iter().chain(&second.team_rankings) {
+            if !teams.contains(&ranking.team_id) {
+                teams.push(ranking.team_id);
+            }
+        }
+        for team_id in teams {
+            let (first, second) = (self.ranking(team_id), second.ranking(team_id));
+            let (first_rank, second_rank) = (first.map(|r| r.rank), second.map(|r| r.rank));
+            if first_rank != second_rank {
+                found.push(Discrepancy::Rank {
+                    team_id,
+                    first: first_rank,
+                    second: second_rank,
+                });
+            } else if let (Some(first), Some(second)) = (first, second) {
+                if first.is_winner != second.is_winner {
+                    found.push(Discrepancy::Winner {
+                        team_id,
+                        first: first.is_winner,
+                        second: second.is_winner,
+                    });
+                }
+            }
+        }
+
+        found
+    }
+
+    /// The ballot to record once the entries agree
+    pub fn submission(&self) -> SubmitBallotRequest {
+        SubmitBallotRequest {
+            match_id: self.entry.match_id,
+            notes: self.entry.notes.clone(),
+            speaker_scores: self
+                .speaker_scores
+                .iter()
+                .map(|s| SpeakerScoreInput {
+                    allocation_id: s.allocation_id,
+                    score: s.score.to_f64().unwrap_or_default(),
+                    feedback: s.feedback.clone(),
+                })
+                .collect(),
+            team_rankings: self
+                .team_rankings
+                .iter()
+                .map(|r| TeamRankingInput {
+                    team_id: r.team_id,
+                    rank: r.rank,
+                    is_winner: r.is_winner,
+                })
+                .collect(),
+            confirm_low_point_win: self.entry.confirm_low_point_win,
+            // Paper ballots carry notes only as the free-text field
+            speaker_notes: Vec::new(),
+        }
+    }
+}
+
+/// The two entries to compare: the latest two, which are by different
+/// admins as each admin keeps one entry per room. `entries` are oldest first.
+pub fn latest_pair(entries: &[EnteredBallot]) -> Option<(&EnteredBallot, &EnteredBallot)> {
+    match entries {
+        [.., first, second] => Some((first, second)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn entered(scores: &[(u128, i64)], ranks: &[(u128, i32)]) -> EnteredBallot {
+        let entry_id = Uuid::new_v4();
+        EnteredBallot {
+            entry: BallotEntry {
+                id: entry_id,
+                match_id: id(99),
+                entered_by: Uuid::new_v4(),
+                notes: None,
+                confirm_low_point_win: false,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            },
+            speaker_scores: scores
+                .iter()
+                .map(|&(allocation, score)| BallotEntryScore {
+                    entry_id,
+                    allocation_id: id(allocation),
+                    score: Decimal::from(score),
+                    feedback: None,
+                })
+                .collect(),
+            team_rankings: ranks
+                .iter()
+                .map(|&(team, rank)| BallotEntryRanking {
+                    entry_id,
+                    team_id: id(team),
+                    rank,
+                    is_winner: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_matching_entries_have_no_discrepancies() {
+        let first = entered(&[(1, 75), (2, 74)], &[(10, 1), (20, 2)]);
+        let second = entered(&[(2, 74), (1, 75)], &[(20, 2), (10, 1)]);
+        assert!(first.discrepancies(&second).is_empty());
+    }
+
+    #[test]
+    fn test_differences_are_listed_per_speaker_and_team() {
+        let first = entered(&[(1, 75), (2, 74)], &[(10, 1), (20, 2)]);
+        let second = entered(&[(1, 76)], &[(10, 2), (20, 1)]);
+        assert_eq!(
+            first.discrepancies(&second),
+            [
+                Discrepancy::Score {
+                    allocation_id: id(1),
+                    first: Some(Decimal::from(75)),
+                    second: Some(Decimal::from(76)),
+                },
+                Discrepancy::Score {
+                    allocation_id: id(2),
+                    first: Some(Decimal::from(74)),
+                    second: None,
+                },
+                Discrepancy::Rank {
+                    team_id: id(10),
+                    first: Some(1),
+                    second: Some(2),
+                },
+                Discrepancy::Rank {
+                    team_id: id(20),
+                    first: Some(2),
+                    second: Some(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_latest_two_entries_are_compared() {
+        let entries = [
+            entered(&[(1, 70)], &[]),
+            entered(&[(1, 71)], &[]),
+            entered(&[(1, 72)], &[]),
+        ];
+        let (first, second) = latest_pair(&entries).unwrap();
+        assert_eq!(first.score(id(1)), Some(Decimal::from(71)));
+        assert_eq!(second.score(id(1)), Some(Decimal::from(72)));
+        assert!(latest_pair(&entries[..1]).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//! Speaker eligibility for category tabs and breaks.
+//!
+//! Every user records whether they are a novice and whether they speak
+//! English as a second language. A speaker counts towards a category's tab
+//! when they are eligible for it; a team counts when all of its speakers
+//! are.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::models::SpeakerEligibility;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EligibilityFilter {
+    /// Everyone
+    Open,
+    Novice,
+    Esl,
+}
+
+impl EligibilityFilter {
+    pub const ALL: &'static [EligibilityFilter] = &[
+        EligibilityFilter::Open,
+        EligibilityFilter::Novice,
+        EligibilityFilter::Esl,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EligibilityFilter::Open => "open",
+            EligibilityFilter::Novice => "novice",
+            EligibilityFilter::Esl => "esl",
+        }
+    }
+
+    pub fn admits(self, speaker: &SpeakerEligibility) -> bool {
+        self.admits_flags(speaker.is_novice, speaker.is_esl)
+    }
+
+    /// Same as `admits`, for speakers known only by their flags. Guests
+    /// have neither.
+    pub fn admits_flags(self, is_novice: bool, is_esl: bool) -> bool {
+        match self {
+            EligibilityFilter::Open => true,
+            EligibilityFilter::Novice => is_novice,
+            EligibilityFilter::Esl => is_esl,
+        }
+    }
+
+    /// A team is eligible when it has speakers and every one of them is
+    pub fn admits_team(self, speakers: &[SpeakerEligibility]) -> bool {
+        !speakers.is_empty() && speakers.iter().all(|s| self.admits(s))
+    }
+
+    /// SQL condition selecting eligible rows of `users` aliased as `alias`
+    pub fn sql_condition(self, alias: &str) -> String {
+        match self {
+            EligibilityFilter::Open => "TRUE".to_string(),
+            EligibilityFilter::Novice => format!("{}.is_novice", alias),
+            EligibilityFilter::Esl => format!("{}.is_esl", alias),
+        }
+    }
+}
+
+impl fmt::Display for EligibilityFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EligibilityFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EligibilityFilter::ALL
+            .iter()
+            .copied()
+            .find(|filter| filter.as_str() == s)
+            .ok_or_else(|| format!("Unknown eligibility category: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn speaker(is_novice: bool, is_esl: bool) -> SpeakerEligibility {
+        SpeakerEligibility {
+            user_id: Uuid::new_v4(),
+            username: "speaker".to_string(),
+            institution_id: None,
+            institution_name: None,
+            is_novice,
+            is_esl,
+        }
+    }
+
+    #[test]
+    fn test_team_needs_every_speaker_eligible() {
+        let mixed = [speaker(true, false), speaker(false, false)];
+        assert!(EligibilityFilter::Open.admits_team(&mixed));
+        assert!(!EligibilityFilter::Novice.admits_team(&mixed));
+
+        let novices = [speaker(true, true), speaker(true, false)];
+        assert!(EligibilityFilter::Novice.admits_team(&novices));
+        assert!(!EligibilityFilter::Esl.admits_team(&novices));
+        assert!(!EligibilityFilter::Open.admits_team(&[]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for filter in EligibilityFilter::ALL {
+            assert_eq!(filter.as_str().parse::<EligibilityFilter>(), Ok(*filter));
+        }
+        assert!("pro_am".parse::<EligibilityFilter>().is_err());
+    }
+
+    #[test]
+    fn test_sql_condition() {
+        assert_eq!(EligibilityFilter::Novice.sql_condition("u"), "u.is_novice");
+        assert_eq!(EligibilityFilter::Open.sql_condition("u"), "TRUE");
+    }
+}
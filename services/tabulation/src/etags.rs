@@ -0,0 +1,37 @@
+//! Conditional GETs for the match and series endpoints that clients poll
+//! during rounds. Each middleware looks up the resource's version and lets
+//! [`common::etag::conditional_get`] answer unchanged requests with a 304.
+
+use axum::{
+    extract::{Path, Request, State},
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use common::etag::conditional_get;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub async fn match_etag(
+    State(state): State<Arc<AppState>>,
+    viewer: Option<Extension<Uuid>>,
+    Path(match_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let version = state.db.match_version(match_id).await.ok().flatten();
+    conditional_get(request, next, version, viewer.map(|Extension(id)| id)).await
+}
+
+pub async fn series_etag(
+    State(state): State<Arc<AppState>>,
+    viewer: Option<Extension<Uuid>>,
+    Path(series_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let version = state.db.series_version(series_id).await.ok().flatten();
+    conditional_get(request, next, version, viewer.map(|Extension(id)| id)).await
+}
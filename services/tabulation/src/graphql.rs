@@ -0,0 +1,612 @@
+//! Read-only GraphQL view of the tabulation data.
+//!
+//! Events lead to their series, series to their matches, and matches to
+//! teams, allocations and ballots, so a match page takes one request instead
+//! of several. Fields follow the REST release rules: rankings and speaks show
+//! once released (see `ResultVisibility`), ballots only to admins and the
+//! adjudicator who owns them, and browsing events and series needs a login
+//! just as the REST listings do.
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Error, Object, Result, Schema, SimpleObject,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{
+    AllocationRole, AllocationWithUser, Ballot, EventInfo, FourTeamPosition, FourTeamSpeakerRole,
+    Match, MatchSeries, MatchStatus, MatchTeam, ResultVisibility, TeamFormat, TwoTeamPosition,
+    TwoTeamSpeakerRole,
+};
+use crate::AppState;
+
+/// Queries nested deeper than this are refused
+const MAX_DEPTH: usize = 10;
+
+pub type TabrelaSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema() -> TabrelaSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+/// Who is asking, attached to every request alongside the app state
+#[derive(Debug, Clone, Copy)]
+pub struct Viewer {
+    pub user_id: Option<Uuid>,
+    pub is_admin: bool,
+}
+
+fn state<'a>(ctx: &Context<'a>) -> &'a AppState {
+    ctx.data_unchecked::<Arc<AppState>>()
+}
+
+fn viewer(ctx: &Context<'_>) -> Viewer {
+    *ctx.data_unchecked::<Viewer>()
+}
+
+fn require_login(ctx: &Context<'_>) -> Result<Viewer> {
+    let viewer = viewer(ctx);
+    match viewer.user_id {
+        Some(_) => Ok(viewer),
+        None => Err(Error::new("Login required")),
+    }
+}
+
+fn db_error(e: sqlx::Error) -> Error {
+    tracing::error!("GraphQL query failed: {:?}", e);
+    Error::new("Database error")
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// An event and its rounds (login required)
+    async fn event(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<Event>> {
+        require_login(ctx)?;
+        let event = state(ctx).db.get_event_by_id(id).await.map_err(db_error)?;
+        Ok(event.map(Event))
+    }
+
+    /// A round and its rooms (login required)
+    async fn series(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<Series>> {
+        require_login(ctx)?;
+        let series = state(ctx).db.get_series_by_id(id).await.map_err(db_error)?;
+        Ok(series.map(Series))
+    }
+
+    /// A match as its public page shows it
+    #[graphql(name = "match")]
+    async fn match_(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<MatchNode>> {
+        let db = &state(ctx).db;
+        let Some(record) = db.get_match_by_id(id).await.map_err(db_error)? else {
+            return Ok(None);
+        };
+        let Some(series) = db
+            .get_series_by_id(record.series_id)
+            .await
+            .map_err(db_error)?
+        else {
+            return Ok(None);
+        };
+        let speaks_withheld = speaks_withheld(ctx, series.event_id).await?;
+        Ok(Some(MatchNode::new(
+            record,
+            series,
+            speaks_withheld,
+            viewer(ctx),
+        )))
+    }
+}
+
+async fn speaks_withheld(ctx: &Context<'_>, event_id: Uuid) -> Result<bool> {
+    let event = state(ctx)
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(db_error)?;
+    Ok(event.is_some_and(|e| e.speaks_withheld))
+}
+
+pub struct Event(EventInfo);
+
+#[Object]
+impl Event {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.0.title
+    }
+
+    async fn is_locked(&self) -> bool {
+        self.0.is_locked
+    }
+
+    async fn team_entry(&self) -> bool {
+        self.0.team_entry
+    }
+
+    async fn speaks_withheld(&self) -> bool {
+        self.0.speaks_withheld
+    }
+
+    async fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.0.archived_at
+    }
+
+    /// Rounds in order
+    async fn series(&self, ctx: &Context<'_>) -> Result<Vec<Series>> {
+        let series = state(ctx)
+            .db
+            .list_event_series(self.0.id)
+            .await
+            .map_err(db_error)?;
+        Ok(series.into_iter().map(Series).collect())
+    }
+}
+
+pub struct Series(MatchSeries);
+
+#[Object]
+impl Series {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn event_id(&self) -> Uuid {
+        self.0.event_id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.description.as_deref()
+    }
+
+    async fn round_number(&self) -> Option<i32> {
+        self.0.round_number
+    }
+
+    async fn team_format(&self) -> TeamFormat {
+        self.0.team_format
+    }
+
+    async fn allow_reply_speeches(&self) -> bool {
+        self.0.allow_reply_speeches
+    }
+
+    async fn is_break_round(&self) -> bool {
+        self.0.is_break_round
+    }
+
+    async fn is_silent(&self) -> bool {
+        self.0.is_silent
+    }
+
+    async fn is_training(&self) -> bool {
+        self.0.is_training
+    }
+
+    async fn double_entry(&self) -> bool {
+        self.0.double_entry
+    }
+
+    async fn event(&self, ctx: &Context<'_>) -> Result<Option<Event>> {
+        let event = state(ctx)
+            .db
+            .get_event_by_id(self.0.event_id)
+            .await
+            .map_err(db_error)?;
+        Ok(event.map(Event))
+    }
+
+    /// Rooms by name
+    async fn matches(&self, ctx: &Context<'_>) -> Result<Vec<MatchNode>> {
+        let matches = state(ctx)
+            .db
+            .list_series_matches(self.0.id)
+            .await
+            .map_err(db_error)?;
+        let speaks_withheld = speaks_withheld(ctx, self.0.event_id).await?;
+        let viewer = viewer(ctx);
+        Ok(matches
+            .into_iter()
+            .map(|record| MatchNode::new(record, self.0.clone(), speaks_withheld, viewer))
+            .collect())
+    }
+}
+
+pub struct MatchNode {
+    record: Match,
+    series: MatchSeries,
+    speaks_withheld: bool,
+    visibility: ResultVisibility,
+}
+
+impl MatchNode {
+    fn new(record: Match, series: MatchSeries, speaks_withheld: bool, viewer: Viewer) -> Self {
+        let visibility =
+            ResultVisibility::new(&record, series.is_silent, speaks_withheld, viewer.is_admin);
+        Self {
+            record,
+            series,
+            speaks_withheld,
+            visibility,
+        }
+    }
+}
+
+#[Object(name = "Match")]
+impl MatchNode {
+    async fn id(&self) -> Uuid {
+        self.record.id
+    }
+
+    async fn series_id(&self) -> Uuid {
+        self.record.series_id
+    }
+
+    async fn room_name(&self) -> Option<&str> {
+        self.record.room_name.as_deref()
+    }
+
+    async fn venue_id(&self) -> Option<Uuid> {
+        self.record.venue_id
+    }
+
+    async fn motion(&self) -> Option<&str> {
+        self.record.motion.as_deref()
+    }
+
+    async fn info_slide(&self) -> Option<&str> {
+        self.record.info_slide.as_deref()
+    }
+
+    async fn status(&self) -> MatchStatus {
+        self.record.status
+    }
+
+    async fn scheduled_time(&self) -> Option<DateTime<Utc>> {
+        self.record.scheduled_time
+    }
+
+    async fn scores_released(&self) -> bool {
+        self.record.scores_released
+    }
+
+    async fn rankings_released(&self) -> bool {
+        self.record.rankings_released
+    }
+
+    /// Scores are released but the event is holding speaks back
+    async fn speaks_withheld(&self) -> bool {
+        self.record.scores_released && self.speaks_withheld
+    }
+
+    async fn is_silent(&self) -> bool {
+        self.series.is_silent
+    }
+
+    async fn series(&self) -> Series {
+        Series(self.series.clone())
+    }
+
+    async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<TeamNode>> {
+        let teams = state(ctx)
+            .db
+            .list_teams_by_match(self.record.id)
+            .await
+            .map_err(db_error)?;
+        Ok(teams
+            .into_iter()
+            .map(|team| TeamNode {
+                team,
+                visibility: self.visibility,
+            })
+            .collect())
+    }
+
+    /// Judges on the panel, voting and trainee
+    async fn adjudicators(&self, ctx: &Context<'_>) -> Result<Vec<AllocationNode>> {
+        let allocations = state(ctx)
+            .db
+            .list_allocations_by_match(self.record.id)
+            .await
+            .map_err(db_error)?;
+        Ok(allocations
+            .into_iter()
+            .filter(|a| {
+                matches!(
+                    a.role,
+                    AllocationRole::VotingAdjudicator | AllocationRole::NonVotingAdjudicator
+                )
+            })
+            .map(|allocation| AllocationNode {
+                allocation,
+                visibility: self.visibility,
+            })
+            .collect())
+    }
+
+    /// Every ballot for admins, the viewer's own for an adjudicator, and
+    /// none for anyone else
+    async fn ballots(&self, ctx: &Context<'_>) -> Result<Vec<BallotNode>> {
+        let viewer = viewer(ctx);
+        if viewer.user_id.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let ballots = state(ctx)
+            .db
+            .list_ballots_by_match(self.record.id)
+            .await
+            .map_err(db_error)?;
+        Ok(ballots
+            .into_iter()
+            .filter(|b| viewer.is_admin || Some(b.adjudicator_id) == viewer.user_id)
+            .map(BallotNode)
+            .collect())
+    }
+}
+
+pub struct TeamNode {
+    team: MatchTeam,
+    visibility: ResultVisibility,
+}
+
+#[Object(name = "Team")]
+impl TeamNode {
+    async fn id(&self) -> Uuid {
+        self.team.id
+    }
+
+    async fn two_team_position(&self) -> Option<TwoTeamPosition> {
+        self.team.two_team_position
+    }
+
+    async fn four_team_position(&self) -> Option<FourTeamPosition> {
+        self.team.four_team_position
+    }
+
+    async fn team_name(&self) -> Option<&str> {
+        self.team.team_name.as_deref()
+    }
+
+    async fn institution(&self) -> Option<&str> {
+        self.team.institution.as_deref()
+    }
+
+    async fn institution_id(&self) -> Option<Uuid> {
+        self.team.institution_id
+    }
+
+    async fn registered_team_id(&self) -> Option<Uuid> {
+        self.team.registered_team_id
+    }
+
+    /// Hidden until rankings are released
+    async fn final_rank(&self) -> Option<i32> {
+        self.team.final_rank.filter(|_| self.visibility.rankings)
+    }
+
+    /// Hidden until speaks are released
+    async fn total_speaker_points(&self) -> Option<Decimal> {
+        self.team
+            .total_speaker_points
+            .filter(|_| self.visibility.speaks)
+    }
+
+    /// Speakers and resources on the team
+    async fn allocations(&self, ctx: &Context<'_>) -> Result<Vec<AllocationNode>> {
+        let allocations = state(ctx)
+            .db
+            .list_allocations_by_team(self.team.id)
+            .await
+            .map_err(db_error)?;
+        Ok(allocations
+            .into_iter()
+            .map(|allocation| AllocationNode {
+                allocation,
+                visibility: self.visibility,
+            })
+            .collect())
+    }
+}
+
+pub struct AllocationNode {
+    allocation: AllocationWithUser,
+    visibility: ResultVisibility,
+}
+
+#[Object(name = "Allocation")]
+impl AllocationNode {
+    async fn id(&self) -> Uuid {
+        self.allocation.id
+    }
+
+    async fn user_id(&self) -> Option<Uuid> {
+        self.allocation.user_id
+    }
+
+    async fn guest_name(&self) -> Option<&str> {
+        self.allocation.guest_name.as_deref()
+    }
+
+    async fn username(&self) -> &str {
+        &self.allocation.username
+    }
+
+    async fn role(&self) -> AllocationRole {
+        self.allocation.role
+    }
+
+    async fn team_id(&self) -> Option<Uuid> {
+        self.allocation.team_id
+    }
+
+    async fn two_team_speaker_role(&self) -> Option<TwoTeamSpeakerRole> {
+        self.allocation.two_team_speaker_role
+    }
+
+    async fn four_team_speaker_role(&self) -> Option<FourTeamSpeakerRole> {
+        self.allocation.four_team_speaker_role
+    }
+
+    async fn is_chair(&self) -> bool {
+        self.allocation.is_chair.unwrap_or(false)
+    }
+
+    /// A speaker's score averaged over the voting ballots, once speaks are
+    /// released
+    async fn score(&self, ctx: &Context<'_>) -> Result<Option<Decimal>> {
+        if self.allocation.role != AllocationRole::Speaker || !self.visibility.speaks {
+            return Ok(None);
+        }
+        state(ctx)
+            .db
+            .get_allocation_average_score(self.allocation.id)
+            .await
+            .map_err(db_error)
+    }
+
+    /// Whether an adjudicator has submitted their ballot
+    async fn has_submitted(&self, ctx: &Context<'_>) -> Result<bool> {
+        let Some(user_id) = self.allocation.user_id else {
+            return Ok(false);
+        };
+        let ballot = state(ctx)
+            .db
+            .get_ballot_by_adjudicator_match(self.allocation.match_id, user_id)
+            .await
+            .map_err(db_error)?;
+        Ok(ballot.is_some_and(|b| b.is_submitted))
+    }
+}
+
+pub struct BallotNode(Ballot);
+
+#[derive(SimpleObject)]
+#[graphql(name = "SpeakerScore")]
+pub struct SpeakerScoreNode {
+    allocation_id: Uuid,
+    score: Decimal,
+    feedback: Option<String>,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "TeamRanking")]
+pub struct TeamRankingNode {
+    team_id: Uuid,
+    rank: i32,
+    is_winner: Option<bool>,
+    total_speaks: Option<Decimal>,
+    margin: Option<Decimal>,
+}
+
+#[Object(name = "Ballot")]
+impl BallotNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn adjudicator_id(&self) -> Uuid {
+        self.0.adjudicator_id
+    }
+
+    async fn is_voting(&self) -> bool {
+        self.0.is_voting
+    }
+
+    async fn is_submitted(&self) -> bool {
+        self.0.is_submitted
+    }
+
+    async fn submitted_at(&self) -> Option<DateTime<Utc>> {
+        self.0.submitted_at
+    }
+
+    async fn notes(&self) -> Option<&str> {
+        self.0.notes.as_deref()
+    }
+
+    async fn low_point_win(&self) -> bool {
+        self.0.low_point_win
+    }
+
+    async fn scores(&self, ctx: &Context<'_>) -> Result<Vec<SpeakerScoreNode>> {
+        let scores = state(ctx)
+            .db
+            .list_speaker_scores_by_ballot(self.0.id)
+            .await
+            .map_err(db_error)?;
+        Ok(scores
+            .into_iter()
+            .map(|s| SpeakerScoreNode {
+                allocation_id: s.allocation_id,
+                score: s.score,
+                feedback: s.feedback,
+            })
+            .collect())
+    }
+
+    async fn rankings(&self, ctx: &Context<'_>) -> Result<Vec<TeamRankingNode>> {
+        let rankings = state(ctx)
+            .db
+            .list_team_rankings_by_ballot(self.0.id)
+            .await
+            .map_err(db_error)?;
+        Ok(rankings
+            .into_iter()
+            .map(|r| TeamRankingNode {
+                team_id: r.team_id,
+                rank: r.rank,
+                is_winner: r.is_winner,
+                total_speaks: r.total_speaks,
+                margin: r.margin,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::Request;
+
+    fn anonymous(query: &str) -> Request {
+        Request::new(query).data(Viewer {
+            user_id: None,
+            is_admin: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_browsing_events_needs_a_login() {
+        let response = schema()
+            .execute(anonymous(
+                "{ event(id: \"00000000-0000-0000-0000-000000000000\") { title } }",
+            ))
+            .await;
+        assert_eq!(response.errors[0].message, "Login required");
+    }
+
+    #[tokio::test]
+    async fn test_deep_queries_are_refused() {
+        let nesting = "series { matches { ".repeat(MAX_DEPTH);
+        let query = format!(
+            "{{ match(id: \"00000000-0000-0000-0000-000000000000\") {{ {}id{} }} }}",
+            nesting,
+            " } }".repeat(MAX_DEPTH)
+        );
+        let response = schema().execute(anonymous(&query)).await;
+        assert_eq!(response.errors[0].message, "Query is nested too deep.");
+    }
+}
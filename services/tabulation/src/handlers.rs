@@ -1,29 +1,67 @@
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse, Redirect, Response,
+    },
     Extension, Json,
 };
 use chrono::Utc;
-use rust_decimal::Decimal;
+use common::{
+    auth_middleware::EventAccess,
+    error::{api_error, db_error},
+    pending_actions::{self, ProtectedAction},
+    storage::StorageError,
+    Calendar, CalendarEntry, Feature, NotificationKind, Pagination,
+};
+use futures_util::{stream, Stream, StreamExt};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
 use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    database::UpdateAllocationParams,
+    archive, attachments,
+    ballot_feed::BallotUpdate,
+    database::{UpdateAllocationParams, UpdateMatchParams},
+    double_entry,
+    eligibility::EligibilityFilter,
+    graphql, judge_stats, margins,
     models::{
-        AdjudicatorResponse, Allocation, AllocationHistory, AllocationHistoryResponse,
-        AllocationPoolResponse, AllocationRole, Ballot, BallotResponse, CheckedInUserResponse,
-        CreateAllocationRequest, CreateMatchRequest, CreateSeriesRequest, CurrentAllocationInfo,
+        AddTeamMemberRequest, AdjudicatorResponse, Allocation, AllocationHistory,
+        AllocationHistoryResponse, AllocationPoolResponse, AllocationRole, AllocationWithUser,
+        AssignTeamRequest, Attachment, Ballot, BallotEntry, BallotEntryRanking, BallotEntryRequest,
+        BallotEntryScore, BallotResponse, CalendarAllocation, CheckedInUserResponse,
+        CloneStructureQuery, CreateAllocationRequest, CreateAttachmentRequest,
+        CreateInstitutionRequest, CreateMatchRequest, CreateRegisteredTeamRequest,
+        CreateSeriesRequest, CreateVenueRequest, CurrentAllocationInfo, DoubleEntryRequest,
+        EligibilityQuery, EventInfo, EventRegistrationResponse, JudgeStatsQuery, JudgingRecord,
         Match, MatchListQuery, MatchListResponse, MatchResponse, MatchSeries, MatchStatus,
-        MatchTeamResponse, PerformanceQuery, PerformanceResponse, RankingCount,
-        ReleaseToggleRequest, ResourceResponse, SeriesListQuery, SeriesListResponse,
-        SeriesResponse, SpeakerResponse, SpeakerScore, SpeakerScoreResponse, SubmitBallotRequest,
-        SubmitFeedbackRequest, SwapAllocationRequest, TeamFormat, TeamRanking, TeamRankingResponse,
-        UpdateAllocationRequest, UpdateMatchRequest, UpdateSeriesRequest, UpdateTeamRequest,
+        MatchTeam, MatchTeamResponse, PerformanceQuery, PerformanceResponse, RegisterTeamRequest,
+        RegisteredTeam, RegisteredTeamMember, RegisteredTeamResponse, ReleaseToggleRequest,
+        ResourceResponse, ResultVisibility, ScheduleConflict, ScheduleQuery, ScoreTimelineQuery,
+        SeriesListQuery, SeriesListResponse, SeriesResponse, SetAccreditationRequest,
+        SilentRoundRequest, SimulateStandingsQuery, SpeakerNoteInput, SpeakerNoteResponse,
+        SpeakerResponse, SpeakerScore, SpeakerScoreInput, SpeakerScoreResponse,
+        SubmitBallotRequest, SubmitFeedbackRequest, SwapAllocationRequest, TabCategory, TabQuery,
+        TeamEntryRequest, TeamFormat, TeamRanking, TeamRankingInput, TeamRankingResponse,
+        TrainingBallotsResponse, TrainingMatchBallots, TrainingRoundRequest, TrainingSeriesQuery,
+        UpdateAllocationRequest, UpdateEligibilityRequest, UpdateInstitutionRequest,
+        UpdateMatchRequest, UpdateRegisteredTeamRequest, UpdateSeriesRequest,
+        UpdateTabCategoryRequest, UpdateTeamRequest, UpdateTieBreaksRequest, UpdateVenueRequest,
+        Venue, WalkInPoolResponse, WithholdSpeaksRequest,
     },
-    AppState,
+    motion_stats, notifications, printables, results_cache,
+    rotation::{self, DrawnPosition, Position, RoundPosition},
+    score_timeline, simulate, suggestions,
+    tab::{self, TieBreak},
+    tabbycat, teams, undo, AppState,
 };
 
 // ============================================================================
@@ -42,6 +80,9 @@ pub async fn create_series(
             Json(json!({"error": e.to_string()})),
         )
     })?;
+    if payload.double_entry && !payload.is_break_round {
+        return Err(double_entry_break_rounds_only());
+    }
 
     // Verify event exists
     let event = state
@@ -56,11 +97,20 @@ pub async fn create_series(
             )
         })?;
 
-    if event.is_none() {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(json!({"error": "Event not found"})),
-        ));
+    match event {
+        None => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            ));
+        }
+        Some(event) if event.archived_at.is_some() => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "Event is archived and read-only"})),
+            ));
+        }
+        Some(_) => {}
     }
 
     let now = Utc::now();
@@ -73,6 +123,9 @@ pub async fn create_series(
         team_format: payload.team_format,
         allow_reply_speeches: payload.allow_reply_speeches,
         is_break_round: payload.is_break_round,
+        is_silent: payload.is_silent,
+        is_training: payload.is_training,
+        double_entry: payload.double_entry,
         created_by: admin_id,
         created_at: now,
         updated_at: now,
@@ -102,13 +155,77 @@ pub async fn create_series(
     ))
 }
 
+/// Copy another event's series (names, formats, round numbers, reply and
+/// round settings) into this one, without matches, so a recurring event
+/// need not be rebuilt by hand (admin only)
+pub async fn clone_event_structure(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<CloneStructureQuery>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if query.from == event_id {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "An event cannot be cloned into itself"})),
+        ));
+    }
+    for id in [event_id, query.from] {
+        let event = state.db.get_event_by_id(id).await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+        if event.is_none() {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found", "event_id": id})),
+            ));
+        }
+    }
+
+    // Round numbers are not unique (friendlies share them), so cloning into
+    // an event with series would silently double its structure
+    let (_, existing) = state
+        .db
+        .list_series_by_event(event_id, 1, 1)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    if existing > 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Event already has series; clone only into an empty event"})),
+        ));
+    }
+
+    let series = state
+        .db
+        .clone_series_structure(query.from, event_id, admin_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to clone series"))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": format!("Cloned {} series", series.len()),
+            "series": series
+        })),
+    ))
+}
+
 /// List series for an event
 pub async fn list_series(
     State(state): State<Arc<AppState>>,
     Query(query): Query<SeriesListQuery>,
 ) -> Result<Json<SeriesListResponse>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let pagination = Pagination::new(query.page, query.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
 
     let (series_list, total) = state
         .db
@@ -133,13 +250,16 @@ pub async fn list_series(
             team_format: s.team_format,
             allow_reply_speeches: s.allow_reply_speeches,
             is_break_round: s.is_break_round,
+            is_silent: s.is_silent,
+            is_training: s.is_training,
+            double_entry: s.double_entry,
             match_count,
             created_at: s.created_at,
             updated_at: s.updated_at,
         });
     }
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(SeriesListResponse {
         series: series_responses,
@@ -187,6 +307,9 @@ pub async fn get_series(
         team_format: series.team_format,
         allow_reply_speeches: series.allow_reply_speeches,
         is_break_round: series.is_break_round,
+        is_silent: series.is_silent,
+        is_training: series.is_training,
+        double_entry: series.double_entry,
         match_count,
         created_at: series.created_at,
         updated_at: series.updated_at,
@@ -240,13 +363,124 @@ pub async fn update_series(
     })))
 }
 
-/// Delete a series (admin only)
-pub async fn delete_series(
+/// Make a series silent, or reveal it (admin only). Results of a silent
+/// series stay hidden from participants whatever its matches' release flags
+/// say; revealing it announces every match already released.
+pub async fn set_series_silent(
     State(state): State<Arc<AppState>>,
     Path(series_id): Path<Uuid>,
+    Json(payload): Json<SilentRoundRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    // Verify series exists
-    state
+    let existing = state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    let updated = state
+        .db
+        .set_series_silent(series_id, payload.is_silent)
+        .await
+        .map_err(|e| write_error(e, "Failed to update series"))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    if existing.is_silent != updated.is_silent {
+        refresh_performance(&state, updated.event_id).await;
+    }
+
+    let mut revealed = 0;
+    if existing.is_silent && !updated.is_silent {
+        let (matches, _) = state
+            .db
+            .list_matches_by_series(series_id, 1, 1000)
+            .await
+            .unwrap_or_default();
+        for match_record in matches {
+            if match_record.scores_released || match_record.rankings_released {
+                revealed += 1;
+                notifications::announce(&state, NotificationKind::ResultsReleased, match_record);
+            }
+        }
+    }
+
+    Ok(Json(json!({
+        "message": "Series updated successfully",
+        "series": updated,
+        "revealed_matches": revealed
+    })))
+}
+
+/// Mark a series as training, or as a real round (admin only). Training
+/// ballots are collected as usual but left out of performance stats,
+/// season standings and tabs; trainers read them under `/training`.
+pub async fn set_series_training(
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<Uuid>,
+    Json(payload): Json<TrainingRoundRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let existing = state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    let updated = state
+        .db
+        .set_series_training(series_id, payload.is_training)
+        .await
+        .map_err(|e| write_error(e, "Failed to update series"))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    if existing.is_training != updated.is_training {
+        refresh_performance(&state, updated.event_id).await;
+    }
+
+    Ok(Json(json!({
+        "message": "Series updated successfully",
+        "series": updated
+    })))
+}
+
+/// Require two admins to enter a break round's paper ballots, or stop
+/// requiring it (admin only). Pending entries are kept if it is turned off.
+pub async fn set_series_double_entry(
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<Uuid>,
+    Json(payload): Json<DoubleEntryRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let series = state
         .db
         .get_series_by_id(series_id)
         .await
@@ -262,6 +496,90 @@ pub async fn delete_series(
                 Json(json!({"error": "Series not found"})),
             )
         })?;
+    if payload.double_entry && !series.is_break_round {
+        return Err(double_entry_break_rounds_only());
+    }
+
+    let updated = state
+        .db
+        .set_series_double_entry(series_id, payload.double_entry)
+        .await
+        .map_err(|e| write_error(e, "Failed to update series"))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "message": "Series updated successfully",
+        "series": updated
+    })))
+}
+
+fn double_entry_break_rounds_only() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({"error": "Double entry is only available for break rounds"})),
+    )
+}
+
+/// Delete a series (admin only). Erasing a series takes its matches and
+/// ballots with it, so it waits for a second admin to confirm.
+pub async fn delete_series(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(series_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let series = find_series(&state, series_id).await?;
+    let matches = state
+        .db
+        .get_series_match_count(series_id)
+        .await
+        .map_err(db_error)?;
+
+    let pending = state
+        .pending_actions
+        .request(
+            ProtectedAction::DeleteSeries,
+            series_id,
+            &format!("Delete series {} and its {} matches", series.name, matches),
+            admin_id,
+        )
+        .await
+        .map_err(db_error)?;
+    Ok(pending_actions::awaiting_confirmation(&pending))
+}
+
+async fn find_series(
+    state: &AppState,
+    series_id: Uuid,
+) -> Result<MatchSeries, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })
+}
+
+/// Delete a series once a second admin has confirmed
+async fn remove_series(
+    state: &AppState,
+    series_id: Uuid,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let series = find_series(state, series_id).await?;
 
     state.db.delete_series(series_id).await.map_err(|_| {
         (
@@ -269,8 +587,61 @@ pub async fn delete_series(
             Json(json!({"error": "Failed to delete series"})),
         )
     })?;
+    refresh_performance(state, series.event_id).await;
+
+    Ok((
+        StatusCode::OK,
+        Json(json!({"message": "Series deleted successfully"})),
+    ))
+}
+
+/// List destructive actions waiting for a second admin (admin only)
+pub async fn list_pending_actions(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let actions = state.pending_actions.list().await.map_err(db_error)?;
+    Ok(Json(json!({"pending_actions": actions})))
+}
+
+/// Confirm another admin's pending action, carrying it out (admin only)
+pub async fn confirm_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_id, true)
+        .await?;
+
+    let result = match action.kind() {
+        Ok(ProtectedAction::DeleteSeries) => remove_series(&state, action.target_id).await,
+        _ => Err(api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Unknown action: {}", action.action),
+        )),
+    };
+    if result.is_err() {
+        if let Err(e) = state.pending_actions.reopen(action.id).await {
+            tracing::error!("Failed to reopen pending action {}: {}", action.id, e);
+        }
+    }
+    result
+}
 
-    Ok(Json(json!({"message": "Series deleted successfully"})))
+/// Reject a pending action, or withdraw one's own (admin only)
+pub async fn reject_pending_action(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(action_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let action = state
+        .pending_actions
+        .decide(action_id, admin_id, false)
+        .await?;
+    Ok(Json(
+        json!({"message": "Action rejected", "pending_action": action}),
+    ))
 }
 
 // ============================================================================
@@ -308,11 +679,21 @@ pub async fn create_match(
             )
         })?;
 
+    let venue = match payload.venue_id {
+        Some(venue_id) => {
+            let venue = find_venue(&state, venue_id).await?;
+            check_venue_free(&state, series.id, venue.id, payload.scheduled_time, None).await?;
+            Some(venue)
+        }
+        None => None,
+    };
+
     let now = Utc::now();
     let match_record = Match {
         id: Uuid::new_v4(),
         series_id: payload.series_id,
-        room_name: payload.room_name,
+        room_name: venue.as_ref().map(|v| v.name.clone()).or(payload.room_name),
+        venue_id: venue.map(|v| v.id),
         motion: payload.motion,
         info_slide: payload.info_slide,
         status: MatchStatus::Draft,
@@ -323,12 +704,11 @@ pub async fn create_match(
         updated_at: now,
     };
 
-    let created = state.db.create_match(&match_record).await.map_err(|_| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create match"})),
-        )
-    })?;
+    let created = state
+        .db
+        .create_match(&match_record)
+        .await
+        .map_err(|e| match_write_error(e, "Failed to create match"))?;
 
     // Create teams based on format (FR-03, FR-04)
     let teams = state
@@ -357,8 +737,8 @@ pub async fn list_matches(
     State(state): State<Arc<AppState>>,
     Query(query): Query<MatchListQuery>,
 ) -> Result<Json<MatchListResponse>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let pagination = Pagination::new(query.page, query.per_page, 20);
+    let (page, per_page) = (pagination.page, pagination.per_page);
 
     let (matches, total) = if let Some(series_id) = query.series_id {
         state
@@ -389,7 +769,7 @@ pub async fn list_matches(
         match_responses.push(response);
     }
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
+    let total_pages = pagination.total_pages(total) as i32;
 
     Ok(Json(MatchListResponse {
         matches: match_responses,
@@ -442,7 +822,7 @@ pub async fn update_match(
     Json(payload): Json<UpdateMatchRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     // Verify match exists
-    state
+    let existing = state
         .db
         .get_match_by_id(match_id)
         .await
@@ -459,23 +839,50 @@ pub async fn update_match(
             )
         })?;
 
+    let venue = match payload.venue_id {
+        Some(venue_id) => Some(find_venue(&state, venue_id).await?),
+        None => None,
+    };
+    // A room typed in by hand takes the match out of its venue
+    let venue_change = match (&venue, &payload.room_name) {
+        (Some(venue), _) => Some(Some(venue.id)),
+        (None, Some(_)) => Some(None),
+        (None, None) => None,
+    };
+    let venue_id = venue_change.unwrap_or(existing.venue_id);
+    let status = payload.status.unwrap_or(existing.status);
+    if let (Some(venue_id), false) = (venue_id, status == MatchStatus::Cancelled) {
+        let scheduled_time = payload.scheduled_time.or(existing.scheduled_time);
+        check_venue_free(
+            &state,
+            existing.series_id,
+            venue_id,
+            scheduled_time,
+            Some(match_id),
+        )
+        .await?;
+    }
+
     let updated = state
         .db
-        .update_match(
+        .update_match(UpdateMatchParams {
             match_id,
-            payload.room_name.as_deref(),
-            payload.motion.as_deref(),
-            payload.info_slide.as_deref(),
-            payload.status,
-            payload.scheduled_time,
-        )
+            room_name: venue
+                .as_ref()
+                .map(|v| v.name.as_str())
+                .or(payload.room_name.as_deref()),
+            venue: venue_change,
+            motion: payload.motion.as_deref(),
+            info_slide: payload.info_slide.as_deref(),
+            status: payload.status,
+            scheduled_time: payload.scheduled_time,
+        })
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to update match"})),
-            )
-        })?;
+        .map_err(|e| match_write_error(e, "Failed to update match"))?;
+
+    if existing.status != MatchStatus::Published && updated.status == MatchStatus::Published {
+        notifications::announce(&state, NotificationKind::DrawPublished, updated.clone());
+    }
 
     Ok(Json(json!({
         "message": "Match updated successfully",
@@ -490,7 +897,7 @@ pub async fn toggle_release(
     Json(payload): Json<ReleaseToggleRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     // Verify match exists
-    let _match_record = state
+    let existing = state
         .db
         .get_match_by_id(match_id)
         .await
@@ -526,6 +933,20 @@ pub async fn toggle_release(
             )
         })?;
 
+    // Silent rounds announce their results when the silence is lifted
+    let is_silent = state
+        .db
+        .get_series_by_id(updated.series_id)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|series| series.is_silent);
+    let newly_released = (updated.scores_released && !existing.scores_released)
+        || (updated.rankings_released && !existing.rankings_released);
+    if newly_released && !is_silent {
+        notifications::announce(&state, NotificationKind::ResultsReleased, updated.clone());
+    }
+
     Ok(Json(json!({
         "message": "Release status updated successfully",
         "match": updated
@@ -538,7 +959,7 @@ pub async fn delete_match(
     Path(match_id): Path<Uuid>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     // Verify match exists
-    state
+    let existing = state
         .db
         .get_match_by_id(match_id)
         .await
@@ -561,6 +982,9 @@ pub async fn delete_match(
             Json(json!({"error": "Failed to delete match"})),
         )
     })?;
+    if let Ok(Some(series)) = state.db.get_series_by_id(existing.series_id).await {
+        refresh_performance(&state, series.event_id).await;
+    }
 
     Ok(Json(json!({"message": "Match deleted successfully"})))
 }
@@ -597,131 +1021,114 @@ pub async fn update_team(
 }
 
 // ============================================================================
-// Allocation Handlers - FR-05 to FR-09
+// Institution & Eligibility Handlers
 // ============================================================================
 
-/// Get allocation pool for a series (checked-in users) - FR-05
-pub async fn get_allocation_pool(
+pub async fn list_institutions(
     State(state): State<Arc<AppState>>,
-    Path(series_id): Path<Uuid>,
-) -> Result<Json<AllocationPoolResponse>, (StatusCode, Json<Value>)> {
-    // Get series to find event
-    let series = state
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let institutions = state.db.list_institutions().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!({"institutions": institutions})))
+}
+
+/// Add an institution (Admin only)
+pub async fn create_institution(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateInstitutionRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let institution = state
         .db
-        .get_series_by_id(series_id)
+        .create_institution(&payload.name, payload.short_code.as_deref())
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Series not found"})),
-            )
-        })?;
+        .map_err(|e| institution_write_error(e, "Failed to create institution"))?;
 
-    // Get all checked-in users for this event
-    let checked_in = state
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Institution created successfully",
+            "institution": institution
+        })),
+    ))
+}
+
+/// Rename an institution or change its code (Admin only)
+pub async fn update_institution(
+    State(state): State<Arc<AppState>>,
+    Path(institution_id): Path<Uuid>,
+    Json(payload): Json<UpdateInstitutionRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let institution = state
         .db
-        .get_checked_in_users_for_event(series.event_id)
+        .update_institution(
+            institution_id,
+            payload.name.as_deref(),
+            payload.short_code.as_deref(),
+        )
         .await
-        .map_err(|_| {
+        .map_err(|e| institution_write_error(e, "Failed to update institution"))?
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to get checked-in users"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Institution not found"})),
             )
         })?;
 
-    let mut users = Vec::new();
-    let mut total_allocated = 0i64;
-
-    for attendance in &checked_in {
-        // Get user info
-        let user = state
-            .db
-            .get_user_by_id(attendance.user_id)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error"})),
-                )
-            })?;
-
-        if let Some(user) = user {
-            // Check if user is already allocated in this series
-            let allocation = state
-                .db
-                .get_user_allocation_in_series(series_id, attendance.user_id)
-                .await
-                .ok()
-                .flatten();
-
-            let is_allocated = allocation.is_some();
-            if is_allocated {
-                total_allocated += 1;
-            }
-
-            let current_allocation = if let Some(alloc) = allocation {
-                let match_record = state
-                    .db
-                    .get_match_by_id(alloc.match_id)
-                    .await
-                    .ok()
-                    .flatten();
-                Some(CurrentAllocationInfo {
-                    match_id: alloc.match_id,
-                    room_name: match_record.and_then(|m| m.room_name),
-                    role: alloc.role,
-                })
-            } else {
-                None
-            };
-
-            users.push(CheckedInUserResponse {
-                user_id: user.id,
-                username: user.username,
-                checked_in_at: attendance.checked_in_at.unwrap_or_else(Utc::now),
-                is_allocated,
-                current_allocation,
-            });
-        }
-    }
-
-    let total_checked_in = checked_in.len() as i64;
-    let total_available = total_checked_in - total_allocated;
-
-    Ok(Json(AllocationPoolResponse {
-        event_id: series.event_id,
-        series_id,
-        checked_in_users: users,
-        total_checked_in,
-        total_allocated,
-        total_available,
-    }))
+    Ok(Json(json!({
+        "message": "Institution updated successfully",
+        "institution": institution
+    })))
 }
 
-/// Create an allocation - FR-07
-pub async fn create_allocation(
+/// Delete an institution; its members and teams are left without one
+/// (Admin only)
+pub async fn delete_institution(
     State(state): State<Arc<AppState>>,
-    Extension(admin_id): Extension<Uuid>,
-    Json(payload): Json<CreateAllocationRequest>,
-) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    // Validate: either user_id or guest_name must be provided
-    if payload.user_id.is_none() && payload.guest_name.is_none() {
+    Path(institution_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let deleted = state
+        .db
+        .delete_institution(institution_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to delete institution"))?;
+
+    if !deleted {
         return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Either user_id or guest_name must be provided"})),
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Institution not found"})),
         ));
     }
 
-    // Verify match exists
-    let match_record = state
+    Ok(Json(json!({"message": "Institution deleted successfully"})))
+}
+
+/// A user's institution and novice/ESL status
+pub async fn get_speaker_eligibility(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let eligibility = state
         .db
-        .get_match_by_id(payload.match_id)
+        .get_speaker_eligibility(user_id)
         .await
         .map_err(|_| {
             (
@@ -732,34 +1139,23 @@ pub async fn create_allocation(
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "Match not found"})),
+                Json(json!({"error": "User not found"})),
             )
         })?;
 
-    // If user_id provided, verify user exists
-    let mut was_checked_in = false;
-    if let Some(user_id) = payload.user_id {
-        let _user = state
-            .db
-            .get_user_by_id(user_id)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Database error"})),
-                )
-            })?
-            .ok_or_else(|| {
-                (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({"error": "User not found"})),
-                )
-            })?;
+    Ok(Json(json!(eligibility)))
+}
 
-        // Get the series to find the event
-        let series = state
+/// Set a user's institution and novice/ESL status (Admin only)
+pub async fn update_speaker_eligibility(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpdateEligibilityRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if let Some(institution_id) = payload.institution_id {
+        state
             .db
-            .get_series_by_id(match_record.series_id)
+            .get_institution(institution_id)
             .await
             .map_err(|_| {
                 (
@@ -770,256 +1166,188 @@ pub async fn create_allocation(
             .ok_or_else(|| {
                 (
                     StatusCode::NOT_FOUND,
-                    Json(json!({"error": "Series not found"})),
+                    Json(json!({"error": "Institution not found"})),
                 )
             })?;
+    }
 
-        // For friendly matches, we allow very flexible allocations:
-        // - Same user can have multiple speaker roles (e.g., PM and Reply)
-        // - Same user can be speaker AND adjudicator
-        // - We only prevent exact duplicate allocations (same role + same speaker position)
-        let existing_allocations = state
-            .db
-            .list_allocations_by_match(payload.match_id)
-            .await
-            .unwrap_or_default();
+    let updated = state
+        .db
+        .update_speaker_eligibility(
+            user_id,
+            payload.institution_id,
+            payload.is_novice,
+            payload.is_esl,
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update eligibility"})),
+            )
+        })?;
 
-        let has_exact_duplicate = existing_allocations.iter().any(|a| {
-            if a.user_id != Some(user_id) {
-                return false;
-            }
-            if a.role != payload.role {
-                return false;
-            }
-            // For speakers, also check the specific speaker role
-            if payload.role == AllocationRole::Speaker {
-                // Allow same user as speaker if they have different speaker roles
-                let same_two_team_role = a.two_team_speaker_role == payload.two_team_speaker_role
-                    && payload.two_team_speaker_role.is_some();
-                let same_four_team_role = a.four_team_speaker_role
-                    == payload.four_team_speaker_role
-                    && payload.four_team_speaker_role.is_some();
-                return same_two_team_role || same_four_team_role;
-            }
-            // For adjudicators, don't allow duplicate (can only be voting OR non-voting once)
-            true
-        });
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User not found"})),
+        ));
+    }
 
-        if has_exact_duplicate {
-            return Err((
-                StatusCode::CONFLICT,
-                Json(json!({"error": "User already has this exact role in this match"})),
-            ));
-        }
+    let eligibility = state
+        .db
+        .get_speaker_eligibility(user_id)
+        .await
+        .ok()
+        .flatten();
 
-        // Check if user is checked in for this event
-        was_checked_in = state
-            .db
-            .is_user_checked_in(series.event_id, user_id)
-            .await
-            .unwrap_or(false);
-    }
+    Ok(Json(json!({
+        "message": "Eligibility updated successfully",
+        "eligibility": eligibility
+    })))
+}
 
-    // Get series for validation (if not already fetched)
-    let series = state
+/// Users eligible for a category, e.g. the novice break (Admin only)
+pub async fn list_eligible_speakers(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EligibilityQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = query.category.unwrap_or(EligibilityFilter::Open);
+    let speakers = state
         .db
-        .get_series_by_id(match_record.series_id)
+        .list_eligible_speakers(category, query.institution_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Series not found"})),
-            )
         })?;
 
-    // Validate team_id if speaker role
-    if payload.role == AllocationRole::Speaker && payload.team_id.is_none() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Speakers must be assigned to a team"})),
-        ));
-    }
+    Ok(Json(json!({
+        "category": category,
+        "total": speakers.len(),
+        "speakers": speakers
+    })))
+}
 
-    // Validate speaker role based on team format
-    if payload.role == AllocationRole::Speaker {
-        match series.team_format {
-            TeamFormat::TwoTeam => {
-                if payload.two_team_speaker_role.is_none() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(json!({"error": "Two-team speaker role required"})),
-                    ));
-                }
-            }
-            TeamFormat::FourTeam => {
-                if payload.four_team_speaker_role.is_none() {
-                    return Err((
-                        StatusCode::BAD_REQUEST,
-                        Json(json!({"error": "Four-team speaker role required"})),
-                    ));
-                }
-            }
-        }
+/// Report duplicate institution names and codes as conflicts
+fn institution_write_error(e: sqlx::Error, message: &str) -> (StatusCode, Json<Value>) {
+    let duplicate = e
+        .as_database_error()
+        .and_then(|db| db.code())
+        .is_some_and(|code| code == "23505");
+
+    if duplicate {
+        (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "An institution with this name or code already exists"})),
+        )
+    } else {
+        write_error(e, message)
     }
+}
 
-    let now = Utc::now();
-    let allocation = Allocation {
-        id: Uuid::new_v4(),
-        match_id: payload.match_id,
-        user_id: payload.user_id,
-        guest_name: payload.guest_name.clone(),
-        role: payload.role,
-        team_id: payload.team_id,
-        two_team_speaker_role: payload.two_team_speaker_role,
-        four_team_speaker_role: payload.four_team_speaker_role,
-        is_chair: Some(payload.is_chair),
-        allocated_at: now,
-        allocated_by: admin_id,
-        was_checked_in,
-        created_at: now,
-        updated_at: now,
-    };
+// ============================================================================
+// Venue Handlers
+// ============================================================================
 
-    let created = state.db.create_allocation(&allocation).await.map_err(|e| {
-        tracing::error!("Failed to create allocation: {:?}", e);
+pub async fn list_venues(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let venues = state.db.list_venues().await.map_err(|_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({"error": "Failed to create allocation"})),
+            Json(json!({"error": "Database error"})),
         )
     })?;
 
-    // Create allocation history
-    let history = AllocationHistory {
-        id: Uuid::new_v4(),
-        allocation_id: Some(created.id),
-        match_id: payload.match_id,
-        user_id: payload.user_id,
-        guest_name: payload.guest_name.clone(),
-        action: "created".to_string(),
-        previous_role: None,
-        new_role: Some(payload.role),
-        previous_team_id: None,
-        new_team_id: payload.team_id,
-        changed_by: admin_id,
-        changed_at: now,
-        notes: None,
-    };
-    let _ = state.db.create_allocation_history(&history).await;
+    Ok(Json(json!({"venues": venues})))
+}
 
-    // If this is an adjudicator with a user account, create a ballot for them
-    // (Guest adjudicators don't submit ballots through the system)
-    if let Some(user_id) = payload.user_id {
-        if payload.role == AllocationRole::VotingAdjudicator
-            || payload.role == AllocationRole::NonVotingAdjudicator
-        {
-            let ballot = Ballot {
-                id: Uuid::new_v4(),
-                match_id: payload.match_id,
-                adjudicator_id: user_id,
-                is_voting: payload.role == AllocationRole::VotingAdjudicator,
-                is_submitted: false,
-                submitted_at: None,
-                notes: None,
-                created_at: now,
-                updated_at: now,
-            };
-            let _ = state.db.create_ballot(&ballot).await;
-        }
-    }
+/// Add a venue (Admin only)
+pub async fn create_venue(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateVenueRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let venue = state
+        .db
+        .create_venue(&payload)
+        .await
+        .map_err(|e| venue_write_error(e, "Failed to create venue"))?;
 
     Ok((
         StatusCode::CREATED,
         Json(json!({
-            "message": "Allocation created successfully",
-            "allocation": created
+            "message": "Venue created successfully",
+            "venue": venue
         })),
     ))
 }
 
-/// Update an allocation - FR-09
-pub async fn update_allocation(
+/// Change a venue; matches held there follow a rename (Admin only)
+pub async fn update_venue(
     State(state): State<Arc<AppState>>,
-    Extension(admin_id): Extension<Uuid>,
-    Path(allocation_id): Path<Uuid>,
-    Json(payload): Json<UpdateAllocationRequest>,
+    Path(venue_id): Path<Uuid>,
+    Json(payload): Json<UpdateVenueRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    // Get existing allocation
-    let existing = state
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let venue = state
         .db
-        .get_allocation_by_id(allocation_id)
+        .update_venue(venue_id, &payload)
         .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-        })?
+        .map_err(|e| venue_write_error(e, "Failed to update venue"))?
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "Allocation not found"})),
-            )
-        })?;
-
-    let updated = state
-        .db
-        .update_allocation(UpdateAllocationParams {
-            allocation_id,
-            role: payload.role,
-            team_id: payload.team_id,
-            two_team_speaker_role: payload.two_team_speaker_role,
-            four_team_speaker_role: payload.four_team_speaker_role,
-            is_chair: payload.is_chair,
-            allocated_by: admin_id,
-        })
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to update allocation"})),
+                Json(json!({"error": "Venue not found"})),
             )
         })?;
 
-    // Create allocation history
-    let history = AllocationHistory {
-        id: Uuid::new_v4(),
-        allocation_id: Some(allocation_id),
-        match_id: existing.match_id,
-        user_id: existing.user_id,
-        guest_name: existing.guest_name.clone(),
-        action: "updated".to_string(),
-        previous_role: Some(existing.role),
-        new_role: payload.role,
-        previous_team_id: existing.team_id,
-        new_team_id: payload.team_id,
-        changed_by: admin_id,
-        changed_at: Utc::now(),
-        notes: None,
-    };
-    let _ = state.db.create_allocation_history(&history).await;
-
     Ok(Json(json!({
-        "message": "Allocation updated successfully",
-        "allocation": updated
+        "message": "Venue updated successfully",
+        "venue": venue
     })))
 }
 
-/// Swap two allocations - US-1.6
-pub async fn swap_allocations(
+/// Delete a venue; its matches keep the room name (Admin only)
+pub async fn delete_venue(
     State(state): State<Arc<AppState>>,
-    Extension(admin_id): Extension<Uuid>,
-    Json(payload): Json<SwapAllocationRequest>,
+    Path(venue_id): Path<Uuid>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    // Get both allocations
-    let alloc1 = state
+    let deleted = state
         .db
-        .get_allocation_by_id(payload.allocation_id_1)
+        .delete_venue(venue_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to delete venue"))?;
+
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Venue not found"})),
+        ));
+    }
+
+    Ok(Json(json!({"message": "Venue deleted successfully"})))
+}
+
+async fn find_venue(state: &AppState, venue_id: Uuid) -> Result<Venue, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_venue(venue_id)
         .await
         .map_err(|_| {
             (
@@ -1030,352 +1358,491 @@ pub async fn swap_allocations(
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "First allocation not found"})),
+                Json(json!({"error": "Venue not found"})),
             )
-        })?;
+        })
+}
 
-    let alloc2 = state
+fn venue_booked(conflict: Option<&Match>) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": "Venue is already booked for this round at that time",
+            "conflicting_match": conflict.map(|m| json!({
+                "id": m.id,
+                "room_name": m.room_name,
+                "scheduled_time": m.scheduled_time
+            }))
+        })),
+    )
+}
+
+/// Refuse a venue another live match of the series holds at the same time
+async fn check_venue_free(
+    state: &AppState,
+    series_id: Uuid,
+    venue_id: Uuid,
+    scheduled_time: Option<chrono::DateTime<Utc>>,
+    exclude_match_id: Option<Uuid>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let conflict = state
         .db
-        .get_allocation_by_id(payload.allocation_id_2)
+        .find_venue_conflict(series_id, venue_id, scheduled_time, exclude_match_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(json!({"error": "Second allocation not found"})),
-            )
         })?;
 
-    // Swap the team and role information
-    let _ = state
-        .db
-        .update_allocation(UpdateAllocationParams {
-            allocation_id: alloc1.id,
-            role: Some(alloc2.role),
-            team_id: alloc2.team_id,
-            two_team_speaker_role: alloc2.two_team_speaker_role,
-            four_team_speaker_role: alloc2.four_team_speaker_role,
-            is_chair: alloc2.is_chair,
-            allocated_by: admin_id,
-        })
-        .await;
+    match conflict {
+        Some(conflict) => Err(venue_booked(Some(&conflict))),
+        None => Ok(()),
+    }
+}
 
-    let _ = state
-        .db
-        .update_allocation(UpdateAllocationParams {
-            allocation_id: alloc2.id,
-            role: Some(alloc1.role),
-            team_id: alloc1.team_id,
-            two_team_speaker_role: alloc1.two_team_speaker_role,
-            four_team_speaker_role: alloc1.four_team_speaker_role,
-            is_chair: alloc1.is_chair,
-            allocated_by: admin_id,
-        })
-        .await;
+/// A booking that raced past `check_venue_free` still hits the unique index
+fn match_write_error(e: sqlx::Error, message: &str) -> (StatusCode, Json<Value>) {
+    let double_booked = e
+        .as_database_error()
+        .and_then(|db| db.constraint())
+        .is_some_and(|constraint| constraint == "idx_matches_venue_slot");
 
-    // Create history for both
-    let now = Utc::now();
-    let history1 = AllocationHistory {
-        id: Uuid::new_v4(),
-        allocation_id: Some(alloc1.id),
-        match_id: alloc1.match_id,
-        user_id: alloc1.user_id,
-        guest_name: alloc1.guest_name.clone(),
-        action: "swapped".to_string(),
-        previous_role: Some(alloc1.role),
-        new_role: Some(alloc2.role),
-        previous_team_id: alloc1.team_id,
-        new_team_id: alloc2.team_id,
-        changed_by: admin_id,
-        changed_at: now,
-        notes: Some(format!("Swapped with allocation {}", alloc2.id)),
-    };
-    let _ = state.db.create_allocation_history(&history1).await;
+    if double_booked {
+        venue_booked(None)
+    } else {
+        write_error(e, message)
+    }
+}
 
-    let history2 = AllocationHistory {
-        id: Uuid::new_v4(),
-        allocation_id: Some(alloc2.id),
-        match_id: alloc2.match_id,
-        user_id: alloc2.user_id,
-        guest_name: alloc2.guest_name.clone(),
-        action: "swapped".to_string(),
-        previous_role: Some(alloc2.role),
-        new_role: Some(alloc1.role),
-        previous_team_id: alloc2.team_id,
-        new_team_id: alloc1.team_id,
-        changed_by: admin_id,
-        changed_at: now,
-        notes: Some(format!("Swapped with allocation {}", alloc1.id)),
-    };
-    let _ = state.db.create_allocation_history(&history2).await;
+fn venue_write_error(e: sqlx::Error, message: &str) -> (StatusCode, Json<Value>) {
+    let duplicate = e
+        .as_database_error()
+        .and_then(|db| db.code())
+        .is_some_and(|code| code == "23505");
 
-    Ok(Json(json!({"message": "Allocations swapped successfully"})))
+    if duplicate {
+        (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A venue with this name already exists"})),
+        )
+    } else {
+        write_error(e, message)
+    }
 }
 
-/// Delete an allocation
-pub async fn delete_allocation(
+// ============================================================================
+// Team Registration Handlers
+// ============================================================================
+
+/// Form a team with the current user as first speaker
+pub async fn create_registered_team(
     State(state): State<Arc<AppState>>,
-    Extension(admin_id): Extension<Uuid>,
-    Path(allocation_id): Path<Uuid>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<CreateRegisteredTeamRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    let mut member_ids = vec![user_id];
+    for partner_id in payload.partner_ids {
+        if !member_ids.contains(&partner_id) {
+            member_ids.push(partner_id);
+        }
+    }
+    if member_ids.len() > teams::MAX_TEAM_SIZE {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("Teams have at most {} members", teams::MAX_TEAM_SIZE)
+            })),
+        ));
+    }
+    for member_id in &member_ids[1..] {
+        ensure_user_exists(&state, *member_id).await?;
+    }
+
+    let team = state
+        .db
+        .create_registered_team(
+            &payload.name,
+            payload.institution.as_deref(),
+            user_id,
+            &member_ids,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to create team: {:?}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to create team"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Team created successfully",
+            "team": registered_team_response(&state, team).await?
+        })),
+    ))
+}
+
+/// Teams the current user is a member of
+pub async fn list_my_registered_teams(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    // Get allocation for history
-    let allocation = state
+    let found = state
         .db
-        .get_allocation_by_id(allocation_id)
+        .list_registered_teams_for_user(user_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
+        })?;
+
+    let mut responses = Vec::with_capacity(found.len());
+    for team in found {
+        responses.push(registered_team_response(&state, team).await?);
+    }
+
+    Ok(Json(json!({"teams": responses})))
+}
+
+pub async fn get_registered_team(
+    State(state): State<Arc<AppState>>,
+    Path(team_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let team = find_registered_team(&state, team_id).await?;
+    Ok(Json(json!(registered_team_response(&state, team).await?)))
+}
+
+/// Rename a team or change its institution (members only)
+pub async fn update_registered_team(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(team_id): Path<Uuid>,
+    Json(payload): Json<UpdateRegisteredTeamRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("Validation error: {}", e)})),
+        )
+    })?;
+
+    ensure_team_member(&state, team_id, user_id).await?;
+
+    let team = state
+        .db
+        .update_registered_team(
+            team_id,
+            payload.name.as_deref(),
+            payload.institution.as_deref(),
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update team"})),
+            )
         })?
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "Allocation not found"})),
+                Json(json!({"error": "Team not found"})),
             )
         })?;
 
-    // Create history before deletion
-    let history = AllocationHistory {
-        id: Uuid::new_v4(),
-        allocation_id: Some(allocation_id),
-        match_id: allocation.match_id,
-        user_id: allocation.user_id,
-        guest_name: allocation.guest_name.clone(),
-        action: "deleted".to_string(),
-        previous_role: Some(allocation.role),
-        new_role: None,
-        previous_team_id: allocation.team_id,
-        new_team_id: None,
-        changed_by: admin_id,
-        changed_at: Utc::now(),
-        notes: None,
-    };
-    let _ = state.db.create_allocation_history(&history).await;
+    Ok(Json(json!({
+        "message": "Team updated successfully",
+        "team": registered_team_response(&state, team).await?
+    })))
+}
 
-    state
+/// Add a partner as the team's next speaker (members only)
+pub async fn add_registered_team_member(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(team_id): Path<Uuid>,
+    Json(payload): Json<AddTeamMemberRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let members = ensure_team_member(&state, team_id, user_id).await?;
+    if members.len() >= teams::MAX_TEAM_SIZE {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("Teams have at most {} members", teams::MAX_TEAM_SIZE)
+            })),
+        ));
+    }
+    ensure_user_exists(&state, payload.user_id).await?;
+
+    // The new member must not already speak for another team entered in
+    // the same events
+    let events = state
         .db
-        .delete_allocation(allocation_id)
+        .list_team_registration_events(team_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to delete allocation"})),
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    ensure_no_registration_conflicts(&state, team_id, &[payload.user_id], &events).await?;
+
+    let added = state
+        .db
+        .add_registered_team_member(team_id, payload.user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to add member"})),
             )
         })?;
 
-    Ok(Json(json!({"message": "Allocation deleted successfully"})))
+    if !added {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "User is already on this team"})),
+        ));
+    }
+
+    let team = find_registered_team(&state, team_id).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Member added successfully",
+            "team": registered_team_response(&state, team).await?
+        })),
+    ))
 }
 
-/// Get allocation history for a match
-pub async fn get_allocation_history(
+/// Remove a member; members may remove themselves or a partner
+pub async fn remove_registered_team_member(
     State(state): State<Arc<AppState>>,
-    Path(match_id): Path<Uuid>,
-    Query(query): Query<PerformanceQuery>,
-) -> Result<Json<AllocationHistoryResponse>, (StatusCode, Json<Value>)> {
-    let page = query.page.unwrap_or(1).max(1);
-    let per_page = query.per_page.unwrap_or(50).clamp(1, 100);
+    Extension(user_id): Extension<Uuid>,
+    Path((team_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let members = ensure_team_member(&state, team_id, user_id).await?;
+    if !members.iter().any(|m| m.user_id == member_id) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "User is not on this team"})),
+        ));
+    }
+    if members.len() == 1 {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A team must keep at least one member"})),
+        ));
+    }
 
-    let (history, total) = state
+    state
         .db
-        .list_allocation_history(match_id, page, per_page)
+        .remove_registered_team_member(team_id, member_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
+                Json(json!({"error": "Failed to remove member"})),
             )
         })?;
 
-    let total_pages = ((total as f64) / (per_page as f64)).ceil() as i32;
-
-    Ok(Json(AllocationHistoryResponse {
-        history,
-        total,
-        page,
-        per_page,
-        total_pages,
-    }))
+    Ok(Json(json!({"message": "Member removed successfully"})))
 }
 
-// ============================================================================
-// Ballot Handlers - FR-10 to FR-13
-// ============================================================================
+/// Enter a team into a team-entry event (team members only)
+pub async fn register_team(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<RegisterTeamRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let event = find_team_entry_event(&state, event_id).await?;
+    if event.is_locked {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Registration is closed for this event"})),
+        ));
+    }
 
-/// Get ballot for current adjudicator - US-2.1
-pub async fn get_my_ballot(
+    let members = ensure_team_member(&state, payload.team_id, user_id).await?;
+    let member_ids: Vec<Uuid> = members.iter().map(|m| m.user_id).collect();
+    ensure_no_registration_conflicts(&state, payload.team_id, &member_ids, &[event_id]).await?;
+
+    let registration = state
+        .db
+        .register_team(event_id, payload.team_id, user_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to register team"))?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "Team is already registered for this event"})),
+            )
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Team registered successfully",
+            "registration": registration
+        })),
+    ))
+}
+
+/// Withdraw a team before it has been drawn (team members or admins)
+pub async fn withdraw_team(
     State(state): State<Arc<AppState>>,
     Extension(user_id): Extension<Uuid>,
-    Path(match_id): Path<Uuid>,
-) -> Result<Json<BallotResponse>, (StatusCode, Json<Value>)> {
-    // Verify user is allocated as adjudicator (use the specific adjudicator lookup)
-    let allocation = state
+    Path((event_id, team_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let is_admin = state.db.is_user_admin(user_id).await.unwrap_or(false);
+    if !is_admin {
+        ensure_team_member(&state, team_id, user_id).await?;
+    }
+
+    let drawn = state
         .db
-        .get_adjudicator_allocation_by_user_match(match_id, user_id)
+        .is_team_drawn_in_event(event_id, team_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
-        })?
-        .ok_or_else(|| {
-            (
-                StatusCode::FORBIDDEN,
-                Json(json!({"error": "You are not an adjudicator for this match"})),
-            )
         })?;
+    if drawn {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Team has already been drawn into a round"})),
+        ));
+    }
 
-    // No need to check role again - the query already filters for adjudicators
-    if allocation.role != AllocationRole::VotingAdjudicator
-        && allocation.role != AllocationRole::NonVotingAdjudicator
-    {
+    let withdrawn = state
+        .db
+        .withdraw_team(event_id, team_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to withdraw team"))?;
+
+    if !withdrawn {
         return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "You are not an adjudicator for this match"})),
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Team is not registered for this event"})),
         ));
     }
 
-    // Get or create ballot
-    let ballot = match state
+    Ok(Json(json!({"message": "Team withdrawn successfully"})))
+}
+
+/// Teams entered into an event, with their members
+pub async fn list_event_registrations(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let registrations = state
         .db
-        .get_ballot_by_adjudicator_match(match_id, user_id)
+        .list_event_registrations(event_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
-        })? {
-        Some(b) => b,
-        None => {
-            // Create ballot if doesn't exist
-            let now = Utc::now();
-            let new_ballot = Ballot {
-                id: Uuid::new_v4(),
-                match_id,
-                adjudicator_id: user_id,
-                is_voting: allocation.role == AllocationRole::VotingAdjudicator,
-                is_submitted: false,
-                submitted_at: None,
-                notes: None,
-                created_at: now,
-                updated_at: now,
-            };
-            state.db.create_ballot(&new_ballot).await.map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Failed to create ballot"})),
-                )
-            })?
-        }
-    };
+        })?;
 
-    let user = state.db.get_user_by_id(user_id).await.ok().flatten();
-    let username = user.map(|u| u.username).unwrap_or_default();
+    let mut responses = Vec::with_capacity(registrations.len());
+    for (registration, team) in registrations {
+        responses.push(EventRegistrationResponse {
+            team: registered_team_response(&state, team).await?,
+            registered_by: registration.registered_by,
+            registered_at: registration.registered_at,
+        });
+    }
 
-    // Get speaker scores
-    let scores = state
+    Ok(Json(json!({
+        "event_id": event_id,
+        "total": responses.len(),
+        "registrations": responses
+    })))
+}
+
+/// Switch an event between individual allocation and team entry (Admin only)
+pub async fn set_team_entry(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<TeamEntryRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let updated = state
         .db
-        .list_speaker_scores_by_ballot(ballot.id)
+        .set_event_team_entry(event_id, payload.team_entry)
         .await
-        .unwrap_or_default();
+        .map_err(|e| write_error(e, "Failed to update event"))?;
 
-    let mut score_responses = Vec::new();
-    for score in scores {
-        let alloc = state
-            .db
-            .get_allocation_by_id(score.allocation_id)
-            .await
-            .ok()
-            .flatten();
-        let speaker_username = if let Some(a) = &alloc {
-            if let Some(user_id) = a.user_id {
-                state
-                    .db
-                    .get_user_by_id(user_id)
-                    .await
-                    .ok()
-                    .flatten()
-                    .map(|u| u.username)
-                    .unwrap_or_default()
-            } else {
-                a.guest_name.clone().unwrap_or_default()
-            }
-        } else {
-            String::new()
-        };
-
-        score_responses.push(SpeakerScoreResponse {
-            id: score.id,
-            allocation_id: score.allocation_id,
-            speaker_username,
-            score: score.score,
-            feedback: score.feedback,
-        });
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Event not found"})),
+        ));
     }
 
-    // Get team rankings
-    let rankings = state
+    Ok(Json(json!({
+        "message": "Team entry updated successfully",
+        "event_id": event_id,
+        "team_entry": payload.team_entry
+    })))
+}
+
+/// Withhold speaker scores until the end of the tournament, or reveal them
+/// (Admin only). Released matches keep showing rankings and results.
+pub async fn set_speaks_withheld(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<WithholdSpeaksRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let updated = state
         .db
-        .list_team_rankings_by_ballot(ballot.id)
+        .set_event_speaks_withheld(event_id, payload.speaks_withheld)
         .await
-        .unwrap_or_default();
+        .map_err(|e| write_error(e, "Failed to update event"))?;
 
-    let mut ranking_responses = Vec::new();
-    for ranking in rankings {
-        let team = state
-            .db
-            .get_team_by_id(ranking.team_id)
-            .await
-            .ok()
-            .flatten();
-        ranking_responses.push(TeamRankingResponse {
-            id: ranking.id,
-            team_id: ranking.team_id,
-            team_name: team.and_then(|t| t.team_name),
-            rank: ranking.rank,
-            is_winner: ranking.is_winner,
-        });
+    if !updated {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Event not found"})),
+        ));
     }
+    state.results_cache.invalidate(event_id).await;
 
-    Ok(Json(BallotResponse {
-        id: ballot.id,
-        match_id: ballot.match_id,
-        adjudicator_id: ballot.adjudicator_id,
-        adjudicator_username: username,
-        is_voting: ballot.is_voting,
-        is_submitted: ballot.is_submitted,
-        submitted_at: ballot.submitted_at,
-        notes: ballot.notes,
-        speaker_scores: score_responses,
-        team_rankings: ranking_responses,
-    }))
+    Ok(Json(json!({
+        "message": "Speaker score release updated successfully",
+        "event_id": event_id,
+        "speaks_withheld": payload.speaks_withheld
+    })))
 }
 
-/// Submit ballot (voting adjudicator) - FR-10, FR-12, US-2.2
-pub async fn submit_ballot(
+/// Draw a registered team onto one side of a match, allocating its members
+/// as that side's speakers (Admin only)
+pub async fn assign_registered_team(
     State(state): State<Arc<AppState>>,
-    Extension(user_id): Extension<Uuid>,
-    Json(payload): Json<SubmitBallotRequest>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(side_id): Path<Uuid>,
+    Json(payload): Json<AssignTeamRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    payload.validate().map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": e.to_string()})),
-        )
-    })?;
-
-    // Verify user is a voting adjudicator for this match (use specific adjudicator lookup)
-    let allocation = state
+    let side = state
         .db
-        .get_adjudicator_allocation_by_user_match(payload.match_id, user_id)
+        .get_team_by_id(side_id)
         .await
         .map_err(|_| {
             (
@@ -1385,200 +1852,153 @@ pub async fn submit_ballot(
         })?
         .ok_or_else(|| {
             (
-                StatusCode::FORBIDDEN,
-                Json(json!({"error": "You are not an adjudicator for this match"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Team not found"})),
             )
         })?;
-
-    if allocation.role != AllocationRole::VotingAdjudicator {
-        return Err((
-            StatusCode::FORBIDDEN,
-            Json(json!({"error": "Only voting adjudicators can submit ballots with scores"})),
-        ));
-    }
-
-    // Get or create ballot
-    let ballot = match state
+    let match_record = state
         .db
-        .get_ballot_by_adjudicator_match(payload.match_id, user_id)
+        .get_match_by_id(side.match_id)
         .await
         .ok()
         .flatten()
-    {
-        Some(b) => b,
-        None => {
-            let now = Utc::now();
-            let new_ballot = Ballot {
-                id: Uuid::new_v4(),
-                match_id: payload.match_id,
-                adjudicator_id: user_id,
-                is_voting: true,
-                is_submitted: false,
-                submitted_at: None,
-                notes: None,
-                created_at: now,
-                updated_at: now,
-            };
-            state.db.create_ballot(&new_ballot).await.map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Failed to create ballot"})),
-                )
-            })?
-        }
-    };
-
-    // FR-13: Validate unique rankings
-    let mut ranks: Vec<i32> = payload.team_rankings.iter().map(|r| r.rank).collect();
-    ranks.sort();
-    let unique_ranks: Vec<i32> = ranks
-        .iter()
-        .cloned()
-        .collect::<std::collections::HashSet<_>>()
-        .into_iter()
-        .collect();
-    if ranks.len() != unique_ranks.len() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": "Rankings must be unique (no ties)"})),
-        ));
-    }
-
-    // Delete existing scores and rankings (to support re-submission/updates)
-    state
-        .db
-        .delete_speaker_scores_by_ballot(ballot.id)
-        .await
-        .ok();
-    state
-        .db
-        .delete_team_rankings_by_ballot(ballot.id)
-        .await
-        .ok();
-
-    // Create speaker scores
-    let now = Utc::now();
-    for score_input in &payload.speaker_scores {
-        let score = SpeakerScore {
-            id: Uuid::new_v4(),
-            ballot_id: ballot.id,
-            allocation_id: score_input.allocation_id,
-            score: Decimal::from_f64_retain(score_input.score).unwrap_or_else(|| Decimal::from(75)),
-            feedback: score_input.feedback.clone(),
-            created_at: now,
-            updated_at: now,
-        };
-        state.db.create_speaker_score(&score).await.map_err(|_| {
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to save speaker score"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
             )
         })?;
-    }
-
-    // Create team rankings
-    for ranking_input in &payload.team_rankings {
-        let ranking = TeamRanking {
-            id: Uuid::new_v4(),
-            ballot_id: ballot.id,
-            team_id: ranking_input.team_id,
-            rank: ranking_input.rank,
-            is_winner: ranking_input.is_winner,
-            created_at: now,
-            updated_at: now,
-        };
-        state.db.create_team_ranking(&ranking).await.map_err(|_| {
+    let series = state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to save team ranking"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
             )
         })?;
-    }
+    find_team_entry_event(&state, series.event_id).await?;
 
-    // Mark ballot as submitted
-    let submitted = state
+    let has_results = state
         .db
-        .submit_ballot(ballot.id, payload.notes.as_deref())
+        .list_ballots_by_match(match_record.id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to submit ballot"})),
+                Json(json!({"error": "Database error"})),
             )
-        })?;
-
-    // Recalculate final rankings from all submitted voting ballots
-    if let Ok(rankings) = state.db.get_match_team_rankings(payload.match_id).await {
-        // Get total speaker points for each team
-        let teams = state
-            .db
-            .list_teams_by_match(payload.match_id)
-            .await
-            .unwrap_or_default();
+        })?
+        .iter()
+        .any(|b| b.is_submitted);
+    if has_results {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Match already has submitted ballots"})),
+        ));
+    }
 
-        for (rank_position, (team_id, _avg_rank)) in rankings.iter().enumerate() {
-            // Calculate total speaker points from submitted ballots for this team
-            let total_points = calculate_team_total_points(&state.db, *team_id).await;
+    let entry = match payload.registered_team_id {
+        Some(team_id) => {
+            let team = find_registered_team(&state, team_id).await?;
+            let registered = state
+                .db
+                .get_team_registration(series.event_id, team_id)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({"error": "Database error"})),
+                    )
+                })?;
+            if registered.is_none() {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": "Team is not registered for this event"})),
+                ));
+            }
 
-            // Update team with final rank (1-indexed) and total points
-            let _ = state
+            let drawn = state
                 .db
-                .update_team_results(*team_id, (rank_position + 1) as i32, total_points)
-                .await;
-        }
+                .is_team_drawn_in_series(series.id, team_id, side.id)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({"error": "Database error"})),
+                    )
+                })?;
+            if drawn {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({"error": "Team is already drawn in this round"})),
+                ));
+            }
 
-        // Also update teams that don't have any rankings yet (set them to last place)
-        for team in teams {
-            if !rankings.iter().any(|(tid, _)| *tid == team.id) {
-                let total_points = calculate_team_total_points(&state.db, team.id).await;
-                let _ = state
-                    .db
-                    .update_team_results(team.id, (rankings.len() + 1) as i32, total_points)
-                    .await;
+            let members = state
+                .db
+                .list_registered_team_members(team_id)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({"error": "Database error"})),
+                    )
+                })?;
+            let lineup = teams::lineup(&side, &members)
+                .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+
+            let repeats = find_position_repeats(&state, &series, &side, false, |d| {
+                d.registered_team_id == Some(team_id)
+            })
+            .await?;
+            if !repeats.is_empty() && !payload.allow_position_repeat {
+                return Err((
+                    StatusCode::CONFLICT,
+                    Json(json!({
+                        "error": format!(
+                            "Team has already been drawn as {} {} times; resubmit with allow_position_repeat to draw anyway",
+                            repeats[0].position.label(),
+                            repeats.len()
+                        ),
+                        "position_repeats": repeats
+                    })),
+                ));
             }
+            Some((team, lineup))
         }
-    }
-
-    Ok(Json(json!({
-        "message": "Ballot submitted successfully",
-        "ballot": submitted
-    })))
-}
+        None => None,
+    };
 
-/// Calculate total speaker points for a team from all submitted voting ballots
-async fn calculate_team_total_points(db: &crate::database::Database, team_id: Uuid) -> Decimal {
-    // Get all allocations for this team (speakers)
-    let allocations = db
-        .list_allocations_by_team(team_id)
+    let updated = state
+        .db
+        .assign_registered_team(
+            series.event_id,
+            &side,
+            entry
+                .as_ref()
+                .map(|(team, lineup)| (team, lineup.as_slice())),
+            admin_id,
+        )
         .await
-        .unwrap_or_default();
+        .map_err(|e| write_error(e, "Failed to assign team"))?;
 
-    let mut total = Decimal::ZERO;
-    for alloc in allocations {
-        if let Ok(Some(avg_score)) = db.get_allocation_average_score(alloc.id).await {
-            total += avg_score;
-        }
-    }
-    total
+    Ok(Json(json!({
+        "message": if entry.is_some() { "Team assigned successfully" } else { "Team cleared successfully" },
+        "team": updated
+    })))
 }
 
-/// Submit feedback only (non-voting adjudicator) - FR-11, US-2.3
-pub async fn submit_feedback(
-    State(state): State<Arc<AppState>>,
-    Extension(user_id): Extension<Uuid>,
-    Json(payload): Json<SubmitFeedbackRequest>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    payload.validate().map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(json!({"error": e.to_string()})),
-        )
-    })?;
-
-    // Verify user is allocated to this match
-    let allocation = state
+async fn find_registered_team(
+    state: &AppState,
+    team_id: Uuid,
+) -> Result<RegisteredTeam, (StatusCode, Json<Value>)> {
+    state
         .db
-        .get_allocation_by_user_match(payload.match_id, user_id)
+        .get_registered_team(team_id)
         .await
         .map_err(|_| {
             (
@@ -1588,188 +2008,3946 @@ pub async fn submit_feedback(
         })?
         .ok_or_else(|| {
             (
-                StatusCode::FORBIDDEN,
-                Json(json!({"error": "You are not allocated to this match"})),
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Team not found"})),
             )
-        })?;
+        })
+}
 
-    if allocation.role != AllocationRole::NonVotingAdjudicator
-        && allocation.role != AllocationRole::VotingAdjudicator
-    {
-        return Err((
+async fn registered_team_response(
+    state: &AppState,
+    team: RegisteredTeam,
+) -> Result<RegisteredTeamResponse, (StatusCode, Json<Value>)> {
+    let members = state
+        .db
+        .list_registered_team_members(team.id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok(RegisteredTeamResponse { team, members })
+}
+
+/// The team's members, if `user_id` is one of them
+async fn ensure_team_member(
+    state: &AppState,
+    team_id: Uuid,
+    user_id: Uuid,
+) -> Result<Vec<RegisteredTeamMember>, (StatusCode, Json<Value>)> {
+    find_registered_team(state, team_id).await?;
+    let members = state
+        .db
+        .list_registered_team_members(team_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    if !members.iter().any(|m| m.user_id == user_id) {
+        return Err((
             StatusCode::FORBIDDEN,
-            Json(json!({"error": "Only adjudicators can submit feedback"})),
+            Json(json!({"error": "Only team members can do this"})),
         ));
     }
 
-    // Get or create ballot
-    let ballot = match state
+    Ok(members)
+}
+
+async fn ensure_user_exists(
+    state: &AppState,
+    user_id: Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    state
         .db
-        .get_ballot_by_adjudicator_match(payload.match_id, user_id)
+        .get_user_by_id(user_id)
         .await
-        .ok()
-        .flatten()
-    {
-        Some(b) => b,
-        None => {
-            let now = Utc::now();
-            let new_ballot = Ballot {
-                id: Uuid::new_v4(),
-                match_id: payload.match_id,
-                adjudicator_id: user_id,
-                is_voting: allocation.role == AllocationRole::VotingAdjudicator,
-                is_submitted: false,
-                submitted_at: None,
-                notes: None,
-                created_at: now,
-                updated_at: now,
-            };
-            state.db.create_ballot(&new_ballot).await.map_err(|_| {
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    Ok(())
+}
+
+/// A speaker can only represent one team per event
+async fn ensure_no_registration_conflicts(
+    state: &AppState,
+    team_id: Uuid,
+    user_ids: &[Uuid],
+    event_ids: &[Uuid],
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let conflicts = state
+        .db
+        .find_registration_conflicts(team_id, user_ids, event_ids)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    if !conflicts.is_empty() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Some members are already registered with another team",
+                "usernames": conflicts
+            })),
+        ));
+    }
+
+    Ok(())
+}
+
+/// The event, if it takes team entries and is not archived
+async fn find_team_entry_event(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<EventInfo, (StatusCode, Json<Value>)> {
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    if event.archived_at.is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event is archived and read-only"})),
+        ));
+    }
+    if !event.team_entry {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Event does not take team entries"})),
+        ));
+    }
+
+    Ok(event)
+}
+
+// ============================================================================
+// Allocation Handlers - FR-05 to FR-09
+// ============================================================================
+
+/// Refuse to touch a match's allocations unless the user manages its event
+async fn require_match_access(
+    state: &AppState,
+    access: &EventAccess,
+    match_id: Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if let EventAccess::All = access {
+        return Ok(());
+    }
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Match not found"})),
+        )
+    };
+    let match_record = state
+        .db
+        .get_match_by_id(match_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    let series = state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    access.require(series.event_id)
+}
+
+/// Get allocation pool for a series (checked-in users) - FR-05
+pub async fn get_allocation_pool(
+    State(state): State<Arc<AppState>>,
+    Extension(access): Extension<EventAccess>,
+    Path(series_id): Path<Uuid>,
+) -> Result<Json<AllocationPoolResponse>, (StatusCode, Json<Value>)> {
+    // Get series to find event
+    let series = state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+    access.require(series.event_id)?;
+
+    // Get all checked-in users for this event
+    let checked_in = state
+        .db
+        .get_checked_in_users_for_event(series.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to get checked-in users"})),
+            )
+        })?;
+
+    let mut users = Vec::new();
+    let mut total_allocated = 0i64;
+
+    for attendance in &checked_in {
+        // Get user info
+        let user = state
+            .db
+            .get_user_by_id(attendance.user_id)
+            .await
+            .map_err(|_| {
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(json!({"error": "Failed to create ballot"})),
+                    Json(json!({"error": "Database error"})),
                 )
-            })?
+            })?;
+
+        if let Some(user) = user {
+            // Check if user is already allocated in this series
+            let allocation = state
+                .db
+                .get_user_allocation_in_series(series_id, attendance.user_id)
+                .await
+                .ok()
+                .flatten();
+
+            let is_allocated = allocation.is_some();
+            if is_allocated {
+                total_allocated += 1;
+            }
+
+            let current_allocation = if let Some(alloc) = allocation {
+                let match_record = state
+                    .db
+                    .get_match_by_id(alloc.match_id)
+                    .await
+                    .ok()
+                    .flatten();
+                Some(CurrentAllocationInfo {
+                    match_id: alloc.match_id,
+                    room_name: match_record.and_then(|m| m.room_name),
+                    role: alloc.role,
+                })
+            } else {
+                None
+            };
+
+            users.push(CheckedInUserResponse {
+                user_id: user.id,
+                username: user.username,
+                checked_in_at: attendance.checked_in_at.unwrap_or_else(Utc::now),
+                payment_status: attendance.payment_status.clone(),
+                is_allocated,
+                current_allocation,
+            });
         }
-    };
+    }
+
+    // Guests registered at the desk join the pool, allocated by name
+    let registered = state
+        .db
+        .get_walk_ins_for_event(series.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to get walk-ins"})),
+            )
+        })?;
+    let mut walk_ins = Vec::new();
+    for walk_in in registered {
+        let allocation = state
+            .db
+            .get_guest_allocation_in_series(series_id, &walk_in.name)
+            .await
+            .ok()
+            .flatten();
+        let current_allocation = match allocation {
+            Some(alloc) => {
+                total_allocated += 1;
+                let match_record = state
+                    .db
+                    .get_match_by_id(alloc.match_id)
+                    .await
+                    .ok()
+                    .flatten();
+                Some(CurrentAllocationInfo {
+                    match_id: alloc.match_id,
+                    room_name: match_record.and_then(|m| m.room_name),
+                    role: alloc.role,
+                })
+            }
+            None => None,
+        };
+
+        walk_ins.push(WalkInPoolResponse {
+            walk_in_id: walk_in.id,
+            name: walk_in.name,
+            institution_id: walk_in.institution_id,
+            registered_at: walk_in.created_at,
+            is_allocated: current_allocation.is_some(),
+            current_allocation,
+        });
+    }
+
+    let total_checked_in = (checked_in.len() + walk_ins.len()) as i64;
+    let total_available = total_checked_in - total_allocated;
+
+    Ok(Json(AllocationPoolResponse {
+        event_id: series.event_id,
+        series_id,
+        checked_in_users: users,
+        walk_ins,
+        total_checked_in,
+        total_allocated,
+        total_available,
+    }))
+}
+
+/// Create an allocation - FR-07
+pub async fn create_allocation(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Extension(access): Extension<EventAccess>,
+    Json(mut payload): Json<CreateAllocationRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Validate: one of user_id, guest_name or walk_in_id must be provided
+    if payload.user_id.is_none() && payload.guest_name.is_none() && payload.walk_in_id.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Either user_id, guest_name or walk_in_id must be provided"})),
+        ));
+    }
+    if payload.user_id.is_some() && payload.walk_in_id.is_some() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "A walk-in cannot also be a user"})),
+        ));
+    }
+    require_match_access(&state, &access, payload.match_id).await?;
+
+    // Verify match exists
+    let match_record = state
+        .db
+        .get_match_by_id(payload.match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
+        })?;
+
+    // Walk-ins are allocated under their name, having registered at the desk
+    let mut was_checked_in = false;
+    if let Some(walk_in_id) = payload.walk_in_id {
+        let walk_in = state
+            .db
+            .get_walk_in_by_id(walk_in_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Walk-in not found"})),
+                )
+            })?;
+        let series = state
+            .db
+            .get_series_by_id(match_record.series_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Series not found"})),
+                )
+            })?;
+        if walk_in.event_id != series.event_id {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Walk-in is registered for another event"})),
+            ));
+        }
+        payload.guest_name = Some(walk_in.name);
+        was_checked_in = true;
+    }
+
+    // If user_id provided, verify user exists
+    let mut schedule_conflicts = Vec::new();
+    if let Some(user_id) = payload.user_id {
+        let _user = state
+            .db
+            .get_user_by_id(user_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "User not found"})),
+                )
+            })?;
+        if state.db.is_user_suspended(user_id).await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })? {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({"error": "User is suspended"})),
+            ));
+        }
+
+        // Get the series to find the event
+        let series = state
+            .db
+            .get_series_by_id(match_record.series_id)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "Series not found"})),
+                )
+            })?;
+
+        // For friendly matches, we allow very flexible allocations:
+        // - Same user can have multiple speaker roles (e.g., PM and Reply)
+        // - Same user can be speaker AND adjudicator
+        // - We only prevent exact duplicate allocations (same role + same speaker position)
+        let existing_allocations = state
+            .db
+            .list_allocations_by_match(payload.match_id)
+            .await
+            .unwrap_or_default();
+
+        let has_exact_duplicate = existing_allocations.iter().any(|a| {
+            if a.user_id != Some(user_id) {
+                return false;
+            }
+            if a.role != payload.role {
+                return false;
+            }
+            // For speakers, also check the specific speaker role
+            if payload.role == AllocationRole::Speaker {
+                // Allow same user as speaker if they have different speaker roles
+                let same_two_team_role = a.two_team_speaker_role == payload.two_team_speaker_role
+                    && payload.two_team_speaker_role.is_some();
+                let same_four_team_role = a.four_team_speaker_role
+                    == payload.four_team_speaker_role
+                    && payload.four_team_speaker_role.is_some();
+                return same_two_team_role || same_four_team_role;
+            }
+            // For adjudicators, don't allow duplicate (can only be voting OR non-voting once)
+            true
+        });
+
+        if has_exact_duplicate {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({"error": "User already has this exact role in this match"})),
+            ));
+        }
+
+        // Rooms are checked one series at a time, so a user can end up in
+        // two rounds running at once without this
+        schedule_conflicts =
+            find_schedule_conflicts(&state, user_id, &match_record, series.event_id).await?;
+        if !schedule_conflicts.is_empty() && !payload.allow_schedule_conflict {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": "User is already allocated to another match at that time; resubmit with allow_schedule_conflict to allocate anyway",
+                    "schedule_conflicts": schedule_conflicts
+                })),
+            ));
+        }
+
+        // Check if user is checked in for this event
+        was_checked_in = state
+            .db
+            .is_user_checked_in(series.event_id, user_id)
+            .await
+            .unwrap_or(false);
+    }
+
+    // Get series for validation (if not already fetched)
+    let series = state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    // Validate team_id if speaker role
+    if payload.role == AllocationRole::Speaker && payload.team_id.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Speakers must be assigned to a team"})),
+        ));
+    }
+
+    // Team-entry events draw whole registered teams instead
+    if payload.role == AllocationRole::Speaker {
+        let team_entry = state
+            .db
+            .get_event_by_id(series.event_id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|event| event.team_entry);
+        if team_entry {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "This event takes team entries; assign a registered team to the side instead"
+                })),
+            ));
+        }
+    }
+
+    // Share out the positions across the event's rounds
+    let mut position_repeats = Vec::new();
+    if let (AllocationRole::Speaker, Some(user_id), Some(team_id)) =
+        (payload.role, payload.user_id, payload.team_id)
+    {
+        let side = state.db.get_team_by_id(team_id).await.ok().flatten();
+        if let Some(side) = side {
+            position_repeats =
+                find_position_repeats(&state, &series, &side, true, |d| d.user_id == Some(user_id))
+                    .await?;
+        }
+        if !position_repeats.is_empty() && !payload.allow_position_repeat {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!(
+                        "Speaker has already been drawn as {} {} times; resubmit with allow_position_repeat to allocate anyway",
+                        position_repeats[0].position.label(),
+                        position_repeats.len()
+                    ),
+                    "position_repeats": position_repeats
+                })),
+            ));
+        }
+    }
+
+    // Validate speaker role based on team format
+    if payload.role == AllocationRole::Speaker {
+        match series.team_format {
+            TeamFormat::TwoTeam => {
+                if payload.two_team_speaker_role.is_none() {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": "Two-team speaker role required"})),
+                    ));
+                }
+            }
+            TeamFormat::FourTeam => {
+                if payload.four_team_speaker_role.is_none() {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(json!({"error": "Four-team speaker role required"})),
+                    ));
+                }
+            }
+        }
+    }
+
+    let now = Utc::now();
+    let allocation = Allocation {
+        id: Uuid::new_v4(),
+        match_id: payload.match_id,
+        user_id: payload.user_id,
+        guest_name: payload.guest_name.clone(),
+        role: payload.role,
+        team_id: payload.team_id,
+        two_team_speaker_role: payload.two_team_speaker_role,
+        four_team_speaker_role: payload.four_team_speaker_role,
+        is_chair: Some(payload.is_chair),
+        allocated_at: now,
+        allocated_by: admin_id,
+        was_checked_in,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let created = state.db.create_allocation(&allocation).await.map_err(|e| {
+        tracing::error!("Failed to create allocation: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to create allocation"})),
+        )
+    })?;
+
+    // Create allocation history
+    let history = AllocationHistory {
+        id: Uuid::new_v4(),
+        allocation_id: Some(created.id),
+        match_id: payload.match_id,
+        user_id: payload.user_id,
+        guest_name: payload.guest_name.clone(),
+        action: "created".to_string(),
+        previous_role: None,
+        new_role: Some(payload.role),
+        previous_team_id: None,
+        new_team_id: payload.team_id,
+        changed_by: admin_id,
+        changed_at: now,
+        notes: None,
+    };
+    let _ = state.db.create_allocation_history(&history).await;
+
+    // If this is an adjudicator with a user account, create a ballot for them
+    // (Guest adjudicators don't submit ballots through the system)
+    if let Some(user_id) = payload.user_id {
+        if payload.role == AllocationRole::VotingAdjudicator
+            || payload.role == AllocationRole::NonVotingAdjudicator
+        {
+            let ballot = Ballot {
+                id: Uuid::new_v4(),
+                match_id: payload.match_id,
+                adjudicator_id: user_id,
+                is_voting: payload.role == AllocationRole::VotingAdjudicator,
+                is_submitted: false,
+                submitted_at: None,
+                notes: None,
+                low_point_win: false,
+                created_at: now,
+                updated_at: now,
+            };
+            let _ = state.db.get_or_create_ballot(&ballot).await;
+        }
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Allocation created successfully",
+            "allocation": created,
+            "schedule_conflicts": schedule_conflicts,
+            "position_repeats": position_repeats
+        })),
+    ))
+}
+
+/// The user's other allocations that clash with a match, counting a round
+/// as taking `ROUND_DURATION_MINUTES` from its scheduled time
+async fn find_schedule_conflicts(
+    state: &AppState,
+    user_id: Uuid,
+    match_record: &Match,
+    event_id: Uuid,
+) -> Result<Vec<ScheduleConflict>, (StatusCode, Json<Value>)> {
+    if match_record.status == MatchStatus::Cancelled {
+        return Ok(Vec::new());
+    }
+
+    state
+        .db
+        .list_schedule_conflicts(
+            user_id,
+            match_record,
+            event_id,
+            ROUND_DURATION_MINUTES as i32,
+        )
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })
+}
+
+/// Rounds the team or speaker picked out by `is_drawn` was already on
+/// `side`'s position, when drawing it there again would go over
+/// `rotation::MAX_REPEATS`. Other sides and speeches in the same match are
+/// not counted.
+async fn find_position_repeats(
+    state: &AppState,
+    series: &MatchSeries,
+    side: &MatchTeam,
+    speakers: bool,
+    is_drawn: impl Fn(&DrawnPosition) -> bool,
+) -> Result<Vec<RoundPosition>, (StatusCode, Json<Value>)> {
+    let Some(position) = Position::of(side.two_team_position, side.four_team_position) else {
+        return Ok(Vec::new());
+    };
+    if !rotation::counts_for_rotation(series) {
+        return Ok(Vec::new());
+    }
+
+    let drawn = state
+        .db
+        .list_drawn_positions(series.event_id, speakers)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    let history: Vec<DrawnPosition> = drawn
+        .into_iter()
+        .filter(|d| d.match_id != side.match_id && is_drawn(d))
+        .collect();
+    Ok(rotation::repeats(&history, position))
+}
+
+/// Update an allocation - FR-09
+pub async fn update_allocation(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Extension(access): Extension<EventAccess>,
+    Path(allocation_id): Path<Uuid>,
+    Json(payload): Json<UpdateAllocationRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    // Get existing allocation
+    let existing = state
+        .db
+        .get_allocation_by_id(allocation_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Allocation not found"})),
+            )
+        })?;
+    require_match_access(&state, &access, existing.match_id).await?;
+
+    let updated = state
+        .db
+        .update_allocation(UpdateAllocationParams {
+            allocation_id,
+            role: payload.role,
+            team_id: payload.team_id,
+            two_team_speaker_role: payload.two_team_speaker_role,
+            four_team_speaker_role: payload.four_team_speaker_role,
+            is_chair: payload.is_chair,
+            allocated_by: admin_id,
+        })
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update allocation"})),
+            )
+        })?;
+
+    // Create allocation history
+    let history = AllocationHistory {
+        id: Uuid::new_v4(),
+        allocation_id: Some(allocation_id),
+        match_id: existing.match_id,
+        user_id: existing.user_id,
+        guest_name: existing.guest_name.clone(),
+        action: "updated".to_string(),
+        previous_role: Some(existing.role),
+        new_role: payload.role,
+        previous_team_id: existing.team_id,
+        new_team_id: payload.team_id,
+        changed_by: admin_id,
+        changed_at: Utc::now(),
+        notes: None,
+    };
+    let _ = state.db.create_allocation_history(&history).await;
+
+    // The allocation may have come to clash since the match was rescheduled
+    let mut schedule_conflicts = Vec::new();
+    if let Some(user_id) = existing.user_id {
+        let match_record = state
+            .db
+            .get_match_by_id(existing.match_id)
+            .await
+            .ok()
+            .flatten();
+        let series = match &match_record {
+            Some(m) => state.db.get_series_by_id(m.series_id).await.ok().flatten(),
+            None => None,
+        };
+        if let (Some(match_record), Some(series)) = (match_record, series) {
+            schedule_conflicts =
+                find_schedule_conflicts(&state, user_id, &match_record, series.event_id).await?;
+        }
+    }
+
+    Ok(Json(json!({
+        "message": "Allocation updated successfully",
+        "allocation": updated,
+        "schedule_conflicts": schedule_conflicts
+    })))
+}
+
+/// Swap two allocations - US-1.6
+pub async fn swap_allocations(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Extension(access): Extension<EventAccess>,
+    Json(payload): Json<SwapAllocationRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    // Get both allocations
+    let alloc1 = state
+        .db
+        .get_allocation_by_id(payload.allocation_id_1)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "First allocation not found"})),
+            )
+        })?;
+
+    let alloc2 = state
+        .db
+        .get_allocation_by_id(payload.allocation_id_2)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Second allocation not found"})),
+            )
+        })?;
+    require_match_access(&state, &access, alloc1.match_id).await?;
+    if alloc2.match_id != alloc1.match_id {
+        require_match_access(&state, &access, alloc2.match_id).await?;
+    }
+
+    // Swap the team and role information
+    let _ = state
+        .db
+        .update_allocation(UpdateAllocationParams {
+            allocation_id: alloc1.id,
+            role: Some(alloc2.role),
+            team_id: alloc2.team_id,
+            two_team_speaker_role: alloc2.two_team_speaker_role,
+            four_team_speaker_role: alloc2.four_team_speaker_role,
+            is_chair: alloc2.is_chair,
+            allocated_by: admin_id,
+        })
+        .await;
+
+    let _ = state
+        .db
+        .update_allocation(UpdateAllocationParams {
+            allocation_id: alloc2.id,
+            role: Some(alloc1.role),
+            team_id: alloc1.team_id,
+            two_team_speaker_role: alloc1.two_team_speaker_role,
+            four_team_speaker_role: alloc1.four_team_speaker_role,
+            is_chair: alloc1.is_chair,
+            allocated_by: admin_id,
+        })
+        .await;
+
+    // History for both, written once the swap can no longer be undone
+    let now = Utc::now();
+    let history1 = AllocationHistory {
+        id: Uuid::new_v4(),
+        allocation_id: Some(alloc1.id),
+        match_id: alloc1.match_id,
+        user_id: alloc1.user_id,
+        guest_name: alloc1.guest_name.clone(),
+        action: undo::SWAPPED.to_string(),
+        previous_role: Some(alloc1.role),
+        new_role: Some(alloc2.role),
+        previous_team_id: alloc1.team_id,
+        new_team_id: alloc2.team_id,
+        changed_by: admin_id,
+        changed_at: now,
+        notes: Some(format!("Swapped with allocation {}", alloc2.id)),
+    };
+
+    let history2 = AllocationHistory {
+        id: Uuid::new_v4(),
+        allocation_id: Some(alloc2.id),
+        match_id: alloc2.match_id,
+        user_id: alloc2.user_id,
+        guest_name: alloc2.guest_name.clone(),
+        action: undo::SWAPPED.to_string(),
+        previous_role: Some(alloc2.role),
+        new_role: Some(alloc1.role),
+        previous_team_id: alloc2.team_id,
+        new_team_id: alloc1.team_id,
+        changed_by: admin_id,
+        changed_at: now,
+        notes: Some(format!("Swapped with allocation {}", alloc1.id)),
+    };
+    let undo_token = undo::stage(
+        &state,
+        undo::SWAPPED,
+        vec![alloc1, alloc2],
+        vec![history1, history2],
+        admin_id,
+    )
+    .await;
+
+    Ok(Json(json!({
+        "message": "Allocations swapped successfully",
+        "undo": undo_token
+    })))
+}
+
+/// Delete an allocation
+pub async fn delete_allocation(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Extension(access): Extension<EventAccess>,
+    Path(allocation_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    // Get allocation for history
+    let allocation = state
+        .db
+        .get_allocation_by_id(allocation_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Allocation not found"})),
+            )
+        })?;
+    require_match_access(&state, &access, allocation.match_id).await?;
+
+    // Written once the delete can no longer be undone
+    let history = AllocationHistory {
+        id: Uuid::new_v4(),
+        allocation_id: Some(allocation_id),
+        match_id: allocation.match_id,
+        user_id: allocation.user_id,
+        guest_name: allocation.guest_name.clone(),
+        action: undo::DELETED.to_string(),
+        previous_role: Some(allocation.role),
+        new_role: None,
+        previous_team_id: allocation.team_id,
+        new_team_id: None,
+        changed_by: admin_id,
+        changed_at: Utc::now(),
+        notes: None,
+    };
+
+    state
+        .db
+        .delete_allocation(allocation_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to delete allocation"})),
+            )
+        })?;
+
+    let undo_token = undo::stage(
+        &state,
+        undo::DELETED,
+        vec![allocation],
+        vec![history],
+        admin_id,
+    )
+    .await;
+
+    Ok(Json(json!({
+        "message": "Allocation deleted successfully",
+        "undo": undo_token
+    })))
+}
+
+/// Undo an allocation delete or swap while its undo window is open
+pub async fn undo_allocation_change(
+    State(state): State<Arc<AppState>>,
+    Extension(access): Extension<EventAccess>,
+    Path(change_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Nothing to undo; the change is already final"})),
+        )
+    };
+    let change = state
+        .db
+        .get_staged_change(change_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    for match_id in change.match_ids() {
+        require_match_access(&state, &access, match_id).await?;
+    }
+
+    state
+        .db
+        .undo_staged_change(change_id)
+        .await
+        .map_err(|e| {
+            tracing::warn!("Failed to undo allocation change {}: {}", change_id, e);
+            (
+                StatusCode::CONFLICT,
+                Json(json!({"error": "The allocations have changed since; undo them by hand"})),
+            )
+        })?
+        .ok_or_else(not_found)?;
+
+    Ok(Json(json!({
+        "message": format!("Allocation change undone ({})", change.action),
+        "allocations": change.snapshot.0
+    })))
+}
+
+/// Get allocation history for a match
+pub async fn get_allocation_history(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<Uuid>,
+    Query(query): Query<PerformanceQuery>,
+) -> Result<Json<AllocationHistoryResponse>, (StatusCode, Json<Value>)> {
+    let pagination = Pagination::new(query.page, query.per_page, 50);
+    let (page, per_page) = (pagination.page, pagination.per_page);
+
+    let (history, total) = state
+        .db
+        .list_allocation_history(match_id, page, per_page)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let total_pages = pagination.total_pages(total) as i32;
+
+    Ok(Json(AllocationHistoryResponse {
+        history,
+        total,
+        page,
+        per_page,
+        total_pages,
+    }))
+}
+
+// ============================================================================
+// Ballot Handlers - FR-10 to FR-13
+// ============================================================================
+
+/// Get ballot for current adjudicator - US-2.1
+pub async fn get_my_ballot(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<BallotResponse>, (StatusCode, Json<Value>)> {
+    // Verify user is allocated as adjudicator (use the specific adjudicator lookup)
+    let allocation = state
+        .db
+        .get_adjudicator_allocation_by_user_match(match_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "You are not an adjudicator for this match"})),
+            )
+        })?;
+
+    // No need to check role again - the query already filters for adjudicators
+    if allocation.role != AllocationRole::VotingAdjudicator
+        && allocation.role != AllocationRole::NonVotingAdjudicator
+    {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "You are not an adjudicator for this match"})),
+        ));
+    }
+
+    // Get or create ballot
+    let ballot = match state
+        .db
+        .get_ballot_by_adjudicator_match(match_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })? {
+        Some(b) => b,
+        None => {
+            // Create ballot if doesn't exist
+            let now = Utc::now();
+            let new_ballot = Ballot {
+                id: Uuid::new_v4(),
+                match_id,
+                adjudicator_id: user_id,
+                is_voting: allocation.role == AllocationRole::VotingAdjudicator,
+                is_submitted: false,
+                submitted_at: None,
+                notes: None,
+                low_point_win: false,
+                created_at: now,
+                updated_at: now,
+            };
+            state
+                .db
+                .get_or_create_ballot(&new_ballot)
+                .await
+                .map_err(|e| write_error(e, "Failed to create ballot"))?
+        }
+    };
+
+    let user = state.db.get_user_by_id(user_id).await.ok().flatten();
+    let username = user.map(|u| u.username).unwrap_or_default();
+
+    // Get speaker scores
+    let scores = state
+        .db
+        .list_speaker_scores_by_ballot(ballot.id)
+        .await
+        .unwrap_or_default();
+
+    let mut score_responses = Vec::new();
+    for score in scores {
+        let alloc = state
+            .db
+            .get_allocation_by_id(score.allocation_id)
+            .await
+            .ok()
+            .flatten();
+        let speaker_username = if let Some(a) = &alloc {
+            if let Some(user_id) = a.user_id {
+                state
+                    .db
+                    .get_user_by_id(user_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|u| u.username)
+                    .unwrap_or_default()
+            } else {
+                a.guest_name.clone().unwrap_or_default()
+            }
+        } else {
+            String::new()
+        };
+
+        score_responses.push(SpeakerScoreResponse {
+            id: score.id,
+            allocation_id: score.allocation_id,
+            speaker_username,
+            score: score.score,
+            feedback: score.feedback,
+        });
+    }
+
+    // Get team rankings
+    let rankings = state
+        .db
+        .list_team_rankings_by_ballot(ballot.id)
+        .await
+        .unwrap_or_default();
+
+    let mut ranking_responses = Vec::new();
+    for ranking in rankings {
+        let team = state
+            .db
+            .get_team_by_id(ranking.team_id)
+            .await
+            .ok()
+            .flatten();
+        ranking_responses.push(TeamRankingResponse {
+            id: ranking.id,
+            team_id: ranking.team_id,
+            team_name: team.and_then(|t| t.team_name),
+            rank: ranking.rank,
+            is_winner: ranking.is_winner,
+            total_speaks: ranking.total_speaks,
+            margin: ranking.margin,
+        });
+    }
+
+    let speaker_notes = speaker_note_responses(&state, &ballot).await;
+    Ok(Json(BallotResponse {
+        id: ballot.id,
+        match_id: ballot.match_id,
+        adjudicator_id: ballot.adjudicator_id,
+        adjudicator_username: username,
+        is_voting: ballot.is_voting,
+        is_submitted: ballot.is_submitted,
+        submitted_at: ballot.submitted_at,
+        notes: ballot.notes,
+        low_point_win: ballot.low_point_win,
+        speaker_scores: score_responses,
+        team_rankings: ranking_responses,
+        speaker_notes,
+    }))
+}
+
+/// Submit ballot (voting adjudicator) - FR-10, FR-12, US-2.2
+pub async fn submit_ballot(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<SubmitBallotRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    // Verify user is a voting adjudicator for this match (use specific adjudicator lookup)
+    let allocation = state
+        .db
+        .get_adjudicator_allocation_by_user_match(payload.match_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "You are not an adjudicator for this match"})),
+            )
+        })?;
+
+    if allocation.role != AllocationRole::VotingAdjudicator {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Only voting adjudicators can submit ballots with scores"})),
+        ));
+    }
+
+    if match_series(&state, payload.match_id)
+        .await?
+        .requires_double_entry()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(
+                json!({"error": "Results for this round are entered by two admins from the paper ballot"}),
+            ),
+        ));
+    }
+
+    let submitted = record_ballot(&state, user_id, &payload).await?;
+
+    Ok(Json(json!({
+        "message": "Ballot submitted successfully",
+        "ballot": submitted
+    })))
+}
+
+/// The series a match belongs to
+async fn match_series(
+    state: &AppState,
+    match_id: Uuid,
+) -> Result<MatchSeries, (StatusCode, Json<Value>)> {
+    let match_record = state
+        .db
+        .get_match_by_id(match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
+        })?;
+    state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })
+}
+
+/// One score per speaker, one ranking per team and no tied ranks (FR-13)
+fn check_ballot_lines(
+    speaker_scores: &[SpeakerScoreInput],
+    team_rankings: &[TeamRankingInput],
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let mut scored = HashSet::new();
+    if !speaker_scores
+        .iter()
+        .all(|score| scored.insert(score.allocation_id))
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A speaker can only be scored once per ballot"})),
+        ));
+    }
+    let mut ranked = HashSet::new();
+    if !team_rankings
+        .iter()
+        .all(|ranking| ranked.insert(ranking.team_id))
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A team can only be ranked once per ballot"})),
+        ));
+    }
+    let mut ranks = HashSet::new();
+    if !team_rankings
+        .iter()
+        .all(|ranking| ranks.insert(ranking.rank))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Rankings must be unique (no ties)"})),
+        ));
+    }
+    Ok(())
+}
+
+/// One note per speaker, each for a speaker in the match
+fn check_speaker_notes(
+    notes: &[SpeakerNoteInput],
+    allocations: &[AllocationWithUser],
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let mut noted = HashSet::new();
+    if !notes.iter().all(|note| noted.insert(note.allocation_id)) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "A speaker can only have one note per ballot"})),
+        ));
+    }
+    let is_speaker = |allocation_id: Uuid| {
+        allocations
+            .iter()
+            .any(|a| a.id == allocation_id && a.role == AllocationRole::Speaker)
+    };
+    if !notes.iter().all(|note| is_speaker(note.allocation_id)) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Speaker notes must be for speakers in this match"})),
+        ));
+    }
+    Ok(())
+}
+
+/// Record `adjudicator_id`'s ballot for a match, replacing any earlier
+/// submission, then update the match's results and tell listeners
+async fn record_ballot(
+    state: &AppState,
+    adjudicator_id: Uuid,
+    payload: &SubmitBallotRequest,
+) -> Result<Ballot, (StatusCode, Json<Value>)> {
+    check_ballot_lines(&payload.speaker_scores, &payload.team_rankings)?;
+
+    // Get or create ballot
+    let now = Utc::now();
+    let ballot = state
+        .db
+        .get_or_create_ballot(&Ballot {
+            id: Uuid::new_v4(),
+            match_id: payload.match_id,
+            adjudicator_id,
+            is_voting: true,
+            is_submitted: false,
+            submitted_at: None,
+            notes: None,
+            low_point_win: false,
+            created_at: now,
+            updated_at: now,
+        })
+        .await
+        .map_err(|e| write_error(e, "Failed to create ballot"))?;
+
+    // Team totals on this ballot. Ranking a team above one with more speaks
+    // is usually a slip, so the adjudicator has to confirm it.
+    let scores: Vec<Decimal> = payload
+        .speaker_scores
+        .iter()
+        .map(|s| Decimal::from_f64(s.score).unwrap_or_else(|| Decimal::from(75)))
+        .collect();
+    let allocations = state
+        .db
+        .list_allocations_by_match(payload.match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    check_speaker_notes(&payload.speaker_notes, &allocations)?;
+    let speaks: Vec<(Uuid, Decimal)> = payload
+        .speaker_scores
+        .iter()
+        .zip(&scores)
+        .filter_map(|(input, score)| {
+            let allocation = allocations.iter().find(|a| a.id == input.allocation_id)?;
+            Some((allocation.team_id?, *score))
+        })
+        .collect();
+    let rankings: Vec<(Uuid, i32)> = payload
+        .team_rankings
+        .iter()
+        .map(|r| (r.team_id, r.rank))
+        .collect();
+    let team_margins = margins::team_margins(&rankings, &speaks, &state.db.rounding());
+    let low_point_wins = margins::low_point_wins(&team_margins);
+
+    if !low_point_wins.is_empty() && !payload.confirm_low_point_win {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Ballot ranks a team above one with more speaks; check the scores or resubmit with confirm_low_point_win",
+                "low_point_wins": low_point_wins,
+                "team_margins": team_margins
+            })),
+        ));
+    }
+
+    // Replace the ballot's scores and rankings (re-submission updates them)
+    let speaker_scores: Vec<SpeakerScore> = payload
+        .speaker_scores
+        .iter()
+        .zip(&scores)
+        .map(|(score_input, &score)| SpeakerScore {
+            id: Uuid::new_v4(),
+            ballot_id: ballot.id,
+            allocation_id: score_input.allocation_id,
+            score,
+            feedback: score_input.feedback.clone(),
+            created_at: now,
+            updated_at: now,
+        })
+        .collect();
+    let team_rankings: Vec<TeamRanking> = payload
+        .team_rankings
+        .iter()
+        .map(|ranking_input| {
+            let margin = team_margins
+                .iter()
+                .find(|m| m.team_id == ranking_input.team_id);
+            TeamRanking {
+                id: Uuid::new_v4(),
+                ballot_id: ballot.id,
+                team_id: ranking_input.team_id,
+                rank: ranking_input.rank,
+                is_winner: ranking_input.is_winner,
+                total_speaks: margin.map(|m| m.total_speaks),
+                margin: margin.map(|m| m.margin),
+                created_at: now,
+                updated_at: now,
+            }
+        })
+        .collect();
+
+    state
+        .db
+        .replace_speaker_notes(ballot.id, &payload.speaker_notes)
+        .await
+        .map_err(|e| write_error(e, "Failed to save speaker notes"))?;
+
+    let submitted = state
+        .db
+        .save_ballot_results(
+            ballot.id,
+            &speaker_scores,
+            &team_rankings,
+            payload.notes.as_deref(),
+            !low_point_wins.is_empty(),
+        )
+        .await
+        .map_err(|e| write_error(e, "Failed to submit ballot"))?;
+
+    // Recalculate final rankings from all submitted voting ballots
+    recalculate_team_results(&state.db, payload.match_id).await;
+    if let Err(e) = state.db.refresh_match_performance(payload.match_id).await {
+        tracing::error!(
+            "Failed to refresh performance after ballot {}: {:?}",
+            submitted.id,
+            e
+        );
+    }
+    announce_ballot(state, &submitted).await;
+
+    Ok(submitted)
+}
+
+/// Update every team's final rank and total speaker points in a match from
+/// its submitted voting ballots
+pub(crate) async fn recalculate_team_results(db: &crate::database::Database, match_id: Uuid) {
+    if let Ok(rankings) = db.get_match_team_rankings(match_id).await {
+        // Get total speaker points for each team
+        let teams = db.list_teams_by_match(match_id).await.unwrap_or_default();
+
+        for (rank_position, (team_id, _avg_rank)) in rankings.iter().enumerate() {
+            // Calculate total speaker points from submitted ballots for this team
+            let total_points = calculate_team_total_points(db, *team_id).await;
+
+            // Update team with final rank (1-indexed) and total points
+            let _ = db
+                .update_team_results(*team_id, (rank_position + 1) as i32, total_points)
+                .await;
+        }
+
+        // Also update teams that don't have any rankings yet (set them to last place)
+        for team in teams {
+            if !rankings.iter().any(|(tid, _)| *tid == team.id) {
+                let total_points = calculate_team_total_points(db, team.id).await;
+                let _ = db
+                    .update_team_results(team.id, (rankings.len() + 1) as i32, total_points)
+                    .await;
+            }
+        }
+    }
+}
+
+/// Calculate total speaker points for a team from all submitted voting ballots.
+/// The total is the sum of the speakers' rounded averages, so it matches the
+/// scores shown for them.
+async fn calculate_team_total_points(db: &crate::database::Database, team_id: Uuid) -> Decimal {
+    // Get all allocations for this team (speakers)
+    let allocations = db
+        .list_allocations_by_team(team_id)
+        .await
+        .unwrap_or_default();
+
+    let mut total = Decimal::ZERO;
+    for alloc in allocations {
+        if let Ok(Some(avg_score)) = db.get_allocation_average_score(alloc.id).await {
+            total += avg_score;
+        }
+    }
+    db.rounding().round(total)
+}
+
+/// Submit feedback only (non-voting adjudicator) - FR-11, US-2.3
+pub async fn submit_feedback(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Json(payload): Json<SubmitFeedbackRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    // Verify user is allocated to this match
+    let allocation = state
+        .db
+        .get_allocation_by_user_match(payload.match_id, user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "You are not allocated to this match"})),
+            )
+        })?;
+
+    if allocation.role != AllocationRole::NonVotingAdjudicator
+        && allocation.role != AllocationRole::VotingAdjudicator
+    {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Only adjudicators can submit feedback"})),
+        ));
+    }
+
+    // Get or create ballot
+    let now = Utc::now();
+    let ballot = state
+        .db
+        .get_or_create_ballot(&Ballot {
+            id: Uuid::new_v4(),
+            match_id: payload.match_id,
+            adjudicator_id: user_id,
+            is_voting: allocation.role == AllocationRole::VotingAdjudicator,
+            is_submitted: false,
+            submitted_at: None,
+            notes: None,
+            low_point_win: false,
+            created_at: now,
+            updated_at: now,
+        })
+        .await
+        .map_err(|e| write_error(e, "Failed to create ballot"))?;
+
+    let allocations = state
+        .db
+        .list_allocations_by_match(payload.match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    check_speaker_notes(&payload.speaker_notes, &allocations)?;
+    state
+        .db
+        .replace_speaker_notes(ballot.id, &payload.speaker_notes)
+        .await
+        .map_err(|e| write_error(e, "Failed to save speaker notes"))?;
+
+    // Submit with notes only
+    let submitted = state
+        .db
+        .submit_ballot(ballot.id, Some(&payload.notes), false)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to submit feedback"})),
+            )
+        })?;
+    announce_ballot(&state, &submitted).await;
+
+    Ok(Json(json!({
+        "message": "Feedback submitted successfully",
+        "ballot": submitted
+    })))
+}
+
+/// Admin: Get all ballots for a match - US-3.2
+pub async fn admin_get_match_ballots(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<Vec<BallotResponse>>, (StatusCode, Json<Value>)> {
+    Ok(Json(match_ballot_responses(&state, match_id).await?))
+}
+
+/// Every ballot filed in a match, with its scores and rankings
+async fn match_ballot_responses(
+    state: &AppState,
+    match_id: Uuid,
+) -> Result<Vec<BallotResponse>, (StatusCode, Json<Value>)> {
+    let ballots = state
+        .db
+        .list_ballots_by_match(match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let mut responses = Vec::new();
+    for ballot in ballots {
+        let user = state
+            .db
+            .get_user_by_id(ballot.adjudicator_id)
+            .await
+            .ok()
+            .flatten();
+        let username = user.map(|u| u.username).unwrap_or_default();
+
+        let scores = state
+            .db
+            .list_speaker_scores_by_ballot(ballot.id)
+            .await
+            .unwrap_or_default();
+
+        let mut score_responses = Vec::new();
+        for score in scores {
+            let alloc = state
+                .db
+                .get_allocation_by_id(score.allocation_id)
+                .await
+                .ok()
+                .flatten();
+            let speaker_username = if let Some(a) = &alloc {
+                if let Some(user_id) = a.user_id {
+                    state
+                        .db
+                        .get_user_by_id(user_id)
+                        .await
+                        .ok()
+                        .flatten()
+                        .map(|u| u.username)
+                        .unwrap_or_default()
+                } else {
+                    a.guest_name.clone().unwrap_or_default()
+                }
+            } else {
+                String::new()
+            };
+
+            score_responses.push(SpeakerScoreResponse {
+                id: score.id,
+                allocation_id: score.allocation_id,
+                speaker_username,
+                score: score.score,
+                feedback: score.feedback,
+            });
+        }
+
+        let rankings = state
+            .db
+            .list_team_rankings_by_ballot(ballot.id)
+            .await
+            .unwrap_or_default();
+
+        let mut ranking_responses = Vec::new();
+        for ranking in rankings {
+            let team = state
+                .db
+                .get_team_by_id(ranking.team_id)
+                .await
+                .ok()
+                .flatten();
+            ranking_responses.push(TeamRankingResponse {
+                id: ranking.id,
+                team_id: ranking.team_id,
+                team_name: team.and_then(|t| t.team_name),
+                rank: ranking.rank,
+                is_winner: ranking.is_winner,
+                total_speaks: ranking.total_speaks,
+                margin: ranking.margin,
+            });
+        }
+
+        let speaker_notes = speaker_note_responses(state, &ballot).await;
+        responses.push(BallotResponse {
+            id: ballot.id,
+            match_id: ballot.match_id,
+            adjudicator_id: ballot.adjudicator_id,
+            adjudicator_username: username,
+            is_voting: ballot.is_voting,
+            is_submitted: ballot.is_submitted,
+            submitted_at: ballot.submitted_at,
+            notes: ballot.notes,
+            low_point_win: ballot.low_point_win,
+            speaker_scores: score_responses,
+            team_rankings: ranking_responses,
+            speaker_notes,
+        });
+    }
+
+    Ok(responses)
+}
+
+/// A ballot's notes to speakers, with who each is for
+async fn speaker_note_responses(state: &AppState, ballot: &Ballot) -> Vec<SpeakerNoteResponse> {
+    let notes = state
+        .db
+        .list_speaker_notes_by_ballot(ballot.id)
+        .await
+        .unwrap_or_default();
+    if notes.is_empty() {
+        return Vec::new();
+    }
+    let allocations = state
+        .db
+        .list_allocations_by_match(ballot.match_id)
+        .await
+        .unwrap_or_default();
+
+    notes
+        .into_iter()
+        .map(|note| SpeakerNoteResponse {
+            allocation_id: note.allocation_id,
+            speaker_username: allocations
+                .iter()
+                .find(|a| a.id == note.allocation_id)
+                .map(|a| a.username.clone())
+                .unwrap_or_default(),
+            strengths: note.strengths,
+            improvements: note.improvements,
+            adjcore_only: note.adjcore_only,
+        })
+        .collect()
+}
+
+/// Notes adjudicators left the current user on rounds whose results are
+/// out. Notes kept for the adjudication core are not shown.
+pub async fn my_speaker_notes(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let notes = state
+        .db
+        .list_received_speaker_notes(user_id, true)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok(Json(json!({ "notes": notes })))
+}
+
+// ============================================================================
+// Training Handlers
+// ============================================================================
+
+/// Every note adjudicators have left a member, released or not and
+/// including those kept for the adjudication core (trainers and admins)
+pub async fn get_member_speaker_notes(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let notes = state
+        .db
+        .list_received_speaker_notes(user_id, false)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok(Json(json!({ "user_id": user_id, "notes": notes })))
+}
+
+/// Training series, optionally at one event (trainers and admins)
+pub async fn list_training_series(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TrainingSeriesQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let series = state
+        .db
+        .list_training_series(query.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok(Json(json!({ "series": series })))
+}
+
+/// Every ballot filed in a training series, room by room, submitted or not
+/// (trainers and admins). Ballots from real rounds are not served here.
+pub async fn get_training_ballots(
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<Uuid>,
+) -> Result<Json<TrainingBallotsResponse>, (StatusCode, Json<Value>)> {
+    let series = state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .filter(|series| series.is_training)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Training series not found"})),
+            )
+        })?;
+
+    let (match_records, _) = state
+        .db
+        .list_matches_by_series(series_id, 1, 1000)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let mut matches = Vec::with_capacity(match_records.len());
+    for match_record in match_records {
+        matches.push(TrainingMatchBallots {
+            ballots: match_ballot_responses(&state, match_record.id).await?,
+            match_id: match_record.id,
+            room_name: match_record.room_name,
+            motion: match_record.motion,
+            status: match_record.status,
+        });
+    }
+
+    Ok(Json(TrainingBallotsResponse { series, matches }))
+}
+
+/// Enter a chair's paper ballot in a double-entry round (admin only). Each
+/// admin keeps one entry per room, replaced when they enter again. Once
+/// the latest two entries match, the ballot is recorded for the chair;
+/// until then their differences are returned.
+pub async fn enter_ballot(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(match_id): Path<Uuid>,
+    Json(payload): Json<BallotEntryRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    if !match_series(&state, match_id)
+        .await?
+        .requires_double_entry()
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "This round does not use double entry"})),
+        ));
+    }
+    check_ballot_lines(&payload.speaker_scores, &payload.team_rankings)?;
+
+    let allocations = state
+        .db
+        .list_allocations_by_match(match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    let chair = allocations
+        .iter()
+        .find(|a| a.role == AllocationRole::VotingAdjudicator && a.is_chair == Some(true))
+        .and_then(|a| a.user_id)
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Allocate a chair before entering the ballot"})),
+            )
+        })?;
+
+    let now = Utc::now();
+    let entry = BallotEntry {
+        id: Uuid::new_v4(),
+        match_id,
+        entered_by: admin_id,
+        notes: payload.notes.clone(),
+        confirm_low_point_win: payload.confirm_low_point_win,
+        created_at: now,
+        updated_at: now,
+    };
+    let scores: Vec<BallotEntryScore> = payload
+        .speaker_scores
+        .iter()
+        .map(|s| BallotEntryScore {
+            entry_id: entry.id,
+            allocation_id: s.allocation_id,
+            score: Decimal::from_f64(s.score).unwrap_or_else(|| Decimal::from(75)),
+            feedback: s.feedback.clone(),
+        })
+        .collect();
+    let rankings: Vec<BallotEntryRanking> = payload
+        .team_rankings
+        .iter()
+        .map(|r| BallotEntryRanking {
+            entry_id: entry.id,
+            team_id: r.team_id,
+            rank: r.rank,
+            is_winner: r.is_winner,
+        })
+        .collect();
+    state
+        .db
+        .save_ballot_entry(&entry, &scores, &rankings)
+        .await
+        .map_err(|e| write_error(e, "Failed to save ballot entry"))?;
+
+    let entries = state.db.list_ballot_entries(match_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+    let Some((first, second)) = double_entry::latest_pair(&entries) else {
+        return Ok(Json(json!({
+            "status": "awaiting_second_entry",
+            "message": "Entry saved; another admin must enter the ballot too",
+            "entries": entries
+        })));
+    };
+    let discrepancies = first.discrepancies(second);
+    if !discrepancies.is_empty() {
+        return Ok(Json(json!({
+            "status": "discrepancies",
+            "message": "The entries do not match; check the paper ballot and correct an entry",
+            "discrepancies": discrepancies,
+            "entries": entries
+        })));
+    }
+
+    let ballot = record_ballot(&state, chair, &second.submission()).await?;
+    if let Err(e) = state.db.delete_ballot_entries(match_id).await {
+        tracing::error!("Failed to clear ballot entries for {}: {:?}", match_id, e);
+    }
+
+    Ok(Json(json!({
+        "status": "accepted",
+        "message": "Entries match; ballot recorded",
+        "ballot": ballot
+    })))
+}
+
+/// A match's pending double-entry entries and where the latest two differ
+/// (admin only)
+pub async fn list_ballot_entries(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let entries = state.db.list_ballot_entries(match_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+    let discrepancies = double_entry::latest_pair(&entries)
+        .map(|(first, second)| first.discrepancies(second))
+        .unwrap_or_default();
+
+    Ok(Json(json!({
+        "match_id": match_id,
+        "entries": entries,
+        "discrepancies": discrepancies
+    })))
+}
+
+/// Voting adjudicators yet to submit in every match under way, grouped by
+/// room with how long since it started and how to reach them (Admin only)
+pub async fn get_outstanding_ballots(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    tab_viewer(&state, event_id, None).await?;
+    let ballots = state
+        .db
+        .list_outstanding_ballots(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let now = Utc::now();
+    let total = ballots.len();
+    let mut rooms: Vec<Value> = Vec::new();
+    let mut current: Option<Uuid> = None;
+    for ballot in ballots {
+        if current != Some(ballot.match_id) {
+            current = Some(ballot.match_id);
+            rooms.push(json!({
+                "match_id": ballot.match_id,
+                "series_name": ballot.series_name,
+                "room_name": ballot.room_name,
+                "scheduled_time": ballot.scheduled_time,
+                "minutes_since_start": ballot
+                    .scheduled_time
+                    .map(|start| (now - start).num_minutes().max(0)),
+                "adjudicators": []
+            }));
+        }
+        if let Some(adjudicators) = rooms
+            .last_mut()
+            .and_then(|room| room["adjudicators"].as_array_mut())
+        {
+            adjudicators.push(json!({
+                "user_id": ballot.adjudicator_id,
+                "username": ballot.username,
+                "email": ballot.email,
+                "phone_number": ballot.phone_number,
+                "is_chair": ballot.is_chair
+            }));
+        }
+    }
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "outstanding_ballots": total,
+        "rooms": rooms
+    })))
+}
+
+/// Ballots handed out and submitted per event, for the committee's termly
+/// report (Admin only)
+pub async fn get_ballot_stats(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let stats = state.db.ballot_stats().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(Json(json!(stats)))
+}
+
+/// Stream of ballot submissions in an event with each room's outstanding
+/// ballots, for chasing judges without refreshing (Admin only)
+pub async fn ballot_stream(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, (StatusCode, Json<Value>)> {
+    tab_viewer(&state, event_id, None).await?;
+    let outstanding = state
+        .db
+        .list_ballot_progress(event_id, None)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    // Subscribe before the first snapshot goes out so nothing falls between
+    let receiver = state.ballot_feed.subscribe();
+    let snapshot = SseEvent::default()
+        .event("progress")
+        .data(json!({ "outstanding": outstanding }).to_string());
+
+    let updates = stream::unfold((state, receiver), move |(state, mut receiver)| async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(update) if update.event_id == event_id => {
+                    let rooms = state
+                        .db
+                        .list_ballot_progress(event_id, Some(update.match_id))
+                        .await
+                        .unwrap_or_default();
+                    let (room, outstanding): (Vec<_>, Vec<_>) = rooms
+                        .into_iter()
+                        .partition(|r| r.match_id == update.match_id);
+                    SseEvent::default().event("ballot").data(
+                        json!({
+                            "ballot": update,
+                            "room": room.into_iter().next(),
+                            "outstanding": outstanding
+                                .into_iter()
+                                .filter(|r| r.outstanding > 0)
+                                .collect::<Vec<_>>()
+                        })
+                        .to_string(),
+                    )
+                }
+                Ok(_) => continue,
+                // Too far behind to replay: send the counts as they stand
+                Err(RecvError::Lagged(_)) => {
+                    let outstanding = state
+                        .db
+                        .list_ballot_progress(event_id, None)
+                        .await
+                        .unwrap_or_default();
+                    SseEvent::default()
+                        .event("progress")
+                        .data(json!({ "outstanding": outstanding }).to_string())
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((event, (state, receiver)));
+        }
+    });
+
+    Ok(
+        Sse::new(stream::once(async { snapshot }).chain(updates).map(Ok))
+            .keep_alive(KeepAlive::default()),
+    )
+}
+
+/// Tell open ballot streams about a submission
+async fn announce_ballot(state: &AppState, ballot: &Ballot) {
+    let Ok(Some(match_record)) = state.db.get_match_by_id(ballot.match_id).await else {
+        return;
+    };
+    let Ok(Some(series)) = state.db.get_series_by_id(match_record.series_id).await else {
+        return;
+    };
+
+    // The ballot may have changed the event's standings
+    state.results_cache.invalidate(series.event_id).await;
+    state.ballot_feed.publish(BallotUpdate {
+        event_id: series.event_id,
+        match_id: ballot.match_id,
+        ballot_id: ballot.id,
+        adjudicator_id: ballot.adjudicator_id,
+        is_voting: ballot.is_voting,
+        low_point_win: ballot.low_point_win,
+        submitted_at: ballot.submitted_at.unwrap_or_else(Utc::now),
+    });
+}
+
+// ============================================================================
+// Performance/Tabulation Handlers - FR-14
+// ============================================================================
+
+/// Get performance tab for a user - US-3.1
+pub async fn get_user_performance(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<PerformanceQuery>,
+) -> Result<Json<PerformanceResponse>, (StatusCode, Json<Value>)> {
+    let user = state
+        .db
+        .get_user_by_id(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    if let Some(season_id) = query.season_id {
+        let exists = state.db.season_exists(season_id).await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+        if !exists {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Season not found"})),
+            ));
+        }
+    }
+
+    Ok(Json(
+        user_performance(
+            &state,
+            user_id,
+            user.username,
+            query.event_id,
+            query.season_id,
+        )
+        .await,
+    ))
+}
+
+/// A member's released speaker scores in the order spoken, each with the
+/// rolling average up to it, for a progress chart
+pub async fn get_score_timeline(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<ScoreTimelineQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let window = query.window.unwrap_or(score_timeline::DEFAULT_WINDOW);
+    if !(1..=score_timeline::MAX_WINDOW).contains(&window) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("window must be between 1 and {}", score_timeline::MAX_WINDOW)
+            })),
+        ));
+    }
+
+    let user = state
+        .db
+        .get_user_by_id(user_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    if let Some(season_id) = query.season_id {
+        let exists = state.db.season_exists(season_id).await.map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+        if !exists {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Season not found"})),
+            ));
+        }
+    }
+
+    let speeches = state
+        .db
+        .list_released_speeches(user_id, query.season_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "user_id": user_id,
+        "username": user.username,
+        "window": window,
+        "points": score_timeline::timeline(speeches, window, &state.db.rounding()),
+    })))
+}
+
+/// Rebuild an event's performance read model after its results change. A
+/// failure leaves the old figures in place until the next ballot, so it is
+/// logged rather than failing the request.
+async fn refresh_performance(state: &AppState, event_id: Uuid) {
+    if let Err(e) = state.db.refresh_event_performance(event_id).await {
+        tracing::error!(
+            "Failed to refresh performance for event {}: {:?}",
+            event_id,
+            e
+        );
+    }
+}
+
+/// A member's rounds, scores and results, overall, at one event or over one
+/// season
+pub async fn user_performance(
+    state: &AppState,
+    user_id: Uuid,
+    username: String,
+    event_id: Option<Uuid>,
+    season_id: Option<Uuid>,
+) -> PerformanceResponse {
+    let (total_rounds, speaker_rounds, adjudicator_rounds) = state
+        .db
+        .get_user_round_counts(user_id, event_id, season_id)
+        .await
+        .unwrap_or((0, 0, 0));
+
+    let totals = state
+        .db
+        .get_user_performance_totals(user_id, event_id, season_id)
+        .await
+        .unwrap_or_default();
+    let (wins, losses) = (totals.wins, totals.losses);
+
+    let win_rate = if wins + losses > 0 {
+        Some(Decimal::from(wins * 100) / Decimal::from(wins + losses))
+    } else {
+        None
+    };
+
+    PerformanceResponse {
+        user_id,
+        username,
+        total_rounds,
+        rounds_as_speaker: speaker_rounds,
+        rounds_as_adjudicator: adjudicator_rounds,
+        average_speaker_score: totals
+            .average_speaker_score
+            .map(|average| state.db.rounding().round(average)),
+        total_wins: wins,
+        total_losses: losses,
+        win_rate,
+        rankings: totals.rankings(),
+    }
+}
+
+// ============================================================================
+// Tab Handlers
+// ============================================================================
+
+/// The event, and whether the caller may see unreleased tabs and withheld
+/// speaks
+async fn tab_viewer(
+    state: &AppState,
+    event_id: Uuid,
+    current_user_id: Option<Extension<Uuid>>,
+) -> Result<(EventInfo, bool), (StatusCode, Json<Value>)> {
+    let event = state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let is_admin = match current_user_id {
+        Some(Extension(user_id)) => state.db.is_user_admin(user_id).await.unwrap_or(false),
+        None => false,
+    };
+    Ok((event, is_admin))
+}
+
+/// Each category's settings, with defaults for categories never configured
+async fn tab_categories(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<Vec<TabCategory>, (StatusCode, Json<Value>)> {
+    let stored = state.db.list_tab_categories(event_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    })?;
+
+    Ok(EligibilityFilter::ALL
+        .iter()
+        .map(|category| {
+            stored
+                .iter()
+                .find(|c| c.category == category.as_str())
+                .cloned()
+                .unwrap_or_else(|| TabCategory {
+                    event_id,
+                    category: category.to_string(),
+                    break_size: 0,
+                    team_tab_released: false,
+                    speaker_tab_released: false,
+                    break_released: false,
+                    updated_by: None,
+                    updated_at: Utc::now(),
+                })
+        })
+        .collect())
+}
+
+/// Results behind the tabs; silent rounds only count for admins
+async fn tab_results(
+    state: &AppState,
+    event_id: Uuid,
+    include_silent: bool,
+) -> Result<(Vec<tab::TeamResult>, Vec<tab::SpeechResult>), (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let results = state
+        .db
+        .list_tab_team_results(event_id, include_silent)
+        .await
+        .map_err(db_error)?;
+    let speeches = state
+        .db
+        .list_tab_speeches(event_id, include_silent)
+        .await
+        .map_err(db_error)?;
+    Ok((results, speeches))
+}
+
+/// The event's tie-break rules and draw seed
+async fn tie_break_rules(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<(Vec<TieBreak>, Option<i64>), (StatusCode, Json<Value>)> {
+    let settings = state
+        .db
+        .get_tie_breaks(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let mut rules = settings.rules();
+    if rules.is_empty() {
+        rules = TieBreak::DEFAULT.to_vec();
+    }
+    Ok((rules, settings.tie_break_seed))
+}
+
+fn tab_not_released(category: EligibilityFilter, what: &str) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({"error": format!("The {} {} has not been released", category, what)})),
+    )
+}
+
+/// Team tab for a category (respects the category's release toggle)
+pub async fn get_team_tab(
+    State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<TabQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = query.category.unwrap_or(EligibilityFilter::Open);
+    let (event, is_admin) = tab_viewer(&state, event_id, current_user_id).await?;
+    if !is_admin {
+        state.features.require(Feature::PublicTab).await?;
+    }
+    let settings = tab_categories(&state, event_id).await?;
+    let released = settings
+        .iter()
+        .any(|c| c.category == category.as_str() && c.team_tab_released);
+    if !released && !is_admin {
+        return Err(tab_not_released(category, "team tab"));
+    }
+    let cache_key = state
+        .results_cache
+        .key(event_id, results_cache::TEAM_TAB, category, is_admin)
+        .await;
+    if let Some(tab) = state.results_cache.get(cache_key.as_deref()).await {
+        return Ok(Json(tab));
+    }
+
+    let (results, speeches) = tab_results(&state, event_id, is_admin).await?;
+    let (rules, seed) = tie_break_rules(&state, event_id).await?;
+    let standings = tab::team_standings(
+        &results,
+        &speeches,
+        &rules,
+        seed.unwrap_or_default(),
+        &state.db.rounding(),
+    );
+    let mut teams = tab::team_tab(&standings, category);
+    if event.speaks_withheld && !is_admin {
+        teams
+            .iter_mut()
+            .for_each(tab::TeamTabEntry::withhold_speaks);
+    }
+
+    let tab = json!({
+        "event_id": event_id,
+        "category": category,
+        "released": released,
+        "speaks_withheld": event.speaks_withheld,
+        "tie_breaks": rules,
+        "teams": teams
+    });
+    state.results_cache.put(cache_key.as_deref(), &tab).await;
+    Ok(Json(tab))
+}
+
+/// Speaker tab for a category (respects the category's release toggle)
+pub async fn get_speaker_tab(
+    State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<TabQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = query.category.unwrap_or(EligibilityFilter::Open);
+    let (event, is_admin) = tab_viewer(&state, event_id, current_user_id).await?;
+    if !is_admin {
+        state.features.require(Feature::PublicTab).await?;
+    }
+    let settings = tab_categories(&state, event_id).await?;
+    let released = settings
+        .iter()
+        .any(|c| c.category == category.as_str() && c.speaker_tab_released);
+    if !released && !is_admin {
+        return Err(tab_not_released(category, "speaker tab"));
+    }
+    if event.speaks_withheld && !is_admin {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Speaker scores are withheld until the end of the tournament"})),
+        ));
+    }
+    let cache_key = state
+        .results_cache
+        .key(event_id, results_cache::SPEAKER_TAB, category, is_admin)
+        .await;
+    if let Some(tab) = state.results_cache.get(cache_key.as_deref()).await {
+        return Ok(Json(tab));
+    }
+
+    let (results, speeches) = tab_results(&state, event_id, is_admin).await?;
+
+    let tab = json!({
+        "event_id": event_id,
+        "category": category,
+        "released": released,
+        "speakers": tab::speaker_tab(&results, &speeches, category, &state.db.rounding())
+    });
+    state.results_cache.put(cache_key.as_deref(), &tab).await;
+    Ok(Json(tab))
+}
+
+/// Teams breaking in a category (respects the category's release toggle)
+pub async fn get_break(
+    State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<TabQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = query.category.unwrap_or(EligibilityFilter::Open);
+    let (event, is_admin) = tab_viewer(&state, event_id, current_user_id).await?;
+    if !is_admin {
+        state.features.require(Feature::PublicTab).await?;
+    }
+    let settings = tab_categories(&state, event_id).await?;
+    let Some(current) = settings.iter().find(|c| c.category == category.as_str()) else {
+        return Err(tab_not_released(category, "break"));
+    };
+    if !current.break_released && !is_admin {
+        return Err(tab_not_released(category, "break"));
+    }
+    let cache_key = state
+        .results_cache
+        .key(event_id, results_cache::BREAK, category, is_admin)
+        .await;
+    if let Some(tab) = state.results_cache.get(cache_key.as_deref()).await {
+        return Ok(Json(tab));
+    }
+
+    let (results, speeches) = tab_results(&state, event_id, is_admin).await?;
+    let (rules, seed) = tie_break_rules(&state, event_id).await?;
+    let standings = tab::team_standings(
+        &results,
+        &speeches,
+        &rules,
+        seed.unwrap_or_default(),
+        &state.db.rounding(),
+    );
+    let mut breaks = tab::breaks(&standings, &break_sizes(&settings));
+    let mut teams = breaks.remove(&category).unwrap_or_default();
+    if event.speaks_withheld && !is_admin {
+        teams
+            .iter_mut()
+            .for_each(|entry| entry.team.withhold_speaks());
+    }
+
+    let tab = json!({
+        "event_id": event_id,
+        "category": category,
+        "released": current.break_released,
+        "break_size": current.break_size,
+        "speaks_withheld": event.speaks_withheld,
+        "tie_breaks": rules,
+        "teams": teams
+    });
+    state.results_cache.put(cache_key.as_deref(), &tab).await;
+    Ok(Json(tab))
+}
+
+/// Every category's size matters: earlier breaks take teams out of later ones
+fn break_sizes(settings: &[TabCategory]) -> HashMap<EligibilityFilter, usize> {
+    settings
+        .iter()
+        .filter_map(|c| {
+            let category = c.category.parse::<EligibilityFilter>().ok()?;
+            Some((category, c.break_size.max(0) as usize))
+        })
+        .collect()
+}
+
+/// Standings and break as they would be if the rooms still awaiting results
+/// finished as given, for planning before the last rounds (Admin only)
+pub async fn simulate_standings(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<SimulateStandingsQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let category = query.category.unwrap_or(EligibilityFilter::Open);
+    let scenario = simulate::parse_scenario(query.results.as_deref().unwrap_or_default())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+
+    tab_viewer(&state, event_id, None).await?;
+    let outstanding = state
+        .db
+        .list_outstanding_teams(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    let hypothetical = simulate::scenario_results(&outstanding, &scenario)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+
+    let (mut results, speeches) = tab_results(&state, event_id, true).await?;
+    results.extend(hypothetical);
+    let (rules, seed) = tie_break_rules(&state, event_id).await?;
+    let settings = tab_categories(&state, event_id).await?;
+    let break_size = settings
+        .iter()
+        .find(|c| c.category == category.as_str())
+        .map_or(0, |c| c.break_size);
+
+    let standings = tab::team_standings(
+        &results,
+        &speeches,
+        &rules,
+        seed.unwrap_or_default(),
+        &state.db.rounding(),
+    );
+    let teams = tab::team_tab(&standings, category);
+    let mut breaks = tab::breaks(&standings, &break_sizes(&settings));
+
+    // Rooms the scenario leaves open count for nothing
+    let simulated: HashSet<Uuid> = scenario.iter().flatten().copied().collect();
+    let undecided: Vec<&simulate::OutstandingTeam> = outstanding
+        .iter()
+        .filter(|t| !simulated.contains(&t.match_team_id))
+        .collect();
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "category": category,
+        "tie_breaks": rules,
+        "simulated_rooms": scenario.len(),
+        "undecided": undecided,
+        "teams": teams,
+        "break_size": break_size,
+        "break": breaks.remove(&category).unwrap_or_default()
+    })))
+}
+
+/// Break sizes and release toggles for every category (Admin only)
+pub async fn list_tab_categories(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    tab_viewer(&state, event_id, None).await?;
+    let categories = tab_categories(&state, event_id).await?;
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "categories": categories
+    })))
+}
+
+/// Set a category's break size and release toggles (Admin only)
+pub async fn update_tab_category(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path((event_id, category)): Path<(Uuid, EligibilityFilter)>,
+    Json(payload): Json<UpdateTabCategoryRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+    tab_viewer(&state, event_id, None).await?;
+
+    let updated = state
+        .db
+        .upsert_tab_category(event_id, category, &payload, admin_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to update tab settings"))?;
+    state.results_cache.invalidate(event_id).await;
+
+    Ok(Json(json!({
+        "message": "Tab settings updated successfully",
+        "category": updated
+    })))
+}
+
+/// An event's tie-break rules (Admin only)
+pub async fn get_tie_breaks(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let (rules, seed) = tie_break_rules(&state, event_id).await?;
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "tie_breaks": rules,
+        "tie_break_seed": seed
+    })))
+}
+
+/// Replace an event's tie-break rules (Admin only). The draw seed is picked
+/// the first time the draw rule is used and kept until a reseed is asked for.
+pub async fn update_tie_breaks(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<UpdateTieBreaksRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if payload.tie_breaks.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "At least one tie-break rule is required"})),
+        ));
+    }
+    for (i, rule) in payload.tie_breaks.iter().enumerate() {
+        if payload.tie_breaks[..i].contains(rule) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": format!("Tie-break rule {} is listed twice", rule)})),
+            ));
+        }
+    }
+
+    let current = state
+        .db
+        .get_tie_breaks(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let seed = match current.tie_break_seed {
+        Some(seed) if !payload.reseed => Some(seed),
+        _ if payload.tie_breaks.contains(&TieBreak::Draw) => Some(rand::random::<i64>()),
+        seed => seed,
+    };
+
+    let updated = state
+        .db
+        .set_tie_breaks(event_id, &payload.tie_breaks, seed)
+        .await
+        .map_err(|e| write_error(e, "Failed to update tie-break rules"))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+    state.results_cache.invalidate(event_id).await;
+
+    Ok(Json(json!({
+        "message": "Tie-break rules updated successfully",
+        "event_id": event_id,
+        "tie_breaks": updated.rules(),
+        "tie_break_seed": updated.tie_break_seed
+    })))
+}
+
+// ============================================================================
+// Adjudicator Suggestion Handlers
+// ============================================================================
+
+/// Checked-in judges for a room, best first (Admin only). Candidates are
+/// scored on agreement with their past panels, rounds judged and rooms
+/// already judged that day; institution clashes are listed last.
+pub async fn get_adjudicator_suggestions(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let match_record = state
+        .db
+        .get_match_by_id(match_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
+        })?;
+    let series = state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+
+    let day = match_record
+        .scheduled_time
+        .unwrap_or_else(Utc::now)
+        .date_naive();
+    let candidates = state
+        .db
+        .list_adjudicator_candidates(series.event_id, series.id, day)
+        .await
+        .map_err(db_error)?;
+    let room_institutions = state
+        .db
+        .list_match_institutions(match_id)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .collect();
+    let scores = state
+        .db
+        .list_given_scores(series.event_id, None)
+        .await
+        .map_err(db_error)?;
+    let deviations = judge_stats::judge_stats(&scores, judge_stats::DEFAULT_THRESHOLD)
+        .into_iter()
+        .filter_map(|judge| Some((judge.adjudicator_id, judge.mean_abs_deviation?)))
+        .collect();
+
+    Ok(Json(json!({
+        "match_id": match_id,
+        "series_id": series.id,
+        "suggestions": suggestions::suggest(&candidates, &deviations, &room_institutions)
+    })))
+}
+
+// ============================================================================
+// Judge Calibration Handlers
+// ============================================================================
+
+/// Each judge's scoring spread and deviation from their panels, for
+/// calibration between rounds (Admin only)
+pub async fn get_judge_stats(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+    Query(query): Query<JudgeStatsQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let threshold = query.threshold.unwrap_or(judge_stats::DEFAULT_THRESHOLD);
+    if threshold <= Decimal::ZERO {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Threshold must be positive"})),
+        ));
+    }
+
+    state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let scores = state
+        .db
+        .list_given_scores(event_id, query.series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+    let judges = judge_stats::judge_stats(&scores, threshold);
+    let outliers = judges.iter().filter(|j| j.is_outlier).count();
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "series_id": query.series_id,
+        "threshold": threshold,
+        "outliers": outliers,
+        "judges": judges
+    })))
+}
+
+/// Side balance of each motion set at an event, from confirmed results, to
+/// check motion fairness between rounds (Admin only)
+pub async fn get_motion_stats(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let results = state
+        .db
+        .list_motion_results(event_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "motions": motion_stats::motion_stats(&results, &state.db.rounding())
+    })))
+}
+
+/// Positions each team and speaker has been drawn on across the event's
+/// rounds, most unevenly spread first, with the positions the draw should
+/// avoid for them (Admin only)
+pub async fn get_position_rotation(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    let sides = state
+        .db
+        .list_drawn_positions(event_id, false)
+        .await
+        .map_err(db_error)?;
+    let speeches = state
+        .db
+        .list_drawn_positions(event_id, true)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(json!({
+        "event_id": event_id,
+        "max_repeats": rotation::MAX_REPEATS,
+        "teams": rotation::team_rotation(&sides),
+        "speakers": rotation::speaker_rotation(&speeches)
+    })))
+}
+
+// ============================================================================
+// Judging Record Handlers
+// ============================================================================
+
+/// A member's judging record: rooms chaired, panelled and trainee'd, and
+/// their accreditation with its history
+pub async fn get_judging_record(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<JudgingRecord>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let user = state
+        .db
+        .get_user_by_id(user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            )
+        })?;
+
+    let rounds = state
+        .db
+        .get_judging_rounds(user_id)
+        .await
+        .map_err(db_error)?;
+    let history = state
+        .db
+        .list_accreditation_history(user_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(JudgingRecord {
+        user_id,
+        username: user.username,
+        accreditation: history.first().map(|change| change.level),
+        rounds,
+        history,
+    }))
+}
+
+/// Grade a judge, adding to their accreditation ledger (Admin only)
+pub async fn set_judge_accreditation(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetAccreditationRequest>,
+) -> Result<Json<JudgingRecord>, (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+    state
+        .db
+        .set_accreditation(user_id, payload.level, note, admin_id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_foreign_key_violation() => (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "User not found"})),
+            ),
+            e => write_error(e, "Failed to set accreditation"),
+        })?;
+
+    get_judging_record(State(state), Path(user_id)).await
+}
+
+// ============================================================================
+// Printable Handlers
+// ============================================================================
+
+/// The series' event title, for printed headings
+async fn series_event_title(
+    state: &AppState,
+    series: &MatchSeries,
+) -> Result<String, (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_event_by_id(series.event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .map(|event| event.title)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })
+}
+
+/// A paper ballot for one room, to fill in by hand where the venue has no
+/// reliable internet (admin only)
+pub async fn match_ballot_pdf(
+    State(state): State<Arc<AppState>>,
+    Path(match_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let match_record = state
+        .db
+        .get_match_by_id(match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
+        })?;
+    let series = state
+        .db
+        .get_series_by_id(match_record.series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+    let event_title = series_event_title(&state, &series).await?;
+    let room = build_match_response(&state, &match_record, true).await?;
+
+    Ok(printables::ballot(&event_title, &series, &room).into_response())
+}
+
+/// Room slips for every room of a round: teams, speakers, panel and space
+/// for the motion (admin only)
+pub async fn series_room_slips(
+    State(state): State<Arc<AppState>>,
+    Path(series_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let series = state
+        .db
+        .get_series_by_id(series_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Series not found"})),
+            )
+        })?;
+    let event_title = series_event_title(&state, &series).await?;
+    let (matches, _) = state
+        .db
+        .list_matches_by_series(series_id, 1, 1000)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let mut rooms = Vec::with_capacity(matches.len());
+    for match_record in &matches {
+        rooms.push(build_match_response(&state, match_record, true).await?);
+    }
+
+    Ok(printables::room_slips(&event_title, &series, &rooms).into_response())
+}
+
+// ============================================================================
+// Tabbycat Interop Handlers
+// ============================================================================
+
+async fn tabbycat_document(
+    state: &AppState,
+    event_id: Uuid,
+) -> Result<tabbycat::Tournament, (StatusCode, Json<Value>)> {
+    tabbycat::export(&state.db, event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })
+}
+
+fn csv_attachment(
+    filename: &str,
+    body: Result<String, csv::Error>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let body = body.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to write CSV"})),
+        )
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Export an event's rounds, draws and results as a Tabbycat document (admin only)
+pub async fn export_tabbycat(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<tabbycat::Tournament>, (StatusCode, Json<Value>)> {
+    tabbycat_document(&state, event_id).await.map(Json)
+}
+
+/// Export an event's speakers as Tabbycat's `speakers.csv` (admin only)
+pub async fn export_tabbycat_speakers(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let document = tabbycat_document(&state, event_id).await?;
+    csv_attachment("speakers.csv", tabbycat::speakers_csv(&document))
+}
+
+/// Export an event's adjudicators as Tabbycat's `judges.csv` (admin only)
+pub async fn export_tabbycat_judges(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let document = tabbycat_document(&state, event_id).await?;
+    csv_attachment("judges.csv", tabbycat::judges_csv(&document))
+}
+
+/// Import rounds, draws and results from a Tabbycat document into an event
+/// that has no rounds yet (admin only)
+pub async fn import_tabbycat(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<tabbycat::Tournament>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let summary = tabbycat::import(&state.db, event_id, admin_id, &payload)
+        .await
+        .map_err(|e| match e {
+            tabbycat::ImportError::Invalid(problems) => (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Invalid Tabbycat document", "problems": problems})),
+            ),
+            tabbycat::ImportError::EventNotFound => (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            ),
+            tabbycat::ImportError::EventNotEmpty => {
+                (StatusCode::CONFLICT, Json(json!({"error": e.to_string()})))
+            }
+            tabbycat::ImportError::Database(e) => {
+                tracing::error!("Tabbycat import into event {} failed: {}", event_id, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Failed to import tournament"})),
+                )
+            }
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Tournament imported successfully",
+            "summary": summary
+        })),
+    ))
+}
+
+// ============================================================================
+// Archive Handlers
+// ============================================================================
+
+/// Export an event to an immutable archive and make it read-only (admin only)
+pub async fn archive_event(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let record = archive::archive_event(&state.db, &state.storage, event_id, admin_id)
+        .await
+        .map_err(|e| match e {
+            archive::ArchiveError::EventNotFound => (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            ),
+            archive::ArchiveError::AlreadyArchived(ref record) => (
+                StatusCode::CONFLICT,
+                Json(json!({"error": e.to_string(), "archive": record})),
+            ),
+            e => {
+                tracing::error!("Archiving event {} failed: {}", event_id, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Failed to archive event"})),
+                )
+            }
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Event archived successfully",
+            "location": state.storage.location(&record.archive_key),
+            "archive": record
+        })),
+    ))
+}
+
+/// Download an archived event's zip (admin only)
+pub async fn download_archive(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let record = state
+        .db
+        .get_event_archive(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Event has not been archived"})),
+        ))?;
+
+    let body = state.storage.get(&record.archive_key).await.map_err(|e| {
+        tracing::error!("Failed to read archive {}: {}", record.archive_key, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Failed to read archive"})),
+        )
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"event-{}.zip\"", event_id),
+            ),
+            (
+                header::HeaderName::from_static("x-archive-sha256"),
+                record.archive_sha256,
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+// ============================================================================
+// Attachment Handlers
+// ============================================================================
+
+/// Register an info pack for an event and get an upload URL (admin only)
+pub async fn create_event_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(event_id): Path<Uuid>,
+    Json(payload): Json<CreateAttachmentRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_event_by_id(event_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+
+    create_attachment(&state, admin_id, Some(event_id), None, payload).await
+}
+
+/// Register an info slide for a match and get an upload URL (admin only)
+pub async fn create_match_attachment(
+    State(state): State<Arc<AppState>>,
+    Extension(admin_id): Extension<Uuid>,
+    Path(match_id): Path<Uuid>,
+    Json(payload): Json<CreateAttachmentRequest>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    state
+        .db
+        .get_match_by_id(match_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
+        })?;
+
+    create_attachment(&state, admin_id, None, Some(match_id), payload).await
+}
+
+async fn create_attachment(
+    state: &Arc<AppState>,
+    admin_id: Uuid,
+    event_id: Option<Uuid>,
+    match_id: Option<Uuid>,
+    payload: CreateAttachmentRequest,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    payload.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+    attachments::check_content_type(&payload.content_type)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({"error": e}))))?;
+    if payload
+        .size_bytes
+        .is_some_and(|size| size as u64 > state.config.attachment_max_bytes)
+    {
+        return Err(attachment_too_large(state));
+    }
+
+    let id = Uuid::new_v4();
+    let attachment = Attachment {
+        id,
+        event_id,
+        match_id,
+        storage_key: attachments::storage_key(id, &payload.filename),
+        filename: payload.filename,
+        content_type: payload.content_type,
+        size_bytes: payload.size_bytes,
+        uploaded_by: admin_id,
+        created_at: Utc::now(),
+        uploaded_at: None,
+    };
+
+    let created = state
+        .db
+        .create_attachment(&attachment)
+        .await
+        .map_err(|e| write_error(e, "Failed to create attachment"))?;
+    let upload = attachments::upload_instructions(&state.storage, &created);
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({
+            "message": "Attachment created, upload the file then confirm it",
+            "attachment": created,
+            "upload": upload
+        })),
+    ))
+}
+
+/// Receive an attachment's contents when files are kept on disk (admin only)
+pub async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(attachment_id): Path<Uuid>,
+    body: Bytes,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let attachment = find_attachment(&state, attachment_id).await?;
+
+    if attachment.uploaded_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Attachment has already been uploaded"})),
+        ));
+    }
+    if body.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "File cannot be empty"})),
+        ));
+    }
+
+    state
+        .storage
+        .put(
+            &attachment.storage_key,
+            body.to_vec(),
+            &attachment.content_type,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to store attachment {}: {}", attachment_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to store file"})),
+            )
+        })?;
+
+    Ok(Json(
+        json!({"message": "File uploaded, confirm it to publish"}),
+    ))
+}
+
+/// Confirm an attachment's upload so it becomes visible (admin only)
+pub async fn complete_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let attachment = find_attachment(&state, attachment_id).await?;
+
+    let size = match state.storage.size(&attachment.storage_key).await {
+        Ok(size) => size,
+        Err(StorageError::NotFound(_)) => {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({"error": "File has not been uploaded yet"})),
+            ));
+        }
+        Err(e) => {
+            tracing::error!("Failed to check attachment {}: {}", attachment_id, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to check uploaded file"})),
+            ));
+        }
+    };
+
+    // Presigned uploads cannot limit their size, so enforce it here
+    if size > state.config.attachment_max_bytes {
+        if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+            tracing::warn!(
+                "Failed to remove oversized attachment {}: {}",
+                attachment_id,
+                e
+            );
+        }
+        return Err(attachment_too_large(&state));
+    }
+
+    let updated = state
+        .db
+        .mark_attachment_uploaded(attachment_id, size as i64)
+        .await
+        .map_err(|e| write_error(e, "Failed to update attachment"))?;
+
+    Ok(Json(json!({
+        "message": "Attachment uploaded successfully",
+        "attachment": updated
+    })))
+}
+
+/// Delete an attachment and its file (admin only)
+pub async fn delete_attachment(
+    State(state): State<Arc<AppState>>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let attachment = find_attachment(&state, attachment_id).await?;
+
+    state
+        .db
+        .delete_attachment(attachment_id)
+        .await
+        .map_err(|e| write_error(e, "Failed to delete attachment"))?;
+
+    if let Err(e) = state.storage.delete(&attachment.storage_key).await {
+        tracing::warn!(
+            "Deleted attachment {} but not its file {}: {}",
+            attachment_id,
+            attachment.storage_key,
+            e
+        );
+    }
 
-    // Submit with notes only
-    let submitted = state
+    Ok(Json(json!({"message": "Attachment deleted successfully"})))
+}
+
+/// List an event's info packs
+pub async fn list_event_attachments(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let attachments = state
         .db
-        .submit_ballot(ballot.id, Some(&payload.notes))
+        .list_attachments(Some(event_id), None)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Failed to submit feedback"})),
+                Json(json!({"error": "Database error"})),
             )
         })?;
 
-    Ok(Json(json!({
-        "message": "Feedback submitted successfully",
-        "ballot": submitted
-    })))
+    Ok(Json(json!({ "attachments": attachments })))
 }
 
-/// Admin: Get all ballots for a match - US-3.2
-pub async fn admin_get_match_ballots(
+/// List a match's info slides; empty until the match is released unless
+/// the user is an admin
+pub async fn list_match_attachments(
     State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
     Path(match_id): Path<Uuid>,
-) -> Result<Json<Vec<BallotResponse>>, (StatusCode, Json<Value>)> {
-    let ballots = state
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let match_record = state
         .db
-        .list_ballots_by_match(match_id)
+        .get_match_by_id(match_id)
         .await
         .map_err(|_| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({"error": "Database error"})),
             )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Match not found"})),
+            )
         })?;
 
-    let mut responses = Vec::new();
-    for ballot in ballots {
-        let user = state
-            .db
-            .get_user_by_id(ballot.adjudicator_id)
-            .await
-            .ok()
-            .flatten();
-        let username = user.map(|u| u.username).unwrap_or_default();
+    let is_admin = if let Some(Extension(user_id)) = current_user_id {
+        state.db.is_user_admin(user_id).await.unwrap_or(false)
+    } else {
+        false
+    };
 
-        let scores = state
+    let attachments = if match_record.is_released() || is_admin {
+        state
             .db
-            .list_speaker_scores_by_ballot(ballot.id)
+            .list_attachments(None, Some(match_id))
             .await
-            .unwrap_or_default();
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+    } else {
+        Vec::new()
+    };
 
-        let mut score_responses = Vec::new();
-        for score in scores {
-            let alloc = state
-                .db
-                .get_allocation_by_id(score.allocation_id)
-                .await
-                .ok()
-                .flatten();
-            let speaker_username = if let Some(a) = &alloc {
-                if let Some(user_id) = a.user_id {
-                    state
-                        .db
-                        .get_user_by_id(user_id)
-                        .await
-                        .ok()
-                        .flatten()
-                        .map(|u| u.username)
-                        .unwrap_or_default()
-                } else {
-                    a.guest_name.clone().unwrap_or_default()
-                }
-            } else {
-                String::new()
-            };
+    Ok(Json(json!({ "attachments": attachments })))
+}
 
-            score_responses.push(SpeakerScoreResponse {
-                id: score.id,
-                allocation_id: score.allocation_id,
-                speaker_username,
-                score: score.score,
-                feedback: score.feedback,
-            });
-        }
+/// Download an attachment. Object storage redirects to a short-lived URL;
+/// files on disk are served directly.
+pub async fn get_attachment(
+    State(state): State<Arc<AppState>>,
+    current_user_id: Option<Extension<Uuid>>,
+    Path(attachment_id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Attachment not found"})),
+        )
+    };
+    let attachment = find_attachment(&state, attachment_id).await?;
+    if attachment.uploaded_at.is_none() {
+        return Err(not_found());
+    }
 
-        let rankings = state
+    if let Some(match_id) = attachment.match_id {
+        let is_admin = if let Some(Extension(user_id)) = current_user_id {
+            state.db.is_user_admin(user_id).await.unwrap_or(false)
+        } else {
+            false
+        };
+        let released = state
             .db
-            .list_team_rankings_by_ballot(ballot.id)
+            .get_match_by_id(match_id)
             .await
-            .unwrap_or_default();
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Database error"})),
+                )
+            })?
+            .is_some_and(|m| m.is_released());
 
-        let mut ranking_responses = Vec::new();
-        for ranking in rankings {
-            let team = state
-                .db
-                .get_team_by_id(ranking.team_id)
-                .await
-                .ok()
-                .flatten();
-            ranking_responses.push(TeamRankingResponse {
-                id: ranking.id,
-                team_id: ranking.team_id,
-                team_name: team.and_then(|t| t.team_name),
-                rank: ranking.rank,
-                is_winner: ranking.is_winner,
-            });
+        // Unreleased slides are hidden entirely rather than forbidden
+        if !released && !is_admin {
+            return Err(not_found());
         }
+    }
 
-        responses.push(BallotResponse {
-            id: ballot.id,
-            match_id: ballot.match_id,
-            adjudicator_id: ballot.adjudicator_id,
-            adjudicator_username: username,
-            is_voting: ballot.is_voting,
-            is_submitted: ballot.is_submitted,
-            submitted_at: ballot.submitted_at,
-            notes: ballot.notes,
-            speaker_scores: score_responses,
-            team_rankings: ranking_responses,
-        });
+    if let Some(url) = attachments::download_url(&state.storage, &attachment) {
+        return Ok(Redirect::temporary(&url).into_response());
     }
 
-    Ok(Json(responses))
-}
+    let body = state
+        .storage
+        .get(&attachment.storage_key)
+        .await
+        .map_err(|e| match e {
+            StorageError::NotFound(_) => not_found(),
+            e => {
+                tracing::error!("Failed to read attachment {}: {}", attachment_id, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": "Failed to read attachment"})),
+                )
+            }
+        })?;
 
-// ============================================================================
-// Performance/Tabulation Handlers - FR-14
-// ============================================================================
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type.clone()),
+            (
+                header::CONTENT_DISPOSITION,
+                attachments::content_disposition(&attachment.filename),
+            ),
+            (header::CACHE_CONTROL, "private, max-age=300".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
 
-/// Get performance tab for a user - US-3.1
-pub async fn get_user_performance(
-    State(state): State<Arc<AppState>>,
-    Path(user_id): Path<Uuid>,
-    Query(query): Query<PerformanceQuery>,
-) -> Result<Json<PerformanceResponse>, (StatusCode, Json<Value>)> {
-    let user = state
+async fn find_attachment(
+    state: &Arc<AppState>,
+    attachment_id: Uuid,
+) -> Result<Attachment, (StatusCode, Json<Value>)> {
+    state
         .db
-        .get_user_by_id(user_id)
+        .get_attachment_by_id(attachment_id)
         .await
         .map_err(|_| {
             (
@@ -1780,57 +5958,178 @@ pub async fn get_user_performance(
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
-                Json(json!({"error": "User not found"})),
+                Json(json!({"error": "Attachment not found"})),
             )
-        })?;
+        })
+}
 
-    let (total_rounds, speaker_rounds, adjudicator_rounds) = state
+fn attachment_too_large(state: &AppState) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(json!({
+            "error": format!(
+                "File is larger than the {} byte limit",
+                state.config.attachment_max_bytes
+            )
+        })),
+    )
+}
+
+/// Map a failed write, reporting archived events as read-only rather than
+/// as a server error
+fn write_error(e: sqlx::Error, message: &str) -> (StatusCode, Json<Value>) {
+    let archived = e
+        .as_database_error()
+        .and_then(|db| db.code())
+        .is_some_and(|code| code == "55000");
+    let duplicate = e
+        .as_database_error()
+        .is_some_and(|db| db.is_unique_violation());
+
+    if archived {
+        (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Event is archived and read-only"})),
+        )
+    } else if duplicate {
+        (
+            StatusCode::CONFLICT,
+            Json(json!({"error": format!("{}: it conflicts with an existing record", message)})),
+        )
+    } else {
+        tracing::error!("{}: {}", message, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": message})),
+        )
+    }
+}
+
+// ============================================================================
+// Calendar Handlers
+// ============================================================================
+
+/// Length assumed for a round in calendar entries and allocation clashes
+const ROUND_DURATION_MINUTES: i64 = 90;
+
+/// iCalendar feed of the current user's allocations
+pub async fn my_calendar(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+) -> Result<Calendar, (StatusCode, Json<Value>)> {
+    let allocations = state
         .db
-        .get_user_round_counts(user_id, query.event_id)
+        .list_calendar_allocations(user_id)
         .await
-        .unwrap_or((0, 0, 0));
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+        })?;
+
+    let mut calendar = Calendar::new("My Tabrela rounds");
+    for allocation in allocations {
+        calendar.push(calendar_entry(allocation));
+    }
 
-    let avg_score = state
+    Ok(calendar)
+}
+
+/// Every room the current user is drawn into for an event, in one call
+pub async fn my_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(user_id): Extension<Uuid>,
+    Query(query): Query<ScheduleQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": "Database error"})),
+        )
+    };
+    let event = state
         .db
-        .get_average_speaker_score(user_id, query.event_id)
+        .get_event_by_id(query.event_id)
         .await
-        .unwrap_or(None);
-
-    let (wins, losses) = state
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": "Event not found"})),
+            )
+        })?;
+    let schedule = state
         .db
-        .get_user_win_loss(user_id, query.event_id)
+        .list_schedule(user_id, query.event_id)
         .await
-        .unwrap_or((0, 0));
+        .map_err(db_error)?;
 
-    let win_rate = if wins + losses > 0 {
-        Some(Decimal::from(wins * 100) / Decimal::from(wins + losses))
-    } else {
-        None
+    Ok(Json(json!({
+        "event_id": event.id,
+        "event_title": event.title,
+        "schedule": schedule
+    })))
+}
+
+fn calendar_entry(allocation: CalendarAllocation) -> CalendarEntry {
+    let role = match (allocation.role, allocation.is_chair) {
+        (AllocationRole::Speaker, _) => format!(
+            "Speaking ({})",
+            notifications::position_label(
+                allocation.two_team_position,
+                allocation.four_team_position
+            )
+        ),
+        (AllocationRole::VotingAdjudicator, Some(true)) => "Chairing".to_string(),
+        (AllocationRole::VotingAdjudicator, _) => "Judging".to_string(),
+        (AllocationRole::NonVotingAdjudicator, _) => "Trainee judging".to_string(),
+        (AllocationRole::Resource, _) => "Resource".to_string(),
     };
 
-    let ranking_dist = state
-        .db
-        .get_user_ranking_distribution(user_id, query.event_id)
-        .await
-        .unwrap_or_default();
+    let mut description = vec![allocation.event_title];
+    if let Some(team_name) = allocation.team_name {
+        description.push(format!("Team: {}", team_name));
+    }
 
-    let rankings = ranking_dist
-        .into_iter()
-        .map(|(rank, count)| RankingCount { rank, count })
-        .collect();
+    let location = match (allocation.room_name, allocation.event_location) {
+        (Some(room), Some(venue)) => Some(format!("{}, {}", room, venue)),
+        (room, venue) => room.or(venue),
+    };
 
-    Ok(Json(PerformanceResponse {
-        user_id,
-        username: user.username,
-        total_rounds,
-        rounds_as_speaker: speaker_rounds,
-        rounds_as_adjudicator: adjudicator_rounds,
-        average_speaker_score: avg_score,
-        total_wins: wins,
-        total_losses: losses,
-        win_rate,
-        rankings,
-    }))
+    let start = allocation.scheduled_time.unwrap_or(allocation.event_date);
+    CalendarEntry {
+        uid: format!("allocation-{}@tabrela", allocation.allocation_id),
+        start,
+        end: start + chrono::Duration::minutes(ROUND_DURATION_MINUTES),
+        summary: format!("{} · {}", allocation.series_name, role),
+        description: Some(description.join("\n")),
+        location,
+        updated_at: allocation.updated_at,
+    }
+}
+
+// ============================================================================
+// GraphQL Handlers
+// ============================================================================
+
+/// Run a GraphQL query as the current user, if any
+pub async fn graphql(
+    State(state): State<Arc<AppState>>,
+    Extension(schema): Extension<graphql::TabrelaSchema>,
+    current_user_id: Option<Extension<Uuid>>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let user_id = current_user_id.map(|Extension(user_id)| user_id);
+    let is_admin = match user_id {
+        Some(user_id) => state.db.is_user_admin(user_id).await.unwrap_or(false),
+        None => false,
+    };
+
+    let request = request
+        .data(state.clone())
+        .data(graphql::Viewer { user_id, is_admin });
+    Json(schema.execute(request).await)
 }
 
 // ============================================================================
@@ -1855,6 +6154,20 @@ async fn build_match_response(
         .map(|s| s.team_format)
         .unwrap_or(TeamFormat::TwoTeam);
 
+    let speaks_withheld = match &series {
+        Some(series) => state
+            .db
+            .get_event_by_id(series.event_id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|event| event.speaks_withheld),
+        None => false,
+    };
+    let is_silent = series.as_ref().is_some_and(|s| s.is_silent);
+    let visibility = ResultVisibility::new(match_record, is_silent, speaks_withheld, is_admin);
+    let (show_rankings, show_speaks) = (visibility.rankings, visibility.speaks);
+
     let teams = state
         .db
         .list_teams_by_match(match_record.id)
@@ -1876,7 +6189,7 @@ async fn build_match_response(
             match alloc.role {
                 AllocationRole::Speaker => {
                     // Get average score from submitted voting ballots if scores are released
-                    let score = if match_record.scores_released || is_admin {
+                    let score = if show_speaks {
                         state
                             .db
                             .get_allocation_average_score(alloc.id)
@@ -1915,16 +6228,14 @@ async fn build_match_response(
             four_team_position: team.four_team_position,
             team_name: team.team_name,
             institution: team.institution,
-            final_rank: if match_record.rankings_released || is_admin {
-                team.final_rank
-            } else {
-                None
-            },
-            total_speaker_points: if match_record.scores_released || is_admin {
+            final_rank: if show_rankings { team.final_rank } else { None },
+            total_speaker_points: if show_speaks {
                 team.total_speaker_points
             } else {
                 None
             },
+            registered_team_id: team.registered_team_id,
+            institution_id: team.institution_id,
             speakers,
             resources,
         });
@@ -1973,12 +6284,15 @@ async fn build_match_response(
         series_id: match_record.series_id,
         series_name,
         room_name: match_record.room_name.clone(),
+        venue_id: match_record.venue_id,
         motion: match_record.motion.clone(),
         info_slide: match_record.info_slide.clone(),
         status: match_record.status,
         scheduled_time: match_record.scheduled_time,
         scores_released: match_record.scores_released,
         rankings_released: match_record.rankings_released,
+        speaks_withheld: match_record.scores_released && speaks_withheld,
+        is_silent,
         teams: team_responses,
         adjudicators,
         created_at: match_record.created_at,
@@ -0,0 +1,271 @@
+//! Judge calibration statistics from submitted voting ballots.
+//!
+//! For each judge: the average and standard deviation of the scores they
+//! gave, and per match their deviation from the panel consensus. The
+//! consensus for a speech is the mean score the *other* voting judges gave
+//! it, so a judge on a two-person panel is not half-compared with
+//! themselves. Matches judged alone have no consensus to compare against.
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// How far a judge's match deviation may stray, in points per speech,
+/// before it is flagged
+pub const DEFAULT_THRESHOLD: Decimal = Decimal::TWO;
+
+/// One score on one submitted voting ballot
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct GivenScore {
+    pub adjudicator_id: Uuid,
+    pub username: String,
+    pub match_id: Uuid,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub allocation_id: Uuid,
+    pub score: Decimal,
+    pub low_point_win: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchDeviation {
+    pub match_id: Uuid,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub speeches: usize,
+    pub average_score: Decimal,
+    /// Mean of this judge's score less the consensus, per speech; negative
+    /// means harsher than the rest of the panel
+    pub deviation: Option<Decimal>,
+    pub flagged: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JudgeStats {
+    pub adjudicator_id: Uuid,
+    pub username: String,
+    pub ballots: usize,
+    pub scores_given: usize,
+    pub average_score: Decimal,
+    pub standard_deviation: Decimal,
+    /// Mean of the match deviations where there was a panel to compare with
+    pub mean_deviation: Option<Decimal>,
+    /// Same, ignoring direction: how far off they are whichever way
+    pub mean_abs_deviation: Option<Decimal>,
+    pub low_point_wins: usize,
+    pub flagged_matches: usize,
+    /// Consistently away from their panels by more than the threshold
+    pub is_outlier: bool,
+    pub matches: Vec<MatchDeviation>,
+}
+
+/// One judge's scores in one match while they are gathered
+#[derive(Default)]
+struct MatchScores {
+    scores: Vec<Decimal>,
+    deviations: Vec<Decimal>,
+    low_point_win: bool,
+}
+
+fn mean(values: &[Decimal]) -> Decimal {
+    if values.is_empty() {
+        return Decimal::ZERO;
+    }
+    values.iter().sum::<Decimal>() / Decimal::from(values.len())
+}
+
+/// Population standard deviation, to the hundredth
+fn standard_deviation(values: &[Decimal]) -> Decimal {
+    let average = mean(values);
+    let variance = mean(
+        &values
+            .iter()
+            .map(|v| (v - average) * (v - average))
+            .collect::<Vec<_>>(),
+    );
+    variance
+        .to_f64()
+        .and_then(|v| Decimal::from_f64_retain(v.sqrt()))
+        .unwrap_or_default()
+        .round_dp(2)
+}
+
+/// Stats for every judge with at least one submitted voting ballot, most
+/// deviant first
+pub fn judge_stats(scores: &[GivenScore], threshold: Decimal) -> Vec<JudgeStats> {
+    // Every score each speech received, by judge
+    let mut by_speech: HashMap<Uuid, Vec<(Uuid, Decimal)>> = HashMap::new();
+    for given in scores {
+        by_speech
+            .entry(given.allocation_id)
+            .or_default()
+            .push((given.adjudicator_id, given.score));
+    }
+
+    let mut judges: Vec<JudgeStats> = Vec::new();
+    let mut judge_index: HashMap<Uuid, usize> = HashMap::new();
+    let mut judge_scores: Vec<Vec<Decimal>> = Vec::new();
+    let mut per_match: HashMap<(usize, Uuid), MatchScores> = HashMap::new();
+    let mut match_order: Vec<(usize, Uuid)> = Vec::new();
+
+    for given in scores {
+        let judge = *judge_index.entry(given.adjudicator_id).or_insert_with(|| {
+            judges.push(JudgeStats {
+                adjudicator_id: given.adjudicator_id,
+                username: given.username.clone(),
+                ballots: 0,
+                scores_given: 0,
+                average_score: Decimal::ZERO,
+                standard_deviation: Decimal::ZERO,
+                mean_deviation: None,
+                mean_abs_deviation: None,
+                low_point_wins: 0,
+                flagged_matches: 0,
+                is_outlier: false,
+                matches: Vec::new(),
+            });
+            judge_scores.push(Vec::new());
+            judges.len() - 1
+        });
+        judge_scores[judge].push(given.score);
+
+        let others: Vec<Decimal> = by_speech[&given.allocation_id]
+            .iter()
+            .filter(|(judge_id, _)| *judge_id != given.adjudicator_id)
+            .map(|(_, score)| *score)
+            .collect();
+
+        let key = (judge, given.match_id);
+        let entry = per_match.entry(key).or_insert_with(|| {
+            match_order.push(key);
+            MatchScores::default()
+        });
+        entry.scores.push(given.score);
+        if !others.is_empty() {
+            entry.deviations.push(given.score - mean(&others));
+        }
+        entry.low_point_win |= given.low_point_win;
+    }
+
+    for &(judge, match_id) in &match_order {
+        let gathered = &per_match[&(judge, match_id)];
+        let sample = scores
+            .iter()
+            .find(|s| s.match_id == match_id)
+            .expect("every match came from a score");
+        let deviation =
+            (!gathered.deviations.is_empty()).then(|| mean(&gathered.deviations).round_dp(2));
+        let flagged = deviation.is_some_and(|d| d.abs() >= threshold);
+
+        let stats = &mut judges[judge];
+        stats.ballots += 1;
+        stats.low_point_wins += usize::from(gathered.low_point_win);
+        stats.flagged_matches += usize::from(flagged);
+        stats.matches.push(MatchDeviation {
+            match_id,
+            series_name: sample.series_name.clone(),
+            room_name: sample.room_name.clone(),
+            speeches: gathered.scores.len(),
+            average_score: mean(&gathered.scores).round_dp(2),
+            deviation,
+            flagged,
+        });
+    }
+
+    for (stats, given) in judges.iter_mut().zip(&judge_scores) {
+        stats.scores_given = given.len();
+        stats.average_score = mean(given).round_dp(2);
+        stats.standard_deviation = standard_deviation(given);
+
+        let deviations: Vec<Decimal> = stats.matches.iter().filter_map(|m| m.deviation).collect();
+        if !deviations.is_empty() {
+            let mean_deviation = mean(&deviations).round_dp(2);
+            stats.mean_deviation = Some(mean_deviation);
+            let absolute: Vec<Decimal> = deviations.iter().map(|d| d.abs()).collect();
+            stats.mean_abs_deviation = Some(mean(&absolute).round_dp(2));
+            stats.is_outlier = mean_deviation.abs() >= threshold;
+        }
+    }
+
+    judges.sort_by(|a, b| {
+        let spread = |j: &JudgeStats| j.mean_deviation.map(|d| d.abs()).unwrap_or_default();
+        spread(b)
+            .cmp(&spread(a))
+            .then_with(|| a.username.cmp(&b.username))
+    });
+    judges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn given(judge: u128, room: u128, speech: u128, score: i64) -> GivenScore {
+        GivenScore {
+            adjudicator_id: Uuid::from_u128(judge),
+            username: format!("judge{}", judge),
+            match_id: Uuid::from_u128(100 + room),
+            series_name: "Round 1".to_string(),
+            room_name: Some(format!("Room {}", room)),
+            allocation_id: Uuid::from_u128(1000 + speech),
+            score: Decimal::from(score),
+            low_point_win: false,
+        }
+    }
+
+    #[test]
+    fn test_deviation_against_the_rest_of_the_panel() {
+        // Three judges on two speeches; judge 3 is four points harsh
+        let scores = vec![
+            given(1, 1, 1, 76),
+            given(2, 1, 1, 76),
+            given(3, 1, 1, 72),
+            given(1, 1, 2, 74),
+            given(2, 1, 2, 74),
+            given(3, 1, 2, 70),
+        ];
+        let stats = judge_stats(&scores, DEFAULT_THRESHOLD);
+
+        let harsh = &stats[0];
+        assert_eq!(harsh.username, "judge3");
+        assert_eq!(harsh.mean_deviation, Some(Decimal::from(-4)));
+        assert!(harsh.is_outlier);
+        assert_eq!((harsh.ballots, harsh.flagged_matches), (1, 1));
+        assert_eq!(harsh.average_score, Decimal::from(71));
+        assert_eq!(harsh.standard_deviation, Decimal::ONE);
+
+        // 76 against the mean of 76 and 72
+        let fair = stats.iter().find(|j| j.username == "judge1").unwrap();
+        assert_eq!(fair.mean_deviation, Some(Decimal::from(2)));
+        assert_eq!(fair.mean_abs_deviation, Some(Decimal::from(2)));
+        assert!(fair.is_outlier);
+        assert_eq!(
+            judge_stats(&scores, Decimal::from(3))
+                .iter()
+                .filter(|j| j.is_outlier)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_solo_judges_have_no_consensus() {
+        let scores = vec![given(1, 1, 1, 75), given(1, 2, 2, 77)];
+        let stats = judge_stats(&scores, DEFAULT_THRESHOLD);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].ballots, 2);
+        assert_eq!(stats[0].mean_deviation, None);
+        assert!(!stats[0].is_outlier);
+        assert!(stats[0].matches.iter().all(|m| m.deviation.is_none()));
+    }
+
+    #[test]
+    fn test_low_point_wins_counted_per_ballot() {
+        let mut scores = vec![given(1, 1, 1, 75), given(1, 1, 2, 77)];
+        for score in &mut scores {
+            score.low_point_win = true;
+        }
+        assert_eq!(judge_stats(&scores, DEFAULT_THRESHOLD)[0].low_point_wins, 1);
+    }
+}
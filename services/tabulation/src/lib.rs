@@ -1,54 +1,213 @@
+pub mod archive;
+pub mod attachments;
 pub mod auth_middleware;
+pub mod ballot_feed;
 pub mod config;
 pub mod database;
+pub mod double_entry;
+pub mod eligibility;
+pub mod etags;
+pub mod graphql;
 pub mod handlers;
+pub mod judge_stats;
+pub mod margins;
 pub mod models;
+pub mod motion_stats;
+pub mod notifications;
+pub mod outbox;
+pub mod printables;
+pub mod results_cache;
+pub mod rotation;
+pub mod rounding;
+pub mod score_timeline;
+pub mod seed;
+pub mod simulate;
+pub mod suggestions;
+pub mod tab;
+pub mod tabbycat;
+pub mod teams;
+pub mod undo;
 
+pub use common::startup::StartupError;
 pub use config::Config;
 pub use database::Database;
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
+use ballot_feed::BallotFeed;
+use common::{
+    fields::sparse_fieldsets, versioning::CURRENT_VERSION, EventBus, Features, KvStore,
+    Maintenance, Notifier, PendingActions, Storage,
+};
+use results_cache::ResultsCache;
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
 
 pub struct AppState {
     pub db: Database,
     pub config: Config,
+    pub notifier: Notifier,
+    pub events: EventBus,
+    pub storage: Storage,
+    pub ballot_feed: BallotFeed,
+    pub results_cache: ResultsCache,
+    /// Cached suspension checks, shared between replicas when Redis is set up
+    pub kv: KvStore,
+    /// Feature flags, with admins' overrides from the database
+    pub features: Features,
+    /// Whether non-admins are being turned away
+    pub maintenance: Maintenance,
+    /// Destructive admin actions waiting for a second admin
+    pub pending_actions: PendingActions,
 }
 
-pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
-    let config = Config::from_env()?;
-    let db = Database::new(&config.database_url).await?;
+/// Connect to the database, run migrations and build the shared state.
+/// Tests can skip this and construct `AppState` around their own database.
+pub async fn build_state(config: Config) -> Result<Arc<AppState>, StartupError> {
+    let db = Database::new(&config.database_url)
+        .await?
+        .with_rounding(config.rounding);
+    let db = match &config.database_replica_url {
+        Some(url) => db.with_replica(common::ReadReplica::connect(url, 10)?),
+        None => db,
+    };
     db.migrate().await?;
 
-    let state = Arc::new(AppState {
+    let notifier = Notifier::new(&config.notifications);
+    let storage = Storage::new(&config.storage);
+    let events = EventBus::connect(&config.event_bus).await;
+    let kv = KvStore::new(&config.kv);
+    let results_cache = ResultsCache::new(kv.clone(), config.results_cache);
+    let features = Features::new(db.pool().clone(), &config.features);
+    let maintenance = Maintenance::new(db.pool().clone(), outbox::SERVICE);
+    let pending_actions = PendingActions::new(db.pool().clone(), outbox::SERVICE);
+
+    Ok(Arc::new(AppState {
         db,
-        config: config.clone(),
-    });
+        config,
+        notifier,
+        events,
+        storage,
+        ballot_feed: BallotFeed::new(),
+        results_cache,
+        kv,
+        features,
+        maintenance,
+        pending_actions,
+    }))
+}
 
-    let cors = configure_cors(&config);
+/// Tabbycat interop, for admins. Imports carry whole tournaments, so they
+/// get the bulk body limit.
+fn tabbycat_routes(state: &AppState) -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/admin/events/:event_id/tabbycat",
+            get(handlers::export_tabbycat)
+                .post(handlers::import_tabbycat)
+                .layer(DefaultBodyLimit::max(state.config.limits.bulk_body_bytes)),
+        )
+        .route(
+            "/admin/events/:event_id/tabbycat/speakers.csv",
+            get(handlers::export_tabbycat_speakers),
+        )
+        .route(
+            "/admin/events/:event_id/tabbycat/judges.csv",
+            get(handlers::export_tabbycat_judges),
+        )
+}
+
+/// Assemble the service's routes around already-built state
+pub fn create_app(state: Arc<AppState>) -> Router {
+    let cors = common::configure_cors(&state.config.cors, &[]);
 
     // Public routes (optional authentication - show public data with optional user context)
     let public_routes = Router::new()
-        // Match viewing (respects release toggles)
-        .route("/matches/:match_id", get(handlers::get_match))
+        // Match viewing (respects release toggles). Heavy responses can be
+        // trimmed with ?fields=, and polling clients get 304s while nothing
+        // changes
+        .route(
+            "/matches/:match_id",
+            get(handlers::get_match)
+                .layer(middleware::from_fn(sparse_fieldsets))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    etags::match_etag,
+                )),
+        )
+        // Attachments (match slides respect release)
+        .route(
+            "/events/:event_id/attachments",
+            get(handlers::list_event_attachments),
+        )
+        .route(
+            "/matches/:match_id/attachments",
+            get(handlers::list_match_attachments),
+        )
+        .route("/attachments/:attachment_id", get(handlers::get_attachment))
+        // Tabs and breaks (respect per-category release toggles), read
+        // from the replica when there is one
+        .route(
+            "/events/:event_id/tab/teams",
+            get(handlers::get_team_tab)
+                .layer(middleware::from_fn(sparse_fieldsets))
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route(
+            "/events/:event_id/tab/speakers",
+            get(handlers::get_speaker_tab)
+                .layer(middleware::from_fn(sparse_fieldsets))
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route(
+            "/events/:event_id/break",
+            get(handlers::get_break)
+                .layer(middleware::from_fn(sparse_fieldsets))
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        // Events, series, matches and ballots in one query
+        .route(
+            "/graphql",
+            post(handlers::graphql).layer(Extension(graphql::schema())),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::optional_auth_middleware,
+            auth_middleware::optional_auth_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
-    // Authenticated routes (require login)
-    let authenticated_routes = Router::new()
+    // Read-only routes (require login or an API key for integrations)
+    let readable_routes = Router::new()
         // Series viewing
         .route("/series", get(handlers::list_series))
-        .route("/series/:series_id", get(handlers::get_series))
+        .route(
+            "/series/:series_id",
+            get(handlers::get_series)
+                .layer(middleware::from_fn(sparse_fieldsets))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    etags::series_etag,
+                )),
+        )
         // Match listing
-        .route("/matches", get(handlers::list_matches))
+        .route(
+            "/matches",
+            get(handlers::list_matches).layer(middleware::from_fn(sparse_fieldsets)),
+        )
+        // Institutions and venues
+        .route("/institutions", get(handlers::list_institutions))
+        .route("/venues", get(handlers::list_venues))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::read_access_middleware::<AppState>,
+        ))
+        .with_state(state.clone());
+
+    // Authenticated routes (require login)
+    let authenticated_routes = Router::new()
         // Adjudicator ballot access
         .route("/matches/:match_id/my-ballot", get(handlers::get_my_ballot))
         .route(
@@ -59,14 +218,57 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/matches/:match_id/submit-feedback",
             post(handlers::submit_feedback),
         )
+        .route("/me/speaker-notes", get(handlers::my_speaker_notes))
         // User performance
         .route(
             "/users/:user_id/performance",
             get(handlers::get_user_performance),
         )
+        .route(
+            "/users/:user_id/score-timeline",
+            get(handlers::get_score_timeline),
+        )
+        .route(
+            "/users/:user_id/judging-record",
+            get(handlers::get_judging_record),
+        )
+        // Personal calendar feed
+        .route("/calendar/me.ics", get(handlers::my_calendar))
+        .route("/me/schedule", get(handlers::my_schedule))
+        // Speaker eligibility
+        .route(
+            "/users/:user_id/eligibility",
+            get(handlers::get_speaker_eligibility),
+        )
+        // Team registration
+        .route("/registered-teams", post(handlers::create_registered_team))
+        .route(
+            "/registered-teams/mine",
+            get(handlers::list_my_registered_teams),
+        )
+        .route(
+            "/registered-teams/:team_id",
+            get(handlers::get_registered_team).put(handlers::update_registered_team),
+        )
+        .route(
+            "/registered-teams/:team_id/members",
+            post(handlers::add_registered_team_member),
+        )
+        .route(
+            "/registered-teams/:team_id/members/:user_id",
+            delete(handlers::remove_registered_team_member),
+        )
+        .route(
+            "/events/:event_id/registrations",
+            get(handlers::list_event_registrations).post(handlers::register_team),
+        )
+        .route(
+            "/events/:event_id/registrations/:team_id",
+            delete(handlers::withdraw_team),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::auth_middleware,
+            auth_middleware::auth_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
@@ -76,6 +278,34 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
         .route("/admin/series", post(handlers::create_series))
         .route("/admin/series/:series_id", put(handlers::update_series))
         .route("/admin/series/:series_id", delete(handlers::delete_series))
+        .route(
+            "/admin/pending-actions",
+            get(handlers::list_pending_actions),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/confirm",
+            post(handlers::confirm_pending_action),
+        )
+        .route(
+            "/admin/pending-actions/:action_id/reject",
+            post(handlers::reject_pending_action),
+        )
+        .route(
+            "/admin/events/:event_id/clone-structure",
+            post(handlers::clone_event_structure),
+        )
+        .route(
+            "/admin/series/:series_id/silent",
+            put(handlers::set_series_silent),
+        )
+        .route(
+            "/admin/series/:series_id/training",
+            put(handlers::set_series_training),
+        )
+        .route(
+            "/admin/series/:series_id/double-entry",
+            put(handlers::set_series_double_entry),
+        )
         // Match management
         .route("/admin/matches", post(handlers::create_match))
         .route("/admin/matches/:match_id", put(handlers::update_match))
@@ -88,13 +318,144 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
             "/admin/matches/:match_id/ballots",
             get(handlers::admin_get_match_ballots),
         )
+        .route(
+            "/admin/matches/:match_id/ballot-entries",
+            get(handlers::list_ballot_entries).post(handlers::enter_ballot),
+        )
+        .route(
+            "/admin/matches/:match_id/ballot-pdf",
+            get(handlers::match_ballot_pdf),
+        )
+        .route(
+            "/admin/series/:series_id/room-slips",
+            get(handlers::series_room_slips),
+        )
+        .route(
+            "/admin/events/:event_id/outstanding-ballots",
+            get(handlers::get_outstanding_ballots),
+        )
+        .route(
+            "/admin/events/:event_id/ballot-stream",
+            get(handlers::ballot_stream),
+        )
+        .route(
+            "/admin/stats",
+            get(handlers::get_ballot_stats)
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
         .route(
             "/admin/matches/:match_id/history",
             get(handlers::get_allocation_history),
         )
+        .route(
+            "/admin/matches/:match_id/adjudicator-suggestions",
+            get(handlers::get_adjudicator_suggestions),
+        )
         // Team management
         .route("/admin/teams/:team_id", put(handlers::update_team))
-        // Allocation management
+        .route(
+            "/admin/teams/:team_id/assign",
+            post(handlers::assign_registered_team),
+        )
+        .route(
+            "/admin/events/:event_id/team-entry",
+            put(handlers::set_team_entry),
+        )
+        .route(
+            "/admin/events/:event_id/speaks-withheld",
+            put(handlers::set_speaks_withheld),
+        )
+        // Venues
+        .route("/admin/venues", post(handlers::create_venue))
+        .route(
+            "/admin/venues/:venue_id",
+            put(handlers::update_venue).delete(handlers::delete_venue),
+        )
+        // Institutions and speaker eligibility
+        .route("/admin/institutions", post(handlers::create_institution))
+        .route(
+            "/admin/institutions/:institution_id",
+            put(handlers::update_institution).delete(handlers::delete_institution),
+        )
+        .route(
+            "/admin/users/:user_id/eligibility",
+            put(handlers::update_speaker_eligibility),
+        )
+        .route("/admin/eligibility", get(handlers::list_eligible_speakers))
+        // Category tabs
+        .route(
+            "/admin/events/:event_id/tab",
+            get(handlers::list_tab_categories),
+        )
+        .route(
+            "/admin/events/:event_id/tab/:category",
+            put(handlers::update_tab_category),
+        )
+        .route(
+            "/admin/events/:event_id/tie-breaks",
+            get(handlers::get_tie_breaks).put(handlers::update_tie_breaks),
+        )
+        .route(
+            "/admin/events/:event_id/standings/simulate",
+            get(handlers::simulate_standings),
+        )
+        // Judge calibration
+        .route(
+            "/admin/events/:event_id/judge-stats",
+            get(handlers::get_judge_stats)
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route(
+            "/admin/events/:event_id/motion-stats",
+            get(handlers::get_motion_stats)
+                .layer(middleware::from_fn(common::replica::replica_reads)),
+        )
+        .route(
+            "/admin/events/:event_id/position-rotation",
+            get(handlers::get_position_rotation),
+        )
+        .route(
+            "/admin/users/:user_id/accreditation",
+            post(handlers::set_judge_accreditation),
+        )
+        .merge(tabbycat_routes(&state))
+        // Attachments
+        .route(
+            "/admin/events/:event_id/attachments",
+            post(handlers::create_event_attachment),
+        )
+        .route(
+            "/admin/matches/:match_id/attachments",
+            post(handlers::create_match_attachment),
+        )
+        .route(
+            "/admin/attachments/:attachment_id",
+            delete(handlers::delete_attachment),
+        )
+        .route(
+            "/admin/attachments/:attachment_id/content",
+            put(handlers::upload_attachment).layer(DefaultBodyLimit::max(
+                state.config.attachment_max_bytes as usize,
+            )),
+        )
+        .route(
+            "/admin/attachments/:attachment_id/complete",
+            post(handlers::complete_attachment),
+        )
+        // Archival
+        .route(
+            "/admin/events/:event_id/archive",
+            get(handlers::download_archive).post(handlers::archive_event),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::admin_middleware::<AppState>,
+        ))
+        .with_state(state.clone());
+
+    // Event admin routes - allocations, for admins and the members an
+    // admin has made managers of the event
+    let event_admin_routes = Router::new()
         .route(
             "/admin/series/:series_id/pool",
             get(handlers::get_allocation_pool),
@@ -102,83 +463,159 @@ pub async fn create_app() -> Result<Router, Box<dyn std::error::Error>> {
         .route("/admin/allocations", post(handlers::create_allocation))
         .route(
             "/admin/allocations/:allocation_id",
-            put(handlers::update_allocation),
+            put(handlers::update_allocation).delete(handlers::delete_allocation),
         )
+        .route("/admin/allocations/swap", post(handlers::swap_allocations))
         .route(
-            "/admin/allocations/:allocation_id",
-            delete(handlers::delete_allocation),
+            "/admin/allocation-changes/:change_id/undo",
+            post(handlers::undo_allocation_change),
         )
-        .route("/admin/allocations/swap", post(handlers::swap_allocations))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_middleware::admin_middleware,
+            auth_middleware::event_admin_middleware::<AppState>,
         ))
         .with_state(state.clone());
 
-    let app = Router::new()
+    // Trainer routes - training ballots, for trainers and admins
+    let trainer_routes = Router::new()
+        .route("/training/series", get(handlers::list_training_series))
+        .route(
+            "/training/series/:series_id/ballots",
+            get(handlers::get_training_ballots),
+        )
+        .route(
+            "/training/members/:user_id/speaker-notes",
+            get(handlers::get_member_speaker_notes),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware::trainer_middleware,
+        ))
+        .with_state(state.clone());
+
+    let api = Router::new()
         .merge(public_routes)
+        .merge(readable_routes)
         .merge(authenticated_routes)
         .merge(admin_routes)
-        .route("/health", get(|| async { "OK" }))
-        .layer(cors);
+        .merge(event_admin_routes)
+        .merge(trainer_routes);
 
-    Ok(app)
+    // New clients use /v1; the unversioned paths stay for app releases
+    // that predate it, announcing their deprecation
+    common::Versions::new()
+        .mount(CURRENT_VERSION, api.clone())
+        .unversioned(api, state.config.unversioned.clone())
+        .into_router()
+        .route("/health", get(|| async { "OK" }))
+        .layer(middleware::from_fn_with_state(
+            (state.clone(), state.maintenance.clone()),
+            common::maintenance::maintenance_mode::<AppState>,
+        ))
+        .layer(DefaultBodyLimit::max(state.config.limits.body_bytes))
+        .layer(middleware::from_fn_with_state(
+            state.config.limits.clone(),
+            common::limits::enforce_limits,
+        ))
+        .layer(state.config.compression.layer())
+        .layer(cors)
 }
 
-fn configure_cors(config: &Config) -> CorsLayer {
-    if config.cors_strict_mode {
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
-    } else if config.allowed_origins.contains(&"*".to_string()) {
-        CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers(Any)
-            .allow_credentials(false)
-    } else {
-        // Specific origins mode (non-strict) - allow listed origins only
-        let mut cors_layer = CorsLayer::new();
-        for origin in &config.allowed_origins {
-            if origin != "*" {
-                if let Ok(header_value) = origin.parse::<http::header::HeaderValue>() {
-                    cors_layer = cors_layer.allow_origin(header_value);
-                }
-            }
-        }
-        cors_layer
-            .allow_methods([
-                http::Method::GET,
-                http::Method::POST,
-                http::Method::PUT,
-                http::Method::PATCH,
-                http::Method::DELETE,
-                http::Method::OPTIONS,
-            ])
-            .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION])
-            .allow_credentials(true)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use chrono::NaiveDate;
+    use common::{storage::StorageSettings, versioning::Deprecation};
+    use sqlx::postgres::PgPoolOptions;
+    use std::time::Duration;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    async fn state() -> Arc<AppState> {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://tabulation.invalid/tabrela")
+            .unwrap();
+        let config = Config {
+            database_url: String::new(),
+            database_replica_url: None,
+            host: "127.0.0.1".to_string(),
+            port: 0,
+            jwt_secret: "test-secret".to_string(),
+            auth_service_url: String::new(),
+            attendance_service_url: String::new(),
+            attachment_max_bytes: 0,
+            rounding: Default::default(),
+            results_cache: Duration::ZERO,
+            undo_window: Duration::ZERO,
+            cors: Default::default(),
+            compression: Default::default(),
+            limits: Default::default(),
+            log_redaction: Default::default(),
+            unversioned: Deprecation {
+                since: NaiveDate::default(),
+                sunset: None,
+                info_url: None,
+            },
+            notifications: Default::default(),
+            outbox: Default::default(),
+            event_bus: Default::default(),
+            kv: Default::default(),
+            features: Default::default(),
+            storage: StorageSettings {
+                backend: Default::default(),
+                dir: String::new(),
+                s3: None,
+            },
+        };
+
+        Arc::new(AppState {
+            db: Database::from_pool(pool.clone()),
+            notifier: Notifier::new(&config.notifications),
+            events: EventBus::connect(&config.event_bus).await,
+            storage: Storage::new(&config.storage),
+            ballot_feed: BallotFeed::new(),
+            results_cache: ResultsCache::new(KvStore::memory(), config.results_cache),
+            kv: KvStore::memory(),
+            features: Features::new(pool.clone(), &config.features),
+            maintenance: Maintenance::new(pool.clone(), outbox::SERVICE),
+            pending_actions: PendingActions::new(pool, outbox::SERVICE),
+            config,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_tabbycat_import_takes_bulk_bodies() {
+        let state = state().await;
+        let limits = state.config.limits.clone();
+        let app = tabbycat_routes(&state)
+            .layer(Extension(Uuid::new_v4()))
+            .with_state(state)
+            .layer(DefaultBodyLimit::max(limits.body_bytes));
+        let import = |padding: usize| {
+            let body = format!(r#"{{"padding":"{}"}}"#, "x".repeat(padding));
+            let request = Request::builder()
+                .method("POST")
+                .uri(format!("/admin/events/{}/tabbycat", Uuid::new_v4()))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+
+        // Past the usual limit the body is read, and found not to be a
+        // tournament, before anything touches the database
+        assert_eq!(
+            import(limits.body_bytes + 1).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            import(limits.bulk_body_bytes).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
     }
 }
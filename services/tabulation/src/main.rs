@@ -1,22 +1,71 @@
-use tabulation::{create_app, Config};
+use common::redact::{RedactSettings, Redactor};
+use tabulation::{build_state, create_app, outbox, seed, undo, Config, StartupError};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == "--print-config-schema") {
+        print!("{}", Config::schema_doc());
+        return Ok(());
+    }
+
     // Initialize tracing
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "tabulation=debug,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        // Emails, phone numbers and codes are redacted unless LOG_REDACT_ALLOW
+        // says otherwise
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(Redactor::new(&RedactSettings::from_env()).writer(std::io::stdout)),
+        )
         .init();
 
     // Load configuration
-    let config = Config::from_env().expect("Failed to load configuration");
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => exit_on_startup_error(e.into()),
+    };
+
+    // `tabulation seed [--seed N] [--users N] [--reset]` fills the database with demo data
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("seed") {
+        let result = async {
+            let options = seed::SeedOptions::from_args(args)?;
+            let peppers = seed::password_pepper()?;
+            let state = build_state(config.clone()).await?;
+            Ok::<_, Box<dyn std::error::Error>>(seed::run(&state.db, &options, &peppers).await?)
+        }
+        .await;
+
+        match result {
+            Ok(summary) => {
+                println!("Seeded {}", summary);
+                println!(
+                    "Log in as {}admin (or any {}* user) with password '{}'",
+                    seed::USERNAME_PREFIX,
+                    seed::USERNAME_PREFIX,
+                    seed::DEFAULT_PASSWORD
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
 
     // Create application
-    let app = create_app().await?;
+    let state = match build_state(config.clone()).await {
+        Ok(state) => state,
+        Err(e) => exit_on_startup_error(e),
+    };
+    outbox::spawn(state.clone());
+    undo::spawn(state.clone());
+    let app = create_app(state);
 
     // Start server
     let addr = format!("{}:{}", config.host, config.port);
@@ -28,3 +77,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Log why startup failed and exit with the code for that failure
+fn exit_on_startup_error(e: StartupError) -> ! {
+    tracing::error!("Startup failed: {}", e);
+    std::process::exit(e.exit_code());
+}
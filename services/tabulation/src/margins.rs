@@ -0,0 +1,156 @@
+//! Team totals, margins and low-point wins on a single ballot.
+//!
+//! A team's total is the sum of its speakers' scores on the ballot, reply
+//! speeches included. Its margin is that total less the average total of the
+//! other teams, which in a two-team room is the plain difference. A ballot
+//! has a low-point win when it ranks a team above one with more speaks.
+
+use crate::rounding::RoundingPolicy;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamMargin {
+    pub team_id: Uuid,
+    pub rank: i32,
+    pub total_speaks: Decimal,
+    pub margin: Decimal,
+}
+
+/// A higher-ranked team with fewer speaks than a lower-ranked one
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LowPointWin {
+    pub team_id: Uuid,
+    pub rank: i32,
+    pub total_speaks: Decimal,
+    pub over_team_id: Uuid,
+    pub over_rank: i32,
+    pub over_total_speaks: Decimal,
+}
+
+/// Totals and margins for every ranked team. `speaks` pairs each score with
+/// the team of the speaker; teams without scores total zero.
+pub fn team_margins(
+    rankings: &[(Uuid, i32)],
+    speaks: &[(Uuid, Decimal)],
+    rounding: &RoundingPolicy,
+) -> Vec<TeamMargin> {
+    let totals: Vec<Decimal> = rankings
+        .iter()
+        .map(|(team_id, _)| {
+            rounding.round(
+                speaks
+                    .iter()
+                    .filter(|(team, _)| team == team_id)
+                    .map(|(_, score)| *score)
+                    .sum::<Decimal>(),
+            )
+        })
+        .collect();
+    let sum: Decimal = totals.iter().sum();
+    let others = Decimal::from(rankings.len().saturating_sub(1).max(1));
+
+    rankings
+        .iter()
+        .zip(&totals)
+        .map(|(&(team_id, rank), &total)| TeamMargin {
+            team_id,
+            rank,
+            total_speaks: total,
+            margin: if rankings.len() > 1 {
+                rounding.round(total - (sum - total) / others)
+            } else {
+                Decimal::ZERO
+            },
+        })
+        .collect()
+}
+
+/// Every pair ranked against its speaks, best-ranked team first
+pub fn low_point_wins(margins: &[TeamMargin]) -> Vec<LowPointWin> {
+    let mut ranked: Vec<&TeamMargin> = margins.iter().collect();
+    ranked.sort_by_key(|m| m.rank);
+
+    let mut found = Vec::new();
+    for (i, higher) in ranked.iter().enumerate() {
+        for lower in &ranked[i + 1..] {
+            if lower.rank > higher.rank && higher.total_speaks < lower.total_speaks {
+                found.push(LowPointWin {
+                    team_id: higher.team_id,
+                    rank: higher.rank,
+                    total_speaks: higher.total_speaks,
+                    over_team_id: lower.team_id,
+                    over_rank: lower.rank,
+                    over_total_speaks: lower.total_speaks,
+                });
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn speaks(entries: &[(u128, i64)]) -> Vec<(Uuid, Decimal)> {
+        entries
+            .iter()
+            .map(|&(t, score)| (team(t), Decimal::from(score)))
+            .collect()
+    }
+
+    #[test]
+    fn test_two_team_margin_is_the_difference() {
+        let margins = team_margins(
+            &[(team(1), 1), (team(2), 2)],
+            &speaks(&[(1, 76), (1, 75), (1, 74), (2, 75), (2, 74), (2, 73)]),
+            &RoundingPolicy::default(),
+        );
+        assert_eq!(margins[0].total_speaks, Decimal::from(225));
+        assert_eq!(margins[0].margin, Decimal::from(3));
+        assert_eq!(margins[1].margin, Decimal::from(-3));
+        assert!(low_point_wins(&margins).is_empty());
+    }
+
+    #[test]
+    fn test_four_team_margin_against_average_of_others() {
+        let margins = team_margins(
+            &[(team(1), 1), (team(2), 2), (team(3), 3), (team(4), 4)],
+            &speaks(&[(1, 160), (2, 152), (3, 150), (4, 148)]),
+            &RoundingPolicy::default(),
+        );
+        // 160 - (152 + 150 + 148) / 3
+        assert_eq!(margins[0].margin, Decimal::from(10));
+        assert_eq!(margins[1].margin, Decimal::new(-67, 2));
+        assert_eq!(margins[3].margin, Decimal::from(-6));
+    }
+
+    #[test]
+    fn test_low_point_wins_list_every_inverted_pair() {
+        let margins = team_margins(
+            &[(team(1), 1), (team(2), 2), (team(3), 3), (team(4), 4)],
+            &speaks(&[(1, 150), (2, 155), (3, 151), (4, 140)]),
+            &RoundingPolicy::default(),
+        );
+        let found = low_point_wins(&margins);
+        let pairs: Vec<(i32, i32)> = found.iter().map(|l| (l.rank, l.over_rank)).collect();
+        assert_eq!(pairs, [(1, 2), (1, 3)]);
+        assert_eq!(found[0].over_total_speaks, Decimal::from(155));
+    }
+
+    #[test]
+    fn test_equal_speaks_are_not_a_low_point_win() {
+        let margins = team_margins(
+            &[(team(1), 1), (team(2), 2)],
+            &speaks(&[(1, 150), (2, 150)]),
+            &RoundingPolicy::default(),
+        );
+        assert!(low_point_wins(&margins).is_empty());
+    }
+}
@@ -4,11 +4,16 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::eligibility::EligibilityFilter;
+use crate::tab::TieBreak;
+
 // ============================================================================
 // Enums - Match PostgreSQL types
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "team_format", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum TeamFormat {
@@ -25,7 +30,9 @@ impl std::fmt::Display for TeamFormat {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "two_team_position", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum TwoTeamPosition {
@@ -33,7 +40,9 @@ pub enum TwoTeamPosition {
     Opposition,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "four_team_position", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum FourTeamPosition {
@@ -43,7 +52,9 @@ pub enum FourTeamPosition {
     ClosingOpposition,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "two_team_speaker_role", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum TwoTeamSpeakerRole {
@@ -57,7 +68,9 @@ pub enum TwoTeamSpeakerRole {
     OppositionReply,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "four_team_speaker_role", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum FourTeamSpeakerRole {
@@ -71,7 +84,9 @@ pub enum FourTeamSpeakerRole {
     OppositionWhip,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "allocation_role", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum AllocationRole {
@@ -81,7 +96,22 @@ pub enum AllocationRole {
     NonVotingAdjudicator,
 }
 
+/// How far the adjudication core trusts a judge
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "accreditation_level", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum AccreditationLevel {
+    /// Judges as a non-voting trainee
+    Trainee,
+    /// May sit on a panel
+    Accredited,
+    /// May chair a room
+    ChairQualified,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, async_graphql::Enum,
+)]
 #[sqlx(type_name = "match_status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum MatchStatus {
@@ -106,16 +136,31 @@ pub struct MatchSeries {
     pub team_format: TeamFormat,
     pub allow_reply_speeches: bool,
     pub is_break_round: bool,
+    /// Results are hidden from non-admins until an admin lifts the silence
+    pub is_silent: bool,
+    /// Practice round: ballots are kept out of stats, standings and tabs
+    pub is_training: bool,
+    /// Break round results are entered by two admins from the paper ballot
+    pub double_entry: bool,
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl MatchSeries {
+    /// Results are only recorded from two matching admin entries. A series
+    /// that stops being a break round stops needing them.
+    pub fn requires_double_entry(&self) -> bool {
+        self.double_entry && self.is_break_round
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Match {
     pub id: Uuid,
     pub series_id: Uuid,
     pub room_name: Option<String>,
+    pub venue_id: Option<Uuid>, // room_name follows the venue's name
     pub motion: Option<String>,
     pub info_slide: Option<String>,
     pub status: MatchStatus,
@@ -126,6 +171,38 @@ pub struct Match {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Match {
+    /// Draws, motions and info slides become public once the match leaves
+    /// draft
+    pub fn is_released(&self) -> bool {
+        self.status != MatchStatus::Draft
+    }
+}
+
+/// Which of a match's results a viewer may see
+#[derive(Debug, Clone, Copy)]
+pub struct ResultVisibility {
+    pub rankings: bool,
+    pub speaks: bool,
+}
+
+impl ResultVisibility {
+    /// Admins see everything. Others see what has been released, except
+    /// that silent rounds hide everything until revealed and released
+    /// rankings still show while the event withholds speaks.
+    pub fn new(
+        match_record: &Match,
+        is_silent: bool,
+        speaks_withheld: bool,
+        is_admin: bool,
+    ) -> Self {
+        Self {
+            rankings: is_admin || (match_record.rankings_released && !is_silent),
+            speaks: is_admin || (match_record.scores_released && !speaks_withheld && !is_silent),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct MatchTeam {
     pub id: Uuid,
@@ -136,6 +213,8 @@ pub struct MatchTeam {
     pub institution: Option<String>,
     pub final_rank: Option<i32>,
     pub total_speaker_points: Option<Decimal>,
+    pub registered_team_id: Option<Uuid>, // Set in team-entry events
+    pub institution_id: Option<Uuid>,     // Set from the speakers' institution
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -173,6 +252,9 @@ pub struct AllocationWithUser {
     pub allocated_at: DateTime<Utc>,
     pub allocated_by: Uuid,
     pub was_checked_in: bool, // Track if user was checked in when allocated
+    /// The member has been suspended since, so someone should take their place
+    #[serde(default)]
+    pub needs_replacement: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -184,6 +266,7 @@ pub struct Ballot {
     pub is_submitted: bool,
     pub submitted_at: Option<DateTime<Utc>>,
     pub notes: Option<String>,
+    pub low_point_win: bool, // Confirmed by the adjudicator on submission
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -199,6 +282,19 @@ pub struct SpeakerScore {
     pub updated_at: DateTime<Utc>,
 }
 
+/// What an adjudicator told one speaker on their ballot, beyond the score
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SpeakerNote {
+    pub ballot_id: Uuid,
+    pub allocation_id: Uuid,
+    pub strengths: Option<String>,
+    pub improvements: Option<String>,
+    /// Kept from the speaker: only admins and trainers read it
+    pub adjcore_only: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TeamRanking {
     pub id: Uuid,
@@ -206,10 +302,40 @@ pub struct TeamRanking {
     pub team_id: Uuid,
     pub rank: i32,
     pub is_winner: Option<bool>,
+    pub total_speaks: Option<Decimal>, // Team's speaks on this ballot
+    pub margin: Option<Decimal>,       // Over the average of the other teams
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One admin's typed copy of a paper ballot in a double-entry round
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BallotEntry {
+    pub id: Uuid,
+    pub match_id: Uuid,
+    pub entered_by: Uuid,
+    pub notes: Option<String>,
+    pub confirm_low_point_win: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BallotEntryScore {
+    pub entry_id: Uuid,
+    pub allocation_id: Uuid,
+    pub score: Decimal,
+    pub feedback: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BallotEntryRanking {
+    pub entry_id: Uuid,
+    pub team_id: Uuid,
+    pub rank: i32,
+    pub is_winner: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct AllocationHistory {
     pub id: Uuid,
@@ -227,24 +353,130 @@ pub struct AllocationHistory {
     pub notes: Option<String>,
 }
 
-// ============================================================================
-// JWT Claims
-// ============================================================================
+/// A file attached to an event (info pack) or match (info slide)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub match_id: Option<Uuid>,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: Option<i64>,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub uploaded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Institution {
+    pub id: Uuid,
+    pub name: String,
+    pub short_code: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Venue {
+    pub id: Uuid,
+    pub name: String,
+    pub building: Option<String>,
+    pub capacity: Option<i32>,
+    pub is_accessible: bool,
+    pub accessibility_notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String,
+/// A user's institution and the categories they may compete in
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SpeakerEligibility {
+    pub user_id: Uuid,
     pub username: String,
-    pub exp: i64,
-    pub iat: i64,
-    pub jti: String,
-    pub token_type: String,
+    pub institution_id: Option<Uuid>,
+    pub institution_name: Option<String>,
+    pub is_novice: bool,
+    pub is_esl: bool,
+}
+
+/// An event's break size and tab release toggles for one speaker category
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TabCategory {
+    pub event_id: Uuid,
+    pub category: String,
+    pub break_size: i32,
+    pub team_tab_released: bool,
+    pub speaker_tab_released: bool,
+    pub break_released: bool,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The rules an event's team standings are ordered by
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TieBreakSettings {
+    pub event_id: Uuid,
+    pub tie_breaks: Vec<String>,
+    pub tie_break_seed: Option<i64>,
+}
+
+impl TieBreakSettings {
+    pub fn rules(&self) -> Vec<TieBreak> {
+        self.tie_breaks
+            .iter()
+            .filter_map(|rule| rule.parse().ok())
+            .collect()
+    }
+}
+
+/// A persistent team that members form to enter team-entry events
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RegisteredTeam {
+    pub id: Uuid,
+    pub name: String,
+    pub institution: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RegisteredTeamMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub speaker_order: i32,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// A registered team's entry into an event
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TeamRegistration {
+    pub event_id: Uuid,
+    pub team_id: Uuid,
+    pub registered_by: Uuid,
+    pub registered_at: DateTime<Utc>,
 }
 
 // ============================================================================
 // Request Types
 // ============================================================================
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAttachmentRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Filename must be between 1 and 255 characters"
+    ))]
+    pub filename: String,
+    pub content_type: String,
+    /// Expected size, checked against the limit before handing out an upload URL
+    #[validate(range(min = 1, message = "File cannot be empty"))]
+    pub size_bytes: Option<i64>,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateSeriesRequest {
     pub event_id: Uuid,
@@ -261,6 +493,13 @@ pub struct CreateSeriesRequest {
     pub allow_reply_speeches: bool,
     #[serde(default)]
     pub is_break_round: bool,
+    #[serde(default)]
+    pub is_silent: bool,
+    #[serde(default)]
+    pub is_training: bool,
+    /// Only allowed on break rounds
+    #[serde(default)]
+    pub double_entry: bool,
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -277,6 +516,8 @@ pub struct CreateMatchRequest {
     pub series_id: Uuid,
     #[validate(length(max = 255))]
     pub room_name: Option<String>,
+    /// Takes precedence over room_name
+    pub venue_id: Option<Uuid>,
     pub motion: Option<String>,
     pub info_slide: Option<String>,
     pub scheduled_time: Option<DateTime<Utc>>,
@@ -284,8 +525,10 @@ pub struct CreateMatchRequest {
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct UpdateMatchRequest {
+    /// A room typed in by hand; detaches the match from its venue
     #[validate(length(max = 255))]
     pub room_name: Option<String>,
+    pub venue_id: Option<Uuid>,
     pub motion: Option<String>,
     pub info_slide: Option<String>,
     pub status: Option<MatchStatus>,
@@ -317,17 +560,241 @@ pub struct UpdateTeamRequest {
     pub institution: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateInstitutionRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(min = 1, max = 20))]
+    pub short_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateInstitutionRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 20))]
+    pub short_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateVenueRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub building: Option<String>,
+    #[validate(range(min = 1))]
+    pub capacity: Option<i32>,
+    #[serde(default)]
+    pub is_accessible: bool,
+    #[validate(length(max = 1000))]
+    pub accessibility_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateVenueRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(max = 255))]
+    pub building: Option<String>,
+    #[validate(range(min = 1))]
+    pub capacity: Option<i32>,
+    pub is_accessible: Option<bool>,
+    #[validate(length(max = 1000))]
+    pub accessibility_notes: Option<String>,
+}
+
+/// Replaces a user's institution and eligibility
+#[derive(Debug, Deserialize)]
+pub struct UpdateEligibilityRequest {
+    pub institution_id: Option<Uuid>,
+    #[serde(default)]
+    pub is_novice: bool,
+    #[serde(default)]
+    pub is_esl: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EligibilityQuery {
+    pub category: Option<EligibilityFilter>,
+    pub institution_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTabCategoryRequest {
+    #[validate(range(min = 0, max = 256, message = "Break size must be between 0 and 256"))]
+    pub break_size: Option<i32>,
+    pub team_tab_released: Option<bool>,
+    pub speaker_tab_released: Option<bool>,
+    pub break_released: Option<bool>,
+}
+
+/// Replaces an event's tie-break rules, in order
+#[derive(Debug, Deserialize)]
+pub struct UpdateTieBreaksRequest {
+    pub tie_breaks: Vec<TieBreak>,
+    /// Pick a new seed for the draw rule
+    #[serde(default)]
+    pub reseed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TabQuery {
+    pub category: Option<EligibilityFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimulateStandingsQuery {
+    pub category: Option<EligibilityFilter>,
+    /// Rooms separated by `;`, each its team ids best first
+    pub results: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JudgeStatsQuery {
+    /// Points per speech away from the panel before a judge is flagged
+    pub threshold: Option<Decimal>,
+    pub series_id: Option<Uuid>,
+}
+
+/// One entry in a judge's accreditation ledger
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AccreditationChange {
+    pub id: Uuid,
+    pub level: AccreditationLevel,
+    pub note: Option<String>,
+    pub set_by: Option<Uuid>,
+    pub set_by_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SetAccreditationRequest {
+    pub level: AccreditationLevel,
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
+
+/// Rooms a member has judged, by the seat they held. Cancelled and draft
+/// matches are not counted.
+#[derive(Debug, Clone, Default, Serialize, sqlx::FromRow)]
+pub struct JudgingRounds {
+    pub rounds_chaired: i64,
+    pub rounds_panelled: i64,
+    pub rounds_as_trainee: i64,
+    pub ballots_submitted: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JudgingRecord {
+    pub user_id: Uuid,
+    pub username: String,
+    /// Latest level in the ledger, if the member has been graded
+    pub accreditation: Option<AccreditationLevel>,
+    #[serde(flatten)]
+    pub rounds: JudgingRounds,
+    /// Every accreditation change, newest first
+    pub history: Vec<AccreditationChange>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRegisteredTeamRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+    #[validate(length(max = 255))]
+    pub institution: Option<String>,
+    /// Other members, in speaking order after the creator
+    #[serde(default)]
+    pub partner_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRegisteredTeamRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub name: Option<String>,
+    #[validate(length(max = 255))]
+    pub institution: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddTeamMemberRequest {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterTeamRequest {
+    pub team_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TeamEntryRequest {
+    pub team_entry: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithholdSpeaksRequest {
+    pub speaks_withheld: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SilentRoundRequest {
+    pub is_silent: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrainingRoundRequest {
+    pub is_training: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DoubleEntryRequest {
+    pub double_entry: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrainingSeriesQuery {
+    pub event_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignTeamRequest {
+    /// Registered team to put on this side; None clears the side
+    pub registered_team_id: Option<Uuid>,
+    /// Draw the team even though it has had this position `MAX_REPEATS` times
+    #[serde(default)]
+    pub allow_position_repeat: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateAllocationRequest {
     pub match_id: Uuid,
     pub user_id: Option<Uuid>,      // Optional for guest allocations
     pub guest_name: Option<String>, // Required if user_id is None
+    /// A guest registered at the desk, allocated under their name
+    pub walk_in_id: Option<Uuid>,
     pub role: AllocationRole,
     pub team_id: Option<Uuid>,
     pub two_team_speaker_role: Option<TwoTeamSpeakerRole>,
     pub four_team_speaker_role: Option<FourTeamSpeakerRole>,
     #[serde(default)]
     pub is_chair: bool,
+    /// Allocate even though the user is busy in another room at the time
+    #[serde(default)]
+    pub allow_schedule_conflict: bool,
+    /// Allocate a speaker who has had the side's position `MAX_REPEATS` times
+    #[serde(default)]
+    pub allow_position_repeat: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -352,6 +819,12 @@ pub struct SubmitBallotRequest {
     pub notes: Option<String>,
     pub speaker_scores: Vec<SpeakerScoreInput>,
     pub team_rankings: Vec<TeamRankingInput>,
+    /// Accept a ballot that ranks a team above one with more speaks
+    #[serde(default)]
+    pub confirm_low_point_win: bool,
+    #[serde(default)]
+    #[validate(nested)]
+    pub speaker_notes: Vec<SpeakerNoteInput>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -361,6 +834,17 @@ pub struct SpeakerScoreInput {
     pub feedback: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct SpeakerNoteInput {
+    pub allocation_id: Uuid,
+    #[validate(length(max = 5000))]
+    pub strengths: Option<String>,
+    #[validate(length(max = 5000))]
+    pub improvements: Option<String>,
+    #[serde(default)]
+    pub adjcore_only: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TeamRankingInput {
     pub team_id: Uuid,
@@ -368,11 +852,31 @@ pub struct TeamRankingInput {
     pub is_winner: Option<bool>,
 }
 
+/// An admin's entry of a chair's paper ballot in a double-entry round
+#[derive(Debug, Deserialize, Validate)]
+pub struct BallotEntryRequest {
+    #[validate(length(max = 5000))]
+    pub notes: Option<String>,
+    pub speaker_scores: Vec<SpeakerScoreInput>,
+    pub team_rankings: Vec<TeamRankingInput>,
+    #[serde(default)]
+    pub confirm_low_point_win: bool,
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct SubmitFeedbackRequest {
     pub match_id: Uuid,
     #[validate(length(max = 5000))]
     pub notes: String,
+    #[serde(default)]
+    #[validate(nested)]
+    pub speaker_notes: Vec<SpeakerNoteInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneStructureQuery {
+    /// The event whose series are copied
+    pub from: Uuid,
 }
 
 #[derive(Debug, Deserialize)]
@@ -391,9 +895,19 @@ pub struct MatchListQuery {
     pub per_page: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ScoreTimelineQuery {
+    /// Only speeches at events in this season
+    pub season_id: Option<Uuid>,
+    /// Speeches in the rolling average
+    pub window: Option<usize>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PerformanceQuery {
     pub event_id: Option<Uuid>,
+    /// Only count events in this season
+    pub season_id: Option<Uuid>,
     pub page: Option<i32>,
     pub per_page: Option<i32>,
 }
@@ -402,6 +916,32 @@ pub struct PerformanceQuery {
 // Response Types
 // ============================================================================
 
+/// Where and how to upload an attachment's contents
+#[derive(Debug, Serialize)]
+pub struct AttachmentUpload {
+    pub method: &'static str,
+    /// Presigned object storage URL, or the service's own upload route
+    /// (relative to the tabulation API) when files are kept on disk
+    pub url: String,
+    /// Headers the upload must send exactly
+    pub headers: std::collections::BTreeMap<&'static str, String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegisteredTeamResponse {
+    #[serde(flatten)]
+    pub team: RegisteredTeam,
+    pub members: Vec<RegisteredTeamMember>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventRegistrationResponse {
+    pub team: RegisteredTeamResponse,
+    pub registered_by: Uuid,
+    pub registered_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SeriesResponse {
     pub id: Uuid,
@@ -412,6 +952,9 @@ pub struct SeriesResponse {
     pub team_format: TeamFormat,
     pub allow_reply_speeches: bool,
     pub is_break_round: bool,
+    pub is_silent: bool,
+    pub is_training: bool,
+    pub double_entry: bool,
     pub match_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -432,12 +975,17 @@ pub struct MatchResponse {
     pub series_id: Uuid,
     pub series_name: String,
     pub room_name: Option<String>,
+    pub venue_id: Option<Uuid>,
     pub motion: Option<String>,
     pub info_slide: Option<String>,
     pub status: MatchStatus,
     pub scheduled_time: Option<DateTime<Utc>>,
     pub scores_released: bool,
     pub rankings_released: bool,
+    /// Scores are released but the event is withholding speaks
+    pub speaks_withheld: bool,
+    /// The round is silent, so released results are still hidden
+    pub is_silent: bool,
     pub teams: Vec<MatchTeamResponse>,
     pub adjudicators: Vec<AdjudicatorResponse>,
     pub created_at: DateTime<Utc>,
@@ -462,6 +1010,8 @@ pub struct MatchTeamResponse {
     pub institution: Option<String>,
     pub final_rank: Option<i32>,
     pub total_speaker_points: Option<Decimal>,
+    pub registered_team_id: Option<Uuid>,
+    pub institution_id: Option<Uuid>,
     pub speakers: Vec<SpeakerResponse>,
     pub resources: Vec<ResourceResponse>,
 }
@@ -506,8 +1056,26 @@ pub struct BallotResponse {
     pub is_submitted: bool,
     pub submitted_at: Option<DateTime<Utc>>,
     pub notes: Option<String>,
+    pub low_point_win: bool,
     pub speaker_scores: Vec<SpeakerScoreResponse>,
     pub team_rankings: Vec<TeamRankingResponse>,
+    pub speaker_notes: Vec<SpeakerNoteResponse>,
+}
+
+/// One room of a training series with every ballot its adjudicators filed
+#[derive(Debug, Serialize)]
+pub struct TrainingMatchBallots {
+    pub match_id: Uuid,
+    pub room_name: Option<String>,
+    pub motion: Option<String>,
+    pub status: MatchStatus,
+    pub ballots: Vec<BallotResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrainingBallotsResponse {
+    pub series: MatchSeries,
+    pub matches: Vec<TrainingMatchBallots>,
 }
 
 #[derive(Debug, Serialize)]
@@ -519,6 +1087,30 @@ pub struct SpeakerScoreResponse {
     pub feedback: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SpeakerNoteResponse {
+    pub allocation_id: Uuid,
+    pub speaker_username: String,
+    pub strengths: Option<String>,
+    pub improvements: Option<String>,
+    pub adjcore_only: bool,
+}
+
+/// A note left for a member, with the round and adjudicator it came from
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReceivedSpeakerNote {
+    pub match_id: Uuid,
+    pub event_id: Uuid,
+    pub event_title: String,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub adjudicator_username: String,
+    pub strengths: Option<String>,
+    pub improvements: Option<String>,
+    pub adjcore_only: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TeamRankingResponse {
     pub id: Uuid,
@@ -526,6 +1118,8 @@ pub struct TeamRankingResponse {
     pub team_name: Option<String>,
     pub rank: i32,
     pub is_winner: Option<bool>,
+    pub total_speaks: Option<Decimal>,
+    pub margin: Option<Decimal>,
 }
 
 #[derive(Debug, Serialize)]
@@ -548,6 +1142,30 @@ pub struct RankingCount {
     pub count: i64,
 }
 
+/// A user's summed rows of `user_event_performance`
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct PerformanceTotals {
+    pub average_speaker_score: Option<Decimal>,
+    pub wins: i64,
+    pub losses: i64,
+    pub firsts: i64,
+    pub seconds: i64,
+    pub thirds: i64,
+    pub fourths: i64,
+}
+
+impl PerformanceTotals {
+    /// How often the user placed at each rank, leaving out ranks never taken
+    pub fn rankings(&self) -> Vec<RankingCount> {
+        [self.firsts, self.seconds, self.thirds, self.fourths]
+            .into_iter()
+            .zip(1..)
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, rank)| RankingCount { rank, count })
+            .collect()
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct PerformanceListResponse {
     pub performances: Vec<PerformanceResponse>,
@@ -562,6 +1180,8 @@ pub struct CheckedInUserResponse {
     pub user_id: Uuid,
     pub username: String,
     pub checked_in_at: DateTime<Utc>,
+    /// Where the member stands with the event's fee; None when it is free
+    pub payment_status: Option<String>,
     pub is_allocated: bool,
     pub current_allocation: Option<CurrentAllocationInfo>,
 }
@@ -573,11 +1193,24 @@ pub struct CurrentAllocationInfo {
     pub role: AllocationRole,
 }
 
+/// A guest registered at the desk, allocated by name
+#[derive(Debug, Serialize)]
+pub struct WalkInPoolResponse {
+    pub walk_in_id: Uuid,
+    pub name: String,
+    pub institution_id: Option<Uuid>,
+    pub registered_at: DateTime<Utc>,
+    pub is_allocated: bool,
+    pub current_allocation: Option<CurrentAllocationInfo>,
+}
+
+/// Totals count walk-ins alongside checked-in members
 #[derive(Debug, Serialize)]
 pub struct AllocationPoolResponse {
     pub event_id: Uuid,
     pub series_id: Uuid,
     pub checked_in_users: Vec<CheckedInUserResponse>,
+    pub walk_ins: Vec<WalkInPoolResponse>,
     pub total_checked_in: i64,
     pub total_allocated: i64,
     pub total_available: i64,
@@ -605,6 +1238,10 @@ pub struct EventInfo {
     pub id: Uuid,
     pub title: String,
     pub is_locked: bool,
+    pub team_entry: bool,
+    /// Speaker scores stay hidden until the end of the tournament
+    pub speaks_withheld: bool,
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 // Attendance record for checking check-in status
@@ -615,4 +1252,120 @@ pub struct AttendanceInfo {
     pub user_id: Uuid,
     pub is_checked_in: bool,
     pub checked_in_at: Option<DateTime<Utc>>,
+    /// unpaid, pending, paid or waived; None when the event is free
+    pub payment_status: Option<String>,
+}
+
+/// A guest registered at the attendance desk
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WalkInGuest {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub institution_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+// A voting adjudicator yet to submit in a match that is under way
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OutstandingBallot {
+    pub match_id: Uuid,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub scheduled_time: Option<DateTime<Utc>>,
+    pub adjudicator_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub phone_number: String,
+    pub is_chair: bool,
+}
+
+// Submitted and outstanding ballots in one room
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RoomBallotProgress {
+    pub match_id: Uuid,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub submitted: i64,
+    pub outstanding: i64,
+}
+
+// Ballots handed out and submitted in one event's draws
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventBallotCount {
+    pub event_id: Uuid,
+    pub title: String,
+    pub event_date: DateTime<Utc>,
+    pub matches: i64,
+    pub ballots: i64,
+    pub submitted: i64,
+}
+
+// Ballot numbers for the admin stats report
+#[derive(Debug, Clone, Serialize)]
+pub struct BallotStats {
+    pub total_ballots: i64,
+    pub submitted_ballots: i64,
+    pub average_ballots_per_event: Option<f64>,
+    pub ballots_per_event: Vec<EventBallotCount>,
+}
+
+// Another allocation of the same user in the event that overlaps a match
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScheduleConflict {
+    pub allocation_id: Uuid,
+    pub role: AllocationRole,
+    pub match_id: Uuid,
+    pub room_name: Option<String>,
+    pub scheduled_time: Option<DateTime<Utc>>,
+    pub series_id: Uuid,
+    pub series_name: String,
+}
+
+// One of a user's allocations in an event, with where and when to be
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScheduleEntry {
+    pub allocation_id: Uuid,
+    pub match_id: Uuid,
+    pub series_id: Uuid,
+    pub series_name: String,
+    pub round_number: Option<i32>,
+    pub role: AllocationRole,
+    pub is_chair: Option<bool>,
+    pub team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub two_team_position: Option<TwoTeamPosition>,
+    pub four_team_position: Option<FourTeamPosition>,
+    pub two_team_speaker_role: Option<TwoTeamSpeakerRole>,
+    pub four_team_speaker_role: Option<FourTeamSpeakerRole>,
+    pub room_name: Option<String>,
+    pub venue_id: Option<Uuid>,
+    pub scheduled_time: Option<DateTime<Utc>>,
+    pub status: MatchStatus,
+    pub motion: Option<String>,
+    pub info_slide: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQuery {
+    pub event_id: Uuid,
+}
+
+// A user's allocation with the match and event details a calendar entry needs
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CalendarAllocation {
+    pub allocation_id: Uuid,
+    pub match_id: Uuid,
+    pub role: AllocationRole,
+    pub is_chair: Option<bool>,
+    pub team_name: Option<String>,
+    pub two_team_position: Option<TwoTeamPosition>,
+    pub four_team_position: Option<FourTeamPosition>,
+    pub room_name: Option<String>,
+    pub scheduled_time: Option<DateTime<Utc>>,
+    pub series_name: String,
+    pub event_title: String,
+    pub event_date: DateTime<Utc>,
+    pub event_location: Option<String>,
+    pub updated_at: DateTime<Utc>,
 }
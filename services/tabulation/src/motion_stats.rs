@@ -0,0 +1,248 @@
+//! Side balance per motion, so the adjudication core can check after each
+//! round whether a motion favoured one side.
+//!
+//! Results come from the final team ranks of matches with at least one
+//! submitted voting ballot. Rooms debating the same motion (compared with
+//! surrounding whitespace ignored) are pooled. For two-team rooms the
+//! balance is how often government won; for four-team rooms it is each
+//! position's average team points, 3 for a first down to 0 for a fourth.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use uuid::Uuid;
+
+use crate::models::{FourTeamPosition, TwoTeamPosition};
+use crate::rounding::RoundingPolicy;
+
+/// One team's final rank in a room with confirmed results
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct MotionResult {
+    pub match_id: Uuid,
+    pub motion: String,
+    pub series_name: String,
+    pub two_team_position: Option<TwoTeamPosition>,
+    pub four_team_position: Option<FourTeamPosition>,
+    pub final_rank: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TwoTeamBalance {
+    pub debates: usize,
+    pub government_wins: usize,
+    pub opposition_wins: usize,
+    pub government_win_percentage: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionBalance {
+    pub debates: usize,
+    pub firsts: usize,
+    /// Team points, 3 for a first down to 0 for a fourth
+    pub average_points: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FourTeamBalance {
+    pub debates: usize,
+    pub opening_government: PositionBalance,
+    pub opening_opposition: PositionBalance,
+    pub closing_government: PositionBalance,
+    pub closing_opposition: PositionBalance,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MotionStats {
+    pub motion: String,
+    /// Series the motion was set in, in round order
+    pub rounds: Vec<String>,
+    pub two_team: Option<TwoTeamBalance>,
+    pub four_team: Option<FourTeamBalance>,
+}
+
+/// Balance for each motion in `results`, in the order the motions were
+/// first debated. `results` should be sorted by round.
+pub fn motion_stats(results: &[MotionResult], rounding: &RoundingPolicy) -> Vec<MotionStats> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut by_motion: BTreeMap<&str, Vec<&MotionResult>> = BTreeMap::new();
+    for result in results {
+        let motion = result.motion.trim();
+        if motion.is_empty() {
+            continue;
+        }
+        by_motion
+            .entry(motion)
+            .or_insert_with(|| {
+                order.push(motion);
+                Vec::new()
+            })
+            .push(result);
+    }
+
+    order
+        .into_iter()
+        .map(|motion| {
+            let results = &by_motion[motion];
+            let mut rounds: Vec<String> = Vec::new();
+            for result in results {
+                if !rounds.contains(&result.series_name) {
+                    rounds.push(result.series_name.clone());
+                }
+            }
+            MotionStats {
+                motion: motion.to_string(),
+                rounds,
+                two_team: two_team_balance(results, rounding),
+                four_team: four_team_balance(results, rounding),
+            }
+        })
+        .collect()
+}
+
+fn two_team_balance(
+    results: &[&MotionResult],
+    rounding: &RoundingPolicy,
+) -> Option<TwoTeamBalance> {
+    let winners: BTreeMap<Uuid, TwoTeamPosition> = results
+        .iter()
+        .filter(|r| r.final_rank == 1)
+        .filter_map(|r| Some((r.match_id, r.two_team_position?)))
+        .collect();
+    if winners.is_empty() {
+        return None;
+    }
+
+    let government_wins = winners
+        .values()
+        .filter(|&&p| p == TwoTeamPosition::Government)
+        .count();
+    let debates = winners.len();
+    Some(TwoTeamBalance {
+        debates,
+        government_wins,
+        opposition_wins: debates - government_wins,
+        government_win_percentage: rounding
+            .round(Decimal::from(government_wins * 100) / Decimal::from(debates)),
+    })
+}
+
+fn four_team_balance(
+    results: &[&MotionResult],
+    rounding: &RoundingPolicy,
+) -> Option<FourTeamBalance> {
+    let at = |position: FourTeamPosition| -> PositionBalance {
+        let ranks: Vec<i32> = results
+            .iter()
+            .filter(|r| r.four_team_position == Some(position))
+            .map(|r| r.final_rank)
+            .collect();
+        let points: Vec<Decimal> = ranks
+            .iter()
+            .map(|&rank| Decimal::from((4 - rank).clamp(0, 3)))
+            .collect();
+        PositionBalance {
+            debates: ranks.len(),
+            firsts: ranks.iter().filter(|&&rank| rank == 1).count(),
+            average_points: rounding.average(&points).unwrap_or_default(),
+        }
+    };
+
+    let debates = results
+        .iter()
+        .filter(|r| r.four_team_position.is_some())
+        .map(|r| r.match_id)
+        .collect::<BTreeSet<_>>()
+        .len();
+    if debates == 0 {
+        return None;
+    }
+    Some(FourTeamBalance {
+        debates,
+        opening_government: at(FourTeamPosition::OpeningGovernment),
+        opening_opposition: at(FourTeamPosition::OpeningOpposition),
+        closing_government: at(FourTeamPosition::ClosingGovernment),
+        closing_opposition: at(FourTeamPosition::ClosingOpposition),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_team(room: u128, motion: &str, government_rank: i32) -> Vec<MotionResult> {
+        [
+            (TwoTeamPosition::Government, government_rank),
+            (TwoTeamPosition::Opposition, 3 - government_rank),
+        ]
+        .into_iter()
+        .map(|(position, final_rank)| MotionResult {
+            match_id: Uuid::from_u128(room),
+            motion: motion.to_string(),
+            series_name: "Round 1".to_string(),
+            two_team_position: Some(position),
+            four_team_position: None,
+            final_rank,
+        })
+        .collect()
+    }
+
+    fn four_team(room: u128, ranks: [i32; 4]) -> Vec<MotionResult> {
+        [
+            FourTeamPosition::OpeningGovernment,
+            FourTeamPosition::OpeningOpposition,
+            FourTeamPosition::ClosingGovernment,
+            FourTeamPosition::ClosingOpposition,
+        ]
+        .into_iter()
+        .zip(ranks)
+        .map(|(position, final_rank)| MotionResult {
+            match_id: Uuid::from_u128(room),
+            motion: "THW ban zoos".to_string(),
+            series_name: "Round 2".to_string(),
+            two_team_position: None,
+            four_team_position: Some(position),
+            final_rank,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_two_team_rooms_are_pooled_by_motion() {
+        let results: Vec<MotionResult> = [
+            two_team(1, "THW tax meat", 1),
+            two_team(2, " THW tax meat ", 1),
+            two_team(3, "THW tax meat", 2),
+            two_team(4, "THBT cities should be car-free", 2),
+        ]
+        .concat();
+        let stats = motion_stats(&results, &RoundingPolicy::default());
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].motion, "THW tax meat");
+        let balance = stats[0].two_team.as_ref().unwrap();
+        assert_eq!((balance.debates, balance.government_wins), (3, 2));
+        assert_eq!(balance.government_win_percentage, Decimal::new(6667, 2));
+        assert!(stats[0].four_team.is_none());
+        assert_eq!(stats[1].two_team.as_ref().unwrap().opposition_wins, 1);
+    }
+
+    #[test]
+    fn test_four_team_positions_average_team_points() {
+        let results = [four_team(1, [1, 2, 3, 4]), four_team(2, [2, 4, 1, 3])].concat();
+        let stats = motion_stats(&results, &RoundingPolicy::default());
+
+        let balance = stats[0].four_team.as_ref().unwrap();
+        assert_eq!(balance.debates, 2);
+        // OG took 3 then 2 points
+        assert_eq!(
+            balance.opening_government.average_points,
+            Decimal::new(25, 1)
+        );
+        assert_eq!(balance.opening_government.firsts, 1);
+        assert_eq!(
+            balance.closing_opposition.average_points,
+            Decimal::new(5, 1)
+        );
+        assert!(stats[0].two_team.is_none());
+    }
+}
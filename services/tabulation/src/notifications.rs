@@ -0,0 +1,200 @@
+//! Chat notifications for draws and results (see `common::notify`)
+
+use std::sync::Arc;
+
+use common::{Notification, NotificationKind};
+
+use crate::database::Database;
+use crate::models::{
+    AllocationRole, AllocationWithUser, FourTeamPosition, Match, MatchTeam, TwoTeamPosition,
+};
+use crate::AppState;
+
+/// Build and post a match notification in the background. Does nothing when
+/// `kind` is not enabled; failures are logged rather than surfaced to the
+/// admin who triggered it.
+pub fn announce(state: &Arc<AppState>, kind: NotificationKind, match_record: Match) {
+    if !state.notifier.is_enabled(kind) {
+        return;
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        let notification = match kind {
+            NotificationKind::DrawPublished => draw_published(&state.db, &match_record).await,
+            NotificationKind::ResultsReleased => results_released(&state.db, &match_record).await,
+            _ => return,
+        };
+
+        match notification {
+            Ok(notification) => state.notifier.notify(notification),
+            Err(e) => tracing::warn!(
+                "Failed to build {} notification for match {}: {}",
+                kind.as_str(),
+                match_record.id,
+                e
+            ),
+        }
+    });
+}
+
+pub(crate) fn position_label(
+    two_team: Option<TwoTeamPosition>,
+    four_team: Option<FourTeamPosition>,
+) -> &'static str {
+    match (two_team, four_team) {
+        (Some(TwoTeamPosition::Government), _) => "Government",
+        (Some(TwoTeamPosition::Opposition), _) => "Opposition",
+        (_, Some(FourTeamPosition::OpeningGovernment)) => "Opening Government",
+        (_, Some(FourTeamPosition::OpeningOpposition)) => "Opening Opposition",
+        (_, Some(FourTeamPosition::ClosingGovernment)) => "Closing Government",
+        (_, Some(FourTeamPosition::ClosingOpposition)) => "Closing Opposition",
+        (None, None) => "Team",
+    }
+}
+
+fn team_heading(team: &MatchTeam) -> String {
+    let position = position_label(team.two_team_position, team.four_team_position);
+    match &team.team_name {
+        Some(name) => format!("{} · {}", position, name),
+        None => position.to_string(),
+    }
+}
+
+/// Series name plus an "Event · Room" line shared by every match notification
+async fn match_context(
+    db: &Database,
+    match_record: &Match,
+) -> Result<(String, String), sqlx::Error> {
+    let series = db.get_series_by_id(match_record.series_id).await?;
+    let event = match &series {
+        Some(series) => db.get_event_by_id(series.event_id).await?,
+        None => None,
+    };
+
+    let series_name = series
+        .map(|s| s.name)
+        .unwrap_or_else(|| "Match".to_string());
+    let mut context = Vec::new();
+    if let Some(event) = event {
+        context.push(event.title);
+    }
+    if let Some(room) = &match_record.room_name {
+        context.push(room.clone());
+    }
+
+    Ok((series_name, context.join(" · ")))
+}
+
+/// Teams with their speakers, then the panel
+pub async fn draw_published(
+    db: &Database,
+    match_record: &Match,
+) -> Result<Notification, sqlx::Error> {
+    let (series_name, context) = match_context(db, match_record).await?;
+    let teams = db.list_teams_by_match(match_record.id).await?;
+    let allocations = db.list_allocations_by_match(match_record.id).await?;
+
+    let mut description = context;
+    if let Some(time) = match_record.scheduled_time {
+        description.push_str(&format!("\nStarts {}", time.format("%a %d %b, %H:%M UTC")));
+    }
+
+    let mut notification = Notification::new(
+        NotificationKind::DrawPublished,
+        format!("Draw published: {}", series_name),
+    )
+    .description(description);
+
+    for team in &teams {
+        let speakers: Vec<&str> = allocations
+            .iter()
+            .filter(|a| a.team_id == Some(team.id) && a.role == AllocationRole::Speaker)
+            .map(|a| a.username.as_str())
+            .collect();
+        let value = if speakers.is_empty() {
+            "TBA".to_string()
+        } else {
+            speakers.join(", ")
+        };
+        notification = notification.inline_field(team_heading(team), value);
+    }
+
+    let panel = panel_summary(&allocations);
+    if !panel.is_empty() {
+        notification = notification.field("Adjudicators", panel);
+    }
+
+    Ok(notification)
+}
+
+fn panel_summary(allocations: &[AllocationWithUser]) -> String {
+    let mut panel: Vec<&AllocationWithUser> = allocations
+        .iter()
+        .filter(|a| {
+            matches!(
+                a.role,
+                AllocationRole::VotingAdjudicator | AllocationRole::NonVotingAdjudicator
+            )
+        })
+        .collect();
+    // Chair first, trainees last
+    panel.sort_by_key(|a| {
+        (
+            a.is_chair != Some(true),
+            a.role == AllocationRole::NonVotingAdjudicator,
+        )
+    });
+
+    panel
+        .iter()
+        .map(|a| match (a.is_chair, a.role) {
+            (Some(true), _) => format!("{} (chair)", a.username),
+            (_, AllocationRole::NonVotingAdjudicator) => format!("{} (trainee)", a.username),
+            _ => a.username.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Final standings of the room. Speaker points are only included once scores
+/// are released and the event is not withholding them.
+pub async fn results_released(
+    db: &Database,
+    match_record: &Match,
+) -> Result<Notification, sqlx::Error> {
+    let (series_name, context) = match_context(db, match_record).await?;
+    let speaks_withheld = match db.get_series_by_id(match_record.series_id).await? {
+        Some(series) => db
+            .get_event_by_id(series.event_id)
+            .await?
+            .is_some_and(|event| event.speaks_withheld),
+        None => false,
+    };
+    let mut teams = db.list_teams_by_match(match_record.id).await?;
+    teams.sort_by_key(|t| t.final_rank.unwrap_or(i32::MAX));
+
+    let standings: Vec<String> = teams
+        .iter()
+        .map(|team| {
+            let rank = team
+                .final_rank
+                .map(|r| format!("{}.", r))
+                .unwrap_or_else(|| "–".to_string());
+            match (
+                match_record.scores_released && !speaks_withheld,
+                team.total_speaker_points,
+            ) {
+                (true, Some(points)) => format!("{} {} ({} pts)", rank, team_heading(team), points),
+                _ => format!("{} {}", rank, team_heading(team)),
+            }
+        })
+        .collect();
+
+    Ok(Notification::new(
+        NotificationKind::ResultsReleased,
+        format!("Results released: {}", series_name),
+    )
+    .description(context)
+    .field("Standings", standings.join("\n")))
+}
@@ -0,0 +1,134 @@
+//! Domain events the tabulation service publishes through the outbox (see
+//! `common::outbox`)
+
+use std::sync::Arc;
+
+use common::outbox::{OutboxEvent, Relay};
+use common::{Notification, NotificationKind};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub const SERVICE: &str = "tabulation";
+
+/// An adjudicator submitted a scored ballot
+pub const BALLOT_CONFIRMED: &str = "ballot_confirmed";
+
+/// `ballot_confirmed` payload, built in SQL by `Database::save_ballot_results`
+#[derive(Debug, Deserialize)]
+struct BallotConfirmed {
+    series_name: String,
+    event_title: Option<String>,
+    room_name: Option<String>,
+    adjudicator_username: Option<String>,
+    is_voting: bool,
+    rankings: Vec<RankedTeam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RankedTeam {
+    team_id: Uuid,
+    team_name: Option<String>,
+    rank: i32,
+}
+
+/// Start delivering the service's events in the background
+pub fn spawn(state: Arc<AppState>) {
+    Relay::new(state.db.pool().clone(), SERVICE, &state.config.outbox)
+        .with_chat(state.notifier.clone(), notification)
+        .with_bus(state.events.clone())
+        .with_features(state.features.clone())
+        .spawn();
+}
+
+fn notification(event: &OutboxEvent) -> Option<Notification> {
+    if event.kind != BALLOT_CONFIRMED {
+        return None;
+    }
+    let ballot: BallotConfirmed = match serde_json::from_value(event.payload.clone()) {
+        Ok(ballot) => ballot,
+        Err(e) => {
+            tracing::warn!("Unreadable {} event {}: {}", event.kind, event.id, e);
+            return None;
+        }
+    };
+    Some(ballot_confirmed(&ballot))
+}
+
+fn ballot_confirmed(ballot: &BallotConfirmed) -> Notification {
+    let context: Vec<&str> = [&ballot.event_title, &ballot.room_name]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    let adjudicator = ballot.adjudicator_username.as_deref().unwrap_or("Unknown");
+    let adjudicator = if ballot.is_voting {
+        adjudicator.to_string()
+    } else {
+        format!("{} (trainee)", adjudicator)
+    };
+
+    let mut notification = Notification::new(
+        NotificationKind::BallotConfirmed,
+        format!("Ballot in: {}", ballot.series_name),
+    )
+    .description(context.join(" · "))
+    .inline_field("Adjudicator", adjudicator);
+
+    if !ballot.rankings.is_empty() {
+        let rankings: Vec<String> = ballot
+            .rankings
+            .iter()
+            .map(|team| match &team.team_name {
+                Some(name) => format!("{}. {}", team.rank, name),
+                None => format!("{}. Team {}", team.rank, &team.team_id.to_string()[..8]),
+            })
+            .collect();
+        notification = notification.field("Rankings", rankings.join("\n"));
+    }
+    notification
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    #[test]
+    fn test_ballot_confirmed_notification() {
+        let event = OutboxEvent {
+            id: Uuid::nil(),
+            service: SERVICE.to_string(),
+            kind: BALLOT_CONFIRMED.to_string(),
+            payload: json!({
+                "ballot_id": Uuid::nil(),
+                "series_name": "Round 3",
+                "event_title": "Winter Open",
+                "room_name": "Room 2",
+                "adjudicator_username": "ayesha",
+                "is_voting": true,
+                "rankings": [
+                    {"team_id": Uuid::nil(), "team_name": "Lahore A", "rank": 1},
+                    {"team_id": Uuid::nil(), "team_name": null, "rank": 2}
+                ]
+            }),
+            created_at: Utc::now(),
+            attempts: 0,
+            delivered_to: Vec::new(),
+        };
+
+        let posted = notification(&event).unwrap();
+        assert_eq!(posted.kind, NotificationKind::BallotConfirmed);
+        assert_eq!(posted.title, "Ballot in: Round 3");
+        assert_eq!(posted.description, "Winter Open · Room 2");
+        assert_eq!(posted.fields[1].value, "1. Lahore A\n2. Team 00000000");
+
+        let other = OutboxEvent {
+            kind: "something_else".to_string(),
+            ..event
+        };
+        assert!(notification(&other).is_none());
+    }
+}
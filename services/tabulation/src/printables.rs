@@ -0,0 +1,295 @@
+//! Printable paper backups: a ballot per room for adjudicators to fill in
+//! by hand, and room slips listing who goes where in a round. Both are
+//! plain PDFs, so venues without reliable internet can run from paper.
+
+use common::pdf::{PdfDocument, TextStyle};
+
+use crate::models::{
+    AdjudicatorResponse, FourTeamSpeakerRole, MatchResponse, MatchSeries, MatchStatus,
+    TwoTeamSpeakerRole,
+};
+use crate::notifications::position_label;
+
+/// Blank for a handwritten value
+const BLANK: &str = "________";
+/// Longest name shown in full in the speaker tables
+const NAME_COLUMN: usize = 30;
+/// Handwritten lines left for the motion and for notes
+const WRITING_LINES: usize = 3;
+
+/// A ballot for one room: team sheet with score and rank blanks, the
+/// panel, and space for the motion if it is not set yet
+pub fn ballot(event_title: &str, series: &MatchSeries, room: &MatchResponse) -> PdfDocument {
+    let mut doc = PdfDocument::new(
+        format!("Ballot: {}, {}", series.name, room_name(room)),
+        format!(
+            "ballot-{}-{}.pdf",
+            slug(&series.name),
+            slug(&room_name(room))
+        ),
+    );
+    push_ballot(&mut doc, event_title, series, room);
+    doc
+}
+
+/// Room slips for a round: each room's teams, speakers and panel, with
+/// space for the motion, separated by cut lines
+pub fn room_slips(event_title: &str, series: &MatchSeries, rooms: &[MatchResponse]) -> PdfDocument {
+    let mut doc = PdfDocument::new(
+        format!("Room slips: {}", series.name),
+        format!("room-slips-{}.pdf", slug(&series.name)),
+    );
+    doc.push(
+        TextStyle::Heading,
+        &format!("{}: {}", event_title, series.name),
+    );
+
+    let rooms: Vec<&MatchResponse> = printable(rooms).collect();
+    if rooms.is_empty() {
+        doc.push(TextStyle::Body, "No rooms have been drawn.");
+    }
+    for room in rooms {
+        doc.push(TextStyle::Mono, &"- ".repeat(45));
+        doc.push(TextStyle::Heading, &room_name(room));
+        if let Some(time) = room.scheduled_time {
+            doc.push(
+                TextStyle::Body,
+                &format!("Starts {}", time.format("%a %d %b, %H:%M UTC")),
+            );
+        }
+        for team in &room.teams {
+            let speakers: Vec<&str> = team.speakers.iter().map(|s| s.username.as_str()).collect();
+            doc.push(
+                TextStyle::Body,
+                &format!(
+                    "{}: {}{}",
+                    position_label(team.two_team_position, team.four_team_position),
+                    team.team_name.as_deref().unwrap_or("TBC"),
+                    if speakers.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" ({})", speakers.join(", "))
+                    }
+                ),
+            );
+        }
+        doc.push(TextStyle::Body, &panel(&room.adjudicators));
+        push_motion(&mut doc, room);
+    }
+    doc
+}
+
+fn push_ballot(
+    doc: &mut PdfDocument,
+    event_title: &str,
+    series: &MatchSeries,
+    room: &MatchResponse,
+) {
+    doc.push(
+        TextStyle::Heading,
+        &format!("Ballot: {}, {}", series.name, room_name(room)),
+    );
+    doc.push(TextStyle::Body, event_title);
+    doc.push(TextStyle::Body, &panel(&room.adjudicators));
+    push_motion(doc, room);
+
+    for team in &room.teams {
+        doc.blank_line();
+        doc.push(
+            TextStyle::Heading,
+            &format!(
+                "{}: {}",
+                position_label(team.two_team_position, team.four_team_position),
+                team.team_name.as_deref().unwrap_or("TBC")
+            ),
+        );
+        doc.push(
+            TextStyle::Mono,
+            &format!(
+                "{:<26}  {:<NAME_COLUMN$}  {:>8}",
+                "Position", "Speaker", "Score"
+            ),
+        );
+        for speaker in &team.speakers {
+            let role = match (
+                speaker.two_team_speaker_role,
+                speaker.four_team_speaker_role,
+            ) {
+                (Some(role), _) => two_team_role(role),
+                (_, Some(role)) => four_team_role(role),
+                (None, None) => "",
+            };
+            doc.push(
+                TextStyle::Mono,
+                &format!(
+                    "{:<26}  {:<NAME_COLUMN$}  {:>8}",
+                    role,
+                    fit(&speaker.username, NAME_COLUMN),
+                    BLANK
+                ),
+            );
+        }
+        doc.push(
+            TextStyle::Body,
+            &format!("Team total: {}    Rank: {}", BLANK, BLANK),
+        );
+    }
+
+    doc.blank_line();
+    doc.push(TextStyle::Body, "Notes:");
+    for _ in 0..WRITING_LINES {
+        doc.blank_line();
+        doc.push(TextStyle::Mono, &"_".repeat(90));
+    }
+    doc.blank_line();
+    doc.push(
+        TextStyle::Body,
+        &format!(
+            "Adjudicator: {}{}    Signature: {}{}",
+            BLANK, BLANK, BLANK, BLANK
+        ),
+    );
+}
+
+/// The motion, or lines to write it on
+fn push_motion(doc: &mut PdfDocument, room: &MatchResponse) {
+    match room.motion.as_deref().filter(|m| !m.trim().is_empty()) {
+        Some(motion) => doc.push(TextStyle::Body, &format!("Motion: {}", motion)),
+        None => {
+            doc.push(TextStyle::Body, "Motion:");
+            for _ in 0..WRITING_LINES - 1 {
+                doc.blank_line();
+                doc.push(TextStyle::Mono, &"_".repeat(90));
+            }
+        }
+    }
+}
+
+/// Rooms worth printing: cancelled rooms are left out
+fn printable(rooms: &[MatchResponse]) -> impl Iterator<Item = &MatchResponse> {
+    rooms
+        .iter()
+        .filter(|room| room.status != MatchStatus::Cancelled)
+}
+
+fn room_name(room: &MatchResponse) -> String {
+    room.room_name
+        .clone()
+        .unwrap_or_else(|| "Unnamed room".to_string())
+}
+
+/// "Chair: A. Panel: B, C. Trainees: D." from the room's adjudicators
+fn panel(adjudicators: &[AdjudicatorResponse]) -> String {
+    let names = |pick: fn(&AdjudicatorResponse) -> bool| -> Vec<&str> {
+        adjudicators
+            .iter()
+            .filter(|a| pick(a))
+            .map(|a| a.username.as_str())
+            .collect()
+    };
+    let chair = names(|a| a.is_chair);
+    let panel = names(|a| a.is_voting && !a.is_chair);
+    let trainees = names(|a| !a.is_voting && !a.is_chair);
+
+    let mut parts = vec![format!(
+        "Chair: {}.",
+        if chair.is_empty() {
+            "TBC".to_string()
+        } else {
+            chair.join(", ")
+        }
+    )];
+    if !panel.is_empty() {
+        parts.push(format!("Panel: {}.", panel.join(", ")));
+    }
+    if !trainees.is_empty() {
+        parts.push(format!("Trainees: {}.", trainees.join(", ")));
+    }
+    parts.join(" ")
+}
+
+fn two_team_role(role: TwoTeamSpeakerRole) -> &'static str {
+    match role {
+        TwoTeamSpeakerRole::PrimeMinister => "Prime Minister",
+        TwoTeamSpeakerRole::DeputyPrimeMinister => "Deputy Prime Minister",
+        TwoTeamSpeakerRole::GovernmentWhip => "Government Whip",
+        TwoTeamSpeakerRole::LeaderOfOpposition => "Leader of Opposition",
+        TwoTeamSpeakerRole::DeputyLeaderOfOpposition => "Deputy Leader of Opposition",
+        TwoTeamSpeakerRole::OppositionWhip => "Opposition Whip",
+        TwoTeamSpeakerRole::GovernmentReply => "Government Reply",
+        TwoTeamSpeakerRole::OppositionReply => "Opposition Reply",
+    }
+}
+
+fn four_team_role(role: FourTeamSpeakerRole) -> &'static str {
+    match role {
+        FourTeamSpeakerRole::PrimeMinister => "Prime Minister",
+        FourTeamSpeakerRole::DeputyPrimeMinister => "Deputy Prime Minister",
+        FourTeamSpeakerRole::LeaderOfOpposition => "Leader of Opposition",
+        FourTeamSpeakerRole::DeputyLeaderOfOpposition => "Deputy Leader of Opposition",
+        FourTeamSpeakerRole::MemberOfGovernment => "Member of Government",
+        FourTeamSpeakerRole::GovernmentWhip => "Government Whip",
+        FourTeamSpeakerRole::MemberOfOpposition => "Member of Opposition",
+        FourTeamSpeakerRole::OppositionWhip => "Opposition Whip",
+    }
+}
+
+/// `name` cut to `width` characters, marking the cut with `~`
+fn fit(name: &str, width: usize) -> String {
+    let mut fitted: String = name.chars().take(width).collect();
+    if name.chars().count() > width {
+        fitted.pop();
+        fitted.push('~');
+    }
+    fitted
+}
+
+/// Lowercase letters and digits, other runs of characters as one hyphen
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn adjudicator(name: &str, is_voting: bool, is_chair: bool) -> AdjudicatorResponse {
+        AdjudicatorResponse {
+            allocation_id: Uuid::new_v4(),
+            user_id: None,
+            guest_name: None,
+            username: name.to_string(),
+            is_voting,
+            is_chair,
+            has_submitted: false,
+        }
+    }
+
+    #[test]
+    fn test_panel_lists_chair_panellists_and_trainees() {
+        assert_eq!(
+            panel(&[
+                adjudicator("Tara", false, false),
+                adjudicator("Bo", true, false),
+                adjudicator("Ann", true, true),
+            ]),
+            "Chair: Ann. Panel: Bo. Trainees: Tara."
+        );
+        assert_eq!(panel(&[]), "Chair: TBC.");
+    }
+
+    #[test]
+    fn test_slugs() {
+        assert_eq!(slug("Round 3: Semis"), "round-3-semis");
+        assert_eq!(slug("Room B/12"), "room-b-12");
+    }
+}
@@ -0,0 +1,133 @@
+//! Released tabs and breaks as the public sees them, kept in the shared
+//! [`KvStore`] so a results page refreshed by a whole room is worked out
+//! once rather than once per request.
+//!
+//! Entries are keyed by a per-event generation that is bumped whenever a
+//! ballot or the event's tab settings change, so entries made before the
+//! change are never read again and simply expire. Other edits, such as
+//! renaming a team, show once the entry expires. Release toggles and
+//! withheld speaks are checked before the cache is read, and admin views
+//! are never cached.
+
+use common::KvStore;
+use serde_json::Value;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::eligibility::EligibilityFilter;
+
+pub const TEAM_TAB: &str = "team_tab";
+pub const SPEAKER_TAB: &str = "speaker_tab";
+pub const BREAK: &str = "break";
+
+/// How much longer than an entry a generation counter lives. Once it
+/// expires it restarts at zero, after every entry from those generations
+/// has expired too.
+const GENERATION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone)]
+pub struct ResultsCache {
+    store: KvStore,
+    /// Zero turns the cache off
+    ttl: Duration,
+}
+
+impl ResultsCache {
+    pub fn new(store: KvStore, ttl: Duration) -> Self {
+        Self { store, ttl }
+    }
+
+    fn generation_key(event_id: Uuid) -> String {
+        format!("results:{}:generation", event_id)
+    }
+
+    /// Where `view` of the event's tab for `category` is cached, or None
+    /// when it should not be: for admins, with the cache off, or when the
+    /// store cannot be reached
+    pub async fn key(
+        &self,
+        event_id: Uuid,
+        view: &str,
+        category: EligibilityFilter,
+        is_admin: bool,
+    ) -> Option<String> {
+        if is_admin || self.ttl.is_zero() {
+            return None;
+        }
+        let generation = match self.store.get(&Self::generation_key(event_id)).await {
+            Ok(generation) => generation.unwrap_or_else(|| "0".to_string()),
+            Err(e) => {
+                tracing::warn!("Not caching results: {}", e);
+                return None;
+            }
+        };
+        Some(format!(
+            "results:{}:{}:{}:{}",
+            event_id, generation, view, category
+        ))
+    }
+
+    pub async fn get(&self, key: Option<&str>) -> Option<Value> {
+        let cached = self.store.get(key?).await.ok()??;
+        serde_json::from_str(&cached).ok()
+    }
+
+    pub async fn put(&self, key: Option<&str>, value: &Value) {
+        let Some(key) = key else {
+            return;
+        };
+        if let Err(e) = self.store.set(key, &value.to_string(), self.ttl).await {
+            tracing::warn!("Failed to cache results: {}", e);
+        }
+    }
+
+    /// Stop serving what is cached for the event
+    pub async fn invalidate(&self, event_id: Uuid) {
+        let ttl = self.ttl + GENERATION_TTL;
+        if let Err(e) = self.store.incr(&Self::generation_key(event_id), ttl).await {
+            tracing::warn!("Failed to invalidate cached results: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_invalidate_moves_to_a_new_generation() {
+        let cache = ResultsCache::new(KvStore::memory(), Duration::from_secs(30));
+        let event_id = Uuid::new_v4();
+        let tab = json!({"teams": ["Lahore A"]});
+
+        let key = cache
+            .key(event_id, TEAM_TAB, EligibilityFilter::Open, false)
+            .await;
+        assert!(cache.get(key.as_deref()).await.is_none());
+        cache.put(key.as_deref(), &tab).await;
+        assert_eq!(cache.get(key.as_deref()).await, Some(tab));
+
+        cache.invalidate(event_id).await;
+        let key = cache
+            .key(event_id, TEAM_TAB, EligibilityFilter::Open, false)
+            .await;
+        assert!(cache.get(key.as_deref()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_admins_and_disabled_cache_have_no_key() {
+        let cache = ResultsCache::new(KvStore::memory(), Duration::from_secs(30));
+        let event_id = Uuid::new_v4();
+        assert!(cache
+            .key(event_id, BREAK, EligibilityFilter::Open, true)
+            .await
+            .is_none());
+
+        let off = ResultsCache::new(KvStore::memory(), Duration::ZERO);
+        assert!(off
+            .key(event_id, BREAK, EligibilityFilter::Open, false)
+            .await
+            .is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! Position rotation: which sides each team and speaker has been drawn on
+//! across an event's rounds, so the draw can share out the positions.
+//!
+//! Only preliminary rounds count; break rounds are seeded, and friendlies
+//! and practice rounds have no draw to balance. Teams are matched up across
+//! rounds like on the tab, by registered team and then by name. Nobody
+//! should be drawn on the same position more than [`MAX_REPEATS`] times.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::models::{FourTeamPosition, MatchSeries, TwoTeamPosition};
+use crate::notifications::position_label;
+
+/// Times a team or speaker may be drawn on one position in an event
+pub const MAX_REPEATS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Position {
+    TwoTeam(TwoTeamPosition),
+    FourTeam(FourTeamPosition),
+}
+
+impl Position {
+    pub fn of(
+        two_team: Option<TwoTeamPosition>,
+        four_team: Option<FourTeamPosition>,
+    ) -> Option<Self> {
+        two_team
+            .map(Position::TwoTeam)
+            .or(four_team.map(Position::FourTeam))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Position::TwoTeam(TwoTeamPosition::Government) => "government",
+            Position::TwoTeam(TwoTeamPosition::Opposition) => "opposition",
+            Position::FourTeam(FourTeamPosition::OpeningGovernment) => "opening_government",
+            Position::FourTeam(FourTeamPosition::OpeningOpposition) => "opening_opposition",
+            Position::FourTeam(FourTeamPosition::ClosingGovernment) => "closing_government",
+            Position::FourTeam(FourTeamPosition::ClosingOpposition) => "closing_opposition",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match *self {
+            Position::TwoTeam(p) => position_label(Some(p), None),
+            Position::FourTeam(p) => position_label(None, Some(p)),
+        }
+    }
+
+    /// Every position of this one's format, in speaking order
+    fn format(&self) -> &'static [Position] {
+        match self {
+            Position::TwoTeam(_) => &[
+                Position::TwoTeam(TwoTeamPosition::Government),
+                Position::TwoTeam(TwoTeamPosition::Opposition),
+            ],
+            Position::FourTeam(_) => &[
+                Position::FourTeam(FourTeamPosition::OpeningGovernment),
+                Position::FourTeam(FourTeamPosition::OpeningOpposition),
+                Position::FourTeam(FourTeamPosition::ClosingGovernment),
+                Position::FourTeam(FourTeamPosition::ClosingOpposition),
+            ],
+        }
+    }
+}
+
+/// Whether positions in `series` count towards rotation
+pub fn counts_for_rotation(series: &MatchSeries) -> bool {
+    series.round_number.is_some() && !series.is_break_round && !series.is_training
+}
+
+/// A team's side in one room, with the speaker when drawn for a speaker
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DrawnPosition {
+    pub match_id: Uuid,
+    pub match_team_id: Uuid,
+    pub registered_team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub username: Option<String>,
+    pub series_name: String,
+    pub two_team_position: Option<TwoTeamPosition>,
+    pub four_team_position: Option<FourTeamPosition>,
+}
+
+impl DrawnPosition {
+    pub fn position(&self) -> Option<Position> {
+        Position::of(self.two_team_position, self.four_team_position)
+    }
+
+    /// Identity of the team across rounds
+    pub fn team_key(&self) -> String {
+        match (self.registered_team_id, &self.team_name) {
+            (Some(id), _) => id.to_string(),
+            (None, Some(name)) if !name.trim().is_empty() => {
+                format!("name:{}", name.trim().to_lowercase())
+            }
+            _ => self.match_team_id.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundPosition {
+    pub series_name: String,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RotationEntry {
+    pub registered_team_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    /// Positions in round order
+    pub rounds: Vec<RoundPosition>,
+    /// Times on each position of the formats debated
+    pub counts: BTreeMap<&'static str, usize>,
+    /// Most minus fewest times on a position of the same format
+    pub imbalance: usize,
+    /// Positions already had `MAX_REPEATS` times, for the draw to avoid
+    pub avoid: Vec<Position>,
+}
+
+/// Rotation of each team in `drawn`, which should be in round order
+pub fn team_rotation(drawn: &[DrawnPosition]) -> Vec<RotationEntry> {
+    rotation(drawn, |d| Some(d.team_key()), |d| d.team_name.clone())
+}
+
+/// Rotation of each speaker with an account in `drawn`
+pub fn speaker_rotation(drawn: &[DrawnPosition]) -> Vec<RotationEntry> {
+    rotation(
+        drawn,
+        |d| d.user_id.map(|id| id.to_string()),
+        |d| d.username.clone(),
+    )
+}
+
+/// The rounds `history` was already on `position` in, when drawing it
+/// there again would go over `MAX_REPEATS`
+pub fn repeats(history: &[DrawnPosition], position: Position) -> Vec<RoundPosition> {
+    let mut seen = HashSet::new();
+    let rounds: Vec<RoundPosition> = history
+        .iter()
+        .filter(|d| d.position() == Some(position) && seen.insert(d.match_id))
+        .map(|d| RoundPosition {
+            series_name: d.series_name.clone(),
+            position,
+        })
+        .collect();
+    if rounds.len() >= MAX_REPEATS {
+        rounds
+    } else {
+        Vec::new()
+    }
+}
+
+fn rotation(
+    drawn: &[DrawnPosition],
+    key: impl Fn(&DrawnPosition) -> Option<String>,
+    name: impl Fn(&DrawnPosition) -> Option<String>,
+) -> Vec<RotationEntry> {
+    let mut order: Vec<String> = Vec::new();
+    let mut by_key: HashMap<String, Vec<&DrawnPosition>> = HashMap::new();
+    for d in drawn.iter().filter(|d| d.position().is_some()) {
+        let Some(key) = key(d) else { continue };
+        by_key
+            .entry(key.clone())
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(d);
+    }
+
+    let mut entries: Vec<RotationEntry> =
+        order.iter().map(|key| entry(&by_key[key], &name)).collect();
+    entries.sort_by(|a, b| b.imbalance.cmp(&a.imbalance).then(a.name.cmp(&b.name)));
+    entries
+}
+
+fn entry(
+    drawn: &[&DrawnPosition],
+    name: &impl Fn(&DrawnPosition) -> Option<String>,
+) -> RotationEntry {
+    // A side can carry several of a speaker's speeches; count the room once
+    let mut rounds: Vec<(Uuid, RoundPosition)> = Vec::new();
+    for d in drawn {
+        if rounds.iter().any(|(match_id, _)| *match_id == d.match_id) {
+            continue;
+        }
+        if let Some(position) = d.position() {
+            let round = RoundPosition {
+                series_name: d.series_name.clone(),
+                position,
+            };
+            rounds.push((d.match_id, round));
+        }
+    }
+    let rounds: Vec<RoundPosition> = rounds.into_iter().map(|(_, round)| round).collect();
+
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut imbalance = 0;
+    let mut avoid = Vec::new();
+    let mut formats: Vec<&'static [Position]> = Vec::new();
+    for round in &rounds {
+        let format = round.position.format();
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    for format in formats {
+        let times: Vec<usize> = format
+            .iter()
+            .map(|&position| rounds.iter().filter(|r| r.position == position).count())
+            .collect();
+        for (&position, &n) in format.iter().zip(&times) {
+            counts.insert(position.as_str(), n);
+            if n >= MAX_REPEATS {
+                avoid.push(position);
+            }
+        }
+        let most = times.iter().max().copied().unwrap_or(0);
+        let fewest = times.iter().min().copied().unwrap_or(0);
+        imbalance = imbalance.max(most - fewest);
+    }
+
+    RotationEntry {
+        registered_team_id: drawn[0].registered_team_id,
+        user_id: drawn[0].user_id,
+        name: drawn
+            .iter()
+            .find_map(|d| name(d))
+            .unwrap_or_else(|| "Unnamed team".to_string()),
+        rounds,
+        counts,
+        imbalance,
+        avoid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn(team: &str, round: u128, position: FourTeamPosition) -> DrawnPosition {
+        DrawnPosition {
+            match_id: Uuid::from_u128(round),
+            match_team_id: Uuid::new_v4(),
+            registered_team_id: None,
+            team_name: Some(team.to_string()),
+            user_id: None,
+            username: None,
+            series_name: format!("Round {}", round),
+            two_team_position: None,
+            four_team_position: Some(position),
+        }
+    }
+
+    #[test]
+    fn test_team_rotation_counts_positions() {
+        use FourTeamPosition::*;
+        let history = [
+            drawn("Alpha", 1, ClosingOpposition),
+            drawn("Beta", 1, OpeningGovernment),
+            drawn("alpha ", 2, ClosingOpposition),
+            drawn("Beta", 2, OpeningOpposition),
+        ];
+        let entries = team_rotation(&history);
+
+        assert_eq!(entries.len(), 2);
+        let alpha = &entries[0];
+        assert_eq!(alpha.name, "Alpha");
+        assert_eq!(alpha.counts["closing_opposition"], 2);
+        assert_eq!(alpha.counts["opening_government"], 0);
+        assert_eq!(alpha.imbalance, 2);
+        assert_eq!(alpha.avoid, [Position::FourTeam(ClosingOpposition)]);
+        assert_eq!(entries[1].imbalance, 1);
+        assert!(entries[1].avoid.is_empty());
+    }
+
+    #[test]
+    fn test_repeats_only_past_the_limit() {
+        use FourTeamPosition::*;
+        let position = Position::FourTeam(ClosingOpposition);
+        let once = [drawn("Alpha", 1, ClosingOpposition)];
+        assert!(repeats(&once, position).is_empty());
+
+        let twice = [once[0].clone(), drawn("Alpha", 2, ClosingOpposition)];
+        assert_eq!(repeats(&twice, position).len(), 2);
+        assert!(repeats(&twice, Position::FourTeam(OpeningGovernment)).is_empty());
+    }
+
+    #[test]
+    fn test_positions_serialize_in_snake_case() {
+        let json = serde_json::to_value(Position::TwoTeam(TwoTeamPosition::Government)).unwrap();
+        assert_eq!(json, serde_json::json!("government"));
+        assert_eq!(
+            Position::FourTeam(FourTeamPosition::ClosingOpposition).label(),
+            "Closing Opposition"
+        );
+    }
+}
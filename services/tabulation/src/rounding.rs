@@ -0,0 +1,164 @@
+//! How aggregated results are rounded.
+//!
+//! Averaged speaks, team totals, margins and averaged ranks are summed and
+//! divided as decimals and rounded once, under one policy, so binary float
+//! error (an average of 0.4999999 instead of 0.5) can never change who
+//! ranks above whom. Stored scores have two decimal places, so a policy
+//! keeps at most two.
+
+use common::config::EnvReader;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::{fmt, str::FromStr};
+
+/// Most decimal places a policy may keep (the score columns' scale)
+pub const MAX_PRECISION: u32 = 2;
+
+/// What happens to a value exactly halfway between two roundings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Banker's rounding: halves go to the even neighbour (72.125 is 72.12)
+    #[default]
+    HalfEven,
+    /// Halves go away from zero (72.125 is 72.13)
+    HalfUp,
+}
+
+impl RoundingMode {
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+        }
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "half_even" | "bankers" => Ok(RoundingMode::HalfEven),
+            "half_up" => Ok(RoundingMode::HalfUp),
+            other => Err(format!(
+                "Invalid SCORE_ROUNDING '{}': expected half_even or half_up",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundingMode::HalfEven => write!(f, "half_even"),
+            RoundingMode::HalfUp => write!(f, "half_up"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundingPolicy {
+    pub mode: RoundingMode,
+    /// Decimal places kept
+    pub precision: u32,
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RoundingMode::default(),
+            precision: MAX_PRECISION,
+        }
+    }
+}
+
+impl RoundingPolicy {
+    pub fn read(env: &mut EnvReader) -> Self {
+        let precision: u32 = env.parse("SCORE_PRECISION");
+        if precision > MAX_PRECISION {
+            env.check::<(), _>(Err(format!(
+                "SCORE_PRECISION must be between 0 and {}",
+                MAX_PRECISION
+            )));
+        }
+
+        Self {
+            mode: env.parse("SCORE_ROUNDING"),
+            precision: precision.min(MAX_PRECISION),
+        }
+    }
+
+    pub fn round(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.precision, self.mode.strategy())
+    }
+
+    /// The rounded mean, or `None` for no values
+    pub fn average(&self, values: &[Decimal]) -> Option<Decimal> {
+        if values.is_empty() {
+            return None;
+        }
+        let sum: Decimal = values.iter().sum();
+        Some(self.round(sum / Decimal::from(values.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(mode: RoundingMode, precision: u32) -> RoundingPolicy {
+        RoundingPolicy { mode, precision }
+    }
+
+    #[test]
+    fn test_halves_follow_the_mode() {
+        let half = Decimal::new(72125, 3);
+        assert_eq!(
+            policy(RoundingMode::HalfEven, 2).round(half),
+            Decimal::new(7212, 2)
+        );
+        assert_eq!(
+            policy(RoundingMode::HalfUp, 2).round(half),
+            Decimal::new(7213, 2)
+        );
+    }
+
+    #[test]
+    fn test_precision_sets_places_kept() {
+        let value = Decimal::new(75555, 3);
+        assert_eq!(
+            policy(RoundingMode::HalfUp, 0).round(value),
+            Decimal::from(76)
+        );
+        assert_eq!(
+            policy(RoundingMode::HalfUp, 1).round(value),
+            Decimal::new(756, 1)
+        );
+    }
+
+    #[test]
+    fn test_averages_are_exact_before_rounding() {
+        // 1.5 in floats can land either side of the half; as decimals it is
+        // exactly a half and rounds by the mode
+        let ranks = [Decimal::from(1), Decimal::from(2)];
+        assert_eq!(
+            policy(RoundingMode::HalfEven, 0).average(&ranks),
+            Some(Decimal::from(2))
+        );
+        let thirds = [Decimal::from(1), Decimal::from(1), Decimal::from(2)];
+        assert_eq!(
+            RoundingPolicy::default().average(&thirds),
+            Some(Decimal::new(133, 2))
+        );
+        assert_eq!(RoundingPolicy::default().average(&[]), None);
+    }
+
+    #[test]
+    fn test_mode_round_trip() {
+        for mode in [RoundingMode::HalfEven, RoundingMode::HalfUp] {
+            assert_eq!(mode.to_string().parse::<RoundingMode>(), Ok(mode));
+        }
+        assert_eq!("Bankers".parse(), Ok(RoundingMode::HalfEven));
+        assert!("ceiling".parse::<RoundingMode>().is_err());
+    }
+}
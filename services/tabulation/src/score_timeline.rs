@@ -0,0 +1,117 @@
+//! A member's speaker scores round by round, for charting progress. Each
+//! point is the score shown for the speech (the mean of the voting
+//! ballots) with the rolling average of the last few speeches up to it.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::rounding::RoundingPolicy;
+
+/// Speeches in the rolling average unless the caller picks another window
+pub const DEFAULT_WINDOW: usize = 5;
+/// Largest rolling window a caller may ask for
+pub const MAX_WINDOW: usize = 50;
+
+/// One released speech and its unrounded score
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SpokenRound {
+    pub match_id: Uuid,
+    pub event_id: Uuid,
+    pub event_title: String,
+    pub series_id: Uuid,
+    pub series_name: String,
+    pub round_number: Option<i32>,
+    pub room_name: Option<String>,
+    /// When the match was scheduled, or the event date if it was not
+    pub spoken_at: DateTime<Utc>,
+    pub score: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelinePoint {
+    #[serde(flatten)]
+    pub round: SpokenRound,
+    /// Mean of this score and up to `window - 1` before it
+    pub rolling_average: Decimal,
+}
+
+/// Points for `rounds`, which must be in the order they were spoken.
+/// Scores are rounded first, so the averages match the scores shown.
+pub fn timeline(
+    rounds: Vec<SpokenRound>,
+    window: usize,
+    rounding: &RoundingPolicy,
+) -> Vec<TimelinePoint> {
+    let window = window.max(1);
+    let scores: Vec<Decimal> = rounds.iter().map(|r| rounding.round(r.score)).collect();
+
+    rounds
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut round)| {
+            round.score = scores[i];
+            let recent = &scores[(i + 1).saturating_sub(window)..=i];
+            TimelinePoint {
+                round,
+                rolling_average: rounding.average(recent).unwrap_or(scores[i]),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spoken(score: Decimal) -> SpokenRound {
+        SpokenRound {
+            match_id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            event_title: "League night".to_string(),
+            series_id: Uuid::new_v4(),
+            series_name: "Round 1".to_string(),
+            round_number: Some(1),
+            room_name: None,
+            spoken_at: Utc::now(),
+            score,
+        }
+    }
+
+    #[test]
+    fn test_rolling_average_covers_the_window() {
+        let rounds = [70, 74, 78, 80]
+            .into_iter()
+            .map(|score| spoken(Decimal::from(score)))
+            .collect();
+        let averages: Vec<Decimal> = timeline(rounds, 3, &RoundingPolicy::default())
+            .into_iter()
+            .map(|p| p.rolling_average)
+            .collect();
+        assert_eq!(
+            averages,
+            [
+                Decimal::from(70),
+                Decimal::from(72),
+                Decimal::from(74),
+                Decimal::new(7733, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scores_are_rounded_before_averaging() {
+        let points = timeline(
+            vec![
+                spoken(Decimal::new(75125, 3)),
+                spoken(Decimal::new(75135, 3)),
+            ],
+            DEFAULT_WINDOW,
+            &RoundingPolicy::default(),
+        );
+        assert_eq!(points[0].round.score, Decimal::new(7512, 2));
+        assert_eq!(points[1].round.score, Decimal::new(7514, 2));
+        assert_eq!(points[1].rolling_average, Decimal::new(7513, 2));
+    }
+}
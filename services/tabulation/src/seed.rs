@@ -0,0 +1,825 @@
+//! Deterministic demo data for development and demo environments.
+//!
+//! Run with `cargo run -p tabulation -- seed`. The same `--seed` always
+//! produces the same people, draws, speaker scores and results, so a bug
+//! seen against demo data can be reproduced on another machine.
+
+use auth::security::Peppers;
+use chrono::{DateTime, Duration, Utc};
+use common::config::{ConfigError, ConfigVar, EnvReader};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rust_decimal::Decimal;
+use std::fmt;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::handlers::recalculate_team_results;
+use crate::margins;
+use crate::models::{
+    Allocation, AllocationRole, Ballot, FourTeamPosition, FourTeamSpeakerRole, Match, MatchSeries,
+    MatchStatus, MatchTeam, SpeakerScore, TeamFormat, TeamRanking, TwoTeamPosition,
+    TwoTeamSpeakerRole,
+};
+
+/// Every seeded username starts with this, which is how `--reset` finds them
+pub const USERNAME_PREFIX: &str = "seed_";
+
+/// `LIKE` pattern matching `USERNAME_PREFIX` (the underscore is escaped)
+const USERNAME_PATTERN: &str = "seed\\_%";
+
+/// Password shared by every seeded account
+pub const DEFAULT_PASSWORD: &str = "tabrela-demo";
+
+/// Enough for one four-team room: eight speakers, a chair and the admin
+pub const MIN_USERS: usize = 10;
+
+/// Registration numbers carry a three digit sequence
+pub const MAX_USERS: usize = 999;
+
+pub const SEED_SCHEMA: &[ConfigVar] = &[
+    ConfigVar::required(
+        "PASSWORD_PEPPER",
+        "Must match the auth service so seeded accounts can log in",
+    ),
+    ConfigVar::default(
+        "PASSWORD_PEPPER_VERSION",
+        "1",
+        "Must match the auth service's current pepper version",
+    ),
+];
+
+const FIRST_NAMES: &[&str] = &[
+    "ayesha", "bilal", "fatima", "hamza", "hira", "imran", "maryam", "omar", "sana", "usman",
+    "zainab", "ali", "amna", "danish", "eman", "faraz", "iqra", "junaid", "kinza", "saad",
+];
+
+const INSTITUTIONS: &[&str] = &[
+    "LUMS", "NUST", "IBA", "FAST", "GIKI", "Habib", "UET", "Aga Khan",
+];
+
+const MOTIONS: &[&str] = &[
+    "This House would ban private schools",
+    "This House believes that developing nations should prioritise industry over the environment",
+    "This House would abolish the right to silence",
+    "This House regrets the rise of influencer culture",
+    "This House would make voting compulsory",
+    "This House supports a universal basic income",
+    "This House would nationalise natural monopolies",
+    "This House believes that social media has done more harm than good",
+];
+
+const FEEDBACK: &[&str] = &[
+    "Clear extension from closing government",
+    "Opening opposition needed more direct clash",
+    "Good use of POIs across the room",
+    "Whips should compare rather than summarise",
+];
+
+#[derive(Debug)]
+pub enum SeedError {
+    InvalidArgument(String),
+    Config(ConfigError),
+    AlreadySeeded,
+    Hashing(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::InvalidArgument(msg) => write!(f, "Invalid argument: {}", msg),
+            SeedError::Config(e) => write!(f, "{}", e),
+            SeedError::AlreadySeeded => {
+                write!(
+                    f,
+                    "Seed data already present; rerun with --reset to replace it"
+                )
+            }
+            SeedError::Hashing(msg) => write!(f, "Failed to hash seed password: {}", msg),
+            SeedError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+impl From<sqlx::Error> for SeedError {
+    fn from(e: sqlx::Error) -> Self {
+        SeedError::Database(e)
+    }
+}
+
+/// Options for the `seed` subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedOptions {
+    /// RNG seed; the same value always produces the same data
+    pub seed: u64,
+    /// Number of accounts to create, including the admin
+    pub users: usize,
+    /// Delete previously seeded data first
+    pub reset: bool,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            users: 48,
+            reset: false,
+        }
+    }
+}
+
+impl SeedOptions {
+    /// Parse the arguments that follow `seed` on the command line
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, SeedError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => options.seed = parse_value(&arg, args.next())?,
+                "--users" => options.users = parse_value(&arg, args.next())?,
+                "--reset" => options.reset = true,
+                other => {
+                    return Err(SeedError::InvalidArgument(format!(
+                        "unknown option '{}'",
+                        other
+                    )))
+                }
+            }
+        }
+
+        if !(MIN_USERS..=MAX_USERS).contains(&options.users) {
+            return Err(SeedError::InvalidArgument(format!(
+                "--users must be between {} and {}",
+                MIN_USERS, MAX_USERS
+            )));
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, SeedError> {
+    value
+        .as_deref()
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| SeedError::InvalidArgument(format!("{} expects a number", flag)))
+}
+
+/// Read the seed-only settings from the environment
+pub fn password_pepper() -> Result<Peppers, SeedError> {
+    let mut env = EnvReader::new(&[SEED_SCHEMA]);
+    let pepper = env.string("PASSWORD_PEPPER");
+    let version = env.parse("PASSWORD_PEPPER_VERSION");
+    env.finish().map_err(SeedError::Config)?;
+    Ok(Peppers::new(version, pepper))
+}
+
+// ============================================================================
+// Generators
+// ============================================================================
+
+/// A generated account. The first user returned by `generate_users` is the admin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeedUser {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub reg_number: String,
+    pub year_joined: i32,
+    pub phone_number: String,
+    /// Typical speaker score, in half points, that generated ballots scatter around
+    pub skill: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SeedEvent {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub event_type: &'static str,
+    pub event_date: DateTime<Utc>,
+    pub location: Option<String>,
+    pub created_by: Uuid,
+}
+
+/// One room of a generated draw
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomPlan {
+    /// Speaker indices for each team, in position order
+    pub teams: Vec<Vec<usize>>,
+    /// Adjudicator indices, chair first
+    pub panel: Vec<usize>,
+}
+
+fn random_uuid(rng: &mut ChaCha8Rng) -> Uuid {
+    uuid::Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+pub fn generate_users(rng: &mut ChaCha8Rng, count: usize) -> Vec<SeedUser> {
+    (0..count)
+        .map(|i| {
+            let username = if i == 0 {
+                format!("{}admin", USERNAME_PREFIX)
+            } else {
+                let name = FIRST_NAMES.choose(rng).copied().unwrap_or("debater");
+                format!("{}{}_{:03}", USERNAME_PREFIX, name, i)
+            };
+            let year_joined = rng.gen_range(2019..=2025);
+
+            SeedUser {
+                id: random_uuid(rng),
+                email: format!("{}@example.com", username),
+                reg_number: format!("20{:02}{:03}", year_joined % 100, i),
+                year_joined,
+                phone_number: format!("+92300{:07}", i),
+                skill: rng.gen_range(140..=160),
+                username,
+            }
+        })
+        .collect()
+}
+
+fn team_shape(format: TeamFormat) -> (usize, usize) {
+    // (teams per room, speakers per team)
+    match format {
+        TeamFormat::TwoTeam => (2, 3),
+        TeamFormat::FourTeam => (4, 2),
+    }
+}
+
+/// Shuffle `pool` into rooms. Every room gets full teams and a chair; anyone
+/// left over joins a panel as a wing.
+pub fn draw_rooms(rng: &mut ChaCha8Rng, pool: &[usize], format: TeamFormat) -> Vec<RoomPlan> {
+    let (team_count, team_size) = team_shape(format);
+    let speakers_per_room = team_count * team_size;
+
+    let mut pool = pool.to_vec();
+    pool.shuffle(rng);
+
+    // Aim for two adjudicators per room, but never drop below one
+    let room_count =
+        (pool.len() / (speakers_per_room + 2)).max(usize::from(pool.len() > speakers_per_room));
+    let mut remaining = pool.into_iter();
+
+    let mut rooms: Vec<RoomPlan> = (0..room_count)
+        .map(|_| RoomPlan {
+            teams: (0..team_count)
+                .map(|_| remaining.by_ref().take(team_size).collect())
+                .collect(),
+            panel: Vec::new(),
+        })
+        .collect();
+
+    for (i, adjudicator) in remaining.enumerate() {
+        if let Some(room) = rooms.get_mut(i % room_count.max(1)) {
+            room.panel.push(adjudicator);
+        }
+    }
+
+    rooms
+}
+
+/// One speaker's score on one ballot, in half points
+fn score_halves(rng: &mut ChaCha8Rng, skill: u32) -> u32 {
+    (skill as i64 + rng.gen_range(-6..=6)).clamp(100, 200) as u32
+}
+
+pub(crate) fn speaker_role(
+    team: &MatchTeam,
+    slot: usize,
+) -> (Option<TwoTeamSpeakerRole>, Option<FourTeamSpeakerRole>) {
+    use FourTeamSpeakerRole as Four;
+    use TwoTeamSpeakerRole as Two;
+
+    let two = team
+        .two_team_position
+        .map(|position| match (position, slot) {
+            (TwoTeamPosition::Government, 0) => Two::PrimeMinister,
+            (TwoTeamPosition::Government, 1) => Two::DeputyPrimeMinister,
+            (TwoTeamPosition::Government, _) => Two::GovernmentWhip,
+            (TwoTeamPosition::Opposition, 0) => Two::LeaderOfOpposition,
+            (TwoTeamPosition::Opposition, 1) => Two::DeputyLeaderOfOpposition,
+            (TwoTeamPosition::Opposition, _) => Two::OppositionWhip,
+        });
+    let four = team
+        .four_team_position
+        .map(|position| match (position, slot) {
+            (FourTeamPosition::OpeningGovernment, 0) => Four::PrimeMinister,
+            (FourTeamPosition::OpeningGovernment, _) => Four::DeputyPrimeMinister,
+            (FourTeamPosition::OpeningOpposition, 0) => Four::LeaderOfOpposition,
+            (FourTeamPosition::OpeningOpposition, _) => Four::DeputyLeaderOfOpposition,
+            (FourTeamPosition::ClosingGovernment, 0) => Four::MemberOfGovernment,
+            (FourTeamPosition::ClosingGovernment, _) => Four::GovernmentWhip,
+            (FourTeamPosition::ClosingOpposition, 0) => Four::MemberOfOpposition,
+            (FourTeamPosition::ClosingOpposition, _) => Four::OppositionWhip,
+        });
+
+    (two, four)
+}
+
+// ============================================================================
+// Writing to the database
+// ============================================================================
+
+/// Row counts written by a seed run
+#[derive(Debug, Default, PartialEq)]
+pub struct SeedSummary {
+    pub users: usize,
+    pub events: usize,
+    pub series: usize,
+    pub matches: usize,
+    pub allocations: usize,
+    pub ballots: usize,
+}
+
+impl fmt::Display for SeedSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} users, {} events, {} series, {} matches, {} allocations, {} ballots",
+            self.users, self.events, self.series, self.matches, self.allocations, self.ballots
+        )
+    }
+}
+
+/// How far a generated series has progressed
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    /// Drawn, ballots outstanding
+    Published,
+    /// Ballots in, results hidden
+    Completed,
+    /// Ballots in, scores and rankings visible
+    Released,
+}
+
+struct SeriesSpec {
+    event_id: Uuid,
+    name: String,
+    round_number: Option<i32>,
+    format: TeamFormat,
+    scheduled_time: DateTime<Utc>,
+    stage: Stage,
+}
+
+struct Seeder<'a> {
+    db: &'a Database,
+    rng: ChaCha8Rng,
+    users: Vec<SeedUser>,
+    summary: SeedSummary,
+}
+
+/// Populate the database with demo users, events, series, matches,
+/// allocations and ballots
+pub async fn run(
+    db: &Database,
+    options: &SeedOptions,
+    peppers: &Peppers,
+) -> Result<SeedSummary, SeedError> {
+    if options.reset {
+        db.delete_users_like(USERNAME_PATTERN).await?;
+    } else if db.count_users_like(USERNAME_PATTERN).await? > 0 {
+        return Err(SeedError::AlreadySeeded);
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(options.seed);
+    let users = generate_users(&mut rng, options.users);
+
+    // One hash for everyone: Argon2 is deliberately slow
+    let (password_hash, salt) = auth::security::hash_password(DEFAULT_PASSWORD, peppers.current())
+        .map_err(|e| SeedError::Hashing(e.to_string()))?;
+    for user in &users {
+        db.insert_seed_user(user, &password_hash, &salt, peppers.current_version())
+            .await?;
+    }
+    db.grant_admin(users[0].id).await?;
+
+    let mut seeder = Seeder {
+        db,
+        rng,
+        summary: SeedSummary {
+            users: users.len(),
+            ..Default::default()
+        },
+        users,
+    };
+    seeder.seed_events().await?;
+
+    Ok(seeder.summary)
+}
+
+impl Seeder<'_> {
+    fn admin(&self) -> Uuid {
+        self.users[0].id
+    }
+
+    async fn create_event(
+        &mut self,
+        title: &str,
+        event_type: &'static str,
+        event_date: DateTime<Utc>,
+        check_in: bool,
+    ) -> Result<SeedEvent, SeedError> {
+        let event = SeedEvent {
+            id: random_uuid(&mut self.rng),
+            title: title.to_string(),
+            description: Some(format!("Generated demo data: {}", title)),
+            event_type,
+            event_date,
+            location: INSTITUTIONS
+                .choose(&mut self.rng)
+                .map(|i| format!("{} Campus", i)),
+            created_by: self.admin(),
+        };
+        self.db.insert_seed_event(&event).await?;
+        self.summary.events += 1;
+
+        let checked_in_by = check_in.then(|| self.admin());
+        for user in &self.users[1..] {
+            self.db
+                .insert_seed_attendance(event.id, user.id, checked_in_by)
+                .await?;
+        }
+
+        Ok(event)
+    }
+
+    async fn seed_events(&mut self) -> Result<(), SeedError> {
+        let now = Utc::now();
+
+        // A past tournament: two finished rounds and one awaiting ballots
+        let tournament = self
+            .create_event(
+                "Tabrela Demo Open",
+                "tournament",
+                now - Duration::days(14),
+                true,
+            )
+            .await?;
+        for (round, stage) in [
+            (1, Stage::Released),
+            (2, Stage::Completed),
+            (3, Stage::Published),
+        ] {
+            self.seed_series(SeriesSpec {
+                event_id: tournament.id,
+                name: format!("Round {}", round),
+                round_number: Some(round),
+                format: TeamFormat::FourTeam,
+                scheduled_time: tournament.event_date + Duration::hours(2 * round as i64),
+                stage,
+            })
+            .await?;
+        }
+
+        // A past weekly practice with friendly two-team matches
+        let practice = self
+            .create_event(
+                "Weekly Practice",
+                "weekly_match",
+                now - Duration::days(7),
+                true,
+            )
+            .await?;
+        self.seed_series(SeriesSpec {
+            event_id: practice.id,
+            name: "Friendly Matches".to_string(),
+            round_number: None,
+            format: TeamFormat::TwoTeam,
+            scheduled_time: practice.event_date,
+            stage: Stage::Released,
+        })
+        .await?;
+
+        // An upcoming meeting with availability but no check-ins yet
+        self.create_event("General Meeting", "meeting", now + Duration::days(7), false)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn seed_series(&mut self, spec: SeriesSpec) -> Result<(), SeedError> {
+        let now = Utc::now();
+        let series = MatchSeries {
+            id: random_uuid(&mut self.rng),
+            event_id: spec.event_id,
+            name: spec.name.clone(),
+            description: None,
+            round_number: spec.round_number,
+            team_format: spec.format,
+            allow_reply_speeches: false,
+            is_break_round: false,
+            is_silent: false,
+            is_training: false,
+            double_entry: false,
+            created_by: self.admin(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.db.create_series(&series).await?;
+        self.summary.series += 1;
+
+        let pool: Vec<usize> = (1..self.users.len()).collect();
+        let rooms = draw_rooms(&mut self.rng, &pool, spec.format);
+        for (index, room) in rooms.iter().enumerate() {
+            self.seed_match(&series, &spec, index, room).await?;
+        }
+
+        Ok(())
+    }
+
+    fn allocation(&self, match_id: Uuid, user: usize, role: AllocationRole) -> Allocation {
+        let now = Utc::now();
+        Allocation {
+            id: Uuid::new_v4(),
+            match_id,
+            user_id: Some(self.users[user].id),
+            guest_name: None,
+            role,
+            team_id: None,
+            two_team_speaker_role: None,
+            four_team_speaker_role: None,
+            is_chair: None,
+            allocated_at: now,
+            allocated_by: self.admin(),
+            was_checked_in: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    async fn seed_match(
+        &mut self,
+        series: &MatchSeries,
+        spec: &SeriesSpec,
+        index: usize,
+        room: &RoomPlan,
+    ) -> Result<(), SeedError> {
+        let now = Utc::now();
+        let released = spec.stage == Stage::Released;
+        let match_record = Match {
+            id: random_uuid(&mut self.rng),
+            series_id: series.id,
+            room_name: Some(format!("Room {}", 101 + index)),
+            venue_id: None,
+            motion: MOTIONS.choose(&mut self.rng).map(|m| m.to_string()),
+            info_slide: None,
+            status: if spec.stage == Stage::Published {
+                MatchStatus::Published
+            } else {
+                MatchStatus::Completed
+            },
+            scheduled_time: Some(spec.scheduled_time),
+            scores_released: released,
+            rankings_released: released,
+            created_at: now,
+            updated_at: now,
+        };
+        self.db.create_match(&match_record).await?;
+        self.summary.matches += 1;
+
+        // Speakers: (allocation, team, user index)
+        let teams = self
+            .db
+            .create_teams_for_match(match_record.id, series.team_format)
+            .await?;
+        let mut speakers = Vec::new();
+        for (team, members) in teams.iter().zip(&room.teams) {
+            let institution = INSTITUTIONS
+                .choose(&mut self.rng)
+                .copied()
+                .unwrap_or("Tabrela");
+            let team_name = format!("{} {}", institution, self.rng.gen_range('A'..='D'));
+            self.db
+                .update_team(team.id, Some(&team_name), Some(institution))
+                .await?;
+
+            for (slot, &member) in members.iter().enumerate() {
+                let (two, four) = speaker_role(team, slot);
+                let allocation = Allocation {
+                    team_id: Some(team.id),
+                    two_team_speaker_role: two,
+                    four_team_speaker_role: four,
+                    ..self.allocation(match_record.id, member, AllocationRole::Speaker)
+                };
+                self.db.create_allocation(&allocation).await?;
+                speakers.push((allocation.id, team.id, member));
+            }
+        }
+
+        // Panel: a voting chair, one voting wing, then non-voting trainees
+        let mut ballots = Vec::new();
+        for (seat, &member) in room.panel.iter().enumerate() {
+            let role = if seat < 2 {
+                AllocationRole::VotingAdjudicator
+            } else {
+                AllocationRole::NonVotingAdjudicator
+            };
+            let allocation = Allocation {
+                is_chair: Some(seat == 0),
+                ..self.allocation(match_record.id, member, role)
+            };
+            self.db.create_allocation(&allocation).await?;
+
+            let ballot = Ballot {
+                id: Uuid::new_v4(),
+                match_id: match_record.id,
+                adjudicator_id: self.users[member].id,
+                is_voting: role == AllocationRole::VotingAdjudicator,
+                is_submitted: false,
+                submitted_at: None,
+                notes: None,
+                low_point_win: false,
+                created_at: now,
+                updated_at: now,
+            };
+            self.db.create_ballot(&ballot).await?;
+            ballots.push(ballot);
+        }
+        self.summary.allocations += speakers.len() + room.panel.len();
+        self.summary.ballots += ballots.len();
+
+        if spec.stage == Stage::Published {
+            return Ok(());
+        }
+
+        for ballot in &ballots {
+            if ballot.is_voting {
+                self.fill_ballot(ballot, &teams, &speakers).await?;
+                self.db.submit_ballot(ballot.id, None, false).await?;
+            } else {
+                let notes = FEEDBACK.choose(&mut self.rng).copied();
+                self.db.submit_ballot(ballot.id, notes, false).await?;
+            }
+        }
+        recalculate_team_results(self.db, match_record.id).await;
+        self.db.refresh_match_performance(match_record.id).await?;
+
+        Ok(())
+    }
+
+    /// Score every speaker and rank teams by their speakers' totals
+    async fn fill_ballot(
+        &mut self,
+        ballot: &Ballot,
+        teams: &[MatchTeam],
+        speakers: &[(Uuid, Uuid, usize)],
+    ) -> Result<(), SeedError> {
+        let now = Utc::now();
+        let mut totals: Vec<(Uuid, u32)> = teams.iter().map(|team| (team.id, 0)).collect();
+
+        for &(allocation_id, team_id, member) in speakers {
+            let halves = score_halves(&mut self.rng, self.users[member].skill);
+            let score = SpeakerScore {
+                id: Uuid::new_v4(),
+                ballot_id: ballot.id,
+                allocation_id,
+                score: Decimal::new(halves as i64 * 5, 1),
+                feedback: None,
+                created_at: now,
+                updated_at: now,
+            };
+            self.db.create_speaker_score(&score).await?;
+
+            if let Some(total) = totals.iter_mut().find(|(id, _)| *id == team_id) {
+                total.1 += halves;
+            }
+        }
+
+        // Stable sort keeps the earlier position ahead on equal points
+        totals.sort_by_key(|(_, points)| std::cmp::Reverse(*points));
+        let winners = totals.len() / 2;
+        let rankings: Vec<(Uuid, i32)> = totals
+            .iter()
+            .enumerate()
+            .map(|(position, (team_id, _))| (*team_id, position as i32 + 1))
+            .collect();
+        let speaks: Vec<(Uuid, Decimal)> = totals
+            .iter()
+            .map(|&(team_id, halves)| (team_id, Decimal::new(halves as i64 * 5, 1)))
+            .collect();
+        let team_margins = margins::team_margins(&rankings, &speaks, &self.db.rounding());
+
+        for (position, margin) in team_margins.iter().enumerate() {
+            let ranking = TeamRanking {
+                id: Uuid::new_v4(),
+                ballot_id: ballot.id,
+                team_id: margin.team_id,
+                rank: margin.rank,
+                is_winner: Some(position < winners),
+                total_speaks: Some(margin.total_speaks),
+                margin: Some(margin.margin),
+                created_at: now,
+                updated_at: now,
+            };
+            self.db.create_team_ranking(&ranking).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_options_from_args() {
+        assert_eq!(
+            SeedOptions::from_args(args(&[])).unwrap(),
+            SeedOptions::default()
+        );
+
+        let options =
+            SeedOptions::from_args(args(&["--seed", "7", "--users", "20", "--reset"])).unwrap();
+        assert_eq!(
+            options,
+            SeedOptions {
+                seed: 7,
+                users: 20,
+                reset: true
+            }
+        );
+
+        assert!(SeedOptions::from_args(args(&["--seed"])).is_err());
+        assert!(SeedOptions::from_args(args(&["--users", "5"])).is_err());
+        assert!(SeedOptions::from_args(args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn test_users_are_deterministic_and_valid() {
+        let first = generate_users(&mut ChaCha8Rng::seed_from_u64(1), 50);
+        let second = generate_users(&mut ChaCha8Rng::seed_from_u64(1), 50);
+        let other = generate_users(&mut ChaCha8Rng::seed_from_u64(2), 50);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+
+        assert_eq!(first[0].username, "seed_admin");
+        for user in &first {
+            assert!(user.username.starts_with(USERNAME_PREFIX));
+            assert!(user.username.len() <= 50);
+            assert_eq!(user.reg_number.len(), 7);
+            assert!(user.reg_number.starts_with("20"));
+            assert!(user.reg_number.chars().all(|c| c.is_ascii_digit()));
+            assert!((2000..=2099).contains(&user.year_joined));
+            assert!(user.phone_number.starts_with('+'));
+            assert_eq!(user.phone_number.len(), 13);
+        }
+
+        let unique = |f: fn(&SeedUser) -> &String| {
+            first.iter().map(f).collect::<HashSet<_>>().len() == first.len()
+        };
+        assert!(unique(|u| &u.username));
+        assert!(unique(|u| &u.email));
+        assert!(unique(|u| &u.reg_number));
+        assert!(unique(|u| &u.phone_number));
+    }
+
+    #[test]
+    fn test_draw_rooms() {
+        let pool: Vec<usize> = (1..48).collect();
+        let rooms = draw_rooms(
+            &mut ChaCha8Rng::seed_from_u64(3),
+            &pool,
+            TeamFormat::FourTeam,
+        );
+        assert_eq!(rooms.len(), 4);
+
+        let mut seen = HashSet::new();
+        for room in &rooms {
+            assert_eq!(room.teams.len(), 4);
+            assert!(room.teams.iter().all(|team| team.len() == 2));
+            assert!(!room.panel.is_empty());
+            for person in room.teams.iter().flatten().chain(&room.panel) {
+                assert!(seen.insert(*person), "{} allocated twice", person);
+            }
+        }
+        assert_eq!(seen.len(), pool.len());
+
+        let rooms = draw_rooms(
+            &mut ChaCha8Rng::seed_from_u64(3),
+            &pool[..9],
+            TeamFormat::FourTeam,
+        );
+        assert_eq!(rooms.len(), 1);
+        assert_eq!(rooms[0].panel.len(), 1);
+
+        let rooms = draw_rooms(
+            &mut ChaCha8Rng::seed_from_u64(3),
+            &pool,
+            TeamFormat::TwoTeam,
+        );
+        assert!(rooms
+            .iter()
+            .all(|room| room.teams.iter().all(|t| t.len() == 3)));
+    }
+}
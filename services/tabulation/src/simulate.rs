@@ -0,0 +1,156 @@
+//! Projected standings from hypothetical results.
+//!
+//! A scenario gives, for some of the preliminary rooms still without
+//! results, the order the teams finish in. It is written as rooms separated
+//! by `;`, each a comma-separated list of the room's team ids best first,
+//! so `a,b;c,d,e,f` has `a` beating `b` in one room and `c` winning another.
+//! Hypothetical rooms only carry placings: they add team points and wins but
+//! no speaks or margins.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::models::TeamFormat;
+use crate::tab::TeamResult;
+
+/// A team in a preliminary room that has no result yet
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OutstandingTeam {
+    pub match_team_id: Uuid,
+    pub match_id: Uuid,
+    pub series_name: String,
+    pub room_name: Option<String>,
+    pub registered_team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub institution: Option<String>,
+    pub team_format: TeamFormat,
+}
+
+/// Each room's team ids, best first
+pub fn parse_scenario(scenario: &str) -> Result<Vec<Vec<Uuid>>, String> {
+    scenario
+        .split(';')
+        .map(str::trim)
+        .filter(|room| !room.is_empty())
+        .map(|room| {
+            room.split(',')
+                .map(|id| {
+                    id.trim()
+                        .parse::<Uuid>()
+                        .map_err(|_| format!("'{}' is not a team id", id.trim()))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The placings a scenario gives, as results the tab can count. Every room
+/// must list all of its teams exactly once.
+pub fn scenario_results(
+    outstanding: &[OutstandingTeam],
+    scenario: &[Vec<Uuid>],
+) -> Result<Vec<TeamResult>, String> {
+    let by_id: HashMap<Uuid, &OutstandingTeam> =
+        outstanding.iter().map(|t| (t.match_team_id, t)).collect();
+    let mut decided: HashSet<Uuid> = HashSet::new();
+    let mut results = Vec::new();
+
+    for room in scenario {
+        let Some(first) = room.first() else {
+            continue;
+        };
+        let match_id = by_id
+            .get(first)
+            .ok_or_else(|| format!("Team {} is not in a room awaiting results", first))?
+            .match_id;
+        if !decided.insert(match_id) {
+            return Err(format!("Room {} is given more than once", match_id));
+        }
+
+        let teams: Vec<&OutstandingTeam> = outstanding
+            .iter()
+            .filter(|t| t.match_id == match_id)
+            .collect();
+        let listed: HashSet<&Uuid> = room.iter().collect();
+        let complete = listed.len() == room.len()
+            && room.len() == teams.len()
+            && teams.iter().all(|t| listed.contains(&t.match_team_id));
+        if !complete {
+            return Err(format!(
+                "Room {} must list each of its {} teams once",
+                match_id,
+                teams.len()
+            ));
+        }
+
+        for (rank, team_id) in room.iter().enumerate() {
+            let team = by_id[team_id];
+            results.push(TeamResult {
+                match_team_id: team.match_team_id,
+                match_id,
+                registered_team_id: team.registered_team_id,
+                team_name: team.team_name.clone(),
+                institution: team.institution.clone(),
+                team_format: team.team_format,
+                final_rank: rank as i32 + 1,
+                speaker_points: None,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(n: u128, room: u128) -> OutstandingTeam {
+        OutstandingTeam {
+            match_team_id: Uuid::from_u128(n),
+            match_id: Uuid::from_u128(100 + room),
+            series_name: "Round 4".to_string(),
+            room_name: None,
+            registered_team_id: None,
+            team_name: Some(format!("Team {}", n)),
+            institution: None,
+            team_format: TeamFormat::TwoTeam,
+        }
+    }
+
+    fn ids(ns: &[u128]) -> Vec<Uuid> {
+        ns.iter().map(|&n| Uuid::from_u128(n)).collect()
+    }
+
+    #[test]
+    fn test_parse_rooms_best_first() {
+        let a = Uuid::from_u128(1);
+        let b = Uuid::from_u128(2);
+        let scenario = parse_scenario(&format!(" {a}, {b} ;; ")).unwrap();
+        assert_eq!(scenario, [vec![a, b]]);
+        assert!(parse_scenario("not-a-team").is_err());
+    }
+
+    #[test]
+    fn test_placings_follow_the_listed_order() {
+        let outstanding = [team(1, 1), team(2, 1), team(3, 2), team(4, 2)];
+        let results = scenario_results(&outstanding, &[ids(&[2, 1])]).unwrap();
+        let placings: Vec<(Uuid, i32)> = results
+            .iter()
+            .map(|r| (r.match_team_id, r.final_rank))
+            .collect();
+        assert_eq!(placings, [(Uuid::from_u128(2), 1), (Uuid::from_u128(1), 2)]);
+        assert!(results.iter().all(|r| r.speaker_points.is_none()));
+    }
+
+    #[test]
+    fn test_rooms_must_be_complete_and_outstanding() {
+        let outstanding = [team(1, 1), team(2, 1), team(3, 2), team(4, 2)];
+        assert!(scenario_results(&outstanding, &[ids(&[1])]).is_err());
+        assert!(scenario_results(&outstanding, &[ids(&[1, 3])]).is_err());
+        assert!(scenario_results(&outstanding, &[ids(&[1, 1])]).is_err());
+        assert!(scenario_results(&outstanding, &[ids(&[9, 1])]).is_err());
+        assert!(scenario_results(&outstanding, &[ids(&[1, 2]), ids(&[2, 1])]).is_err());
+    }
+}
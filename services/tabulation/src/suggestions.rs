@@ -0,0 +1,184 @@
+//! Ranking candidate judges for a room.
+//!
+//! Every checked-in user not yet allocated in the round is a candidate. They
+//! are scored on how closely their past ballots agreed with their panels
+//! (the calibration record from `judge_stats`), how many rounds they have
+//! judged, their accreditation, and how many rooms they have already judged
+//! that day. Candidates from an institution represented in the room clash
+//! with it and are listed after every clean candidate, whatever their score.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::models::AccreditationLevel;
+
+/// Rounds judged beyond this earn nothing more
+pub const EXPERIENCE_CAP: i64 = 20;
+/// Points for a judge whose ballots match their panels exactly
+pub const AGREEMENT_POINTS: Decimal = Decimal::TEN;
+/// Points lost per point of speech score a judge is usually off by
+pub const DEVIATION_PENALTY: Decimal = Decimal::from_parts(5, 0, 0, false, 0);
+/// Points lost per room already judged that day
+pub const FATIGUE_PENALTY: Decimal = Decimal::from_parts(5, 0, 0, false, 0);
+
+/// Points for a judge's accreditation. Trainees are held back so that,
+/// other things equal, the voting seats go to accredited judges; judges
+/// never graded earn nothing either way.
+pub fn accreditation_points(level: Option<AccreditationLevel>) -> Decimal {
+    match level {
+        Some(AccreditationLevel::ChairQualified) => Decimal::TEN,
+        Some(AccreditationLevel::Accredited) => Decimal::from(5),
+        Some(AccreditationLevel::Trainee) => Decimal::from(-5),
+        None => Decimal::ZERO,
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Candidate {
+    pub user_id: Uuid,
+    pub username: String,
+    pub institution_id: Option<Uuid>,
+    pub institution_name: Option<String>,
+    /// Submitted ballots, in any event
+    pub rounds_judged: i64,
+    /// Adjudicator allocations in the event on the room's day
+    pub rooms_today: i64,
+    /// Latest level in the accreditation ledger
+    pub accreditation: Option<AccreditationLevel>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub rank: usize,
+    pub user_id: Uuid,
+    pub username: String,
+    pub institution: Option<String>,
+    pub score: Decimal,
+    pub rounds_judged: i64,
+    pub rooms_today: i64,
+    pub accreditation: Option<AccreditationLevel>,
+    /// How far their scores usually sit from their panels', if ever paneled
+    pub mean_abs_deviation: Option<Decimal>,
+    /// Institutions in the room the candidate clashes with
+    pub clashes: Vec<String>,
+}
+
+fn score(candidate: &Candidate, deviation: Option<Decimal>) -> Decimal {
+    let experience = Decimal::from(candidate.rounds_judged.clamp(0, EXPERIENCE_CAP));
+    let agreement = deviation
+        .map(|d| AGREEMENT_POINTS - DEVIATION_PENALTY * d)
+        .unwrap_or_default();
+    let fatigue = FATIGUE_PENALTY * Decimal::from(candidate.rooms_today.max(0));
+    (experience + agreement + accreditation_points(candidate.accreditation) - fatigue).round_dp(2)
+}
+
+/// Candidates best first. `deviations` holds each judge's mean absolute
+/// deviation from their panels; `room_institutions` every institution in
+/// the room by id.
+pub fn suggest(
+    candidates: &[Candidate],
+    deviations: &HashMap<Uuid, Decimal>,
+    room_institutions: &HashMap<Uuid, String>,
+) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = candidates
+        .iter()
+        .map(|candidate| {
+            let deviation = deviations.get(&candidate.user_id).copied();
+            Suggestion {
+                rank: 0,
+                user_id: candidate.user_id,
+                username: candidate.username.clone(),
+                institution: candidate.institution_name.clone(),
+                score: score(candidate, deviation),
+                rounds_judged: candidate.rounds_judged,
+                rooms_today: candidate.rooms_today,
+                accreditation: candidate.accreditation,
+                mean_abs_deviation: deviation,
+                clashes: candidate
+                    .institution_id
+                    .and_then(|id| room_institutions.get(&id))
+                    .cloned()
+                    .into_iter()
+                    .collect(),
+            }
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        a.clashes
+            .is_empty()
+            .cmp(&b.clashes.is_empty())
+            .reverse()
+            .then_with(|| b.score.cmp(&a.score))
+            .then_with(|| a.username.cmp(&b.username))
+    });
+    for (i, suggestion) in suggestions.iter_mut().enumerate() {
+        suggestion.rank = i + 1;
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(n: u128, rounds_judged: i64, rooms_today: i64) -> Candidate {
+        Candidate {
+            user_id: Uuid::from_u128(n),
+            username: format!("judge{}", n),
+            institution_id: None,
+            institution_name: None,
+            rounds_judged,
+            rooms_today,
+            accreditation: None,
+        }
+    }
+
+    #[test]
+    fn test_experience_agreement_and_fatigue() {
+        let candidates = [candidate(1, 4, 0), candidate(2, 40, 2), candidate(3, 4, 0)];
+        let deviations = HashMap::from([(Uuid::from_u128(3), Decimal::ONE)]);
+        let ranked = suggest(&candidates, &deviations, &HashMap::new());
+
+        let order: Vec<&str> = ranked.iter().map(|s| s.username.as_str()).collect();
+        assert_eq!(order, ["judge2", "judge3", "judge1"]);
+        // Experience capped at 20, less two rooms already judged today
+        assert_eq!(ranked[0].score, Decimal::from(10));
+        // 4 rounds + (10 - 5 * 1)
+        assert_eq!(ranked[1].score, Decimal::from(9));
+        assert_eq!((ranked[2].rank, ranked[2].score), (3, Decimal::from(4)));
+    }
+
+    #[test]
+    fn test_accreditation_outweighs_a_few_rounds() {
+        let mut chair = candidate(1, 2, 0);
+        chair.accreditation = Some(AccreditationLevel::ChairQualified);
+        let mut trainee = candidate(2, 8, 0);
+        trainee.accreditation = Some(AccreditationLevel::Trainee);
+        let ranked = suggest(
+            &[trainee, candidate(3, 6, 0), chair],
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let order: Vec<&str> = ranked.iter().map(|s| s.username.as_str()).collect();
+        assert_eq!(order, ["judge1", "judge3", "judge2"]);
+        assert_eq!(ranked[0].score, Decimal::from(12));
+        assert_eq!(ranked[2].score, Decimal::from(3));
+    }
+
+    #[test]
+    fn test_clashed_candidates_come_last() {
+        let institution = Uuid::from_u128(99);
+        let mut clashed = candidate(1, 20, 0);
+        clashed.institution_id = Some(institution);
+        let candidates = [clashed, candidate(2, 1, 0)];
+        let room = HashMap::from([(institution, "LUMS".to_string())]);
+
+        let ranked = suggest(&candidates, &HashMap::new(), &room);
+        assert_eq!(ranked[0].username, "judge2");
+        assert_eq!(ranked[1].clashes, ["LUMS"]);
+    }
+}
@@ -0,0 +1,763 @@
+//! Team and speaker tabs and breaks, overall and per speaker category.
+//!
+//! Only results from preliminary rounds count: break rounds and friendlies
+//! (series without a round number) are left out. Teams only exist per room
+//! unless the event uses team entry, so rooms are matched up across rounds
+//! by registered team, falling back to the team name.
+//!
+//! Teams are ordered by the event's chain of tie-break rules, points then
+//! speaks unless configured otherwise. A category's tab lists the speakers
+//! eligible for it, and the teams whose every speaker is, in the same order. Breaks are filled in category order: a team breaking in
+//! the open break does not take a novice or ESL place.
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::{fmt, str::FromStr};
+use uuid::Uuid;
+
+use crate::eligibility::EligibilityFilter;
+use crate::models::TeamFormat;
+use crate::rounding::RoundingPolicy;
+
+/// One team's placing in one room
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TeamResult {
+    pub match_team_id: Uuid,
+    pub match_id: Uuid,
+    pub registered_team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub institution: Option<String>,
+    pub team_format: TeamFormat,
+    pub final_rank: i32,
+    pub speaker_points: Option<Decimal>,
+}
+
+/// One speech, scored as the average of the submitted voting ballots
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SpeechResult {
+    pub match_team_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub is_novice: bool,
+    pub is_esl: bool,
+    pub is_reply: bool,
+    pub score: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamTabEntry {
+    pub rank: usize,
+    pub registered_team_id: Option<Uuid>,
+    pub team_name: String,
+    pub institution: Option<String>,
+    pub points: i32,
+    pub wins: usize,
+    /// Left out while the event withholds speaks
+    pub speaker_points: Option<Decimal>,
+    /// Speaks above the average of the other teams in the room
+    pub average_margin: Option<Decimal>,
+    pub rounds: usize,
+    pub speakers: Vec<String>,
+    #[serde(skip)]
+    key: String,
+    #[serde(skip)]
+    eligibility: Vec<(bool, bool)>,
+    /// Teams with the same group were level on every tie-break rule
+    #[serde(skip)]
+    group: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerTabEntry {
+    pub rank: usize,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub team_name: Option<String>,
+    pub total: Decimal,
+    pub average: Decimal,
+    pub speeches: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakEntry {
+    pub break_rank: usize,
+    #[serde(flatten)]
+    pub team: TeamTabEntry,
+}
+
+/// One rule for ordering teams on the tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieBreak {
+    /// Team points: 3/2/1/0 in four-team rooms, 1 per win in two-team rooms
+    Points,
+    /// Rooms won outright
+    Wins,
+    /// Total team speaker points
+    Speaks,
+    /// Results against the other teams still level, in rooms they shared
+    HeadToHead,
+    AverageMargin,
+    /// A random order fixed by the event's stored seed
+    Draw,
+}
+
+impl TieBreak {
+    pub const ALL: &'static [TieBreak] = &[
+        TieBreak::Points,
+        TieBreak::Wins,
+        TieBreak::Speaks,
+        TieBreak::HeadToHead,
+        TieBreak::AverageMargin,
+        TieBreak::Draw,
+    ];
+
+    /// Used by events that never configured their own rules
+    pub const DEFAULT: &'static [TieBreak] = &[TieBreak::Points, TieBreak::Speaks];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TieBreak::Points => "points",
+            TieBreak::Wins => "wins",
+            TieBreak::Speaks => "speaks",
+            TieBreak::HeadToHead => "head_to_head",
+            TieBreak::AverageMargin => "average_margin",
+            TieBreak::Draw => "draw",
+        }
+    }
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TieBreak::ALL
+            .iter()
+            .copied()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| format!("Unknown tie-break rule: {}", s))
+    }
+}
+
+/// Team points for a placing: 3/2/1/0 in four-team rooms, 1 for a win in
+/// two-team rooms
+pub fn team_points(format: TeamFormat, final_rank: i32) -> i32 {
+    match format {
+        TeamFormat::FourTeam => (4 - final_rank).max(0),
+        TeamFormat::TwoTeam => i32::from(final_rank == 1),
+    }
+}
+
+fn team_key(result: &TeamResult) -> String {
+    match (result.registered_team_id, &result.team_name) {
+        (Some(id), _) => id.to_string(),
+        (None, Some(name)) if !name.trim().is_empty() => {
+            format!("name:{}", name.trim().to_lowercase())
+        }
+        _ => result.match_team_id.to_string(),
+    }
+}
+
+fn speaker_key(speech: &SpeechResult) -> String {
+    match speech.user_id {
+        Some(id) => id.to_string(),
+        None => format!("guest:{}", speech.name.trim().to_lowercase()),
+    }
+}
+
+/// Every team on the open tab, best first. Teams are ordered by the first
+/// rule, teams level on it by the next, and so on; teams still level after
+/// the last rule share a rank.
+pub fn team_standings(
+    results: &[TeamResult],
+    speeches: &[SpeechResult],
+    rules: &[TieBreak],
+    seed: i64,
+    rounding: &RoundingPolicy,
+) -> Vec<TeamTabEntry> {
+    let mut speakers_by_room: HashMap<Uuid, Vec<&SpeechResult>> = HashMap::new();
+    for speech in speeches {
+        speakers_by_room
+            .entry(speech.match_team_id)
+            .or_default()
+            .push(speech);
+    }
+
+    let mut entries: Vec<TeamTabEntry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut seen_speakers: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut rooms: HashMap<Uuid, Vec<(usize, &TeamResult)>> = HashMap::new();
+
+    for result in results {
+        let key = team_key(result);
+        let position = *index.entry(key.clone()).or_insert_with(|| {
+            entries.push(TeamTabEntry {
+                rank: 0,
+                registered_team_id: result.registered_team_id,
+                team_name: result
+                    .team_name
+                    .clone()
+                    .unwrap_or_else(|| "Unnamed team".to_string()),
+                institution: result.institution.clone(),
+                points: 0,
+                wins: 0,
+                speaker_points: Some(Decimal::ZERO),
+                average_margin: None,
+                rounds: 0,
+                speakers: Vec::new(),
+                key: key.clone(),
+                eligibility: Vec::new(),
+                group: 0,
+            });
+            entries.len() - 1
+        });
+        rooms
+            .entry(result.match_id)
+            .or_default()
+            .push((position, result));
+
+        let entry = &mut entries[position];
+        entry.points += team_points(result.team_format, result.final_rank);
+        entry.wins += usize::from(result.final_rank == 1);
+        entry.speaker_points = Some(
+            entry.speaker_points.unwrap_or_default() + result.speaker_points.unwrap_or_default(),
+        );
+        entry.rounds += 1;
+
+        let seen = seen_speakers.entry(key).or_default();
+        for speech in speakers_by_room
+            .get(&result.match_team_id)
+            .into_iter()
+            .flatten()
+        {
+            if seen.insert(speaker_key(speech)) {
+                entry.speakers.push(speech.name.clone());
+                entry.eligibility.push((speech.is_novice, speech.is_esl));
+            }
+        }
+    }
+
+    let mut margins: Vec<Vec<Decimal>> = vec![Vec::new(); entries.len()];
+    for room in rooms.values() {
+        for &(position, result) in room {
+            let opponents: Vec<Decimal> = room
+                .iter()
+                .filter(|(other, _)| *other != position)
+                .filter_map(|(_, other)| other.speaker_points)
+                .collect();
+            if let (Some(own), false) = (result.speaker_points, opponents.is_empty()) {
+                let average = opponents.iter().sum::<Decimal>() / Decimal::from(opponents.len());
+                margins[position].push(own - average);
+            }
+        }
+    }
+    for (entry, margins) in entries.iter_mut().zip(&margins) {
+        entry.average_margin = rounding.average(margins);
+    }
+
+    // Start from name order so anything left level is listed predictably
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|&a, &b| entries[a].team_name.cmp(&entries[b].team_name));
+    let draw = draw_positions(&entries, seed);
+
+    let mut groups = vec![order];
+    for &rule in rules {
+        groups = groups
+            .into_iter()
+            .flat_map(|group| {
+                if group.len() < 2 {
+                    return vec![group];
+                }
+                let key = |team: usize| -> Decimal {
+                    match rule {
+                        TieBreak::Points => Decimal::from(entries[team].points),
+                        TieBreak::Wins => Decimal::from(entries[team].wins),
+                        TieBreak::Speaks => entries[team].speaker_points.unwrap_or_default(),
+                        TieBreak::HeadToHead => Decimal::from(head_to_head(team, &group, &rooms)),
+                        TieBreak::AverageMargin => {
+                            entries[team].average_margin.unwrap_or(Decimal::MIN)
+                        }
+                        TieBreak::Draw => Decimal::from(draw[team]),
+                    }
+                };
+                split_group(group.iter().map(|&team| (team, key(team))).collect())
+            })
+            .collect();
+    }
+
+    let mut sorted: Vec<Option<TeamTabEntry>> = entries.into_iter().map(Some).collect();
+    let mut standings = Vec::with_capacity(sorted.len());
+    for (group_index, group) in groups.into_iter().enumerate() {
+        for team in group {
+            if let Some(mut entry) = sorted[team].take() {
+                entry.group = group_index;
+                standings.push(entry);
+            }
+        }
+    }
+    assign_team_ranks(&mut standings);
+    standings
+}
+
+/// Order a group by its keys, highest first, and split it where the key
+/// changes
+fn split_group(mut keyed: Vec<(usize, Decimal)>) -> Vec<Vec<usize>> {
+    keyed.sort_by_key(|&(_, key)| std::cmp::Reverse(key));
+
+    let mut split: Vec<Vec<usize>> = Vec::new();
+    let mut last = None;
+    for (team, key) in keyed {
+        match split.last_mut() {
+            Some(current) if last == Some(key) => current.push(team),
+            _ => split.push(vec![team]),
+        }
+        last = Some(key);
+    }
+    split
+}
+
+/// Placings above, less placings below, the other teams of `group` in the
+/// rooms where they met
+fn head_to_head(
+    team: usize,
+    group: &[usize],
+    rooms: &HashMap<Uuid, Vec<(usize, &TeamResult)>>,
+) -> i64 {
+    let mut balance = 0;
+    for room in rooms.values() {
+        let Some((_, own)) = room.iter().find(|(position, _)| *position == team) else {
+            continue;
+        };
+        for (other, result) in room {
+            if *other != team && group.contains(other) {
+                balance += (result.final_rank - own.final_rank).signum() as i64;
+            }
+        }
+    }
+    balance
+}
+
+/// Every team's place in a random order fixed by the event's seed. Higher
+/// wins; the order only depends on the seed and the teams' identities.
+fn draw_positions(entries: &[TeamTabEntry], seed: i64) -> Vec<u64> {
+    let mut keys: Vec<&str> = entries.iter().map(|e| e.key.as_str()).collect();
+    keys.sort_unstable();
+    keys.shuffle(&mut ChaCha8Rng::seed_from_u64(seed as u64));
+
+    entries
+        .iter()
+        .map(|entry| {
+            let place = keys.iter().position(|k| *k == entry.key).unwrap_or(0);
+            (keys.len() - place) as u64
+        })
+        .collect()
+}
+
+impl TeamTabEntry {
+    /// Every speaker who represented the team must be eligible. Teams
+    /// without recorded speakers only appear on the open tab.
+    pub fn is_eligible(&self, category: EligibilityFilter) -> bool {
+        if category == EligibilityFilter::Open {
+            return true;
+        }
+        !self.eligibility.is_empty()
+            && self
+                .eligibility
+                .iter()
+                .all(|&(novice, esl)| category.admits_flags(novice, esl))
+    }
+
+    /// Drop everything worked out from speaker scores, keeping the order
+    pub fn withhold_speaks(&mut self) {
+        self.speaker_points = None;
+        self.average_margin = None;
+    }
+}
+
+/// Number the entries, giving teams the tie-break rules could not separate
+/// the same rank
+fn assign_team_ranks(entries: &mut [TeamTabEntry]) {
+    for i in 0..entries.len() {
+        entries[i].rank = if i > 0 && entries[i].group == entries[i - 1].group {
+            entries[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+}
+
+/// The team tab for one category, ranked within it
+pub fn team_tab(standings: &[TeamTabEntry], category: EligibilityFilter) -> Vec<TeamTabEntry> {
+    let mut tab: Vec<TeamTabEntry> = standings
+        .iter()
+        .filter(|entry| entry.is_eligible(category))
+        .cloned()
+        .collect();
+    assign_team_ranks(&mut tab);
+    tab
+}
+
+/// The speaker tab for one category. Reply speeches and unscored speeches
+/// are left out.
+pub fn speaker_tab(
+    results: &[TeamResult],
+    speeches: &[SpeechResult],
+    category: EligibilityFilter,
+    rounding: &RoundingPolicy,
+) -> Vec<SpeakerTabEntry> {
+    let team_names: HashMap<Uuid, &Option<String>> = results
+        .iter()
+        .map(|r| (r.match_team_id, &r.team_name))
+        .collect();
+
+    let mut entries: Vec<SpeakerTabEntry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for speech in speeches {
+        let Some(score) = speech.score else {
+            continue;
+        };
+        if speech.is_reply || !category.admits_flags(speech.is_novice, speech.is_esl) {
+            continue;
+        }
+        let Some(team_name) = team_names.get(&speech.match_team_id) else {
+            continue;
+        };
+
+        let position = *index.entry(speaker_key(speech)).or_insert_with(|| {
+            entries.push(SpeakerTabEntry {
+                rank: 0,
+                user_id: speech.user_id,
+                name: speech.name.clone(),
+                team_name: None,
+                total: Decimal::ZERO,
+                average: Decimal::ZERO,
+                speeches: 0,
+            });
+            entries.len() - 1
+        });
+
+        let entry = &mut entries[position];
+        entry.total += score;
+        entry.speeches += 1;
+        // Speakers who swapped teams are listed with their latest one
+        if team_name.is_some() {
+            entry.team_name = (*team_name).clone();
+        }
+    }
+
+    for entry in &mut entries {
+        entry.average = rounding.round(entry.total / Decimal::from(entry.speeches));
+        entry.total = rounding.round(entry.total);
+    }
+
+    entries.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then(b.average.cmp(&a.average))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    for i in 0..entries.len() {
+        entries[i].rank = if i > 0 && entries[i].total == entries[i - 1].total {
+            entries[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+
+    entries
+}
+
+/// Fill each category's break in turn. `break_sizes` gives the number of
+/// places per category; categories without one do not break.
+pub fn breaks(
+    standings: &[TeamTabEntry],
+    break_sizes: &HashMap<EligibilityFilter, usize>,
+) -> HashMap<EligibilityFilter, Vec<BreakEntry>> {
+    let mut broken: HashSet<String> = HashSet::new();
+    let mut result = HashMap::new();
+
+    for &category in EligibilityFilter::ALL {
+        let size = break_sizes.get(&category).copied().unwrap_or(0);
+        let teams: Vec<BreakEntry> = team_tab(standings, category)
+            .into_iter()
+            .filter(|team| !broken.contains(&team.key))
+            .take(size)
+            .enumerate()
+            .map(|(i, team)| BreakEntry {
+                break_rank: i + 1,
+                team,
+            })
+            .collect();
+
+        broken.extend(teams.iter().map(|entry| entry.team.key.clone()));
+        result.insert(category, teams);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rooms 1-4 share the first match, 5-8 the second, and so on
+    fn result(room: u128, name: &str, rank: i32, speaks: i64) -> TeamResult {
+        TeamResult {
+            match_team_id: Uuid::from_u128(room),
+            match_id: Uuid::from_u128(100 + (room - 1) / 4),
+            registered_team_id: None,
+            team_name: Some(name.to_string()),
+            institution: None,
+            team_format: TeamFormat::FourTeam,
+            final_rank: rank,
+            speaker_points: Some(Decimal::from(speaks)),
+        }
+    }
+
+    fn speech(room: u128, name: &str, novice: bool, score: i64) -> SpeechResult {
+        SpeechResult {
+            match_team_id: Uuid::from_u128(room),
+            user_id: None,
+            name: name.to_string(),
+            is_novice: novice,
+            is_esl: false,
+            is_reply: false,
+            score: Some(Decimal::from(score)),
+        }
+    }
+
+    /// Two rounds of four teams; Delta are novices
+    fn fixture() -> (Vec<TeamResult>, Vec<SpeechResult>) {
+        let results = vec![
+            result(1, "Alpha", 1, 160),
+            result(2, "Beta", 2, 150),
+            result(3, "Gamma", 3, 150),
+            result(4, "Delta", 4, 140),
+            result(5, "alpha", 2, 155),
+            result(6, "Beta", 3, 150),
+            result(7, "Gamma", 4, 140),
+            result(8, "Delta", 1, 160),
+        ];
+        let speeches = vec![
+            speech(1, "ali", false, 80),
+            speech(1, "amna", false, 80),
+            speech(4, "iqra", true, 70),
+            speech(4, "usman", true, 70),
+            speech(8, "iqra", true, 83),
+            speech(8, "usman", true, 82),
+            speech(2, "sara", true, 75),
+            speech(2, "zain", false, 75),
+        ];
+        (results, speeches)
+    }
+
+    #[test]
+    fn test_team_points() {
+        assert_eq!(team_points(TeamFormat::FourTeam, 1), 3);
+        assert_eq!(team_points(TeamFormat::FourTeam, 4), 0);
+        assert_eq!(team_points(TeamFormat::TwoTeam, 1), 1);
+        assert_eq!(team_points(TeamFormat::TwoTeam, 2), 0);
+    }
+
+    #[test]
+    fn test_teams_matched_across_rounds_by_name() {
+        let (results, speeches) = fixture();
+        let standings = team_standings(
+            &results,
+            &speeches,
+            TieBreak::DEFAULT,
+            0,
+            &RoundingPolicy::default(),
+        );
+        assert_eq!(standings.len(), 4);
+
+        let open = team_tab(&standings, EligibilityFilter::Open);
+        let names: Vec<&str> = open.iter().map(|t| t.team_name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "Beta", "Delta", "Gamma"]);
+        assert_eq!((open[0].points, open[0].rounds), (5, 2));
+        assert_eq!(open[0].speaker_points, Some(Decimal::from(315)));
+        // Beta and Delta are level on points and speaks
+        assert_eq!((open[1].rank, open[2].rank), (2, 2));
+        assert_eq!(open[3].rank, 4);
+        assert_eq!(open[2].speakers, ["iqra", "usman"]);
+    }
+
+    #[test]
+    fn test_category_tabs_filter_speakers_and_teams() {
+        let (results, speeches) = fixture();
+        let standings = team_standings(
+            &results,
+            &speeches,
+            TieBreak::DEFAULT,
+            0,
+            &RoundingPolicy::default(),
+        );
+
+        let novice = team_tab(&standings, EligibilityFilter::Novice);
+        assert_eq!(novice.len(), 1);
+        assert_eq!((novice[0].team_name.as_str(), novice[0].rank), ("Delta", 1));
+
+        let speakers = speaker_tab(
+            &results,
+            &speeches,
+            EligibilityFilter::Novice,
+            &RoundingPolicy::default(),
+        );
+        let names: Vec<&str> = speakers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["iqra", "usman", "sara"]);
+        assert_eq!(speakers[0].total, Decimal::from(153));
+        assert_eq!(speakers[0].average, Decimal::new(7650, 2));
+        assert_eq!((speakers[1].rank, speakers[1].speeches), (2, 2));
+    }
+
+    #[test]
+    fn test_reply_speeches_do_not_count() {
+        let (results, mut speeches) = fixture();
+        speeches.push(SpeechResult {
+            is_reply: true,
+            ..speech(1, "ali", false, 40)
+        });
+        let speakers = speaker_tab(
+            &results,
+            &speeches,
+            EligibilityFilter::Open,
+            &RoundingPolicy::default(),
+        );
+        let ali = speakers.iter().find(|s| s.name == "ali").unwrap();
+        assert_eq!((ali.total, ali.speeches), (Decimal::from(80), 1));
+    }
+
+    #[test]
+    fn test_open_breakers_skip_category_breaks() {
+        let (results, speeches) = fixture();
+        let standings = team_standings(
+            &results,
+            &speeches,
+            TieBreak::DEFAULT,
+            0,
+            &RoundingPolicy::default(),
+        );
+
+        let sizes = HashMap::from([(EligibilityFilter::Open, 3), (EligibilityFilter::Novice, 1)]);
+        let broken = breaks(&standings, &sizes);
+        assert_eq!(broken[&EligibilityFilter::Open].len(), 3);
+        // Delta broke open, so no novice team is left
+        assert!(broken[&EligibilityFilter::Novice].is_empty());
+        assert!(broken[&EligibilityFilter::Esl].is_empty());
+
+        let sizes = HashMap::from([(EligibilityFilter::Open, 2), (EligibilityFilter::Novice, 1)]);
+        let broken = breaks(&standings, &sizes);
+        let novice = &broken[&EligibilityFilter::Novice];
+        assert_eq!(novice[0].team.team_name, "Delta");
+        assert_eq!(novice[0].break_rank, 1);
+    }
+
+    /// Two-team rooms: A beat B, B beat C and D beat A
+    fn two_team_fixture() -> Vec<TeamResult> {
+        let two_team = |room: u128, name: &str, rank: i32, speaks: i64| TeamResult {
+            team_format: TeamFormat::TwoTeam,
+            match_id: Uuid::from_u128(100 + (room - 1) / 2),
+            ..result(room, name, rank, speaks)
+        };
+        vec![
+            two_team(1, "A", 1, 150),
+            two_team(2, "B", 2, 160),
+            two_team(3, "B", 1, 160),
+            two_team(4, "C", 2, 150),
+            two_team(5, "A", 2, 150),
+            two_team(6, "D", 1, 155),
+        ]
+    }
+
+    fn names(standings: &[TeamTabEntry]) -> Vec<&str> {
+        standings.iter().map(|t| t.team_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rule_chain_orders_level_teams() {
+        let results = two_team_fixture();
+        let rules = [TieBreak::Points, TieBreak::Speaks];
+        assert_eq!(
+            names(&team_standings(
+                &results,
+                &[],
+                &rules,
+                0,
+                &RoundingPolicy::default()
+            )),
+            ["B", "A", "D", "C"]
+        );
+
+        let rules = [TieBreak::Points, TieBreak::HeadToHead];
+        let standings = team_standings(&results, &[], &rules, 0, &RoundingPolicy::default());
+        assert_eq!(names(&standings), ["D", "A", "B", "C"]);
+        assert_eq!(
+            standings.iter().map(|t| t.rank).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+
+        let rules = [TieBreak::Points, TieBreak::AverageMargin];
+        let standings = team_standings(&results, &[], &rules, 0, &RoundingPolicy::default());
+        assert_eq!(names(&standings), ["B", "D", "A", "C"]);
+        assert_eq!(standings[2].average_margin, Some(Decimal::new(-75, 1)));
+    }
+
+    #[test]
+    fn test_teams_level_on_every_rule_share_a_rank() {
+        let results = two_team_fixture();
+        let standings = team_standings(
+            &results,
+            &[],
+            &[TieBreak::Wins],
+            0,
+            &RoundingPolicy::default(),
+        );
+        assert_eq!(
+            standings.iter().map(|t| t.rank).collect::<Vec<_>>(),
+            [1, 1, 1, 4]
+        );
+    }
+
+    #[test]
+    fn test_draw_is_fixed_by_seed() {
+        let results = two_team_fixture();
+        let first = team_standings(
+            &results,
+            &[],
+            &[TieBreak::Draw],
+            7,
+            &RoundingPolicy::default(),
+        );
+        let again = team_standings(
+            &results,
+            &[],
+            &[TieBreak::Draw],
+            7,
+            &RoundingPolicy::default(),
+        );
+        assert_eq!(names(&first), names(&again));
+        assert_eq!(
+            first.iter().map(|t| t.rank).collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_tie_break_round_trip() {
+        for rule in TieBreak::ALL {
+            assert_eq!(rule.as_str().parse::<TieBreak>(), Ok(*rule));
+        }
+        assert!("coin_toss".parse::<TieBreak>().is_err());
+    }
+}
@@ -0,0 +1,1184 @@
+//! Tabbycat interop: move a tournament between Tabrela and Tabbycat.
+//!
+//! The JSON document mirrors the objects in Tabbycat's API (teams with their
+//! speakers, adjudicators, rounds with motions, pairings and ballot results)
+//! but refers to teams and people by name instead of hyperlinks, so it can be
+//! assembled from a Tabbycat export or fed to a script that posts to its API.
+//! Sides use Tabbycat's codes: `aff`/`neg` for two-team rounds and
+//! `og`/`oo`/`cg`/`co` for British Parliamentary.
+//!
+//! `speakers.csv` and `judges.csv` follow the columns of Tabbycat's
+//! `importtournament` command.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::handlers::recalculate_team_results;
+use crate::margins;
+use crate::models::{
+    Allocation, AllocationRole, AllocationWithUser, Ballot, FourTeamPosition, Match, MatchSeries,
+    MatchStatus, MatchTeam, SpeakerScore, TeamFormat, TeamRanking, TwoTeamPosition,
+};
+use crate::seed::speaker_role;
+
+/// Large enough to fetch every round and room of a tournament in one page
+const EXPORT_PAGE_SIZE: i32 = 10_000;
+
+/// Notes attached to ballots created from imported results
+const IMPORTED_BALLOT_NOTES: &str = "Imported from Tabbycat";
+
+// ============================================================================
+// Document
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Tournament {
+    pub name: String,
+    #[serde(default)]
+    pub teams: Vec<Team>,
+    #[serde(default)]
+    pub adjudicators: Vec<Adjudicator>,
+    #[serde(default)]
+    pub rounds: Vec<Round>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Team {
+    /// Unique team name, e.g. "LUMS A"
+    pub reference: String,
+    #[serde(default)]
+    pub institution: Option<String>,
+    /// In speaking order
+    #[serde(default)]
+    pub speakers: Vec<Speaker>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Speaker {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Adjudicator {
+    pub name: String,
+    #[serde(default)]
+    pub institution: Option<String>,
+    /// Carried for Tabbycat; Tabrela does not rate adjudicators
+    #[serde(default)]
+    pub base_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Preliminary,
+    Elimination,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Round {
+    pub seq: i32,
+    pub name: String,
+    pub stage: Stage,
+    /// Results are kept from participants until the round is revealed
+    #[serde(default)]
+    pub silent: bool,
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub motions: Vec<Motion>,
+    #[serde(default)]
+    pub pairings: Vec<Pairing>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Motion {
+    pub text: String,
+    #[serde(default)]
+    pub info_slide: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Pairing {
+    #[serde(default)]
+    pub venue: Option<String>,
+    pub teams: Vec<PairingTeam>,
+    #[serde(default)]
+    pub adjudicators: Panel,
+    /// Text of the motion debated here when the round offered several.
+    /// Defaults to the round's first motion.
+    #[serde(default)]
+    pub motion: Option<String>,
+    #[serde(default)]
+    pub result: Option<PairingResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PairingTeam {
+    pub side: Side,
+    /// `Team::reference`
+    pub team: String,
+    /// Who speaks in this room, in order. Defaults to the first speakers on
+    /// the team's roster.
+    #[serde(default)]
+    pub speakers: Vec<String>,
+}
+
+impl PairingTeam {
+    /// Speakers in this room, taken from the roster when not listed
+    fn lineup<'a>(&'a self, roster: &'a Team) -> Vec<&'a str> {
+        if !self.speakers.is_empty() {
+            return self.speakers.iter().map(String::as_str).collect();
+        }
+        let per_team = match self.side.format() {
+            TeamFormat::TwoTeam => 3,
+            TeamFormat::FourTeam => 2,
+        };
+        roster
+            .speakers
+            .iter()
+            .take(per_team)
+            .map(|s| s.name.as_str())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Panel {
+    #[serde(default)]
+    pub chair: Option<String>,
+    #[serde(default)]
+    pub panellists: Vec<String>,
+    #[serde(default)]
+    pub trainees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PairingResult {
+    pub teams: Vec<TeamResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TeamResult {
+    pub side: Side,
+    /// 1 is first. Either this or `points` must be given.
+    #[serde(default)]
+    pub rank: Option<i32>,
+    /// Tabbycat team points: 3-0 in BP, 1/0 for two teams
+    #[serde(default)]
+    pub points: Option<i32>,
+    #[serde(default)]
+    pub speeches: Vec<Speech>,
+}
+
+impl TeamResult {
+    fn resolved_rank(&self, team_count: usize) -> Option<i32> {
+        self.rank
+            .or_else(|| self.points.map(|points| team_count as i32 - points))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Speech {
+    pub speaker: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Aff,
+    Neg,
+    Og,
+    Oo,
+    Cg,
+    Co,
+}
+
+impl Side {
+    pub fn code(self) -> &'static str {
+        match self {
+            Side::Aff => "aff",
+            Side::Neg => "neg",
+            Side::Og => "og",
+            Side::Oo => "oo",
+            Side::Cg => "cg",
+            Side::Co => "co",
+        }
+    }
+
+    fn from_team(team: &MatchTeam) -> Option<Self> {
+        match (team.two_team_position, team.four_team_position) {
+            (Some(TwoTeamPosition::Government), _) => Some(Side::Aff),
+            (Some(TwoTeamPosition::Opposition), _) => Some(Side::Neg),
+            (_, Some(FourTeamPosition::OpeningGovernment)) => Some(Side::Og),
+            (_, Some(FourTeamPosition::OpeningOpposition)) => Some(Side::Oo),
+            (_, Some(FourTeamPosition::ClosingGovernment)) => Some(Side::Cg),
+            (_, Some(FourTeamPosition::ClosingOpposition)) => Some(Side::Co),
+            (None, None) => None,
+        }
+    }
+
+    fn format(self) -> TeamFormat {
+        match self {
+            Side::Aff | Side::Neg => TeamFormat::TwoTeam,
+            _ => TeamFormat::FourTeam,
+        }
+    }
+
+    fn matches(self, team: &MatchTeam) -> bool {
+        Side::from_team(team) == Some(self)
+    }
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The document is inconsistent; nothing was written
+    Invalid(Vec<String>),
+    EventNotFound,
+    /// The event already has rounds; importing again would duplicate them
+    EventNotEmpty,
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Invalid(problems) => {
+                write!(f, "Invalid Tabbycat document: {}", problems.join("; "))
+            }
+            ImportError::EventNotFound => write!(f, "Event not found"),
+            ImportError::EventNotEmpty => write!(
+                f,
+                "Event already has rounds; import into an event without any"
+            ),
+            ImportError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<sqlx::Error> for ImportError {
+    fn from(e: sqlx::Error) -> Self {
+        ImportError::Database(e)
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Build the document for every round of `event_id`
+pub async fn export(db: &Database, event_id: Uuid) -> Result<Option<Tournament>, sqlx::Error> {
+    let Some(event) = db.get_event_by_id(event_id).await? else {
+        return Ok(None);
+    };
+
+    let mut builder = ExportBuilder::default();
+    let (series_list, _) = db
+        .list_series_by_event(event_id, 1, EXPORT_PAGE_SIZE)
+        .await?;
+
+    for (index, series) in series_list.iter().enumerate() {
+        let seq = series.round_number.unwrap_or(index as i32 + 1);
+        let (matches, _) = db
+            .list_matches_by_series(series.id, 1, EXPORT_PAGE_SIZE)
+            .await?;
+
+        let mut round = Round {
+            seq,
+            name: series.name.clone(),
+            stage: if series.is_break_round {
+                Stage::Elimination
+            } else {
+                Stage::Preliminary
+            },
+            silent: series.is_silent,
+            starts_at: matches.iter().filter_map(|m| m.scheduled_time).min(),
+            motions: Vec::new(),
+            pairings: Vec::new(),
+        };
+
+        for match_record in &matches {
+            if let Some(text) = &match_record.motion {
+                if !round.motions.iter().any(|m| &m.text == text) {
+                    round.motions.push(Motion {
+                        text: text.clone(),
+                        info_slide: match_record.info_slide.clone(),
+                    });
+                }
+            }
+
+            let teams = db.list_teams_by_match(match_record.id).await?;
+            let allocations = db.list_allocations_by_match(match_record.id).await?;
+            let mut scores = HashMap::new();
+            for allocation in &allocations {
+                if allocation.role == AllocationRole::Speaker {
+                    if let Some(score) = db.get_allocation_average_score(allocation.id).await? {
+                        scores.insert(allocation.id, score);
+                    }
+                }
+            }
+
+            round
+                .pairings
+                .push(builder.pairing(seq, match_record, &teams, &allocations, &scores));
+        }
+
+        builder.rounds.push(round);
+    }
+
+    Ok(Some(Tournament {
+        name: event.title,
+        teams: builder.teams,
+        adjudicators: builder.adjudicators,
+        rounds: builder.rounds,
+    }))
+}
+
+/// Collects the tournament-wide team and adjudicator lists while pairings
+/// are converted. Tabrela teams only exist per room, so teams are matched
+/// up across rounds by name.
+#[derive(Default)]
+struct ExportBuilder {
+    teams: Vec<Team>,
+    adjudicators: Vec<Adjudicator>,
+    rounds: Vec<Round>,
+}
+
+impl ExportBuilder {
+    fn pairing(
+        &mut self,
+        seq: i32,
+        match_record: &Match,
+        teams: &[MatchTeam],
+        allocations: &[AllocationWithUser],
+        scores: &HashMap<Uuid, Decimal>,
+    ) -> Pairing {
+        let mut pairing = Pairing {
+            venue: match_record.room_name.clone(),
+            teams: Vec::new(),
+            adjudicators: Panel::default(),
+            motion: match_record.motion.clone(),
+            result: None,
+        };
+        let mut results = Vec::new();
+
+        for team in teams {
+            let Some(side) = Side::from_team(team) else {
+                continue;
+            };
+            let reference = team.team_name.clone().unwrap_or_else(|| {
+                format!(
+                    "R{} {} {}",
+                    seq,
+                    match_record.room_name.as_deref().unwrap_or("Room"),
+                    side.code().to_uppercase()
+                )
+            });
+
+            let speakers: Vec<&AllocationWithUser> = allocations
+                .iter()
+                .filter(|a| a.role == AllocationRole::Speaker && a.team_id == Some(team.id))
+                .collect();
+            self.add_team(&reference, team.institution.as_deref(), &speakers);
+
+            pairing.teams.push(PairingTeam {
+                side,
+                team: reference,
+                speakers: speakers.iter().map(|a| a.username.clone()).collect(),
+            });
+
+            if let Some(rank) = team.final_rank {
+                results.push(TeamResult {
+                    side,
+                    rank: Some(rank),
+                    points: Some(teams.len() as i32 - rank),
+                    speeches: speakers
+                        .iter()
+                        .filter_map(|a| {
+                            let score = scores.get(&a.id)?.round_dp(2).to_f64()?;
+                            Some(Speech {
+                                speaker: a.username.clone(),
+                                score,
+                            })
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        for allocation in allocations {
+            let name = allocation.username.clone();
+            match (allocation.role, allocation.is_chair) {
+                (AllocationRole::VotingAdjudicator, Some(true)) => {
+                    pairing.adjudicators.chair = Some(name.clone())
+                }
+                (AllocationRole::VotingAdjudicator, _) => {
+                    pairing.adjudicators.panellists.push(name.clone())
+                }
+                (AllocationRole::NonVotingAdjudicator, _) => {
+                    pairing.adjudicators.trainees.push(name.clone())
+                }
+                _ => continue,
+            }
+            if !self.adjudicators.iter().any(|a| a.name == name) {
+                self.adjudicators.push(Adjudicator {
+                    name,
+                    institution: None,
+                    base_score: None,
+                });
+            }
+        }
+
+        let finished = matches!(match_record.status, MatchStatus::Completed);
+        if finished && results.len() == pairing.teams.len() && !results.is_empty() {
+            pairing.result = Some(PairingResult { teams: results });
+        }
+
+        pairing
+    }
+
+    fn add_team(
+        &mut self,
+        reference: &str,
+        institution: Option<&str>,
+        speakers: &[&AllocationWithUser],
+    ) {
+        let index = match self.teams.iter().position(|t| t.reference == reference) {
+            Some(index) => index,
+            None => {
+                self.teams.push(Team {
+                    reference: reference.to_string(),
+                    institution: institution.map(str::to_string),
+                    speakers: Vec::new(),
+                });
+                self.teams.len() - 1
+            }
+        };
+
+        let team = &mut self.teams[index];
+        for speaker in speakers {
+            if !team.speakers.iter().any(|s| s.name == speaker.username) {
+                team.speakers.push(Speaker {
+                    name: speaker.username.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// `speakers.csv` for Tabbycat's importer
+pub fn speakers_csv(tournament: &Tournament) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["name", "institution", "team_name"])?;
+    for team in &tournament.teams {
+        for speaker in &team.speakers {
+            writer.write_record([
+                speaker.name.as_str(),
+                team.institution.as_deref().unwrap_or(""),
+                team.reference.as_str(),
+            ])?;
+        }
+    }
+    finish_csv(writer)
+}
+
+/// `judges.csv` for Tabbycat's importer
+pub fn judges_csv(tournament: &Tournament) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["name", "institution", "base_score"])?;
+    for adjudicator in &tournament.adjudicators {
+        let base_score = adjudicator
+            .base_score
+            .map(|score| score.to_string())
+            .unwrap_or_default();
+        writer.write_record([
+            adjudicator.name.as_str(),
+            adjudicator.institution.as_deref().unwrap_or(""),
+            base_score.as_str(),
+        ])?;
+    }
+    finish_csv(writer)
+}
+
+fn finish_csv(writer: csv::Writer<Vec<u8>>) -> Result<String, csv::Error> {
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("CSV built from UTF-8 strings"))
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// What an import created
+#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+pub struct ImportSummary {
+    pub rounds: usize,
+    pub matches: usize,
+    pub allocations: usize,
+    pub ballots: usize,
+    /// Names that matched a Tabrela username
+    pub matched_users: usize,
+    /// Names imported as guests because no account matched
+    pub guests: usize,
+}
+
+/// Check that the document is internally consistent before anything is
+/// written, returning every problem found
+pub fn validate(tournament: &Tournament) -> Result<(), ImportError> {
+    let mut problems = Vec::new();
+
+    let mut references = HashSet::new();
+    for team in &tournament.teams {
+        if !references.insert(team.reference.as_str()) {
+            problems.push(format!("Team '{}' is listed twice", team.reference));
+        }
+    }
+
+    let mut seqs = HashSet::new();
+    for round in &tournament.rounds {
+        let label = format!("Round {} ({})", round.seq, round.name);
+        if !seqs.insert(round.seq) {
+            problems.push(format!("{}: seq is used by another round", label));
+        }
+
+        let mut format = None;
+        for (index, pairing) in round.pairings.iter().enumerate() {
+            let room = format!(
+                "{} room {}",
+                label,
+                pairing
+                    .venue
+                    .clone()
+                    .unwrap_or_else(|| (index + 1).to_string())
+            );
+            validate_pairing(tournament, pairing, &room, &mut format, &mut problems);
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ImportError::Invalid(problems))
+    }
+}
+
+fn validate_pairing(
+    tournament: &Tournament,
+    pairing: &Pairing,
+    room: &str,
+    round_format: &mut Option<TeamFormat>,
+    problems: &mut Vec<String>,
+) {
+    let sides: Vec<Side> = pairing.teams.iter().map(|t| t.side).collect();
+    let expected: &[Side] = match sides.first().map(|side| side.format()) {
+        Some(TeamFormat::TwoTeam) => &[Side::Aff, Side::Neg],
+        Some(TeamFormat::FourTeam) => &[Side::Og, Side::Oo, Side::Cg, Side::Co],
+        None => {
+            problems.push(format!("{}: no teams", room));
+            return;
+        }
+    };
+    let format = sides[0].format();
+
+    if sides.len() != expected.len() || expected.iter().any(|side| !sides.contains(side)) {
+        let codes: Vec<&str> = expected.iter().map(|side| side.code()).collect();
+        problems.push(format!(
+            "{}: sides must be exactly {}",
+            room,
+            codes.join(", ")
+        ));
+    }
+    match round_format {
+        Some(existing) if *existing != format => {
+            problems.push(format!("{}: mixes two-team and BP rooms", room))
+        }
+        _ => *round_format = Some(format),
+    }
+
+    for pairing_team in &pairing.teams {
+        let Some(roster) = tournament
+            .teams
+            .iter()
+            .find(|t| t.reference == pairing_team.team)
+        else {
+            problems.push(format!("{}: unknown team '{}'", room, pairing_team.team));
+            continue;
+        };
+        for name in &pairing_team.speakers {
+            if !roster.speakers.iter().any(|s| &s.name == name) {
+                problems.push(format!(
+                    "{}: '{}' is not on the roster of '{}'",
+                    room, name, roster.reference
+                ));
+            }
+        }
+    }
+
+    let Some(result) = &pairing.result else {
+        return;
+    };
+    let mut ranks = Vec::new();
+    for team_result in &result.teams {
+        if !sides.contains(&team_result.side) {
+            problems.push(format!("{}: result for a side not in the pairing", room));
+            continue;
+        }
+        match team_result.resolved_rank(expected.len()) {
+            Some(rank) if (1..=expected.len() as i32).contains(&rank) => ranks.push(rank),
+            _ => problems.push(format!(
+                "{}: {} needs a rank between 1 and {}",
+                room,
+                team_result.side.code(),
+                expected.len()
+            )),
+        }
+
+        let lineup = pairing
+            .teams
+            .iter()
+            .find(|t| t.side == team_result.side)
+            .and_then(|t| {
+                let roster = tournament
+                    .teams
+                    .iter()
+                    .find(|team| team.reference == t.team)?;
+                Some((t.team.as_str(), t.lineup(roster)))
+            });
+        for speech in &team_result.speeches {
+            if !(0.0..=100.0).contains(&speech.score) {
+                problems.push(format!(
+                    "{}: score {} for '{}' is outside 0-100",
+                    room, speech.score, speech.speaker
+                ));
+            }
+            if let Some((team, speakers)) = &lineup {
+                if !speakers.contains(&speech.speaker.as_str()) {
+                    problems.push(format!(
+                        "{}: '{}' does not speak for '{}'",
+                        room, speech.speaker, team
+                    ));
+                }
+            }
+        }
+    }
+    ranks.sort_unstable();
+    ranks.dedup();
+    if result.teams.len() != expected.len() || ranks.len() != expected.len() {
+        problems.push(format!(
+            "{}: result must rank every team exactly once",
+            room
+        ));
+    }
+}
+
+/// Create every round, room, allocation and result in `tournament` under
+/// `event_id`. Names matching a username become allocations for that account;
+/// everyone else is imported as a guest.
+pub async fn import(
+    db: &Database,
+    event_id: Uuid,
+    admin_id: Uuid,
+    tournament: &Tournament,
+) -> Result<ImportSummary, ImportError> {
+    validate(tournament)?;
+
+    if db.get_event_by_id(event_id).await?.is_none() {
+        return Err(ImportError::EventNotFound);
+    }
+    let (_, existing_rounds) = db.list_series_by_event(event_id, 1, 1).await?;
+    if existing_rounds > 0 {
+        return Err(ImportError::EventNotEmpty);
+    }
+
+    let mut names: Vec<String> = tournament
+        .teams
+        .iter()
+        .flat_map(|t| t.speakers.iter().map(|s| s.name.clone()))
+        .chain(tournament.adjudicators.iter().map(|a| a.name.clone()))
+        .collect();
+    for pairing in tournament.rounds.iter().flat_map(|r| &r.pairings) {
+        let panel = &pairing.adjudicators;
+        names.extend(panel.chair.iter().cloned());
+        names.extend(panel.panellists.iter().cloned());
+        names.extend(panel.trainees.iter().cloned());
+    }
+    let users: HashMap<String, Uuid> = db
+        .find_users_by_usernames(&names)
+        .await?
+        .into_iter()
+        .map(|user| (user.username.to_lowercase(), user.id))
+        .collect();
+
+    let mut importer = Importer {
+        db,
+        event_id,
+        admin_id,
+        tournament,
+        users,
+        summary: ImportSummary::default(),
+    };
+    for round in &tournament.rounds {
+        importer.round(round).await?;
+    }
+    db.refresh_event_performance(event_id).await?;
+
+    Ok(importer.summary)
+}
+
+struct Importer<'a> {
+    db: &'a Database,
+    event_id: Uuid,
+    admin_id: Uuid,
+    tournament: &'a Tournament,
+    /// Lowercased username -> user id
+    users: HashMap<String, Uuid>,
+    summary: ImportSummary,
+}
+
+impl Importer<'_> {
+    async fn round(&mut self, round: &Round) -> Result<(), ImportError> {
+        let now = Utc::now();
+        let team_format = round
+            .pairings
+            .first()
+            .and_then(|p| p.teams.first())
+            .map(|t| t.side.format())
+            .unwrap_or(TeamFormat::FourTeam);
+
+        let series = MatchSeries {
+            id: Uuid::new_v4(),
+            event_id: self.event_id,
+            name: round.name.clone(),
+            description: None,
+            round_number: Some(round.seq),
+            team_format,
+            allow_reply_speeches: false,
+            is_break_round: round.stage == Stage::Elimination,
+            is_silent: round.silent,
+            is_training: false,
+            double_entry: false,
+            created_by: self.admin_id,
+            created_at: now,
+            updated_at: now,
+        };
+        self.db.create_series(&series).await?;
+        self.summary.rounds += 1;
+
+        for pairing in &round.pairings {
+            let motion = match &pairing.motion {
+                Some(text) => round.motions.iter().find(|m| &m.text == text),
+                None => round.motions.first(),
+            };
+            let match_record = Match {
+                id: Uuid::new_v4(),
+                series_id: series.id,
+                room_name: pairing.venue.clone(),
+                venue_id: None,
+                motion: pairing
+                    .motion
+                    .clone()
+                    .or_else(|| motion.map(|m| m.text.clone())),
+                info_slide: motion.and_then(|m| m.info_slide.clone()),
+                status: if pairing.result.is_some() {
+                    MatchStatus::Completed
+                } else {
+                    MatchStatus::Published
+                },
+                scheduled_time: round.starts_at,
+                scores_released: false,
+                rankings_released: false,
+                created_at: now,
+                updated_at: now,
+            };
+            self.db.create_match(&match_record).await?;
+            self.summary.matches += 1;
+
+            self.pairing(&match_record, team_format, pairing).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn pairing(
+        &mut self,
+        match_record: &Match,
+        team_format: TeamFormat,
+        pairing: &Pairing,
+    ) -> Result<(), ImportError> {
+        let teams = self
+            .db
+            .create_teams_for_match(match_record.id, team_format)
+            .await?;
+
+        // Speaker name -> allocation, per side
+        let mut speakers: HashMap<(Side, String), Uuid> = HashMap::new();
+        for pairing_team in &pairing.teams {
+            let Some(team) = teams.iter().find(|t| pairing_team.side.matches(t)) else {
+                continue;
+            };
+            let Some(roster) = self
+                .tournament
+                .teams
+                .iter()
+                .find(|t| t.reference == pairing_team.team)
+            else {
+                continue;
+            };
+            self.db
+                .update_team(
+                    team.id,
+                    Some(&roster.reference),
+                    roster.institution.as_deref(),
+                )
+                .await?;
+
+            for (slot, name) in pairing_team.lineup(roster).into_iter().enumerate() {
+                let (two, four) = speaker_role(team, slot);
+                let allocation = Allocation {
+                    team_id: Some(team.id),
+                    two_team_speaker_role: two,
+                    four_team_speaker_role: four,
+                    ..self.allocation(match_record.id, name, AllocationRole::Speaker)
+                };
+                self.db.create_allocation(&allocation).await?;
+                speakers.insert((pairing_team.side, name.to_string()), allocation.id);
+            }
+        }
+
+        let panel = &pairing.adjudicators;
+        let seats = panel
+            .chair
+            .iter()
+            .map(|name| (name, AllocationRole::VotingAdjudicator, true))
+            .chain(
+                panel
+                    .panellists
+                    .iter()
+                    .map(|name| (name, AllocationRole::VotingAdjudicator, false)),
+            )
+            .chain(
+                panel
+                    .trainees
+                    .iter()
+                    .map(|name| (name, AllocationRole::NonVotingAdjudicator, false)),
+            );
+        for (name, role, is_chair) in seats {
+            let allocation = Allocation {
+                is_chair: Some(is_chair),
+                ..self.allocation(match_record.id, name, role)
+            };
+            self.db.create_allocation(&allocation).await?;
+        }
+
+        if let Some(result) = &pairing.result {
+            let chair = panel.chair.as_deref().and_then(|name| self.user_id(name));
+            self.result(match_record, &teams, &speakers, result, chair)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Store the result as one submitted ballot, by the chair when they have
+    /// an account and otherwise by the importing admin
+    async fn result(
+        &mut self,
+        match_record: &Match,
+        teams: &[MatchTeam],
+        speakers: &HashMap<(Side, String), Uuid>,
+        result: &PairingResult,
+        chair: Option<Uuid>,
+    ) -> Result<(), ImportError> {
+        let now = Utc::now();
+        let ballot = Ballot {
+            id: Uuid::new_v4(),
+            match_id: match_record.id,
+            adjudicator_id: chair.unwrap_or(self.admin_id),
+            is_voting: true,
+            is_submitted: false,
+            submitted_at: None,
+            notes: None,
+            low_point_win: false,
+            created_at: now,
+            updated_at: now,
+        };
+        self.db.create_ballot(&ballot).await?;
+        self.summary.ballots += 1;
+
+        let mut rankings = Vec::new();
+        let mut speaks = Vec::new();
+        for team_result in &result.teams {
+            let Some(team) = teams.iter().find(|t| team_result.side.matches(t)) else {
+                continue;
+            };
+            rankings.push((team.id, team_result.resolved_rank(teams.len()).unwrap_or(1)));
+            for speech in &team_result.speeches {
+                if speakers.contains_key(&(team_result.side, speech.speaker.clone())) {
+                    speaks.push((team.id, speech_score(speech.score)));
+                }
+            }
+        }
+        let team_margins = margins::team_margins(&rankings, &speaks, &self.db.rounding());
+
+        for team_result in &result.teams {
+            let Some(team) = teams.iter().find(|t| team_result.side.matches(t)) else {
+                continue;
+            };
+            let rank = team_result.resolved_rank(teams.len()).unwrap_or(1);
+            let margin = team_margins.iter().find(|m| m.team_id == team.id);
+            let ranking = TeamRanking {
+                id: Uuid::new_v4(),
+                ballot_id: ballot.id,
+                team_id: team.id,
+                rank,
+                is_winner: Some(rank as usize <= teams.len() / 2),
+                total_speaks: margin.map(|m| m.total_speaks),
+                margin: margin.map(|m| m.margin),
+                created_at: now,
+                updated_at: now,
+            };
+            self.db.create_team_ranking(&ranking).await?;
+
+            for speech in &team_result.speeches {
+                let Some(&allocation_id) =
+                    speakers.get(&(team_result.side, speech.speaker.clone()))
+                else {
+                    continue;
+                };
+                let score = SpeakerScore {
+                    id: Uuid::new_v4(),
+                    ballot_id: ballot.id,
+                    allocation_id,
+                    score: speech_score(speech.score),
+                    feedback: None,
+                    created_at: now,
+                    updated_at: now,
+                };
+                self.db.create_speaker_score(&score).await?;
+            }
+        }
+
+        self.db
+            .submit_ballot(
+                ballot.id,
+                Some(IMPORTED_BALLOT_NOTES),
+                !margins::low_point_wins(&team_margins).is_empty(),
+            )
+            .await?;
+        recalculate_team_results(self.db, match_record.id).await;
+
+        Ok(())
+    }
+
+    fn user_id(&self, name: &str) -> Option<Uuid> {
+        self.users.get(&name.to_lowercase()).copied()
+    }
+
+    fn allocation(&mut self, match_id: Uuid, name: &str, role: AllocationRole) -> Allocation {
+        let now = Utc::now();
+        let user_id = self.user_id(name);
+        if user_id.is_some() {
+            self.summary.matched_users += 1;
+        } else {
+            self.summary.guests += 1;
+        }
+        self.summary.allocations += 1;
+
+        Allocation {
+            id: Uuid::new_v4(),
+            match_id,
+            user_id,
+            guest_name: user_id.is_none().then(|| name.to_string()),
+            role,
+            team_id: None,
+            two_team_speaker_role: None,
+            four_team_speaker_role: None,
+            is_chair: None,
+            allocated_at: now,
+            allocated_by: self.admin_id,
+            was_checked_in: false,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Imported scores are kept to the hundredth, like submitted ones
+fn speech_score(score: f64) -> Decimal {
+    Decimal::try_from(score).unwrap_or_default().round_dp(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(reference: &str, speakers: &[&str]) -> Team {
+        Team {
+            reference: reference.to_string(),
+            institution: Some("LUMS".to_string()),
+            speakers: speakers
+                .iter()
+                .map(|name| Speaker {
+                    name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn tournament(result: Option<PairingResult>) -> Tournament {
+        Tournament {
+            name: "Open".to_string(),
+            teams: vec![team("LUMS A", &["ali", "sana"]), team("NUST B", &["omar"])],
+            adjudicators: vec![],
+            rounds: vec![Round {
+                seq: 1,
+                name: "Round 1".to_string(),
+                stage: Stage::Preliminary,
+                silent: false,
+                starts_at: None,
+                motions: vec![],
+                pairings: vec![Pairing {
+                    venue: Some("Room 1".to_string()),
+                    teams: vec![
+                        PairingTeam {
+                            side: Side::Aff,
+                            team: "LUMS A".to_string(),
+                            speakers: vec![],
+                        },
+                        PairingTeam {
+                            side: Side::Neg,
+                            team: "NUST B".to_string(),
+                            speakers: vec![],
+                        },
+                    ],
+                    adjudicators: Panel {
+                        chair: Some("hira".to_string()),
+                        ..Panel::default()
+                    },
+                    motion: None,
+                    result,
+                }],
+            }],
+        }
+    }
+
+    fn team_result(side: Side, points: i32, speeches: &[(&str, f64)]) -> TeamResult {
+        TeamResult {
+            side,
+            rank: None,
+            points: Some(points),
+            speeches: speeches
+                .iter()
+                .map(|(speaker, score)| Speech {
+                    speaker: speaker.to_string(),
+                    score: *score,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let result = PairingResult {
+            teams: vec![
+                team_result(Side::Aff, 1, &[("ali", 76.0), ("sana", 75.5)]),
+                team_result(Side::Neg, 0, &[("omar", 74.0)]),
+            ],
+        };
+        assert!(validate(&tournament(Some(result))).is_ok());
+        assert!(validate(&tournament(None)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_document_reports_every_problem() {
+        let mut doc = tournament(Some(PairingResult {
+            teams: vec![
+                team_result(Side::Aff, 1, &[("omar", 120.0)]),
+                team_result(Side::Neg, 1, &[]),
+            ],
+        }));
+        doc.rounds[0].pairings[0].teams[1].team = "Missing".to_string();
+
+        let Err(ImportError::Invalid(problems)) = validate(&doc) else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].contains("unknown team 'Missing'"));
+        assert!(problems[1].contains("outside 0-100"));
+        assert!(problems[2].contains("'omar' does not speak for 'LUMS A'"));
+        assert!(problems[3].contains("rank every team exactly once"));
+    }
+
+    #[test]
+    fn test_lineup_defaults_to_roster() {
+        let mut doc = tournament(None);
+        doc.teams[0].speakers.push(Speaker {
+            name: "extra".to_string(),
+        });
+        doc.teams[0].speakers.push(Speaker {
+            name: "reserve".to_string(),
+        });
+        let pairing_team = &doc.rounds[0].pairings[0].teams[0];
+        assert_eq!(
+            pairing_team.lineup(&doc.teams[0]),
+            vec!["ali", "sana", "extra"]
+        );
+
+        doc.rounds[0].pairings[0].teams[0].speakers = vec!["reserve".to_string()];
+        let pairing_team = &doc.rounds[0].pairings[0].teams[0];
+        assert_eq!(pairing_team.lineup(&doc.teams[0]), vec!["reserve"]);
+
+        doc.rounds[0].pairings[0].teams[0].speakers = vec!["stranger".to_string()];
+        let Err(ImportError::Invalid(problems)) = validate(&doc) else {
+            panic!("expected validation to fail");
+        };
+        assert!(problems[0].contains("'stranger' is not on the roster of 'LUMS A'"));
+    }
+
+    #[test]
+    fn test_sides_must_match_format() {
+        let mut doc = tournament(None);
+        doc.rounds[0].pairings[0].teams[1].side = Side::Oo;
+        assert!(matches!(validate(&doc), Err(ImportError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_side_codes() {
+        let json = serde_json::to_string(&[Side::Aff, Side::Og, Side::Co]).unwrap();
+        assert_eq!(json, r#"["aff","og","co"]"#);
+    }
+
+    #[test]
+    fn test_participant_csvs() {
+        let mut doc = tournament(None);
+        doc.teams[1].institution = None;
+        doc.adjudicators.push(Adjudicator {
+            name: "Hira, K".to_string(),
+            institution: None,
+            base_score: Some(3.5),
+        });
+
+        assert_eq!(
+            speakers_csv(&doc).unwrap(),
+            "name,institution,team_name\nali,LUMS,LUMS A\nsana,LUMS,LUMS A\nomar,,NUST B\n"
+        );
+        assert_eq!(
+            judges_csv(&doc).unwrap(),
+            "name,institution,base_score\n\"Hira, K\",,3.5\n"
+        );
+    }
+}
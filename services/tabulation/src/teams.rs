@@ -0,0 +1,128 @@
+//! Team entry: members form persistent teams and register them for events
+//! in team-entry mode. The draw then assigns a whole team to a side of a
+//! match, and its members are allocated as that side's speakers in their
+//! speaking order.
+
+use uuid::Uuid;
+
+use crate::models::{FourTeamSpeakerRole, MatchTeam, RegisteredTeamMember, TwoTeamSpeakerRole};
+use crate::seed::speaker_role;
+
+/// Largest team any format can field (two-team sides have three speakers)
+pub const MAX_TEAM_SIZE: usize = 3;
+
+/// One speaker of a team assigned to a side
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineupSlot {
+    pub user_id: Uuid,
+    pub two_team_speaker_role: Option<TwoTeamSpeakerRole>,
+    pub four_team_speaker_role: Option<FourTeamSpeakerRole>,
+}
+
+/// Number of speeches a side gives, not counting reply speeches
+pub fn speaking_slots(side: &MatchTeam) -> usize {
+    if side.four_team_position.is_some() {
+        2
+    } else {
+        3
+    }
+}
+
+/// Speaker roles for a team's members on the given side, in speaking order
+pub fn lineup(
+    side: &MatchTeam,
+    members: &[RegisteredTeamMember],
+) -> Result<Vec<LineupSlot>, String> {
+    let slots = speaking_slots(side);
+    if members.len() > slots {
+        return Err(format!(
+            "Team has {} members but this side only has {} speaking positions",
+            members.len(),
+            slots
+        ));
+    }
+
+    let mut ordered: Vec<&RegisteredTeamMember> = members.iter().collect();
+    ordered.sort_by_key(|m| m.speaker_order);
+
+    Ok(ordered
+        .into_iter()
+        .enumerate()
+        .map(|(slot, member)| {
+            let (two, four) = speaker_role(side, slot);
+            LineupSlot {
+                user_id: member.user_id,
+                two_team_speaker_role: two,
+                four_team_speaker_role: four,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FourTeamPosition, TwoTeamPosition};
+    use chrono::Utc;
+
+    fn side(two: Option<TwoTeamPosition>, four: Option<FourTeamPosition>) -> MatchTeam {
+        MatchTeam {
+            id: Uuid::new_v4(),
+            match_id: Uuid::new_v4(),
+            two_team_position: two,
+            four_team_position: four,
+            team_name: None,
+            institution: None,
+            final_rank: None,
+            total_speaker_points: None,
+            registered_team_id: None,
+            institution_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn member(order: i32) -> RegisteredTeamMember {
+        RegisteredTeamMember {
+            user_id: Uuid::new_v4(),
+            username: format!("speaker_{}", order),
+            speaker_order: order,
+            joined_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_lineup_follows_speaking_order() {
+        let (first, second) = (member(0), member(1));
+        let co = side(None, Some(FourTeamPosition::ClosingOpposition));
+
+        let slots = lineup(&co, &[second.clone(), first.clone()]).unwrap();
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[0].user_id, first.user_id);
+        assert_eq!(
+            slots[0].four_team_speaker_role,
+            Some(FourTeamSpeakerRole::MemberOfOpposition)
+        );
+        assert_eq!(
+            slots[1].four_team_speaker_role,
+            Some(FourTeamSpeakerRole::OppositionWhip)
+        );
+        assert_eq!(slots[1].two_team_speaker_role, None);
+    }
+
+    #[test]
+    fn test_two_team_sides_take_three_speakers() {
+        let gov = side(Some(TwoTeamPosition::Government), None);
+        let slots = lineup(&gov, &[member(0), member(1), member(2)]).unwrap();
+        assert_eq!(
+            slots[2].two_team_speaker_role,
+            Some(TwoTeamSpeakerRole::GovernmentWhip)
+        );
+    }
+
+    #[test]
+    fn test_oversized_team_is_rejected() {
+        let oo = side(None, Some(FourTeamPosition::OpeningOpposition));
+        assert!(lineup(&oo, &[member(0), member(1), member(2)]).is_err());
+    }
+}
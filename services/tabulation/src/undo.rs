@@ -0,0 +1,151 @@
+//! A short undo window for deleting and swapping allocations, which are
+//! easy to get wrong during a hurried re-draw.
+//!
+//! The change is made at once, and the allocations as they were are kept in
+//! `staged_allocation_changes` for `UNDO_WINDOW_SECS`. Until then an undo
+//! puts them back and nothing reaches the allocation history; afterwards a
+//! background task makes the change final and writes its history. Scores
+//! already entered against a deleted allocation are not brought back.
+//!
+//! Undoing and finalizing both claim the row with a single `DELETE`, so
+//! only one of them ever happens, on whichever replica gets there first.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::types::Json;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::{
+    models::{Allocation, AllocationHistory},
+    AppState,
+};
+
+/// Allocation history actions that can be undone
+pub const DELETED: &str = "deleted";
+pub const SWAPPED: &str = "swapped";
+
+/// How often due changes are made final
+const FINALIZE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An allocation delete or swap that can still be undone
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StagedChange {
+    pub id: Uuid,
+    /// `deleted` or `swapped`, as in the allocation history
+    pub action: String,
+    /// The allocations before the change
+    pub snapshot: Json<Vec<Allocation>>,
+    pub history: Json<Vec<AllocationHistory>>,
+    pub staged_by: Uuid,
+    pub staged_at: DateTime<Utc>,
+    pub finalize_at: DateTime<Utc>,
+}
+
+impl StagedChange {
+    /// Matches whose allocations the change touched
+    pub fn match_ids(&self) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self.snapshot.iter().map(|a| a.match_id).collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+}
+
+/// What a client needs to offer an undo
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UndoToken {
+    pub change_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<&StagedChange> for UndoToken {
+    fn from(change: &StagedChange) -> Self {
+        Self {
+            change_id: change.id,
+            expires_at: change.finalize_at,
+        }
+    }
+}
+
+/// When a change staged at `staged_at` becomes final
+pub fn finalize_at(staged_at: DateTime<Utc>, window: Duration) -> DateTime<Utc> {
+    staged_at + chrono::Duration::from_std(window).unwrap_or_else(|_| chrono::Duration::zero())
+}
+
+/// Make an allocation change undoable for the configured window, or final
+/// at once when the window is zero. Returns what the client needs to undo.
+pub async fn stage(
+    state: &AppState,
+    action: &str,
+    snapshot: Vec<Allocation>,
+    history: Vec<AllocationHistory>,
+    staged_by: Uuid,
+) -> Option<UndoToken> {
+    let window = state.config.undo_window;
+    if !window.is_zero() {
+        let staged_at = Utc::now();
+        match state
+            .db
+            .stage_allocation_change(
+                action,
+                &snapshot,
+                &history,
+                staged_by,
+                staged_at,
+                finalize_at(staged_at, window),
+            )
+            .await
+        {
+            Ok(change) => return Some(UndoToken::from(&change)),
+            Err(e) => {
+                tracing::error!("Failed to stage allocation change, making it final: {}", e);
+            }
+        }
+    }
+
+    for mut entry in history {
+        // The deleted allocation is already gone; history written before a
+        // delete ends up without its id in the same way
+        if action == DELETED {
+            entry.allocation_id = None;
+        }
+        let _ = state.db.create_allocation_history(&entry).await;
+    }
+    None
+}
+
+/// Run the finalizer for as long as the process lives
+pub fn spawn(state: Arc<AppState>) {
+    if state.config.undo_window.is_zero() {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(FINALIZE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match state.db.finalize_due_allocation_changes().await {
+                Ok(0) => {}
+                Ok(entries) => tracing::debug!("Wrote {} allocation history entries", entries),
+                Err(e) => tracing::warn!("Failed to finalize allocation changes: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_finalize_at() {
+        let staged_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            finalize_at(staged_at, Duration::from_secs(15)),
+            Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 15).unwrap()
+        );
+        assert_eq!(finalize_at(staged_at, Duration::ZERO), staged_at);
+    }
+}
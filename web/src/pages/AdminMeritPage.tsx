@@ -1,7 +1,7 @@
 import { useState, useEffect, useCallback } from 'react';
 import { Link } from 'react-router-dom';
-import { AdminMeritService } from '../services/merit';
-import type { UserMeritInfo, MeritHistoryEntry } from '../services/types';
+import { AdminMeritService, AdminSeasonService, MeritService } from '../services/merit';
+import type { UserMeritInfo, MeritHistoryEntry, Season } from '../services/types';
 
 export default function AdminMeritPage() {
   const [users, setUsers] = useState<UserMeritInfo[]>([]);
@@ -11,6 +11,9 @@ export default function AdminMeritPage() {
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [successMessage, setSuccessMessage] = useState<string | null>(null);
+  const [seasons, setSeasons] = useState<Season[]>([]);
+  const [seasonId, setSeasonId] = useState<string>('');
+  const [isDownloadingReport, setIsDownloadingReport] = useState(false);
   
   // Modal state for updating merit
   const [selectedUser, setSelectedUser] = useState<UserMeritInfo | null>(null);
@@ -27,11 +30,21 @@ export default function AdminMeritPage() {
 
   const perPage = 20;
 
+  useEffect(() => {
+    MeritService.listSeasons()
+      .then((response) => setSeasons(response.seasons))
+      .catch((err) => console.error('Failed to load seasons:', err));
+  }, []);
+
   const loadUsers = useCallback(async () => {
     setIsLoading(true);
     setError(null);
     try {
-      const response = await AdminMeritService.listAllMerits(currentPage, perPage);
+      const response = await AdminMeritService.listAllMerits(
+        currentPage,
+        perPage,
+        seasonId || undefined
+      );
       setUsers(response.users);
       setTotalPages(response.total_pages);
       setTotal(response.total);
@@ -41,7 +54,7 @@ export default function AdminMeritPage() {
     } finally {
       setIsLoading(false);
     }
-  }, [currentPage]);
+  }, [currentPage, seasonId]);
 
   useEffect(() => {
     loadUsers();
@@ -55,7 +68,8 @@ export default function AdminMeritPage() {
       const response = await AdminMeritService.getUserMeritHistory(
         historyUser.user_id,
         historyPage,
-        10
+        10,
+        seasonId || undefined
       );
       setHistory(response.history);
       setHistoryTotalPages(response.total_pages);
@@ -64,7 +78,7 @@ export default function AdminMeritPage() {
     } finally {
       setHistoryLoading(false);
     }
-  }, [historyUser, historyPage]);
+  }, [historyUser, historyPage, seasonId]);
 
   useEffect(() => {
     if (historyUser) {
@@ -101,6 +115,27 @@ export default function AdminMeritPage() {
     }
   };
 
+  const handleDownloadSeasonReport = async () => {
+    const season = seasons.find((s) => s.id === seasonId);
+    if (!season) return;
+
+    setIsDownloadingReport(true);
+    setError(null);
+    try {
+      const blob = await AdminSeasonService.downloadSeasonReport(season.id);
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `season-report-${season.name}.pdf`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : 'Failed to generate season report');
+    } finally {
+      setIsDownloadingReport(false);
+    }
+  };
+
   const formatDateTime = (dateString: string) => {
     return new Date(dateString).toLocaleString('en-US', {
       year: 'numeric',
@@ -142,6 +177,42 @@ export default function AdminMeritPage() {
           </div>
         )}
 
+        {/* Season filter */}
+        {seasons.length > 0 && (
+          <div className="mb-6 bg-white shadow rounded-lg p-4">
+            <label htmlFor="season" className="block text-sm font-medium text-gray-700 mb-1">
+              Season
+            </label>
+            <select
+              id="season"
+              value={seasonId}
+              onChange={(e) => {
+                setSeasonId(e.target.value);
+                setCurrentPage(1);
+              }}
+              className="block w-full sm:w-64 rounded-md border-gray-300 shadow-sm focus:border-indigo-500 focus:ring-indigo-500 sm:text-sm"
+            >
+              <option value="">All time</option>
+              {seasons.map((season) => (
+                <option key={season.id} value={season.id}>
+                  {season.name}
+                  {season.closed_at ? ' (closed)' : ''}
+                </option>
+              ))}
+            </select>
+            {seasonId && (
+              <button
+                type="button"
+                onClick={handleDownloadSeasonReport}
+                disabled={isDownloadingReport}
+                className="mt-3 inline-flex items-center px-3 py-1.5 border border-gray-300 text-sm font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 disabled:opacity-50"
+              >
+                {isDownloadingReport ? 'Generating…' : '📄 Season Report (PDF)'}
+              </button>
+            )}
+          </div>
+        )}
+
         {/* Stats */}
         <div className="mb-6 bg-white shadow rounded-lg p-6">
           <div className="grid grid-cols-1 sm:grid-cols-3 gap-4">
@@ -183,6 +254,11 @@ export default function AdminMeritPage() {
                     <th className="px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider">
                       Merit Points
                     </th>
+                    {seasonId && (
+                      <th className="px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider">
+                        Season Merit
+                      </th>
+                    )}
                     <th className="px-6 py-3 text-right text-xs font-medium text-gray-500 uppercase tracking-wider">
                       Actions
                     </th>
@@ -212,6 +288,11 @@ export default function AdminMeritPage() {
                           {user.merit_points}
                         </span>
                       </td>
+                      {seasonId && (
+                        <td className="px-6 py-4 whitespace-nowrap text-sm text-gray-900">
+                          {user.season_merit ?? 0}
+                        </td>
+                      )}
                       <td className="px-6 py-4 whitespace-nowrap text-right text-sm font-medium space-x-2">
                         <button
                           onClick={() => setSelectedUser(user)}
@@ -16,6 +16,7 @@ export default function AttendanceDashboardPage() {
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [isAdmin, setIsAdmin] = useState<boolean | null>(null);
+  const [currentPage, setCurrentPage] = useState(1);
 
   useEffect(() => {
     const checkAdminAndLoad = async () => {
@@ -24,9 +25,7 @@ export default function AttendanceDashboardPage() {
         setIsAdmin(response.is_admin);
         if (!response.is_admin) {
           navigate('/events');
-          return;
         }
-        await loadData();
       } catch {
         setIsAdmin(false);
         navigate('/events');
@@ -35,11 +34,17 @@ export default function AttendanceDashboardPage() {
     checkAdminAndLoad();
   }, [navigate]);
 
+  useEffect(() => {
+    if (isAdmin) {
+      loadData();
+    }
+  }, [isAdmin, currentPage]);
+
   const loadData = async () => {
     setIsLoading(true);
     setError(null);
     try {
-      const response = await AttendanceService.getAttendanceMatrix();
+      const response = await AttendanceService.getAttendanceMatrix(currentPage);
       setData(response);
     } catch (err) {
       setError('Failed to load attendance data. Please try again.');
@@ -105,7 +110,15 @@ export default function AttendanceDashboardPage() {
         <MostReliableUsers users={data.aggregate_stats.most_reliable_users} />
 
         {/* Attendance Matrix */}
-        <AttendanceMatrix events={data.events} rows={data.rows} />
+        <AttendanceMatrix
+          events={data.events}
+          rows={data.rows}
+          page={data.page}
+          perPage={data.per_page}
+          totalPages={data.total_pages}
+          totalUsers={data.aggregate_stats.total_users}
+          onPageChange={setCurrentPage}
+        />
       </div>
     </div>
   );
@@ -279,7 +292,23 @@ function MostReliableUsers({ users }: { users: { user_id: string; username: stri
   );
 }
 
-function AttendanceMatrix({ events, rows }: { events: EventSummary[]; rows: AttendanceMatrixRow[] }) {
+function AttendanceMatrix({
+  events,
+  rows,
+  page,
+  perPage,
+  totalPages,
+  totalUsers,
+  onPageChange,
+}: {
+  events: EventSummary[];
+  rows: AttendanceMatrixRow[];
+  page: number;
+  perPage: number;
+  totalPages: number;
+  totalUsers: number;
+  onPageChange: (page: number) => void;
+}) {
   const formatDate = (dateStr: string) => {
     const date = new Date(dateStr);
     return date.toLocaleDateString('en-US', { month: 'short', day: 'numeric' });
@@ -293,6 +322,8 @@ function AttendanceMatrix({ events, rows }: { events: EventSummary[]; rows: Atte
         return 'bg-yellow-400 text-yellow-900';
       case 'unavailable':
         return 'bg-red-400 text-white';
+      case 'excused':
+        return 'bg-blue-300 text-blue-900';
       case 'no_response':
       default:
         return 'bg-gray-200 text-gray-500';
@@ -307,6 +338,8 @@ function AttendanceMatrix({ events, rows }: { events: EventSummary[]; rows: Atte
         return 'A';
       case 'unavailable':
         return '✗';
+      case 'excused':
+        return 'E';
       case 'no_response':
       default:
         return '—';
@@ -336,6 +369,9 @@ function AttendanceMatrix({ events, rows }: { events: EventSummary[]; rows: Atte
           <span className="flex items-center gap-1">
             <span className="w-4 h-4 bg-red-400 rounded"></span> Unavailable
           </span>
+          <span className="flex items-center gap-1">
+            <span className="w-4 h-4 bg-blue-300 rounded"></span> Excused
+          </span>
           <span className="flex items-center gap-1">
             <span className="w-4 h-4 bg-gray-200 rounded"></span> No Response
           </span>
@@ -436,6 +472,32 @@ function AttendanceMatrix({ events, rows }: { events: EventSummary[]; rows: Atte
             </tfoot>
           </table>
         </div>
+
+        {/* Pagination */}
+        {totalPages > 1 && (
+          <div className="bg-gray-50 px-6 py-3 flex items-center justify-between border-t border-gray-200">
+            <div className="text-sm text-gray-500">
+              Showing {(page - 1) * perPage + 1} to {Math.min(page * perPage, totalUsers)} of{' '}
+              {totalUsers} users
+            </div>
+            <div className="flex space-x-2">
+              <button
+                onClick={() => onPageChange(Math.max(1, page - 1))}
+                disabled={page === 1}
+                className="px-3 py-1 text-sm border rounded disabled:opacity-50 disabled:cursor-not-allowed hover:bg-gray-100"
+              >
+                Previous
+              </button>
+              <button
+                onClick={() => onPageChange(Math.min(totalPages, page + 1))}
+                disabled={page === totalPages}
+                className="px-3 py-1 text-sm border rounded disabled:opacity-50 disabled:cursor-not-allowed hover:bg-gray-100"
+              >
+                Next
+              </button>
+            </div>
+          </div>
+        )}
       </div>
     </div>
   );
@@ -111,6 +111,21 @@ export default function EventDetailPage() {
     }
   };
 
+  const handlePayFee = async () => {
+    if (!eventId) return;
+
+    setActionLoading('payment');
+    setError(null);
+
+    try {
+      const { checkout_url } = await AttendanceService.startCheckout(eventId);
+      window.location.href = checkout_url;
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : 'Failed to start the payment');
+      setActionLoading(null);
+    }
+  };
+
   const handleCheckIn = async (userId: string, isCheckedIn: boolean) => {
     if (!eventId) return;
 
@@ -188,6 +203,44 @@ export default function EventDetailPage() {
     }
   };
 
+  const handleDownloadReport = async () => {
+    if (!eventId || !event) return;
+
+    setActionLoading('download-report');
+    setError(null);
+
+    try {
+      const blob = await AttendanceService.downloadEventReport(eventId);
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `attendance-${event.event_date.slice(0, 10)}.pdf`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : 'Failed to download report');
+    } finally {
+      setActionLoading(null);
+    }
+  };
+
+  const handleSendReport = async () => {
+    if (!eventId) return;
+
+    setActionLoading('send-report');
+    setError(null);
+    setSuccessMessage(null);
+
+    try {
+      const response = await AttendanceService.sendEventReport(eventId);
+      setSuccessMessage(`Report emailed to ${response.recipients.join(', ')}.`);
+    } catch (err: unknown) {
+      setError(err instanceof Error ? err.message : 'Failed to send report');
+    } finally {
+      setActionLoading(null);
+    }
+  };
+
   const handleDeleteEvent = async () => {
     if (!eventId) return;
 
@@ -259,6 +312,8 @@ export default function EventDetailPage() {
 
   const isAvailable = myAttendance?.is_available ?? false;
   const isCheckedIn = myAttendance?.is_checked_in ?? false;
+  const paymentStatus = myAttendance?.payment_status ?? 'unpaid';
+  const feeSettled = paymentStatus === 'paid' || paymentStatus === 'waived';
 
   return (
     <div className="min-h-screen bg-gray-50 py-8">
@@ -316,6 +371,20 @@ export default function EventDetailPage() {
                 >
                   {actionLoading === 'lock' ? '...' : event.is_locked ? 'Unlock' : 'Lock'}
                 </button>
+                <button
+                  onClick={handleDownloadReport}
+                  disabled={actionLoading === 'download-report'}
+                  className="px-3 py-1 text-sm font-medium rounded-md border border-gray-300 text-gray-700 hover:bg-gray-50"
+                >
+                  {actionLoading === 'download-report' ? '...' : 'Report PDF'}
+                </button>
+                <button
+                  onClick={handleSendReport}
+                  disabled={actionLoading === 'send-report'}
+                  className="px-3 py-1 text-sm font-medium rounded-md border border-gray-300 text-gray-700 hover:bg-gray-50"
+                >
+                  {actionLoading === 'send-report' ? '...' : 'Email Report'}
+                </button>
                 <button
                   onClick={handleDeleteEvent}
                   disabled={actionLoading === 'delete'}
@@ -350,6 +419,33 @@ export default function EventDetailPage() {
               </div>
             )}
           </div>
+          {event.tags.length > 0 && (
+            <div className="flex flex-wrap gap-2 mt-4">
+              {event.tags.map((tag) => (
+                <Link
+                  key={tag}
+                  to={`/events?tags=${encodeURIComponent(tag)}`}
+                  className="px-2 py-0.5 text-xs font-medium rounded-full bg-gray-100 text-gray-700 hover:bg-gray-200"
+                >
+                  #{tag}
+                </Link>
+              ))}
+            </div>
+          )}
+          {event.staff && event.staff.length > 0 && (
+            <div className="mt-4 text-sm text-gray-600">
+              <span className="font-medium text-gray-700">Running this event: </span>
+              {event.staff.map((member, index) => (
+                <span key={member.user_id}>
+                  {index > 0 && ', '}
+                  <Link to={`/users/${member.username}`} className="text-indigo-600 hover:text-indigo-800">
+                    {member.username}
+                  </Link>{' '}
+                  ({member.role})
+                </span>
+              ))}
+            </div>
+          )}
         </div>
 
         {/* Stats */}
@@ -410,6 +506,31 @@ export default function EventDetailPage() {
               <p className="text-sm text-gray-500">Event is locked - attendance cannot be changed.</p>
             )}
           </div>
+          {event.fee && (
+            <div className="mt-4 flex flex-wrap items-center gap-4 text-sm">
+              <span className="text-gray-700">
+                Fee: {(event.fee.amount_minor / 100).toFixed(2)} {event.fee.currency}
+                {event.fee.description && ` (${event.fee.description})`}
+              </span>
+              <span className={`px-3 py-1 rounded-full font-medium ${
+                feeSettled ? 'bg-green-100 text-green-800' : 'bg-yellow-100 text-yellow-800'
+              }`}>
+                {paymentStatus === 'paid' && '✓ Paid'}
+                {paymentStatus === 'waived' && '✓ Waived'}
+                {paymentStatus === 'pending' && 'Payment pending'}
+                {paymentStatus === 'unpaid' && 'Unpaid'}
+              </span>
+              {!feeSettled && (
+                <button
+                  onClick={handlePayFee}
+                  disabled={actionLoading === 'payment'}
+                  className="px-4 py-2 text-sm font-medium rounded-md bg-indigo-600 text-white hover:bg-indigo-700"
+                >
+                  {actionLoading === 'payment' ? '...' : 'Pay online'}
+                </button>
+              )}
+            </div>
+          )}
         </div>
 
         {/* My Matches - shown to users who are allocated to matches */}
@@ -14,6 +14,7 @@ export default function EventFormPage() {
   const [eventDate, setEventDate] = useState('');
   const [eventTime, setEventTime] = useState('');
   const [location, setLocation] = useState('');
+  const [tags, setTags] = useState('');
   const [isLoading, setIsLoading] = useState(false);
   const [isFetching, setIsFetching] = useState(isEditing);
   const [error, setError] = useState<string | null>(null);
@@ -27,6 +28,7 @@ export default function EventFormPage() {
           setDescription(event.description || '');
           setEventType(event.event_type);
           setLocation(event.location || '');
+          setTags(event.tags.join(', '));
 
           // Parse date and time
           const date = new Date(event.event_date);
@@ -52,6 +54,10 @@ export default function EventFormPage() {
       // Combine date and time into ISO string
       const dateTimeString = `${eventDate}T${eventTime}:00`;
       const eventDateTime = new Date(dateTimeString).toISOString();
+      const tagList = tags
+        .split(',')
+        .map((tag) => tag.trim())
+        .filter(Boolean);
 
       if (isEditing && eventId) {
         const data: UpdateEventRequest = {
@@ -60,6 +66,7 @@ export default function EventFormPage() {
           event_type: eventType,
           event_date: eventDateTime,
           location: location || undefined,
+          tags: tagList,
         };
         await AttendanceService.updateEvent(eventId, data);
         navigate(`/events/${eventId}`);
@@ -70,6 +77,7 @@ export default function EventFormPage() {
           event_type: eventType,
           event_date: eventDateTime,
           location: location || undefined,
+          tags: tagList,
         };
         const response = await AttendanceService.createEvent(data);
         navigate(`/events/${response.event.id}`);
@@ -209,6 +217,22 @@ export default function EventFormPage() {
               />
             </div>
 
+            {/* Tags */}
+            <div>
+              <label htmlFor="tags" className="block text-sm font-medium text-gray-700 mb-1">
+                Tags
+              </label>
+              <input
+                type="text"
+                id="tags"
+                value={tags}
+                onChange={(e) => setTags(e.target.value)}
+                className="block w-full rounded-md border-gray-300 shadow-sm focus:border-indigo-500 focus:ring-indigo-500"
+                placeholder="novice, british parliamentary"
+              />
+              <p className="mt-1 text-xs text-gray-500">Separate tags with commas</p>
+            </div>
+
             {/* Submit Button */}
             <div className="flex gap-4">
               <button
@@ -1,8 +1,8 @@
 import { useState, useEffect, useCallback } from 'react';
-import { Link } from 'react-router-dom';
+import { Link, useSearchParams } from 'react-router-dom';
 import { AttendanceService } from '../services/attendance';
 import { AdminService } from '../services/admin';
-import type { Event, EventType } from '../services/types';
+import type { Event, EventCategory, EventType } from '../services/types';
 
 export default function EventsPage() {
   const [events, setEvents] = useState<Event[]>([]);
@@ -13,8 +13,18 @@ export default function EventsPage() {
   const [isAdmin, setIsAdmin] = useState(false);
   const [eventTypeFilter, setEventTypeFilter] = useState<string>('');
   const [upcomingOnly, setUpcomingOnly] = useState(true);
+  const [categories, setCategories] = useState<EventCategory[]>([]);
+  const [categoryFilter, setCategoryFilter] = useState<string>('');
+  const [searchParams, setSearchParams] = useSearchParams();
+  const tagFilter = searchParams.get('tags') || '';
   const perPage = 10;
 
+  useEffect(() => {
+    AttendanceService.listCategories()
+      .then((response) => setCategories(response.categories))
+      .catch((err) => console.error('Failed to load categories:', err));
+  }, []);
+
   useEffect(() => {
     const checkAdmin = async () => {
       try {
@@ -31,12 +41,12 @@ export default function EventsPage() {
     setIsLoading(true);
     setError(null);
     try {
-      const response = await AttendanceService.listEvents(
-        currentPage,
-        perPage,
-        eventTypeFilter || undefined,
-        upcomingOnly
-      );
+      const response = await AttendanceService.listEvents(currentPage, perPage, {
+        eventType: eventTypeFilter || undefined,
+        upcomingOnly,
+        categoryId: categoryFilter || undefined,
+        tags: tagFilter ? tagFilter.split(',') : undefined,
+      });
       setEvents(response.events);
       setTotalPages(response.total_pages);
     } catch (err) {
@@ -45,7 +55,7 @@ export default function EventsPage() {
     } finally {
       setIsLoading(false);
     }
-  }, [currentPage, eventTypeFilter, upcomingOnly]);
+  }, [currentPage, eventTypeFilter, upcomingOnly, categoryFilter, tagFilter]);
 
   useEffect(() => {
     loadEvents();
@@ -124,6 +134,29 @@ export default function EventsPage() {
                 <option value="other">Other</option>
               </select>
             </div>
+            {categories.length > 0 && (
+              <div>
+                <label htmlFor="category" className="block text-sm font-medium text-gray-700 mb-1">
+                  Category
+                </label>
+                <select
+                  id="category"
+                  value={categoryFilter}
+                  onChange={(e) => {
+                    setCategoryFilter(e.target.value);
+                    setCurrentPage(1);
+                  }}
+                  className="block w-full rounded-md border-gray-300 shadow-sm focus:border-indigo-500 focus:ring-indigo-500 sm:text-sm"
+                >
+                  <option value="">All Categories</option>
+                  {categories.map((category) => (
+                    <option key={category.id} value={category.id}>
+                      {category.path}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            )}
             <div className="flex items-center pt-6">
               <input
                 id="upcomingOnly"
@@ -139,6 +172,26 @@ export default function EventsPage() {
                 Upcoming events only
               </label>
             </div>
+            {tagFilter && (
+              <div className="flex items-center gap-2 pt-6 text-sm text-gray-700">
+                Tagged
+                {tagFilter.split(',').map((tag) => (
+                  <span key={tag} className="px-2 py-0.5 text-xs font-medium rounded-full bg-gray-100">
+                    #{tag}
+                  </span>
+                ))}
+                <button
+                  type="button"
+                  onClick={() => {
+                    setSearchParams({});
+                    setCurrentPage(1);
+                  }}
+                  className="text-indigo-600 hover:text-indigo-800"
+                >
+                  Clear
+                </button>
+              </div>
+            )}
           </div>
         </div>
 
@@ -150,7 +150,7 @@ export default function Home() {
       setEventLoading(true);
       try {
         // Get upcoming events
-        const eventsResponse = await AttendanceService.listEvents(1, 1, undefined, true);
+        const eventsResponse = await AttendanceService.listEvents(1, 1, { upcomingOnly: true });
         
         if (eventsResponse.events.length === 0) {
           setUpcomingEvent(null);
@@ -16,7 +16,7 @@ import type {
   AllocationRole,
 } from '../services/types';
 
-type DragItem = CheckedInUserInfo | { guest_name: string; isGuest: true };
+type DragItem = CheckedInUserInfo | { guest_name: string; isGuest: true; walk_in_id?: string };
 
 export default function MatchDetailPage() {
   const { matchId } = useParams<{ matchId: string }>();
@@ -94,6 +94,7 @@ export default function MatchDetailPage() {
         match_id: matchId,
         user_id: isGuest ? undefined : (draggedUser as CheckedInUserInfo).user_id,
         guest_name: isGuest ? draggedUser.guest_name : undefined,
+        walk_in_id: isGuest ? draggedUser.walk_in_id : undefined,
         role: 'speaker',
         team_id: teamId,
         two_team_speaker_role: twoTeamRole,
@@ -114,6 +115,7 @@ export default function MatchDetailPage() {
         match_id: matchId,
         user_id: isGuest ? undefined : (draggedUser as CheckedInUserInfo).user_id,
         guest_name: isGuest ? draggedUser.guest_name : undefined,
+        walk_in_id: isGuest ? draggedUser.walk_in_id : undefined,
         role: isVoting ? 'voting_adjudicator' : 'non_voting_adjudicator',
         is_chair: isChair,
       });
@@ -132,6 +134,7 @@ export default function MatchDetailPage() {
         match_id: matchId,
         user_id: isGuest ? undefined : (draggedUser as CheckedInUserInfo).user_id,
         guest_name: isGuest ? draggedUser.guest_name : undefined,
+        walk_in_id: isGuest ? draggedUser.walk_in_id : undefined,
         role: 'resource',
       });
       await loadMatchData();
@@ -222,6 +225,7 @@ export default function MatchDetailPage() {
 
   // Get all users from allocation pool - allow multiple allocations for friendly matches
   const availableUsers = allocationPool?.checked_in_users || [];
+  const walkIns = allocationPool?.walk_ins || [];
 
   const isFourTeam = series?.team_format === 'four_team';
 
@@ -294,7 +298,7 @@ export default function MatchDetailPage() {
                 <h2 className="text-lg font-semibold flex items-center gap-2">
                   Available Participants
                   <span className="text-sm font-normal text-gray-500">
-                    ({availableUsers.length})
+                    ({availableUsers.length + walkIns.length})
                   </span>
                 </h2>
                 <button
@@ -306,7 +310,7 @@ export default function MatchDetailPage() {
               </div>
               
               <div className="space-y-2 max-h-[calc(100vh-300px)] overflow-y-auto">
-                {availableUsers.length === 0 ? (
+                {availableUsers.length + walkIns.length === 0 ? (
                   <p className="text-gray-500 text-sm text-center py-4">
                     No available participants
                   </p>
@@ -330,6 +334,11 @@ export default function MatchDetailPage() {
                             In use
                           </span>
                         )}
+                        {(user.payment_status === 'unpaid' || user.payment_status === 'pending') && (
+                          <span className="text-xs px-1.5 py-0.5 bg-red-100 text-red-800 rounded">
+                            Unpaid
+                          </span>
+                        )}
                       </div>
                       <div className="text-xs text-gray-500">
                         {user.checked_in_at 
@@ -344,6 +353,36 @@ export default function MatchDetailPage() {
                     </div>
                   ))
                 )}
+                {walkIns.map(walkIn => (
+                  <div
+                    key={walkIn.walk_in_id}
+                    draggable
+                    onDragStart={() =>
+                      handleDragStart({ guest_name: walkIn.name, isGuest: true, walk_in_id: walkIn.walk_in_id })
+                    }
+                    onDragEnd={handleDragEnd}
+                    className={`p-3 rounded-lg cursor-grab hover:bg-gray-100 transition-colors border ${
+                      walkIn.is_allocated
+                        ? 'bg-yellow-50 border-yellow-300'
+                        : 'bg-gray-50 border-gray-200'
+                    }`}
+                  >
+                    <div className="flex items-center justify-between">
+                      <div className="font-medium text-sm">{walkIn.name}</div>
+                      <span className="text-xs px-1.5 py-0.5 bg-indigo-100 text-indigo-800 rounded">
+                        {walkIn.is_allocated ? 'In use' : 'Walk-in'}
+                      </span>
+                    </div>
+                    <div className="text-xs text-gray-500">
+                      Registered at {new Date(walkIn.registered_at).toLocaleTimeString()}
+                    </div>
+                    {walkIn.current_allocation && (
+                      <div className="text-xs text-yellow-700 mt-1">
+                        {walkIn.current_allocation.role.replace('_', ' ')} in {walkIn.current_allocation.room_name || 'another match'}
+                      </div>
+                    )}
+                  </div>
+                ))}
               </div>
             </div>
 
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { useParams, Link } from 'react-router-dom';
+import { useParams, Link, useNavigate } from 'react-router-dom';
 import { useAuth } from '../context/AuthContext';
 import { ProfileService, MeritService, AwardService } from '../services/merit';
 import type {
@@ -68,6 +68,7 @@ function AwardCard({ award }: { award: AwardResponse }) {
 export default function ProfilePage() {
   const { username } = useParams<{ username: string }>();
   const { user } = useAuth();
+  const navigate = useNavigate();
   const [profile, setProfile] = useState<ProfileResponse | null>(null);
   const [meritHistory, setMeritHistory] = useState<MeritHistoryEntry[]>([]);
   const [awards, setAwards] = useState<AwardResponse[]>([]);
@@ -91,6 +92,11 @@ export default function ProfilePage() {
       
       try {
         const profileData = await ProfileService.getProfile(username);
+        if (profileData.redirect_to) {
+          // Linked by a username the member has since changed
+          navigate(`/users/${profileData.redirect_to}`, { replace: true });
+          return;
+        }
         setProfile(profileData);
       } catch (err) {
         setError(err instanceof Error ? err.message : 'Failed to load profile');
@@ -100,7 +106,7 @@ export default function ProfilePage() {
     };
 
     loadProfile();
-  }, [username]);
+  }, [username, navigate]);
 
   // Load awards (public - visible on all profiles)
   useEffect(() => {
@@ -3,6 +3,9 @@ import { TokenManager } from './tokenManager';
 import type {
   Event,
   EventListResponse,
+  EventListFilters,
+  EventCategory,
+  TagCount,
   EventAttendanceResponse,
   CreateEventRequest,
   UpdateEventRequest,
@@ -32,14 +35,12 @@ class AttendanceHttpClient {
       ...options.headers,
     };
 
-    const accessToken = TokenManager.getAccessToken();
-    if (accessToken) {
-      (headers as Record<string, string>)['Authorization'] = `Bearer ${accessToken}`;
-    }
+    Object.assign(headers, TokenManager.authHeaders(options.method));
 
     const response = await fetch(url, {
       ...options,
       headers,
+      credentials: TokenManager.credentials(),
     });
 
     if (!response.ok) {
@@ -68,9 +69,30 @@ class AttendanceHttpClient {
     });
   }
 
+  async put<T>(endpoint: string, data?: unknown): Promise<T> {
+    return this.request<T>(endpoint, {
+      method: 'PUT',
+      body: data ? JSON.stringify(data) : undefined,
+    });
+  }
+
   async delete<T>(endpoint: string): Promise<T> {
     return this.request<T>(endpoint, { method: 'DELETE' });
   }
+
+  async download(endpoint: string): Promise<Blob> {
+    const response = await fetch(`${this.baseUrl}${endpoint}`, {
+      headers: TokenManager.authHeaders('GET'),
+      credentials: TokenManager.credentials(),
+    });
+
+    if (!response.ok) {
+      const errorData = await response.json().catch(() => ({ error: 'Download failed' }));
+      throw new Error(errorData.error || `HTTP error! status: ${response.status}`);
+    }
+
+    return response.blob();
+  }
 }
 
 const httpClient = new AttendanceHttpClient();
@@ -84,12 +106,13 @@ export class AttendanceService {
   static async listEvents(
     page = 1,
     perPage = 20,
-    eventType?: string,
-    upcomingOnly = false
+    filters: EventListFilters = {}
   ): Promise<EventListResponse> {
     let url = `/events?page=${page}&per_page=${perPage}`;
-    if (eventType) url += `&event_type=${eventType}`;
-    if (upcomingOnly) url += `&upcoming_only=true`;
+    if (filters.eventType) url += `&event_type=${filters.eventType}`;
+    if (filters.upcomingOnly) url += `&upcoming_only=true`;
+    if (filters.categoryId) url += `&category_id=${filters.categoryId}`;
+    if (filters.tags?.length) url += `&tags=${encodeURIComponent(filters.tags.join(','))}`;
     return httpClient.get<EventListResponse>(url);
   }
 
@@ -114,6 +137,61 @@ export class AttendanceService {
     return httpClient.post<{ message: string; event: Event }>(`/events/${eventId}/lock`, data);
   }
 
+  static async downloadEventReport(eventId: string, format: 'pdf' | 'csv' = 'pdf'): Promise<Blob> {
+    return httpClient.download(`/admin/events/${eventId}/report?format=${format}`);
+  }
+
+  static async sendEventReport(eventId: string): Promise<{ message: string; recipients: string[] }> {
+    return httpClient.post<{ message: string; recipients: string[] }>(
+      `/admin/events/${eventId}/send-report`
+    );
+  }
+
+  static async setEventTags(eventId: string, tags: string[]): Promise<{ message: string; event: Event }> {
+    return httpClient.put<{ message: string; event: Event }>(`/events/${eventId}/tags`, { tags });
+  }
+
+  // ========================================================================
+  // Category and Tag Methods
+  // ========================================================================
+
+  static async listCategories(): Promise<{ categories: EventCategory[] }> {
+    return httpClient.get<{ categories: EventCategory[] }>('/event-categories');
+  }
+
+  static async createCategory(name: string, parentId?: string): Promise<{ message: string; id: string }> {
+    return httpClient.post<{ message: string; id: string }>('/admin/event-categories', {
+      name,
+      parent_id: parentId,
+    });
+  }
+
+  static async updateCategory(
+    categoryId: string,
+    data: { name?: string; parent_id?: string; move_to_top?: boolean }
+  ): Promise<{ message: string }> {
+    return httpClient.patch<{ message: string }>(`/admin/event-categories/${categoryId}`, data);
+  }
+
+  static async deleteCategory(categoryId: string): Promise<{ message: string }> {
+    return httpClient.delete<{ message: string }>(`/admin/event-categories/${categoryId}`);
+  }
+
+  static async listTags(): Promise<{ tags: TagCount[] }> {
+    return httpClient.get<{ tags: TagCount[] }>('/tags');
+  }
+
+  static async renameTag(tag: string, to: string): Promise<{ message: string; tag: string; events: number }> {
+    return httpClient.post<{ message: string; tag: string; events: number }>(
+      `/admin/tags/${encodeURIComponent(tag)}/rename`,
+      { to }
+    );
+  }
+
+  static async deleteTag(tag: string): Promise<{ message: string; events: number }> {
+    return httpClient.delete<{ message: string; events: number }>(`/admin/tags/${encodeURIComponent(tag)}`);
+  }
+
   // ========================================================================
   // Attendance Methods
   // ========================================================================
@@ -126,6 +204,10 @@ export class AttendanceService {
     return httpClient.get<AttendanceRecord>(`/events/${eventId}/my-attendance`);
   }
 
+  static async startCheckout(eventId: string): Promise<{ checkout_url: string }> {
+    return httpClient.post<{ checkout_url: string }>(`/events/${eventId}/checkout`);
+  }
+
   static async setAvailability(eventId: string, isAvailable: boolean): Promise<{ message: string; attendance: AttendanceRecord }> {
     const data: SetAvailabilityRequest = { is_available: isAvailable };
     return httpClient.post<{ message: string; attendance: AttendanceRecord }>(`/events/${eventId}/availability`, data);
@@ -152,7 +234,9 @@ export class AttendanceService {
   // Dashboard/Matrix Methods (Admin only)
   // ========================================================================
 
-  static async getAttendanceMatrix(): Promise<AttendanceMatrixResponse> {
-    return httpClient.get<AttendanceMatrixResponse>('/attendance/matrix');
+  static async getAttendanceMatrix(page = 1, perPage = 50): Promise<AttendanceMatrixResponse> {
+    return httpClient.get<AttendanceMatrixResponse>(
+      `/attendance/matrix?page=${page}&per_page=${perPage}`
+    );
   }
 }
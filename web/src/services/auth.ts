@@ -72,7 +72,7 @@ export class AuthService {
   }
 
   static isAuthenticated(): boolean {
-    return TokenManager.getAccessToken() !== null;
+    return TokenManager.hasSession();
   }
 
   static getStoredUser(): UserResponse | null {
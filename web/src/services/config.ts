@@ -15,3 +15,7 @@ export const AUTH_API_URL = `${API_BASE_URL}/api/auth`;
 export const ATTENDANCE_API_URL = `${API_BASE_URL}/api/attendance`;
 export const MERIT_API_URL = `${API_BASE_URL}/api/merit`;
 export const TABULATION_API_URL = `${API_BASE_URL}/api/tabulation`;
+
+// Set VITE_AUTH_COOKIES=true when the auth service runs with
+// TOKEN_DELIVERY=cookie: tokens then live in httpOnly cookies, not localStorage
+export const AUTH_COOKIES = import.meta.env.VITE_AUTH_COOKIES === 'true';
@@ -1,4 +1,4 @@
-import { AUTH_API_URL } from './config';
+import { AUTH_API_URL, AUTH_COOKIES } from './config';
 import { TokenManager } from './tokenManager';
 import type { ApiError } from './types';
 
@@ -7,17 +7,20 @@ import type { ApiError } from './types';
 export class HttpClient {
   private static async refreshAccessToken(): Promise<boolean> {
     const refreshToken = TokenManager.getRefreshToken();
-    if (!refreshToken) {
+    if (!refreshToken && !AUTH_COOKIES) {
       return false;
     }
 
     try {
+      // With cookie delivery the refresh token cookie goes instead of a body
       const response = await fetch(`${AUTH_API_URL}/refresh`, {
         method: 'POST',
         headers: {
           'Content-Type': 'application/json',
+          'X-Device-Id': TokenManager.getDeviceId(),
         },
-        body: JSON.stringify({ refresh_token: refreshToken }),
+        body: refreshToken ? JSON.stringify({ refresh_token: refreshToken }) : undefined,
+        credentials: TokenManager.credentials(),
       });
 
       if (!response.ok) {
@@ -26,8 +29,14 @@ export class HttpClient {
       }
 
       const data = await response.json();
-      TokenManager.updateAccessToken(data.access_token);
-      TokenManager.updateRefreshToken(data.refresh_token);
+      if (data.access_token && data.refresh_token) {
+        TokenManager.updateAccessToken(data.access_token);
+        TokenManager.updateRefreshToken(data.refresh_token);
+      }
+      // Each refresh renews the session's CSRF token
+      if (data.csrf_token) {
+        TokenManager.updateCsrfToken(data.csrf_token);
+      }
       return true;
     } catch (error) {
       TokenManager.clearTokens();
@@ -40,10 +49,12 @@ export class HttpClient {
     options: RequestInit = {}
   ): Promise<T> {
     const accessToken = TokenManager.getAccessToken();
+    const hasSession = TokenManager.hasSession();
     let csrfToken = TokenManager.getCsrfToken();
 
     const headers: Record<string, string> = {
       'Content-Type': 'application/json',
+      'X-Device-Id': TokenManager.getDeviceId(),
       ...(options.headers as Record<string, string>),
     };
 
@@ -52,14 +63,13 @@ export class HttpClient {
     }
 
     // For non-GET requests, ensure we have a CSRF token
-    if (options.method !== 'GET' && !csrfToken && accessToken) {
+    if (options.method !== 'GET' && !csrfToken && hasSession) {
       try {
         // Fetch CSRF token if we don't have one but are authenticated
         const response = await fetch(`${AUTH_API_URL}/csrf-token`, {
           method: 'GET',
-          headers: {
-            'Authorization': `Bearer ${accessToken}`,
-          },
+          headers: TokenManager.authHeaders(),
+          credentials: TokenManager.credentials(),
         });
         if (response.ok) {
           const data = await response.json();
@@ -78,21 +88,27 @@ export class HttpClient {
     let response = await fetch(`${AUTH_API_URL}${endpoint}`, {
       ...options,
       headers,
+      credentials: TokenManager.credentials(),
     });
 
     // If token expired, try to refresh
-    if (response.status === 401 && accessToken) {
+    if (response.status === 401 && hasSession) {
       const refreshed = await this.refreshAccessToken();
       if (refreshed) {
         // Retry the original request with new token
         const newAccessToken = TokenManager.getAccessToken();
         if (newAccessToken) {
           headers['Authorization'] = `Bearer ${newAccessToken}`;
-          response = await fetch(`${AUTH_API_URL}${endpoint}`, {
-            ...options,
-            headers,
-          });
         }
+        const newCsrfToken = TokenManager.getCsrfToken();
+        if (newCsrfToken && options.method !== 'GET') {
+          headers['X-CSRF-Token'] = newCsrfToken;
+        }
+        response = await fetch(`${AUTH_API_URL}${endpoint}`, {
+          ...options,
+          headers,
+          credentials: TokenManager.credentials(),
+        });
       }
     }
 
@@ -15,8 +15,15 @@ import type {
   UpgradeAwardRequest,
   EditAwardRequest,
   AdminAwardListResponse,
+  Season,
+  SeasonStandingsResponse,
+  SeasonReport,
+  CreateSeasonRequest,
+  UpdateSeasonRequest,
 } from './types';
 
+const seasonParam = (seasonId?: string) => (seasonId ? `&season_id=${seasonId}` : '');
+
 class MeritHttpClient {
   private baseUrl: string;
 
@@ -35,14 +42,12 @@ class MeritHttpClient {
       ...options.headers,
     };
 
-    const accessToken = TokenManager.getAccessToken();
-    if (accessToken) {
-      (headers as Record<string, string>)['Authorization'] = `Bearer ${accessToken}`;
-    }
+    Object.assign(headers, TokenManager.authHeaders(options.method));
 
     const response = await fetch(url, {
       ...options,
       headers,
+      credentials: TokenManager.credentials(),
     });
 
     if (!response.ok) {
@@ -77,6 +82,25 @@ class MeritHttpClient {
       body: data ? JSON.stringify(data) : undefined,
     });
   }
+
+  async delete<T>(endpoint: string): Promise<T> {
+    return this.request<T>(endpoint, { method: 'DELETE' });
+  }
+
+  async downloadPost(endpoint: string): Promise<Blob> {
+    const response = await fetch(`${this.baseUrl}${endpoint}`, {
+      method: 'POST',
+      headers: TokenManager.authHeaders('POST'),
+      credentials: TokenManager.credentials(),
+    });
+
+    if (!response.ok) {
+      const errorData = await response.json().catch(() => ({ error: 'Download failed' }));
+      throw new Error(errorData.error || `HTTP error! status: ${response.status}`);
+    }
+
+    return response.blob();
+  }
 }
 
 const httpClient = new MeritHttpClient();
@@ -134,11 +158,22 @@ export class MeritService {
   /**
    * Get current user's merit history
    */
-  static async getMyMeritHistory(page = 1, perPage = 20): Promise<MeritHistoryResponse> {
+  static async getMyMeritHistory(
+    page = 1,
+    perPage = 20,
+    seasonId?: string
+  ): Promise<MeritHistoryResponse> {
     return httpClient.get<MeritHistoryResponse>(
-      `/merit/me/history?page=${page}&per_page=${perPage}`
+      `/merit/me/history?page=${page}&per_page=${perPage}${seasonParam(seasonId)}`
     );
   }
+
+  /**
+   * List all seasons, newest first
+   */
+  static async listSeasons(): Promise<{ seasons: Season[] }> {
+    return httpClient.get<{ seasons: Season[] }>('/seasons');
+  }
 }
 
 // ============================================================================
@@ -149,9 +184,13 @@ export class AdminMeritService {
   /**
    * List all users with their merit points (admin only)
    */
-  static async listAllMerits(page = 1, perPage = 50): Promise<AdminMeritListResponse> {
+  static async listAllMerits(
+    page = 1,
+    perPage = 50,
+    seasonId?: string
+  ): Promise<AdminMeritListResponse> {
     return httpClient.get<AdminMeritListResponse>(
-      `/admin/merit?page=${page}&per_page=${perPage}`
+      `/admin/merit?page=${page}&per_page=${perPage}${seasonParam(seasonId)}`
     );
   }
 
@@ -190,12 +229,77 @@ export class AdminMeritService {
   static async getUserMeritHistory(
     userId: string,
     page = 1,
-    perPage = 20
+    perPage = 20,
+    seasonId?: string
   ): Promise<MeritHistoryResponse> {
     return httpClient.get<MeritHistoryResponse>(
-      `/admin/merit/${userId}/history?page=${page}&per_page=${perPage}`
+      `/admin/merit/${userId}/history?page=${page}&per_page=${perPage}${seasonParam(seasonId)}`
+    );
+  }
+}
+
+// ============================================================================
+// Admin Season Service
+// ============================================================================
+
+export class AdminSeasonService {
+  /**
+   * Create a season (admin only)
+   */
+  static async createSeason(data: CreateSeasonRequest): Promise<{ message: string; season: Season }> {
+    return httpClient.post('/admin/seasons', data);
+  }
+
+  /**
+   * Rename or move an open season (admin only)
+   */
+  static async updateSeason(
+    seasonId: string,
+    data: UpdateSeasonRequest
+  ): Promise<{ message: string; season: Season }> {
+    return httpClient.patch(`/admin/seasons/${seasonId}`, data);
+  }
+
+  /**
+   * Delete an open season (admin only)
+   */
+  static async deleteSeason(seasonId: string): Promise<{ message: string }> {
+    return httpClient.delete(`/admin/seasons/${seasonId}`);
+  }
+
+  /**
+   * Close a finished season, freezing its standings (admin only)
+   */
+  static async closeSeason(seasonId: string): Promise<{ message: string; season: Season }> {
+    return httpClient.post(`/admin/seasons/${seasonId}/close`);
+  }
+
+  /**
+   * Get a season's standings (admin only)
+   */
+  static async getSeasonStandings(
+    seasonId: string,
+    page = 1,
+    perPage = 50
+  ): Promise<SeasonStandingsResponse> {
+    return httpClient.get<SeasonStandingsResponse>(
+      `/admin/seasons/${seasonId}/standings?page=${page}&per_page=${perPage}`
     );
   }
+
+  /**
+   * Generate a season's end-of-season report (admin only)
+   */
+  static async generateSeasonReport(seasonId: string): Promise<SeasonReport> {
+    return httpClient.post<SeasonReport>(`/admin/seasons/${seasonId}/report`);
+  }
+
+  /**
+   * Generate a season's end-of-season report as a PDF (admin only)
+   */
+  static async downloadSeasonReport(seasonId: string): Promise<Blob> {
+    return httpClient.downloadPost(`/admin/seasons/${seasonId}/report?format=pdf`);
+  }
 }
 
 // ============================================================================
@@ -213,8 +317,10 @@ export class AwardService {
   /**
    * Get current user's awards
    */
-  static async getMyAwards(): Promise<AwardListResponse> {
-    return httpClient.get<AwardListResponse>('/awards/me');
+  static async getMyAwards(seasonId?: string): Promise<AwardListResponse> {
+    return httpClient.get<AwardListResponse>(
+      seasonId ? `/awards/me?season_id=${seasonId}` : '/awards/me'
+    );
   }
 
   /**
@@ -233,9 +339,13 @@ export class AdminAwardService {
   /**
    * List all awards (admin only)
    */
-  static async listAllAwards(page = 1, perPage = 50): Promise<AdminAwardListResponse> {
+  static async listAllAwards(
+    page = 1,
+    perPage = 50,
+    seasonId?: string
+  ): Promise<AdminAwardListResponse> {
     return httpClient.get<AdminAwardListResponse>(
-      `/admin/awards?page=${page}&per_page=${perPage}`
+      `/admin/awards?page=${page}&per_page=${perPage}${seasonParam(seasonId)}`
     );
   }
 
@@ -42,14 +42,12 @@ class TabulationHttpClient {
       ...options.headers,
     };
 
-    const accessToken = TokenManager.getAccessToken();
-    if (accessToken) {
-      (headers as Record<string, string>)['Authorization'] = `Bearer ${accessToken}`;
-    }
+    Object.assign(headers, TokenManager.authHeaders(options.method));
 
     const response = await fetch(url, {
       ...options,
       headers,
+      credentials: TokenManager.credentials(),
     });
 
     if (!response.ok) {
@@ -228,8 +226,17 @@ export class TabulationService {
   // Performance Methods
   // ========================================================================
 
-  static async getUserPerformance(userId: string, eventId?: string): Promise<PerformanceResponse> {
-    const params = eventId ? `?event_id=${eventId}` : '';
-    return httpClient.get<PerformanceResponse>(`/users/${userId}/performance${params}`);
+  static async getUserPerformance(
+    userId: string,
+    eventId?: string,
+    seasonId?: string
+  ): Promise<PerformanceResponse> {
+    const params = new URLSearchParams();
+    if (eventId) params.set('event_id', eventId);
+    if (seasonId) params.set('season_id', seasonId);
+    const query = params.toString();
+    return httpClient.get<PerformanceResponse>(
+      `/users/${userId}/performance${query ? `?${query}` : ''}`
+    );
   }
 }
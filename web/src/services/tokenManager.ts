@@ -1,3 +1,4 @@
+import { AUTH_COOKIES } from './config';
 import type { AuthResponse, UserResponse } from './types';
 
 // Token Management
@@ -6,10 +7,15 @@ export class TokenManager {
   private static REFRESH_TOKEN_KEY = 'refresh_token';
   private static CSRF_TOKEN_KEY = 'csrf_token';
   private static USER_KEY = 'user';
+  // Kept across logouts: refresh tokens are bound to it
+  private static DEVICE_ID_KEY = 'device_id';
 
   static setTokens(auth: AuthResponse, csrfToken: string, user: UserResponse): void {
-    localStorage.setItem(this.ACCESS_TOKEN_KEY, auth.access_token);
-    localStorage.setItem(this.REFRESH_TOKEN_KEY, auth.refresh_token);
+    // With cookie delivery the browser keeps the tokens out of our reach
+    if (auth.access_token && auth.refresh_token) {
+      localStorage.setItem(this.ACCESS_TOKEN_KEY, auth.access_token);
+      localStorage.setItem(this.REFRESH_TOKEN_KEY, auth.refresh_token);
+    }
     localStorage.setItem(this.CSRF_TOKEN_KEY, csrfToken);
     localStorage.setItem(this.USER_KEY, JSON.stringify(user));
   }
@@ -22,6 +28,31 @@ export class TokenManager {
     return localStorage.getItem(this.REFRESH_TOKEN_KEY);
   }
 
+  // Whether the user is signed in, as far as the client can tell
+  static hasSession(): boolean {
+    return AUTH_COOKIES ? this.getUser() !== null : this.getAccessToken() !== null;
+  }
+
+  // Credentials mode for fetch: cookies must also go to a cross-site API
+  static credentials(): RequestCredentials {
+    return AUTH_COOKIES ? 'include' : 'same-origin';
+  }
+
+  // Headers authenticating a request to any service. Requests authenticated
+  // by cookie also need the CSRF token when they change state.
+  static authHeaders(method: string = 'GET'): Record<string, string> {
+    const headers: Record<string, string> = {};
+    const accessToken = this.getAccessToken();
+    if (accessToken) {
+      headers['Authorization'] = `Bearer ${accessToken}`;
+    }
+    const csrfToken = this.getCsrfToken();
+    if (AUTH_COOKIES && csrfToken && method !== 'GET') {
+      headers['X-CSRF-Token'] = csrfToken;
+    }
+    return headers;
+  }
+
   static getCsrfToken(): string | null {
     return localStorage.getItem(this.CSRF_TOKEN_KEY);
   }
@@ -49,4 +80,13 @@ export class TokenManager {
   static updateCsrfToken(csrfToken: string): void {
     localStorage.setItem(this.CSRF_TOKEN_KEY, csrfToken);
   }
+
+  static getDeviceId(): string {
+    let deviceId = localStorage.getItem(this.DEVICE_ID_KEY);
+    if (!deviceId) {
+      deviceId = crypto.randomUUID();
+      localStorage.setItem(this.DEVICE_ID_KEY, deviceId);
+    }
+    return deviceId;
+  }
 }
@@ -14,8 +14,9 @@ export interface LoginRequest {
 }
 
 export interface AuthResponse {
-  access_token: string;
-  refresh_token: string;
+  // Absent when the server delivers tokens as cookies
+  access_token?: string;
+  refresh_token?: string;
   token_type: string;
   expires_in: number;
 }
@@ -118,18 +119,48 @@ export interface Event {
   event_type: EventType;
   event_date: string;
   location: string | null;
+  category_id: string | null;
+  tags: string[];
   created_by: string;
   is_locked: boolean;
+  /** Conveners and volunteers; only on a single fetched event */
+  staff?: EventStaffMember[];
+  /** What members pay to attend; absent for free events */
+  fee?: EventFee;
   created_at: string;
   updated_at: string;
 }
 
+export interface EventFee {
+  event_id: string;
+  /** In the currency's smallest unit */
+  amount_minor: number;
+  currency: string;
+  description: string | null;
+  set_by: string | null;
+  updated_at: string;
+}
+
+export type PaymentStatus = 'unpaid' | 'pending' | 'paid' | 'waived';
+
+export type StaffRole = 'convener' | 'volunteer';
+
+export interface EventStaffMember {
+  user_id: string;
+  username: string;
+  role: StaffRole;
+  assigned_by: string | null;
+  created_at: string;
+}
+
 export interface CreateEventRequest {
   title: string;
   description?: string;
   event_type: EventType;
   event_date: string;
   location?: string;
+  category_id?: string;
+  tags?: string[];
 }
 
 export interface UpdateEventRequest {
@@ -138,6 +169,32 @@ export interface UpdateEventRequest {
   event_type?: EventType;
   event_date?: string;
   location?: string;
+  category_id?: string;
+  remove_category?: boolean;
+  tags?: string[];
+}
+
+export interface EventListFilters {
+  eventType?: string;
+  upcomingOnly?: boolean;
+  categoryId?: string;
+  tags?: string[];
+}
+
+export interface EventCategory {
+  id: string;
+  name: string;
+  parent_id: string | null;
+  path: string;
+  depth: number;
+  event_count: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TagCount {
+  tag: string;
+  event_count: number;
 }
 
 export interface EventListResponse {
@@ -158,6 +215,8 @@ export interface AttendanceRecord {
   checked_in_by: string | null;
   checked_in_at: string | null;
   availability_set_at: string;
+  /** Only for events with a fee */
+  payment_status?: PaymentStatus;
 }
 
 export interface AttendanceStats {
@@ -208,12 +267,13 @@ export interface UserAttendanceSummary {
   username: string;
   events_available: number;
   events_checked_in: number;
+  events_excused: number;
   total_events: number;
   availability_rate: number;
   attendance_rate: number;
 }
 
-export type AttendanceCellStatus = 'no_response' | 'available' | 'checked_in' | 'unavailable';
+export type AttendanceCellStatus = 'no_response' | 'available' | 'checked_in' | 'unavailable' | 'excused';
 
 export interface AttendanceMatrixRow {
   user: UserAttendanceSummary;
@@ -243,6 +303,9 @@ export interface AttendanceMatrixResponse {
   events: EventSummary[];
   rows: AttendanceMatrixRow[];
   aggregate_stats: AggregateStats;
+  page: number;
+  per_page: number;
+  total_pages: number;
 }
 
 // ============================================================================
@@ -284,8 +347,11 @@ export interface AdminProfileResponse {
   created_at: string;
 }
 
-// Union type for profile responses
-export type ProfileResponse = PublicProfileResponse | PrivateProfileResponse | AdminProfileResponse;
+// Union type for profile responses. A profile found by a former username
+// carries the current one in redirect_to.
+export type ProfileResponse = (PublicProfileResponse | PrivateProfileResponse | AdminProfileResponse) & {
+  redirect_to?: string;
+};
 
 // Merit response
 export interface MeritResponse {
@@ -328,6 +394,8 @@ export interface UserMeritInfo {
   user_id: string;
   username: string;
   merit_points: number;
+  // Only present when the list is filtered to a season
+  season_merit?: number;
 }
 
 // Admin merit list response
@@ -339,6 +407,82 @@ export interface AdminMeritListResponse {
   total_pages: number;
 }
 
+// ============================================================================
+// Season Types
+// ============================================================================
+
+export interface Season {
+  id: string;
+  name: string;
+  starts_on: string;
+  ends_on: string;
+  closed_at: string | null;
+  closed_by: string | null;
+  created_by: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface SeasonStanding {
+  rank: number;
+  user_id: string;
+  username: string;
+  merit_earned: number;
+  awards: number;
+  wins: number;
+  losses: number;
+  average_speaker_score: number | null;
+}
+
+export interface SeasonStandingsResponse {
+  season: Season;
+  standings: SeasonStanding[];
+  total: number;
+  page: number;
+  per_page: number;
+  total_pages: number;
+}
+
+export interface SeasonReport {
+  season: Season;
+  events_held: number;
+  merit_leaders: SeasonStanding[];
+  top_speakers: {
+    user_id: string;
+    username: string;
+    average_speaker_score: number;
+    speeches: number;
+  }[];
+  most_improved: {
+    user_id: string;
+    username: string;
+    previous_average: number;
+    season_average: number;
+    improvement: number;
+  }[];
+  attendance_champions: { user_id: string; username: string; events_attended: number }[];
+  awards: {
+    user_id: string;
+    username: string;
+    title: string;
+    tier: AwardTier;
+    awarded_at: string;
+  }[];
+  generated_at: string;
+}
+
+export interface CreateSeasonRequest {
+  name: string;
+  starts_on: string;
+  ends_on: string;
+}
+
+export interface UpdateSeasonRequest {
+  name?: string;
+  starts_on?: string;
+  ends_on?: string;
+}
+
 // ============================================================================
 // Award Types
 // ============================================================================
@@ -596,6 +740,16 @@ export interface CheckedInUserInfo {
   user_id: string;
   username: string;
   checked_in_at: string | null; // null if not checked in but available for allocation
+  payment_status?: PaymentStatus | null; // null when the event is free
+  is_allocated: boolean;
+  current_allocation: CurrentAllocationInfo | null;
+}
+
+export interface WalkInPoolInfo {
+  walk_in_id: string;
+  name: string;
+  institution_id: string | null;
+  registered_at: string;
   is_allocated: boolean;
   current_allocation: CurrentAllocationInfo | null;
 }
@@ -604,6 +758,7 @@ export interface AllocationPoolResponse {
   event_id: string;
   series_id: string;
   checked_in_users: CheckedInUserInfo[];
+  walk_ins?: WalkInPoolInfo[]; // guests registered at the desk
   all_users?: CheckedInUserInfo[]; // all users in the system for friendly matches
   total_checked_in: number;
   total_allocated: number;
@@ -614,6 +769,7 @@ export interface CreateAllocationRequest {
   match_id: string;
   user_id?: string;  // Optional for guest allocations
   guest_name?: string;  // Required if user_id is not provided
+  walk_in_id?: string;  // A guest registered at the desk, allocated by name
   role: AllocationRole;
   team_id?: string;
   two_team_speaker_role?: TwoTeamSpeakerRole;